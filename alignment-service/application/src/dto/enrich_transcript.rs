@@ -12,6 +12,10 @@ pub struct EnrichTranscriptRequest {
     pub transcript: Transcript,
     #[validate(length(min = 1, max = 64))]
     pub session_id: Option<String>,
+    /// Start of `samples` on the stream's timeline. `transcript` is taken on
+    /// that timeline too, and the aligned words are returned on it.
+    #[serde(default)]
+    pub time_offset_ms: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -20,4 +24,27 @@ pub struct EnrichTranscriptResponse {
     pub transcript: Transcript,
     pub aligned_words: Vec<WordTiming>,
     pub text: String,
+    /// Set when the aligner did not run; `aligned_words` is then empty.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skipped_reason: Option<SkipReason>,
+    /// Rate of the request's audio when the aligner resampled it to its
+    /// model's rate.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resampled_from_hz: Option<u32>,
+}
+
+/// Why a request was answered without running the aligner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    /// The transcript has no segments or only whitespace.
+    EmptyTranscript,
+}
+
+impl SkipReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::EmptyTranscript => "empty_transcript",
+        }
+    }
 }
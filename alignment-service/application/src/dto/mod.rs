@@ -1,3 +1,3 @@
 mod enrich_transcript;
 
-pub use enrich_transcript::{EnrichTranscriptRequest, EnrichTranscriptResponse};
+pub use enrich_transcript::{EnrichTranscriptRequest, EnrichTranscriptResponse, SkipReason};
@@ -1,3 +1,4 @@
+use common_domain::ErrorCode;
 use rustycog_command::CommandError;
 use alignment_domain::DomainError;
 use thiserror::Error;
@@ -10,6 +11,10 @@ pub enum ApplicationError {
     #[error("Validation error: {0}")]
     Validation(String),
 
+    /// Too many alignments are running and waiting; the caller should retry.
+    #[error("Overloaded: {0}")]
+    Overloaded(String),
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
@@ -18,11 +23,14 @@ impl From<ApplicationError> for CommandError {
     fn from(error: ApplicationError) -> Self {
         match error {
             ApplicationError::Domain(err) => {
-                CommandError::business("domain_error", err.to_string())
+                CommandError::business(ErrorCode::from(&err).as_str(), err.to_string())
             }
             ApplicationError::Validation(message) => {
                 CommandError::validation("validation_error", message)
             }
+            ApplicationError::Overloaded(message) => {
+                CommandError::business(ErrorCode::ResourceExhausted.as_str(), message)
+            }
             ApplicationError::Internal(message) => {
                 CommandError::infrastructure("internal_error", message)
             }
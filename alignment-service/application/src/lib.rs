@@ -1,9 +1,11 @@
 pub mod command;
 pub mod dto;
 pub mod error;
+pub mod limiter;
 pub mod usecase;
 
 pub use command::*;
 pub use dto::*;
 pub use error::*;
+pub use limiter::{AlignmentLimiter, LimiterStats};
 pub use usecase::{AlignTranscriptUseCase, AlignTranscriptUseCaseImpl};
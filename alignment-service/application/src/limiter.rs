@@ -0,0 +1,117 @@
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use serde::Serialize;
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+use crate::ApplicationError;
+
+/// Bounds how many alignments run at once and how many requests may wait
+/// for one, so a burst queues briefly instead of exhausting GPU memory.
+pub struct AlignmentLimiter {
+    semaphore: Semaphore,
+    max_concurrent: usize,
+    max_queued: usize,
+    queued: AtomicUsize,
+    rejected: AtomicU64,
+}
+
+/// Point-in-time view of an [`AlignmentLimiter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct LimiterStats {
+    pub in_flight: usize,
+    pub max_concurrent: usize,
+    pub queued: usize,
+    pub max_queued: usize,
+    /// Requests refused because the queue was full, since startup.
+    pub rejected: u64,
+}
+
+/// Frees a queue slot when the waiting request gets its permit or gives up.
+struct QueueSlot<'a>(&'a AtomicUsize);
+
+impl Drop for QueueSlot<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl AlignmentLimiter {
+    /// `max_concurrent` alignments at once (at least one) and up to
+    /// `max_queued` requests waiting behind them.
+    pub fn new(max_concurrent: usize, max_queued: usize) -> Self {
+        let max_concurrent = max_concurrent.max(1);
+        Self {
+            semaphore: Semaphore::new(max_concurrent),
+            max_concurrent,
+            max_queued,
+            queued: AtomicUsize::new(0),
+            rejected: AtomicU64::new(0),
+        }
+    }
+
+    /// Waits for a free slot, or fails with [`ApplicationError::Overloaded`]
+    /// when `max_queued` requests are already waiting.
+    pub async fn acquire(&self) -> Result<SemaphorePermit<'_>, ApplicationError> {
+        if let Ok(permit) = self.semaphore.try_acquire() {
+            return Ok(permit);
+        }
+        if self.queued.fetch_add(1, Ordering::SeqCst) >= self.max_queued {
+            self.queued.fetch_sub(1, Ordering::SeqCst);
+            self.rejected.fetch_add(1, Ordering::Relaxed);
+            return Err(ApplicationError::Overloaded(format!(
+                "{} alignments running and {} queued, the limit",
+                self.max_concurrent, self.max_queued
+            )));
+        }
+        let _slot = QueueSlot(&self.queued);
+        self.semaphore
+            .acquire()
+            .await
+            .map_err(|_| ApplicationError::Internal("alignment limiter closed".to_string()))
+    }
+
+    pub fn stats(&self) -> LimiterStats {
+        LimiterStats {
+            in_flight: self.max_concurrent - self.semaphore.available_permits(),
+            max_concurrent: self.max_concurrent,
+            queued: self.queued.load(Ordering::SeqCst),
+            max_queued: self.max_queued,
+            rejected: self.rejected.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn requests_beyond_the_queue_are_refused() {
+        let limiter = AlignmentLimiter::new(1, 1);
+        let running = limiter.acquire().await.expect("free slot");
+
+        let waiting = limiter.acquire();
+        tokio::pin!(waiting);
+        assert!(poll_once(waiting.as_mut()).await.is_none());
+        assert_eq!(limiter.stats().queued, 1);
+
+        let refused = limiter.acquire().await.err().expect("queue full");
+        assert!(matches!(refused, ApplicationError::Overloaded(_)));
+        assert_eq!(limiter.stats().rejected, 1);
+
+        drop(running);
+        waiting.await.expect("queued request runs");
+        assert_eq!(limiter.stats().queued, 0);
+    }
+
+    /// Polls `future` once, returning its output if it was ready.
+    async fn poll_once<F: std::future::Future + Unpin>(mut future: F) -> Option<F::Output> {
+        std::future::poll_fn(|cx| {
+            std::task::Poll::Ready(match std::pin::Pin::new(&mut future).poll(cx) {
+                std::task::Poll::Ready(output) => Some(output),
+                std::task::Poll::Pending => None,
+            })
+        })
+        .await
+    }
+}
@@ -3,9 +3,15 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use uuid::Uuid;
 
-use alignment_domain::{AlignmentPort, AlignmentRequest, AudioChunk};
+use alignment_domain::{
+    ms_to_sample, AlignmentPort, AlignmentRequest, AudioChunk, Transcript, WordTiming,
+};
+use vocal_features::YinConfig;
 
-use crate::{ApplicationError, EnrichTranscriptRequest, EnrichTranscriptResponse};
+use crate::{
+    AlignmentLimiter, ApplicationError, EnrichTranscriptRequest, EnrichTranscriptResponse,
+    SkipReason,
+};
 
 #[async_trait]
 pub trait AlignTranscriptUseCase: Send + Sync {
@@ -18,6 +24,9 @@ pub trait AlignTranscriptUseCase: Send + Sync {
 pub struct AlignTranscriptUseCaseImpl {
     aligner: Arc<dyn AlignmentPort>,
     default_sample_rate_hz: u32,
+    word_prosody: bool,
+    max_drift_ms: Option<u64>,
+    limiter: Option<Arc<AlignmentLimiter>>,
 }
 
 impl AlignTranscriptUseCaseImpl {
@@ -25,8 +34,90 @@ impl AlignTranscriptUseCaseImpl {
         Self {
             aligner,
             default_sample_rate_hz,
+            word_prosody: false,
+            max_drift_ms: None,
+            limiter: None,
         }
     }
+
+    /// Attaches the RMS energy and mean pitch of each word's audio window to
+    /// the aligned words.
+    pub fn with_word_prosody(mut self, enabled: bool) -> Self {
+        self.word_prosody = enabled;
+        self
+    }
+
+    /// Keeps aligned words within `max_drift_ms` of their segment's times,
+    /// flagging the words that had to be moved back into that band.
+    pub fn with_max_drift_ms(mut self, max_drift_ms: Option<u64>) -> Self {
+        self.max_drift_ms = max_drift_ms;
+        self
+    }
+
+    /// Runs alignments through `limiter`, refusing requests when its queue
+    /// is full.
+    pub fn with_limiter(mut self, limiter: Arc<AlignmentLimiter>) -> Self {
+        self.limiter = Some(limiter);
+        self
+    }
+}
+
+/// Clamps words aligned more than `max_drift_ms` outside their segment into
+/// the band and marks them `out_of_band`. Words are matched to segments by
+/// position, so nothing is checked when the aligner dropped words; segments
+/// without times constrain nothing.
+fn enforce_drift_band(
+    words: &mut [WordTiming],
+    transcript: &Transcript,
+    max_drift_ms: u64,
+    sample_rate_hz: u32,
+) {
+    let bands: Vec<(u64, u64)> = transcript
+        .segments
+        .iter()
+        .flat_map(|segment| {
+            let band = if segment.end_ms > segment.start_ms {
+                (
+                    segment.start_ms.saturating_sub(max_drift_ms),
+                    segment.end_ms.saturating_add(max_drift_ms),
+                )
+            } else {
+                (0, u64::MAX)
+            };
+            std::iter::repeat(band).take(segment.text.split_whitespace().count())
+        })
+        .collect();
+    if bands.len() != words.len() {
+        return;
+    }
+
+    for (word, (low, high)) in words.iter_mut().zip(bands) {
+        if word.start_ms >= low && word.end_ms <= high {
+            continue;
+        }
+        word.start_ms = word.start_ms.clamp(low, high);
+        word.end_ms = word.end_ms.clamp(word.start_ms, high);
+        word.start_sample = word
+            .start_sample
+            .map(|_| ms_to_sample(word.start_ms, sample_rate_hz));
+        word.end_sample = word
+            .end_sample
+            .map(|_| ms_to_sample(word.end_ms, sample_rate_hz));
+        word.out_of_band = true;
+    }
+}
+
+fn attach_prosody(words: &mut [WordTiming], samples: &[f32], sample_rate_hz: u32) {
+    let yin = YinConfig::with_sample_rate(sample_rate_hz);
+    for word in words {
+        let start = ms_to_sample(word.start_ms, sample_rate_hz) as usize;
+        let end = (ms_to_sample(word.end_ms, sample_rate_hz) as usize).min(samples.len());
+        let Some(window) = samples.get(start..end).filter(|window| !window.is_empty()) else {
+            continue;
+        };
+        word.energy_rms = Some(vocal_features::rms_energy(window));
+        word.pitch_hz = vocal_features::estimate_mean_f0(window, &yin);
+    }
 }
 
 #[async_trait]
@@ -56,21 +147,60 @@ impl AlignTranscriptUseCase for AlignTranscriptUseCaseImpl {
             "starting transcript enrichment"
         );
 
-        let aligned_words = self
+        // Nothing to align: answer without the model, which rejects an empty
+        // target sequence.
+        if text.is_empty() {
+            tracing::debug!(session_id = %session_id, "empty transcript, alignment skipped");
+            return Ok(EnrichTranscriptResponse {
+                session_id,
+                transcript,
+                aligned_words: Vec::new(),
+                text,
+                skipped_reason: Some(SkipReason::EmptyTranscript),
+                resampled_from_hz: None,
+            });
+        }
+
+        let offset_ms = request
+            .time_offset_ms
+            .map_or(0, |offset_ms| i64::try_from(offset_ms).unwrap_or(i64::MAX));
+        // The aligner works on the chunk's own timeline.
+        let mut chunk_transcript = transcript.clone();
+        if offset_ms != 0 {
+            chunk_transcript.shift(-offset_ms, sample_rate_hz);
+        }
+
+        let samples = self.word_prosody.then(|| request.samples.clone());
+        let _permit = match &self.limiter {
+            Some(limiter) => Some(limiter.acquire().await.inspect_err(|_| {
+                tracing::warn!(session_id = %session_id, "alignment queue full, request refused");
+            })?),
+            None => None,
+        };
+        let output = self
             .aligner
             .align(AlignmentRequest {
-                audio: AudioChunk {
-                    sample_rate_hz,
-                    samples: request.samples,
-                },
-                transcript: transcript.clone(),
+                audio: AudioChunk::mono(sample_rate_hz, request.samples),
+                transcript: chunk_transcript.clone(),
             })
-            .await?
-            .words;
+            .await?;
+        let mut aligned_words = output.words;
+        if let Some(max_drift_ms) = self.max_drift_ms {
+            enforce_drift_band(&mut aligned_words, &chunk_transcript, max_drift_ms, sample_rate_hz);
+        }
+        if let Some(samples) = samples {
+            attach_prosody(&mut aligned_words, &samples, sample_rate_hz);
+        }
+        if offset_ms != 0 {
+            for word in &mut aligned_words {
+                word.shift(offset_ms, sample_rate_hz);
+            }
+        }
 
         tracing::debug!(
             session_id = %session_id,
             aligned_word_count = aligned_words.len(),
+            out_of_band_count = aligned_words.iter().filter(|word| word.out_of_band).count(),
             "transcript enrichment completed"
         );
 
@@ -79,6 +209,76 @@ impl AlignTranscriptUseCase for AlignTranscriptUseCaseImpl {
             transcript,
             aligned_words,
             text,
+            skipped_reason: None,
+            resampled_from_hz: output.resampled_from_hz,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(start_ms: u64, end_ms: u64) -> WordTiming {
+        WordTiming {
+            word: "a".to_string(),
+            start_ms,
+            end_ms,
+            confidence: 1.0,
+            speaker: None,
+            start_sample: None,
+            end_sample: None,
+            energy_rms: None,
+            pitch_hz: None,
+            out_of_band: false,
+        }
+    }
+
+    #[test]
+    fn prosody_is_measured_over_each_word_window() {
+        let mut samples = test_audio::tone(200.0, 16_000, 300);
+        samples.extend(vec![0.0; 4_800]);
+        let mut words = vec![word(0, 300), word(300, 600), word(900, 1_000)];
+        attach_prosody(&mut words, &samples, 16_000);
+
+        let pitch = words[0].pitch_hz.expect("voiced");
+        assert!((pitch - 200.0).abs() < 5.0, "pitch {pitch}");
+        assert!(words[0].energy_rms.unwrap() > 0.3);
+        assert_eq!(words[1].energy_rms, Some(0.0));
+        assert_eq!(words[1].pitch_hz, None);
+        assert_eq!(words[2].energy_rms, None);
+    }
+
+    #[test]
+    fn words_outside_the_drift_band_are_clamped_and_flagged() {
+        use alignment_domain::{LanguageTag, TranscriptSegment};
+
+        let segment = |text: &str, start_ms: u64, end_ms: u64| TranscriptSegment {
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+            tokens: Vec::new(),
+            speaker: None,
+            language: None,
+            no_speech_prob: None,
+            avg_logprob: None,
+        };
+        let transcript = Transcript {
+            language: LanguageTag::en(),
+            segments: vec![segment(" hello there", 1_000, 2_000), segment(" bye", 5_000, 5_500)],
+        };
+        let mut words = vec![word(900, 1_400), word(1_500, 1_900), word(8_000, 8_400)];
+        words[2].start_sample = Some(0);
+        enforce_drift_band(&mut words, &transcript, 500, 16_000);
+
+        assert!(!words[0].out_of_band && !words[1].out_of_band);
+        assert_eq!((words[0].start_ms, words[0].end_ms), (900, 1_400));
+        assert!(words[2].out_of_band);
+        assert_eq!((words[2].start_ms, words[2].end_ms), (6_000, 6_000));
+        assert_eq!(words[2].start_sample, Some(96_000));
+
+        let mut dropped = vec![word(8_000, 8_400)];
+        enforce_drift_band(&mut dropped, &transcript, 500, 16_000);
+        assert!(!dropped[0].out_of_band);
+    }
+}
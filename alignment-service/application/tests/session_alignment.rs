@@ -22,7 +22,14 @@ impl AlignmentPort for MockAlignmentPort {
                 start_ms: 0,
                 end_ms: 250,
                 confidence: 0.9,
+                speaker: None,
+                start_sample: None,
+                end_sample: None,
+                energy_rms: None,
+                pitch_hz: None,
+                out_of_band: false,
             }],
+            resampled_from_hz: None,
         })
     }
 }
@@ -38,18 +45,23 @@ async fn enrich_command_flow_returns_enriched_transcript() {
 
     let response = handler
         .handle(EnrichTranscriptCommand::new(EnrichTranscriptRequest {
-            samples: vec![0.1, 0.2, 0.3],
+            samples: test_audio::speech_like(16_000, 500),
             sample_rate_hz: Some(16_000),
             transcript: Transcript {
-                language: LanguageTag::En,
+                language: LanguageTag::en(),
                 segments: vec![TranscriptSegment {
                     text: "hello world".to_string(),
                     start_ms: 0,
                     end_ms: 500,
                     tokens: Vec::new(),
+                    speaker: None,
+                    language: None,
+                    no_speech_prob: None,
+                    avg_logprob: None,
                 }],
             },
             session_id: Some("it-session".to_string()),
+            time_offset_ms: None,
         }))
         .await
         .expect("command succeeds");
@@ -58,3 +70,35 @@ async fn enrich_command_flow_returns_enriched_transcript() {
     assert_eq!(response.text, "hello world");
     assert!(!response.aligned_words.is_empty());
 }
+
+#[tokio::test]
+async fn time_offset_returns_words_on_the_stream_timeline() {
+    let usecase = AlignTranscriptUseCaseImpl::new(Arc::new(MockAlignmentPort), 16_000);
+
+    let response = usecase
+        .enrich_transcript(EnrichTranscriptRequest {
+            samples: test_audio::speech_like(16_000, 500),
+            sample_rate_hz: Some(16_000),
+            transcript: Transcript {
+                language: LanguageTag::en(),
+                segments: vec![TranscriptSegment {
+                    text: "hello".to_string(),
+                    start_ms: 30_000,
+                    end_ms: 30_500,
+                    tokens: Vec::new(),
+                    speaker: None,
+                    language: None,
+                    no_speech_prob: None,
+                    avg_logprob: None,
+                }],
+            },
+            session_id: None,
+            time_offset_ms: Some(30_000),
+        })
+        .await
+        .expect("alignment succeeds");
+
+    let word = &response.aligned_words[0];
+    assert_eq!((word.start_ms, word.end_ms), (30_000, 30_250));
+    assert_eq!(response.transcript.segments[0].start_ms, 30_000);
+}
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 use rustycog_config::{
@@ -13,8 +15,15 @@ pub type AppConfig = AlignmentConfig;
 pub struct AlignmentConfig {
     #[serde(default)]
     pub server: ServerConfig,
+    /// REST listener, served next to gRPC when set. Needs the `http` feature.
+    #[serde(default)]
+    pub http: Option<ServerConfig>,
     #[serde(default)]
     pub logging: LoggingConfig,
+    /// Admin API listener (drain, reload, metrics, log level), off when
+    /// unset; bind it to an address the public cannot reach.
+    #[serde(default)]
+    pub admin: Option<ServerConfig>,
     #[serde(default)]
     pub alignment: AlignmentRuntimeConfig,
 }
@@ -29,15 +38,84 @@ pub struct AlignmentRuntimeConfig {
     pub config_path: String,
     #[serde(default = "default_vocab_path")]
     pub vocab_path: String,
+    /// `cpu`, `cuda`, or `auto` to use CUDA when it initializes and fall
+    /// back to the CPU otherwise.
     #[serde(default = "default_device")]
     pub device: String,
+    /// `fp32`, `fp16` or `int8`. Reduced precisions load `model_fp16.onnx`
+    /// or `model_int8.onnx` from the directory of `model_path`.
+    #[serde(default = "default_precision")]
+    pub precision: String,
+    #[serde(default)]
+    pub download_missing_models: bool,
+    #[serde(default)]
+    pub model_files: ModelFilesConfig,
+    /// Run one alignment pass over silence before serving traffic.
+    #[serde(default)]
+    pub warmup_on_start: bool,
+    /// Entries added to `vocab.json` at load time, each mapped to the id of
+    /// a character the model already emits, e.g. `"ё" = 12`.
+    #[serde(default)]
+    pub extra_vocab: BTreeMap<String, u32>,
+    /// Transcript graphemes rewritten before alignment, e.g. `"ß" = "ss"`,
+    /// for characters the vocabulary lacks.
+    #[serde(default)]
+    pub grapheme_map: BTreeMap<String, String>,
+    /// Attach the RMS energy and mean pitch of each word's audio window to
+    /// the aligned words.
+    #[serde(default)]
+    pub word_prosody: bool,
+    /// Align each transcript segment against its own slice of the audio,
+    /// anchored on the segment's times, instead of the whole clip at once.
+    #[serde(default = "default_anchored")]
+    pub anchored: bool,
+    /// Audio added around each anchored segment for drift in its times.
+    #[serde(default = "default_anchor_padding_ms")]
+    pub anchor_padding_ms: u64,
+    /// Longest anchored span; longer segments are split between words at
+    /// their token times.
+    #[serde(default = "default_max_window_ms")]
+    pub max_window_ms: u64,
+    /// Furthest an aligned word may sit from its segment's times. Words
+    /// beyond it are clamped back and flagged `out_of_band`, and anchored
+    /// windows are padded by no more than it. Unbounded when unset.
+    #[serde(default)]
+    pub max_drift_ms: Option<u64>,
+    /// Alignments run at once; unlimited when unset. Bounds GPU memory use
+    /// under bursts.
+    #[serde(default)]
+    pub max_concurrent: Option<usize>,
+    /// Requests waiting for one of the `max_concurrent` slots. Further
+    /// requests fail with `resource_exhausted`.
+    #[serde(default = "default_max_queued")]
+    pub max_queued: usize,
+}
+
+/// Optional checksums and download sources for the three wav2vec2 files.
+/// Sources are `https://...` URLs or `hf://owner/repo/file[@revision]`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelFilesConfig {
+    #[serde(default)]
+    pub model_sha256: Option<String>,
+    #[serde(default)]
+    pub model_source: Option<String>,
+    #[serde(default)]
+    pub config_sha256: Option<String>,
+    #[serde(default)]
+    pub config_source: Option<String>,
+    #[serde(default)]
+    pub vocab_sha256: Option<String>,
+    #[serde(default)]
+    pub vocab_source: Option<String>,
 }
 
 impl Default for AlignmentConfig {
     fn default() -> Self {
         Self {
             server: ServerConfig::default(),
+            http: None,
             logging: LoggingConfig::default(),
+            admin: None,
             alignment: AlignmentRuntimeConfig::default(),
         }
     }
@@ -51,6 +129,19 @@ impl Default for AlignmentRuntimeConfig {
             config_path: default_config_path(),
             vocab_path: default_vocab_path(),
             device: default_device(),
+            precision: default_precision(),
+            download_missing_models: false,
+            model_files: ModelFilesConfig::default(),
+            warmup_on_start: false,
+            extra_vocab: BTreeMap::new(),
+            grapheme_map: BTreeMap::new(),
+            word_prosody: false,
+            anchored: default_anchored(),
+            anchor_padding_ms: default_anchor_padding_ms(),
+            max_window_ms: default_max_window_ms(),
+            max_drift_ms: None,
+            max_concurrent: None,
+            max_queued: default_max_queued(),
         }
     }
 }
@@ -109,6 +200,26 @@ fn default_device() -> String {
     "cpu".to_string()
 }
 
+fn default_max_queued() -> usize {
+    16
+}
+
+fn default_precision() -> String {
+    "fp32".to_string()
+}
+
+fn default_anchored() -> bool {
+    true
+}
+
+fn default_anchor_padding_ms() -> u64 {
+    500
+}
+
+fn default_max_window_ms() -> u64 {
+    30_000
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -118,6 +229,11 @@ mod tests {
         let cfg = AlignmentConfig::default();
         assert_eq!(cfg.alignment.sample_rate_hz, 16_000);
         assert_eq!(cfg.alignment.device, "cpu");
+        assert_eq!(cfg.alignment.precision, "fp32");
+        assert!(!cfg.alignment.download_missing_models);
+        assert!(!cfg.alignment.warmup_on_start);
+        assert!(cfg.alignment.anchored);
+        assert_eq!(cfg.alignment.max_window_ms, 30_000);
         assert_eq!(cfg.server.port, 8080);
     }
 }
@@ -1,48 +1,7 @@
-use serde::{Deserialize, Serialize};
-
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub enum LanguageTag {
-    Fr,
-    En,
-    Auto,
-    Other(String),
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AudioChunk {
-    pub sample_rate_hz: u32,
-    pub samples: Vec<f32>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TranscriptToken {
-    pub text: String,
-    pub start_ms: u64,
-    pub end_ms: u64,
-    pub confidence: f32,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TranscriptSegment {
-    pub text: String,
-    pub start_ms: u64,
-    pub end_ms: u64,
-    pub tokens: Vec<TranscriptToken>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct WordTiming {
-    pub word: String,
-    pub start_ms: u64,
-    pub end_ms: u64,
-    pub confidence: f32,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Transcript {
-    pub language: LanguageTag,
-    pub segments: Vec<TranscriptSegment>,
-}
+pub use common_domain::{
+    ms_to_sample, AudioChunk, LanguageTag, Transcript, TranscriptSegment, TranscriptToken,
+    WordTiming,
+};
 
 #[derive(Debug, Clone)]
 pub struct AlignmentRequest {
@@ -53,4 +12,7 @@ pub struct AlignmentRequest {
 #[derive(Debug, Clone)]
 pub struct AlignmentOutput {
     pub words: Vec<WordTiming>,
+    /// Rate of the request's audio when the aligner had to resample it to
+    /// the rate its model expects.
+    pub resampled_from_hz: Option<u32>,
 }
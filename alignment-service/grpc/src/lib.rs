@@ -7,30 +7,51 @@ use anyhow::Context;
 use alignment_application::{
     EnrichTranscriptCommand, EnrichTranscriptRequest, EnrichTranscriptResponse,
 };
-use alignment_domain::{LanguageTag, Transcript, TranscriptSegment, TranscriptToken, WordTiming};
+use alignment_domain::{ms_to_sample, Transcript};
+use common_domain::ErrorCode;
+use log_context::grpc_request_span;
 use rustycog_command::{CommandContext, CommandError, GenericCommandService};
 use rustycog_config::ServerConfig;
-use tonic::{transport::Server, Request, Response, Status};
+use service_health::{health_router, ReadinessCheck};
+use tonic::{service::Routes, transport::Server, Request, Response, Status};
+use tracing::Instrument;
 
 const MAX_MESSAGE_BYTES: usize = 64 * 1024 * 1024;
-const LANGUAGE_TAG_CODE_FR: i32 = 1;
-const LANGUAGE_TAG_CODE_EN: i32 = 2;
-const LANGUAGE_TAG_CODE_AUTO: i32 = 3;
-const LANGUAGE_TAG_CODE_OTHER: i32 = 4;
+/// How far a transcript may end past its audio and still be clamped to it
+/// rather than rejected. Decoders round their last timestamps up, but a
+/// transcript running further out was taken from other audio.
+const TRANSCRIPT_OVERRUN_TOLERANCE_MS: u64 = 1_000;
+const TRANSCRIPT_CLAMPED: &str = "transcript_clamped";
 
 pub mod pb {
     tonic::include_proto!("alignment.v1");
+
+    // The shared messages moved to `common.v1`; re-exported so code written
+    // against this package's former copies keeps compiling.
+    pub use common_proto::v1::{
+        LanguageTag, LanguageTagCode, Transcript, TranscriptSegment, TranscriptToken, WordTiming,
+    };
 }
 
 pub use pb::alignment_service_client::AlignmentServiceClient;
 pub use pb::alignment_service_server::AlignmentServiceServer;
 
+vocal_proto_mappings::transcript_mappings!(pb, alignment_domain);
+vocal_proto_mappings::word_timing_mappings!(pb, alignment_domain);
+
+/// `default_sample_rate_hz` must match the use case's: requests without a
+/// rate are aligned, and their transcript duration checked, at that rate.
 pub async fn serve_grpc(
     command_service: Arc<GenericCommandService>,
     server_config: ServerConfig,
+    readiness: Arc<dyn ReadinessCheck>,
+    default_sample_rate_hz: u32,
 ) -> anyhow::Result<()> {
     let address = resolve_bind_addr(&server_config)?;
-    let service = AlignmentGrpcService { command_service };
+    let service = AlignmentGrpcService {
+        command_service,
+        default_sample_rate_hz,
+    };
 
     tracing::info!(
         host = %server_config.host,
@@ -38,12 +59,18 @@ pub async fn serve_grpc(
         "starting alignment gRPC server"
     );
 
+    // Health probes are plain HTTP/1.1 GETs served on the gRPC port.
+    let routes = Routes::new(
+        AlignmentServiceServer::new(service)
+            .max_decoding_message_size(MAX_MESSAGE_BYTES)
+            .max_encoding_message_size(MAX_MESSAGE_BYTES),
+    )
+    .into_axum_router()
+    .merge(health_router(readiness));
+
     Server::builder()
-        .add_service(
-            AlignmentServiceServer::new(service)
-                .max_decoding_message_size(MAX_MESSAGE_BYTES)
-                .max_encoding_message_size(MAX_MESSAGE_BYTES),
-        )
+        .accept_http1(true)
+        .add_routes(Routes::from(routes))
         .serve(address)
         .await
         .context("alignment gRPC server failed")
@@ -52,6 +79,7 @@ pub async fn serve_grpc(
 #[derive(Clone)]
 struct AlignmentGrpcService {
     command_service: Arc<GenericCommandService>,
+    default_sample_rate_hz: u32,
 }
 
 #[tonic::async_trait]
@@ -60,16 +88,27 @@ impl pb::alignment_service_server::AlignmentService for AlignmentGrpcService {
         &self,
         request: Request<pb::EnrichTranscriptRequest>,
     ) -> Result<Response<pb::EnrichTranscriptResponse>, Status> {
-        let request = map_enrich_request(request.into_inner())?;
-        let command = EnrichTranscriptCommand::new(request);
-        let context = CommandContext::new();
-        let result = self
-            .command_service
-            .execute(command, context)
-            .await
-            .map_err(map_command_error)?;
-
-        Ok(Response::new(map_enrich_response(result)))
+        let span = grpc_request_span(
+            "alignment.v1.AlignmentService/EnrichTranscript",
+            request.metadata(),
+            request.get_ref().session_id.as_deref(),
+        );
+        async move {
+            let (request, warning) = map_enrich_request(request.into_inner(), self.default_sample_rate_hz)?;
+            let command = EnrichTranscriptCommand::new(request);
+            let context = CommandContext::new();
+            let result = self
+                .command_service
+                .execute(command, context)
+                .await
+                .map_err(map_command_error)?;
+
+            let mut response = map_enrich_response(result);
+            response.warnings.extend(warning);
+            Ok(Response::new(response))
+        }
+        .instrument(span)
+        .await
     }
 }
 
@@ -84,7 +123,10 @@ fn resolve_bind_addr(config: &ServerConfig) -> anyhow::Result<SocketAddr> {
         .with_context(|| format!("no socket address resolved for `{bind}`"))
 }
 
-fn map_enrich_request(request: pb::EnrichTranscriptRequest) -> Result<EnrichTranscriptRequest, Status> {
+fn map_enrich_request(
+    request: pb::EnrichTranscriptRequest,
+    default_sample_rate_hz: u32,
+) -> Result<(EnrichTranscriptRequest, Option<pb::AlignmentWarning>), Status> {
     if request.samples.is_empty() {
         return Err(Status::invalid_argument(
             "samples must contain at least one frame",
@@ -93,142 +135,98 @@ fn map_enrich_request(request: pb::EnrichTranscriptRequest) -> Result<EnrichTran
 
     validate_sample_rate(request.sample_rate_hz)?;
     validate_optional_text(&request.session_id, "session_id", 64)?;
-    let transcript = map_transcript_from_proto(request.transcript)?;
-
-    Ok(EnrichTranscriptRequest {
+    let transcript = request
+        .transcript
+        .ok_or_else(|| Status::invalid_argument("transcript is required"))?;
+    let mut transcript = transcript_from_proto(transcript)
+        .map_err(|err| Status::invalid_argument(format!("transcript is invalid: {err}")))?;
+    let warning = fit_transcript_to_audio(
+        &mut transcript,
+        request.samples.len(),
+        request.sample_rate_hz.unwrap_or(default_sample_rate_hz),
+        request.time_offset_ms,
+    )?;
+
+    let request = EnrichTranscriptRequest {
         samples: request.samples,
         sample_rate_hz: request.sample_rate_hz,
         transcript,
         session_id: request.session_id,
-    })
+        time_offset_ms: request.time_offset_ms,
+    };
+    Ok((request, warning))
 }
 
-fn map_enrich_response(response: EnrichTranscriptResponse) -> pb::EnrichTranscriptResponse {
-    pb::EnrichTranscriptResponse {
-        session_id: response.session_id,
-        transcript: Some(map_transcript(response.transcript)),
-        aligned_words: response
-            .aligned_words
-            .into_iter()
-            .map(map_word_timing)
-            .collect(),
-        text: response.text,
-    }
-}
-
-fn map_transcript(transcript: Transcript) -> pb::Transcript {
-    pb::Transcript {
-        language: Some(map_language_tag(transcript.language)),
-        segments: transcript
-            .segments
-            .into_iter()
-            .map(map_transcript_segment)
-            .collect(),
+/// Checks that `transcript` ends within the audio it is aligned against. A
+/// transcript ending up to [`TRANSCRIPT_OVERRUN_TOLERANCE_MS`] past the audio
+/// is clamped to its end with a warning; one ending further out is rejected.
+fn fit_transcript_to_audio(
+    transcript: &mut Transcript,
+    sample_count: usize,
+    sample_rate_hz: u32,
+    time_offset_ms: Option<u64>,
+) -> Result<Option<pb::AlignmentWarning>, Status> {
+    let duration_ms = sample_count as u64 * 1_000 / u64::from(sample_rate_hz);
+    let audio_end_ms = time_offset_ms.unwrap_or(0).saturating_add(duration_ms);
+    let transcript_end_ms = transcript
+        .segments
+        .iter()
+        .flat_map(|segment| {
+            std::iter::once(segment.end_ms).chain(segment.tokens.iter().map(|token| token.end_ms))
+        })
+        .max()
+        .unwrap_or(0);
+    let overrun_ms = transcript_end_ms.saturating_sub(audio_end_ms);
+    if overrun_ms == 0 {
+        return Ok(None);
     }
-}
-
-fn map_transcript_segment(segment: TranscriptSegment) -> pb::TranscriptSegment {
-    pb::TranscriptSegment {
-        text: segment.text,
-        start_ms: segment.start_ms,
-        end_ms: segment.end_ms,
-        tokens: segment.tokens.into_iter().map(map_transcript_token).collect(),
+    if overrun_ms > TRANSCRIPT_OVERRUN_TOLERANCE_MS {
+        return Err(Status::invalid_argument(format!(
+            "transcript ends at {transcript_end_ms} ms, {overrun_ms} ms after the audio \
+             ({audio_end_ms} ms)"
+        )));
     }
-}
 
-fn map_transcript_token(token: TranscriptToken) -> pb::TranscriptToken {
-    pb::TranscriptToken {
-        text: token.text,
-        start_ms: token.start_ms,
-        end_ms: token.end_ms,
-        confidence: token.confidence,
-    }
-}
-
-fn map_word_timing(word: WordTiming) -> pb::WordTiming {
-    pb::WordTiming {
-        word: word.word,
-        start_ms: word.start_ms,
-        end_ms: word.end_ms,
-        confidence: word.confidence,
+    let end_sample = ms_to_sample(audio_end_ms, sample_rate_hz);
+    for segment in &mut transcript.segments {
+        segment.start_ms = segment.start_ms.min(audio_end_ms);
+        segment.end_ms = segment.end_ms.min(audio_end_ms);
+        for token in &mut segment.tokens {
+            token.start_ms = token.start_ms.min(audio_end_ms);
+            token.end_ms = token.end_ms.min(audio_end_ms);
+            token.start_sample = token.start_sample.map(|sample| sample.min(end_sample));
+            token.end_sample = token.end_sample.map(|sample| sample.min(end_sample));
+        }
     }
+    tracing::warn!(
+        transcript_end_ms,
+        audio_end_ms,
+        overrun_ms,
+        "transcript ends after its audio, clamped"
+    );
+    Ok(Some(pb::AlignmentWarning {
+        code: TRANSCRIPT_CLAMPED.to_string(),
+        message: format!(
+            "transcript ended {overrun_ms} ms after the audio and was clamped to {audio_end_ms} ms"
+        ),
+    }))
 }
 
-fn map_transcript_from_proto(transcript: Option<pb::Transcript>) -> Result<Transcript, Status> {
-    let transcript = transcript.ok_or_else(|| Status::invalid_argument("transcript is required"))?;
-
-    Ok(Transcript {
-        language: map_language_tag_from_proto(transcript.language)?,
-        segments: transcript
-            .segments
-            .into_iter()
-            .map(map_transcript_segment_from_proto)
-            .collect(),
-    })
-}
-
-fn map_transcript_segment_from_proto(segment: pb::TranscriptSegment) -> TranscriptSegment {
-    TranscriptSegment {
-        text: segment.text,
-        start_ms: segment.start_ms,
-        end_ms: segment.end_ms,
-        tokens: segment
-            .tokens
+fn map_enrich_response(response: EnrichTranscriptResponse) -> pb::EnrichTranscriptResponse {
+    pb::EnrichTranscriptResponse {
+        session_id: response.session_id,
+        transcript: Some(transcript_to_proto(response.transcript)),
+        aligned_words: response
+            .aligned_words
             .into_iter()
-            .map(map_transcript_token_from_proto)
+            .map(word_timing_to_proto)
             .collect(),
-    }
-}
-
-fn map_transcript_token_from_proto(token: pb::TranscriptToken) -> TranscriptToken {
-    TranscriptToken {
-        text: token.text,
-        start_ms: token.start_ms,
-        end_ms: token.end_ms,
-        confidence: token.confidence,
-    }
-}
-
-fn map_language_tag(language: LanguageTag) -> pb::LanguageTag {
-    match language {
-        LanguageTag::Fr => pb::LanguageTag {
-            code: LANGUAGE_TAG_CODE_FR,
-            other: None,
-        },
-        LanguageTag::En => pb::LanguageTag {
-            code: LANGUAGE_TAG_CODE_EN,
-            other: None,
-        },
-        LanguageTag::Auto => pb::LanguageTag {
-            code: LANGUAGE_TAG_CODE_AUTO,
-            other: None,
-        },
-        LanguageTag::Other(value) => pb::LanguageTag {
-            code: LANGUAGE_TAG_CODE_OTHER,
-            other: Some(value),
-        },
-    }
-}
-
-fn map_language_tag_from_proto(language: Option<pb::LanguageTag>) -> Result<LanguageTag, Status> {
-    let language = language.ok_or_else(|| Status::invalid_argument("transcript.language is required"))?;
-
-    match language.code {
-        LANGUAGE_TAG_CODE_FR => Ok(LanguageTag::Fr),
-        LANGUAGE_TAG_CODE_EN => Ok(LanguageTag::En),
-        LANGUAGE_TAG_CODE_AUTO => Ok(LanguageTag::Auto),
-        LANGUAGE_TAG_CODE_OTHER => {
-            let value = language.other.unwrap_or_default();
-            if value.is_empty() {
-                return Err(Status::invalid_argument(
-                    "transcript.language.other cannot be empty when code is OTHER",
-                ));
-            }
-            Ok(LanguageTag::Other(value))
-        }
-        _ => Err(Status::invalid_argument(
-            "transcript.language.code is invalid",
-        )),
+        text: response.text,
+        skipped_reason: response
+            .skipped_reason
+            .map(|reason| reason.as_str().to_string()),
+        warnings: Vec::new(),
+        resampled_from_hz: response.resampled_from_hz,
     }
 }
 
@@ -237,13 +235,18 @@ fn map_command_error(error: CommandError) -> Status {
         CommandError::Validation { .. } => Status::invalid_argument(error.to_string()),
         CommandError::Authentication { .. } => Status::unauthenticated(error.to_string()),
         CommandError::Business { .. } => {
-            let message = error.message().to_ascii_lowercase();
-            if message.contains("not found") {
-                Status::not_found(error.to_string())
-            } else if message.contains("permission") || message.contains("forbidden") {
-                Status::permission_denied(error.to_string())
-            } else {
-                Status::failed_precondition(error.to_string())
+            let message = error.to_string();
+            match ErrorCode::from_code(error.error_code()) {
+                ErrorCode::InvalidInput | ErrorCode::AudioQuality => {
+                    Status::invalid_argument(message)
+                }
+                ErrorCode::NotFound => Status::not_found(message),
+                ErrorCode::AlreadyExists => Status::already_exists(message),
+                ErrorCode::PermissionDenied => Status::permission_denied(message),
+                ErrorCode::FailedPrecondition => Status::failed_precondition(message),
+                ErrorCode::ResourceExhausted => Status::resource_exhausted(message),
+                ErrorCode::Unavailable => Status::unavailable(message),
+                ErrorCode::Internal => Status::internal(message),
             }
         }
         CommandError::Infrastructure { .. }
@@ -285,13 +288,22 @@ fn validate_optional_text(value: &Option<String>, field: &str, max_len: usize) -
 mod tests {
     use std::{net::TcpListener, sync::Arc, time::Duration};
 
-    use alignment_application::{AlignTranscriptUseCase, AlignmentCommandRegistryFactory};
-    use alignment_domain::WordTiming;
+    use alignment_application::{
+        AlignTranscriptUseCase, AlignTranscriptUseCaseImpl, AlignmentCommandRegistryFactory,
+    };
+    use alignment_domain::{
+        AlignmentOutput, AlignmentPort, AlignmentRequest, DomainError, LanguageTag, Transcript,
+        TranscriptSegment, WordTiming,
+    };
     use rustycog_command::GenericCommandService;
     use rustycog_config::ServerConfig;
+    use service_health::AlwaysReady;
     use tonic::Request;
 
-    use super::{pb, serve_grpc, AlignmentServiceClient};
+    use super::{
+        fit_transcript_to_audio, map_enrich_request, pb, serve_grpc, transcript_to_proto,
+        AlignmentServiceClient,
+    };
 
     struct MockAlignmentUseCase;
 
@@ -314,12 +326,30 @@ mod tests {
                     start_ms: 0,
                     end_ms: 150,
                     confidence: 0.95,
+                    speaker: None,
+                    start_sample: None,
+                    end_sample: None,
+                    energy_rms: None,
+                    pitch_hz: None,
+                    out_of_band: false,
                 }],
                 text: "hello world".to_string(),
+                skipped_reason: None,
+                resampled_from_hz: None,
             })
         }
     }
 
+    /// Fails the test if the model is reached.
+    struct UnreachableAligner;
+
+    #[tonic::async_trait]
+    impl AlignmentPort for UnreachableAligner {
+        async fn align(&self, _request: AlignmentRequest) -> Result<AlignmentOutput, DomainError> {
+            panic!("blank transcripts must not reach the aligner");
+        }
+    }
+
     #[tokio::test]
     async fn enrich_transcript_rpc_smoke() {
         let port = pick_free_port();
@@ -331,17 +361,19 @@ mod tests {
             AlignmentCommandRegistryFactory::create_registry(Arc::new(MockAlignmentUseCase));
         let command_service = Arc::new(GenericCommandService::new(Arc::new(registry)));
 
-        let server = tokio::spawn(async move { serve_grpc(command_service, server_config).await });
+        let server = tokio::spawn(async move {
+            serve_grpc(command_service, server_config, Arc::new(AlwaysReady), 16_000).await
+        });
         let endpoint = format!("http://127.0.0.1:{port}");
         let mut client = connect_with_retry(endpoint).await;
 
         let response = client
             .enrich_transcript(Request::new(pb::EnrichTranscriptRequest {
-                samples: vec![0.1, 0.2, 0.3],
+                samples: test_audio::speech_like(16_000, 500),
                 sample_rate_hz: Some(16_000),
                 transcript: Some(pb::Transcript {
                     language: Some(pb::LanguageTag {
-                        code: super::LANGUAGE_TAG_CODE_EN,
+                        code: pb::LanguageTagCode::En as i32,
                         other: None,
                     }),
                     segments: vec![pb::TranscriptSegment {
@@ -349,9 +381,14 @@ mod tests {
                         start_ms: 0,
                         end_ms: 250,
                         tokens: vec![],
+                        speaker: None,
+                        language: None,
+                        no_speech_prob: None,
+                        avg_logprob: None,
                     }],
                 }),
                 session_id: Some("it-session".to_string()),
+                time_offset_ms: None,
             }))
             .await
             .expect("rpc succeeds")
@@ -365,6 +402,111 @@ mod tests {
         let _ = server.await;
     }
 
+    #[tokio::test]
+    async fn blank_transcript_gets_an_empty_alignment_with_a_reason() {
+        let port = pick_free_port();
+        let mut server_config = ServerConfig::default();
+        server_config.host = "127.0.0.1".to_string();
+        server_config.port = port;
+
+        let usecase = AlignTranscriptUseCaseImpl::new(Arc::new(UnreachableAligner), 16_000);
+        let registry = AlignmentCommandRegistryFactory::create_registry(Arc::new(usecase));
+        let command_service = Arc::new(GenericCommandService::new(Arc::new(registry)));
+
+        let server = tokio::spawn(async move {
+            serve_grpc(command_service, server_config, Arc::new(AlwaysReady), 16_000).await
+        });
+        let mut client = connect_with_retry(format!("http://127.0.0.1:{port}")).await;
+
+        let response = client
+            .enrich_transcript(Request::new(pb::EnrichTranscriptRequest {
+                samples: test_audio::speech_like(16_000, 500),
+                sample_rate_hz: Some(16_000),
+                transcript: Some(pb::Transcript {
+                    language: Some(pb::LanguageTag {
+                        code: pb::LanguageTagCode::En as i32,
+                        other: None,
+                    }),
+                    segments: vec![pb::TranscriptSegment {
+                        text: " \t ".to_string(),
+                        start_ms: 0,
+                        end_ms: 250,
+                        tokens: vec![],
+                        speaker: None,
+                        language: None,
+                        no_speech_prob: None,
+                        avg_logprob: None,
+                    }],
+                }),
+                session_id: None,
+                time_offset_ms: None,
+            }))
+            .await
+            .expect("blank transcripts are not an error")
+            .into_inner();
+
+        assert!(response.aligned_words.is_empty());
+        assert_eq!(response.text, "");
+        assert_eq!(response.skipped_reason.as_deref(), Some("empty_transcript"));
+
+        server.abort();
+        let _ = server.await;
+    }
+
+    fn segment_ending_at(end_ms: u64) -> Transcript {
+        Transcript {
+            language: LanguageTag::en(),
+            segments: vec![TranscriptSegment {
+                text: "hello world".to_string(),
+                start_ms: 1_000,
+                end_ms,
+                tokens: Vec::new(),
+                speaker: None,
+                language: None,
+                no_speech_prob: None,
+                avg_logprob: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn transcripts_slightly_past_the_audio_are_clamped_with_a_warning() {
+        // One second of audio starting at 1 s on the stream's timeline.
+        let mut transcript = segment_ending_at(2_300);
+        let warning = fit_transcript_to_audio(&mut transcript, 16_000, 16_000, Some(1_000))
+            .expect("within tolerance")
+            .expect("clamp reported");
+        assert_eq!(warning.code, "transcript_clamped");
+        assert_eq!(transcript.segments[0].end_ms, 2_000);
+
+        let mut fitting = segment_ending_at(2_000);
+        let warning = fit_transcript_to_audio(&mut fitting, 16_000, 16_000, Some(1_000))
+            .expect("fits");
+        assert!(warning.is_none());
+    }
+
+    #[test]
+    fn transcripts_far_past_the_audio_are_rejected() {
+        let mut transcript = segment_ending_at(5_000);
+        let status = fit_transcript_to_audio(&mut transcript, 16_000, 16_000, None)
+            .expect_err("transcript from other audio");
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[test]
+    fn requests_without_a_rate_are_checked_at_the_default_rate() {
+        // One second of audio at the default 16 kHz.
+        let request = pb::EnrichTranscriptRequest {
+            samples: vec![0.0; 16_000],
+            sample_rate_hz: None,
+            transcript: Some(transcript_to_proto(segment_ending_at(5_000))),
+            session_id: None,
+            time_offset_ms: None,
+        };
+        let status = map_enrich_request(request, 16_000).expect_err("transcript past the audio");
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
     fn pick_free_port() -> u16 {
         TcpListener::bind("127.0.0.1:0")
             .expect("bind ephemeral port")
@@ -0,0 +1,282 @@
+use axum::{
+    extract::{FromRequest, Multipart, Request, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rustycog_command::CommandContext;
+use rustycog_http::AppState;
+use serde::Deserialize;
+use validator::Validate;
+use wav_io::decode_wav;
+
+use alignment_application::{
+    EnrichTranscriptCommand, EnrichTranscriptRequest, EnrichTranscriptResponse,
+};
+use alignment_domain::{LanguageTag, Transcript, TranscriptSegment};
+
+use crate::error::{error_mapper, HttpError};
+
+/// JSON body of `POST /api/v1/align`. Audio is either `samples` at
+/// `sample_rate_hz`, as in the gRPC request, or a WAV file in `audio_base64`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AlignJsonRequest {
+    #[serde(default)]
+    pub samples: Vec<f32>,
+    #[serde(default)]
+    pub sample_rate_hz: Option<u32>,
+    #[serde(default)]
+    pub audio_base64: Option<String>,
+    pub transcript: TranscriptInput,
+    /// Language of a plain-text `transcript`; ignored for a full transcript.
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Start of the audio on the stream's timeline; see
+    /// [`EnrichTranscriptRequest::time_offset_ms`].
+    #[serde(default)]
+    pub time_offset_ms: Option<u64>,
+}
+
+/// A transcript as the ASR service returns it, or plain text aligned as a
+/// single segment spanning the whole clip.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum TranscriptInput {
+    Transcript(Transcript),
+    Text(String),
+}
+
+/// Takes an [`AlignJsonRequest`], or a `multipart/form-data` upload with the
+/// WAV file in `audio`, the transcript JSON or plain text in `transcript`,
+/// and optional `language`, `session_id` and `time_offset_ms` text parts.
+pub async fn align_transcript(State(state): State<AppState>, request: Request) -> Response {
+    let request = if is_multipart(&request) {
+        match Multipart::from_request(request, &state).await {
+            Ok(multipart) => multipart_request(multipart).await,
+            Err(rejection) => return rejection.into_response(),
+        }
+    } else {
+        match Json::<AlignJsonRequest>::from_request(request, &state).await {
+            Ok(Json(request)) => json_request(request),
+            Err(rejection) => return rejection.into_response(),
+        }
+    };
+
+    let result = match request {
+        Ok(request) => {
+            tracing::info!(
+                sample_count = request.samples.len(),
+                sample_rate_hz = request.sample_rate_hz.unwrap_or(0),
+                segment_count = request.transcript.segments.len(),
+                session_id = request.session_id.as_deref().unwrap_or("auto"),
+                "received align request"
+            );
+            execute_align(&state, request).await
+        }
+        Err(error) => Err(error),
+    };
+
+    match result {
+        Ok(result) => {
+            tracing::info!(
+                aligned_word_count = result.aligned_words.len(),
+                "align request completed"
+            );
+            (StatusCode::OK, Json(result)).into_response()
+        }
+        Err(error) => {
+            tracing::error!(error = ?error, "align request failed");
+            error.into_response()
+        }
+    }
+}
+
+fn is_multipart(request: &Request) -> bool {
+    request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("multipart/form-data"))
+}
+
+fn invalid(message: String) -> HttpError {
+    HttpError::Validation { message }
+}
+
+fn json_request(request: AlignJsonRequest) -> Result<EnrichTranscriptRequest, HttpError> {
+    let (samples, sample_rate_hz) = match request.audio_base64 {
+        Some(encoded) => {
+            let bytes = STANDARD
+                .decode(encoded.trim())
+                .map_err(|err| invalid(format!("audio_base64: {err}")))?;
+            let wav = decode_wav(&bytes).map_err(|err| invalid(format!("audio_base64: {err}")))?;
+            (wav.samples, Some(wav.sample_rate_hz))
+        }
+        None => (request.samples, request.sample_rate_hz),
+    };
+    build_request(
+        samples,
+        sample_rate_hz,
+        request.transcript,
+        request.language,
+        request.session_id,
+        request.time_offset_ms,
+    )
+}
+
+async fn multipart_request(mut multipart: Multipart) -> Result<EnrichTranscriptRequest, HttpError> {
+    let mut audio = None;
+    let mut transcript = None;
+    let mut language = None;
+    let mut session_id = None;
+    let mut time_offset_ms = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|err| invalid(format!("invalid multipart body: {err}")))?
+    {
+        let name = field.name().unwrap_or_default().to_string();
+        if name == "audio" {
+            let bytes = field
+                .bytes()
+                .await
+                .map_err(|err| invalid(format!("cannot read `audio`: {err}")))?;
+            audio = Some(decode_wav(&bytes).map_err(|err| invalid(format!("audio: {err}")))?);
+            continue;
+        }
+
+        let slot = match name.as_str() {
+            "transcript" => &mut transcript,
+            "language" => &mut language,
+            "session_id" => &mut session_id,
+            "time_offset_ms" => &mut time_offset_ms,
+            _ => continue,
+        };
+        let text = field
+            .text()
+            .await
+            .map_err(|err| invalid(format!("cannot read `{name}`: {err}")))?;
+        *slot = Some(text);
+    }
+
+    let audio = audio.ok_or_else(|| invalid("multipart body needs an `audio` WAV file".into()))?;
+    let transcript =
+        transcript.ok_or_else(|| invalid("multipart body needs a `transcript` part".into()))?;
+    let transcript = match serde_json::from_str::<Transcript>(&transcript) {
+        Ok(transcript) => TranscriptInput::Transcript(transcript),
+        Err(_) if !transcript.trim_start().starts_with('{') => TranscriptInput::Text(transcript),
+        Err(err) => return Err(invalid(format!("transcript: {err}"))),
+    };
+    let time_offset_ms = time_offset_ms
+        .map(|text| {
+            text.trim()
+                .parse()
+                .map_err(|_| invalid(format!("invalid time_offset_ms `{text}`")))
+        })
+        .transpose()?;
+    build_request(
+        audio.samples,
+        Some(audio.sample_rate_hz),
+        transcript,
+        language,
+        session_id,
+        time_offset_ms,
+    )
+}
+
+fn build_request(
+    samples: Vec<f32>,
+    sample_rate_hz: Option<u32>,
+    transcript: TranscriptInput,
+    language: Option<String>,
+    session_id: Option<String>,
+    time_offset_ms: Option<u64>,
+) -> Result<EnrichTranscriptRequest, HttpError> {
+    let transcript = match transcript {
+        TranscriptInput::Transcript(transcript) => transcript,
+        TranscriptInput::Text(text) => {
+            let language = match language.as_deref() {
+                Some(tag) => {
+                    LanguageTag::parse(tag).map_err(|err| invalid(format!("language: {err}")))?
+                }
+                None => LanguageTag::Auto,
+            };
+            let offset_ms = time_offset_ms.unwrap_or(0);
+            let duration_ms = match sample_rate_hz {
+                Some(rate) if rate > 0 => samples.len() as u64 * 1_000 / u64::from(rate),
+                _ => 0,
+            };
+            Transcript {
+                language,
+                segments: vec![TranscriptSegment {
+                    text,
+                    start_ms: offset_ms,
+                    end_ms: offset_ms + duration_ms,
+                    tokens: Vec::new(),
+                    speaker: None,
+                    language: None,
+                    no_speech_prob: None,
+                    avg_logprob: None,
+                }],
+            }
+        }
+    };
+
+    let request = EnrichTranscriptRequest {
+        samples,
+        sample_rate_hz,
+        transcript,
+        session_id,
+        time_offset_ms,
+    };
+    request.validate().map_err(|err| invalid(err.to_string()))?;
+    Ok(request)
+}
+
+async fn execute_align(
+    state: &AppState,
+    request: EnrichTranscriptRequest,
+) -> Result<EnrichTranscriptResponse, HttpError> {
+    let command = EnrichTranscriptCommand::new(request);
+    let context = CommandContext::new();
+    state
+        .command_service
+        .execute(command, context)
+        .await
+        .map_err(error_mapper)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_wav_and_plain_text_become_an_enrich_request() {
+        let wav = test_audio::wav_bytes(&test_audio::tone(440.0, 16_000, 500), 16_000);
+        let request: AlignJsonRequest = serde_json::from_value(serde_json::json!({
+            "audio_base64": STANDARD.encode(wav),
+            "transcript": "bonjour le monde",
+            "language": "fr-FR",
+        }))
+        .expect("valid body");
+
+        let request = json_request(request).expect("converts");
+        assert_eq!(request.sample_rate_hz, Some(16_000));
+        assert_eq!(request.samples.len(), 8_000);
+        assert_eq!(request.transcript.language.to_string(), "fr-FR");
+        assert_eq!(request.transcript.segments[0].end_ms, 500);
+    }
+
+    #[test]
+    fn bad_base64_is_a_validation_error() {
+        let request: AlignJsonRequest = serde_json::from_value(serde_json::json!({
+            "audio_base64": "%%%",
+            "transcript": "hello",
+        }))
+        .expect("valid body");
+        assert!(matches!(json_request(request), Err(HttpError::Validation { .. })));
+    }
+}
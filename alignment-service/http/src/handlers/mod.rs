@@ -0,0 +1,3 @@
+mod align;
+
+pub use align::{align_transcript, AlignJsonRequest};
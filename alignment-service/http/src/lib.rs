@@ -0,0 +1,41 @@
+//! Optional REST front end for alignment-service, for scripts that want word
+//! timings without a gRPC client.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::DefaultBodyLimit,
+    middleware,
+    routing::{get, post},
+};
+use log_context::http_request_span;
+use rustycog_config::ServerConfig;
+use rustycog_http::{AppState, RouteBuilder};
+use service_health::{liveness, readiness, ReadinessCheck, LIVENESS_PATH, READINESS_PATH};
+
+pub mod error;
+pub mod handlers;
+
+pub use error::{error_mapper, HttpError};
+pub use handlers::*;
+
+pub const ALIGN_PATH: &str = "/api/v1/align";
+
+pub async fn create_app_routes(
+    state: AppState,
+    config: ServerConfig,
+    readiness_check: Arc<dyn ReadinessCheck>,
+) -> anyhow::Result<()> {
+    // Base64 WAV and float-array bodies can be large.
+    let align_route = post(align_transcript)
+        .layer(DefaultBodyLimit::max(64 * 1024 * 1024))
+        .layer(middleware::from_fn(http_request_span));
+
+    RouteBuilder::new(state)
+        .health_check()
+        .route(LIVENESS_PATH, get(liveness))
+        .route(READINESS_PATH, get(move || readiness(readiness_check.clone())))
+        .route(ALIGN_PATH, align_route)
+        .build(config)
+        .await
+}
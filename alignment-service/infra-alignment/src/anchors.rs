@@ -0,0 +1,219 @@
+//! Alignment windows anchored on the transcript's own timings, so each
+//! segment is aligned against its slice of the audio rather than the whole
+//! clip. Long recordings stay robust to one bad stretch, and the trellis of
+//! every pass stays small.
+
+use alignment_domain::{Transcript, TranscriptSegment};
+
+#[derive(Debug, Clone, Copy)]
+pub struct AnchorConfig {
+    /// Audio added on both sides of an anchored span, covering drift in the
+    /// transcript's timings.
+    pub padding_ms: u64,
+    /// Longest span aligned in one pass. Longer segments are split between
+    /// words, at word times taken from their tokens.
+    pub max_window_ms: u64,
+}
+
+impl Default for AnchorConfig {
+    fn default() -> Self {
+        Self {
+            padding_ms: 500,
+            max_window_ms: 30_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AlignmentWindow {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+}
+
+/// One window per segment, or per run of words for segments longer than
+/// `max_window_ms`. `None` when a segment has no usable timings, e.g. plain
+/// text spread over the clip, so the caller aligns the whole clip instead.
+pub(crate) fn anchor_windows(
+    transcript: &Transcript,
+    duration_ms: u64,
+    config: &AnchorConfig,
+) -> Option<Vec<AlignmentWindow>> {
+    let mut windows = Vec::new();
+    for segment in &transcript.segments {
+        let text = segment.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        if segment.end_ms <= segment.start_ms || segment.start_ms >= duration_ms {
+            return None;
+        }
+        let spans = if segment.end_ms - segment.start_ms > config.max_window_ms {
+            split_segment(segment, config.max_window_ms)
+        } else {
+            vec![(segment.start_ms, segment.end_ms, text.to_string())]
+        };
+        windows.extend(spans.into_iter().map(|(start_ms, end_ms, text)| AlignmentWindow {
+            start_ms: start_ms.saturating_sub(config.padding_ms),
+            end_ms: end_ms.saturating_add(config.padding_ms).min(duration_ms),
+            text,
+        }));
+    }
+    (!windows.is_empty()).then_some(windows)
+}
+
+/// Splits a long segment into runs of words spanning at most
+/// `max_window_ms`, or keeps it whole when its tokens do not line up with
+/// its words.
+fn split_segment(segment: &TranscriptSegment, max_window_ms: u64) -> Vec<(u64, u64, String)> {
+    let words: Vec<&str> = segment.text.split_whitespace().collect();
+    let anchors = word_anchors(segment);
+    if anchors.len() != words.len() {
+        return vec![(segment.start_ms, segment.end_ms, words.join(" "))];
+    }
+
+    let mut spans = Vec::new();
+    let mut first = 0;
+    for index in 1..=words.len() {
+        let split = index == words.len()
+            || anchors[index].1.saturating_sub(anchors[first].0) > max_window_ms;
+        if split {
+            spans.push((anchors[first].0, anchors[index - 1].1, words[first..index].join(" ")));
+            first = index;
+        }
+    }
+    spans
+}
+
+/// Start and end of each word of `segment`, from its tokens. A token led by
+/// whitespace starts a word; Whisper's special tokens are skipped.
+fn word_anchors(segment: &TranscriptSegment) -> Vec<(u64, u64)> {
+    let mut anchors: Vec<(u64, u64)> = Vec::new();
+    for token in &segment.tokens {
+        let text = token.text.trim();
+        if text.is_empty() || text.starts_with("[_") || text.starts_with("<|") {
+            continue;
+        }
+        match anchors.last_mut() {
+            Some(last) if !token.text.starts_with(char::is_whitespace) => {
+                last.1 = last.1.max(token.end_ms);
+            }
+            _ => anchors.push((token.start_ms, token.end_ms.max(token.start_ms))),
+        }
+    }
+    anchors
+}
+
+#[cfg(test)]
+mod tests {
+    use alignment_domain::{LanguageTag, TranscriptToken};
+
+    use super::*;
+
+    fn token(text: &str, start_ms: u64, end_ms: u64) -> TranscriptToken {
+        TranscriptToken {
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+            confidence: 1.0,
+            start_sample: None,
+            end_sample: None,
+            disfluency: false,
+        }
+    }
+
+    fn segment(
+        text: &str,
+        start_ms: u64,
+        end_ms: u64,
+        tokens: Vec<TranscriptToken>,
+    ) -> TranscriptSegment {
+        TranscriptSegment {
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+            tokens,
+            speaker: None,
+            language: None,
+            no_speech_prob: None,
+            avg_logprob: None,
+        }
+    }
+
+    fn transcript(segments: Vec<TranscriptSegment>) -> Transcript {
+        Transcript {
+            language: LanguageTag::en(),
+            segments,
+        }
+    }
+
+    #[test]
+    fn each_segment_gets_a_padded_window() {
+        let transcript = transcript(vec![
+            segment(" Hello there.", 200, 1_500, Vec::new()),
+            segment("  ", 1_500, 1_600, Vec::new()),
+            segment(" Bye.", 4_000, 4_800, Vec::new()),
+        ]);
+        let windows =
+            anchor_windows(&transcript, 5_000, &AnchorConfig::default()).expect("anchored");
+        assert_eq!(
+            windows,
+            vec![
+                AlignmentWindow {
+                    start_ms: 0,
+                    end_ms: 2_000,
+                    text: "Hello there.".to_string(),
+                },
+                AlignmentWindow {
+                    start_ms: 3_500,
+                    end_ms: 5_000,
+                    text: "Bye.".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn segments_without_timings_are_not_anchored() {
+        let transcript = transcript(vec![segment("plain text", 0, 0, Vec::new())]);
+        assert_eq!(anchor_windows(&transcript, 5_000, &AnchorConfig::default()), None);
+    }
+
+    #[test]
+    fn long_segments_split_between_words_at_token_times() {
+        let long = segment(
+            " one two three",
+            0,
+            50_000,
+            vec![
+                token("[_BEG_]", 0, 0),
+                token(" one", 0, 10_000),
+                token(" tw", 20_000, 22_000),
+                token("o", 22_000, 25_000),
+                token(" three", 40_000, 50_000),
+            ],
+        );
+        let config = AnchorConfig {
+            padding_ms: 0,
+            max_window_ms: 30_000,
+        };
+        let windows = anchor_windows(&transcript(vec![long]), 60_000, &config).expect("anchored");
+        let spans: Vec<_> = windows
+            .iter()
+            .map(|window| (window.start_ms, window.end_ms, window.text.as_str()))
+            .collect();
+        assert_eq!(spans, vec![(0, 25_000, "one two"), (40_000, 50_000, "three")]);
+    }
+
+    #[test]
+    fn long_segments_with_mismatched_tokens_stay_whole() {
+        let long = segment(" one two", 0, 50_000, vec![token(" one", 0, 10_000)]);
+        let config = AnchorConfig {
+            padding_ms: 0,
+            max_window_ms: 30_000,
+        };
+        let windows = anchor_windows(&transcript(vec![long]), 60_000, &config).expect("anchored");
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0].text, "one two");
+    }
+}
@@ -1,39 +1,257 @@
 use alignment_domain::{
-    AlignmentOutput, AlignmentPort, AlignmentRequest, DomainError, WordTiming,
+    ms_to_sample, AlignmentOutput, AlignmentPort, AlignmentRequest, AudioChunk, DomainError,
+    WordTiming,
 };
 use async_trait::async_trait;
+use service_health::{DependencyStatus, ReadinessCheck};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use vocal_dsp::resample_linear;
 use wav2vec2_rs::{
     AlignmentError, AlignmentInput, ForcedAligner as CoreForcedAligner, ForcedAlignerBuilder,
     RuntimeKind, Wav2Vec2Config,
 };
 
+mod anchors;
+mod precision;
+mod vocab;
+
+pub use anchors::AnchorConfig;
+pub use precision::Precision;
+pub use vocab::CustomVocabulary;
+
+use anchors::{anchor_windows, AlignmentWindow};
+
+const WARMUP_TRANSCRIPT: &str = "bonjour";
+/// Tries CUDA and falls back to the CPU when CUDA cannot be used.
+const AUTO_DEVICE: &str = "auto";
+const CUDA_DEVICE: &str = "cuda";
+const CPU_DEVICE: &str = "cpu";
+
 #[derive(Debug, Clone)]
 pub struct Wav2Vec2AdapterConfig {
     pub model_path: String,
     pub config_path: String,
     pub vocab_path: String,
+    /// `cpu`, `cuda`, or `auto`: CUDA when it initializes, else the CPU,
+    /// with requests that run CUDA out of memory retried on the CPU.
     pub device: String,
+    /// Weights to load; reduced precisions read the converted sibling of
+    /// `model_path` named by [`Precision::model_path`].
+    pub precision: Precision,
+    pub vocabulary: CustomVocabulary,
+    /// Align each transcript segment against its own slice of the audio;
+    /// `None` aligns the whole clip in one pass.
+    pub anchors: Option<AnchorConfig>,
 }
 
 pub struct Wav2Vec2ForcedAligner {
     aligner: CoreForcedAligner,
+    /// Device `aligner` runs on, `auto` resolved.
+    device: String,
+    vocabulary: CustomVocabulary,
+    anchors: Option<AnchorConfig>,
+    /// Model files, kept under `auto` to build the CPU fallback on demand.
+    fallback_files: Option<ModelFiles>,
+    cpu_fallback: Mutex<Option<Arc<CoreForcedAligner>>>,
+    cpu_fallbacks: AtomicU64,
+}
+
+#[derive(Debug, Clone)]
+struct ModelFiles {
+    model_path: String,
+    config_path: String,
+    vocab_path: String,
+}
+
+impl ModelFiles {
+    fn build(&self, device: &str) -> Result<CoreForcedAligner, AlignmentError> {
+        ForcedAlignerBuilder::new(Wav2Vec2Config {
+            model_path: self.model_path.clone(),
+            config_path: self.config_path.clone(),
+            vocab_path: self.vocab_path.clone(),
+            device: device.to_string(),
+            expected_sample_rate_hz: Wav2Vec2Config::DEFAULT_SAMPLE_RATE_HZ,
+        })
+        .with_runtime_kind(RuntimeKind::Onnx)
+        .build()
+    }
+}
+
+/// A word as aligned, before speakers and sample indices are attached.
+struct AlignedWord {
+    word: String,
+    start_ms: u64,
+    end_ms: u64,
+    confidence: f32,
 }
 
 impl Wav2Vec2ForcedAligner {
     pub fn load(adapter_cfg: &Wav2Vec2AdapterConfig) -> Result<Self, DomainError> {
-        let core_cfg = Wav2Vec2Config {
-            model_path: adapter_cfg.model_path.clone(),
+        let vocab_path = if adapter_cfg.vocabulary.extra_tokens.is_empty() {
+            adapter_cfg.vocab_path.clone()
+        } else {
+            let merged = adapter_cfg.vocabulary.write_merged(&adapter_cfg.vocab_path)?;
+            merged.to_string_lossy().into_owned()
+        };
+        let files = ModelFiles {
+            model_path: adapter_cfg.precision.model_path(&adapter_cfg.model_path),
             config_path: adapter_cfg.config_path.clone(),
-            vocab_path: adapter_cfg.vocab_path.clone(),
-            device: adapter_cfg.device.clone(),
-            expected_sample_rate_hz: Wav2Vec2Config::DEFAULT_SAMPLE_RATE_HZ,
+            vocab_path,
         };
 
-        let aligner = ForcedAlignerBuilder::new(core_cfg)
-            .with_runtime_kind(RuntimeKind::Onnx)
-            .build()
-            .map_err(Self::map_error)?;
-        Ok(Self { aligner })
+        let auto = adapter_cfg.device.eq_ignore_ascii_case(AUTO_DEVICE);
+        let (aligner, device) = if auto {
+            match files.build(CUDA_DEVICE) {
+                Ok(aligner) => (aligner, CUDA_DEVICE),
+                Err(err) => {
+                    tracing::warn!(error = %err, "wav2vec2 cannot use cuda, falling back to cpu");
+                    (files.build(CPU_DEVICE).map_err(Self::map_error)?, CPU_DEVICE)
+                }
+            }
+        } else {
+            (
+                files.build(&adapter_cfg.device).map_err(Self::map_error)?,
+                adapter_cfg.device.as_str(),
+            )
+        };
+        tracing::info!(device, precision = adapter_cfg.precision.as_str(), "wav2vec2 model loaded");
+
+        Ok(Self {
+            aligner,
+            device: device.to_string(),
+            vocabulary: adapter_cfg.vocabulary.clone(),
+            anchors: adapter_cfg.anchors,
+            fallback_files: (auto && device == CUDA_DEVICE).then_some(files),
+            cpu_fallback: Mutex::new(None),
+            cpu_fallbacks: AtomicU64::new(0),
+        })
+    }
+
+    /// Device the model runs on, with `auto` resolved to `cuda` or `cpu`.
+    pub fn device(&self) -> &str {
+        &self.device
+    }
+
+    /// Requests retried on the CPU after CUDA ran out of memory.
+    pub fn cpu_fallbacks(&self) -> u64 {
+        self.cpu_fallbacks.load(Ordering::Relaxed)
+    }
+
+    /// Runs `align` on the configured device; under `auto`, a CUDA
+    /// out-of-memory failure is retried on a CPU copy of the model.
+    fn run<T>(
+        &self,
+        align: impl Fn(&CoreForcedAligner) -> Result<T, AlignmentError>,
+    ) -> Result<T, DomainError> {
+        let error = match align(&self.aligner) {
+            Ok(output) => return Ok(output),
+            Err(error) => error,
+        };
+        let Some(files) = self.fallback_files.as_ref().filter(|_| is_out_of_memory(&error))
+        else {
+            return Err(Self::map_error(error));
+        };
+
+        tracing::warn!(error = %error, "wav2vec2 ran out of cuda memory, retrying on cpu");
+        let cpu = {
+            let mut fallback = self
+                .cpu_fallback
+                .lock()
+                .map_err(|_| DomainError::internal_error("cpu fallback lock poisoned"))?;
+            match fallback.as_ref() {
+                Some(cpu) => cpu.clone(),
+                None => {
+                    let cpu = Arc::new(files.build(CPU_DEVICE).map_err(Self::map_error)?);
+                    *fallback = Some(cpu.clone());
+                    cpu
+                }
+            }
+        };
+        self.cpu_fallbacks.fetch_add(1, Ordering::Relaxed);
+        align(&cpu).map_err(Self::map_error)
+    }
+
+    /// Runs one forward pass over a second of silence so the ONNX session and
+    /// device memory pools are initialized before the first real request.
+    pub fn warm_up(&self) -> Result<(), DomainError> {
+        let sample_rate_hz = Wav2Vec2Config::DEFAULT_SAMPLE_RATE_HZ;
+        let input = AlignmentInput {
+            sample_rate_hz,
+            samples: vec![0.0; sample_rate_hz as usize],
+            transcript: WARMUP_TRANSCRIPT.to_string(),
+            normalized: None,
+        };
+        self.run(|aligner| aligner.align(&input)).map(|_| ())
+    }
+
+    /// Aligns `text` against `samples`, with times relative to `offset_ms`.
+    fn align_text(
+        &self,
+        samples: Vec<f32>,
+        sample_rate_hz: u32,
+        text: &str,
+        offset_ms: u64,
+    ) -> Result<Vec<AlignedWord>, DomainError> {
+        let input = AlignmentInput {
+            sample_rate_hz,
+            samples,
+            transcript: self.vocabulary.map_graphemes(text),
+            normalized: None,
+        };
+        let output = self.run(|aligner| aligner.align(&input))?;
+
+        // Mapped graphemes are only for the aligner; report the words as the
+        // transcript spelled them when the word count still lines up.
+        let original_words: Vec<&str> = text.split_whitespace().collect();
+        let restore = !self.vocabulary.grapheme_map.is_empty()
+            && original_words.len() == output.words.len();
+
+        Ok(output
+            .words
+            .into_iter()
+            .enumerate()
+            .map(|(index, word)| AlignedWord {
+                word: if restore {
+                    original_words[index].to_string()
+                } else {
+                    word.word
+                },
+                start_ms: offset_ms + word.start_ms,
+                end_ms: offset_ms + word.end_ms,
+                confidence: word.confidence.unwrap_or(0.0),
+            })
+            .collect())
+    }
+
+    /// Aligns every window against its slice of `audio`. Padded windows
+    /// overlap, so a word never starts before the previous one ends.
+    fn align_windows(
+        &self,
+        audio: &AudioChunk,
+        windows: &[AlignmentWindow],
+    ) -> Result<Vec<AlignedWord>, DomainError> {
+        let sample_rate_hz = audio.sample_rate_hz;
+        let mut words: Vec<AlignedWord> = Vec::new();
+        for window in windows {
+            let start = (ms_to_sample(window.start_ms, sample_rate_hz) as usize)
+                .min(audio.samples.len());
+            let end = (ms_to_sample(window.end_ms, sample_rate_hz) as usize)
+                .clamp(start, audio.samples.len());
+            let aligned = self.align_text(
+                audio.samples[start..end].to_vec(),
+                sample_rate_hz,
+                &window.text,
+                window.start_ms,
+            )?;
+            for mut word in aligned {
+                let floor = words.last().map_or(0, |previous| previous.end_ms);
+                word.start_ms = word.start_ms.max(floor);
+                word.end_ms = word.end_ms.max(word.start_ms);
+                words.push(word);
+            }
+        }
+        Ok(words)
     }
 
     fn map_error(error: AlignmentError) -> DomainError {
@@ -47,36 +265,122 @@ impl Wav2Vec2ForcedAligner {
 #[async_trait]
 impl AlignmentPort for Wav2Vec2ForcedAligner {
     async fn align(&self, request: AlignmentRequest) -> Result<AlignmentOutput, DomainError> {
-        let transcript_text = request
-            .transcript
-            .segments
-            .iter()
-            .map(|segment| segment.text.trim())
-            .filter(|text| !text.is_empty())
-            .collect::<Vec<_>>()
-            .join(" ");
-
-        let output = self
-            .aligner
-            .align(&AlignmentInput {
-                sample_rate_hz: request.audio.sample_rate_hz,
-                samples: request.audio.samples,
-                transcript: transcript_text,
-                normalized: None,
-            })
-            .map_err(Self::map_error)?;
+        // Times are in milliseconds, so only the sample indices reported
+        // below depend on the caller's rate.
+        let sample_rate_hz = request.audio.sample_rate_hz;
+        let (audio, resampled_from_hz) = to_model_rate(request.audio);
+        let duration_ms = audio.samples.len() as u64 * 1_000 / u64::from(audio.sample_rate_hz);
+        let windows = self
+            .anchors
+            .and_then(|anchors| anchor_windows(&request.transcript, duration_ms, &anchors));
+
+        // A window whose slice cannot hold its words fails; the whole clip
+        // then gets one unanchored pass.
+        let anchored = windows.and_then(|windows| self.align_windows(&audio, &windows).ok());
+        let words = match anchored {
+            Some(words) => words,
+            None => {
+                let transcript_text = request
+                    .transcript
+                    .segments
+                    .iter()
+                    .map(|segment| segment.text.trim())
+                    .filter(|text| !text.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                self.align_text(audio.samples, audio.sample_rate_hz, &transcript_text, 0)?
+            }
+        };
 
         Ok(AlignmentOutput {
-            words: output
-                .words
+            words: words
                 .into_iter()
                 .map(|word| WordTiming {
+                    speaker: request
+                        .transcript
+                        .speaker_at(word.start_ms, word.end_ms)
+                        .map(str::to_string),
+                    start_sample: Some(ms_to_sample(word.start_ms, sample_rate_hz)),
+                    end_sample: Some(ms_to_sample(word.end_ms, sample_rate_hz)),
                     word: word.word,
                     start_ms: word.start_ms,
                     end_ms: word.end_ms,
-                    confidence: word.confidence.unwrap_or(0.0),
+                    confidence: word.confidence,
+                    energy_rms: None,
+                    pitch_hz: None,
+                    out_of_band: false,
                 })
                 .collect(),
+            resampled_from_hz,
         })
     }
 }
+
+#[async_trait]
+impl ReadinessCheck for Wav2Vec2ForcedAligner {
+    async fn check(&self) -> Vec<DependencyStatus> {
+        let mut detail = format!("device {}", self.device);
+        let fallbacks = self.cpu_fallbacks();
+        if fallbacks > 0 {
+            detail.push_str(&format!(", {fallbacks} requests retried on cpu"));
+        }
+        vec![DependencyStatus::ready("wav2vec2-model").with_detail(detail)]
+    }
+}
+
+/// Whether `error` reports device memory exhaustion rather than bad input.
+fn is_out_of_memory(error: &AlignmentError) -> bool {
+    !matches!(error, AlignmentError::InvalidInput { .. })
+        && reports_out_of_memory(&error.to_string())
+}
+
+/// ONNX Runtime and CUDA spell allocation failures several ways.
+fn reports_out_of_memory(message: &str) -> bool {
+    let message = message.to_ascii_lowercase();
+    ["out of memory", "cudaerrormemoryallocation", "failed to allocate"]
+        .iter()
+        .any(|marker| message.contains(marker))
+}
+
+/// `audio` at the rate the model was trained on, and the rate it was
+/// converted from when it had to be.
+fn to_model_rate(audio: AudioChunk) -> (AudioChunk, Option<u32>) {
+    let model_rate_hz = Wav2Vec2Config::DEFAULT_SAMPLE_RATE_HZ;
+    if audio.sample_rate_hz == model_rate_hz {
+        return (audio, None);
+    }
+    let samples = resample_linear(&audio.samples, audio.sample_rate_hz, model_rate_hz);
+    (AudioChunk::mono(model_rate_hz, samples), Some(audio.sample_rate_hz))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allocation_failures_are_recognised() {
+        assert!(reports_out_of_memory("CUDA failure 2: out of memory"));
+        assert!(reports_out_of_memory(
+            "BFCArena::AllocateRawInternal Failed to allocate memory for requested buffer"
+        ));
+        assert!(reports_out_of_memory("cudaErrorMemoryAllocation"));
+        assert!(!reports_out_of_memory("transcript has no alignable characters"));
+
+        let invalid = AlignmentError::InvalidInput {
+            message: "out of memory".to_string(),
+        };
+        assert!(!is_out_of_memory(&invalid));
+    }
+
+    #[test]
+    fn audio_is_resampled_to_the_model_rate() {
+        let (audio, resampled_from_hz) = to_model_rate(AudioChunk::mono(48_000, vec![0.0; 4_800]));
+        assert_eq!(resampled_from_hz, Some(48_000));
+        assert_eq!(audio.sample_rate_hz, Wav2Vec2Config::DEFAULT_SAMPLE_RATE_HZ);
+        assert_eq!(audio.samples.len(), 1_600);
+
+        let (audio, resampled_from_hz) = to_model_rate(AudioChunk::mono(16_000, vec![0.0; 160]));
+        assert_eq!(resampled_from_hz, None);
+        assert_eq!(audio.samples.len(), 160);
+    }
+}
@@ -0,0 +1,76 @@
+//! Numeric precision of the wav2vec2 weights. Reduced precisions load a
+//! converted copy of the model exported next to it, e.g. `model_int8.onnx`
+//! beside `model.onnx`.
+
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Precision {
+    #[default]
+    Fp32,
+    /// Half-precision weights: half the memory, faster on GPUs.
+    Fp16,
+    /// Dynamically quantized weights: a quarter of the memory and the
+    /// fastest on CPUs, at a small cost in timing accuracy.
+    Int8,
+}
+
+impl Precision {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "fp32" => Some(Self::Fp32),
+            "fp16" => Some(Self::Fp16),
+            "int8" => Some(Self::Int8),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Fp32 => "fp32",
+            Self::Fp16 => "fp16",
+            Self::Int8 => "int8",
+        }
+    }
+
+    /// Model file of this precision for the fp32 `model_path`:
+    /// `<stem>_<precision>.<ext>` in the same directory.
+    pub fn model_path(self, model_path: &str) -> String {
+        if self == Self::Fp32 {
+            return model_path.to_string();
+        }
+        let path = Path::new(model_path);
+        let stem = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("model");
+        let file_name = match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) => format!("{stem}_{}.{ext}", self.as_str()),
+            None => format!("{stem}_{}", self.as_str()),
+        };
+        path.with_file_name(file_name).to_string_lossy().into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn precisions_parse_case_insensitively() {
+        assert_eq!(Precision::parse("INT8"), Some(Precision::Int8));
+        assert_eq!(Precision::parse("fp16"), Some(Precision::Fp16));
+        assert_eq!(Precision::parse("bf16"), None);
+    }
+
+    #[test]
+    fn reduced_precisions_load_a_sibling_file() {
+        let model = "models/wav2vec2/model.onnx";
+        assert_eq!(Precision::Fp32.model_path(model), model);
+        assert_eq!(
+            Precision::Int8.model_path(model),
+            Path::new("models/wav2vec2/model_int8.onnx").to_string_lossy()
+        );
+        assert_eq!(Precision::Fp16.model_path("model"), "model_fp16");
+    }
+}
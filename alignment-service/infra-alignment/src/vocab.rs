@@ -0,0 +1,123 @@
+//! Custom vocabulary merged into the wav2vec2 vocabulary at load time, so
+//! names and acronyms align instead of being dropped.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use alignment_domain::DomainError;
+
+#[derive(Debug, Clone, Default)]
+pub struct CustomVocabulary {
+    /// Extra `vocab.json` entries. Each id must be an existing output of the
+    /// model, so a new grapheme aliases a character it already emits.
+    pub extra_tokens: BTreeMap<String, u32>,
+    /// Rewrites applied to the transcript before alignment, longest source
+    /// first, e.g. `ß` to `ss`. Aligned words keep their original spelling.
+    pub grapheme_map: BTreeMap<String, String>,
+}
+
+impl CustomVocabulary {
+    pub fn is_empty(&self) -> bool {
+        self.extra_tokens.is_empty() && self.grapheme_map.is_empty()
+    }
+
+    /// Writes the vocabulary at `vocab_path` with `extra_tokens` added to a
+    /// file in the temp directory and returns its path.
+    pub(crate) fn write_merged(&self, vocab_path: &str) -> Result<PathBuf, DomainError> {
+        let raw = std::fs::read_to_string(vocab_path).map_err(|err| {
+            DomainError::internal_error(&format!("cannot read vocabulary {vocab_path}: {err}"))
+        })?;
+        let vocab: HashMap<String, u32> = serde_json::from_str(&raw).map_err(|err| {
+            DomainError::internal_error(&format!("invalid vocabulary {vocab_path}: {err}"))
+        })?;
+        let merged = self.merge(vocab)?;
+
+        let target = std::env::temp_dir().join(format!(
+            "{}-{}.json",
+            Path::new(vocab_path)
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or("vocab"),
+            std::process::id()
+        ));
+        let json = serde_json::to_string(&merged)
+            .map_err(|err| DomainError::internal_error(&err.to_string()))?;
+        std::fs::write(&target, json).map_err(|err| {
+            DomainError::internal_error(&format!(
+                "cannot write merged vocabulary {}: {err}",
+                target.display()
+            ))
+        })?;
+        Ok(target)
+    }
+
+    fn merge(&self, mut vocab: HashMap<String, u32>) -> Result<HashMap<String, u32>, DomainError> {
+        let outputs: HashSet<u32> = vocab.values().copied().collect();
+        for (token, id) in &self.extra_tokens {
+            if !outputs.contains(id) {
+                return Err(DomainError::invalid_input(&format!(
+                    "vocabulary entry `{token}` maps to id {id}, which the model does not emit"
+                )));
+            }
+            match vocab.get(token) {
+                Some(existing) if existing != id => {
+                    return Err(DomainError::invalid_input(&format!(
+                        "vocabulary entry `{token}` already has id {existing}"
+                    )));
+                }
+                _ => {
+                    vocab.insert(token.clone(), *id);
+                }
+            }
+        }
+        Ok(vocab)
+    }
+
+    pub(crate) fn map_graphemes(&self, text: &str) -> String {
+        let mut rules: Vec<_> = self.grapheme_map.iter().collect();
+        rules.sort_by_key(|(from, _)| std::cmp::Reverse(from.chars().count()));
+        rules
+            .into_iter()
+            .filter(|(from, _)| !from.is_empty())
+            .fold(text.to_string(), |text, (from, to)| text.replace(from.as_str(), to))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vocabulary() -> CustomVocabulary {
+        CustomVocabulary {
+            extra_tokens: BTreeMap::from([("ё".to_string(), 3)]),
+            grapheme_map: BTreeMap::from([
+                ("ß".to_string(), "ss".to_string()),
+                ("&".to_string(), "et".to_string()),
+                ("&&".to_string(), "and".to_string()),
+            ]),
+        }
+    }
+
+    #[test]
+    fn extra_tokens_alias_existing_outputs() {
+        let vocab = HashMap::from([("е".to_string(), 3), ("a".to_string(), 4)]);
+        let merged = vocabulary().merge(vocab.clone()).expect("merges");
+        assert_eq!(merged["ё"], 3);
+
+        let mut unknown = vocabulary();
+        unknown.extra_tokens.insert("x".to_string(), 99);
+        assert!(unknown.merge(vocab.clone()).is_err());
+
+        let mut conflicting = vocabulary();
+        conflicting.extra_tokens.insert("a".to_string(), 3);
+        assert!(conflicting.merge(vocab).is_err());
+    }
+
+    #[test]
+    fn longest_graphemes_are_mapped_first() {
+        assert_eq!(
+            vocabulary().map_graphemes("Straße && Rhin & Rhône"),
+            "Strasse and Rhin et Rhône"
+        );
+    }
+}
@@ -1,14 +1,26 @@
 use anyhow::Error;
 use alignment_application::{
     AlignTranscriptUseCase, AlignTranscriptUseCaseImpl, AlignmentCommandRegistryFactory,
+    AlignmentLimiter,
 };
-use alignment_configuration::AppConfig;
+use alignment_configuration::{load_config, AlignmentRuntimeConfig, AppConfig};
 use alignment_domain::AlignmentPort;
 use alignment_grpc_server::serve_grpc;
-use alignment_infra_alignment::{Wav2Vec2AdapterConfig, Wav2Vec2ForcedAligner};
+#[cfg(feature = "http")]
+use alignment_http_server::create_app_routes;
+use alignment_infra_alignment::{
+    AnchorConfig, CustomVocabulary, Precision, Wav2Vec2AdapterConfig, Wav2Vec2ForcedAligner,
+};
+use model_manager::{ModelArtifact, ModelManager, ModelSource};
 use rustycog_command::GenericCommandService;
 use rustycog_config::ServerConfig;
+#[cfg(feature = "http")]
+use rustycog_http::{AppState, UserIdExtractor};
+use service_admin::{spawn_admin, ConfigReloader};
+use async_trait::async_trait;
+use service_health::{DependencyStatus, ReadinessCheck};
 use std::sync::Arc;
+use std::time::Instant;
 
 pub async fn build_and_run(config: AppConfig, server_config: ServerConfig) -> Result<(), Error> {
     let app = Application::new(config).await?;
@@ -18,6 +30,35 @@ pub async fn build_and_run(config: AppConfig, server_config: ServerConfig) -> Re
 pub struct Application {
     pub config: AppConfig,
     pub command_service: Arc<GenericCommandService>,
+    /// Reports the device the model runs on and the alignment queue.
+    readiness: Arc<dyn ReadinessCheck>,
+}
+
+/// The aligner's device and, when alignments are limited, the queue in
+/// front of it, for `/readyz` and the admin metrics snapshot.
+struct AlignmentReadiness {
+    aligner: Arc<Wav2Vec2ForcedAligner>,
+    limiter: Option<Arc<AlignmentLimiter>>,
+}
+
+#[async_trait]
+impl ReadinessCheck for AlignmentReadiness {
+    async fn check(&self) -> Vec<DependencyStatus> {
+        let mut statuses = self.aligner.check().await;
+        if let Some(limiter) = &self.limiter {
+            let stats = limiter.stats();
+            let detail = format!(
+                "{}/{} aligning, {}/{} queued, {} rejected",
+                stats.in_flight,
+                stats.max_concurrent,
+                stats.queued,
+                stats.max_queued,
+                stats.rejected
+            );
+            statuses.push(DependencyStatus::ready("alignment-queue").with_detail(detail));
+        }
+        statuses
+    }
 }
 
 impl Application {
@@ -29,26 +70,14 @@ impl Application {
             "initializing alignment application"
         );
 
-        let adapter_cfg = Wav2Vec2AdapterConfig {
-            model_path: config.alignment.model_path.clone(),
-            config_path: config.alignment.config_path.clone(),
-            vocab_path: config.alignment.vocab_path.clone(),
-            device: config.alignment.device.clone(),
-        };
-        let aligner: Arc<dyn AlignmentPort> = Arc::new(
-            Wav2Vec2ForcedAligner::load(&adapter_cfg)
-                .map_err(|err| anyhow::anyhow!("wav2vec2 model loading failed: {err}"))?,
-        );
-        let usecase: Arc<dyn AlignTranscriptUseCase> = Arc::new(AlignTranscriptUseCaseImpl::new(
-            aligner,
-            config.alignment.sample_rate_hz,
-        ));
+        let (usecase, readiness) = build_components(&config.alignment).await?;
         let registry = AlignmentCommandRegistryFactory::create_registry(usecase);
         let command_service = Arc::new(GenericCommandService::new(Arc::new(registry)));
 
         Ok(Self {
             config,
             command_service,
+            readiness,
         })
     }
 
@@ -58,9 +87,132 @@ impl Application {
             port = server_config.port,
             "starting alignment gRPC server"
         );
+        let readiness = spawn_admin(
+            "alignment",
+            self.config.admin.clone(),
+            self.readiness.clone(),
+            ConfigReloader::new(&self.config, load_config),
+        );
 
-        serve_grpc(self.command_service, server_config)
-            .await
-            .map_err(|err| anyhow::anyhow!("server startup failed: {err}"))
+        #[cfg(feature = "http")]
+        if let Some(http_config) = self.config.http.clone() {
+            tracing::info!(
+                host = %http_config.host,
+                port = http_config.port,
+                "starting alignment HTTP server"
+            );
+            let state = AppState::new(self.command_service.clone(), UserIdExtractor::new());
+            let grpc = serve_grpc(
+                self.command_service,
+                server_config,
+                readiness.clone(),
+                self.config.alignment.sample_rate_hz,
+            );
+            let http = create_app_routes(state, http_config, readiness);
+            tokio::try_join!(
+                async { grpc.await.map_err(|err| anyhow::anyhow!("server startup failed: {err}")) },
+                async { http.await.map_err(|err| anyhow::anyhow!("http server failed: {err}")) },
+            )?;
+            return Ok(());
+        }
+        #[cfg(not(feature = "http"))]
+        if self.config.http.is_some() {
+            tracing::warn!("[http] is configured but alignment-service was built without `http`");
+        }
+
+        serve_grpc(
+            self.command_service,
+            server_config,
+            readiness,
+            self.config.alignment.sample_rate_hz,
+        )
+        .await
+        .map_err(|err| anyhow::anyhow!("server startup failed: {err}"))
     }
 }
+
+/// Checks and loads the wav2vec2 model of `config` and wraps it in the
+/// alignment use case. The orchestrator's `monolith` build calls this to
+/// align in-process.
+pub async fn build_usecase(
+    config: &AlignmentRuntimeConfig,
+) -> Result<Arc<dyn AlignTranscriptUseCase>, Error> {
+    build_components(config).await.map(|(usecase, _)| usecase)
+}
+
+async fn build_components(
+    config: &AlignmentRuntimeConfig,
+) -> Result<(Arc<dyn AlignTranscriptUseCase>, Arc<dyn ReadinessCheck>), Error> {
+    let precision = Precision::parse(&config.precision).ok_or_else(|| {
+        anyhow::anyhow!(
+            "alignment.precision: expected fp32, fp16 or int8, got {}",
+            config.precision
+        )
+    })?;
+    ensure_models(config, precision).await?;
+
+    let adapter_cfg = Wav2Vec2AdapterConfig {
+        model_path: config.model_path.clone(),
+        config_path: config.config_path.clone(),
+        vocab_path: config.vocab_path.clone(),
+        device: config.device.clone(),
+        precision,
+        vocabulary: CustomVocabulary {
+            extra_tokens: config.extra_vocab.clone(),
+            grapheme_map: config.grapheme_map.clone(),
+        },
+        anchors: config.anchored.then_some(AnchorConfig {
+            padding_ms: config
+                .max_drift_ms
+                .map_or(config.anchor_padding_ms, |drift| drift.min(config.anchor_padding_ms)),
+            max_window_ms: config.max_window_ms,
+        }),
+    };
+    let aligner = Wav2Vec2ForcedAligner::load(&adapter_cfg)
+        .map_err(|err| anyhow::anyhow!("wav2vec2 model loading failed: {err}"))?;
+    if config.warmup_on_start {
+        let started = Instant::now();
+        match aligner.warm_up() {
+            Ok(()) => tracing::info!(
+                elapsed_ms = started.elapsed().as_millis() as u64,
+                "wav2vec2 warm-up completed"
+            ),
+            Err(err) => tracing::warn!(error = %err, "wav2vec2 warm-up failed"),
+        }
+    }
+    let aligner = Arc::new(aligner);
+    let port: Arc<dyn AlignmentPort> = aligner.clone();
+    let mut usecase = AlignTranscriptUseCaseImpl::new(port, config.sample_rate_hz)
+        .with_word_prosody(config.word_prosody)
+        .with_max_drift_ms(config.max_drift_ms);
+    let limiter = config
+        .max_concurrent
+        .map(|max_concurrent| Arc::new(AlignmentLimiter::new(max_concurrent, config.max_queued)));
+    if let Some(limiter) = &limiter {
+        usecase = usecase.with_limiter(limiter.clone());
+    }
+    let readiness = Arc::new(AlignmentReadiness { aligner, limiter });
+    Ok((Arc::new(usecase), readiness))
+}
+
+async fn ensure_models(config: &AlignmentRuntimeConfig, precision: Precision) -> Result<(), Error> {
+    let files = &config.model_files;
+    let model_path = precision.model_path(&config.model_path);
+    let artifacts = [
+        ("wav2vec2-model", &model_path, &files.model_sha256, &files.model_source),
+        ("wav2vec2-config", &config.config_path, &files.config_sha256, &files.config_source),
+        ("wav2vec2-vocab", &config.vocab_path, &files.vocab_sha256, &files.vocab_source),
+    ]
+    .into_iter()
+    .map(|(name, path, sha256, source)| {
+        Ok(ModelArtifact::new(name, path)
+            .with_sha256(sha256.clone())
+            .with_source(source.as_deref().map(ModelSource::parse).transpose()?))
+    })
+    .collect::<Result<Vec<_>, Error>>()?;
+
+    ModelManager::new(config.download_missing_models)
+        .ensure_all(&artifacts)
+        .await
+        .map_err(|err| anyhow::anyhow!("wav2vec2 model check failed: {err}"))
+}
@@ -1,3 +1,3 @@
 pub mod app;
 
-pub use app::{build_and_run, Application};
+pub use app::{build_and_run, build_usecase, Application};
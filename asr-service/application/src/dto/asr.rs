@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
-use asr_domain::Transcript;
+use asr_domain::{text_metrics::TranscriptScore, Transcript};
 
 #[derive(Debug, Clone, Deserialize, Validate)]
 pub struct TranscribeAudioRequest {
@@ -13,6 +13,31 @@ pub struct TranscribeAudioRequest {
     pub language_hint: Option<String>,
     #[validate(length(min = 1, max = 64))]
     pub session_id: Option<String>,
+    #[serde(default)]
+    #[validate(length(min = 1, max = 64))]
+    pub model: Option<String>,
+    /// Expected transcript; when set the response carries a WER/CER score.
+    #[serde(default)]
+    #[validate(length(min = 1))]
+    pub reference_text: Option<String>,
+    /// `accurate` (default) or `low_latency`.
+    #[serde(default)]
+    #[validate(length(min = 1, max = 32))]
+    pub decode_profile: Option<String>,
+    /// GBNF grammar with a `root` rule constraining the transcript, e.g. to
+    /// a fixed command set.
+    #[serde(default)]
+    #[validate(length(min = 1, max = 65536))]
+    pub grammar: Option<String>,
+    /// Start of `samples` on the stream's timeline. Response timings are
+    /// shifted by it, so chunk-wise callers get absolute timestamps.
+    #[serde(default)]
+    pub time_offset_ms: Option<u64>,
+    /// Text preceding the audio, e.g. the previous chunk's transcript tail,
+    /// used as decoding context for continuity across chunks.
+    #[serde(default)]
+    #[validate(length(min = 1, max = 65536))]
+    pub prompt: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -20,4 +45,13 @@ pub struct TranscribeAudioResponse {
     pub session_id: String,
     pub transcript: Transcript,
     pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<TranscriptScore>,
+    /// Language Whisper identified, set only when the language was
+    /// auto-detected rather than hinted or configured.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detected_language: Option<String>,
+    /// Whisper's probability for `detected_language`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language_probability: Option<f32>,
 }
@@ -3,7 +3,10 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use uuid::Uuid;
 
-use asr_domain::{AudioChunk, LanguageTag, TranscriptionPort, TranscriptionRequest};
+use asr_domain::{
+    text_metrics::TranscriptScore, AudioChunk, DecodeProfile, LanguageTag, TranscriptionOutput,
+    TranscriptionPort, TranscriptionRequest,
+};
 
 use crate::{ApplicationError, TranscribeAudioRequest, TranscribeAudioResponse};
 
@@ -40,28 +43,43 @@ impl AsrUseCase for AsrUseCaseImpl {
             sample_rate_hz,
             language_hint,
             session_id,
+            model,
+            reference_text,
+            decode_profile,
+            grammar,
+            time_offset_ms,
+            prompt,
         } = request;
         tracing::debug!(
             sample_count = samples.len(),
             sample_rate_hz = sample_rate_hz.unwrap_or(self.sample_rate_hz),
             language_hint = language_hint.as_deref().unwrap_or("auto"),
             session_id = session_id.as_deref().unwrap_or("auto"),
+            model = model.as_deref().unwrap_or("default"),
+            decode_profile = decode_profile.as_deref().unwrap_or("accurate"),
             "starting asr transcription"
         );
 
         let input_sample_rate_hz = sample_rate_hz.unwrap_or(self.sample_rate_hz);
         let session_id = session_id.unwrap_or_else(|| Uuid::new_v4().to_string());
-        let transcript = self
+        let TranscriptionOutput {
+            mut transcript,
+            detected_language,
+            language_probability,
+        } = self
             .transcription
             .transcribe(TranscriptionRequest {
                 language_hint: parse_language_hint(language_hint.as_deref())?,
-                audio: AudioChunk {
-                    sample_rate_hz: input_sample_rate_hz,
-                    samples,
-                },
+                model,
+                profile: parse_decode_profile(decode_profile.as_deref())?,
+                grammar,
+                prompt,
+                audio: AudioChunk::mono(input_sample_rate_hz, samples),
             })
-            .await?
-            .transcript;
+            .await?;
+        if let Some(offset_ms) = time_offset_ms {
+            transcript.shift(i64::try_from(offset_ms).unwrap_or(i64::MAX), input_sample_rate_hz);
+        }
         let text = transcript
             .segments
             .iter()
@@ -69,15 +87,23 @@ impl AsrUseCase for AsrUseCaseImpl {
             .filter(|part| !part.is_empty())
             .collect::<Vec<_>>()
             .join(" ");
+        let score = reference_text
+            .as_deref()
+            .map(|reference| TranscriptScore::compute(reference, &text));
 
         let response = TranscribeAudioResponse {
             session_id,
             transcript,
             text,
+            score,
+            detected_language: detected_language.map(|language| language.to_string()),
+            language_probability,
         };
 
         tracing::debug!(
             segment_count = response.transcript.segments.len(),
+            wer = response.score.map(|score| score.wer),
+            detected_language = response.detected_language.as_deref(),
             "asr transcription completed"
         );
 
@@ -85,21 +111,24 @@ impl AsrUseCase for AsrUseCaseImpl {
     }
 }
 
+fn parse_decode_profile(value: Option<&str>) -> Result<DecodeProfile, ApplicationError> {
+    let Some(profile) = value else {
+        return Ok(DecodeProfile::default());
+    };
+
+    DecodeProfile::parse(profile).ok_or_else(|| {
+        ApplicationError::Validation(format!(
+            "decode_profile: expected accurate or low_latency, got {profile}"
+        ))
+    })
+}
+
 fn parse_language_hint(value: Option<&str>) -> Result<Option<LanguageTag>, ApplicationError> {
     let Some(language) = value else {
         return Ok(None);
     };
 
-    let parsed = match language.to_ascii_lowercase().as_str() {
-        "fr" => LanguageTag::Fr,
-        "en" => LanguageTag::En,
-        "auto" => LanguageTag::Auto,
-        other if !other.is_empty() => LanguageTag::Other(other.to_string()),
-        _ => {
-            return Err(ApplicationError::Validation(
-                "language_hint cannot be empty".to_string(),
-            ));
-        }
-    };
-    Ok(Some(parsed))
+    LanguageTag::parse(language)
+        .map(Some)
+        .map_err(|err| ApplicationError::Validation(format!("language_hint: {err}")))
 }
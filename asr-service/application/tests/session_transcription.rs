@@ -15,16 +15,25 @@ impl TranscriptionPort for MockTranscriptionPort {
         &self,
         request: TranscriptionRequest,
     ) -> Result<TranscriptionOutput, DomainError> {
+        let detected_language = request.language_hint.is_none().then(LanguageTag::en);
         let transcript = Transcript {
-            language: request.language_hint.unwrap_or(LanguageTag::En),
+            language: request.language_hint.unwrap_or(LanguageTag::en()),
             segments: vec![TranscriptSegment {
                 text: "hello world".to_string(),
                 start_ms: 0,
                 end_ms: request.audio.samples.len().saturating_mul(10) as u64,
                 tokens: Vec::new(),
+                speaker: None,
+                language: None,
+                no_speech_prob: None,
+                avg_logprob: None,
             }],
         };
-        Ok(TranscriptionOutput { transcript })
+        Ok(TranscriptionOutput {
+            transcript,
+            language_probability: detected_language.as_ref().map(|_| 0.9),
+            detected_language,
+        })
     }
 }
 
@@ -34,10 +43,16 @@ async fn transcribe_command_flow_produces_transcript_text() {
         Arc::new(AsrUseCaseImpl::new(Arc::new(MockTranscriptionPort), 16_000));
     let response = usecase
         .transcribe(TranscribeAudioRequest {
-            samples: vec![0.1, 0.2, 0.3],
+            samples: test_audio::speech_like(16_000, 500),
             sample_rate_hz: Some(16_000),
             language_hint: Some("en".to_string()),
             session_id: Some("it-session".to_string()),
+            model: None,
+            reference_text: Some("Hello, world!".to_string()),
+            decode_profile: None,
+            grammar: None,
+            time_offset_ms: None,
+            prompt: None,
         })
         .await
         .expect("transcription succeeds");
@@ -45,4 +60,84 @@ async fn transcribe_command_flow_produces_transcript_text() {
     assert_eq!(response.session_id, "it-session");
     assert_eq!(response.transcript.segments.len(), 1);
     assert_eq!(response.text, "hello world");
+    assert_eq!(response.score.map(|score| score.wer), Some(0.0));
+}
+
+#[tokio::test]
+async fn unknown_decode_profile_is_rejected() {
+    let usecase: Arc<dyn AsrUseCase> =
+        Arc::new(AsrUseCaseImpl::new(Arc::new(MockTranscriptionPort), 16_000));
+    let error = usecase
+        .transcribe(TranscribeAudioRequest {
+            samples: test_audio::speech_like(16_000, 500),
+            sample_rate_hz: Some(16_000),
+            language_hint: None,
+            session_id: None,
+            model: None,
+            reference_text: None,
+            decode_profile: Some("turbo".to_string()),
+            grammar: None,
+            time_offset_ms: None,
+            prompt: None,
+        })
+        .await
+        .expect_err("unknown profile");
+
+    assert!(error.to_string().contains("decode_profile"), "{error}");
+}
+
+#[tokio::test]
+async fn time_offset_places_timings_on_the_stream_timeline() {
+    let usecase: Arc<dyn AsrUseCase> =
+        Arc::new(AsrUseCaseImpl::new(Arc::new(MockTranscriptionPort), 16_000));
+    let response = usecase
+        .transcribe(TranscribeAudioRequest {
+            samples: vec![0.0; 50],
+            sample_rate_hz: Some(16_000),
+            language_hint: None,
+            session_id: None,
+            model: None,
+            reference_text: None,
+            decode_profile: None,
+            grammar: None,
+            time_offset_ms: Some(30_000),
+            prompt: None,
+        })
+        .await
+        .expect("transcription succeeds");
+
+    let segment = &response.transcript.segments[0];
+    assert_eq!((segment.start_ms, segment.end_ms), (30_000, 30_500));
+}
+
+#[tokio::test]
+async fn auto_detected_language_is_reported() {
+    let usecase: Arc<dyn AsrUseCase> =
+        Arc::new(AsrUseCaseImpl::new(Arc::new(MockTranscriptionPort), 16_000));
+    let request = |language_hint: Option<&str>| TranscribeAudioRequest {
+        samples: test_audio::speech_like(16_000, 500),
+        sample_rate_hz: Some(16_000),
+        language_hint: language_hint.map(str::to_string),
+        session_id: None,
+        model: None,
+        reference_text: None,
+        decode_profile: None,
+        grammar: None,
+        time_offset_ms: None,
+        prompt: None,
+    };
+
+    let detected = usecase
+        .transcribe(request(None))
+        .await
+        .expect("transcription succeeds");
+    assert_eq!(detected.detected_language.as_deref(), Some("en"));
+    assert_eq!(detected.language_probability, Some(0.9));
+
+    let hinted = usecase
+        .transcribe(request(Some("en")))
+        .await
+        .expect("transcription succeeds");
+    assert_eq!(hinted.detected_language, None);
+    assert_eq!(hinted.language_probability, None);
 }
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 use rustycog_config::{
@@ -13,8 +15,15 @@ pub type AppConfig = AsrConfig;
 pub struct AsrConfig {
     #[serde(default)]
     pub server: ServerConfig,
+    /// REST listener, served next to gRPC when set. Needs the `http` feature.
+    #[serde(default)]
+    pub http: Option<ServerConfig>,
     #[serde(default)]
     pub logging: LoggingConfig,
+    /// Admin API listener (drain, reload, metrics, log level), off when
+    /// unset; bind it to an address the public cannot reach.
+    #[serde(default)]
+    pub admin: Option<ServerConfig>,
     #[serde(default)]
     pub queue: QueueConfig,
     #[serde(default)]
@@ -53,13 +62,53 @@ pub struct AsrRuntimeConfig {
     pub dtw_preset: String,
     #[serde(default = "default_dtw_mem_size")]
     pub dtw_mem_size: usize,
+    /// Expected SHA-256 of `model_path`, verified at startup when set.
+    #[serde(default)]
+    pub model_sha256: Option<String>,
+    /// `https://...` URL or `hf://owner/repo/file[@revision]` to fetch the
+    /// model from when `model_path` is missing.
+    #[serde(default)]
+    pub model_source: Option<String>,
+    #[serde(default)]
+    pub download_missing_models: bool,
+    /// Name of the model used when a request does not pick one. The top-level
+    /// `model_path` is registered under this name unless `models` declares it.
+    #[serde(default = "default_model_name")]
+    pub default_model: String,
+    #[serde(default)]
+    pub models: BTreeMap<String, WhisperModelConfig>,
+    /// BCP-47 tag to model name for requests that name no model. Tags are
+    /// looked up most specific first, so `pt-BR` falls back to `pt`.
+    #[serde(default)]
+    pub language_models: BTreeMap<String, String>,
+    /// Decode a second of silence on every model before serving traffic.
+    #[serde(default)]
+    pub warmup_on_start: bool,
+    /// Whisper tokens of a request `prompt` (e.g. the previous chunk's
+    /// transcript) kept as decoding context, counted from its end. Whisper
+    /// reads at most 224; 0 ignores prompts.
+    #[serde(default = "default_prompt_max_tokens")]
+    pub prompt_max_tokens: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WhisperModelConfig {
+    pub path: String,
+    #[serde(default = "default_dtw_preset")]
+    pub dtw_preset: String,
+    #[serde(default)]
+    pub sha256: Option<String>,
+    #[serde(default)]
+    pub source: Option<String>,
 }
 
 impl Default for AsrConfig {
     fn default() -> Self {
         Self {
             server: ServerConfig::default(),
+            http: None,
             logging: LoggingConfig::default(),
+            admin: None,
             queue: QueueConfig::default(),
             service: ServiceConfig::default(),
         }
@@ -94,10 +143,34 @@ impl Default for AsrRuntimeConfig {
             threads: default_threads(),
             dtw_preset: default_dtw_preset(),
             dtw_mem_size: default_dtw_mem_size(),
+            model_sha256: None,
+            model_source: None,
+            download_missing_models: false,
+            default_model: default_model_name(),
+            models: BTreeMap::new(),
+            language_models: BTreeMap::new(),
+            warmup_on_start: false,
+            prompt_max_tokens: default_prompt_max_tokens(),
         }
     }
 }
 
+impl AsrRuntimeConfig {
+    /// Every selectable model keyed by name, including the default one.
+    pub fn registered_models(&self) -> BTreeMap<String, WhisperModelConfig> {
+        let mut models = self.models.clone();
+        models
+            .entry(self.default_model.clone())
+            .or_insert_with(|| WhisperModelConfig {
+                path: self.model_path.clone(),
+                dtw_preset: self.dtw_preset.clone(),
+                sha256: self.model_sha256.clone(),
+                source: self.model_source.clone(),
+            });
+        models
+    }
+}
+
 impl ConfigLoader<AsrConfig> for AsrConfig {
     fn create_default() -> AsrConfig {
         AsrConfig::default()
@@ -154,6 +227,10 @@ fn default_model_path() -> String {
     "models/ggml-base.bin".to_string()
 }
 
+fn default_model_name() -> String {
+    "base".to_string()
+}
+
 fn default_language() -> String {
     "auto".to_string()
 }
@@ -174,6 +251,10 @@ fn default_dtw_mem_size() -> usize {
     128
 }
 
+fn default_prompt_max_tokens() -> usize {
+    224
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -183,6 +264,9 @@ mod tests {
         let cfg = AsrConfig::default();
         assert_eq!(cfg.service.audio.sample_rate_hz, 16_000);
         assert_eq!(cfg.service.asr.temperature, 0.0);
+        assert!(!cfg.service.asr.download_missing_models);
+        assert!(!cfg.service.asr.warmup_on_start);
+        assert_eq!(cfg.service.asr.prompt_max_tokens, 224);
         assert_eq!(cfg.server.port, 8080);
     }
 }
@@ -1,48 +1,58 @@
-use serde::{Deserialize, Serialize};
+pub use common_domain::{
+    ms_to_sample, AudioChunk, LanguageTag, Transcript, TranscriptSegment, TranscriptToken,
+};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub enum LanguageTag {
-    Fr,
-    En,
-    Auto,
-    Other(String),
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AudioChunk {
-    pub sample_rate_hz: u32,
-    pub samples: Vec<f32>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TranscriptToken {
-    pub text: String,
-    pub start_ms: u64,
-    pub end_ms: u64,
-    pub confidence: f32,
+#[derive(Debug, Clone)]
+pub struct TranscriptionRequest {
+    pub language_hint: Option<LanguageTag>,
+    /// Registered Whisper model name; `None` selects the default model.
+    pub model: Option<String>,
+    pub profile: DecodeProfile,
+    /// GBNF grammar with a `root` rule constraining the decoded text, e.g.
+    /// to a fixed set of voice commands.
+    pub grammar: Option<String>,
+    /// Text preceding the audio, e.g. the previous chunk's transcript, given
+    /// to Whisper as context for continuity across chunks.
+    pub prompt: Option<String>,
+    pub audio: AudioChunk,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TranscriptSegment {
-    pub text: String,
-    pub start_ms: u64,
-    pub end_ms: u64,
-    pub tokens: Vec<TranscriptToken>,
+/// How Whisper trades accuracy for latency on one request.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DecodeProfile {
+    /// Segmented decoding that carries text context across segments and
+    /// retries at higher temperatures when a segment decodes poorly.
+    #[default]
+    Accurate,
+    /// One segment, no carried text context, no temperature retries and an
+    /// encoder window sized to the clip: sub-second answers on short
+    /// commands, at some cost in accuracy.
+    LowLatency,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Transcript {
-    pub language: LanguageTag,
-    pub segments: Vec<TranscriptSegment>,
-}
+impl DecodeProfile {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "accurate" => Some(Self::Accurate),
+            "low_latency" => Some(Self::LowLatency),
+            _ => None,
+        }
+    }
 
-#[derive(Debug, Clone)]
-pub struct TranscriptionRequest {
-    pub language_hint: Option<LanguageTag>,
-    pub audio: AudioChunk,
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Accurate => "accurate",
+            Self::LowLatency => "low_latency",
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct TranscriptionOutput {
     pub transcript: Transcript,
+    /// Language Whisper identified when none was requested; `None` when the
+    /// decode language was fixed by a hint or the configuration.
+    pub detected_language: Option<LanguageTag>,
+    /// Probability Whisper assigned to `detected_language`.
+    pub language_probability: Option<f32>,
 }
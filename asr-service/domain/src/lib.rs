@@ -1,5 +1,6 @@
 pub mod entity;
 pub mod port;
+pub mod text_metrics;
 
 pub use entity::*;
 pub use port::*;
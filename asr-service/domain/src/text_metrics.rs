@@ -0,0 +1,197 @@
+//! Word and character error rates between a reference and a hypothesis
+//! transcript.
+//!
+//! Both sides go through [`normalize_text`] first, so case, punctuation and
+//! spacing differences are not counted as errors.
+
+use serde::Serialize;
+
+/// Edit operations aligning a hypothesis onto a reference.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct EditCounts {
+    pub substitutions: usize,
+    pub deletions: usize,
+    pub insertions: usize,
+    /// Number of reference units (words or characters).
+    pub reference_len: usize,
+}
+
+impl EditCounts {
+    pub fn errors(&self) -> usize {
+        self.substitutions + self.deletions + self.insertions
+    }
+
+    /// Errors over reference length. An empty reference scores 0.0 against
+    /// an empty hypothesis and 1.0 against anything else.
+    pub fn rate(&self) -> f64 {
+        if self.reference_len == 0 {
+            return if self.errors() == 0 { 0.0 } else { 1.0 };
+        }
+        self.errors() as f64 / self.reference_len as f64
+    }
+
+    /// Pools counts, e.g. over a corpus, so the rate weighs long references
+    /// more than short ones.
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            substitutions: self.substitutions + other.substitutions,
+            deletions: self.deletions + other.deletions,
+            insertions: self.insertions + other.insertions,
+            reference_len: self.reference_len + other.reference_len,
+        }
+    }
+}
+
+/// WER and CER of one hypothesis, with the underlying counts.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct TranscriptScore {
+    pub wer: f64,
+    pub cer: f64,
+    pub words: EditCounts,
+    pub characters: EditCounts,
+}
+
+impl TranscriptScore {
+    pub fn compute(reference: &str, hypothesis: &str) -> Self {
+        let words = word_edits(reference, hypothesis);
+        let characters = char_edits(reference, hypothesis);
+        Self {
+            wer: words.rate(),
+            cer: characters.rate(),
+            words,
+            characters,
+        }
+    }
+}
+
+/// Lowercases, maps typographic apostrophes to `'`, replaces other
+/// punctuation with spaces and collapses whitespace.
+pub fn normalize_text(text: &str) -> String {
+    let cleaned: String = text
+        .chars()
+        .flat_map(char::to_lowercase)
+        .map(|c| match c {
+            '\u{2019}' | '\u{2018}' | '`' => '\'',
+            c if c.is_alphanumeric() || c == '\'' => c,
+            _ => ' ',
+        })
+        .collect();
+    cleaned.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+pub fn word_edits(reference: &str, hypothesis: &str) -> EditCounts {
+    let reference = normalize_text(reference);
+    let hypothesis = normalize_text(hypothesis);
+    let reference: Vec<&str> = reference.split_whitespace().collect();
+    let hypothesis: Vec<&str> = hypothesis.split_whitespace().collect();
+    edit_counts(&reference, &hypothesis)
+}
+
+/// Character edits over the normalized text, spaces included.
+pub fn char_edits(reference: &str, hypothesis: &str) -> EditCounts {
+    let reference: Vec<char> = normalize_text(reference).chars().collect();
+    let hypothesis: Vec<char> = normalize_text(hypothesis).chars().collect();
+    edit_counts(&reference, &hypothesis)
+}
+
+pub fn wer(reference: &str, hypothesis: &str) -> f64 {
+    word_edits(reference, hypothesis).rate()
+}
+
+pub fn cer(reference: &str, hypothesis: &str) -> f64 {
+    char_edits(reference, hypothesis).rate()
+}
+
+/// Levenshtein alignment keeping the operation breakdown of the cheapest
+/// path; ties prefer substitutions, then deletions.
+fn edit_counts<T: PartialEq>(reference: &[T], hypothesis: &[T]) -> EditCounts {
+    let empty = EditCounts::default();
+    let mut previous: Vec<EditCounts> = (0..=hypothesis.len())
+        .map(|insertions| EditCounts { insertions, ..empty })
+        .collect();
+    let mut current = vec![empty; hypothesis.len() + 1];
+
+    for (i, reference_item) in reference.iter().enumerate() {
+        current[0] = EditCounts {
+            deletions: i + 1,
+            ..empty
+        };
+        for (j, hypothesis_item) in hypothesis.iter().enumerate() {
+            let mut substitution = previous[j];
+            if reference_item != hypothesis_item {
+                substitution.substitutions += 1;
+            }
+            let mut deletion = previous[j + 1];
+            deletion.deletions += 1;
+            let mut insertion = current[j];
+            insertion.insertions += 1;
+
+            current[j + 1] = [substitution, deletion, insertion]
+                .into_iter()
+                .min_by_key(EditCounts::errors)
+                .unwrap_or(substitution);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    EditCounts {
+        reference_len: reference.len(),
+        ..previous[hypothesis.len()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalization_ignores_case_punctuation_and_spacing() {
+        assert_eq!(
+            normalize_text("  Bonjour,   l’Équipe !"),
+            "bonjour l'équipe".to_string()
+        );
+        assert_eq!(wer("Bonjour, le monde.", "bonjour le   monde"), 0.0);
+    }
+
+    #[test]
+    fn operations_are_broken_down() {
+        let counts = word_edits("le chat dort ici", "le chien dort ici bien");
+        assert_eq!(counts.substitutions, 1);
+        assert_eq!(counts.insertions, 1);
+        assert_eq!(counts.deletions, 0);
+        assert_eq!(counts.reference_len, 4);
+        assert_eq!(counts.rate(), 0.5);
+
+        let counts = word_edits("un deux trois", "un trois");
+        assert_eq!(counts.deletions, 1);
+        assert_eq!(counts.errors(), 1);
+    }
+
+    #[test]
+    fn cer_counts_characters() {
+        let counts = char_edits("chat", "chats");
+        assert_eq!(counts.insertions, 1);
+        assert_eq!(counts.reference_len, 4);
+        assert_eq!(cer("chat", "chats"), 0.25);
+    }
+
+    #[test]
+    fn empty_reference_convention() {
+        assert_eq!(wer("", ""), 0.0);
+        assert_eq!(wer("", "bruit"), 1.0);
+        assert_eq!(wer("bonjour", ""), 1.0);
+    }
+
+    #[test]
+    fn merged_counts_pool_over_references() {
+        let pooled = word_edits("a b c d", "a b c d").merge(word_edits("e f", "e g"));
+        assert_eq!(pooled.rate(), 1.0 / 6.0);
+    }
+
+    #[test]
+    fn score_combines_both_rates() {
+        let score = TranscriptScore::compute("le chat", "le chas");
+        assert_eq!(score.wer, 0.5);
+        assert!((score.cer - 1.0 / 7.0).abs() < 1e-9);
+    }
+}
@@ -1,12 +1,19 @@
+use std::path::{Path, PathBuf};
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let protoc = protoc_bin_vendored::protoc_bin_path()?;
     std::env::set_var("PROTOC", protoc);
+    let descriptor = PathBuf::from(std::env::var("OUT_DIR")?).join("asr_descriptor.bin");
 
     tonic_prost_build::configure()
         .build_client(true)
         .build_server(true)
-        .compile_protos(&["../proto/asr.proto"], &["../proto"])?;
+        .extern_path(".common.v1", "::common_proto::v1")
+        .file_descriptor_set_path(&descriptor)
+        .compile_protos(&["../proto/asr.proto"], &["../proto", "../../common-proto/proto"])?;
 
     println!("cargo:rerun-if-changed=../proto/asr.proto");
+    println!("cargo:rerun-if-changed=../../common-proto/proto/common/v1/common.proto");
+    proto_compat::enforce(&descriptor, "asr.v1", Path::new("../proto/asr.v1.lock"))?;
     Ok(())
 }
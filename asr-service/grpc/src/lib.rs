@@ -5,27 +5,39 @@ use std::{
 
 use anyhow::Context;
 use asr_application::{TranscribeAudioCommand, TranscribeAudioRequest, TranscribeAudioResponse};
-use asr_domain::{LanguageTag, Transcript, TranscriptSegment, TranscriptToken};
+use asr_domain::text_metrics::{EditCounts, TranscriptScore};
+use common_domain::ErrorCode;
+use log_context::grpc_request_span;
 use rustycog_command::{CommandContext, CommandError, GenericCommandService};
 use rustycog_config::ServerConfig;
-use tonic::{transport::Server, Request, Response, Status};
+use service_health::{health_router, ReadinessCheck};
+use tonic::{service::Routes, transport::Server, Request, Response, Status};
+use tracing::Instrument;
 
 const MAX_MESSAGE_BYTES: usize = 64 * 1024 * 1024;
-const LANGUAGE_TAG_CODE_FR: i32 = 1;
-const LANGUAGE_TAG_CODE_EN: i32 = 2;
-const LANGUAGE_TAG_CODE_AUTO: i32 = 3;
-const LANGUAGE_TAG_CODE_OTHER: i32 = 4;
+const MAX_REFERENCE_TEXT_BYTES: usize = 1024 * 1024;
+const MAX_GRAMMAR_BYTES: usize = 64 * 1024;
+const MAX_PROMPT_BYTES: usize = 64 * 1024;
 
 pub mod pb {
     tonic::include_proto!("asr.v1");
+
+    // The shared messages moved to `common.v1`; re-exported so code written
+    // against this package's former copies keeps compiling.
+    pub use common_proto::v1::{
+        LanguageTag, LanguageTagCode, Transcript, TranscriptSegment, TranscriptToken,
+    };
 }
 
 pub use pb::asr_service_client::AsrServiceClient;
 pub use pb::asr_service_server::AsrServiceServer;
 
+vocal_proto_mappings::transcript_mappings!(pb, asr_domain);
+
 pub async fn serve_grpc(
     command_service: Arc<GenericCommandService>,
     server_config: ServerConfig,
+    readiness: Arc<dyn ReadinessCheck>,
 ) -> anyhow::Result<()> {
     let address = resolve_bind_addr(&server_config)?;
     let service = AsrGrpcService { command_service };
@@ -36,12 +48,18 @@ pub async fn serve_grpc(
         "starting ASR gRPC server"
     );
 
+    // Health probes are plain HTTP/1.1 GETs served on the gRPC port.
+    let routes = Routes::new(
+        AsrServiceServer::new(service)
+            .max_decoding_message_size(MAX_MESSAGE_BYTES)
+            .max_encoding_message_size(MAX_MESSAGE_BYTES),
+    )
+    .into_axum_router()
+    .merge(health_router(readiness));
+
     Server::builder()
-        .add_service(
-            AsrServiceServer::new(service)
-                .max_decoding_message_size(MAX_MESSAGE_BYTES)
-                .max_encoding_message_size(MAX_MESSAGE_BYTES),
-        )
+        .accept_http1(true)
+        .add_routes(Routes::from(routes))
         .serve(address)
         .await
         .context("ASR gRPC server failed")
@@ -58,16 +76,25 @@ impl pb::asr_service_server::AsrService for AsrGrpcService {
         &self,
         request: Request<pb::TranscribeAudioRequest>,
     ) -> Result<Response<pb::TranscribeAudioResponse>, Status> {
-        let request = map_transcribe_request(request.into_inner())?;
-        let command = TranscribeAudioCommand::new(request);
-        let context = CommandContext::new();
-        let result = self
-            .command_service
-            .execute(command, context)
-            .await
-            .map_err(map_command_error)?;
-
-        Ok(Response::new(map_transcribe_response(result)))
+        let span = grpc_request_span(
+            "asr.v1.AsrService/Transcribe",
+            request.metadata(),
+            request.get_ref().session_id.as_deref(),
+        );
+        async move {
+            let request = map_transcribe_request(request.into_inner())?;
+            let command = TranscribeAudioCommand::new(request);
+            let context = CommandContext::new();
+            let result = self
+                .command_service
+                .execute(command, context)
+                .await
+                .map_err(map_command_error)?;
+
+            Ok(Response::new(map_transcribe_response(result)))
+        }
+        .instrument(span)
+        .await
     }
 }
 
@@ -92,70 +119,52 @@ fn map_transcribe_request(request: pb::TranscribeAudioRequest) -> Result<Transcr
     validate_sample_rate(request.sample_rate_hz)?;
     validate_optional_text(&request.language_hint, "language_hint", 16)?;
     validate_optional_text(&request.session_id, "session_id", 64)?;
+    validate_optional_text(&request.model, "model", 64)?;
+    validate_optional_text(&request.reference_text, "reference_text", MAX_REFERENCE_TEXT_BYTES)?;
+    validate_optional_text(&request.decode_profile, "decode_profile", 32)?;
+    validate_optional_text(&request.grammar, "grammar", MAX_GRAMMAR_BYTES)?;
+    validate_optional_text(&request.prompt, "prompt", MAX_PROMPT_BYTES)?;
 
     Ok(TranscribeAudioRequest {
         samples: request.samples,
         sample_rate_hz: request.sample_rate_hz,
         language_hint: request.language_hint,
         session_id: request.session_id,
+        model: request.model,
+        reference_text: request.reference_text,
+        decode_profile: request.decode_profile,
+        grammar: request.grammar,
+        time_offset_ms: request.time_offset_ms,
+        prompt: request.prompt,
     })
 }
 
 fn map_transcribe_response(response: TranscribeAudioResponse) -> pb::TranscribeAudioResponse {
     pb::TranscribeAudioResponse {
         session_id: response.session_id,
-        transcript: Some(map_transcript(response.transcript)),
+        transcript: Some(transcript_to_proto(response.transcript)),
         text: response.text,
+        score: response.score.map(score_to_proto),
+        detected_language: response.detected_language,
+        language_probability: response.language_probability,
     }
 }
 
-fn map_transcript(transcript: Transcript) -> pb::Transcript {
-    pb::Transcript {
-        language: Some(map_language_tag(transcript.language)),
-        segments: transcript
-            .segments
-            .into_iter()
-            .map(map_transcript_segment)
-            .collect(),
+fn score_to_proto(score: TranscriptScore) -> pb::TranscriptScore {
+    pb::TranscriptScore {
+        wer: score.wer,
+        cer: score.cer,
+        words: Some(edit_counts_to_proto(score.words)),
+        characters: Some(edit_counts_to_proto(score.characters)),
     }
 }
 
-fn map_transcript_segment(segment: TranscriptSegment) -> pb::TranscriptSegment {
-    pb::TranscriptSegment {
-        text: segment.text,
-        start_ms: segment.start_ms,
-        end_ms: segment.end_ms,
-        tokens: segment.tokens.into_iter().map(map_transcript_token).collect(),
-    }
-}
-
-fn map_transcript_token(token: TranscriptToken) -> pb::TranscriptToken {
-    pb::TranscriptToken {
-        text: token.text,
-        start_ms: token.start_ms,
-        end_ms: token.end_ms,
-        confidence: token.confidence,
-    }
-}
-
-fn map_language_tag(language: LanguageTag) -> pb::LanguageTag {
-    match language {
-        LanguageTag::Fr => pb::LanguageTag {
-            code: LANGUAGE_TAG_CODE_FR,
-            other: None,
-        },
-        LanguageTag::En => pb::LanguageTag {
-            code: LANGUAGE_TAG_CODE_EN,
-            other: None,
-        },
-        LanguageTag::Auto => pb::LanguageTag {
-            code: LANGUAGE_TAG_CODE_AUTO,
-            other: None,
-        },
-        LanguageTag::Other(value) => pb::LanguageTag {
-            code: LANGUAGE_TAG_CODE_OTHER,
-            other: Some(value),
-        },
+fn edit_counts_to_proto(counts: EditCounts) -> pb::EditCounts {
+    pb::EditCounts {
+        substitutions: counts.substitutions as u64,
+        deletions: counts.deletions as u64,
+        insertions: counts.insertions as u64,
+        reference_len: counts.reference_len as u64,
     }
 }
 
@@ -164,13 +173,18 @@ fn map_command_error(error: CommandError) -> Status {
         CommandError::Validation { .. } => Status::invalid_argument(error.to_string()),
         CommandError::Authentication { .. } => Status::unauthenticated(error.to_string()),
         CommandError::Business { .. } => {
-            let message = error.message().to_ascii_lowercase();
-            if message.contains("not found") {
-                Status::not_found(error.to_string())
-            } else if message.contains("permission") || message.contains("forbidden") {
-                Status::permission_denied(error.to_string())
-            } else {
-                Status::failed_precondition(error.to_string())
+            let message = error.to_string();
+            match ErrorCode::from_code(error.error_code()) {
+                ErrorCode::InvalidInput | ErrorCode::AudioQuality => {
+                    Status::invalid_argument(message)
+                }
+                ErrorCode::NotFound => Status::not_found(message),
+                ErrorCode::AlreadyExists => Status::already_exists(message),
+                ErrorCode::PermissionDenied => Status::permission_denied(message),
+                ErrorCode::FailedPrecondition => Status::failed_precondition(message),
+                ErrorCode::ResourceExhausted => Status::resource_exhausted(message),
+                ErrorCode::Unavailable => Status::unavailable(message),
+                ErrorCode::Internal => Status::internal(message),
             }
         }
         CommandError::Infrastructure { .. }
@@ -214,11 +228,12 @@ mod tests {
 
     use asr_application::{AsrCommandRegistryFactory, AsrUseCase};
     use asr_domain::{LanguageTag, Transcript, TranscriptSegment};
-    use rustycog_command::GenericCommandService;
+    use rustycog_command::{CommandError, GenericCommandService};
     use rustycog_config::ServerConfig;
+    use service_health::AlwaysReady;
     use tonic::Request;
 
-    use super::{pb, serve_grpc, AsrServiceClient};
+    use super::{map_command_error, pb, serve_grpc, AsrServiceClient, TranscriptScore};
 
     struct MockAsrUseCase;
 
@@ -233,15 +248,24 @@ mod tests {
                     .session_id
                     .unwrap_or_else(|| "generated-session".to_string()),
                 transcript: Transcript {
-                    language: LanguageTag::En,
+                    language: LanguageTag::en(),
                     segments: vec![TranscriptSegment {
                         text: "hello grpc".to_string(),
                         start_ms: 0,
                         end_ms: 200,
                         tokens: vec![],
+                        speaker: None,
+                        language: None,
+                        no_speech_prob: None,
+                        avg_logprob: None,
                     }],
                 },
                 text: "hello grpc".to_string(),
+                score: request
+                    .reference_text
+                    .map(|reference| TranscriptScore::compute(&reference, "hello grpc")),
+                detected_language: None,
+                language_probability: None,
             })
         }
     }
@@ -256,16 +280,24 @@ mod tests {
         let registry = AsrCommandRegistryFactory::create_registry(Arc::new(MockAsrUseCase));
         let command_service = Arc::new(GenericCommandService::new(Arc::new(registry)));
 
-        let server = tokio::spawn(async move { serve_grpc(command_service, server_config).await });
+        let server = tokio::spawn(async move {
+            serve_grpc(command_service, server_config, Arc::new(AlwaysReady)).await
+        });
         let endpoint = format!("http://127.0.0.1:{port}");
         let mut client = connect_with_retry(endpoint).await;
 
         let response = client
             .transcribe(Request::new(pb::TranscribeAudioRequest {
-                samples: vec![0.1, 0.2, 0.3],
+                samples: test_audio::speech_like(16_000, 500),
                 sample_rate_hz: Some(16_000),
                 language_hint: Some("en".to_string()),
                 session_id: Some("it-session".to_string()),
+                model: None,
+                reference_text: Some("hello world".to_string()),
+                decode_profile: None,
+                grammar: None,
+                time_offset_ms: None,
+                prompt: None,
             }))
             .await
             .expect("rpc succeeds")
@@ -273,6 +305,9 @@ mod tests {
 
         assert_eq!(response.session_id, "it-session");
         assert_eq!(response.text, "hello grpc");
+        let score = response.score.expect("reference text requests a score");
+        assert_eq!(score.wer, 0.5);
+        assert_eq!(score.words.map(|words| words.substitutions), Some(1));
 
         server.abort();
         let _ = server.await;
@@ -295,4 +330,17 @@ mod tests {
         }
         panic!("unable to connect gRPC client to {endpoint}");
     }
+
+    #[test]
+    fn business_errors_map_by_code_not_message() {
+        let status = map_command_error(CommandError::business("not_found", "segment missing"));
+        assert_eq!(status.code(), tonic::Code::NotFound);
+
+        let status =
+            map_command_error(CommandError::business("internal", "model not found on disk"));
+        assert_eq!(status.code(), tonic::Code::Internal);
+
+        let status = map_command_error(CommandError::business("domain_error", "permission"));
+        assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+    }
 }
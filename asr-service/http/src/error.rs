@@ -0,0 +1,58 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use common_domain::ErrorCode;
+use http_problem::{ProblemDetails, UNAUTHENTICATED};
+use rustycog_command::CommandError;
+
+#[derive(Debug)]
+pub enum HttpError {
+    Validation { message: String },
+    Unauthorized,
+    Forbidden,
+    NotFound,
+    Business { code: ErrorCode, message: String },
+    Internal { message: String },
+}
+
+impl HttpError {
+    pub fn problem(self) -> ProblemDetails {
+        match self {
+            HttpError::Validation { message } => {
+                ProblemDetails::from_code(ErrorCode::InvalidInput, Some(message))
+            }
+            HttpError::Unauthorized => {
+                ProblemDetails::new(StatusCode::UNAUTHORIZED, UNAUTHENTICATED, None)
+            }
+            HttpError::Forbidden => ProblemDetails::from_code(ErrorCode::PermissionDenied, None),
+            HttpError::NotFound => ProblemDetails::from_code(ErrorCode::NotFound, None),
+            HttpError::Business { code, message } => ProblemDetails::from_code(code, Some(message)),
+            HttpError::Internal { message } => {
+                ProblemDetails::from_code(ErrorCode::Internal, Some(message))
+            }
+        }
+    }
+}
+
+impl IntoResponse for HttpError {
+    fn into_response(self) -> Response {
+        self.problem().into_response()
+    }
+}
+
+pub fn error_mapper(error: CommandError) -> HttpError {
+    match error {
+        CommandError::Validation { .. } => HttpError::Validation {
+            message: error.to_string(),
+        },
+        CommandError::Authentication { .. } => HttpError::Unauthorized,
+        CommandError::Business { .. } => HttpError::Business {
+            code: ErrorCode::from_code(error.error_code()),
+            message: error.to_string(),
+        },
+        _ => HttpError::Internal {
+            message: error.to_string(),
+        },
+    }
+}
@@ -0,0 +1,147 @@
+use axum::{
+    extract::{FromRequest, Multipart, Request, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use rustycog_command::CommandContext;
+use rustycog_http::{AppState, ValidatedJson};
+use validator::Validate;
+use wav_io::decode_wav;
+
+use asr_application::{TranscribeAudioCommand, TranscribeAudioRequest, TranscribeAudioResponse};
+
+use crate::error::{error_mapper, HttpError};
+
+/// Takes either the gRPC request as JSON, or a `multipart/form-data` upload
+/// with the WAV file in `audio` and the optional text fields `language_hint`,
+/// `session_id`, `model`, `reference_text`, `decode_profile`, `grammar`,
+/// `time_offset_ms` and `prompt`.
+pub async fn transcribe_audio(State(state): State<AppState>, request: Request) -> Response {
+    let request = if is_multipart(&request) {
+        match Multipart::from_request(request, &state).await {
+            Ok(multipart) => multipart_request(multipart).await,
+            Err(rejection) => return rejection.into_response(),
+        }
+    } else {
+        match ValidatedJson::<TranscribeAudioRequest>::from_request(request, &state).await {
+            Ok(ValidatedJson(request)) => Ok(request),
+            Err(rejection) => return rejection.into_response(),
+        }
+    };
+
+    let result = match request {
+        Ok(request) => {
+            tracing::info!(
+                sample_count = request.samples.len(),
+                sample_rate_hz = request.sample_rate_hz.unwrap_or(0),
+                language_hint = request.language_hint.as_deref().unwrap_or("auto"),
+                session_id = request.session_id.as_deref().unwrap_or("auto"),
+                "received transcribe request"
+            );
+            execute_transcribe(&state, request).await
+        }
+        Err(error) => Err(error),
+    };
+
+    match result {
+        Ok(result) => {
+            tracing::info!(
+                segment_count = result.transcript.segments.len(),
+                "transcribe request completed"
+            );
+            (StatusCode::OK, Json(result)).into_response()
+        }
+        Err(error) => {
+            tracing::error!(error = ?error, "transcribe request failed");
+            error.into_response()
+        }
+    }
+}
+
+fn is_multipart(request: &Request) -> bool {
+    request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("multipart/form-data"))
+}
+
+async fn multipart_request(mut multipart: Multipart) -> Result<TranscribeAudioRequest, HttpError> {
+    let invalid = |message: String| HttpError::Validation { message };
+    let mut audio = None;
+    let mut request = TranscribeAudioRequest {
+        samples: Vec::new(),
+        sample_rate_hz: None,
+        language_hint: None,
+        session_id: None,
+        model: None,
+        reference_text: None,
+        decode_profile: None,
+        grammar: None,
+        time_offset_ms: None,
+        prompt: None,
+    };
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|err| invalid(format!("invalid multipart body: {err}")))?
+    {
+        let name = field.name().unwrap_or_default().to_string();
+        if name == "audio" {
+            let bytes = field
+                .bytes()
+                .await
+                .map_err(|err| invalid(format!("cannot read `audio`: {err}")))?;
+            audio = Some(decode_wav(&bytes).map_err(|err| invalid(format!("audio: {err}")))?);
+            continue;
+        }
+        if name == "time_offset_ms" {
+            let text = field
+                .text()
+                .await
+                .map_err(|err| invalid(format!("cannot read `{name}`: {err}")))?;
+            let offset = text
+                .trim()
+                .parse()
+                .map_err(|_| invalid(format!("invalid time_offset_ms `{text}`")))?;
+            request.time_offset_ms = Some(offset);
+            continue;
+        }
+
+        let slot = match name.as_str() {
+            "language_hint" => &mut request.language_hint,
+            "session_id" => &mut request.session_id,
+            "model" => &mut request.model,
+            "reference_text" => &mut request.reference_text,
+            "decode_profile" => &mut request.decode_profile,
+            "grammar" => &mut request.grammar,
+            "prompt" => &mut request.prompt,
+            _ => continue,
+        };
+        let text = field
+            .text()
+            .await
+            .map_err(|err| invalid(format!("cannot read `{name}`: {err}")))?;
+        *slot = Some(text);
+    }
+
+    let audio = audio.ok_or_else(|| invalid("multipart body needs an `audio` WAV file".into()))?;
+    request.samples = audio.samples;
+    request.sample_rate_hz = Some(audio.sample_rate_hz);
+    request.validate().map_err(|err| invalid(err.to_string()))?;
+    Ok(request)
+}
+
+async fn execute_transcribe(
+    state: &AppState,
+    request: TranscribeAudioRequest,
+) -> Result<TranscribeAudioResponse, HttpError> {
+    let command = TranscribeAudioCommand::new(request);
+    let context = CommandContext::new();
+    state
+        .command_service
+        .execute(command, context)
+        .await
+        .map_err(error_mapper)
+}
@@ -0,0 +1,3 @@
+mod asr;
+
+pub use asr::transcribe_audio;
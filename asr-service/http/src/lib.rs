@@ -0,0 +1,41 @@
+//! Optional REST front end for asr-service. It serves the same transcribe
+//! command as the gRPC server, for clients that cannot speak gRPC.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::DefaultBodyLimit,
+    middleware,
+    routing::{get, post},
+};
+use log_context::http_request_span;
+use rustycog_config::ServerConfig;
+use rustycog_http::{AppState, RouteBuilder};
+use service_health::{liveness, readiness, ReadinessCheck, LIVENESS_PATH, READINESS_PATH};
+
+pub mod error;
+pub mod handlers;
+
+pub use error::{error_mapper, HttpError};
+pub use handlers::*;
+
+pub const TRANSCRIBE_PATH: &str = "/api/v1/transcribe";
+
+pub async fn create_app_routes(
+    state: AppState,
+    config: ServerConfig,
+    readiness_check: Arc<dyn ReadinessCheck>,
+) -> anyhow::Result<()> {
+    // JSON float arrays and WAV uploads can both be large.
+    let transcribe_route = post(transcribe_audio)
+        .layer(DefaultBodyLimit::max(64 * 1024 * 1024))
+        .layer(middleware::from_fn(http_request_span));
+
+    RouteBuilder::new(state)
+        .health_check()
+        .route(LIVENESS_PATH, get(liveness))
+        .route(READINESS_PATH, get(move || readiness(readiness_check.clone())))
+        .route(TRANSCRIBE_PATH, transcribe_route)
+        .build(config)
+        .await
+}
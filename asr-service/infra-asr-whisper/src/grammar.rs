@@ -0,0 +1,424 @@
+//! GBNF grammar parser: turns the text form accepted by whisper.cpp into the
+//! rule elements its decoder walks. Follows whisper.cpp's `grammar-parser`:
+//! rule ids are assigned in order of first mention, and groups and `*`, `+`,
+//! `?` repetitions become generated sub-rules.
+
+use std::collections::HashMap;
+
+/// Rule that decoding starts from.
+pub(crate) const ROOT_RULE: &str = "root";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ElementKind {
+    /// Ends a rule.
+    End,
+    /// Starts another alternative of the rule.
+    Alternate,
+    /// Expands the rule whose id is the value.
+    RuleRef,
+    /// Matches the character whose code point is the value.
+    Char,
+    /// Matches any character but the value (a `[^...]` class).
+    CharNot,
+    /// Turns the preceding `Char` or `CharAlt` into an inclusive range
+    /// ending at the value.
+    CharRangeUpper,
+    /// Adds another character to the preceding class.
+    CharAlt,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Element {
+    pub kind: ElementKind,
+    pub value: u32,
+}
+
+impl Element {
+    fn new(kind: ElementKind, value: u32) -> Self {
+        Self { kind, value }
+    }
+}
+
+/// Rules indexed by id, each ending with [`ElementKind::End`].
+#[derive(Debug, Clone)]
+pub(crate) struct Grammar {
+    pub rules: Vec<Vec<Element>>,
+    pub root: usize,
+}
+
+impl Grammar {
+    /// All rules back to back, the layout whisper.cpp expects.
+    pub fn elements(&self) -> Vec<Element> {
+        self.rules.iter().flatten().copied().collect()
+    }
+}
+
+pub(crate) fn parse(source: &str) -> Result<Grammar, String> {
+    let mut parser = Parser {
+        chars: source.chars().collect(),
+        pos: 0,
+        symbol_ids: HashMap::new(),
+        rules: Vec::new(),
+    };
+    parser.skip_space(true);
+    while !parser.at_end() {
+        parser.parse_rule()?;
+    }
+
+    for rule in &parser.rules {
+        for element in rule {
+            let defined = parser
+                .rules
+                .get(element.value as usize)
+                .is_some_and(|rule| !rule.is_empty());
+            if element.kind == ElementKind::RuleRef && !defined {
+                let name = parser
+                    .symbol_ids
+                    .iter()
+                    .find(|(_, id)| **id == element.value)
+                    .map(|(name, _)| name.as_str())
+                    .unwrap_or_default();
+                return Err(format!("undefined rule `{name}`"));
+            }
+        }
+    }
+    let root = *parser
+        .symbol_ids
+        .get(ROOT_RULE)
+        .filter(|id| parser.rules.get(**id as usize).is_some_and(|rule| !rule.is_empty()))
+        .ok_or_else(|| format!("grammar has no `{ROOT_RULE}` rule"))?;
+
+    Ok(Grammar {
+        rules: parser.rules,
+        root: root as usize,
+    })
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    symbol_ids: HashMap<String, u32>,
+    rules: Vec<Vec<Element>>,
+}
+
+impl Parser {
+    fn at_end(&self) -> bool {
+        self.pos >= self.chars.len()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    /// Moves past `token` when the input continues with it.
+    fn eat(&mut self, token: &str) -> bool {
+        let matches = token
+            .chars()
+            .enumerate()
+            .all(|(offset, c)| self.peek_at(offset) == Some(c));
+        if matches {
+            self.pos += token.chars().count();
+        }
+        matches
+    }
+
+    fn symbol_id(&mut self, name: &str) -> u32 {
+        let next = self.symbol_ids.len() as u32;
+        *self.symbol_ids.entry(name.to_string()).or_insert(next)
+    }
+
+    fn generated_symbol_id(&mut self, base: &str) -> u32 {
+        let next = self.symbol_ids.len() as u32;
+        self.symbol_ids.insert(format!("{base}_{next}"), next);
+        next
+    }
+
+    fn add_rule(&mut self, id: u32, rule: Vec<Element>) {
+        let id = id as usize;
+        if self.rules.len() <= id {
+            self.rules.resize(id + 1, Vec::new());
+        }
+        self.rules[id] = rule;
+    }
+
+    /// Skips blanks and `#` comments, and line breaks when `newline_ok`.
+    fn skip_space(&mut self, newline_ok: bool) {
+        while let Some(c) = self.peek() {
+            match c {
+                ' ' | '\t' => self.pos += 1,
+                '#' => {
+                    while self.peek().is_some_and(|c| c != '\r' && c != '\n') {
+                        self.pos += 1;
+                    }
+                }
+                '\r' | '\n' if newline_ok => self.pos += 1,
+                _ => break,
+            }
+        }
+    }
+
+    fn parse_name(&mut self) -> Result<String, String> {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return Err(format!("expecting a rule name at character {start}"));
+        }
+        Ok(self.chars[start..self.pos].iter().collect())
+    }
+
+    fn parse_rule(&mut self) -> Result<(), String> {
+        let name = self.parse_name()?;
+        self.skip_space(false);
+        let id = self.symbol_id(&name);
+        if !self.eat("::=") {
+            return Err(format!("expecting `::=` after `{name}`"));
+        }
+        self.skip_space(true);
+        self.parse_alternates(&name, id, false)?;
+
+        match self.peek() {
+            Some('\r') | Some('\n') | None => {}
+            Some(c) => return Err(format!("expecting newline or end after `{name}`, got `{c}`")),
+        }
+        self.skip_space(true);
+        Ok(())
+    }
+
+    fn parse_alternates(&mut self, rule_name: &str, id: u32, nested: bool) -> Result<(), String> {
+        let mut rule = Vec::new();
+        self.parse_sequence(rule_name, &mut rule, nested)?;
+        while self.peek() == Some('|') {
+            rule.push(Element::new(ElementKind::Alternate, 0));
+            self.pos += 1;
+            self.skip_space(true);
+            self.parse_sequence(rule_name, &mut rule, nested)?;
+        }
+        rule.push(Element::new(ElementKind::End, 0));
+        self.add_rule(id, rule);
+        Ok(())
+    }
+
+    fn parse_sequence(
+        &mut self,
+        rule_name: &str,
+        out: &mut Vec<Element>,
+        nested: bool,
+    ) -> Result<(), String> {
+        let mut last_symbol_start = out.len();
+        while let Some(c) = self.peek() {
+            match c {
+                '"' => {
+                    self.pos += 1;
+                    last_symbol_start = out.len();
+                    while self.peek() != Some('"') {
+                        let c = self.parse_char("literal")?;
+                        out.push(Element::new(ElementKind::Char, c));
+                    }
+                    self.pos += 1;
+                }
+                '[' => {
+                    self.pos += 1;
+                    last_symbol_start = out.len();
+                    let start_kind = if self.peek() == Some('^') {
+                        self.pos += 1;
+                        ElementKind::CharNot
+                    } else {
+                        ElementKind::Char
+                    };
+                    while self.peek() != Some(']') {
+                        let c = self.parse_char("character class")?;
+                        let kind = if last_symbol_start < out.len() {
+                            ElementKind::CharAlt
+                        } else {
+                            start_kind
+                        };
+                        out.push(Element::new(kind, c));
+                        if self.peek() == Some('-') && self.peek_at(1).is_some_and(|c| c != ']') {
+                            self.pos += 1;
+                            let upper = self.parse_char("character range")?;
+                            out.push(Element::new(ElementKind::CharRangeUpper, upper));
+                        }
+                    }
+                    self.pos += 1;
+                }
+                c if c.is_ascii_alphanumeric() || c == '-' || c == '_' => {
+                    let name = self.parse_name()?;
+                    let id = self.symbol_id(&name);
+                    last_symbol_start = out.len();
+                    out.push(Element::new(ElementKind::RuleRef, id));
+                    // `parse_name` already moved past the name.
+                    self.skip_space(nested);
+                    continue;
+                }
+                '(' => {
+                    self.pos += 1;
+                    self.skip_space(true);
+                    let id = self.generated_symbol_id(rule_name);
+                    self.parse_alternates(rule_name, id, true)?;
+                    last_symbol_start = out.len();
+                    out.push(Element::new(ElementKind::RuleRef, id));
+                    if self.peek() != Some(')') {
+                        return Err(format!("expecting `)` in `{rule_name}`"));
+                    }
+                    self.pos += 1;
+                }
+                '*' | '+' | '?' => {
+                    if last_symbol_start == out.len() {
+                        return Err(format!("expecting an item before `{c}` in `{rule_name}`"));
+                    }
+                    // S* becomes S' ::= S S' |, S+ becomes S' ::= S S' | S
+                    // and S? becomes S' ::= S |.
+                    let id = self.generated_symbol_id(rule_name);
+                    let item = out.split_off(last_symbol_start);
+                    let mut rule = item.clone();
+                    if c != '?' {
+                        rule.push(Element::new(ElementKind::RuleRef, id));
+                    }
+                    rule.push(Element::new(ElementKind::Alternate, 0));
+                    if c == '+' {
+                        rule.extend_from_slice(&item);
+                    }
+                    rule.push(Element::new(ElementKind::End, 0));
+                    self.add_rule(id, rule);
+                    out.push(Element::new(ElementKind::RuleRef, id));
+                    self.pos += 1;
+                }
+                _ => break,
+            }
+            self.skip_space(nested);
+        }
+        Ok(())
+    }
+
+    /// One character of a literal or class, decoding `\` escapes.
+    fn parse_char(&mut self, context: &str) -> Result<u32, String> {
+        let c = self
+            .peek()
+            .ok_or_else(|| format!("unexpected end of grammar in {context}"))?;
+        self.pos += 1;
+        if c != '\\' {
+            return Ok(c as u32);
+        }
+
+        let escape = self
+            .peek()
+            .ok_or_else(|| format!("unexpected end of grammar in {context}"))?;
+        self.pos += 1;
+        match escape {
+            'x' => self.parse_hex(2),
+            'u' => self.parse_hex(4),
+            'U' => self.parse_hex(8),
+            't' => Ok('\t' as u32),
+            'r' => Ok('\r' as u32),
+            'n' => Ok('\n' as u32),
+            '\\' | '"' | '[' | ']' => Ok(escape as u32),
+            other => Err(format!("unknown escape `\\{other}` in {context}")),
+        }
+    }
+
+    fn parse_hex(&mut self, digits: usize) -> Result<u32, String> {
+        let end = self.pos + digits;
+        let hex: String = self.chars.get(self.pos..end).unwrap_or_default().iter().collect();
+        let value = u32::from_str_radix(&hex, 16)
+            .ok()
+            .filter(|_| hex.len() == digits)
+            .ok_or_else(|| format!("expecting {digits} hex digits, got `{hex}`"))?;
+        self.pos = end;
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn literal(c: char) -> Element {
+        Element::new(ElementKind::Char, c as u32)
+    }
+
+    fn end() -> Element {
+        Element::new(ElementKind::End, 0)
+    }
+
+    #[test]
+    fn alternatives_of_literals_and_rule_references() {
+        let grammar = parse(
+            r#"# lights
+root ::= verb " " room
+verb ::= "on" | "off"
+room ::= [a-z]+
+"#,
+        )
+        .expect("parses");
+
+        assert_eq!(grammar.root, 0);
+        assert_eq!(
+            grammar.rules[0],
+            vec![
+                Element::new(ElementKind::RuleRef, 1),
+                literal(' '),
+                Element::new(ElementKind::RuleRef, 2),
+                end(),
+            ]
+        );
+        assert_eq!(
+            grammar.rules[1],
+            vec![
+                literal('o'),
+                literal('n'),
+                Element::new(ElementKind::Alternate, 0),
+                literal('o'),
+                literal('f'),
+                literal('f'),
+                end(),
+            ]
+        );
+        // room ::= room_3; room_3 ::= [a-z] room_3 | [a-z]
+        assert_eq!(grammar.rules[2], vec![Element::new(ElementKind::RuleRef, 3), end()]);
+        let class = [literal('a'), Element::new(ElementKind::CharRangeUpper, 'z' as u32)];
+        let mut repeated = class.to_vec();
+        repeated.push(Element::new(ElementKind::RuleRef, 3));
+        repeated.push(Element::new(ElementKind::Alternate, 0));
+        repeated.extend_from_slice(&class);
+        repeated.push(end());
+        assert_eq!(grammar.rules[3], repeated);
+        assert_eq!(grammar.elements().len(), 4 + 7 + 2 + 7);
+    }
+
+    #[test]
+    fn groups_escapes_and_negated_classes() {
+        let grammar = parse("root ::= (\"yes\" | \"no\")? [^\\n] \"\\u00e9\"").expect("parses");
+
+        assert_eq!(grammar.rules[0][0], Element::new(ElementKind::RuleRef, 2));
+        assert_eq!(grammar.rules[0][1], Element::new(ElementKind::CharNot, '\n' as u32));
+        assert_eq!(grammar.rules[0][2], literal('é'));
+        assert_eq!(grammar.rules[1].len(), 3 + 1 + 2 + 1);
+        assert_eq!(
+            grammar.rules[2],
+            vec![
+                Element::new(ElementKind::RuleRef, 1),
+                Element::new(ElementKind::Alternate, 0),
+                end(),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_grammars_are_rejected() {
+        assert!(parse("command ::= \"stop\"").unwrap_err().contains("root"));
+        assert!(parse("root ::= verb").unwrap_err().contains("verb"));
+        assert!(parse("root = \"stop\"").unwrap_err().contains("::="));
+        assert!(parse("root ::= * \"stop\"").unwrap_err().contains("before"));
+        assert!(parse("root ::= (\"stop\"").unwrap_err().contains(")"));
+        assert!(parse("root ::= \"stop").is_err());
+    }
+}
@@ -1,14 +1,35 @@
+mod grammar;
+mod registry;
+
 use asr_domain::{
-    DomainError, Transcript, TranscriptSegment, TranscriptToken, TranscriptionOutput,
-    TranscriptionPort, TranscriptionRequest,
+    ms_to_sample, AudioChunk, DecodeProfile, DomainError, Transcript, TranscriptSegment,
+    TranscriptToken, TranscriptionOutput, TranscriptionPort, TranscriptionRequest,
 };
 use async_trait::async_trait;
+use service_health::{DependencyStatus, ReadinessCheck};
+use std::path::Path;
 use std::sync::Mutex;
 use whisper_rs::{
-    DtwMode, DtwModelPreset, DtwParameters, FullParams, SamplingStrategy, WhisperContext,
-    WhisperContextParameters, WhisperTokenData,
+    get_lang_str, DtwMode, DtwModelPreset, DtwParameters, FullParams, SamplingStrategy,
+    WhisperContext, WhisperContextParameters, WhisperGrammarElement, WhisperGrammarElementType,
+    WhisperState, WhisperTokenData,
 };
 
+use grammar::{Element, ElementKind};
+
+pub use registry::WhisperModelRegistry;
+
+const WARMUP_SAMPLE_RATE_HZ: u32 = 16_000;
+/// Encoder frames per second of audio; Whisper's full window is 1500 (30 s).
+const AUDIO_CTX_FRAMES_PER_SECOND: f64 = 50.0;
+const MAX_AUDIO_CTX: i32 = 1_500;
+/// Frames added to a clip's encoder window so its last word is not cut.
+const LOW_LATENCY_AUDIO_CTX_MARGIN: i32 = 64;
+/// Text context of a low-latency decode; short commands need little.
+const LOW_LATENCY_MAX_TEXT_CTX: i32 = 64;
+/// Logit penalty on tokens the grammar rejects, as in whisper.cpp's CLI.
+const GRAMMAR_PENALTY: f32 = 100.0;
+
 #[derive(Debug, Clone)]
 pub struct WhisperAdapterConfig {
     pub model_path: String,
@@ -17,6 +38,8 @@ pub struct WhisperAdapterConfig {
     pub threads: usize,
     pub dtw_preset: String,
     pub dtw_mem_size: usize,
+    /// Tokens kept from the end of a request prompt; 0 ignores prompts.
+    pub prompt_max_tokens: usize,
 }
 
 impl WhisperAdapterConfig {
@@ -39,32 +62,65 @@ impl WhisperAdapterConfig {
     }
 }
 
+/// Whisper decodes with the primary language subtag only, so `pt-BR` and
+/// `pt-PT` both decode as `pt`; regional differences are handled by model
+/// selection and post-processing.
 fn resolve_decode_language(
     config_language: &str,
     hint: Option<&asr_domain::LanguageTag>,
 ) -> Option<String> {
-    if let Some(tag) = hint {
-        return match tag {
-            asr_domain::LanguageTag::Fr => Some("fr".to_string()),
-            asr_domain::LanguageTag::En => Some("en".to_string()),
-            asr_domain::LanguageTag::Auto => None,
-            asr_domain::LanguageTag::Other(code) => {
-                let normalized = code.trim().to_ascii_lowercase();
-                if normalized.is_empty() {
-                    None
-                } else {
-                    Some(normalized)
-                }
-            }
-        };
-    }
+    let configured;
+    let tag = match hint {
+        Some(tag) => tag,
+        None => {
+            configured = asr_domain::LanguageTag::parse(config_language).ok()?;
+            &configured
+        }
+    };
+    tag.primary_language().map(str::to_string)
+}
 
-    let normalized = config_language.trim().to_ascii_lowercase();
-    if normalized.is_empty() || normalized == "auto" {
-        None
-    } else {
-        Some(normalized)
-    }
+/// Encoder window of a low-latency decode: the clip plus a margin, up to
+/// the full 30 s window.
+fn low_latency_audio_ctx(sample_count: usize, sample_rate_hz: u32) -> i32 {
+    let seconds = sample_count as f64 / f64::from(sample_rate_hz.max(1));
+    let frames = (seconds * AUDIO_CTX_FRAMES_PER_SECOND).ceil() as i32;
+    frames
+        .saturating_add(LOW_LATENCY_AUDIO_CTX_MARGIN)
+        .min(MAX_AUDIO_CTX)
+}
+
+/// The last `max_tokens` of `tokens`: the text nearest the audio matters
+/// most for continuity.
+fn prompt_tail<T>(mut tokens: Vec<T>, max_tokens: usize) -> Vec<T> {
+    let excess = tokens.len().saturating_sub(max_tokens);
+    tokens.drain(..excess);
+    tokens
+}
+
+/// Probability of language `lang_id` in Whisper's per-language scores.
+fn language_probability(lang_id: i32, probabilities: &[f32]) -> Option<f32> {
+    usize::try_from(lang_id)
+        .ok()
+        .and_then(|index| probabilities.get(index))
+        .copied()
+}
+
+fn whisper_grammar_element(element: &Element) -> WhisperGrammarElement {
+    let element_type = match element.kind {
+        ElementKind::End => WhisperGrammarElementType::End,
+        ElementKind::Alternate => WhisperGrammarElementType::Alternate,
+        ElementKind::RuleRef => WhisperGrammarElementType::RuleReference,
+        ElementKind::Char => WhisperGrammarElementType::Character,
+        ElementKind::CharNot => WhisperGrammarElementType::NotCharacter,
+        ElementKind::CharRangeUpper => WhisperGrammarElementType::CharacterRangeUpper,
+        ElementKind::CharAlt => WhisperGrammarElementType::CharacterAlternate,
+    };
+    WhisperGrammarElement::new(element_type, element.value)
+}
+
+fn mean(values: &[f32]) -> Option<f32> {
+    (!values.is_empty()).then(|| values.iter().sum::<f32>() / values.len() as f32)
 }
 
 fn to_ms_10ms_units(raw: i64) -> Option<u64> {
@@ -80,6 +136,43 @@ fn token_end_hint_ms(token_data: WhisperTokenData) -> Option<u64> {
     to_ms_10ms_units(token_data.t1)
 }
 
+struct PendingToken {
+    text: String,
+    confidence: f32,
+    start_ms: u64,
+    end_hint_ms: Option<u64>,
+    fallback_end_ms: u64,
+}
+
+impl PendingToken {
+    fn finish(
+        self,
+        next_start_hint_ms: Option<u64>,
+        segment_end_ms: u64,
+        sample_rate_hz: u32,
+    ) -> TranscriptToken {
+        let end_ms = self
+            .end_hint_ms
+            .filter(|end| *end > self.start_ms)
+            .or_else(|| next_start_hint_ms.filter(|next| *next > self.start_ms))
+            .unwrap_or(self.fallback_end_ms);
+
+        let min_end = self.start_ms.saturating_add(1);
+        let max_end = segment_end_ms.max(min_end);
+        let end_ms = end_ms.clamp(min_end, max_end);
+
+        TranscriptToken {
+            text: self.text,
+            start_ms: self.start_ms,
+            end_ms,
+            confidence: self.confidence,
+            start_sample: Some(ms_to_sample(self.start_ms, sample_rate_hz)),
+            end_sample: Some(ms_to_sample(end_ms, sample_rate_hz)),
+            disfluency: false,
+        }
+    }
+}
+
 pub struct WhisperTranscriptionAdapter {
     config: WhisperAdapterConfig,
     runtime: Mutex<WhisperRuntime>,
@@ -98,6 +191,28 @@ impl WhisperTranscriptionAdapter {
     }
 }
 
+#[async_trait]
+impl ReadinessCheck for WhisperTranscriptionAdapter {
+    async fn check(&self) -> Vec<DependencyStatus> {
+        const NAME: &str = "whisper-model";
+        let loaded = match self.runtime.lock() {
+            Ok(runtime) => runtime.context.is_some(),
+            Err(_) => return vec![DependencyStatus::not_ready(NAME, "runtime lock poisoned")],
+        };
+        let status = if loaded {
+            DependencyStatus::ready(NAME)
+        } else if Path::new(&self.config.model_path).is_file() {
+            DependencyStatus::ready(NAME).with_detail("loads on first request")
+        } else {
+            DependencyStatus::not_ready(
+                NAME,
+                format!("model file not found: {}", self.config.model_path),
+            )
+        };
+        vec![status]
+    }
+}
+
 #[async_trait]
 impl TranscriptionPort for WhisperTranscriptionAdapter {
     async fn transcribe(
@@ -109,14 +224,64 @@ impl TranscriptionPort for WhisperTranscriptionAdapter {
 }
 
 impl WhisperTranscriptionAdapter {
+    /// Loads the model and decodes one second of silence so backend kernels
+    /// and memory pools are initialized before the first real request.
+    pub fn warm_up(&self) -> Result<(), DomainError> {
+        self.transcribe_with_runtime(TranscriptionRequest {
+            language_hint: None,
+            model: None,
+            profile: DecodeProfile::Accurate,
+            grammar: None,
+            prompt: None,
+            audio: AudioChunk::mono(
+                WARMUP_SAMPLE_RATE_HZ,
+                vec![0.0; WARMUP_SAMPLE_RATE_HZ as usize],
+            ),
+        })
+        .map(|_| ())
+    }
+
     fn to_dtw_preset(&self) -> DtwModelPreset {
         self.config.to_dtw_preset()
     }
 
+    /// Language Whisper picked during an auto-language decode and its
+    /// probability. The probability needs another encoder pass over the
+    /// first window; if that fails the language is still reported.
+    fn detect_language(
+        &self,
+        state: &WhisperState,
+    ) -> (Option<asr_domain::LanguageTag>, Option<f32>) {
+        let lang_id = state.full_lang_id_from_state();
+        let language = get_lang_str(lang_id)
+            .and_then(|code| asr_domain::LanguageTag::parse(code).ok())
+            .filter(|tag| !tag.is_auto());
+        if language.is_none() {
+            return (None, None);
+        }
+        let probability = state
+            .lang_detect(0, self.config.threads)
+            .ok()
+            .and_then(|(_, probabilities)| language_probability(lang_id, &probabilities));
+        (language, probability)
+    }
+
     fn transcribe_with_runtime(
         &self,
         request: TranscriptionRequest,
     ) -> Result<TranscriptionOutput, DomainError> {
+        let grammar = request
+            .grammar
+            .as_deref()
+            .map(grammar::parse)
+            .transpose()
+            .map_err(|err| DomainError::invalid_input(&format!("grammar: {err}")))?;
+        let grammar_elements: Vec<WhisperGrammarElement> = grammar
+            .iter()
+            .flat_map(|grammar| grammar.elements())
+            .map(|element| whisper_grammar_element(&element))
+            .collect();
+
         let mut runtime = self
             .runtime
             .lock()
@@ -155,6 +320,22 @@ impl WhisperTranscriptionAdapter {
             )
         })?;
 
+        let prompt_tokens = match request.prompt.as_deref() {
+            Some(prompt) if self.config.prompt_max_tokens > 0 && !prompt.trim().is_empty() => {
+                // Every token covers at least one byte, so this never truncates.
+                let tokens = whisper_context
+                    .tokenize(prompt, prompt.len() + 1)
+                    .map_err(|err| {
+                        DomainError::external_service_error(
+                            "whisper",
+                            &format!("failed to tokenize prompt: {err}"),
+                        )
+                    })?;
+                prompt_tail(tokens, self.config.prompt_max_tokens)
+            }
+            _ => Vec::new(),
+        };
+
         let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
         params.set_n_threads(self.config.threads as i32);
         let decode_language =
@@ -164,7 +345,27 @@ impl WhisperTranscriptionAdapter {
         params.set_token_timestamps(true);
         params.set_split_on_word(true);
         params.set_temperature(self.config.temperature);
-        params.set_single_segment(false);
+        match request.profile {
+            DecodeProfile::Accurate => params.set_single_segment(false),
+            DecodeProfile::LowLatency => {
+                params.set_single_segment(true);
+                params.set_no_context(true);
+                params.set_n_max_text_ctx(LOW_LATENCY_MAX_TEXT_CTX);
+                params.set_temperature_inc(0.0);
+                params.set_audio_ctx(low_latency_audio_ctx(
+                    request.audio.samples.len(),
+                    request.audio.sample_rate_hz,
+                ));
+            }
+        }
+        if !prompt_tokens.is_empty() {
+            params.set_tokens(&prompt_tokens);
+        }
+        if let Some(grammar) = &grammar {
+            params.set_grammar(Some(&grammar_elements));
+            params.set_start_rule(grammar.root);
+            params.set_grammar_penalty(GRAMMAR_PENALTY);
+        }
         params.set_print_realtime(false);
         params.set_print_progress(false);
         params.set_print_timestamps(false);
@@ -173,7 +374,15 @@ impl WhisperTranscriptionAdapter {
             DomainError::external_service_error("whisper", &format!("full decode failed: {err}"))
         })?;
 
-        let mut segments = Vec::new();
+        let (detected_language, language_probability) = if decode_language.is_none() {
+            self.detect_language(&state)
+        } else {
+            (None, None)
+        };
+
+        let sample_rate_hz = request.audio.sample_rate_hz;
+        let n_segments = state.full_n_segments().max(0) as usize;
+        let mut segments = Vec::with_capacity(n_segments);
         for idx in 0..state.full_n_segments() {
             let Some(segment) = state.get_segment(idx) else {
                 continue;
@@ -182,7 +391,7 @@ impl WhisperTranscriptionAdapter {
             let end_ms = to_ms_10ms_units(segment.end_timestamp()).unwrap_or(start_ms);
             let text = segment
                 .to_str_lossy()
-                .map(|cow| cow.to_string())
+                .map(|cow| cow.into_owned())
                 .unwrap_or_default();
 
             let n_tokens = segment.n_tokens().max(0) as usize;
@@ -192,67 +401,145 @@ impl WhisperTranscriptionAdapter {
                 1
             };
 
-            let mut raw_tokens = Vec::new();
+            // Each token's end may depend on the next token's start hint, so the
+            // previous token is held back and finalized once its successor is read.
+            let mut tokens = Vec::with_capacity(n_tokens);
+            let mut pending: Option<PendingToken> = None;
+            let mut logprobs = Vec::with_capacity(n_tokens);
             for token_idx in 0..segment.n_tokens().max(0) {
                 let Some(token) = segment.get_token(token_idx) else {
                     continue;
                 };
-                let token_text = token
-                    .to_str_lossy()
-                    .map(|cow| cow.to_string())
-                    .unwrap_or_default();
                 let token_data = token.token_data();
-                raw_tokens.push((
-                    token_text,
-                    token.token_probability(),
-                    token_start_hint_ms(token_data),
-                    token_end_hint_ms(token_data),
-                ));
-            }
+                logprobs.push(token_data.plog);
+                let start_hint_ms = token_start_hint_ms(token_data);
+                if let Some(previous) = pending.take() {
+                    tokens.push(previous.finish(start_hint_ms, end_ms, sample_rate_hz));
+                }
 
-            let mut tokens = Vec::new();
-            for (idx, (text, confidence, start_hint_ms, end_hint_ms)) in
-                raw_tokens.iter().enumerate()
-            {
-                let fallback_start_ms = start_ms.saturating_add(idx as u64 * token_span);
-                let fallback_end_ms = fallback_start_ms.saturating_add(token_span).min(end_ms);
-                let next_start_hint_ms = raw_tokens.get(idx + 1).and_then(|raw| raw.2);
-
-                let token_start_ms = start_hint_ms
-                    .unwrap_or(fallback_start_ms)
-                    .clamp(start_ms, end_ms);
-                let mut token_end_ms = end_hint_ms
-                    .filter(|end| *end > token_start_ms)
-                    .or_else(|| next_start_hint_ms.filter(|next| *next > token_start_ms))
-                    .unwrap_or(fallback_end_ms);
-
-                let min_end = token_start_ms.saturating_add(1);
-                let max_end = end_ms.max(min_end);
-                token_end_ms = token_end_ms.clamp(min_end, max_end);
-
-                tokens.push(TranscriptToken {
-                    text: text.clone(),
-                    start_ms: token_start_ms,
-                    end_ms: token_end_ms,
-                    confidence: *confidence,
+                let fallback_start_ms = start_ms.saturating_add(tokens.len() as u64 * token_span);
+                pending = Some(PendingToken {
+                    text: token
+                        .to_str_lossy()
+                        .map(|cow| cow.into_owned())
+                        .unwrap_or_default(),
+                    confidence: token.token_probability(),
+                    start_ms: start_hint_ms
+                        .unwrap_or(fallback_start_ms)
+                        .clamp(start_ms, end_ms),
+                    end_hint_ms: token_end_hint_ms(token_data),
+                    fallback_end_ms: fallback_start_ms.saturating_add(token_span).min(end_ms),
                 });
             }
+            if let Some(last) = pending.take() {
+                tokens.push(last.finish(None, end_ms, sample_rate_hz));
+            }
 
             segments.push(TranscriptSegment {
                 text,
                 start_ms,
                 end_ms,
                 tokens,
+                speaker: None,
+                language: None,
+                no_speech_prob: Some(segment.no_speech_probability()),
+                avg_logprob: mean(&logprobs),
             });
         }
 
         Ok(TranscriptionOutput {
             transcript: Transcript {
-                language: request
-                    .language_hint
+                language: detected_language
+                    .clone()
+                    .or(request.language_hint)
                     .unwrap_or(asr_domain::LanguageTag::Auto),
                 segments,
             },
+            detected_language,
+            language_probability,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending(start_ms: u64, end_hint_ms: Option<u64>, fallback_end_ms: u64) -> PendingToken {
+        PendingToken {
+            text: "tok".to_string(),
+            confidence: 0.5,
+            start_ms,
+            end_hint_ms,
+            fallback_end_ms,
+        }
+    }
+
+    #[test]
+    fn finish_prefers_own_end_hint() {
+        let token = pending(100, Some(180), 150).finish(Some(200), 1_000, 16_000);
+        assert_eq!((token.start_ms, token.end_ms), (100, 180));
+    }
+
+    #[test]
+    fn finish_falls_back_to_next_start_hint() {
+        let token = pending(100, None, 150).finish(Some(220), 1_000, 16_000);
+        assert_eq!(token.end_ms, 220);
+    }
+
+    #[test]
+    fn finish_uses_fallback_and_keeps_token_non_empty() {
+        let token = pending(100, Some(90), 100).finish(None, 1_000, 16_000);
+        assert_eq!(token.end_ms, 101);
+    }
+
+    #[test]
+    fn finish_clamps_to_segment_end() {
+        let token = pending(100, Some(5_000), 150).finish(None, 400, 16_000);
+        assert_eq!(token.end_ms, 400);
+    }
+
+    #[test]
+    fn finish_reports_sample_indices_at_the_input_rate() {
+        let token = pending(100, Some(180), 150).finish(None, 1_000, 16_000);
+        assert_eq!((token.start_sample, token.end_sample), (Some(1_600), Some(2_880)));
+    }
+
+    #[test]
+    fn low_latency_window_covers_the_clip_up_to_thirty_seconds() {
+        assert_eq!(low_latency_audio_ctx(16_000, 16_000), 50 + 64);
+        assert_eq!(low_latency_audio_ctx(24_000, 16_000), 75 + 64);
+        assert_eq!(low_latency_audio_ctx(16_000 * 60, 16_000), 1_500);
+    }
+
+    #[test]
+    fn prompt_keeps_the_tokens_nearest_the_audio() {
+        assert_eq!(prompt_tail(vec![1, 2, 3, 4], 2), vec![3, 4]);
+        assert_eq!(prompt_tail(vec![1, 2], 4), vec![1, 2]);
+    }
+
+    #[test]
+    fn mean_of_no_values_is_unknown() {
+        assert_eq!(mean(&[]), None);
+        assert_eq!(mean(&[-0.5, -1.5]), Some(-1.0));
+    }
+
+    #[test]
+    fn decode_language_is_the_primary_subtag() {
+        let brazil = asr_domain::LanguageTag::parse("pt-BR").expect("valid tag");
+        assert_eq!(resolve_decode_language("fr", Some(&brazil)).as_deref(), Some("pt"));
+        assert_eq!(
+            resolve_decode_language("fr", Some(&asr_domain::LanguageTag::Auto)),
+            None
+        );
+        assert_eq!(resolve_decode_language("en-GB", None).as_deref(), Some("en"));
+        assert_eq!(resolve_decode_language("auto", None), None);
+    }
+
+    #[test]
+    fn language_probability_is_looked_up_by_id() {
+        assert_eq!(language_probability(1, &[0.1, 0.8, 0.1]), Some(0.8));
+        assert_eq!(language_probability(-1, &[0.1, 0.8, 0.1]), None);
+        assert_eq!(language_probability(3, &[0.1, 0.8, 0.1]), None);
+    }
+}
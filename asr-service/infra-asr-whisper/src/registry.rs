@@ -0,0 +1,211 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use asr_domain::{
+    DomainError, LanguageTag, TranscriptionOutput, TranscriptionPort, TranscriptionRequest,
+};
+use async_trait::async_trait;
+use service_health::{DependencyStatus, ReadinessCheck};
+
+use crate::WhisperTranscriptionAdapter;
+
+/// Routes each request to the Whisper model it names, else to the model
+/// configured for its language hint, else to the default model. Every model
+/// keeps its own lazily loaded context.
+pub struct WhisperModelRegistry {
+    default_model: String,
+    models: BTreeMap<String, Arc<WhisperTranscriptionAdapter>>,
+    /// Canonical BCP-47 tag to model name.
+    language_models: BTreeMap<String, String>,
+}
+
+impl WhisperModelRegistry {
+    pub fn new(
+        default_model: impl Into<String>,
+        models: BTreeMap<String, Arc<WhisperTranscriptionAdapter>>,
+    ) -> Result<Self, DomainError> {
+        let default_model = default_model.into();
+        if !models.contains_key(&default_model) {
+            return Err(DomainError::internal_error(&format!(
+                "default whisper model `{default_model}` is not registered"
+            )));
+        }
+        Ok(Self {
+            default_model,
+            models,
+            language_models: BTreeMap::new(),
+        })
+    }
+
+    /// Maps BCP-47 tags (`pt-BR`, `pt`) to registered model names.
+    pub fn with_language_models(
+        mut self,
+        language_models: &BTreeMap<String, String>,
+    ) -> Result<Self, DomainError> {
+        for (tag, model) in language_models {
+            let language = LanguageTag::parse(tag).map_err(|err| {
+                DomainError::internal_error(&format!("language_models: {err}"))
+            })?;
+            if !self.models.contains_key(model) {
+                return Err(DomainError::internal_error(&format!(
+                    "language_models: `{tag}` maps to unregistered whisper model `{model}`"
+                )));
+            }
+            self.language_models.insert(language.to_string(), model.clone());
+        }
+        Ok(self)
+    }
+
+    /// Warms every registered model in turn; see
+    /// [`WhisperTranscriptionAdapter::warm_up`].
+    pub fn warm_up(&self) -> Result<(), DomainError> {
+        for (name, adapter) in &self.models {
+            adapter.warm_up().map_err(|err| {
+                DomainError::internal_error(&format!(
+                    "warm-up of whisper model `{name}` failed: {err}"
+                ))
+            })?;
+        }
+        Ok(())
+    }
+
+    pub fn model_names(&self) -> impl Iterator<Item = &str> {
+        self.models.keys().map(String::as_str)
+    }
+
+    fn select(
+        &self,
+        model: Option<&str>,
+        language: Option<&LanguageTag>,
+    ) -> Result<&WhisperTranscriptionAdapter, DomainError> {
+        let by_language = || {
+            language?
+                .lookup_chain()
+                .iter()
+                .find_map(|tag| self.language_models.get(tag))
+                .map(String::as_str)
+        };
+        let name = model.or_else(by_language).unwrap_or(&self.default_model);
+        self.models.get(name).map(Arc::as_ref).ok_or_else(|| {
+            let available = self.model_names().collect::<Vec<_>>().join(", ");
+            DomainError::invalid_input(&format!(
+                "unknown whisper model `{name}` (available: {available})"
+            ))
+        })
+    }
+}
+
+#[async_trait]
+impl TranscriptionPort for WhisperModelRegistry {
+    async fn transcribe(
+        &self,
+        request: TranscriptionRequest,
+    ) -> Result<TranscriptionOutput, DomainError> {
+        let adapter = self.select(request.model.as_deref(), request.language_hint.as_ref())?;
+        adapter.transcribe(request).await
+    }
+}
+
+#[async_trait]
+impl ReadinessCheck for WhisperModelRegistry {
+    async fn check(&self) -> Vec<DependencyStatus> {
+        let mut statuses = Vec::with_capacity(self.models.len());
+        for (name, adapter) in &self.models {
+            statuses.extend(adapter.check().await.into_iter().map(|mut status| {
+                status.name = format!("whisper-model:{name}");
+                status
+            }));
+        }
+        statuses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WhisperAdapterConfig;
+
+    fn adapter(model_path: &str) -> Arc<WhisperTranscriptionAdapter> {
+        Arc::new(WhisperTranscriptionAdapter::new(WhisperAdapterConfig {
+            model_path: model_path.to_string(),
+            language: "auto".to_string(),
+            temperature: 0.0,
+            threads: 1,
+            dtw_preset: "base".to_string(),
+            dtw_mem_size: 1024 * 1024,
+            prompt_max_tokens: 224,
+        }))
+    }
+
+    fn registry() -> WhisperModelRegistry {
+        let models = BTreeMap::from([
+            ("base".to_string(), adapter("models/ggml-base.bin")),
+            ("tiny".to_string(), adapter("models/ggml-tiny.bin")),
+        ]);
+        WhisperModelRegistry::new("base", models).expect("default is registered")
+    }
+
+    #[test]
+    fn default_model_must_be_registered() {
+        let models = BTreeMap::from([("tiny".to_string(), adapter("models/ggml-tiny.bin"))]);
+        assert!(WhisperModelRegistry::new("base", models).is_err());
+    }
+
+    #[test]
+    fn selects_requested_or_default_model() {
+        let registry = registry();
+        assert_eq!(
+            registry.select(Some("tiny"), None).unwrap().config.model_path,
+            "models/ggml-tiny.bin"
+        );
+        assert_eq!(
+            registry.select(None, None).unwrap().config.model_path,
+            "models/ggml-base.bin"
+        );
+    }
+
+    #[test]
+    fn unknown_model_lists_available_models() {
+        let error = registry()
+            .select(Some("large-v3"), None)
+            .err()
+            .expect("model is not registered");
+        assert!(error.to_string().contains("base, tiny"));
+    }
+
+    #[test]
+    fn language_hint_selects_the_most_specific_model() {
+        let languages = BTreeMap::from([
+            ("pt".to_string(), "tiny".to_string()),
+            ("pt_br".to_string(), "base".to_string()),
+        ]);
+        let registry = registry()
+            .with_language_models(&languages)
+            .expect("models are registered");
+        let model_for = |tag: &str| {
+            let language = LanguageTag::parse(tag).expect("valid tag");
+            registry
+                .select(None, Some(&language))
+                .unwrap()
+                .config
+                .model_path
+                .clone()
+        };
+        assert_eq!(model_for("pt-BR"), "models/ggml-base.bin");
+        assert_eq!(model_for("pt-PT"), "models/ggml-tiny.bin");
+        assert_eq!(
+            registry
+                .select(Some("tiny"), Some(&LanguageTag::parse("pt-BR").unwrap()))
+                .unwrap()
+                .config
+                .model_path,
+            "models/ggml-tiny.bin"
+        );
+    }
+
+    #[test]
+    fn language_models_must_name_registered_models() {
+        let languages = BTreeMap::from([("de".to_string(), "large".to_string())]);
+        assert!(registry().with_language_models(&languages).is_err());
+    }
+}
@@ -1,12 +1,23 @@
 use anyhow::Error;
 use asr_application::{AsrCommandRegistryFactory, AsrUseCase, AsrUseCaseImpl};
-use asr_configuration::AppConfig;
+use asr_configuration::{load_config, AppConfig, AsrRuntimeConfig};
 use asr_domain::TranscriptionPort;
 use asr_grpc_server::serve_grpc;
-use asr_infra_asr_whisper::{WhisperAdapterConfig, WhisperTranscriptionAdapter};
+#[cfg(feature = "http")]
+use asr_http_server::create_app_routes;
+use asr_infra_asr_whisper::{
+    WhisperAdapterConfig, WhisperModelRegistry, WhisperTranscriptionAdapter,
+};
+use model_manager::{ModelArtifact, ModelManager, ModelSource};
 use rustycog_command::GenericCommandService;
 use rustycog_config::ServerConfig;
+#[cfg(feature = "http")]
+use rustycog_http::{AppState, UserIdExtractor};
+use service_admin::{spawn_admin, ConfigReloader};
+use service_health::ReadinessCheck;
+use std::collections::BTreeMap;
 use std::sync::Arc;
+use std::time::Instant;
 
 pub async fn build_and_run(config: AppConfig, server_config: ServerConfig) -> Result<(), Error> {
     let app = Application::new(config).await?;
@@ -16,6 +27,7 @@ pub async fn build_and_run(config: AppConfig, server_config: ServerConfig) -> Re
 pub struct Application {
     pub config: AppConfig,
     pub command_service: Arc<GenericCommandService>,
+    pub readiness: Arc<dyn ReadinessCheck>,
 }
 
 impl Application {
@@ -29,29 +41,19 @@ impl Application {
 
         tracing::info!(
             sample_rate_hz = config.service.audio.sample_rate_hz,
-            model_path = %config.service.asr.model_path,
+            default_model = %config.service.asr.default_model,
             "initializing ASR application"
         );
 
-        let transcription: Arc<dyn TranscriptionPort> =
-            Arc::new(WhisperTranscriptionAdapter::new(WhisperAdapterConfig {
-                model_path: config.service.asr.model_path.clone(),
-                language: config.service.asr.default_language.clone(),
-                temperature: config.service.asr.temperature,
-                threads: config.service.asr.threads,
-                dtw_preset: config.service.asr.dtw_preset.clone(),
-                dtw_mem_size: normalize_dtw_mem_size(config.service.asr.dtw_mem_size),
-            }));
-        let usecase: Arc<dyn AsrUseCase> = Arc::new(AsrUseCaseImpl::new(
-            transcription,
-            config.service.audio.sample_rate_hz,
-        ));
+        let (usecase, readiness) =
+            build_usecase(&config.service.asr, config.service.audio.sample_rate_hz).await?;
         let registry = AsrCommandRegistryFactory::create_registry(usecase);
         let command_service = Arc::new(GenericCommandService::new(Arc::new(registry)));
 
         Ok(Self {
             config,
             command_service,
+            readiness,
         })
     }
 
@@ -61,13 +63,90 @@ impl Application {
             port = server_config.port,
             "starting ASR gRPC server"
         );
+        let readiness = spawn_admin(
+            "asr",
+            self.config.admin.clone(),
+            self.readiness,
+            ConfigReloader::new(&self.config, load_config),
+        );
+
+        #[cfg(feature = "http")]
+        if let Some(http_config) = self.config.http.clone() {
+            tracing::info!(
+                host = %http_config.host,
+                port = http_config.port,
+                "starting ASR HTTP server"
+            );
+            let state = AppState::new(self.command_service.clone(), UserIdExtractor::new());
+            let grpc = serve_grpc(self.command_service, server_config, readiness.clone());
+            let http = create_app_routes(state, http_config, readiness);
+            tokio::try_join!(
+                async { grpc.await.map_err(|err| anyhow::anyhow!("server startup failed: {err}")) },
+                async { http.await.map_err(|err| anyhow::anyhow!("http server failed: {err}")) },
+            )?;
+            return Ok(());
+        }
+        #[cfg(not(feature = "http"))]
+        if self.config.http.is_some() {
+            tracing::warn!("[http] is configured but asr-service was built without `http`");
+        }
 
-        serve_grpc(self.command_service, server_config)
+        serve_grpc(self.command_service, server_config, readiness)
             .await
             .map_err(|err| anyhow::anyhow!("server startup failed: {err}"))
     }
 }
 
+/// Checks and loads the Whisper models of `asr` and wraps them in the ASR use
+/// case, with the registry as readiness check. The orchestrator's `monolith`
+/// build calls this to transcribe in-process.
+pub async fn build_usecase(
+    asr: &AsrRuntimeConfig,
+    sample_rate_hz: u32,
+) -> Result<(Arc<dyn AsrUseCase>, Arc<dyn ReadinessCheck>), Error> {
+    let model_manager = ModelManager::new(asr.download_missing_models);
+    let mut models = BTreeMap::new();
+    for (name, model) in asr.registered_models() {
+        let artifact = ModelArtifact::new(format!("whisper:{name}"), &model.path)
+            .with_sha256(model.sha256.clone())
+            .with_source(model.source.as_deref().map(ModelSource::parse).transpose()?);
+        model_manager
+            .ensure(&artifact)
+            .await
+            .map_err(|err| anyhow::anyhow!("whisper model check failed: {err}"))?;
+        tracing::info!(model = %name, path = %model.path, "registered whisper model");
+
+        let adapter = WhisperTranscriptionAdapter::new(WhisperAdapterConfig {
+            model_path: model.path,
+            language: asr.default_language.clone(),
+            temperature: asr.temperature,
+            threads: asr.threads,
+            dtw_preset: model.dtw_preset,
+            dtw_mem_size: normalize_dtw_mem_size(asr.dtw_mem_size),
+            prompt_max_tokens: asr.prompt_max_tokens,
+        });
+        models.insert(name, Arc::new(adapter));
+    }
+    let whisper = Arc::new(
+        WhisperModelRegistry::new(asr.default_model.clone(), models)
+            .and_then(|registry| registry.with_language_models(&asr.language_models))
+            .map_err(|err| anyhow::anyhow!("whisper model registry failed: {err}"))?,
+    );
+    if asr.warmup_on_start {
+        let started = Instant::now();
+        match whisper.warm_up() {
+            Ok(()) => tracing::info!(
+                elapsed_ms = started.elapsed().as_millis() as u64,
+                "whisper warm-up completed"
+            ),
+            Err(err) => tracing::warn!(error = %err, "whisper warm-up failed"),
+        }
+    }
+    let transcription: Arc<dyn TranscriptionPort> = whisper.clone();
+    let usecase: Arc<dyn AsrUseCase> = Arc::new(AsrUseCaseImpl::new(transcription, sample_rate_hz));
+    Ok((usecase, whisper))
+}
+
 fn normalize_dtw_mem_size(raw: usize) -> usize {
     const ONE_MIB: usize = 1024 * 1024;
     if raw < ONE_MIB {
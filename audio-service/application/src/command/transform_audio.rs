@@ -33,7 +33,7 @@ impl Command for TransformAudioCommand {
     }
 
     fn validate(&self) -> Result<(), CommandError> {
-        if self.request.samples.is_empty() {
+        if self.request.samples.is_empty() && self.request.encoded_audio.is_none() {
             return Err(CommandError::validation(
                 "samples_missing",
                 "samples must contain at least one frame",
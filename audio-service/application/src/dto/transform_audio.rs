@@ -5,7 +5,8 @@ use audio_domain::TransformMetadata;
 
 #[derive(Debug, Clone, Deserialize, Validate)]
 pub struct TransformAudioRequest {
-    #[validate(length(min = 1))]
+    /// Mono samples; empty when `encoded_audio` is set.
+    #[serde(default)]
     pub samples: Vec<f32>,
     #[validate(range(min = 8_000, max = 192_000))]
     pub sample_rate_hz: Option<u32>,
@@ -13,6 +14,20 @@ pub struct TransformAudioRequest {
     pub target_sample_rate_hz: Option<u32>,
     #[validate(length(min = 1, max = 64))]
     pub session_id: Option<String>,
+    /// Channel the mono samples come from; must be below `channels`.
+    #[serde(default)]
+    pub channel: Option<u8>,
+    #[serde(default)]
+    #[validate(range(min = 1, max = 256))]
+    pub channels: Option<u16>,
+    /// WAV file replacing `samples`; its header sets `sample_rate_hz` and
+    /// the channels are averaged down to mono.
+    #[serde(default)]
+    pub encoded_audio: Option<Vec<u8>>,
+    /// Transform ops run in order; empty runs `clamp` then `resample`.
+    #[serde(default)]
+    #[validate(length(max = 16))]
+    pub ops: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -21,4 +36,6 @@ pub struct TransformAudioResponse {
     pub samples: Vec<f32>,
     pub sample_rate_hz: u32,
     pub metadata: TransformMetadata,
+    pub channel: u8,
+    pub channels: u16,
 }
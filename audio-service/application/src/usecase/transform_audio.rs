@@ -3,7 +3,8 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use uuid::Uuid;
 
-use audio_domain::{AudioTransformPort, AudioTransformRequest};
+use audio_domain::{AudioTransformPort, AudioTransformRequest, DEFAULT_TRANSFORM_OPS};
+use wav_io::decode_wav;
 
 use crate::{ApplicationError, TransformAudioRequest, TransformAudioResponse};
 
@@ -33,8 +34,28 @@ impl TransformAudioUseCaseImpl {
 impl TransformAudioUseCase for TransformAudioUseCaseImpl {
     async fn transform_audio(
         &self,
-        request: TransformAudioRequest,
+        mut request: TransformAudioRequest,
     ) -> Result<TransformAudioResponse, ApplicationError> {
+        let detected = match request.encoded_audio.take() {
+            Some(bytes) => {
+                if !request.samples.is_empty() {
+                    return Err(ApplicationError::Validation(
+                        "send either samples or encoded_audio, not both".to_string(),
+                    ));
+                }
+                let wav = decode_wav(&bytes)
+                    .map_err(|err| ApplicationError::Validation(format!("encoded_audio: {err}")))?;
+                if wav.samples.is_empty() {
+                    return Err(ApplicationError::Validation(
+                        "encoded_audio holds no samples".to_string(),
+                    ));
+                }
+                request.samples = wav.samples;
+                request.sample_rate_hz = Some(wav.sample_rate_hz);
+                Some((wav.sample_rate_hz, wav.channels))
+            }
+            None => None,
+        };
         let source_sample_rate_hz = request.sample_rate_hz.unwrap_or(self.default_sample_rate_hz);
         let target_sample_rate_hz = request
             .target_sample_rate_hz
@@ -42,23 +63,51 @@ impl TransformAudioUseCase for TransformAudioUseCaseImpl {
         let session_id = request
             .session_id
             .unwrap_or_else(|| Uuid::new_v4().to_string());
+        let channel = request.channel.unwrap_or(0);
+        let channels = request.channels.unwrap_or(1);
+        if u16::from(channel) >= channels {
+            return Err(ApplicationError::Validation(format!(
+                "channel {channel} is out of range for {channels} channel(s)"
+            )));
+        }
+
+        let ops = if request.ops.is_empty() {
+            DEFAULT_TRANSFORM_OPS.iter().map(|op| op.to_string()).collect()
+        } else {
+            request.ops
+        };
+        let available = self.transformer.transform_names();
+        if let Some(unknown) = ops.iter().find(|op| !available.contains(op)) {
+            return Err(ApplicationError::Validation(format!(
+                "unknown transform `{unknown}` (available: {})",
+                available.join(", ")
+            )));
+        }
 
         tracing::debug!(
             session_id = %session_id,
             input_samples = request.samples.len(),
             source_sample_rate_hz,
             target_sample_rate_hz,
+            channel,
+            channels,
+            ops = %ops.join(","),
             "starting audio transformation"
         );
 
-        let transformed = self
+        let mut transformed = self
             .transformer
             .transform(AudioTransformRequest {
                 samples: request.samples,
                 source_sample_rate_hz,
                 target_sample_rate_hz,
+                ops,
             })
             .await?;
+        if let Some((sample_rate_hz, channels)) = detected {
+            transformed.metadata.detected_sample_rate_hz = Some(sample_rate_hz);
+            transformed.metadata.detected_channels = Some(channels);
+        }
 
         tracing::debug!(
             session_id = %session_id,
@@ -73,6 +122,8 @@ impl TransformAudioUseCase for TransformAudioUseCaseImpl {
             samples: transformed.samples,
             sample_rate_hz: transformed.sample_rate_hz,
             metadata: transformed.metadata,
+            channel,
+            channels,
         })
     }
 }
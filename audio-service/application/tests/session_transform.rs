@@ -21,6 +21,10 @@ async fn transform_command_flow_produces_resampled_audio() {
             sample_rate_hz: Some(48_000),
             target_sample_rate_hz: Some(16_000),
             session_id: Some("it-session".to_string()),
+            channel: None,
+            channels: None,
+            encoded_audio: None,
+            ops: Vec::new(),
         }))
         .await
         .expect("command succeeds");
@@ -29,4 +33,84 @@ async fn transform_command_flow_produces_resampled_audio() {
     assert_eq!(response.sample_rate_hz, 16_000);
     assert!(response.metadata.resampled);
     assert!(response.samples.len() < 480);
+    assert_eq!((response.channel, response.channels), (0, 1));
+    assert_eq!(response.metadata.applied_ops, vec!["clamp", "resample"]);
+}
+
+#[tokio::test]
+async fn transform_command_rejects_channel_outside_the_recording() {
+    let transformer: Arc<dyn AudioTransformPort> = Arc::new(AudioTransformerAdapter::new());
+    let usecase = TransformAudioUseCaseImpl::new(transformer, 16_000);
+
+    let error = usecase
+        .transform_audio(TransformAudioRequest {
+            samples: vec![0.0; 160],
+            sample_rate_hz: Some(16_000),
+            target_sample_rate_hz: None,
+            session_id: None,
+            channel: Some(2),
+            channels: Some(2),
+            encoded_audio: None,
+            ops: Vec::new(),
+        })
+        .await
+        .expect_err("channel 2 of a stereo recording does not exist");
+
+    assert!(error.to_string().contains("out of range"), "{error}");
+}
+
+#[tokio::test]
+async fn wav_input_takes_rate_and_channels_from_its_header() {
+    let transformer: Arc<dyn AudioTransformPort> = Arc::new(AudioTransformerAdapter::new());
+    let usecase = TransformAudioUseCaseImpl::new(transformer, 16_000);
+    let wav = test_audio::wav_bytes(&test_audio::tone(440.0, 22_050, 100), 22_050);
+
+    let response = usecase
+        .transform_audio(TransformAudioRequest {
+            samples: Vec::new(),
+            sample_rate_hz: None,
+            target_sample_rate_hz: Some(16_000),
+            session_id: None,
+            channel: None,
+            channels: None,
+            encoded_audio: Some(wav),
+            ops: Vec::new(),
+        })
+        .await
+        .expect("wav decodes");
+
+    assert_eq!(response.metadata.source_sample_rate_hz, 22_050);
+    assert_eq!(response.metadata.input_sample_count, 2_205);
+    assert_eq!(response.metadata.detected_sample_rate_hz, Some(22_050));
+    assert_eq!(response.metadata.detected_channels, Some(1));
+    assert_eq!(response.sample_rate_hz, 16_000);
+}
+
+#[tokio::test]
+async fn transform_recipe_is_checked_against_registered_transforms() {
+    let transformer: Arc<dyn AudioTransformPort> = Arc::new(AudioTransformerAdapter::new());
+    let usecase = TransformAudioUseCaseImpl::new(transformer, 16_000);
+    let request = |ops: &[&str]| TransformAudioRequest {
+        samples: vec![0.25; 480],
+        sample_rate_hz: Some(48_000),
+        target_sample_rate_hz: Some(16_000),
+        session_id: None,
+        channel: None,
+        channels: None,
+        encoded_audio: None,
+        ops: ops.iter().map(|op| op.to_string()).collect(),
+    };
+
+    let error = usecase
+        .transform_audio(request(&["trim_silence", "reverb"]))
+        .await
+        .expect_err("reverb is not registered");
+    assert!(error.to_string().contains("unknown transform `reverb`"), "{error}");
+
+    let response = usecase
+        .transform_audio(request(&["denoise", "resample"]))
+        .await
+        .expect("registered ops run");
+    assert_eq!(response.metadata.applied_ops, vec!["denoise", "resample"]);
+    assert_eq!(response.sample_rate_hz, 16_000);
 }
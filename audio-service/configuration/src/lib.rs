@@ -15,6 +15,10 @@ pub struct AudioConfig {
     pub server: ServerConfig,
     #[serde(default)]
     pub logging: LoggingConfig,
+    /// Admin API listener (drain, reload, metrics, log level), off when
+    /// unset; bind it to an address the public cannot reach.
+    #[serde(default)]
+    pub admin: Option<ServerConfig>,
     #[serde(default)]
     pub transformations: TransformationsConfig,
 }
@@ -23,8 +27,40 @@ pub struct AudioConfig {
 pub struct TransformationsConfig {
     #[serde(default = "default_sample_rate")]
     pub sample_rate_hz: u32,
+    /// Frame length of chunked streams, see `audio_infra::AudioChunker`.
     #[serde(default = "default_chunk_ms")]
     pub chunk_ms: u32,
+    /// Samples shared by consecutive frames; below `chunk_ms`.
+    #[serde(default)]
+    pub chunk_overlap_ms: u32,
+    #[serde(default)]
+    pub limiter: LimiterConfig,
+    /// Level change of the `gain` transform op, in dB.
+    #[serde(default)]
+    pub gain_db: f32,
+}
+
+/// How samples beyond the ceiling are brought back in range.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LimiterConfig {
+    #[serde(default)]
+    pub mode: LimiterMode,
+    /// Largest magnitude of the output, at most 1.0.
+    #[serde(default = "default_limiter_ceiling")]
+    pub ceiling: f32,
+    /// Level where the soft limiter starts compressing; below `ceiling`.
+    #[serde(default = "default_limiter_threshold")]
+    pub threshold: f32,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LimiterMode {
+    /// Clamp at the ceiling.
+    #[default]
+    Hard,
+    /// tanh knee from `threshold` towards the ceiling.
+    Soft,
 }
 
 impl Default for AudioConfig {
@@ -32,6 +68,7 @@ impl Default for AudioConfig {
         Self {
             server: ServerConfig::default(),
             logging: LoggingConfig::default(),
+            admin: None,
             transformations: TransformationsConfig::default(),
         }
     }
@@ -42,6 +79,19 @@ impl Default for TransformationsConfig {
         Self {
             sample_rate_hz: default_sample_rate(),
             chunk_ms: default_chunk_ms(),
+            chunk_overlap_ms: 0,
+            limiter: LimiterConfig::default(),
+            gain_db: 0.0,
+        }
+    }
+}
+
+impl Default for LimiterConfig {
+    fn default() -> Self {
+        Self {
+            mode: LimiterMode::default(),
+            ceiling: default_limiter_ceiling(),
+            threshold: default_limiter_threshold(),
         }
     }
 }
@@ -88,3 +138,11 @@ fn default_chunk_ms() -> u32 {
     500
 }
 
+fn default_limiter_ceiling() -> f32 {
+    1.0
+}
+
+fn default_limiter_threshold() -> f32 {
+    0.8
+}
+
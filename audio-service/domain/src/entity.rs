@@ -1,20 +1,55 @@
 use serde::{Deserialize, Serialize};
 
+/// Transform ops applied when a request names none: the limiter, then the
+/// resampler.
+pub const DEFAULT_TRANSFORM_OPS: [&str; 2] = ["clamp", "resample"];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AudioTransformRequest {
     pub samples: Vec<f32>,
     pub source_sample_rate_hz: u32,
+    /// Output rate when the recipe includes `resample`.
     pub target_sample_rate_hz: u32,
+    /// Names of the transform ops to apply, in order.
+    pub ops: Vec<String>,
+}
+
+/// How samples beyond full scale are brought back in range.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Limiter {
+    /// Clamps to `±ceiling`, squaring off loud transients.
+    Hard { ceiling: f32 },
+    /// Passes levels up to `threshold` unchanged and bends louder ones along
+    /// a tanh knee that approaches `ceiling` without reaching it.
+    Soft { threshold: f32, ceiling: f32 },
+}
+
+impl Default for Limiter {
+    fn default() -> Self {
+        Self::Hard { ceiling: 1.0 }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransformMetadata {
+    /// Whether the limiter changed any sample.
     pub clamped: bool,
     pub resampled: bool,
     pub input_sample_count: usize,
     pub output_sample_count: usize,
     pub source_sample_rate_hz: u32,
     pub target_sample_rate_hz: u32,
+    /// Input level diagnostics, see [`crate::SignalLevels`].
+    pub peak_level: f32,
+    pub rms: f32,
+    pub clipped_sample_count: usize,
+    pub estimated_snr_db: Option<f32>,
+    /// Rate and channel count read from the container header of encoded
+    /// input; `None` for raw samples.
+    pub detected_sample_rate_hz: Option<u32>,
+    pub detected_channels: Option<u16>,
+    /// Transform ops run, in order.
+    pub applied_ops: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
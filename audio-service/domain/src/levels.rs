@@ -0,0 +1,86 @@
+/// Samples at or beyond this magnitude count as clipped.
+pub const CLIP_LEVEL: f32 = 0.999;
+/// Frame length of the SNR estimate.
+const SNR_FRAME_MS: usize = 20;
+/// The SNR estimate needs at least this many frames to tell speech from
+/// the noise floor.
+const SNR_MIN_FRAMES: usize = 10;
+
+/// Level diagnostics of a signal, measured before clamping so clipping in
+/// the upload is visible.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SignalLevels {
+    /// Largest absolute sample value.
+    pub peak_level: f32,
+    pub rms: f32,
+    pub clipped_sample_count: usize,
+    /// Loud frames (90th percentile energy) over the noise floor (10th
+    /// percentile), in dB. `None` for clips under 200 ms or with a silent
+    /// floor.
+    pub estimated_snr_db: Option<f32>,
+}
+
+impl SignalLevels {
+    pub fn measure(samples: &[f32], sample_rate_hz: u32) -> Self {
+        let peak_level = samples.iter().fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+        let rms = mean_square(samples).sqrt();
+        let clipped_sample_count = samples
+            .iter()
+            .filter(|sample| sample.abs() >= CLIP_LEVEL)
+            .count();
+        Self {
+            peak_level,
+            rms,
+            clipped_sample_count,
+            estimated_snr_db: estimate_snr_db(samples, sample_rate_hz),
+        }
+    }
+}
+
+fn mean_square(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    samples.iter().map(|sample| sample * sample).sum::<f32>() / samples.len() as f32
+}
+
+fn estimate_snr_db(samples: &[f32], sample_rate_hz: u32) -> Option<f32> {
+    let frame_len = (sample_rate_hz as usize * SNR_FRAME_MS / 1_000).max(1);
+    let mut energies: Vec<f32> = samples.chunks_exact(frame_len).map(mean_square).collect();
+    if energies.len() < SNR_MIN_FRAMES {
+        return None;
+    }
+    energies.sort_by(f32::total_cmp);
+    let noise = energies[energies.len() / 10];
+    let signal = energies[energies.len() * 9 / 10];
+    (noise > 0.0).then(|| 10.0 * (signal / noise).log10())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn peak_rms_and_clipping_are_measured() {
+        let levels = SignalLevels::measure(&[0.5, -1.5, 1.0, 0.0], 16_000);
+        assert_eq!(levels.peak_level, 1.5);
+        assert_eq!(levels.clipped_sample_count, 2);
+        assert!((levels.rms - (3.5f32 / 4.0).sqrt()).abs() < 1e-6);
+        assert_eq!(levels.estimated_snr_db, None);
+    }
+
+    #[test]
+    fn snr_compares_loud_frames_with_the_noise_floor() {
+        // 1 s: half at amplitude 0.5, half at 0.005, i.e. a 40 dB gap.
+        let samples: Vec<f32> = (0..16_000)
+            .map(|index| {
+                let amplitude = if index < 8_000 { 0.5 } else { 0.005 };
+                amplitude * if index % 2 == 0 { 1.0 } else { -1.0 }
+            })
+            .collect();
+        let snr = SignalLevels::measure(&samples, 16_000)
+            .estimated_snr_db
+            .expect("enough frames");
+        assert!((snr - 40.0).abs() < 0.5, "snr {snr}");
+    }
+}
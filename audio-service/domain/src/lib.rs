@@ -1,6 +1,8 @@
 pub mod entity;
+pub mod levels;
 pub mod port;
 
 pub use entity::*;
+pub use levels::SignalLevels;
 pub use port::*;
 pub use rustycog_core::error::DomainError;
@@ -4,6 +4,9 @@ use crate::{AudioTransformRequest, AudioTransformResult, DomainError};
 
 #[async_trait]
 pub trait AudioTransformPort: Send + Sync {
+    /// Names accepted in [`AudioTransformRequest::ops`].
+    fn transform_names(&self) -> Vec<String>;
+
     async fn transform(
         &self,
         request: AudioTransformRequest,
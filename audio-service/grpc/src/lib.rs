@@ -6,9 +6,13 @@ use std::{
 use anyhow::Context;
 use audio_application::{TransformAudioCommand, TransformAudioRequest, TransformAudioResponse};
 use audio_domain::TransformMetadata;
+use common_domain::ErrorCode;
+use log_context::grpc_request_span;
 use rustycog_command::{CommandContext, CommandError, GenericCommandService};
 use rustycog_config::ServerConfig;
-use tonic::{transport::Server, Request, Response, Status};
+use service_health::{health_router, ReadinessCheck};
+use tonic::{service::Routes, transport::Server, Request, Response, Status};
+use tracing::Instrument;
 
 const MAX_MESSAGE_BYTES: usize = 64 * 1024 * 1024;
 
@@ -22,6 +26,7 @@ pub use pb::audio_service_server::AudioServiceServer;
 pub async fn serve_grpc(
     command_service: Arc<GenericCommandService>,
     server_config: ServerConfig,
+    readiness: Arc<dyn ReadinessCheck>,
 ) -> anyhow::Result<()> {
     let address = resolve_bind_addr(&server_config)?;
     let service = AudioGrpcService { command_service };
@@ -32,12 +37,18 @@ pub async fn serve_grpc(
         "starting audio gRPC server"
     );
 
+    // Health probes are plain HTTP/1.1 GETs served on the gRPC port.
+    let routes = Routes::new(
+        AudioServiceServer::new(service)
+            .max_decoding_message_size(MAX_MESSAGE_BYTES)
+            .max_encoding_message_size(MAX_MESSAGE_BYTES),
+    )
+    .into_axum_router()
+    .merge(health_router(readiness));
+
     Server::builder()
-        .add_service(
-            AudioServiceServer::new(service)
-                .max_decoding_message_size(MAX_MESSAGE_BYTES)
-                .max_encoding_message_size(MAX_MESSAGE_BYTES),
-        )
+        .accept_http1(true)
+        .add_routes(Routes::from(routes))
         .serve(address)
         .await
         .context("audio gRPC server failed")
@@ -54,16 +65,25 @@ impl pb::audio_service_server::AudioService for AudioGrpcService {
         &self,
         request: Request<pb::TransformAudioRequest>,
     ) -> Result<Response<pb::TransformAudioResponse>, Status> {
-        let request = map_transform_request(request.into_inner())?;
-        let command = TransformAudioCommand::new(request);
-        let context = CommandContext::new();
-        let result = self
-            .command_service
-            .execute(command, context)
-            .await
-            .map_err(map_command_error)?;
-
-        Ok(Response::new(map_transform_response(result)))
+        let span = grpc_request_span(
+            "audio.v1.AudioService/TransformAudio",
+            request.metadata(),
+            request.get_ref().session_id.as_deref(),
+        );
+        async move {
+            let request = map_transform_request(request.into_inner())?;
+            let command = TransformAudioCommand::new(request);
+            let context = CommandContext::new();
+            let result = self
+                .command_service
+                .execute(command, context)
+                .await
+                .map_err(map_command_error)?;
+
+            Ok(Response::new(map_transform_response(result)))
+        }
+        .instrument(span)
+        .await
     }
 }
 
@@ -79,7 +99,7 @@ fn resolve_bind_addr(config: &ServerConfig) -> anyhow::Result<SocketAddr> {
 }
 
 fn map_transform_request(request: pb::TransformAudioRequest) -> Result<TransformAudioRequest, Status> {
-    if request.samples.is_empty() {
+    if request.samples.is_empty() && request.encoded_audio.is_none() {
         return Err(Status::invalid_argument(
             "samples must contain at least one frame",
         ));
@@ -88,12 +108,29 @@ fn map_transform_request(request: pb::TransformAudioRequest) -> Result<Transform
     validate_sample_rate(request.sample_rate_hz, "sample_rate_hz")?;
     validate_sample_rate(request.target_sample_rate_hz, "target_sample_rate_hz")?;
     validate_optional_text(&request.session_id, "session_id", 64)?;
+    let channel = request
+        .channel
+        .map(|value| {
+            u8::try_from(value).map_err(|_| Status::invalid_argument("channel must be below 256"))
+        })
+        .transpose()?;
+    let channels = request
+        .channels
+        .map(|value| match u16::try_from(value) {
+            Ok(channels @ 1..=256) => Ok(channels),
+            _ => Err(Status::invalid_argument("channels must be between 1 and 256")),
+        })
+        .transpose()?;
 
     Ok(TransformAudioRequest {
         samples: request.samples,
         sample_rate_hz: request.sample_rate_hz,
         target_sample_rate_hz: request.target_sample_rate_hz,
         session_id: request.session_id,
+        channel,
+        channels,
+        encoded_audio: request.encoded_audio,
+        ops: request.ops,
     })
 }
 
@@ -103,6 +140,8 @@ fn map_transform_response(response: TransformAudioResponse) -> pb::TransformAudi
         samples: response.samples,
         sample_rate_hz: response.sample_rate_hz,
         metadata: Some(map_transform_metadata(response.metadata)),
+        channel: u32::from(response.channel),
+        channels: u32::from(response.channels),
     }
 }
 
@@ -114,6 +153,13 @@ fn map_transform_metadata(metadata: TransformMetadata) -> pb::TransformMetadata
         output_sample_count: metadata.output_sample_count as u64,
         source_sample_rate_hz: metadata.source_sample_rate_hz,
         target_sample_rate_hz: metadata.target_sample_rate_hz,
+        peak_level: metadata.peak_level,
+        rms: metadata.rms,
+        clipped_sample_count: metadata.clipped_sample_count as u64,
+        estimated_snr_db: metadata.estimated_snr_db,
+        detected_sample_rate_hz: metadata.detected_sample_rate_hz,
+        detected_channels: metadata.detected_channels.map(u32::from),
+        applied_ops: metadata.applied_ops,
     }
 }
 
@@ -122,13 +168,18 @@ fn map_command_error(error: CommandError) -> Status {
         CommandError::Validation { .. } => Status::invalid_argument(error.to_string()),
         CommandError::Authentication { .. } => Status::unauthenticated(error.to_string()),
         CommandError::Business { .. } => {
-            let message = error.message().to_ascii_lowercase();
-            if message.contains("not found") {
-                Status::not_found(error.to_string())
-            } else if message.contains("permission") || message.contains("forbidden") {
-                Status::permission_denied(error.to_string())
-            } else {
-                Status::failed_precondition(error.to_string())
+            let message = error.to_string();
+            match ErrorCode::from_code(error.error_code()) {
+                ErrorCode::InvalidInput | ErrorCode::AudioQuality => {
+                    Status::invalid_argument(message)
+                }
+                ErrorCode::NotFound => Status::not_found(message),
+                ErrorCode::AlreadyExists => Status::already_exists(message),
+                ErrorCode::PermissionDenied => Status::permission_denied(message),
+                ErrorCode::FailedPrecondition => Status::failed_precondition(message),
+                ErrorCode::ResourceExhausted => Status::resource_exhausted(message),
+                ErrorCode::Unavailable => Status::unavailable(message),
+                ErrorCode::Internal => Status::internal(message),
             }
         }
         CommandError::Infrastructure { .. }
@@ -174,6 +225,7 @@ mod tests {
     use audio_domain::TransformMetadata;
     use rustycog_command::GenericCommandService;
     use rustycog_config::ServerConfig;
+    use service_health::AlwaysReady;
     use tonic::Request;
 
     use super::{pb, serve_grpc, AudioServiceClient};
@@ -200,7 +252,16 @@ mod tests {
                     output_sample_count: 3,
                     source_sample_rate_hz: request.sample_rate_hz.unwrap_or(16_000),
                     target_sample_rate_hz: request.target_sample_rate_hz.unwrap_or(16_000),
+                    peak_level: 0.33,
+                    rms: 0.24,
+                    clipped_sample_count: 0,
+                    estimated_snr_db: None,
+                    detected_sample_rate_hz: None,
+                    detected_channels: None,
+                    applied_ops: request.ops,
                 },
+                channel: request.channel.unwrap_or(0),
+                channels: request.channels.unwrap_or(1),
             })
         }
     }
@@ -215,22 +276,29 @@ mod tests {
         let registry = AudioCommandRegistryFactory::create_registry(Arc::new(MockAudioUseCase));
         let command_service = Arc::new(GenericCommandService::new(Arc::new(registry)));
 
-        let server = tokio::spawn(async move { serve_grpc(command_service, server_config).await });
+        let server = tokio::spawn(async move {
+            serve_grpc(command_service, server_config, Arc::new(AlwaysReady)).await
+        });
         let endpoint = format!("http://127.0.0.1:{port}");
         let mut client = connect_with_retry(endpoint).await;
 
         let response = client
             .transform_audio(Request::new(pb::TransformAudioRequest {
-                samples: vec![0.1, 0.2, 0.3],
+                samples: test_audio::speech_like(48_000, 100),
                 sample_rate_hz: Some(48_000),
                 target_sample_rate_hz: Some(16_000),
                 session_id: Some("it-session".to_string()),
+                channel: Some(1),
+                channels: Some(2),
+                encoded_audio: None,
+                ops: Vec::new(),
             }))
             .await
             .expect("rpc succeeds")
             .into_inner();
 
         assert_eq!(response.session_id, "it-session");
+        assert_eq!((response.channel, response.channels), (1, 2));
         assert_eq!(response.sample_rate_hz, 16_000);
         assert_eq!(response.samples.len(), 3);
         assert!(response.metadata.expect("metadata").resampled);
@@ -1,19 +1,43 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use audio_domain::{
-    AudioTransformPort, AudioTransformRequest, AudioTransformResult, DomainError, TransformMetadata,
+    AudioTransformPort, AudioTransformRequest, AudioTransformResult, DomainError, Limiter,
+    SignalLevels, TransformMetadata,
 };
 
+use crate::transform::{ClampTransform, TransformRegistry, TransformState};
+
+/// Runs the ops of a request through a [`TransformRegistry`]; new
+/// transforms are plugins registered there, not changes to this adapter.
 #[derive(Default)]
-pub struct AudioTransformerAdapter;
+pub struct AudioTransformerAdapter {
+    registry: TransformRegistry,
+}
 
 impl AudioTransformerAdapter {
+    /// Uses the built-in transforms, see [`TransformRegistry::default`].
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    pub fn with_registry(registry: TransformRegistry) -> Self {
+        Self { registry }
+    }
+
+    /// Replaces the default hard clamp at full scale.
+    pub fn with_limiter(mut self, limiter: Limiter) -> Self {
+        self.registry = self.registry.with_plugin(Arc::new(ClampTransform::new(limiter)));
+        self
     }
 }
 
 #[async_trait]
 impl AudioTransformPort for AudioTransformerAdapter {
+    fn transform_names(&self) -> Vec<String> {
+        self.registry.names()
+    }
+
     async fn transform(
         &self,
         request: AudioTransformRequest,
@@ -25,27 +49,36 @@ impl AudioTransformPort for AudioTransformerAdapter {
         }
 
         let input_sample_count = request.samples.len();
-        let mut samples = request.samples;
-        let clamped = clamp_samples(&mut samples);
-        let should_resample =
-            request.source_sample_rate_hz != request.target_sample_rate_hz && !samples.is_empty();
-
-        if should_resample {
-            samples = resample_linear(
-                &samples,
-                request.source_sample_rate_hz,
-                request.target_sample_rate_hz,
-            );
+        let levels = SignalLevels::measure(&request.samples, request.source_sample_rate_hz);
+        let mut state = TransformState {
+            samples: request.samples,
+            sample_rate_hz: request.source_sample_rate_hz,
+            target_sample_rate_hz: request.target_sample_rate_hz,
+            clamped: false,
+            resampled: false,
+        };
+        for op in &request.ops {
+            let plugin = self.registry.get(op).ok_or_else(|| {
+                DomainError::invalid_input(&format!("unknown transform `{op}`"))
+            })?;
+            plugin.apply(&mut state)?;
         }
 
-        let output_sample_count = samples.len();
+        let output_sample_count = state.samples.len();
         let metadata = TransformMetadata {
-            clamped,
-            resampled: should_resample,
+            clamped: state.clamped,
+            resampled: state.resampled,
             input_sample_count,
             output_sample_count,
             source_sample_rate_hz: request.source_sample_rate_hz,
-            target_sample_rate_hz: request.target_sample_rate_hz,
+            target_sample_rate_hz: state.sample_rate_hz,
+            peak_level: levels.peak_level,
+            rms: levels.rms,
+            clipped_sample_count: levels.clipped_sample_count,
+            estimated_snr_db: levels.estimated_snr_db,
+            detected_sample_rate_hz: None,
+            detected_channels: None,
+            applied_ops: request.ops,
         };
 
         tracing::debug!(
@@ -55,74 +88,31 @@ impl AudioTransformPort for AudioTransformerAdapter {
             output_samples = metadata.output_sample_count,
             clamped = metadata.clamped,
             resampled = metadata.resampled,
+            peak_level = metadata.peak_level,
+            clipped_samples = metadata.clipped_sample_count,
+            ops = %metadata.applied_ops.join(","),
             "audio transformation completed"
         );
 
         Ok(AudioTransformResult {
-            samples,
-            sample_rate_hz: request.target_sample_rate_hz,
+            samples: state.samples,
+            sample_rate_hz: state.sample_rate_hz,
             metadata,
         })
     }
 }
 
-fn clamp_samples(samples: &mut [f32]) -> bool {
-    let mut clamped_any = false;
-    for sample in samples {
-        let clamped = sample.clamp(-1.0, 1.0);
-        if clamped != *sample {
-            clamped_any = true;
-            *sample = clamped;
-        }
-    }
-    clamped_any
-}
-
-fn resample_linear(samples: &[f32], source_rate_hz: u32, target_rate_hz: u32) -> Vec<f32> {
-    if source_rate_hz == target_rate_hz {
-        return samples.to_vec();
-    }
-    if samples.len() <= 1 {
-        return samples.to_vec();
-    }
-
-    let output_len = ((samples.len() as u64 * target_rate_hz as u64) / source_rate_hz as u64)
-        .max(1) as usize;
-    if output_len <= 1 {
-        return vec![samples[0]];
-    }
-
-    let mut output = Vec::with_capacity(output_len);
-    let max_source_idx = samples.len() - 1;
-
-    for out_idx in 0..output_len {
-        let source_pos = out_idx as f64 * source_rate_hz as f64 / target_rate_hz as f64;
-        let left_idx = source_pos.floor() as usize;
-        let right_idx = (left_idx + 1).min(max_source_idx);
-        let frac = (source_pos - left_idx as f64) as f32;
-
-        let left = samples[left_idx];
-        let right = samples[right_idx];
-        output.push(left * (1.0 - frac) + right * frac);
-    }
-
-    output
-}
-
-pub fn pcm16le_bytes_to_f32(samples: &[u8]) -> Vec<f32> {
-    samples
-        .chunks_exact(2)
-        .map(|chunk| {
-            let value = i16::from_le_bytes([chunk[0], chunk[1]]);
-            f32::from(value) / f32::from(i16::MAX)
-        })
-        .collect()
-}
-
 #[cfg(test)]
 mod tests {
     use super::AudioTransformerAdapter;
-    use audio_domain::{AudioTransformPort, AudioTransformRequest};
+    use crate::transform::TransformRegistry;
+    use audio_domain::{
+        AudioTransformPort, AudioTransformRequest, Limiter, DEFAULT_TRANSFORM_OPS,
+    };
+
+    fn default_ops() -> Vec<String> {
+        DEFAULT_TRANSFORM_OPS.iter().map(|op| op.to_string()).collect()
+    }
 
     #[tokio::test]
     async fn transform_clamps_samples() {
@@ -132,15 +122,40 @@ mod tests {
                 samples: vec![-2.0, -1.0, 0.0, 1.0, 2.0],
                 source_sample_rate_hz: 16_000,
                 target_sample_rate_hz: 16_000,
+                ops: default_ops(),
             })
             .await
             .expect("adapter runs");
 
         assert_eq!(result.samples, vec![-1.0, -1.0, 0.0, 1.0, 1.0]);
         assert!(result.metadata.clamped);
+        assert_eq!(result.metadata.peak_level, 2.0);
+        assert_eq!(result.metadata.clipped_sample_count, 4);
         assert!(!result.metadata.resampled);
     }
 
+    #[tokio::test]
+    async fn soft_limiter_compresses_instead_of_clamping() {
+        let adapter = AudioTransformerAdapter::new().with_limiter(Limiter::Soft {
+            threshold: 0.8,
+            ceiling: 1.0,
+        });
+        let result = adapter
+            .transform(AudioTransformRequest {
+                samples: vec![0.5, 1.2, -2.0],
+                source_sample_rate_hz: 16_000,
+                target_sample_rate_hz: 16_000,
+                ops: default_ops(),
+            })
+            .await
+            .expect("adapter runs");
+
+        assert_eq!(result.samples[0], 0.5);
+        assert!(result.samples[1] > 0.8 && result.samples[1] < 1.0);
+        assert!(result.samples[2] < -result.samples[1] && result.samples[2] > -1.0);
+        assert!(result.metadata.clamped);
+    }
+
     #[tokio::test]
     async fn transform_resamples_audio() {
         let adapter = AudioTransformerAdapter::new();
@@ -149,6 +164,7 @@ mod tests {
                 samples: (0..480).map(|i| i as f32 / 480.0).collect(),
                 source_sample_rate_hz: 48_000,
                 target_sample_rate_hz: 16_000,
+                ops: default_ops(),
             })
             .await
             .expect("adapter runs");
@@ -157,4 +173,53 @@ mod tests {
         assert!(result.samples.len() < 480);
         assert!(result.metadata.resampled);
     }
+
+    #[tokio::test]
+    async fn recipe_runs_only_the_listed_ops_in_order() {
+        let adapter = AudioTransformerAdapter::new();
+        let mut samples = vec![0.0; 480];
+        samples.extend(vec![1.5; 480]);
+        let result = adapter
+            .transform(AudioTransformRequest {
+                samples,
+                source_sample_rate_hz: 48_000,
+                target_sample_rate_hz: 16_000,
+                ops: vec!["trim_silence".to_string(), "resample".to_string()],
+            })
+            .await
+            .expect("adapter runs");
+
+        assert_eq!(result.samples, vec![1.5; 160]);
+        assert!(!result.metadata.clamped);
+        assert!(result.metadata.resampled);
+        assert_eq!(result.metadata.applied_ops, vec!["trim_silence", "resample"]);
+
+        let unresampled = adapter
+            .transform(AudioTransformRequest {
+                samples: vec![0.5; 480],
+                source_sample_rate_hz: 48_000,
+                target_sample_rate_hz: 16_000,
+                ops: vec!["clamp".to_string()],
+            })
+            .await
+            .expect("adapter runs");
+        assert_eq!(unresampled.sample_rate_hz, 48_000);
+        assert_eq!(unresampled.metadata.target_sample_rate_hz, 48_000);
+    }
+
+    #[tokio::test]
+    async fn ops_outside_the_registry_are_rejected() {
+        let adapter = AudioTransformerAdapter::with_registry(TransformRegistry::empty());
+        assert!(adapter.transform_names().is_empty());
+        let error = adapter
+            .transform(AudioTransformRequest {
+                samples: vec![0.5; 16],
+                source_sample_rate_hz: 16_000,
+                target_sample_rate_hz: 16_000,
+                ops: default_ops(),
+            })
+            .await
+            .expect_err("clamp is not registered");
+        assert!(error.to_string().contains("unknown transform `clamp`"), "{error}");
+    }
 }
@@ -0,0 +1,167 @@
+use audio_domain::DomainError;
+
+/// Frame layout of [`AudioChunker`]: frames of `frame_len` samples starting
+/// every `hop_len` samples, so consecutive frames share
+/// `frame_len - hop_len` samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkSpec {
+    pub frame_len: usize,
+    pub hop_len: usize,
+}
+
+impl ChunkSpec {
+    /// Frames of `chunk_ms` overlapping by `overlap_ms`, which must be
+    /// shorter than the frame.
+    pub fn from_ms(
+        sample_rate_hz: u32,
+        chunk_ms: u32,
+        overlap_ms: u32,
+    ) -> Result<Self, DomainError> {
+        let samples = |ms: u32| (u64::from(sample_rate_hz) * u64::from(ms) / 1_000) as usize;
+        let frame_len = samples(chunk_ms);
+        if frame_len == 0 {
+            return Err(DomainError::invalid_input("chunk_ms must cover at least one sample"));
+        }
+        let overlap_len = samples(overlap_ms);
+        if overlap_len >= frame_len {
+            return Err(DomainError::invalid_input("chunk overlap must be shorter than chunk_ms"));
+        }
+        Ok(Self {
+            frame_len,
+            hop_len: frame_len - overlap_len,
+        })
+    }
+}
+
+/// One frame cut by [`AudioChunker`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioFrame {
+    /// Index of the first sample in the whole stream.
+    pub start_sample: u64,
+    /// Always `frame_len` samples; the last frame is zero-padded.
+    pub samples: Vec<f32>,
+    /// Samples of `samples` that come from the stream.
+    pub valid_len: usize,
+}
+
+/// Splits a stream fed in arbitrary pieces into fixed frames aligned on
+/// multiples of the hop, the same frames whatever the piece sizes. Only the
+/// current frame is buffered.
+pub struct AudioChunker {
+    spec: ChunkSpec,
+    /// Samples from `buffer_start` on, not yet shifted out by a hop.
+    buffer: Vec<f32>,
+    buffer_start: u64,
+    /// End of the last emitted frame.
+    covered_until: u64,
+}
+
+impl AudioChunker {
+    pub fn new(spec: ChunkSpec) -> Self {
+        Self {
+            spec,
+            buffer: Vec::with_capacity(spec.frame_len),
+            buffer_start: 0,
+            covered_until: 0,
+        }
+    }
+
+    /// Returns the frames `samples` completes.
+    pub fn push(&mut self, samples: &[f32]) -> Vec<AudioFrame> {
+        let mut frames = Vec::new();
+        let mut rest = samples;
+        while !rest.is_empty() {
+            let wanted = self.spec.frame_len - self.buffer.len();
+            let (taken, remaining) = rest.split_at(wanted.min(rest.len()));
+            self.buffer.extend_from_slice(taken);
+            rest = remaining;
+            if self.buffer.len() == self.spec.frame_len {
+                frames.push(self.emit(self.spec.frame_len));
+                self.buffer.drain(..self.spec.hop_len);
+                self.buffer_start += self.spec.hop_len as u64;
+            }
+        }
+        frames
+    }
+
+    /// Flushes the samples no frame has covered yet as a last zero-padded
+    /// frame, then starts over for a new stream.
+    pub fn finish(&mut self) -> Option<AudioFrame> {
+        let buffered_until = self.buffer_start + self.buffer.len() as u64;
+        let frame = (buffered_until > self.covered_until).then(|| {
+            let valid_len = self.buffer.len();
+            self.buffer.resize(self.spec.frame_len, 0.0);
+            self.emit(valid_len)
+        });
+        self.buffer.clear();
+        self.buffer_start = 0;
+        self.covered_until = 0;
+        frame
+    }
+
+    fn emit(&mut self, valid_len: usize) -> AudioFrame {
+        self.covered_until = self.buffer_start + valid_len as u64;
+        AudioFrame {
+            start_sample: self.buffer_start,
+            samples: self.buffer.clone(),
+            valid_len,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frames_of(samples: &[f32], spec: ChunkSpec, piece: usize) -> Vec<AudioFrame> {
+        let mut chunker = AudioChunker::new(spec);
+        let mut frames: Vec<AudioFrame> =
+            samples.chunks(piece).flat_map(|part| chunker.push(part)).collect();
+        frames.extend(chunker.finish());
+        frames
+    }
+
+    #[test]
+    fn spec_converts_milliseconds_and_rejects_full_overlap() {
+        let spec = ChunkSpec::from_ms(16_000, 500, 100).expect("valid");
+        assert_eq!(
+            spec,
+            ChunkSpec {
+                frame_len: 8_000,
+                hop_len: 6_400,
+            }
+        );
+        assert!(ChunkSpec::from_ms(16_000, 0, 0).is_err());
+        assert!(ChunkSpec::from_ms(16_000, 100, 100).is_err());
+    }
+
+    #[test]
+    fn overlapping_frames_do_not_depend_on_piece_size() {
+        let samples: Vec<f32> = (0..11).map(|index| index as f32).collect();
+        let spec = ChunkSpec {
+            frame_len: 4,
+            hop_len: 3,
+        };
+        let frames = frames_of(&samples, spec, samples.len());
+        let starts: Vec<u64> = frames.iter().map(|frame| frame.start_sample).collect();
+        assert_eq!(starts, vec![0, 3, 6, 9]);
+        assert_eq!(frames[1].samples, vec![3.0, 4.0, 5.0, 6.0]);
+        assert_eq!(frames[3].samples, vec![9.0, 10.0, 0.0, 0.0]);
+        assert_eq!(frames[3].valid_len, 2);
+        for piece in [1, 2, 5] {
+            assert_eq!(frames_of(&samples, spec, piece), frames);
+        }
+    }
+
+    #[test]
+    fn nothing_is_flushed_when_the_last_frame_covers_the_stream() {
+        let spec = ChunkSpec {
+            frame_len: 4,
+            hop_len: 2,
+        };
+        let frames = frames_of(&[0.5; 6], spec, 6);
+        assert_eq!(frames.len(), 2);
+        assert!(frames.iter().all(|frame| frame.valid_len == 4));
+        assert!(frames_of(&[], spec, 1).is_empty());
+    }
+}
@@ -1,3 +1,11 @@
 pub mod audio;
+pub mod chunker;
+pub mod ops;
+pub mod transform;
 
 pub use audio::AudioTransformerAdapter;
+pub use chunker::{AudioChunker, AudioFrame, ChunkSpec};
+pub use transform::{
+    ClampTransform, DenoiseTransform, GainTransform, ResampleTransform, TransformPlugin,
+    TransformRegistry, TransformState, TrimSilenceTransform,
+};
@@ -0,0 +1,93 @@
+//! Frame-based transform ops: silence trimming and a noise gate.
+
+/// Frame length of both ops.
+const FRAME_MS: usize = 10;
+/// Frames quieter than this are silence for [`trim_silence`] (-50 dBFS).
+const SILENCE_RMS: f32 = 0.003_162;
+/// Frames within this factor of the noise floor are gated (6 dB).
+const GATE_RATIO: f32 = 2.0;
+/// Gain applied to gated frames (-20 dB).
+const GATE_GAIN: f32 = 0.1;
+
+/// Drops leading and trailing frames below -50 dBFS. All-silent audio is
+/// left as is so later steps still get samples. Returns whether anything was
+/// cut.
+pub fn trim_silence(samples: &mut Vec<f32>, sample_rate_hz: u32) -> bool {
+    let frame_len = frame_len(sample_rate_hz);
+    let loud = |frame: &[f32]| frame_rms(frame) >= SILENCE_RMS;
+    let Some(first) = samples.chunks(frame_len).position(loud) else {
+        return false;
+    };
+    let last = samples.chunks(frame_len).rposition(loud).unwrap_or(first);
+    let start = first * frame_len;
+    let end = ((last + 1) * frame_len).min(samples.len());
+    if start == 0 && end == samples.len() {
+        return false;
+    }
+    samples.truncate(end);
+    samples.drain(..start);
+    true
+}
+
+/// Noise gate: estimates the noise floor as the 10th percentile of the frame
+/// energies and attenuates frames within 6 dB of it by 20 dB. Returns
+/// whether any frame was gated.
+pub fn noise_gate(samples: &mut [f32], sample_rate_hz: u32) -> bool {
+    let frame_len = frame_len(sample_rate_hz);
+    let mut levels: Vec<f32> = samples.chunks(frame_len).map(frame_rms).collect();
+    if levels.len() < 10 {
+        return false;
+    }
+    levels.sort_by(f32::total_cmp);
+    let floor = levels[levels.len() / 10];
+    if floor <= 0.0 {
+        return false;
+    }
+    let mut gated = false;
+    for frame in samples.chunks_mut(frame_len) {
+        if frame_rms(frame) < floor * GATE_RATIO {
+            frame.iter_mut().for_each(|sample| *sample *= GATE_GAIN);
+            gated = true;
+        }
+    }
+    gated
+}
+
+fn frame_len(sample_rate_hz: u32) -> usize {
+    (sample_rate_hz as usize * FRAME_MS / 1_000).max(1)
+}
+
+fn frame_rms(frame: &[f32]) -> f32 {
+    (frame.iter().map(|sample| sample * sample).sum::<f32>() / frame.len().max(1) as f32).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn silence_is_trimmed_on_frame_boundaries() {
+        let mut samples = vec![0.0; 160];
+        samples.extend(vec![0.5; 320]);
+        samples.extend(vec![0.0; 200]);
+        assert!(trim_silence(&mut samples, 16_000));
+        assert_eq!(samples, vec![0.5; 320]);
+
+        let mut silent = vec![0.0; 480];
+        assert!(!trim_silence(&mut silent, 16_000));
+        assert_eq!(silent.len(), 480);
+    }
+
+    #[test]
+    fn gate_attenuates_frames_near_the_noise_floor() {
+        let mut samples: Vec<f32> = (0..3_200)
+            .map(|index| {
+                let level = if (1_600..2_400).contains(&index) { 0.5 } else { 0.01 };
+                if index % 2 == 0 { level } else { -level }
+            })
+            .collect();
+        assert!(noise_gate(&mut samples, 16_000));
+        assert!((samples[0] - 0.001).abs() < 1e-6);
+        assert_eq!(samples[2_000], 0.5);
+    }
+}
@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use audio_domain::{DomainError, Limiter};
+
+use vocal_dsp::{clamp_samples, resample_linear, soft_limit_samples};
+
+use crate::ops::{noise_gate, trim_silence};
+
+/// Audio passed from one transform of a recipe to the next.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransformState {
+    pub samples: Vec<f32>,
+    /// Rate of `samples`.
+    pub sample_rate_hz: u32,
+    /// Rate the request asked for; only `resample` moves to it.
+    pub target_sample_rate_hz: u32,
+    pub clamped: bool,
+    pub resampled: bool,
+}
+
+/// A named transform a request may list in its ops.
+pub trait TransformPlugin: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn apply(&self, state: &mut TransformState) -> Result<(), DomainError>;
+}
+
+/// Transforms by name. Registering a name again replaces the earlier
+/// plugin, which is how the configured limiter and gain take over from the
+/// defaults.
+#[derive(Clone)]
+pub struct TransformRegistry {
+    plugins: HashMap<&'static str, Arc<dyn TransformPlugin>>,
+}
+
+impl TransformRegistry {
+    /// A registry without any transform.
+    pub fn empty() -> Self {
+        Self {
+            plugins: HashMap::new(),
+        }
+    }
+
+    pub fn with_plugin(mut self, plugin: Arc<dyn TransformPlugin>) -> Self {
+        self.plugins.insert(plugin.name(), plugin);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Arc<dyn TransformPlugin>> {
+        self.plugins.get(name)
+    }
+
+    /// Registered names, sorted.
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.plugins.keys().map(|name| name.to_string()).collect();
+        names.sort();
+        names
+    }
+}
+
+/// `clamp` and `resample` with full-scale hard clamping, a unity `gain`,
+/// `trim_silence` and `denoise`.
+impl Default for TransformRegistry {
+    fn default() -> Self {
+        Self::empty()
+            .with_plugin(Arc::new(ClampTransform::new(Limiter::default())))
+            .with_plugin(Arc::new(ResampleTransform))
+            .with_plugin(Arc::new(GainTransform::from_db(0.0)))
+            .with_plugin(Arc::new(TrimSilenceTransform))
+            .with_plugin(Arc::new(DenoiseTransform))
+    }
+}
+
+/// `clamp`: runs the limiter.
+pub struct ClampTransform {
+    limiter: Limiter,
+}
+
+impl ClampTransform {
+    pub fn new(limiter: Limiter) -> Self {
+        Self { limiter }
+    }
+}
+
+impl TransformPlugin for ClampTransform {
+    fn name(&self) -> &'static str {
+        "clamp"
+    }
+
+    fn apply(&self, state: &mut TransformState) -> Result<(), DomainError> {
+        state.clamped |= match self.limiter {
+            Limiter::Hard { ceiling } => clamp_samples(&mut state.samples, ceiling),
+            Limiter::Soft { threshold, ceiling } => {
+                soft_limit_samples(&mut state.samples, threshold, ceiling)
+            }
+        };
+        Ok(())
+    }
+}
+
+/// `resample`: converts to the target rate.
+pub struct ResampleTransform;
+
+impl TransformPlugin for ResampleTransform {
+    fn name(&self) -> &'static str {
+        "resample"
+    }
+
+    fn apply(&self, state: &mut TransformState) -> Result<(), DomainError> {
+        let target_rate_hz = state.target_sample_rate_hz;
+        if state.sample_rate_hz != target_rate_hz && !state.samples.is_empty() {
+            state.samples = resample_linear(&state.samples, state.sample_rate_hz, target_rate_hz);
+            state.resampled = true;
+        }
+        state.sample_rate_hz = target_rate_hz;
+        Ok(())
+    }
+}
+
+/// `gain`: scales every sample by a fixed factor. Nothing is clamped, so
+/// recipes that boost the level list `clamp` after it.
+pub struct GainTransform {
+    factor: f32,
+}
+
+impl GainTransform {
+    pub fn from_db(gain_db: f32) -> Self {
+        Self {
+            factor: 10f32.powf(gain_db / 20.0),
+        }
+    }
+}
+
+impl TransformPlugin for GainTransform {
+    fn name(&self) -> &'static str {
+        "gain"
+    }
+
+    fn apply(&self, state: &mut TransformState) -> Result<(), DomainError> {
+        if self.factor != 1.0 {
+            state.samples.iter_mut().for_each(|sample| *sample *= self.factor);
+        }
+        Ok(())
+    }
+}
+
+/// `trim_silence`: see [`trim_silence`].
+pub struct TrimSilenceTransform;
+
+impl TransformPlugin for TrimSilenceTransform {
+    fn name(&self) -> &'static str {
+        "trim_silence"
+    }
+
+    fn apply(&self, state: &mut TransformState) -> Result<(), DomainError> {
+        trim_silence(&mut state.samples, state.sample_rate_hz);
+        Ok(())
+    }
+}
+
+/// `denoise`: see [`noise_gate`].
+pub struct DenoiseTransform;
+
+impl TransformPlugin for DenoiseTransform {
+    fn name(&self) -> &'static str {
+        "denoise"
+    }
+
+    fn apply(&self, state: &mut TransformState) -> Result<(), DomainError> {
+        noise_gate(&mut state.samples, state.sample_rate_hz);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(samples: Vec<f32>) -> TransformState {
+        TransformState {
+            samples,
+            sample_rate_hz: 16_000,
+            target_sample_rate_hz: 16_000,
+            clamped: false,
+            resampled: false,
+        }
+    }
+
+    #[test]
+    fn default_registry_lists_the_builtin_transforms() {
+        assert_eq!(
+            TransformRegistry::default().names(),
+            vec!["clamp", "denoise", "gain", "resample", "trim_silence"]
+        );
+        assert!(TransformRegistry::empty().get("clamp").is_none());
+    }
+
+    #[test]
+    fn registering_a_name_again_replaces_the_plugin() {
+        let registry = TransformRegistry::default()
+            .with_plugin(Arc::new(GainTransform::from_db(20.0)));
+        assert_eq!(registry.names().len(), 5);
+
+        let mut audio = state(vec![0.01, -0.02]);
+        registry.get("gain").expect("gain").apply(&mut audio).unwrap();
+        assert!((audio.samples[0] - 0.1).abs() < 1e-6);
+        assert!((audio.samples[1] + 0.2).abs() < 1e-6);
+        assert!(!audio.clamped);
+    }
+}
@@ -2,12 +2,18 @@ use anyhow::Error;
 use audio_application::{
     AudioCommandRegistryFactory, TransformAudioUseCase, TransformAudioUseCaseImpl,
 };
-use audio_configuration::AppConfig;
-use audio_domain::AudioTransformPort;
+use audio_configuration::{
+    load_config, AppConfig, LimiterConfig, LimiterMode, TransformationsConfig,
+};
+use audio_domain::{AudioTransformPort, Limiter};
 use audio_grpc_server::serve_grpc;
-use audio_infra::AudioTransformerAdapter;
+use audio_infra::{
+    AudioTransformerAdapter, ChunkSpec, ClampTransform, GainTransform, TransformRegistry,
+};
 use rustycog_command::GenericCommandService;
 use rustycog_config::ServerConfig;
+use service_admin::{spawn_admin, ConfigReloader};
+use service_health::AlwaysReady;
 use std::sync::Arc;
 
 pub async fn build_and_run(config: AppConfig, server_config: ServerConfig) -> Result<(), Error> {
@@ -18,6 +24,9 @@ pub async fn build_and_run(config: AppConfig, server_config: ServerConfig) -> Re
 pub struct Application {
     pub config: AppConfig,
     pub command_service: Arc<GenericCommandService>,
+    /// Frame layout from `transformations.chunk_ms`, shared by everything
+    /// that cuts streams into frames.
+    pub chunk_spec: ChunkSpec,
 }
 
 impl Application {
@@ -25,10 +34,20 @@ impl Application {
         tracing::info!(
             default_sample_rate_hz = config.transformations.sample_rate_hz,
             default_chunk_ms = config.transformations.chunk_ms,
+            chunk_overlap_ms = config.transformations.chunk_overlap_ms,
             "initializing audio transformation application"
         );
 
-        let transformer: Arc<dyn AudioTransformPort> = Arc::new(AudioTransformerAdapter::new());
+        let chunk_spec = ChunkSpec::from_ms(
+            config.transformations.sample_rate_hz,
+            config.transformations.chunk_ms,
+            config.transformations.chunk_overlap_ms,
+        )
+        .map_err(|err| anyhow::anyhow!("invalid transformations chunking: {err}"))?;
+        let registry = build_transform_registry(&config.transformations)?;
+        tracing::info!(transforms = %registry.names().join(","), "registered audio transforms");
+        let transformer: Arc<dyn AudioTransformPort> =
+            Arc::new(AudioTransformerAdapter::with_registry(registry));
         let usecase: Arc<dyn TransformAudioUseCase> = Arc::new(TransformAudioUseCaseImpl::new(
             transformer,
             config.transformations.sample_rate_hz,
@@ -39,6 +58,7 @@ impl Application {
         Ok(Self {
             config,
             command_service,
+            chunk_spec,
         })
     }
 
@@ -49,8 +69,49 @@ impl Application {
             "starting audio gRPC server"
         );
 
-        serve_grpc(self.command_service, server_config)
+        let readiness = spawn_admin(
+            "audio",
+            self.config.admin.clone(),
+            Arc::new(AlwaysReady),
+            ConfigReloader::new(&self.config, load_config),
+        );
+        serve_grpc(self.command_service, server_config, readiness)
             .await
             .map_err(|err| anyhow::anyhow!("server startup failed: {err}"))
     }
 }
+
+/// Built-in transforms, with the clamp and gain taken from the config.
+fn build_transform_registry(config: &TransformationsConfig) -> Result<TransformRegistry, Error> {
+    let limiter = build_limiter(&config.limiter)?;
+    if !config.gain_db.is_finite() {
+        return Err(anyhow::anyhow!(
+            "transformations.gain_db must be a finite number, got {}",
+            config.gain_db
+        ));
+    }
+    Ok(TransformRegistry::default()
+        .with_plugin(Arc::new(ClampTransform::new(limiter)))
+        .with_plugin(Arc::new(GainTransform::from_db(config.gain_db))))
+}
+
+fn build_limiter(config: &LimiterConfig) -> Result<Limiter, Error> {
+    let ceiling = config.ceiling;
+    if ceiling <= 0.0 || ceiling > 1.0 {
+        return Err(anyhow::anyhow!(
+            "transformations.limiter.ceiling must be in (0, 1], got {ceiling}"
+        ));
+    }
+    match config.mode {
+        LimiterMode::Hard => Ok(Limiter::Hard { ceiling }),
+        LimiterMode::Soft => {
+            let threshold = config.threshold;
+            if threshold <= 0.0 || threshold >= ceiling {
+                return Err(anyhow::anyhow!(
+                    "transformations.limiter.threshold must be in (0, ceiling), got {threshold}"
+                ));
+            }
+            Ok(Limiter::Soft { threshold, ceiling })
+        }
+    }
+}
@@ -4,7 +4,11 @@ use audio_setup::build_and_run;
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let config = load_config()?;
-    setup_logging(&config);
+    if config.admin.is_some() {
+        service_admin::install_logging(&config);
+    } else {
+        setup_logging(&config);
+    }
     let server_config = config.server.clone();
     build_and_run(config, server_config).await?;
     Ok(())
@@ -0,0 +1,118 @@
+use asr_grpc_server::{pb, AsrServiceClient};
+use serde_json::{json, Value};
+use tonic::transport::Channel;
+use wav_io::WavAudio;
+
+const MAX_MESSAGE_BYTES: usize = 64 * 1024 * 1024;
+
+/// Which API a run goes through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    /// The orchestrator's `POST /api/asr/transcribe`, i.e. the full pipeline.
+    Http,
+    /// The ASR service's `Transcribe` RPC, i.e. Whisper alone.
+    Grpc,
+}
+
+impl Target {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "http" => Some(Self::Http),
+            "grpc" => Some(Self::Grpc),
+            _ => None,
+        }
+    }
+
+    pub fn default_url(self) -> &'static str {
+        match self {
+            Self::Http => "http://127.0.0.1:8090",
+            Self::Grpc => "http://127.0.0.1:8082",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RequestOptions {
+    pub language: Option<String>,
+    pub model: Option<String>,
+}
+
+#[derive(Clone)]
+pub enum TranscribeClient {
+    Http {
+        client: reqwest::Client,
+        url: String,
+    },
+    Grpc(AsrServiceClient<Channel>),
+}
+
+impl TranscribeClient {
+    pub async fn connect(target: Target, base_url: &str) -> Result<Self, String> {
+        let base_url = base_url.trim_end_matches('/');
+        match target {
+            Target::Http => Ok(Self::Http {
+                client: reqwest::Client::new(),
+                url: format!("{base_url}/api/asr/transcribe"),
+            }),
+            Target::Grpc => {
+                let client = AsrServiceClient::connect(base_url.to_string())
+                    .await
+                    .map_err(|err| format!("cannot connect to {base_url}: {err}"))?
+                    .max_decoding_message_size(MAX_MESSAGE_BYTES)
+                    .max_encoding_message_size(MAX_MESSAGE_BYTES);
+                Ok(Self::Grpc(client))
+            }
+        }
+    }
+
+    /// Transcribes `audio` and returns the plain text.
+    pub async fn transcribe(
+        &self,
+        audio: &WavAudio,
+        options: &RequestOptions,
+    ) -> Result<String, String> {
+        match self {
+            Self::Http { client, url } => {
+                let response = client
+                    .post(url)
+                    .json(&json!({
+                        "samples": audio.samples,
+                        "sample_rate_hz": audio.sample_rate_hz,
+                        "language_hint": options.language,
+                        "model": options.model,
+                    }))
+                    .send()
+                    .await
+                    .map_err(|err| format!("request to {url} failed: {err}"))?;
+                let status = response.status();
+                let body: Value = response
+                    .json()
+                    .await
+                    .map_err(|err| format!("invalid response from {url}: {err}"))?;
+                if !status.is_success() {
+                    return Err(format!("{url} answered {status}: {body}"));
+                }
+                Ok(body["text"].as_str().unwrap_or_default().to_string())
+            }
+            Self::Grpc(client) => {
+                let response = client
+                    .clone()
+                    .transcribe(pb::TranscribeAudioRequest {
+                        samples: audio.samples.clone(),
+                        sample_rate_hz: Some(audio.sample_rate_hz),
+                        language_hint: options.language.clone(),
+                        session_id: None,
+                        model: options.model.clone(),
+                        reference_text: None,
+                        decode_profile: None,
+                        grammar: None,
+                        time_offset_ms: None,
+                        prompt: None,
+                    })
+                    .await
+                    .map_err(|status| format!("Transcribe failed: {}", status.message()))?;
+                Ok(response.into_inner().text)
+            }
+        }
+    }
+}
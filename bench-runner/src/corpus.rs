@@ -0,0 +1,72 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A WAV file and, when a sibling `<stem>.txt` exists, its reference text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorpusEntry {
+    pub audio: PathBuf,
+    pub reference: Option<String>,
+}
+
+impl CorpusEntry {
+    pub fn name(&self) -> String {
+        self.audio
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+}
+
+/// Lists the `.wav` files directly under `dir`, sorted by name.
+pub fn discover(dir: &Path) -> Result<Vec<CorpusEntry>, String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|err| format!("cannot read corpus directory `{}`: {err}", dir.display()))?;
+    let mut audio: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("wav"))
+        })
+        .collect();
+    audio.sort();
+
+    audio
+        .into_iter()
+        .map(|audio| {
+            let reference_path = audio.with_extension("txt");
+            let reference = if reference_path.is_file() {
+                let text = fs::read_to_string(&reference_path).map_err(|err| {
+                    format!("cannot read reference `{}`: {err}", reference_path.display())
+                })?;
+                Some(text.trim().to_string())
+            } else {
+                None
+            };
+            Ok(CorpusEntry { audio, reference })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn references_are_paired_by_stem() {
+        let dir = std::env::temp_dir().join(format!("bench-corpus-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("b.wav"), b"").unwrap();
+        fs::write(dir.join("a.WAV"), b"").unwrap();
+        fs::write(dir.join("a.txt"), "bonjour le monde\n").unwrap();
+        fs::write(dir.join("notes.md"), "ignored").unwrap();
+
+        let corpus = discover(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(corpus.len(), 2);
+        assert_eq!(corpus[0].name(), "a.WAV");
+        assert_eq!(corpus[0].reference.as_deref(), Some("bonjour le monde"));
+        assert_eq!(corpus[1].name(), "b.wav");
+        assert_eq!(corpus[1].reference, None);
+    }
+}
@@ -0,0 +1,237 @@
+mod client;
+mod corpus;
+mod metrics;
+mod report;
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::sync::Arc;
+use std::time::Instant;
+
+use futures::stream::{self, StreamExt};
+use wav_io::read_wav;
+
+use client::{RequestOptions, Target, TranscribeClient};
+use corpus::CorpusEntry;
+use report::{Report, Sample};
+
+const USAGE: &str = "\
+usage: bench-runner <corpus-dir> [options]
+
+Sends every .wav in <corpus-dir> through the transcription API and reports
+real-time factor, latency percentiles and, for files with a sibling .txt
+reference, word error rate.
+
+options:
+  --target=http|grpc   orchestrator HTTP API (default) or the ASR gRPC service
+  --url=URL            endpoint (default http://127.0.0.1:8090, grpc :8082)
+  --concurrency=N      requests in flight (default 1)
+  --repeat=N           passes over the corpus (default 1)
+  --language=TAG       language hint sent with each request
+  --model=NAME         Whisper model name sent with each request
+  --json               print the report as JSON
+  --output=PATH        also write the JSON report to PATH";
+
+struct Options {
+    corpus: PathBuf,
+    target: Target,
+    url: String,
+    concurrency: usize,
+    repeat: usize,
+    request: RequestOptions,
+    json: bool,
+    output: Option<PathBuf>,
+}
+
+impl Options {
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut corpus = None;
+        let mut target = Target::Http;
+        let mut url = None;
+        let mut concurrency = 1;
+        let mut repeat = 1;
+        let mut request = RequestOptions::default();
+        let mut json = false;
+        let mut output = None;
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let Some(option) = arg.strip_prefix("--") else {
+                if corpus.replace(PathBuf::from(&arg)).is_some() {
+                    return Err(format!("unexpected argument `{arg}`"));
+                }
+                continue;
+            };
+            if option == "json" {
+                json = true;
+                continue;
+            }
+            if option == "help" {
+                return Err(USAGE.to_string());
+            }
+            let (key, value) = match option.split_once('=') {
+                Some((key, value)) => (key.to_string(), value.to_string()),
+                None => (
+                    option.to_string(),
+                    args.next()
+                        .ok_or_else(|| format!("missing value for `--{option}`"))?,
+                ),
+            };
+            match key.as_str() {
+                "target" => {
+                    target = Target::parse(&value)
+                        .ok_or_else(|| format!("invalid --target `{value}`"))?;
+                }
+                "url" => url = Some(value),
+                "concurrency" => concurrency = positive(&key, &value)?,
+                "repeat" => repeat = positive(&key, &value)?,
+                "language" => request.language = Some(value),
+                "model" => request.model = Some(value),
+                "output" => output = Some(PathBuf::from(value)),
+                _ => return Err(format!("unknown option `--{key}`\n\n{USAGE}")),
+            }
+        }
+
+        Ok(Self {
+            corpus: corpus.ok_or_else(|| USAGE.to_string())?,
+            url: url.unwrap_or_else(|| target.default_url().to_string()),
+            target,
+            concurrency,
+            repeat,
+            request,
+            json,
+            output,
+        })
+    }
+}
+
+fn positive(key: &str, value: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .ok()
+        .filter(|parsed| *parsed > 0)
+        .ok_or_else(|| format!("invalid --{key} `{value}`"))
+}
+
+#[tokio::main]
+async fn main() {
+    if let Err(error) = run().await {
+        eprintln!("bench-runner: {error}");
+        process::exit(1);
+    }
+}
+
+async fn run() -> Result<(), String> {
+    let options = Options::parse(env::args().skip(1))?;
+    let corpus = corpus::discover(&options.corpus)?;
+    if corpus.is_empty() {
+        return Err(format!("no .wav files in `{}`", options.corpus.display()));
+    }
+    let corpus: Vec<Arc<LoadedEntry>> = corpus
+        .into_iter()
+        .map(LoadedEntry::load)
+        .collect::<Result<_, _>>()?;
+
+    let client = TranscribeClient::connect(options.target, &options.url).await?;
+    eprintln!(
+        "running {} file(s) x{} against {} with concurrency {}",
+        corpus.len(),
+        options.repeat,
+        options.url,
+        options.concurrency
+    );
+
+    let jobs = (0..options.repeat).flat_map(|_| corpus.iter().cloned());
+    let started = Instant::now();
+    let samples: Vec<Sample> = stream::iter(jobs)
+        .map(|entry| {
+            let client = client.clone();
+            let request = options.request.clone();
+            async move { measure(&client, &entry, &request).await }
+        })
+        .buffer_unordered(options.concurrency)
+        .collect()
+        .await;
+    let wall_clock = started.elapsed();
+
+    let target = match options.target {
+        Target::Http => "http",
+        Target::Grpc => "grpc",
+    };
+    let report = Report::new(
+        target.to_string(),
+        options.url.clone(),
+        options.concurrency,
+        wall_clock,
+        samples,
+    );
+    let json = serde_json::to_string_pretty(&report)
+        .map_err(|err| format!("cannot serialize report: {err}"))?;
+    if let Some(path) = &options.output {
+        fs::write(path, &json)
+            .map_err(|err| format!("cannot write `{}`: {err}", path.display()))?;
+    }
+    if options.json {
+        println!("{json}");
+    } else {
+        println!("{}", report.render_text());
+    }
+    Ok(())
+}
+
+/// A corpus entry with its audio decoded up front, so decoding is not timed.
+struct LoadedEntry {
+    entry: CorpusEntry,
+    audio: wav_io::WavAudio,
+}
+
+impl LoadedEntry {
+    fn load(entry: CorpusEntry) -> Result<Arc<Self>, String> {
+        let audio = read_wav(&entry.audio)?;
+        Ok(Arc::new(Self { entry, audio }))
+    }
+}
+
+async fn measure(
+    client: &TranscribeClient,
+    loaded: &LoadedEntry,
+    request: &RequestOptions,
+) -> Sample {
+    let name = loaded.entry.name();
+    let audio_ms = loaded.audio.duration_ms();
+    let started = Instant::now();
+    match client.transcribe(&loaded.audio, request).await {
+        Ok(text) => Sample::success(
+            name,
+            audio_ms,
+            started.elapsed(),
+            loaded.entry.reference.as_deref(),
+            &text,
+        ),
+        Err(error) => Sample::failure(name, audio_ms, started.elapsed(), error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        Options::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn grpc_target_defaults_to_the_asr_port() {
+        let options = parse(&["corpus", "--target", "grpc", "--concurrency=4"]).unwrap();
+        assert_eq!(options.target, Target::Grpc);
+        assert_eq!(options.url, "http://127.0.0.1:8082");
+        assert_eq!(options.concurrency, 4);
+    }
+
+    #[test]
+    fn zero_concurrency_is_rejected() {
+        assert!(parse(&["corpus", "--concurrency=0"]).is_err());
+    }
+}
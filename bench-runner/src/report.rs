@@ -0,0 +1,232 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+use asr_domain::text_metrics::{word_edits, EditCounts};
+
+use crate::metrics::percentile;
+
+/// Outcome of one request.
+#[derive(Debug, Clone, Serialize)]
+pub struct Sample {
+    pub file: String,
+    pub audio_ms: u64,
+    pub latency_ms: f64,
+    /// Processing time over audio duration; below 1.0 is faster than real time.
+    pub rtf: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wer: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip)]
+    pub word_errors: Option<EditCounts>,
+}
+
+impl Sample {
+    pub fn success(
+        file: String,
+        audio_ms: u64,
+        latency: Duration,
+        reference: Option<&str>,
+        hypothesis: &str,
+    ) -> Self {
+        let latency_ms = latency.as_secs_f64() * 1000.0;
+        let word_errors = reference.map(|reference| word_edits(reference, hypothesis));
+        Self {
+            file,
+            audio_ms,
+            latency_ms,
+            rtf: rtf(latency_ms, audio_ms),
+            wer: word_errors.map(|counts| counts.rate()),
+            error: None,
+            word_errors,
+        }
+    }
+
+    pub fn failure(file: String, audio_ms: u64, latency: Duration, error: String) -> Self {
+        let latency_ms = latency.as_secs_f64() * 1000.0;
+        Self {
+            file,
+            audio_ms,
+            latency_ms,
+            rtf: rtf(latency_ms, audio_ms),
+            wer: None,
+            error: Some(error),
+            word_errors: None,
+        }
+    }
+}
+
+fn rtf(latency_ms: f64, audio_ms: u64) -> f64 {
+    if audio_ms == 0 {
+        0.0
+    } else {
+        latency_ms / audio_ms as f64
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Summary {
+    pub requests: usize,
+    pub errors: usize,
+    pub concurrency: usize,
+    pub wall_clock_ms: f64,
+    pub audio_ms: u64,
+    /// Summed latency over summed audio duration of successful requests.
+    pub rtf: f64,
+    /// Audio processed per wall-clock second across all workers.
+    pub throughput_x_realtime: f64,
+    pub latency_p50_ms: Option<f64>,
+    pub latency_p95_ms: Option<f64>,
+    pub latency_max_ms: Option<f64>,
+    /// Corpus-level WER: total edits over total reference words.
+    pub wer: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub target: String,
+    pub url: String,
+    pub summary: Summary,
+    pub samples: Vec<Sample>,
+}
+
+impl Report {
+    pub fn new(
+        target: String,
+        url: String,
+        concurrency: usize,
+        wall_clock: Duration,
+        samples: Vec<Sample>,
+    ) -> Self {
+        let succeeded: Vec<&Sample> = samples
+            .iter()
+            .filter(|sample| sample.error.is_none())
+            .collect();
+        let mut latencies: Vec<f64> = succeeded.iter().map(|sample| sample.latency_ms).collect();
+        latencies.sort_by(f64::total_cmp);
+
+        let audio_ms: u64 = succeeded.iter().map(|sample| sample.audio_ms).sum();
+        let total_latency_ms: f64 = latencies.iter().sum();
+        let wall_clock_ms = wall_clock.as_secs_f64() * 1000.0;
+        let word_errors = succeeded
+            .iter()
+            .filter_map(|sample| sample.word_errors)
+            .reduce(EditCounts::merge);
+
+        let summary = Summary {
+            requests: samples.len(),
+            errors: samples.len() - succeeded.len(),
+            concurrency,
+            wall_clock_ms,
+            audio_ms,
+            rtf: rtf(total_latency_ms, audio_ms),
+            throughput_x_realtime: if wall_clock_ms > 0.0 {
+                audio_ms as f64 / wall_clock_ms
+            } else {
+                0.0
+            },
+            latency_p50_ms: percentile(&latencies, 50.0),
+            latency_p95_ms: percentile(&latencies, 95.0),
+            latency_max_ms: latencies.last().copied(),
+            wer: word_errors.map(|counts| counts.rate()),
+        };
+        Self {
+            target,
+            url,
+            summary,
+            samples,
+        }
+    }
+
+    pub fn render_text(&self) -> String {
+        let summary = &self.summary;
+        let mut lines = vec![format!("{} {}", self.target, self.url)];
+        for sample in &self.samples {
+            lines.push(match &sample.error {
+                Some(error) => format!("  {:<32} FAILED {error}", sample.file),
+                None => format!(
+                    "  {:<32} {:>8.0} ms  rtf {:>6.3}{}",
+                    sample.file,
+                    sample.latency_ms,
+                    sample.rtf,
+                    sample
+                        .wer
+                        .map(|wer| format!("  wer {:>5.1}%", wer * 100.0))
+                        .unwrap_or_default()
+                ),
+            });
+        }
+        lines.push(format!(
+            "requests {} (errors {}), concurrency {}, wall clock {:.0} ms",
+            summary.requests, summary.errors, summary.concurrency, summary.wall_clock_ms
+        ));
+        lines.push(format!(
+            "rtf {:.3}, throughput {:.2}x real time",
+            summary.rtf, summary.throughput_x_realtime
+        ));
+        lines.push(format!(
+            "latency p50 {} / p95 {} / max {}",
+            format_ms(summary.latency_p50_ms),
+            format_ms(summary.latency_p95_ms),
+            format_ms(summary.latency_max_ms)
+        ));
+        if let Some(wer) = summary.wer {
+            lines.push(format!("wer {:.2}%", wer * 100.0));
+        }
+        lines.join("\n")
+    }
+}
+
+fn format_ms(value: Option<f64>) -> String {
+    value
+        .map(|value| format!("{value:.0} ms"))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_excludes_failures_and_pools_wer() {
+        let samples = vec![
+            Sample::success(
+                "a.wav".to_string(),
+                2_000,
+                Duration::from_millis(500),
+                Some("un deux trois quatre"),
+                "un deux trois quatre",
+            ),
+            Sample::success(
+                "b.wav".to_string(),
+                1_000,
+                Duration::from_millis(1_000),
+                Some("cinq six"),
+                "cinq sept",
+            ),
+            Sample::failure(
+                "c.wav".to_string(),
+                1_000,
+                Duration::from_millis(10),
+                "boom".to_string(),
+            ),
+        ];
+
+        let report = Report::new(
+            "grpc".to_string(),
+            "http://localhost".to_string(),
+            2,
+            Duration::from_millis(1_000),
+            samples,
+        );
+        let summary = &report.summary;
+        assert_eq!(summary.requests, 3);
+        assert_eq!(summary.errors, 1);
+        assert_eq!(summary.audio_ms, 3_000);
+        assert!((summary.rtf - 0.5).abs() < 1e-9);
+        assert!((summary.throughput_x_realtime - 3.0).abs() < 1e-9);
+        assert_eq!(summary.latency_p95_ms, Some(1_000.0));
+        assert_eq!(summary.wer, Some(1.0 / 6.0));
+    }
+}
@@ -0,0 +1,284 @@
+use serde::{Deserialize, Serialize};
+
+use crate::LanguageTag;
+
+/// Mono samples. A chunk cut from a multi-channel recording keeps its
+/// position in `channel` and the recording's channel count in `channels`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioChunk {
+    pub sample_rate_hz: u32,
+    pub samples: Vec<f32>,
+    #[serde(default)]
+    pub channel: u8,
+    #[serde(default = "default_channels")]
+    pub channels: u16,
+}
+
+fn default_channels() -> u16 {
+    1
+}
+
+impl AudioChunk {
+    pub fn mono(sample_rate_hz: u32, samples: Vec<f32>) -> Self {
+        Self {
+            sample_rate_hz,
+            samples,
+            channel: 0,
+            channels: 1,
+        }
+    }
+
+    /// Splits interleaved frames into one chunk per channel. Returns `None`
+    /// when `channels` is zero, above 256, or does not divide the sample count.
+    pub fn deinterleave(
+        sample_rate_hz: u32,
+        interleaved: &[f32],
+        channels: u16,
+    ) -> Option<Vec<Self>> {
+        let count = usize::from(channels);
+        if count == 0 || count > 256 || !interleaved.len().is_multiple_of(count) {
+            return None;
+        }
+        Some(
+            (0..count)
+                .map(|channel| Self {
+                    sample_rate_hz,
+                    samples: interleaved
+                        .iter()
+                        .skip(channel)
+                        .step_by(count)
+                        .copied()
+                        .collect(),
+                    channel: channel as u8,
+                    channels,
+                })
+                .collect(),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TranscriptToken {
+    pub text: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub confidence: f32,
+    /// Sample indices into the audio the timings were computed on, for
+    /// cutting without the millisecond rounding of `start_ms`/`end_ms`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_sample: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_sample: Option<u64>,
+    /// Part of a filler or a repeated word, tagged by `tag_disfluencies`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disfluency: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TranscriptSegment {
+    pub text: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub tokens: Vec<TranscriptToken>,
+    /// Diarization label, e.g. `"SPEAKER_00"` or `"agent"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+    /// Language of this segment in code-switched audio; `None` means the
+    /// transcript's language.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<LanguageTag>,
+    /// Whisper's probability that the segment's window holds no speech.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub no_speech_prob: Option<f32>,
+    /// Mean token log-probability of the segment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_logprob: Option<f32>,
+}
+
+impl TranscriptSegment {
+    /// Whisper's own silence heuristic: the segment is probably hallucinated
+    /// when the no-speech probability is above `no_speech_threshold` and the
+    /// decoder was unsure of its text (`avg_logprob` below
+    /// `logprob_threshold`). Typical values are 0.6 and -1.0. Segments
+    /// without scores are kept.
+    pub fn is_probably_silence(&self, no_speech_threshold: f32, logprob_threshold: f32) -> bool {
+        match (self.no_speech_prob, self.avg_logprob) {
+            (Some(no_speech), Some(logprob)) => {
+                no_speech > no_speech_threshold && logprob < logprob_threshold
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WordTiming {
+    pub word: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub confidence: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+    /// See [`TranscriptToken::start_sample`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_sample: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_sample: Option<u64>,
+    /// RMS energy of the word's audio window, when prosody is enabled on the
+    /// aligner.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub energy_rms: Option<f32>,
+    /// Mean pitch of the voiced frames of the word, `None` when unvoiced.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pitch_hz: Option<f32>,
+    /// Aligned further than the aligner's `max_drift_ms` from its segment's
+    /// times and clamped back into that band; the timing is unreliable.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub out_of_band: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SynthesizedWordTiming {
+    pub text: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub fit_strategy: String,
+}
+
+/// Speech synthesized for a transcript and where each word landed in it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TtsOutput {
+    pub samples: Vec<f32>,
+    pub sample_rate_hz: u32,
+    pub word_timings: Vec<SynthesizedWordTiming>,
+}
+
+/// Sample index of `ms` at `sample_rate_hz`, rounded down.
+pub fn ms_to_sample(ms: u64, sample_rate_hz: u32) -> u64 {
+    ms * u64::from(sample_rate_hz) / 1_000
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Transcript {
+    pub language: LanguageTag,
+    pub segments: Vec<TranscriptSegment>,
+}
+
+impl Transcript {
+    pub fn segment_language<'a>(&'a self, segment: &'a TranscriptSegment) -> &'a LanguageTag {
+        segment.language.as_ref().unwrap_or(&self.language)
+    }
+
+    /// Speaker of the segment containing the midpoint of `[start_ms, end_ms]`.
+    pub fn speaker_at(&self, start_ms: u64, end_ms: u64) -> Option<&str> {
+        let midpoint = start_ms + end_ms.saturating_sub(start_ms) / 2;
+        self.segments
+            .iter()
+            .find(|segment| segment.start_ms <= midpoint && midpoint < segment.end_ms)
+            .and_then(|segment| segment.speaker.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(start_ms: u64, end_ms: u64, speaker: Option<&str>) -> TranscriptSegment {
+        TranscriptSegment {
+            text: String::new(),
+            start_ms,
+            end_ms,
+            tokens: Vec::new(),
+            speaker: speaker.map(str::to_string),
+            language: None,
+            no_speech_prob: None,
+            avg_logprob: None,
+        }
+    }
+
+    #[test]
+    fn speaker_at_uses_the_segment_under_the_midpoint() {
+        let transcript = Transcript {
+            language: LanguageTag::fr(),
+            segments: vec![
+                segment(0, 1_000, Some("agent")),
+                segment(1_000, 2_000, Some("customer")),
+                segment(2_000, 3_000, None),
+            ],
+        };
+        assert_eq!(transcript.speaker_at(800, 1_100), Some("agent"));
+        assert_eq!(transcript.speaker_at(900, 1_300), Some("customer"));
+        assert_eq!(transcript.speaker_at(2_100, 2_200), None);
+        assert_eq!(transcript.speaker_at(5_000, 5_100), None);
+    }
+
+    #[test]
+    fn deinterleave_splits_frames_per_channel() {
+        let chunks = AudioChunk::deinterleave(8_000, &[0.1, -0.1, 0.2, -0.2, 0.3, -0.3], 2)
+            .expect("even frame count");
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].samples, vec![0.1, 0.2, 0.3]);
+        assert_eq!(chunks[1].samples, vec![-0.1, -0.2, -0.3]);
+        assert_eq!((chunks[1].channel, chunks[1].channels), (1, 2));
+        assert!(AudioChunk::deinterleave(8_000, &[0.0; 5], 2).is_none());
+        assert!(AudioChunk::deinterleave(8_000, &[0.0; 4], 0).is_none());
+    }
+
+    #[test]
+    fn legacy_audio_json_is_mono() {
+        let chunk: AudioChunk =
+            serde_json::from_str(r#"{"sample_rate_hz":16000,"samples":[0.0]}"#).expect("parse");
+        assert_eq!((chunk.channel, chunk.channels), (0, 1));
+    }
+
+    #[test]
+    fn segment_language_falls_back_to_the_transcript() {
+        let mut english = segment(1_000, 2_000, None);
+        english.language = Some(LanguageTag::en());
+        let transcript = Transcript {
+            language: LanguageTag::fr(),
+            segments: vec![segment(0, 1_000, None), english],
+        };
+        let languages: Vec<_> = transcript
+            .segments
+            .iter()
+            .map(|segment| transcript.segment_language(segment).to_string())
+            .collect();
+        assert_eq!(languages, vec!["fr", "en"]);
+    }
+
+    #[test]
+    fn silence_needs_both_scores_past_their_thresholds() {
+        let mut scored = segment(0, 1_000, None);
+        assert!(!scored.is_probably_silence(0.6, -1.0));
+        scored.no_speech_prob = Some(0.9);
+        scored.avg_logprob = Some(-1.4);
+        assert!(scored.is_probably_silence(0.6, -1.0));
+        scored.avg_logprob = Some(-0.2);
+        assert!(!scored.is_probably_silence(0.6, -1.0));
+    }
+
+    #[test]
+    fn speaker_is_omitted_from_json_when_unset() {
+        let json = serde_json::to_value(segment(0, 10, None)).expect("serialize");
+        assert!(json.get("speaker").is_none());
+        let word: WordTiming =
+            serde_json::from_str(r#"{"word":"a","start_ms":0,"end_ms":1,"confidence":1.0}"#)
+                .expect("legacy payload parses");
+        assert_eq!(word.speaker, None);
+        assert_eq!((word.start_sample, word.end_sample), (None, None));
+    }
+
+    #[test]
+    fn ms_to_sample_scales_by_the_rate() {
+        assert_eq!(ms_to_sample(1_250, 16_000), 20_000);
+        assert_eq!(ms_to_sample(1, 44_100), 44);
+        assert_eq!(ms_to_sample(0, 48_000), 0);
+    }
+}
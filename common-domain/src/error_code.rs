@@ -0,0 +1,111 @@
+//! Stable error codes for business failures. Applications put the code in
+//! `CommandError::business(code, ..)` and transports map it to a gRPC or
+//! HTTP status without inspecting the message.
+
+use std::fmt;
+
+#[cfg(feature = "domain-error")]
+use rustycog_core::error::DomainError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    InvalidInput,
+    /// Input audio failed a quality gate: too short, silent, clipped or at
+    /// an unsupported sample rate.
+    AudioQuality,
+    NotFound,
+    AlreadyExists,
+    PermissionDenied,
+    FailedPrecondition,
+    /// A caller went over its quota, e.g. a tenant's concurrent requests.
+    ResourceExhausted,
+    Unavailable,
+    Internal,
+}
+
+impl ErrorCode {
+    pub const ALL: [Self; 9] = [
+        Self::InvalidInput,
+        Self::AudioQuality,
+        Self::NotFound,
+        Self::AlreadyExists,
+        Self::PermissionDenied,
+        Self::FailedPrecondition,
+        Self::ResourceExhausted,
+        Self::Unavailable,
+        Self::Internal,
+    ];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::InvalidInput => "invalid_input",
+            Self::AudioQuality => "audio_quality",
+            Self::NotFound => "not_found",
+            Self::AlreadyExists => "already_exists",
+            Self::PermissionDenied => "permission_denied",
+            Self::FailedPrecondition => "failed_precondition",
+            Self::ResourceExhausted => "resource_exhausted",
+            Self::Unavailable => "unavailable",
+            Self::Internal => "internal",
+        }
+    }
+
+    /// Inverse of [`ErrorCode::as_str`]. Unknown codes, including the former
+    /// catch-all `domain_error`, read as `FailedPrecondition`.
+    pub fn from_code(code: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|candidate| candidate.as_str() == code)
+            .unwrap_or(Self::FailedPrecondition)
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "domain-error")]
+impl From<&DomainError> for ErrorCode {
+    fn from(error: &DomainError) -> Self {
+        match error {
+            DomainError::InvalidInput { .. } => Self::InvalidInput,
+            DomainError::EntityNotFound { .. } => Self::NotFound,
+            DomainError::ResourceAlreadyExists { .. } => Self::AlreadyExists,
+            DomainError::Unauthorized { .. } => Self::PermissionDenied,
+            DomainError::ExternalServiceError { .. } => Self::Unavailable,
+            DomainError::Internal { .. } => Self::Internal,
+            _ => Self::FailedPrecondition,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_round_trip_through_their_string_form() {
+        for code in ErrorCode::ALL {
+            assert_eq!(ErrorCode::from_code(code.as_str()), code);
+        }
+        assert_eq!(ErrorCode::from_code("domain_error"), ErrorCode::FailedPrecondition);
+    }
+
+    #[cfg(feature = "domain-error")]
+    #[test]
+    fn domain_errors_are_classified_by_variant() {
+        let cases = [
+            (DomainError::invalid_input("no audio"), ErrorCode::InvalidInput),
+            (DomainError::internal_error("not found in cache"), ErrorCode::Internal),
+            (
+                DomainError::external_service_error("whisper", "permission denied"),
+                ErrorCode::Unavailable,
+            ),
+        ];
+        for (error, expected) in cases {
+            assert_eq!(ErrorCode::from(&error), expected, "{error}");
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//! Cheap fingerprint of an audio chunk for spotting repeated uploads of the
+//! same recording.
+
+use crate::AudioChunk;
+
+/// Length of one energy frame.
+const FRAME_MS: usize = 50;
+/// Frame energy changes below this ratio count as flat, so re-encoding noise
+/// does not flip bits.
+const FLAT_RATIO: f32 = 1.1;
+
+impl AudioChunk {
+    /// Hex digest of the energy envelope: one bit per 50 ms frame telling
+    /// whether its RMS rose over the previous frame, hashed with the frame
+    /// count and sample rate. It ignores overall gain, so the same recording
+    /// uploaded at another volume matches; anything else that changes the
+    /// envelope, including trimming, does not.
+    pub fn fingerprint(&self) -> String {
+        let frame_len = (self.sample_rate_hz as usize * FRAME_MS / 1_000).max(1);
+        let energies: Vec<f32> = self
+            .samples
+            .chunks(frame_len)
+            .map(|frame| frame.iter().map(|sample| sample * sample).sum::<f32>())
+            .map(|energy| energy / frame_len as f32)
+            .collect();
+
+        let mut hash = Fnv1a::default();
+        hash.write(&self.sample_rate_hz.to_le_bytes());
+        hash.write(&(energies.len() as u64).to_le_bytes());
+        let mut bits = 0u8;
+        for (index, pair) in energies.windows(2).enumerate() {
+            let rising = pair[1] > pair[0] * FLAT_RATIO;
+            bits = (bits << 1) | u8::from(rising);
+            if index % 8 == 7 {
+                hash.write(&[bits]);
+                bits = 0;
+            }
+        }
+        hash.write(&[bits]);
+        format!("{:016x}", hash.0)
+    }
+}
+
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(samples: Vec<f32>) -> AudioChunk {
+        AudioChunk::mono(16_000, samples)
+    }
+
+    fn recording() -> Vec<f32> {
+        (0..16_000)
+            .map(|index| (index as f32 * 0.05).sin() * (index as f32 / 2_000.0).sin().abs())
+            .collect()
+    }
+
+    #[test]
+    fn gain_changes_keep_the_fingerprint() {
+        let original = chunk(recording());
+        let quieter = chunk(recording().into_iter().map(|sample| sample * 0.5).collect());
+        assert_eq!(original.fingerprint(), quieter.fingerprint());
+        assert_eq!(original.fingerprint().len(), 16);
+    }
+
+    #[test]
+    fn different_recordings_differ() {
+        let original = chunk(recording());
+        let mut reversed = recording();
+        reversed.reverse();
+        assert_ne!(original.fingerprint(), chunk(reversed).fingerprint());
+        assert_ne!(original.fingerprint(), chunk(recording()[..8_000].to_vec()).fingerprint());
+    }
+}
@@ -0,0 +1,308 @@
+//! BCP-47 language tags (RFC 5646), restricted to the language, script,
+//! region and variant subtags that speech models and text normalization
+//! care about.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LanguageTagError {
+    Empty,
+    InvalidSubtag { tag: String, subtag: String },
+}
+
+impl fmt::Display for LanguageTagError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "language tag cannot be empty"),
+            Self::InvalidSubtag { tag, subtag } => {
+                write!(f, "invalid BCP-47 language tag `{tag}`: unexpected subtag `{subtag}`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LanguageTagError {}
+
+/// A validated tag in canonical case: `fr`, `pt-BR`, `zh-Hant-TW`,
+/// `sr-Latn`, `de-CH-1996`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Bcp47Tag {
+    language: String,
+    script: Option<String>,
+    region: Option<String>,
+    variants: Vec<String>,
+}
+
+impl Bcp47Tag {
+    /// Primary language subtag, lowercase (`pt` for `pt-BR`).
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Script subtag in title case (`Hant`).
+    pub fn script(&self) -> Option<&str> {
+        self.script.as_deref()
+    }
+
+    /// Region subtag, uppercase letters or three digits (`BR`, `419`).
+    pub fn region(&self) -> Option<&str> {
+        self.region.as_deref()
+    }
+
+    pub fn variants(&self) -> &[String] {
+        &self.variants
+    }
+}
+
+impl FromStr for Bcp47Tag {
+    type Err = LanguageTagError;
+
+    /// Accepts `_` as a separator (`pt_BR`) and any letter case.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(LanguageTagError::Empty);
+        }
+        let invalid = |subtag: &str| LanguageTagError::InvalidSubtag {
+            tag: value.to_string(),
+            subtag: subtag.to_string(),
+        };
+
+        let mut subtags = value.split(['-', '_']).peekable();
+        let language = subtags.next().unwrap_or_default();
+        let alpha = |subtag: &str| subtag.chars().all(|c| c.is_ascii_alphabetic());
+        if !matches!(language.len(), 2..=3 | 5..=8) || !alpha(language) {
+            return Err(invalid(language));
+        }
+
+        let mut tag = Self {
+            language: language.to_ascii_lowercase(),
+            script: None,
+            region: None,
+            variants: Vec::new(),
+        };
+
+        if let Some(script) = subtags.next_if(|subtag| subtag.len() == 4 && alpha(subtag)) {
+            let (first, rest) = script.split_at(1);
+            tag.script = Some(first.to_ascii_uppercase() + &rest.to_ascii_lowercase());
+        }
+
+        if let Some(region) = subtags.next_if(|subtag| {
+            (subtag.len() == 2 && alpha(subtag))
+                || (subtag.len() == 3 && subtag.chars().all(|c| c.is_ascii_digit()))
+        }) {
+            tag.region = Some(region.to_ascii_uppercase());
+        }
+
+        for variant in subtags {
+            let alphanumeric = variant.chars().all(|c| c.is_ascii_alphanumeric());
+            let valid = alphanumeric
+                && match variant.len() {
+                    5..=8 => true,
+                    4 => variant.starts_with(|c: char| c.is_ascii_digit()),
+                    _ => false,
+                };
+            if !valid {
+                return Err(invalid(variant));
+            }
+            tag.variants.push(variant.to_ascii_lowercase());
+        }
+
+        Ok(tag)
+    }
+}
+
+impl fmt::Display for Bcp47Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.language)?;
+        for subtag in self.script.iter().chain(&self.region).chain(&self.variants) {
+            write!(f, "-{subtag}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Language of a request or transcript: a BCP-47 tag, or `auto` to let the
+/// model detect it.
+///
+/// Serialized as the tag string (`"pt-BR"`, `"auto"`). The former enum
+/// encodings (`"Fr"`, `{"Other": "de"}`) are still accepted.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LanguageTag {
+    Auto,
+    Tag(Bcp47Tag),
+}
+
+impl LanguageTag {
+    pub fn parse(value: &str) -> Result<Self, LanguageTagError> {
+        value.parse()
+    }
+
+    pub fn fr() -> Self {
+        Self::Tag(Bcp47Tag {
+            language: "fr".to_string(),
+            script: None,
+            region: None,
+            variants: Vec::new(),
+        })
+    }
+
+    pub fn en() -> Self {
+        Self::Tag(Bcp47Tag {
+            language: "en".to_string(),
+            script: None,
+            region: None,
+            variants: Vec::new(),
+        })
+    }
+
+    pub fn is_auto(&self) -> bool {
+        matches!(self, Self::Auto)
+    }
+
+    pub fn bcp47(&self) -> Option<&Bcp47Tag> {
+        match self {
+            Self::Auto => None,
+            Self::Tag(tag) => Some(tag),
+        }
+    }
+
+    /// Primary language subtag, which is what Whisper decodes with.
+    pub fn primary_language(&self) -> Option<&str> {
+        self.bcp47().map(Bcp47Tag::language)
+    }
+
+    /// RFC 4647 lookup order, most specific first: `zh-Hant-TW`, `zh-Hant`,
+    /// `zh`. Use it to pick per-language models or rules with a fallback.
+    pub fn lookup_chain(&self) -> Vec<String> {
+        let Some(tag) = self.bcp47() else {
+            return Vec::new();
+        };
+        let full = tag.to_string();
+        let mut chain = vec![full.clone()];
+        let mut current = full.as_str();
+        while let Some((prefix, _)) = current.rsplit_once('-') {
+            chain.push(prefix.to_string());
+            current = prefix;
+        }
+        chain
+    }
+}
+
+impl FromStr for LanguageTag {
+    type Err = LanguageTagError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value.trim().eq_ignore_ascii_case("auto") {
+            return Ok(Self::Auto);
+        }
+        value.parse().map(Self::Tag)
+    }
+}
+
+impl fmt::Display for LanguageTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Auto => f.write_str("auto"),
+            Self::Tag(tag) => tag.fmt(f),
+        }
+    }
+}
+
+impl Serialize for LanguageTag {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for LanguageTag {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Encoded {
+            Tag(String),
+            Legacy {
+                #[serde(rename = "Other")]
+                other: String,
+            },
+        }
+
+        let value = match Encoded::deserialize(deserializer)? {
+            Encoded::Tag(value) | Encoded::Legacy { other: value } => value,
+        };
+        value.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Documented as the serialized tag string rather than the enum.
+#[cfg(feature = "openapi")]
+impl utoipa::PartialSchema for LanguageTag {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        utoipa::openapi::ObjectBuilder::new()
+            .schema_type(utoipa::openapi::schema::Type::String)
+            .description(Some("BCP-47 language tag such as `pt-BR`, or `auto`"))
+            .into()
+    }
+}
+
+#[cfg(feature = "openapi")]
+impl utoipa::ToSchema for LanguageTag {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subtags_are_parsed_and_canonicalized() {
+        let tag: Bcp47Tag = "zh_hant_tw".parse().expect("valid tag");
+        assert_eq!(tag.language(), "zh");
+        assert_eq!(tag.script(), Some("Hant"));
+        assert_eq!(tag.region(), Some("TW"));
+        assert_eq!(tag.to_string(), "zh-Hant-TW");
+
+        let tag: Bcp47Tag = "es-419".parse().expect("numeric region");
+        assert_eq!(tag.region(), Some("419"));
+
+        let tag: Bcp47Tag = "de-CH-1996".parse().expect("variant");
+        assert_eq!(tag.variants(), ["1996".to_string()]);
+    }
+
+    #[test]
+    fn regional_variants_stay_distinct() {
+        let brazil = LanguageTag::parse("pt-BR").expect("valid");
+        let portugal = LanguageTag::parse("PT-pt").expect("valid");
+        assert_ne!(brazil, portugal);
+        assert_eq!(portugal.to_string(), "pt-PT");
+        assert_eq!(brazil.primary_language(), Some("pt"));
+    }
+
+    #[test]
+    fn malformed_tags_are_rejected() {
+        for value in ["", "f", "fr--FR", "fr-ABCDE-x", "en-US-ab", "12"] {
+            assert!(LanguageTag::parse(value).is_err(), "`{value}` should be rejected");
+        }
+    }
+
+    #[test]
+    fn lookup_chain_truncates_from_the_right() {
+        let tag = LanguageTag::parse("zh-Hant-TW").expect("valid");
+        assert_eq!(tag.lookup_chain(), vec!["zh-Hant-TW", "zh-Hant", "zh"]);
+        assert!(LanguageTag::Auto.lookup_chain().is_empty());
+    }
+
+    #[test]
+    fn serde_uses_the_tag_string_and_reads_legacy_values() {
+        let json = serde_json::to_string(&LanguageTag::parse("pt-BR").unwrap()).unwrap();
+        assert_eq!(json, r#""pt-BR""#);
+
+        let legacy: LanguageTag = serde_json::from_str(r#""Fr""#).unwrap();
+        assert_eq!(legacy, LanguageTag::fr());
+        let legacy: LanguageTag = serde_json::from_str(r#"{"Other":"de"}"#).unwrap();
+        assert_eq!(legacy.to_string(), "de");
+        let auto: LanguageTag = serde_json::from_str(r#""Auto""#).unwrap();
+        assert!(auto.is_auto());
+    }
+}
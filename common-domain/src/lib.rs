@@ -0,0 +1,19 @@
+//! Transcript and audio entities shared by every service domain.
+//!
+//! Service domains re-export these types from their own `entity` module, so
+//! `asr_domain::Transcript`, `alignment_domain::Transcript` and
+//! `orchestration_domain::Transcript` are the same type.
+
+pub mod entity;
+pub mod error_code;
+pub mod fingerprint;
+pub mod language;
+pub mod pauses;
+pub mod rover;
+pub mod subtitles;
+pub mod transcript_ops;
+
+pub use entity::*;
+pub use error_code::ErrorCode;
+pub use language::{Bcp47Tag, LanguageTag, LanguageTagError};
+pub use pauses::Pause;
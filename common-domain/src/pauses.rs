@@ -0,0 +1,85 @@
+//! Silent gaps between aligned words, for fluency scoring and subtitle
+//! segmentation.
+
+use serde::{Deserialize, Serialize};
+
+use crate::WordTiming;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Pause {
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+impl Pause {
+    pub fn duration_ms(&self) -> u64 {
+        self.end_ms - self.start_ms
+    }
+
+    /// Gaps longer than `min_gap_ms` between consecutive words, in time
+    /// order. Words are taken by start time; a word overlapping its
+    /// predecessor closes no gap.
+    pub fn between(words: &[WordTiming], min_gap_ms: u64) -> Vec<Pause> {
+        let mut order: Vec<&WordTiming> = words.iter().collect();
+        order.sort_by_key(|word| word.start_ms);
+
+        let mut pauses = Vec::new();
+        let mut spoken_until: Option<u64> = None;
+        for word in order {
+            if let Some(end_ms) = spoken_until {
+                if word.start_ms.saturating_sub(end_ms) > min_gap_ms {
+                    pauses.push(Pause {
+                        start_ms: end_ms,
+                        end_ms: word.start_ms,
+                    });
+                }
+            }
+            spoken_until = Some(spoken_until.map_or(word.end_ms, |end| end.max(word.end_ms)));
+        }
+        pauses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(start_ms: u64, end_ms: u64) -> WordTiming {
+        WordTiming {
+            word: "a".to_string(),
+            start_ms,
+            end_ms,
+            confidence: 1.0,
+            speaker: None,
+            start_sample: None,
+            end_sample: None,
+            energy_rms: None,
+            pitch_hz: None,
+            out_of_band: false,
+        }
+    }
+
+    #[test]
+    fn only_gaps_above_the_threshold_are_pauses() {
+        let words = [word(0, 300), word(350, 600), word(1_100, 1_400), word(1_600, 1_800)];
+        assert_eq!(
+            Pause::between(&words, 200),
+            vec![Pause {
+                start_ms: 600,
+                end_ms: 1_100,
+            }]
+        );
+        assert_eq!(Pause::between(&words, 150).len(), 2);
+    }
+
+    #[test]
+    fn overlapping_words_do_not_open_a_gap() {
+        let words = [word(1_000, 1_200), word(0, 900), word(100, 950)];
+        let pauses = Pause::between(&words, 0);
+        assert_eq!(pauses.len(), 1);
+        assert_eq!(pauses[0].start_ms, 950);
+        assert_eq!(pauses[0].duration_ms(), 50);
+        assert!(Pause::between(&[], 0).is_empty());
+    }
+}
@@ -0,0 +1,358 @@
+//! ROVER (Recognizer Output Voting Error Reduction): merges transcripts of
+//! the same audio from several recognizers by aligning their words and
+//! voting on each position.
+
+use crate::{Transcript, TranscriptSegment, TranscriptToken};
+
+/// Weight of the vote count against the mean confidence of a word.
+const ROVER_ALPHA: f32 = 0.5;
+/// Confidence given to a recognizer that has no word at a position.
+const ROVER_NULL_CONFIDENCE: f32 = 0.7;
+
+#[derive(Debug, Clone)]
+struct Word {
+    text: String,
+    key: String,
+    start_ms: u64,
+    end_ms: u64,
+    confidence: f32,
+}
+
+/// One position of the word transition network: what each hypothesis says
+/// there, and the pivot segment the position belongs to.
+struct Slot {
+    words: Vec<Option<Word>>,
+    segment: usize,
+}
+
+impl Transcript {
+    /// Merges hypotheses with ROVER. Words are aligned against the first
+    /// non-empty hypothesis, which also provides the segments, language and
+    /// speakers; at each position the word with the best mix of votes and
+    /// mean confidence wins, and a position is dropped when most
+    /// recognizers have nothing there. Ties go to the earlier hypothesis.
+    ///
+    /// Words come from tokens when a segment has them (a token starting
+    /// with whitespace starts a word) and from the segment text otherwise.
+    /// Returns `None` when there are no hypotheses.
+    pub fn rover(hypotheses: &[Transcript]) -> Option<Transcript> {
+        if hypotheses.is_empty() {
+            return None;
+        }
+        let pivot = hypotheses
+            .iter()
+            .position(|hypothesis| !hypothesis.segments.is_empty())
+            .unwrap_or(0);
+        let count = hypotheses.len();
+        let base = &hypotheses[pivot];
+
+        let mut slots: Vec<Slot> = Vec::new();
+        for (index, segment) in base.segments.iter().enumerate() {
+            for word in segment_words(segment) {
+                let mut words = vec![None; count];
+                words[pivot] = Some(word);
+                slots.push(Slot {
+                    words,
+                    segment: index,
+                });
+            }
+        }
+        for (index, hypothesis) in hypotheses.iter().enumerate() {
+            if index != pivot {
+                let words = hypothesis.segments.iter().flat_map(segment_words).collect();
+                align_into(&mut slots, index, words, count);
+            }
+        }
+
+        let mut segments: Vec<Vec<Word>> = vec![Vec::new(); base.segments.len()];
+        for slot in slots {
+            if let Some(word) = vote(&slot.words) {
+                segments[slot.segment].push(word);
+            }
+        }
+
+        Some(Transcript {
+            language: base.language.clone(),
+            segments: base
+                .segments
+                .iter()
+                .zip(segments)
+                .filter(|(_, words)| !words.is_empty())
+                .map(|(segment, words)| voted_segment(segment, words))
+                .collect(),
+        })
+    }
+}
+
+fn segment_words(segment: &TranscriptSegment) -> Vec<Word> {
+    if segment.tokens.is_empty() {
+        let texts: Vec<&str> = segment.text.split_whitespace().collect();
+        let span = segment.end_ms.saturating_sub(segment.start_ms);
+        let confidence = segment.avg_logprob.map_or(1.0, f32::exp);
+        return texts
+            .iter()
+            .enumerate()
+            .map(|(index, text)| {
+                let at = |i: usize| segment.start_ms + span * i as u64 / texts.len() as u64;
+                word(text, at(index), at(index + 1), confidence)
+            })
+            .collect();
+    }
+
+    let mut groups: Vec<Vec<&TranscriptToken>> = Vec::new();
+    for token in &segment.tokens {
+        match groups.last_mut() {
+            Some(group) if !token.text.starts_with(char::is_whitespace) => group.push(token),
+            _ => groups.push(vec![token]),
+        }
+    }
+    groups
+        .into_iter()
+        .filter_map(|tokens| {
+            let text: String = tokens.iter().map(|token| token.text.as_str()).collect();
+            let confidence =
+                tokens.iter().map(|token| token.confidence).sum::<f32>() / tokens.len() as f32;
+            let first = tokens.first()?;
+            let last = tokens.last()?;
+            Some(word(text.trim(), first.start_ms, last.end_ms, confidence))
+        })
+        .filter(|word| !word.text.is_empty())
+        .collect()
+}
+
+fn word(text: &str, start_ms: u64, end_ms: u64, confidence: f32) -> Word {
+    Word {
+        text: text.to_string(),
+        key: text
+            .chars()
+            .filter(|character| character.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect(),
+        start_ms,
+        end_ms,
+        confidence,
+    }
+}
+
+/// Aligns `words` of hypothesis `index` against the network by edit
+/// distance. A slot matches at no cost when any hypothesis already has the
+/// same word there; unmatched words become new slots.
+fn align_into(slots: &mut Vec<Slot>, index: usize, words: Vec<Word>, count: usize) {
+    let (rows, cols) = (slots.len(), words.len());
+    let matches = |slot: &Slot, word: &Word| {
+        slot.words
+            .iter()
+            .flatten()
+            .any(|existing| existing.key == word.key)
+    };
+    let mut cost = vec![vec![0usize; cols + 1]; rows + 1];
+    for (row, line) in cost.iter_mut().enumerate() {
+        line[0] = row;
+    }
+    for (col, cell) in cost[0].iter_mut().enumerate() {
+        *cell = col;
+    }
+    for row in 1..=rows {
+        for col in 1..=cols {
+            let substitution = usize::from(!matches(&slots[row - 1], &words[col - 1]));
+            cost[row][col] = (cost[row - 1][col - 1] + substitution)
+                .min(cost[row - 1][col] + 1)
+                .min(cost[row][col - 1] + 1);
+        }
+    }
+
+    let mut words: Vec<Option<Word>> = words.into_iter().map(Some).collect();
+    let mut inserted: Vec<(usize, Word)> = Vec::new();
+    let (mut row, mut col) = (rows, cols);
+    while row > 0 || col > 0 {
+        if row > 0 && col > 0 {
+            let word = words[col - 1].as_ref().expect("unvisited");
+            let substitution = usize::from(!matches(&slots[row - 1], word));
+            if cost[row][col] == cost[row - 1][col - 1] + substitution {
+                slots[row - 1].words[index] = words[col - 1].take();
+                row -= 1;
+                col -= 1;
+                continue;
+            }
+        }
+        if row > 0 && cost[row][col] == cost[row - 1][col] + 1 {
+            row -= 1;
+        } else {
+            inserted.push((row, words[col - 1].take().expect("unvisited")));
+            col -= 1;
+        }
+    }
+
+    // `inserted` runs back to front, so later positions are filled first and
+    // earlier insertion points stay valid.
+    for (position, word) in inserted {
+        let neighbour = position.saturating_sub(1);
+        let segment = slots.get(neighbour).map_or(0, |slot| slot.segment);
+        let mut slot_words = vec![None; count];
+        slot_words[index] = Some(word);
+        slots.insert(
+            position,
+            Slot {
+                words: slot_words,
+                segment,
+            },
+        );
+    }
+}
+
+/// Scores each distinct word as `alpha * votes / n + (1 - alpha) * mean
+/// confidence`, with the absent hypotheses voting for no word at
+/// [`ROVER_NULL_CONFIDENCE`].
+fn vote(words: &[Option<Word>]) -> Option<Word> {
+    let count = words.len() as f32;
+    let score = |votes: usize, confidence: f32| {
+        ROVER_ALPHA * votes as f32 / count + (1.0 - ROVER_ALPHA) * confidence
+    };
+
+    let nulls = words.iter().filter(|word| word.is_none()).count();
+    let mut best: Option<(f32, Word)> = None;
+    for (index, candidate) in words.iter().enumerate() {
+        let Some(candidate) = candidate else {
+            continue;
+        };
+        let seen_before = words[..index]
+            .iter()
+            .flatten()
+            .any(|word| word.key == candidate.key);
+        if seen_before {
+            continue;
+        }
+        let same: Vec<&Word> = words
+            .iter()
+            .flatten()
+            .filter(|word| word.key == candidate.key)
+            .collect();
+        let confidence = same.iter().map(|word| word.confidence).sum::<f32>() / same.len() as f32;
+        let candidate_score = score(same.len(), confidence);
+        if best.as_ref().is_none_or(|(best_score, _)| candidate_score > *best_score) {
+            let most_confident = same.iter().copied().fold(candidate, |best, word| {
+                if word.confidence > best.confidence {
+                    word
+                } else {
+                    best
+                }
+            });
+            let mut chosen = most_confident.clone();
+            chosen.confidence = confidence;
+            best = Some((candidate_score, chosen));
+        }
+    }
+
+    let (best_score, word) = best?;
+    if nulls > 0 && score(nulls, ROVER_NULL_CONFIDENCE) > best_score {
+        return None;
+    }
+    Some(word)
+}
+
+fn voted_segment(segment: &TranscriptSegment, words: Vec<Word>) -> TranscriptSegment {
+    TranscriptSegment {
+        text: words
+            .iter()
+            .map(|word| word.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" "),
+        start_ms: segment.start_ms,
+        end_ms: segment.end_ms,
+        tokens: words
+            .into_iter()
+            .map(|word| TranscriptToken {
+                text: format!(" {}", word.text),
+                start_ms: word.start_ms,
+                end_ms: word.end_ms,
+                confidence: word.confidence,
+                start_sample: None,
+                end_sample: None,
+                disfluency: false,
+            })
+            .collect(),
+        speaker: segment.speaker.clone(),
+        language: segment.language.clone(),
+        no_speech_prob: segment.no_speech_prob,
+        avg_logprob: segment.avg_logprob,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::LanguageTag;
+
+    use super::*;
+
+    fn hypothesis(words: &[(&str, f32)]) -> Transcript {
+        let tokens = words
+            .iter()
+            .enumerate()
+            .map(|(index, (text, confidence))| TranscriptToken {
+                text: format!(" {text}"),
+                start_ms: index as u64 * 100,
+                end_ms: index as u64 * 100 + 100,
+                confidence: *confidence,
+                start_sample: None,
+                end_sample: None,
+                disfluency: false,
+            })
+            .collect();
+        Transcript {
+            language: LanguageTag::en(),
+            segments: vec![TranscriptSegment {
+                text: words.iter().map(|(text, _)| *text).collect::<Vec<_>>().join(" "),
+                start_ms: 0,
+                end_ms: words.len() as u64 * 100,
+                tokens,
+                speaker: None,
+                language: None,
+                no_speech_prob: None,
+                avg_logprob: None,
+            }],
+        }
+    }
+
+    fn text(transcript: &Transcript) -> String {
+        transcript
+            .segments
+            .iter()
+            .map(|segment| segment.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn majority_wins_each_position() {
+        let merged = Transcript::rover(&[
+            hypothesis(&[("the", 0.9), ("cat", 0.5), ("sat", 0.9)]),
+            hypothesis(&[("the", 0.9), ("hat", 0.6), ("sat", 0.8)]),
+            hypothesis(&[("the", 0.8), ("hat", 0.7), ("sat", 0.9)]),
+        ])
+        .expect("hypotheses");
+        assert_eq!(text(&merged), "the hat sat");
+        assert_eq!(merged.segments[0].tokens.len(), 3);
+    }
+
+    #[test]
+    fn confidence_breaks_two_way_disagreements() {
+        let merged = Transcript::rover(&[
+            hypothesis(&[("kube", 0.3), ("control", 0.4)]),
+            hypothesis(&[("kubectl", 0.95)]),
+        ])
+        .expect("hypotheses");
+        assert_eq!(text(&merged), "kubectl");
+    }
+
+    #[test]
+    fn words_only_one_unsure_recognizer_heard_are_dropped() {
+        let merged = Transcript::rover(&[
+            hypothesis(&[("hello", 0.9), ("world", 0.9)]),
+            hypothesis(&[("hello", 0.9), ("uh", 0.2), ("world", 0.9)]),
+            hypothesis(&[("hello", 0.9), ("world", 0.8)]),
+        ])
+        .expect("hypotheses");
+        assert_eq!(text(&merged), "hello world");
+        assert!(Transcript::rover(&[]).is_none());
+    }
+}
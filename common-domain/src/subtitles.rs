@@ -0,0 +1,94 @@
+//! SubRip and WebVTT rendering of transcripts, one cue per segment. Call
+//! [`Transcript::resegment`] first to bound cue length.
+
+use std::fmt::Write;
+
+use crate::Transcript;
+
+impl Transcript {
+    pub fn to_srt(&self) -> String {
+        let mut out = String::new();
+        for (index, (start_ms, end_ms, text)) in self.cues().enumerate() {
+            let _ = write!(
+                out,
+                "{}\n{} --> {}\n{text}\n\n",
+                index + 1,
+                timestamp(start_ms, ','),
+                timestamp(end_ms, ',')
+            );
+        }
+        out
+    }
+
+    pub fn to_webvtt(&self) -> String {
+        let mut out = String::from("WEBVTT\n\n");
+        for (start_ms, end_ms, text) in self.cues() {
+            let _ = write!(
+                out,
+                "{} --> {}\n{text}\n\n",
+                timestamp(start_ms, '.'),
+                timestamp(end_ms, '.')
+            );
+        }
+        out
+    }
+
+    fn cues(&self) -> impl Iterator<Item = (u64, u64, &str)> {
+        self.segments
+            .iter()
+            .map(|segment| (segment.start_ms, segment.end_ms, segment.text.trim()))
+            .filter(|(_, _, text)| !text.is_empty())
+    }
+}
+
+/// `HH:MM:SS,mmm` for SubRip, `HH:MM:SS.mmm` for WebVTT.
+fn timestamp(ms: u64, separator: char) -> String {
+    let hours = ms / 3_600_000;
+    let minutes = ms / 60_000 % 60;
+    let seconds = ms / 1_000 % 60;
+    format!("{hours:02}:{minutes:02}:{seconds:02}{separator}{:03}", ms % 1_000)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{LanguageTag, TranscriptSegment};
+
+    use super::*;
+
+    fn transcript() -> Transcript {
+        let segment = |text: &str, start_ms, end_ms| TranscriptSegment {
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+            tokens: Vec::new(),
+            speaker: None,
+            language: None,
+            no_speech_prob: None,
+            avg_logprob: None,
+        };
+        Transcript {
+            language: LanguageTag::en(),
+            segments: vec![
+                segment(" Hello there.", 0, 1_500),
+                segment("  ", 1_500, 1_600),
+                segment(" General Kenobi.", 3_661_250, 3_663_000),
+            ],
+        }
+    }
+
+    #[test]
+    fn srt_numbers_cues_and_skips_blank_segments() {
+        assert_eq!(
+            transcript().to_srt(),
+            "1\n00:00:00,000 --> 00:00:01,500\nHello there.\n\n\
+             2\n01:01:01,250 --> 01:01:03,000\nGeneral Kenobi.\n\n"
+        );
+    }
+
+    #[test]
+    fn webvtt_has_a_header_and_dotted_milliseconds() {
+        let vtt = transcript().to_webvtt();
+        assert!(vtt.starts_with("WEBVTT\n\n00:00:00.000 --> 00:00:01.500\nHello there.\n"));
+        assert!(vtt.contains("01:01:01.250 --> 01:01:03.000\nGeneral Kenobi."));
+    }
+}
@@ -0,0 +1,315 @@
+//! Transcript editing: merging chunked decodes, splitting segments into
+//! cues, splicing corrected segments back in, and shifting chunk timings
+//! onto the timeline of the whole stream.
+
+use crate::{ms_to_sample, Transcript, TranscriptSegment, TranscriptToken, WordTiming};
+
+impl TranscriptSegment {
+    fn midpoint_ms(&self) -> u64 {
+        self.start_ms + self.end_ms.saturating_sub(self.start_ms) / 2
+    }
+}
+
+impl Transcript {
+    /// Merges transcripts of overlapping audio chunks. Timestamps must already
+    /// be absolute. Where two chunks overlap, the earlier chunk keeps the
+    /// segments before the middle of the overlap and the later chunk keeps the
+    /// rest, so words at chunk edges, which decode worst, are dropped.
+    ///
+    /// The first chunk sets the language. Segments of a chunk in another
+    /// language are tagged with it. Returns `None` when there are no chunks.
+    pub fn merge_chunks(chunks: impl IntoIterator<Item = Transcript>) -> Option<Transcript> {
+        let mut chunks = chunks.into_iter();
+        let mut merged = chunks.next()?;
+        merged.segments.sort_by_key(|segment| segment.start_ms);
+
+        for chunk in chunks {
+            let Some(chunk_start) = chunk.segments.iter().map(|segment| segment.start_ms).min()
+            else {
+                continue;
+            };
+            let cut = merged
+                .segments
+                .iter()
+                .map(|segment| segment.end_ms)
+                .max()
+                .filter(|merged_end| *merged_end > chunk_start)
+                .map(|merged_end| chunk_start + (merged_end - chunk_start) / 2);
+            if let Some(cut) = cut {
+                merged.segments.retain(|segment| segment.midpoint_ms() < cut);
+            }
+
+            let foreign = chunk.language != merged.language;
+            let mut segments: Vec<_> = chunk
+                .segments
+                .into_iter()
+                .filter(|segment| cut.is_none_or(|cut| segment.midpoint_ms() >= cut))
+                .map(|mut segment| {
+                    if foreign && segment.language.is_none() {
+                        segment.language = Some(chunk.language.clone());
+                    }
+                    segment
+                })
+                .collect();
+            segments.sort_by_key(|segment| segment.start_ms);
+            merged.segments.extend(segments);
+        }
+        Some(merged)
+    }
+
+    /// Splits segments longer than `max_cue_ms` or `max_chars` into cues,
+    /// breaking only between words (tokens that start with whitespace).
+    /// Segments without tokens are kept whole because their words carry no
+    /// timestamps.
+    pub fn resegment(&self, max_cue_ms: u64, max_chars: usize) -> Transcript {
+        let segments = self
+            .segments
+            .iter()
+            .flat_map(|segment| {
+                let fits = segment.end_ms.saturating_sub(segment.start_ms) <= max_cue_ms
+                    && segment.text.trim().chars().count() <= max_chars;
+                if fits || segment.tokens.is_empty() {
+                    return vec![segment.clone()];
+                }
+                split_segment(segment, max_cue_ms, max_chars)
+            })
+            .collect();
+        Transcript {
+            language: self.language.clone(),
+            segments,
+        }
+    }
+
+    /// Replaces the segments covered by `corrected` with it. A segment is
+    /// covered when its midpoint falls between the first corrected start and
+    /// the last corrected end.
+    pub fn splice(&mut self, corrected: Vec<TranscriptSegment>) {
+        let Some(start_ms) = corrected.iter().map(|segment| segment.start_ms).min() else {
+            return;
+        };
+        let end_ms = corrected.iter().map(|segment| segment.end_ms).max().unwrap_or(start_ms);
+        self.segments.retain(|segment| {
+            let midpoint = segment.midpoint_ms();
+            midpoint < start_ms || midpoint >= end_ms
+        });
+        self.segments.extend(corrected);
+        self.segments.sort_by_key(|segment| segment.start_ms);
+    }
+
+    /// Moves every timing `offset_ms` later (earlier when negative, stopping
+    /// at 0), e.g. from a chunk's own timeline to the stream's. Sample
+    /// indices move by the same duration at `sample_rate_hz`.
+    pub fn shift(&mut self, offset_ms: i64, sample_rate_hz: u32) {
+        let offset_samples = sample_offset(offset_ms, sample_rate_hz);
+        for segment in &mut self.segments {
+            segment.start_ms = segment.start_ms.saturating_add_signed(offset_ms);
+            segment.end_ms = segment.end_ms.saturating_add_signed(offset_ms);
+            for token in &mut segment.tokens {
+                token.start_ms = token.start_ms.saturating_add_signed(offset_ms);
+                token.end_ms = token.end_ms.saturating_add_signed(offset_ms);
+                token.start_sample = token
+                    .start_sample
+                    .map(|sample| sample.saturating_add_signed(offset_samples));
+                token.end_sample = token
+                    .end_sample
+                    .map(|sample| sample.saturating_add_signed(offset_samples));
+            }
+        }
+    }
+}
+
+impl WordTiming {
+    /// See [`Transcript::shift`].
+    pub fn shift(&mut self, offset_ms: i64, sample_rate_hz: u32) {
+        let offset_samples = sample_offset(offset_ms, sample_rate_hz);
+        self.start_ms = self.start_ms.saturating_add_signed(offset_ms);
+        self.end_ms = self.end_ms.saturating_add_signed(offset_ms);
+        self.start_sample = self
+            .start_sample
+            .map(|sample| sample.saturating_add_signed(offset_samples));
+        self.end_sample = self
+            .end_sample
+            .map(|sample| sample.saturating_add_signed(offset_samples));
+    }
+}
+
+fn sample_offset(offset_ms: i64, sample_rate_hz: u32) -> i64 {
+    let samples = ms_to_sample(offset_ms.unsigned_abs(), sample_rate_hz) as i64;
+    if offset_ms < 0 {
+        -samples
+    } else {
+        samples
+    }
+}
+
+fn split_segment(
+    segment: &TranscriptSegment,
+    max_cue_ms: u64,
+    max_chars: usize,
+) -> Vec<TranscriptSegment> {
+    let mut cues: Vec<Vec<TranscriptToken>> = Vec::new();
+    let mut current: Vec<TranscriptToken> = Vec::new();
+    for token in &segment.tokens {
+        if let Some(first) = current.first() {
+            let word_boundary = token.text.starts_with(char::is_whitespace);
+            let chars: usize = current.iter().map(|token| token.text.chars().count()).sum();
+            let too_long = token.end_ms.saturating_sub(first.start_ms) > max_cue_ms
+                || chars + token.text.trim_end().chars().count() > max_chars;
+            if word_boundary && too_long {
+                cues.push(std::mem::take(&mut current));
+            }
+        }
+        current.push(token.clone());
+    }
+    if !current.is_empty() {
+        cues.push(current);
+    }
+
+    cues.into_iter()
+        .map(|tokens| TranscriptSegment {
+            text: tokens
+                .iter()
+                .map(|token| token.text.as_str())
+                .collect::<String>()
+                .trim()
+                .to_string(),
+            start_ms: tokens.first().map_or(segment.start_ms, |token| token.start_ms),
+            end_ms: tokens.last().map_or(segment.end_ms, |token| token.end_ms),
+            tokens,
+            speaker: segment.speaker.clone(),
+            language: segment.language.clone(),
+            no_speech_prob: segment.no_speech_prob,
+            avg_logprob: segment.avg_logprob,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::LanguageTag;
+
+    use super::*;
+
+    fn token(text: &str, start_ms: u64, end_ms: u64) -> TranscriptToken {
+        TranscriptToken {
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+            confidence: 1.0,
+            start_sample: None,
+            end_sample: None,
+            disfluency: false,
+        }
+    }
+
+    fn segment(text: &str, start_ms: u64, end_ms: u64) -> TranscriptSegment {
+        TranscriptSegment {
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+            tokens: Vec::new(),
+            speaker: None,
+            language: None,
+            no_speech_prob: None,
+            avg_logprob: None,
+        }
+    }
+
+    fn transcript(language: LanguageTag, segments: Vec<TranscriptSegment>) -> Transcript {
+        Transcript { language, segments }
+    }
+
+    fn texts(transcript: &Transcript) -> Vec<&str> {
+        transcript.segments.iter().map(|segment| segment.text.as_str()).collect()
+    }
+
+    #[test]
+    fn shift_moves_segments_tokens_and_samples() {
+        let mut chunk = segment("hello", 100, 600);
+        let mut hello = token("hello", 100, 600);
+        hello.start_sample = Some(1_600);
+        hello.end_sample = Some(9_600);
+        chunk.tokens.push(hello);
+        let mut shifted = transcript(LanguageTag::en(), vec![chunk]);
+
+        shifted.shift(30_000, 16_000);
+        let segment = &shifted.segments[0];
+        assert_eq!((segment.start_ms, segment.end_ms), (30_100, 30_600));
+        let token = &segment.tokens[0];
+        assert_eq!((token.start_ms, token.end_ms), (30_100, 30_600));
+        assert_eq!((token.start_sample, token.end_sample), (Some(481_600), Some(489_600)));
+
+        shifted.shift(-30_300, 16_000);
+        let token = &shifted.segments[0].tokens[0];
+        assert_eq!((token.start_ms, token.end_ms), (0, 300));
+        assert_eq!((token.start_sample, token.end_sample), (Some(0), Some(4_800)));
+    }
+
+    #[test]
+    fn merge_keeps_each_chunk_on_its_side_of_the_overlap() {
+        let first = transcript(
+            LanguageTag::fr(),
+            vec![
+                segment("a", 0, 4_000),
+                segment("b", 4_000, 9_000),
+                segment("c-edge", 9_000, 10_000),
+            ],
+        );
+        // Overlap is 8 000..10 000, so the cut is at 9 000.
+        let second = transcript(
+            LanguageTag::fr(),
+            vec![segment("b-edge", 8_000, 8_800), segment("c", 8_800, 12_000)],
+        );
+
+        let merged = Transcript::merge_chunks([first, second]).expect("two chunks");
+        assert_eq!(texts(&merged), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn merge_tags_segments_of_a_foreign_chunk() {
+        let first = transcript(LanguageTag::fr(), vec![segment("bonjour", 0, 1_000)]);
+        let second = transcript(LanguageTag::en(), vec![segment("hello", 1_000, 2_000)]);
+
+        let merged = Transcript::merge_chunks([first, second]).expect("two chunks");
+        assert_eq!(merged.language, LanguageTag::fr());
+        assert_eq!(merged.segments[0].language, None);
+        assert_eq!(merged.segments[1].language, Some(LanguageTag::en()));
+        assert!(Transcript::merge_chunks(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn resegment_splits_long_segments_between_words() {
+        let mut long = segment("one two three four", 0, 4_000);
+        long.speaker = Some("agent".to_string());
+        long.tokens = vec![
+            token(" one", 0, 900),
+            token(" two", 1_000, 1_900),
+            token(" thr", 2_000, 2_400),
+            token("ee", 2_400, 2_900),
+            token(" four", 3_000, 4_000),
+        ];
+        let original = transcript(LanguageTag::en(), vec![long, segment("short", 4_000, 4_500)]);
+
+        let cues = original.resegment(2_000, 80);
+        assert_eq!(texts(&cues), vec!["one two", "three four", "short"]);
+        assert_eq!((cues.segments[1].start_ms, cues.segments[1].end_ms), (2_000, 4_000));
+        assert_eq!(cues.segments[1].speaker.as_deref(), Some("agent"));
+    }
+
+    #[test]
+    fn splice_replaces_covered_segments() {
+        let mut original = transcript(
+            LanguageTag::en(),
+            vec![
+                segment("a", 0, 1_000),
+                segment("b", 1_000, 2_000),
+                segment("c", 2_000, 3_000),
+            ],
+        );
+        original.splice(vec![segment("B1", 900, 1_500), segment("B2", 1_500, 2_100)]);
+        assert_eq!(texts(&original), vec!["a", "B1", "B2", "c"]);
+
+        original.splice(Vec::new());
+        assert_eq!(original.segments.len(), 4);
+    }
+}
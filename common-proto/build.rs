@@ -0,0 +1,17 @@
+use std::path::{Path, PathBuf};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let protoc = protoc_bin_vendored::protoc_bin_path()?;
+    std::env::set_var("PROTOC", protoc);
+    let descriptor = PathBuf::from(std::env::var("OUT_DIR")?).join("common_descriptor.bin");
+
+    tonic_prost_build::configure()
+        .build_client(false)
+        .build_server(false)
+        .file_descriptor_set_path(&descriptor)
+        .compile_protos(&["proto/common/v1/common.proto"], &["proto"])?;
+
+    println!("cargo:rerun-if-changed=proto/common/v1/common.proto");
+    proto_compat::enforce(&descriptor, "common.v1", Path::new("proto/common/v1/common.v1.lock"))?;
+    Ok(())
+}
@@ -0,0 +1,11 @@
+//! Messages shared by the service protos, generated once from
+//! `proto/common/v1/common.proto`.
+//!
+//! The gRPC crates compile their own protos with
+//! `extern_path(".common.v1", "::common_proto::v1")`, so `asr.v1`,
+//! `alignment.v1` and the streaming protocol all use these types, and
+//! re-export them from their `pb` modules for code written before the move.
+
+pub mod v1 {
+    tonic::include_proto!("common.v1");
+}
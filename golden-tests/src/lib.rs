@@ -0,0 +1,235 @@
+//! Golden-corpus accuracy checks.
+//!
+//! `corpus/manifest.json` lists committed recordings with their reference
+//! transcript and hand-checked word timings. `tests/golden.rs` (feature
+//! `golden`, needs the models) runs each recording through Whisper and
+//! wav2vec2 and fails when WER, CER or alignment drift exceed the thresholds.
+//! This crate holds the manifest model and the scoring, which need no model.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use asr_domain::text_metrics::{cer, normalize_text, wer};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct Thresholds {
+    pub max_wer: f64,
+    pub max_cer: f64,
+    /// Mean absolute start/end difference over matched words.
+    pub max_alignment_drift_ms: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ExpectedWord {
+    pub word: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct GoldenCase {
+    pub name: String,
+    /// WAV path, relative to the manifest.
+    pub audio: PathBuf,
+    pub language: String,
+    pub reference: String,
+    #[serde(default)]
+    pub words: Vec<ExpectedWord>,
+    /// Per-case override of the corpus thresholds.
+    #[serde(default)]
+    pub thresholds: Option<Thresholds>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Manifest {
+    pub thresholds: Thresholds,
+    pub cases: Vec<GoldenCase>,
+}
+
+impl Manifest {
+    /// Loads a manifest and resolves case audio paths against its directory.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|err| format!("cannot read `{}`: {err}", path.display()))?;
+        let mut manifest: Self = serde_json::from_str(&content)
+            .map_err(|err| format!("invalid manifest `{}`: {err}", path.display()))?;
+        let base = path.parent().unwrap_or_else(|| Path::new("."));
+        for case in &mut manifest.cases {
+            case.audio = base.join(&case.audio);
+        }
+        Ok(manifest)
+    }
+
+    pub fn thresholds_for(&self, case: &GoldenCase) -> Thresholds {
+        case.thresholds.unwrap_or(self.thresholds)
+    }
+}
+
+/// A word timing produced by the aligner.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlignedWord {
+    pub word: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaseScore {
+    pub wer: f64,
+    pub cer: f64,
+    /// `None` when the case has no expected timings or no word matched.
+    pub alignment_drift_ms: Option<f64>,
+}
+
+impl CaseScore {
+    pub fn compute(case: &GoldenCase, hypothesis: &str, aligned: &[AlignedWord]) -> Self {
+        Self {
+            wer: wer(&case.reference, hypothesis),
+            cer: cer(&case.reference, hypothesis),
+            alignment_drift_ms: alignment_drift_ms(&case.words, aligned),
+        }
+    }
+
+    /// Threshold violations, empty when the case passes.
+    pub fn violations(&self, thresholds: &Thresholds) -> Vec<String> {
+        let mut violations = Vec::new();
+        if self.wer > thresholds.max_wer {
+            violations.push(format!("WER {:.3} > {:.3}", self.wer, thresholds.max_wer));
+        }
+        if self.cer > thresholds.max_cer {
+            violations.push(format!("CER {:.3} > {:.3}", self.cer, thresholds.max_cer));
+        }
+        if let Some(drift) = self.alignment_drift_ms {
+            if drift > thresholds.max_alignment_drift_ms {
+                violations.push(format!(
+                    "alignment drift {drift:.0} ms > {:.0} ms",
+                    thresholds.max_alignment_drift_ms
+                ));
+            }
+        }
+        violations
+    }
+}
+
+/// Pairs expected and aligned words in order (a word the ASR dropped or
+/// inserted is skipped) and averages the start and end differences.
+fn alignment_drift_ms(expected: &[ExpectedWord], aligned: &[AlignedWord]) -> Option<f64> {
+    let mut total = 0.0;
+    let mut matched = 0usize;
+    let mut cursor = 0;
+    for word in expected {
+        let key = normalize_text(&word.word);
+        let Some(offset) = aligned[cursor..]
+            .iter()
+            .position(|candidate| normalize_text(&candidate.word) == key)
+        else {
+            continue;
+        };
+        let candidate = &aligned[cursor + offset];
+        total += word.start_ms.abs_diff(candidate.start_ms) as f64;
+        total += word.end_ms.abs_diff(candidate.end_ms) as f64;
+        matched += 1;
+        cursor += offset + 1;
+    }
+    (matched > 0).then(|| total / (2 * matched) as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn case(reference: &str, words: Vec<ExpectedWord>) -> GoldenCase {
+        GoldenCase {
+            name: "case".to_string(),
+            audio: PathBuf::from("case.wav"),
+            language: "fr".to_string(),
+            reference: reference.to_string(),
+            words,
+            thresholds: None,
+        }
+    }
+
+    fn expected(word: &str, start_ms: u64, end_ms: u64) -> ExpectedWord {
+        ExpectedWord {
+            word: word.to_string(),
+            start_ms,
+            end_ms,
+        }
+    }
+
+    fn aligned(word: &str, start_ms: u64, end_ms: u64) -> AlignedWord {
+        AlignedWord {
+            word: word.to_string(),
+            start_ms,
+            end_ms,
+        }
+    }
+
+    const THRESHOLDS: Thresholds = Thresholds {
+        max_wer: 0.2,
+        max_cer: 0.1,
+        max_alignment_drift_ms: 50.0,
+    };
+
+    #[test]
+    fn exact_transcript_passes() {
+        let case = case("Bonjour, le monde.", vec![expected("bonjour", 0, 400)]);
+        let score = CaseScore::compute(&case, "bonjour le monde", &[aligned("Bonjour", 10, 390)]);
+        assert_eq!(score.wer, 0.0);
+        assert_eq!(score.cer, 0.0);
+        assert_eq!(score.alignment_drift_ms, Some(10.0));
+        assert!(score.violations(&THRESHOLDS).is_empty());
+    }
+
+    #[test]
+    fn regressions_are_reported() {
+        let case = case(
+            "le chat dort",
+            vec![expected("le", 0, 100), expected("dort", 400, 700)],
+        );
+        let score = CaseScore::compute(
+            &case,
+            "le chien dort",
+            &[aligned("le", 0, 100), aligned("chien", 100, 400), aligned("dort", 600, 900)],
+        );
+        assert!((score.wer - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(score.alignment_drift_ms, Some(100.0));
+        let violations = score.violations(&THRESHOLDS);
+        assert_eq!(violations.len(), 3, "{violations:?}");
+    }
+
+    #[test]
+    fn unmatched_words_give_no_drift() {
+        assert_eq!(
+            alignment_drift_ms(&[expected("bonjour", 0, 100)], &[aligned("salut", 0, 100)]),
+            None
+        );
+    }
+
+    #[test]
+    fn committed_manifest_parses() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus/manifest.json");
+        let manifest = Manifest::load(&path).expect("manifest should parse");
+        for case in &manifest.cases {
+            assert!(case.audio.is_file(), "missing audio for `{}`", case.name);
+            let reference = normalize_text(&case.reference);
+            let mut previous_end_ms = 0;
+            for word in &case.words {
+                assert!(
+                    previous_end_ms <= word.start_ms && word.start_ms < word.end_ms,
+                    "`{}`: timings of `{}` overlap or run backwards",
+                    case.name,
+                    word.word
+                );
+                assert!(
+                    reference.contains(&normalize_text(&word.word)),
+                    "`{}`: `{}` is not in the reference",
+                    case.name,
+                    word.word
+                );
+                previous_end_ms = word.end_ms;
+            }
+        }
+    }
+}
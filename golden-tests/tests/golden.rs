@@ -0,0 +1,154 @@
+//! Runs the golden corpus through Whisper and wav2vec2.
+//!
+//! ```text
+//! cargo test -p golden-tests --features golden -- --nocapture
+//! ```
+//!
+//! Model locations default to the development config and can be overridden
+//! with `GOLDEN_WHISPER_MODEL`, `GOLDEN_WHISPER_DTW_PRESET`,
+//! `GOLDEN_WAV2VEC2_DIR` and `GOLDEN_DEVICE`.
+#![cfg(feature = "golden")]
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use alignment_domain::{AlignmentPort, AlignmentRequest};
+use alignment_infra_alignment::{
+    AnchorConfig, CustomVocabulary, Precision, Wav2Vec2AdapterConfig, Wav2Vec2ForcedAligner,
+};
+use asr_domain::{AudioChunk, DecodeProfile, LanguageTag, TranscriptionPort, TranscriptionRequest};
+use asr_infra_asr_whisper::{WhisperAdapterConfig, WhisperTranscriptionAdapter};
+use golden_tests::{AlignedWord, CaseScore, GoldenCase, Manifest};
+use vocal_dsp::resample_linear;
+use wav_io::decode_wav;
+
+const SAMPLE_RATE_HZ: u32 = 16_000;
+
+fn setting(name: &str, default: &str) -> String {
+    env::var(name).unwrap_or_else(|_| default.to_string())
+}
+
+struct Models {
+    whisper: WhisperTranscriptionAdapter,
+    aligner: Wav2Vec2ForcedAligner,
+}
+
+impl Models {
+    fn load() -> Self {
+        let whisper = WhisperTranscriptionAdapter::new(WhisperAdapterConfig {
+            model_path: setting("GOLDEN_WHISPER_MODEL", "../models/ggml-large-v3-q5_0.bin"),
+            language: "auto".to_string(),
+            temperature: 0.0,
+            threads: 4,
+            dtw_preset: setting("GOLDEN_WHISPER_DTW_PRESET", "large_v3"),
+            dtw_mem_size: 128 * 1024 * 1024,
+            prompt_max_tokens: 224,
+        });
+        let wav2vec2_dir = setting("GOLDEN_WAV2VEC2_DIR", "../models/asr-wav2vec2-ctc-french-onnx");
+        let wav2vec2_dir = Path::new(&wav2vec2_dir);
+        let aligner = Wav2Vec2ForcedAligner::load(&Wav2Vec2AdapterConfig {
+            model_path: wav2vec2_dir.join("model.onnx").display().to_string(),
+            config_path: wav2vec2_dir.join("config.json").display().to_string(),
+            vocab_path: wav2vec2_dir.join("vocab.json").display().to_string(),
+            device: setting("GOLDEN_DEVICE", "cpu"),
+            precision: Precision::Fp32,
+            vocabulary: CustomVocabulary::default(),
+            anchors: Some(AnchorConfig::default()),
+        })
+        .expect("wav2vec2 model should load");
+        Self { whisper, aligner }
+    }
+
+    async fn score(&self, case: &GoldenCase) -> Result<CaseScore, String> {
+        let bytes = fs::read(&case.audio)
+            .map_err(|err| format!("cannot read `{}`: {err}", case.audio.display()))?;
+        let wav = decode_wav(&bytes)?;
+        let samples = if wav.sample_rate_hz == SAMPLE_RATE_HZ {
+            wav.samples
+        } else {
+            resample_linear(&wav.samples, wav.sample_rate_hz, SAMPLE_RATE_HZ)
+        };
+        let audio = AudioChunk::mono(SAMPLE_RATE_HZ, samples);
+
+        let transcript = self
+            .whisper
+            .transcribe(TranscriptionRequest {
+                language_hint: Some(
+                    LanguageTag::parse(&case.language).expect("manifest language is BCP-47"),
+                ),
+                model: None,
+                profile: DecodeProfile::Accurate,
+                grammar: None,
+                prompt: None,
+                audio: audio.clone(),
+            })
+            .await
+            .map_err(|err| format!("transcription failed: {err}"))?
+            .transcript;
+        let hypothesis = transcript
+            .segments
+            .iter()
+            .map(|segment| segment.text.trim())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let aligned: Vec<AlignedWord> = self
+            .aligner
+            .align(AlignmentRequest { audio, transcript })
+            .await
+            .map_err(|err| format!("alignment failed: {err}"))?
+            .words
+            .into_iter()
+            .map(|word| AlignedWord {
+                word: word.word,
+                start_ms: word.start_ms,
+                end_ms: word.end_ms,
+            })
+            .collect();
+
+        Ok(CaseScore::compute(case, &hypothesis, &aligned))
+    }
+}
+
+#[tokio::test]
+async fn golden_corpus_meets_accuracy_thresholds() {
+    let manifest_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus/manifest.json");
+    let manifest = Manifest::load(&manifest_path).expect("manifest should load");
+    // An empty corpus would pass without measuring anything.
+    assert!(
+        !manifest.cases.is_empty(),
+        "golden corpus is empty; add recordings to {}",
+        manifest_path.display()
+    );
+
+    let models = Models::load();
+    let mut failures = Vec::new();
+    for case in &manifest.cases {
+        match models.score(case).await {
+            Ok(score) => {
+                eprintln!(
+                    "{:<24} wer {:>5.1}%  cer {:>5.1}%  drift {}",
+                    case.name,
+                    score.wer * 100.0,
+                    score.cer * 100.0,
+                    score
+                        .alignment_drift_ms
+                        .map(|drift| format!("{drift:.0} ms"))
+                        .unwrap_or_else(|| "-".to_string())
+                );
+                let violations = score.violations(&manifest.thresholds_for(case));
+                if !violations.is_empty() {
+                    failures.push(format!("{}: {}", case.name, violations.join(", ")));
+                }
+            }
+            Err(error) => failures.push(format!("{}: {error}", case.name)),
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "golden corpus regressed:\n{}",
+        failures.join("\n")
+    );
+}
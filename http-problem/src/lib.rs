@@ -0,0 +1,101 @@
+//! RFC 7807 `application/problem+json` error bodies for the HTTP crates.
+//!
+//! `type` is a URN ending in a stable code, so clients branch on it (or on
+//! the `code` extension member) rather than on `detail`, which is meant for
+//! people.
+
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use common_domain::ErrorCode;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+pub const PROBLEM_JSON: &str = "application/problem+json";
+pub const PROBLEM_TYPE_PREFIX: &str = "urn:vocal-agent:problem:";
+
+/// Code of requests without valid credentials, which [`ErrorCode`] has no
+/// variant for because no domain raises it.
+pub const UNAUTHENTICATED: &str = "unauthenticated";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ProblemDetails {
+    /// `urn:vocal-agent:problem:<code>`.
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Last segment of `type`, e.g. `invalid_input`.
+    pub code: String,
+}
+
+impl ProblemDetails {
+    pub fn new(status: StatusCode, code: &str, detail: Option<String>) -> Self {
+        Self {
+            problem_type: format!("{PROBLEM_TYPE_PREFIX}{code}"),
+            title: status.canonical_reason().unwrap_or("Error").to_string(),
+            status: status.as_u16(),
+            detail,
+            code: code.to_string(),
+        }
+    }
+
+    pub fn from_code(code: ErrorCode, detail: Option<String>) -> Self {
+        Self::new(status_for(code), code.as_str(), detail)
+    }
+
+    pub fn status_code(&self) -> StatusCode {
+        StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+}
+
+pub fn status_for(code: ErrorCode) -> StatusCode {
+    match code {
+        ErrorCode::InvalidInput | ErrorCode::AudioQuality | ErrorCode::FailedPrecondition => {
+            StatusCode::UNPROCESSABLE_ENTITY
+        }
+        ErrorCode::NotFound => StatusCode::NOT_FOUND,
+        ErrorCode::AlreadyExists => StatusCode::CONFLICT,
+        ErrorCode::PermissionDenied => StatusCode::FORBIDDEN,
+        ErrorCode::ResourceExhausted => StatusCode::TOO_MANY_REQUESTS,
+        ErrorCode::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+        ErrorCode::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+impl IntoResponse for ProblemDetails {
+    fn into_response(self) -> Response {
+        let body = serde_json::to_vec(&self).unwrap_or_default();
+        (self.status_code(), [(header::CONTENT_TYPE, PROBLEM_JSON)], body).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn problems_carry_a_stable_type_per_code() {
+        let problem = ProblemDetails::from_code(ErrorCode::AlreadyExists, Some("dup".into()));
+        let body = serde_json::to_value(&problem).expect("serializes");
+        assert_eq!(body["type"], "urn:vocal-agent:problem:already_exists");
+        assert_eq!(body["status"], 409);
+        assert_eq!(body["title"], "Conflict");
+        assert_eq!(body["code"], "already_exists");
+
+        let response = problem.into_response();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], PROBLEM_JSON);
+    }
+
+    #[test]
+    fn every_code_has_an_error_status() {
+        for code in ErrorCode::ALL {
+            let status = status_for(code);
+            assert!(status.is_client_error() || status.is_server_error(), "{code}");
+        }
+    }
+}
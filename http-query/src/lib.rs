@@ -0,0 +1,243 @@
+//! Query parameters shared by the listing endpoints of the HTTP crates, so
+//! session, job and transcript listings page and filter the same way.
+//!
+//! Handlers take `Query<ListParams>`, call [`ListParams::parse`] and return
+//! a [`Page`]:
+//!
+//! ```
+//! use http_query::ListParams;
+//!
+//! let params = ListParams {
+//!     limit: Some(2),
+//!     status: Some("done,failed".to_string()),
+//!     ..ListParams::default()
+//! };
+//! let query = params.parse().expect("valid query");
+//! let jobs = vec![(1, "done"), (2, "running"), (3, "failed"), (4, "done")];
+//! let matching: Vec<_> = jobs
+//!     .into_iter()
+//!     .filter(|(_, status)| query.matches_status(status))
+//!     .collect();
+//! let page = query.paginate(matching);
+//! assert_eq!(page.items, vec![(1, "done"), (3, "failed")]);
+//! assert!(page.next_cursor.is_some());
+//! ```
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use utoipa::{IntoParams, ToSchema};
+
+pub const DEFAULT_LIMIT: usize = 50;
+pub const MAX_LIMIT: usize = 500;
+
+/// Raw query string of a listing endpoint.
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListParams {
+    /// Page size, 1 to 500. Defaults to 50.
+    pub limit: Option<usize>,
+    /// Items to skip. Mutually exclusive with `cursor`.
+    pub offset: Option<usize>,
+    /// `next_cursor` of the previous page.
+    pub cursor: Option<String>,
+    /// Keep items at or after this Unix time, in milliseconds.
+    pub since_ms: Option<u64>,
+    /// Keep items before this Unix time, in milliseconds.
+    pub until_ms: Option<u64>,
+    /// Comma-separated statuses to keep, e.g. `running,failed`.
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum QueryError {
+    #[error("limit must be between 1 and {MAX_LIMIT}")]
+    Limit,
+    #[error("offset and cursor cannot be combined")]
+    OffsetWithCursor,
+    #[error("invalid cursor")]
+    Cursor,
+    #[error("since_ms must not be after until_ms")]
+    TimeRange,
+}
+
+/// Validated [`ListParams`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListQuery {
+    pub limit: usize,
+    pub offset: usize,
+    pub since_ms: Option<u64>,
+    pub until_ms: Option<u64>,
+    /// Empty means every status.
+    pub statuses: Vec<String>,
+}
+
+/// One page of a listing.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Pass as `cursor` to get the next page; absent on the last page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    /// Matching items across all pages.
+    pub total: usize,
+}
+
+impl ListParams {
+    pub fn parse(self) -> Result<ListQuery, QueryError> {
+        let limit = self.limit.unwrap_or(DEFAULT_LIMIT);
+        if !(1..=MAX_LIMIT).contains(&limit) {
+            return Err(QueryError::Limit);
+        }
+        let offset = match (self.offset, self.cursor) {
+            (Some(_), Some(_)) => return Err(QueryError::OffsetWithCursor),
+            (_, Some(cursor)) => decode_cursor(&cursor)?,
+            (offset, None) => offset.unwrap_or(0),
+        };
+        if let (Some(since), Some(until)) = (self.since_ms, self.until_ms) {
+            if since > until {
+                return Err(QueryError::TimeRange);
+            }
+        }
+        let statuses = self
+            .status
+            .iter()
+            .flat_map(|status| status.split(','))
+            .map(str::trim)
+            .filter(|status| !status.is_empty())
+            .map(str::to_ascii_lowercase)
+            .collect();
+
+        Ok(ListQuery {
+            limit,
+            offset,
+            since_ms: self.since_ms,
+            until_ms: self.until_ms,
+            statuses,
+        })
+    }
+}
+
+impl ListQuery {
+    pub fn matches_time(&self, timestamp_ms: u64) -> bool {
+        self.since_ms.is_none_or(|since| timestamp_ms >= since)
+            && self.until_ms.is_none_or(|until| timestamp_ms < until)
+    }
+
+    /// Case-insensitive.
+    pub fn matches_status(&self, status: &str) -> bool {
+        self.statuses.is_empty()
+            || self
+                .statuses
+                .iter()
+                .any(|wanted| wanted.eq_ignore_ascii_case(status))
+    }
+
+    /// Cuts the page out of the already filtered and ordered `items`.
+    pub fn paginate<T>(&self, items: Vec<T>) -> Page<T> {
+        let total = items.len();
+        let items: Vec<T> = items.into_iter().skip(self.offset).take(self.limit).collect();
+        let end = self.offset + items.len();
+        Page {
+            items,
+            next_cursor: (end < total).then(|| encode_cursor(end)),
+            total,
+        }
+    }
+
+    /// `next_cursor` for stores that page themselves and know `total`.
+    pub fn next_cursor(&self, returned: usize, total: usize) -> Option<String> {
+        let end = self.offset + returned;
+        (end < total).then(|| encode_cursor(end))
+    }
+}
+
+/// Cursors are opaque to clients; they currently wrap an offset.
+fn encode_cursor(offset: usize) -> String {
+    URL_SAFE_NO_PAD.encode(format!("o:{offset}"))
+}
+
+fn decode_cursor(cursor: &str) -> Result<usize, QueryError> {
+    let bytes = URL_SAFE_NO_PAD
+        .decode(cursor)
+        .map_err(|_| QueryError::Cursor)?;
+    std::str::from_utf8(&bytes)
+        .ok()
+        .and_then(|text| text.strip_prefix("o:"))
+        .and_then(|offset| offset.parse().ok())
+        .ok_or(QueryError::Cursor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursors_walk_every_page() {
+        let items: Vec<u32> = (0..7).collect();
+        let mut params = ListParams {
+            limit: Some(3),
+            ..ListParams::default()
+        };
+        let mut seen = Vec::new();
+        loop {
+            let page = params.clone().parse().expect("valid").paginate(items.clone());
+            assert_eq!(page.total, 7);
+            seen.extend(page.items);
+            match page.next_cursor {
+                Some(cursor) => params.cursor = Some(cursor),
+                None => break,
+            }
+        }
+        assert_eq!(seen, items);
+    }
+
+    #[test]
+    fn invalid_parameters_are_rejected() {
+        let parse = |params: ListParams| params.parse().err();
+        assert_eq!(
+            parse(ListParams {
+                limit: Some(0),
+                ..ListParams::default()
+            }),
+            Some(QueryError::Limit)
+        );
+        assert_eq!(
+            parse(ListParams {
+                offset: Some(1),
+                cursor: Some(encode_cursor(2)),
+                ..ListParams::default()
+            }),
+            Some(QueryError::OffsetWithCursor)
+        );
+        assert_eq!(
+            parse(ListParams {
+                cursor: Some("not a cursor".to_string()),
+                ..ListParams::default()
+            }),
+            Some(QueryError::Cursor)
+        );
+        assert_eq!(
+            parse(ListParams {
+                since_ms: Some(10),
+                until_ms: Some(5),
+                ..ListParams::default()
+            }),
+            Some(QueryError::TimeRange)
+        );
+    }
+
+    #[test]
+    fn filters_use_half_open_ranges_and_any_status() {
+        let params: ListParams = serde_json::from_value(serde_json::json!({
+            "since_ms": 100,
+            "until_ms": 200,
+            "status": "Running, failed,",
+        }))
+        .expect("deserializes");
+        let query = params.parse().expect("valid");
+        assert_eq!(query.statuses, ["running", "failed"]);
+        assert!(query.matches_time(100) && !query.matches_time(200));
+        assert!(query.matches_status("RUNNING") && !query.matches_status("done"));
+    }
+}
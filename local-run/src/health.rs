@@ -0,0 +1,134 @@
+use std::fs;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::time::Duration;
+
+const PROBE_TIMEOUT: Duration = Duration::from_millis(500);
+const LIVENESS_PATH: &str = "/healthz";
+
+/// Address a service listens on, read from the `[server]` table of its
+/// `config/<env>.toml`.
+pub fn server_address(config_path: &Path) -> Option<SocketAddr> {
+    let content = fs::read_to_string(config_path).ok()?;
+    let (host, port) = parse_server_table(&content)?;
+    // A wildcard bind is reachable on loopback.
+    let host = match host.as_str() {
+        "0.0.0.0" | "::" | "[::]" => "127.0.0.1".to_string(),
+        _ => host,
+    };
+    (host.as_str(), port).to_socket_addrs().ok()?.next()
+}
+
+/// Ports of the orchestrator's streaming listeners (`port`, `grpc_port`),
+/// keyed by their config path, from the `[service.streaming]` table of its
+/// `config/<env>.toml`.
+pub fn streaming_ports(config_path: &Path) -> Vec<(String, u16)> {
+    let Ok(content) = fs::read_to_string(config_path) else {
+        return Vec::new();
+    };
+    parse_table_ports(&content, "[service.streaming]")
+        .into_iter()
+        .map(|(key, port)| (format!("service.streaming.{key}"), port))
+        .collect()
+}
+
+/// `*port` keys of `table`, in file order.
+fn parse_table_ports(content: &str, table: &str) -> Vec<(String, u16)> {
+    let mut in_table = false;
+    let mut ports = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_table = line == table;
+            continue;
+        }
+        if !in_table {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim();
+        if !key.ends_with("port") {
+            continue;
+        }
+        if let Ok(port) = value.trim().parse() {
+            ports.push((key.to_string(), port));
+        }
+    }
+    ports
+}
+
+fn parse_server_table(content: &str) -> Option<(String, u16)> {
+    let mut in_server = false;
+    let mut host = "127.0.0.1".to_string();
+    let mut port = None;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_server = line == "[server]";
+            continue;
+        }
+        if !in_server {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().trim_matches('"');
+        match key.trim() {
+            "host" => host = value.to_string(),
+            "port" => port = value.parse().ok(),
+            _ => {}
+        }
+    }
+    Some((host, port?))
+}
+
+/// True when `GET /healthz` answers `200`. Every service serves it: the gRPC
+/// services over HTTP/1.1 on their gRPC port.
+pub fn is_live(address: SocketAddr) -> bool {
+    let Ok(mut stream) = TcpStream::connect_timeout(&address, PROBE_TIMEOUT) else {
+        return false;
+    };
+    let _ = stream.set_read_timeout(Some(PROBE_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(PROBE_TIMEOUT));
+    let request =
+        format!("GET {LIVENESS_PATH} HTTP/1.1\r\nHost: {address}\r\nConnection: close\r\n\r\n");
+    if stream.write_all(request.as_bytes()).is_err() {
+        return false;
+    }
+
+    let mut status_line = [0u8; 12];
+    stream.read_exact(&mut status_line).is_ok() && status_line.ends_with(b" 200")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn server_table_is_read_ignoring_other_ports() {
+        let content = "[server]\nhost = \"0.0.0.0\"\nport = 8090\n\n[service.audio]\nport = 8081\n";
+        assert_eq!(
+            parse_server_table(content),
+            Some(("0.0.0.0".to_string(), 8090))
+        );
+    }
+
+    #[test]
+    fn streaming_ports_are_read_apart_from_server() {
+        let content = "[server]\nport = 8090\n\n[service.streaming]\nhost = \"0.0.0.0\"\n\
+                       port = 8091\ngrpc_port = 8092\n# max_message_bytes = 1\n";
+        assert_eq!(
+            parse_table_ports(content, "[service.streaming]"),
+            vec![("port".to_string(), 8091), ("grpc_port".to_string(), 8092)]
+        );
+    }
+
+    #[test]
+    fn missing_port_yields_none() {
+        assert_eq!(parse_server_table("[server]\nhost = \"127.0.0.1\"\n"), None);
+    }
+}
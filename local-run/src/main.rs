@@ -1,8 +1,21 @@
+mod health;
+mod output;
+mod overrides;
+mod supervisor;
+
 use std::env;
 use std::path::{Path, PathBuf};
-use std::process::{self, Child, Command, Stdio};
-use std::thread;
-use std::time::Duration;
+use std::process::{self, Command, Stdio};
+
+use overrides::{canonical_service_name, ConfigOverride, Overrides};
+use supervisor::Supervisor;
+
+const MODEL_SERVICES: &[&str] = &[
+    "audio-service",
+    "asr-service",
+    "alignment-service",
+    "tempo-service",
+];
 
 #[derive(Clone, Copy)]
 struct ServiceSpec {
@@ -11,6 +24,10 @@ struct ServiceSpec {
     bin: &'static str,
     working_dir: &'static str,
     feature: Option<&'static str>,
+    /// Arguments passed to the binary after `--`.
+    args: &'static [&'static str],
+    /// Services whose `/healthz` must answer before this one starts.
+    depends_on: &'static [&'static str],
 }
 
 fn main() {
@@ -20,31 +37,54 @@ fn main() {
     }
 }
 
+const USAGE: &str = "usage: cargo run -p local-run [--cpu] [--env=development|test|production] \
+[--port-offset=N] [--only=SERVICE,..] [--set SERVICE.key=value].. [--mock-downstream]";
+
 fn run() -> Result<(), String> {
     let mut use_cuda = true;
     let mut run_env = "development".to_string();
-
-    for arg in env::args().skip(1) {
-        if arg == "--cpu" {
-            use_cuda = false;
-        } else if let Some(value) = arg.strip_prefix("--env=") {
-            run_env = value.to_string();
-        } else {
-            return Err(format!(
-                "unknown argument `{arg}`\nusage: cargo run -p local-run [--cpu] [--env=development|test|production]"
-            ));
+    let mut mock_downstream = false;
+    let mut only: Option<Vec<String>> = None;
+    let mut overrides = Overrides::default();
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value)),
+            _ => (arg.clone(), None),
+        };
+        let mut value = || {
+            inline_value
+                .map(str::to_string)
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("`{flag}` expects a value\n{USAGE}"))
+        };
+        match flag.as_str() {
+            "--cpu" if inline_value.is_none() => use_cuda = false,
+            "--mock-downstream" if inline_value.is_none() => mock_downstream = true,
+            "--env" => run_env = value()?,
+            "--port-offset" => {
+                let raw = value()?;
+                overrides.port_offset = raw
+                    .parse()
+                    .map_err(|_| format!("`--port-offset {raw}` is not a valid port offset"))?;
+            }
+            "--only" => {
+                only = Some(
+                    value()?
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|name| !name.is_empty())
+                        .map(canonical_service_name)
+                        .collect(),
+                );
+            }
+            "--set" => overrides.values.push(ConfigOverride::parse(&value()?)?),
+            _ => return Err(format!("unknown argument `{arg}`\n{USAGE}")),
         }
     }
 
     let repo_root = resolve_repo_root()?;
-    kill_existing_processes(&[
-        "audio-service",
-        "asr-service",
-        "alignment-service",
-        "tempo-service",
-        "orchestration-service",
-    ]);
-
     let services = vec![
         ServiceSpec {
             name: "audio-service",
@@ -52,6 +92,8 @@ fn run() -> Result<(), String> {
             bin: "audio-service",
             working_dir: "audio-service",
             feature: None,
+            args: &[],
+            depends_on: &[],
         },
         ServiceSpec {
             name: "asr-service",
@@ -59,6 +101,8 @@ fn run() -> Result<(), String> {
             bin: "asr-service",
             working_dir: "asr-service",
             feature: if use_cuda { Some("whisper-cuda") } else { None },
+            args: &[],
+            depends_on: &[],
         },
         ServiceSpec {
             name: "alignment-service",
@@ -70,6 +114,8 @@ fn run() -> Result<(), String> {
             } else {
                 None
             },
+            args: &[],
+            depends_on: &[],
         },
         ServiceSpec {
             name: "tempo-service",
@@ -77,6 +123,8 @@ fn run() -> Result<(), String> {
             bin: "tempo-service",
             working_dir: "tempo-service",
             feature: None,
+            args: &[],
+            depends_on: &[],
         },
         ServiceSpec {
             name: "orchestration-service",
@@ -84,82 +132,70 @@ fn run() -> Result<(), String> {
             bin: "orchestration-service",
             working_dir: "orchestration-service",
             feature: None,
+            args: &[],
+            depends_on: MODEL_SERVICES,
         },
     ];
 
-    let mut children = Vec::with_capacity(services.len());
-    for service in services {
-        let child = spawn_service(service, &repo_root, &run_env)?;
-        println!("started {} (pid={})", service.name, child.id());
-        children.push((service, child));
-    }
+    let services: Vec<ServiceSpec> = if mock_downstream {
+        println!("--mock-downstream: model services are replaced by canned gRPC mocks");
+        services.into_iter().map(mocked).collect()
+    } else {
+        services
+    };
 
-    println!("all services started; press Ctrl+C to stop");
-    monitor_children(&mut children)
-}
-
-fn spawn_service(
-    service: ServiceSpec,
-    repo_root: &Path,
-    run_env: &str,
-) -> Result<Child, String> {
-    let working_dir = repo_root.join(service.working_dir);
-    let mut command = Command::new("cargo");
-    command
-        .arg("run")
-        .arg("-p")
-        .arg(service.package)
-        .arg("--bin")
-        .arg(service.bin);
-    if let Some(feature) = service.feature {
-        command.arg("--features").arg(feature);
+    let known: Vec<&str> = services.iter().map(|service| service.name).collect();
+    if let Some(only) = &only {
+        if let Some(unknown) = only.iter().find(|name| !known.contains(&name.as_str())) {
+            return Err(format!(
+                "`--only` names unknown service `{unknown}` (known: {})",
+                known.join(", ")
+            ));
+        }
     }
-    command
-        .current_dir(&working_dir)
-        .env("RUN_ENV", run_env)
-        .stdin(Stdio::null())
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit());
-
-    command.spawn().map_err(|err| {
-        format!(
-            "could not start {} from `{}`: {err}",
-            service.name,
-            working_dir.display()
-        )
-    })
+    if let Some(unknown) = overrides
+        .values
+        .iter()
+        .find(|value| !known.contains(&value.service.as_str()))
+    {
+        return Err(format!(
+            "`--set` names unknown service `{}` (known: {})",
+            unknown.service,
+            known.join(", ")
+        ));
+    }
+
+    // Dependencies left out by `--only` are expected to be running already.
+    let services: Vec<ServiceSpec> = services
+        .into_iter()
+        .filter(|service| match &only {
+            Some(only) => only.iter().any(|name| name == service.name),
+            None => true,
+        })
+        .collect();
+    let names: Vec<&str> = services.iter().map(|service| service.name).collect();
+    kill_existing_processes(&names);
+
+    println!("starting services; press Ctrl+C to stop");
+    Supervisor::new(services, &repo_root, &run_env, &overrides).run()
 }
 
-fn monitor_children(children: &mut [(ServiceSpec, Child)]) -> Result<(), String> {
-    loop {
-        for idx in 0..children.len() {
-            let status = match children[idx].1.try_wait() {
-                Ok(Some(status)) => {
-                    status
-                }
-                Ok(None) => continue,
-                Err(err) => {
-                    let service_name = children[idx].0.name;
-                    for (_, other_child) in children.iter_mut() {
-                        let _ = other_child.kill();
-                    }
-                    return Err(format!("failed while monitoring {}: {err}", service_name));
-                }
-            };
-
-            let service_name = children[idx].0.name;
-            eprintln!("{} exited with status {}", service_name, status);
-            for (other_idx, (_, other_child)) in children.iter_mut().enumerate() {
-                if other_idx != idx {
-                    let _ = other_child.kill();
-                }
-            }
-            if status.success() {
-                return Ok(());
-            }
-            return Err(format!("{} exited unexpectedly", service_name));
-        }
-        thread::sleep(Duration::from_millis(500));
+/// Swaps a model service for `mock-downstream`, run from the same directory
+/// so it reads that service's config and binds its port.
+fn mocked(service: ServiceSpec) -> ServiceSpec {
+    let args: &'static [&'static str] = match service.name {
+        "audio-service" => &["audio"],
+        "asr-service" => &["asr"],
+        "alignment-service" => &["alignment"],
+        "tempo-service" => &["tempo"],
+        _ => return service,
+    };
+    ServiceSpec {
+        package: "mock-downstream",
+        bin: "mock-downstream",
+        feature: None,
+        args,
+        ..service
     }
 }
 
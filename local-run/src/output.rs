@@ -0,0 +1,29 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::thread;
+
+const COLORS: &[&str] = &["36", "33", "35", "32", "34", "31"];
+
+/// ANSI-colored `[name]` prefix for the service at `index`.
+pub fn prefix(name: &str, index: usize) -> String {
+    format!("\x1b[{}m[{name}]\x1b[0m", COLORS[index % COLORS.len()])
+}
+
+/// Copies `source` line by line to stdout (or stderr) with `prefix`, on a
+/// background thread that ends with the stream.
+pub fn forward_lines<R>(source: R, prefix: String, to_stderr: bool)
+where
+    R: Read + Send + 'static,
+{
+    thread::spawn(move || {
+        for line in BufReader::new(source).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if to_stderr {
+                let _ = writeln!(io::stderr().lock(), "{prefix} {line}");
+            } else {
+                let _ = writeln!(io::stdout().lock(), "{prefix} {line}");
+            }
+        }
+    });
+}
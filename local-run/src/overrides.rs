@@ -0,0 +1,197 @@
+use std::path::{Path, PathBuf};
+
+use crate::health;
+use crate::ServiceSpec;
+
+/// A `--set SERVICE.key=value` flag. `SERVICE` is either the full service
+/// name (`asr-service`) or its short form (`asr`); `key` is a dotted path into
+/// the service's TOML config (`service.asr.default_model`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigOverride {
+    pub service: String,
+    pub key: String,
+    pub value: String,
+}
+
+impl ConfigOverride {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let (path, value) = raw
+            .split_once('=')
+            .ok_or_else(|| format!("`--set {raw}` must look like SERVICE.key=value"))?;
+        let (service, key) = path
+            .split_once('.')
+            .ok_or_else(|| format!("`--set {raw}` is missing the SERVICE. prefix"))?;
+        if service.is_empty() || key.is_empty() {
+            return Err(format!("`--set {raw}` must look like SERVICE.key=value"));
+        }
+        Ok(Self {
+            service: canonical_service_name(service),
+            key: key.to_string(),
+            value: value.to_string(),
+        })
+    }
+}
+
+/// `asr` and `asr-service` both name the ASR service.
+pub fn canonical_service_name(name: &str) -> String {
+    if name.ends_with("-service") {
+        name.to_string()
+    } else {
+        format!("{name}-service")
+    }
+}
+
+/// Per-run adjustments applied on top of each service's `config/<env>.toml`.
+#[derive(Debug, Clone, Default)]
+pub struct Overrides {
+    pub port_offset: u16,
+    pub values: Vec<ConfigOverride>,
+}
+
+impl Overrides {
+    /// Environment variables handed to `service`: its shifted `server.port`
+    /// and streaming ports, the shifted ports of the dependencies local-run
+    /// also starts, then the explicit `--set` values, which win.
+    pub fn env_for(
+        &self,
+        service: &ServiceSpec,
+        started: &[ServiceSpec],
+        repo_root: &Path,
+        run_env: &str,
+    ) -> Vec<(String, String)> {
+        let mut values = Vec::new();
+        if self.port_offset > 0 {
+            if let Some(port) = configured_port(service, repo_root, run_env) {
+                values.push(("server.port".to_string(), self.shift(port).to_string()));
+            }
+            let config = config_path(service, repo_root, run_env);
+            for (key, port) in health::streaming_ports(&config) {
+                values.push((key, self.shift(port).to_string()));
+            }
+            for dependency in started
+                .iter()
+                .filter(|candidate| service.depends_on.contains(&candidate.name))
+            {
+                if let Some(port) = configured_port(dependency, repo_root, run_env) {
+                    values.push((
+                        format!("service.{}.port", short_name(dependency.name)),
+                        self.shift(port).to_string(),
+                    ));
+                }
+            }
+        }
+        values.extend(
+            self.values
+                .iter()
+                .filter(|value| value.service == service.name)
+                .map(|value| (value.key.clone(), value.value.clone())),
+        );
+
+        let prefix = env_prefix(service.name);
+        values
+            .into_iter()
+            .map(|(key, value)| (env_key(&prefix, &key), value))
+            .collect()
+    }
+
+    /// Port `service` ends up listening on, for health probes.
+    pub fn effective_port(&self, service: &ServiceSpec, configured: u16) -> u16 {
+        self.values
+            .iter()
+            .rev()
+            .find(|value| value.service == service.name && value.key == "server.port")
+            .and_then(|value| value.value.parse().ok())
+            .unwrap_or_else(|| self.shift(configured))
+    }
+
+    fn shift(&self, port: u16) -> u16 {
+        port.saturating_add(self.port_offset)
+    }
+}
+
+fn configured_port(service: &ServiceSpec, repo_root: &Path, run_env: &str) -> Option<u16> {
+    health::server_address(&config_path(service, repo_root, run_env))
+        .map(|address| address.port())
+}
+
+pub fn config_path(service: &ServiceSpec, repo_root: &Path, run_env: &str) -> PathBuf {
+    repo_root
+        .join(service.working_dir)
+        .join("config")
+        .join(format!("{run_env}.toml"))
+}
+
+fn short_name(name: &str) -> &str {
+    name.strip_suffix("-service").unwrap_or(name)
+}
+
+/// `asr-service` -> `ASR_SERVICE`, the prefix each service's config loader
+/// reads overrides from.
+fn env_prefix(name: &str) -> String {
+    name.replace('-', "_").to_uppercase()
+}
+
+/// `server.port` -> `ASR_SERVICE_SERVER__PORT`.
+fn env_key(prefix: &str, key: &str) -> String {
+    let path = key
+        .split('.')
+        .map(str::to_uppercase)
+        .collect::<Vec<_>>()
+        .join("__");
+    format!("{prefix}_{path}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_accepts_short_service_names() {
+        let parsed = ConfigOverride::parse("asr.service.asr.default_model=small").unwrap();
+        assert_eq!(
+            parsed,
+            ConfigOverride {
+                service: "asr-service".to_string(),
+                key: "service.asr.default_model".to_string(),
+                value: "small".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn set_without_service_is_rejected() {
+        assert!(ConfigOverride::parse("port=1").is_err());
+        assert!(ConfigOverride::parse("asr.port").is_err());
+    }
+
+    #[test]
+    fn env_keys_nest_with_double_underscore() {
+        assert_eq!(
+            env_key(&env_prefix("orchestration-service"), "service.asr.port"),
+            "ORCHESTRATION_SERVICE_SERVICE__ASR__PORT"
+        );
+    }
+
+    #[test]
+    fn explicit_port_wins_over_offset() {
+        let spec = ServiceSpec {
+            name: "asr-service",
+            package: "asr-setup",
+            bin: "asr-service",
+            working_dir: "asr-service",
+            feature: None,
+            args: &[],
+            depends_on: &[],
+        };
+        let mut overrides = Overrides {
+            port_offset: 100,
+            values: Vec::new(),
+        };
+        assert_eq!(overrides.effective_port(&spec, 8082), 8182);
+
+        overrides
+            .values
+            .push(ConfigOverride::parse("asr.server.port=9000").unwrap());
+        assert_eq!(overrides.effective_port(&spec, 8082), 9000);
+    }
+}
@@ -0,0 +1,253 @@
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::health;
+use crate::output::{forward_lines, prefix};
+use crate::overrides::{config_path, Overrides};
+use crate::ServiceSpec;
+
+const TICK: Duration = Duration::from_millis(500);
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// A service that stays up this long gets its restart backoff reset.
+const STABLE_AFTER: Duration = Duration::from_secs(60);
+
+enum State {
+    /// Waiting for its dependencies to become healthy.
+    Pending,
+    Running { child: Child, started_at: Instant },
+    Backoff { until: Instant },
+    Stopped,
+}
+
+struct Supervised {
+    spec: ServiceSpec,
+    index: usize,
+    address: Option<SocketAddr>,
+    env: Vec<(String, String)>,
+    state: State,
+    healthy: bool,
+    failures: u32,
+}
+
+/// Starts services once their dependencies answer `/healthz`, restarts
+/// crashed ones with exponential backoff, and returns once every service has
+/// exited cleanly.
+pub struct Supervisor {
+    services: Vec<Supervised>,
+    repo_root: PathBuf,
+    run_env: String,
+}
+
+impl Supervisor {
+    pub fn new(
+        services: Vec<ServiceSpec>,
+        repo_root: &Path,
+        run_env: &str,
+        overrides: &Overrides,
+    ) -> Self {
+        let supervised = services
+            .iter()
+            .enumerate()
+            .map(|(index, spec)| Supervised {
+                address: health::server_address(&config_path(spec, repo_root, run_env)).map(
+                    |mut address| {
+                        address.set_port(overrides.effective_port(spec, address.port()));
+                        address
+                    },
+                ),
+                env: overrides.env_for(spec, &services, repo_root, run_env),
+                spec: *spec,
+                index,
+                state: State::Pending,
+                healthy: false,
+                failures: 0,
+            })
+            .collect();
+        Self {
+            services: supervised,
+            repo_root: repo_root.to_path_buf(),
+            run_env: run_env.to_string(),
+        }
+    }
+
+    pub fn run(mut self) -> Result<(), String> {
+        loop {
+            for idx in 0..self.services.len() {
+                self.step(idx)?;
+            }
+            if self
+                .services
+                .iter()
+                .all(|service| matches!(service.state, State::Stopped))
+            {
+                return Ok(());
+            }
+            thread::sleep(TICK);
+        }
+    }
+
+    fn step(&mut self, idx: usize) -> Result<(), String> {
+        let should_start = match &self.services[idx].state {
+            State::Pending => self.dependencies_healthy(idx),
+            State::Backoff { until } => Instant::now() >= *until,
+            State::Running { .. } | State::Stopped => false,
+        };
+        if should_start {
+            return self.start(idx);
+        }
+        self.poll(idx)
+    }
+
+    fn poll(&mut self, idx: usize) -> Result<(), String> {
+        let service = &mut self.services[idx];
+        let State::Running { child, started_at } = &mut service.state else {
+            return Ok(());
+        };
+        let status = child
+            .try_wait()
+            .map_err(|err| format!("failed while monitoring {}: {err}", service.spec.name))?;
+        let Some(status) = status else {
+            if !service.healthy && service.address.is_some_and(health::is_live) {
+                service.healthy = true;
+                println!("{} is healthy", service.spec.name);
+            }
+            return Ok(());
+        };
+
+        if started_at.elapsed() >= STABLE_AFTER {
+            service.failures = 0;
+        }
+        service.healthy = false;
+        if status.success() {
+            eprintln!("{} exited cleanly; not restarting", service.spec.name);
+            service.state = State::Stopped;
+            return Ok(());
+        }
+        service.failures += 1;
+        let delay = backoff_delay(service.failures);
+        eprintln!(
+            "{} exited with status {status}; restarting in {}s",
+            service.spec.name,
+            delay.as_secs()
+        );
+        service.state = State::Backoff {
+            until: Instant::now() + delay,
+        };
+        Ok(())
+    }
+
+    fn dependencies_healthy(&self, idx: usize) -> bool {
+        self.services[idx].spec.depends_on.iter().all(|dependency| {
+            self.services
+                .iter()
+                .filter(|service| service.spec.name == *dependency)
+                .all(|service| service.healthy)
+        })
+    }
+
+    fn start(&mut self, idx: usize) -> Result<(), String> {
+        let service = &mut self.services[idx];
+        let child = spawn_service(
+            service.spec,
+            service.index,
+            &service.env,
+            &self.repo_root,
+            &self.run_env,
+        )?;
+        println!("started {} (pid={})", service.spec.name, child.id());
+        if service.address.is_none() {
+            eprintln!(
+                "{}: no [server] port in its {} config; not waiting for health",
+                service.spec.name, self.run_env
+            );
+            service.healthy = true;
+        }
+        service.state = State::Running {
+            child,
+            started_at: Instant::now(),
+        };
+        Ok(())
+    }
+
+    fn kill_all(&mut self) {
+        for service in &mut self.services {
+            if let State::Running { child, .. } = &mut service.state {
+                let _ = child.kill();
+            }
+        }
+    }
+}
+
+impl Drop for Supervisor {
+    fn drop(&mut self) {
+        self.kill_all();
+    }
+}
+
+fn backoff_delay(failures: u32) -> Duration {
+    let factor = 2u32.saturating_pow(failures.saturating_sub(1));
+    INITIAL_BACKOFF.saturating_mul(factor).min(MAX_BACKOFF)
+}
+
+fn spawn_service(
+    service: ServiceSpec,
+    index: usize,
+    env: &[(String, String)],
+    repo_root: &Path,
+    run_env: &str,
+) -> Result<Child, String> {
+    let working_dir = repo_root.join(service.working_dir);
+    let mut command = Command::new("cargo");
+    command
+        .arg("run")
+        .arg("-p")
+        .arg(service.package)
+        .arg("--bin")
+        .arg(service.bin);
+    if let Some(feature) = service.feature {
+        command.arg("--features").arg(feature);
+    }
+    if !service.args.is_empty() {
+        command.arg("--").args(service.args);
+    }
+    command
+        .current_dir(&working_dir)
+        .env("RUN_ENV", run_env)
+        .envs(env.iter().map(|(key, value)| (key, value)))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let mut child = command.spawn().map_err(|err| {
+        format!(
+            "could not start {} from `{}`: {err}",
+            service.name,
+            working_dir.display()
+        )
+    })?;
+    let prefix = prefix(service.name, index);
+    if let Some(stdout) = child.stdout.take() {
+        forward_lines(stdout, prefix.clone(), false);
+    }
+    if let Some(stderr) = child.stderr.take() {
+        forward_lines(stderr, prefix, true);
+    }
+    Ok(child)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_and_caps() {
+        assert_eq!(backoff_delay(1), Duration::from_secs(1));
+        assert_eq!(backoff_delay(2), Duration::from_secs(2));
+        assert_eq!(backoff_delay(4), Duration::from_secs(8));
+        assert_eq!(backoff_delay(20), MAX_BACKOFF);
+    }
+}
@@ -0,0 +1,167 @@
+//! Log correlation shared by every service: the request id of the call being
+//! served, the spans HTTP and gRPC handlers open with it, and sampling for
+//! logs emitted once per streamed frame.
+//!
+//! The request id comes from the `x-request-id` header (HTTP) or metadata
+//! (gRPC), or is generated, and is forwarded on outgoing gRPC calls so the
+//! downstream services log the same id.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use axum::{extract::Request, http::HeaderValue, middleware::Next, response::Response};
+use tonic::metadata::{MetadataMap, MetadataValue};
+use tracing::{Instrument, Span};
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longer caller-provided ids are replaced by a generated one.
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Request id of the call the current task serves, if any.
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// Runs `future` with `request_id` as [`current_request_id`].
+pub async fn with_request_id<F: Future>(request_id: String, future: F) -> F::Output {
+    REQUEST_ID.scope(request_id, future).await
+}
+
+/// Axum middleware: runs the request in an `http_request` span carrying its
+/// request id, and echoes the id in the response.
+pub async fn http_request_span(request: Request, next: Next) -> Response {
+    let request_id = request_id_or_new(
+        request
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok()),
+    );
+    let span = tracing::info_span!(
+        "http_request",
+        request_id = %request_id,
+        method = %request.method(),
+        path = %request.uri().path(),
+    );
+    let mut response =
+        with_request_id(request_id.clone(), next.run(request).instrument(span)).await;
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+/// `grpc_request` span of an incoming call, with its request id and the
+/// session the message names, if any.
+pub fn grpc_request_span(
+    rpc: &'static str,
+    metadata: &MetadataMap,
+    session_id: Option<&str>,
+) -> Span {
+    let request_id = request_id_or_new(
+        metadata
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok()),
+    );
+    tracing::info_span!(
+        "grpc_request",
+        rpc,
+        request_id = %request_id,
+        session_id = session_id.unwrap_or("auto"),
+    )
+}
+
+/// Adds [`current_request_id`] to the metadata of an outgoing call.
+pub fn propagate_request_id(metadata: &mut MetadataMap) {
+    let Some(request_id) = current_request_id() else {
+        return;
+    };
+    if let Ok(value) = MetadataValue::try_from(request_id.as_str()) {
+        metadata.insert(REQUEST_ID_HEADER, value);
+    }
+}
+
+/// Outgoing gRPC request carrying [`current_request_id`].
+pub fn traced_request<T>(message: T) -> tonic::Request<T> {
+    let mut request = tonic::Request::new(message);
+    propagate_request_id(request.metadata_mut());
+    request
+}
+
+/// The caller's id when it is printable ASCII of a sane length, else a new
+/// one.
+pub fn request_id_or_new(provided: Option<&str>) -> String {
+    provided
+        .filter(|id| {
+            !id.is_empty()
+                && id.len() <= MAX_REQUEST_ID_LEN
+                && id.bytes().all(|byte| byte.is_ascii_graphic())
+        })
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string())
+}
+
+/// Lets one in `every` events through, starting with the first, for logs
+/// that would otherwise fire once per streamed frame. `every = 0` lets none
+/// through.
+#[derive(Debug)]
+pub struct LogSampler {
+    every: u64,
+    seen: AtomicU64,
+}
+
+impl LogSampler {
+    pub fn new(every: u64) -> Self {
+        Self {
+            every,
+            seen: AtomicU64::new(0),
+        }
+    }
+
+    pub fn sample(&self) -> bool {
+        self.every != 0 && self.seen.fetch_add(1, Ordering::Relaxed).is_multiple_of(self.every)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sampler_keeps_one_in_every() {
+        let sampler = LogSampler::new(3);
+        let kept: Vec<bool> = (0..7).map(|_| sampler.sample()).collect();
+        assert_eq!(kept, [true, false, false, true, false, false, true]);
+
+        let muted = LogSampler::new(0);
+        assert!(!(0..5).any(|_| muted.sample()));
+    }
+
+    #[test]
+    fn unusable_request_ids_are_replaced() {
+        assert_eq!(request_id_or_new(Some("req-42")), "req-42");
+        for provided in [None, Some(""), Some("has space"), Some(&*"x".repeat(200))] {
+            let id = request_id_or_new(provided);
+            assert!(Uuid::parse_str(&id).is_ok(), "{provided:?} kept as {id}");
+        }
+    }
+
+    #[tokio::test]
+    async fn request_id_is_forwarded_in_its_scope_only() {
+        let mut metadata = MetadataMap::new();
+        propagate_request_id(&mut metadata);
+        assert!(metadata.get(REQUEST_ID_HEADER).is_none());
+
+        with_request_id("req-42".to_string(), async {
+            assert_eq!(current_request_id().as_deref(), Some("req-42"));
+            propagate_request_id(&mut metadata);
+        })
+        .await;
+        assert_eq!(metadata.get(REQUEST_ID_HEADER).unwrap(), "req-42");
+    }
+}
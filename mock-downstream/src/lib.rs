@@ -0,0 +1,343 @@
+//! Model-free stand-ins for the audio, ASR, alignment and tempo services.
+//!
+//! Each mock implements the service's domain port with canned, deterministic
+//! output and is served through the real application use case and gRPC
+//! server, so request validation and the wire format match production while
+//! no GPU or model file is needed.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rustycog_command::GenericCommandService;
+
+pub const MOCK_TRANSCRIPT: &str = "bonjour ceci est une transcription factice";
+
+/// Downstream service a `mock-downstream` process stands in for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MockService {
+    Audio,
+    Asr,
+    Alignment,
+    Tempo,
+}
+
+impl MockService {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim_end_matches("-service") {
+            "audio" => Some(Self::Audio),
+            "asr" => Some(Self::Asr),
+            "alignment" => Some(Self::Alignment),
+            "tempo" => Some(Self::Tempo),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Audio => "audio",
+            Self::Asr => "asr",
+            Self::Alignment => "alignment",
+            Self::Tempo => "tempo",
+        }
+    }
+}
+
+fn duration_ms(sample_count: usize, sample_rate_hz: u32) -> u64 {
+    if sample_rate_hz == 0 {
+        return 0;
+    }
+    sample_count as u64 * 1000 / u64::from(sample_rate_hz)
+}
+
+/// Splits `[start_ms, end_ms)` evenly across `words`.
+fn spread_words(words: &[&str], start_ms: u64, end_ms: u64) -> Vec<(String, u64, u64)> {
+    if words.is_empty() {
+        return Vec::new();
+    }
+    let span = end_ms.saturating_sub(start_ms);
+    let count = words.len() as u64;
+    words
+        .iter()
+        .enumerate()
+        .map(|(index, word)| {
+            let index = index as u64;
+            (
+                (*word).to_string(),
+                start_ms + span * index / count,
+                start_ms + span * (index + 1) / count,
+            )
+        })
+        .collect()
+}
+
+pub mod audio {
+    use super::*;
+    use audio_application::{
+        AudioCommandRegistryFactory, TransformAudioUseCase, TransformAudioUseCaseImpl,
+    };
+    use audio_domain::{
+        AudioTransformPort, AudioTransformRequest, AudioTransformResult, DomainError,
+        SignalLevels, TransformMetadata, DEFAULT_TRANSFORM_OPS,
+    };
+
+    /// Clamps to `[-1, 1]` and resamples by nearest neighbour, whatever the
+    /// requested ops; it only advertises the default recipe.
+    pub struct MockAudioTransform;
+
+    #[async_trait]
+    impl AudioTransformPort for MockAudioTransform {
+        fn transform_names(&self) -> Vec<String> {
+            DEFAULT_TRANSFORM_OPS.iter().map(|name| name.to_string()).collect()
+        }
+
+        async fn transform(
+            &self,
+            request: AudioTransformRequest,
+        ) -> Result<AudioTransformResult, DomainError> {
+            let source = request.source_sample_rate_hz;
+            let target = request.target_sample_rate_hz;
+            if source == 0 || target == 0 {
+                return Err(DomainError::invalid_input("sample rate must be non-zero"));
+            }
+
+            let input_sample_count = request.samples.len();
+            let levels = SignalLevels::measure(&request.samples, source);
+            let output_sample_count =
+                (input_sample_count as u64 * u64::from(target) / u64::from(source)) as usize;
+            let clamped = request.samples.iter().any(|sample| sample.abs() > 1.0);
+            let samples = (0..output_sample_count)
+                .map(|index| {
+                    let source_index = index as u64 * u64::from(source) / u64::from(target);
+                    request.samples[source_index as usize].clamp(-1.0, 1.0)
+                })
+                .collect();
+
+            Ok(AudioTransformResult {
+                samples,
+                sample_rate_hz: target,
+                metadata: TransformMetadata {
+                    clamped,
+                    resampled: source != target,
+                    input_sample_count,
+                    output_sample_count,
+                    source_sample_rate_hz: source,
+                    target_sample_rate_hz: target,
+                    peak_level: levels.peak_level,
+                    rms: levels.rms,
+                    clipped_sample_count: levels.clipped_sample_count,
+                    estimated_snr_db: levels.estimated_snr_db,
+                    detected_sample_rate_hz: None,
+                    detected_channels: None,
+                    applied_ops: request.ops,
+                },
+            })
+        }
+    }
+
+    pub fn command_service(default_sample_rate_hz: u32) -> Arc<GenericCommandService> {
+        let usecase: Arc<dyn TransformAudioUseCase> = Arc::new(TransformAudioUseCaseImpl::new(
+            Arc::new(MockAudioTransform),
+            default_sample_rate_hz,
+        ));
+        let registry = AudioCommandRegistryFactory::create_registry(usecase);
+        Arc::new(GenericCommandService::new(Arc::new(registry)))
+    }
+}
+
+pub mod asr {
+    use super::*;
+    use asr_application::{AsrCommandRegistryFactory, AsrUseCase, AsrUseCaseImpl};
+    use asr_domain::{
+        DomainError, LanguageTag, Transcript, TranscriptSegment, TranscriptToken,
+        TranscriptionOutput, TranscriptionPort, TranscriptionRequest,
+    };
+
+    /// Answers every request with [`MOCK_TRANSCRIPT`] spread over the audio.
+    pub struct MockTranscription;
+
+    #[async_trait]
+    impl TranscriptionPort for MockTranscription {
+        async fn transcribe(
+            &self,
+            request: TranscriptionRequest,
+        ) -> Result<TranscriptionOutput, DomainError> {
+            let end_ms = duration_ms(request.audio.samples.len(), request.audio.sample_rate_hz);
+            let words: Vec<&str> = MOCK_TRANSCRIPT.split_whitespace().collect();
+            let detected_language = match request.language_hint {
+                None | Some(LanguageTag::Auto) => Some(LanguageTag::fr()),
+                Some(_) => None,
+            };
+            let language = detected_language
+                .clone()
+                .or(request.language_hint)
+                .unwrap_or(LanguageTag::Auto);
+
+            Ok(TranscriptionOutput {
+                transcript: Transcript {
+                    language,
+                    segments: vec![TranscriptSegment {
+                        text: MOCK_TRANSCRIPT.to_string(),
+                        start_ms: 0,
+                        end_ms,
+                        tokens: spread_words(&words, 0, end_ms)
+                            .into_iter()
+                            .map(|(text, start_ms, end_ms)| TranscriptToken {
+                                text,
+                                start_ms,
+                                end_ms,
+                                confidence: 1.0,
+                                start_sample: None,
+                                end_sample: None,
+                                disfluency: false,
+                            })
+                            .collect(),
+                        speaker: None,
+                        language: None,
+                        no_speech_prob: None,
+                        avg_logprob: None,
+                    }],
+                },
+                language_probability: detected_language.as_ref().map(|_| 1.0),
+                detected_language,
+            })
+        }
+    }
+
+    pub fn command_service(sample_rate_hz: u32) -> Arc<GenericCommandService> {
+        let usecase: Arc<dyn AsrUseCase> = Arc::new(AsrUseCaseImpl::new(
+            Arc::new(MockTranscription),
+            sample_rate_hz,
+        ));
+        let registry = AsrCommandRegistryFactory::create_registry(usecase);
+        Arc::new(GenericCommandService::new(Arc::new(registry)))
+    }
+}
+
+pub mod alignment {
+    use super::*;
+    use alignment_application::{
+        AlignTranscriptUseCase, AlignTranscriptUseCaseImpl, AlignmentCommandRegistryFactory,
+    };
+    use alignment_domain::{
+        AlignmentOutput, AlignmentPort, AlignmentRequest, DomainError, WordTiming,
+    };
+
+    /// Spreads each segment's words evenly across the segment.
+    pub struct MockAligner;
+
+    #[async_trait]
+    impl AlignmentPort for MockAligner {
+        async fn align(&self, request: AlignmentRequest) -> Result<AlignmentOutput, DomainError> {
+            let words = request
+                .transcript
+                .segments
+                .iter()
+                .flat_map(|segment| {
+                    let words: Vec<&str> = segment.text.split_whitespace().collect();
+                    spread_words(&words, segment.start_ms, segment.end_ms)
+                })
+                .map(|(word, start_ms, end_ms)| WordTiming {
+                    word,
+                    start_ms,
+                    end_ms,
+                    confidence: 1.0,
+                    speaker: None,
+                    start_sample: None,
+                    end_sample: None,
+                    energy_rms: None,
+                    pitch_hz: None,
+                    out_of_band: false,
+                })
+                .collect();
+            Ok(AlignmentOutput {
+                words,
+                resampled_from_hz: None,
+            })
+        }
+    }
+
+    pub fn command_service(default_sample_rate_hz: u32) -> Arc<GenericCommandService> {
+        let usecase: Arc<dyn AlignTranscriptUseCase> = Arc::new(AlignTranscriptUseCaseImpl::new(
+            Arc::new(MockAligner),
+            default_sample_rate_hz,
+        ));
+        let registry = AlignmentCommandRegistryFactory::create_registry(usecase);
+        Arc::new(GenericCommandService::new(Arc::new(registry)))
+    }
+}
+
+pub mod tempo {
+    use super::*;
+    use tempo_application::{
+        TempoCommandRegistryFactory, TempoMatchUseCase, TempoMatchUseCaseImpl,
+    };
+    use tempo_domain::{DomainError, TempoMatchOutput, TempoMatchPort, TempoMatchRequest};
+
+    /// Returns the TTS audio untouched.
+    pub struct MockTempoMatcher;
+
+    #[async_trait]
+    impl TempoMatchPort for MockTempoMatcher {
+        async fn match_tempo(
+            &self,
+            request: TempoMatchRequest,
+        ) -> Result<TempoMatchOutput, DomainError> {
+            Ok(TempoMatchOutput {
+                samples: request.tts_samples,
+                sample_rate_hz: request.tts_sample_rate_hz,
+            })
+        }
+    }
+
+    pub fn command_service(default_sample_rate_hz: u32) -> Arc<GenericCommandService> {
+        let usecase: Arc<dyn TempoMatchUseCase> = Arc::new(TempoMatchUseCaseImpl::new(
+            Arc::new(MockTempoMatcher),
+            default_sample_rate_hz,
+        ));
+        let registry = TempoCommandRegistryFactory::create_registry(usecase);
+        Arc::new(GenericCommandService::new(Arc::new(registry)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn service_names_accept_the_service_suffix() {
+        assert_eq!(MockService::parse("asr"), Some(MockService::Asr));
+        assert_eq!(MockService::parse("tempo-service"), Some(MockService::Tempo));
+        assert_eq!(MockService::parse("tts"), None);
+    }
+
+    #[test]
+    fn words_cover_the_span_without_gaps() {
+        let words = spread_words(&["a", "b", "c"], 100, 400);
+        assert_eq!(
+            words,
+            vec![
+                ("a".to_string(), 100, 200),
+                ("b".to_string(), 200, 300),
+                ("c".to_string(), 300, 400),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn mock_audio_resamples_and_clamps() {
+        use audio_domain::{AudioTransformPort, AudioTransformRequest};
+
+        let result = audio::MockAudioTransform
+            .transform(AudioTransformRequest {
+                samples: vec![0.5, 2.0, -3.0, 0.0],
+                source_sample_rate_hz: 32_000,
+                target_sample_rate_hz: 16_000,
+                ops: Vec::new(),
+            })
+            .await
+            .expect("transform");
+        assert_eq!(result.samples, vec![0.5, -1.0]);
+        assert!(result.metadata.clamped);
+        assert!(result.metadata.resampled);
+    }
+}
@@ -0,0 +1,66 @@
+use std::env;
+use std::sync::Arc;
+
+use mock_downstream::{alignment, asr, audio, tempo, MockService};
+use service_health::AlwaysReady;
+
+/// Serves one mock downstream service. Run it from that service's directory:
+/// it reads the service's own `config/<RUN_ENV>.toml` (and environment
+/// overrides), so it binds the same port the real service would.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let service = env::args()
+        .nth(1)
+        .as_deref()
+        .and_then(MockService::parse)
+        .ok_or("usage: mock-downstream <audio|asr|alignment|tempo>")?;
+
+    match service {
+        MockService::Audio => {
+            let config = audio_configuration::load_config()?;
+            audio_configuration::setup_logging(&config);
+            log_start(service);
+            let command_service = audio::command_service(config.transformations.sample_rate_hz);
+            audio_grpc_server::serve_grpc(command_service, config.server, Arc::new(AlwaysReady))
+                .await?;
+        }
+        MockService::Asr => {
+            let config = asr_configuration::load_config()?;
+            asr_configuration::setup_logging(&config);
+            log_start(service);
+            let command_service = asr::command_service(config.service.audio.sample_rate_hz);
+            asr_grpc_server::serve_grpc(command_service, config.server, Arc::new(AlwaysReady))
+                .await?;
+        }
+        MockService::Alignment => {
+            let config = alignment_configuration::load_config()?;
+            alignment_configuration::setup_logging(&config);
+            log_start(service);
+            let command_service = alignment::command_service(config.alignment.sample_rate_hz);
+            alignment_grpc_server::serve_grpc(
+                command_service,
+                config.server,
+                Arc::new(AlwaysReady),
+                config.alignment.sample_rate_hz,
+            )
+            .await?;
+        }
+        MockService::Tempo => {
+            let config = tempo_configuration::load_config()?;
+            tempo_configuration::setup_logging(&config);
+            log_start(service);
+            let command_service = tempo::command_service(config.tempo.sample_rate_hz);
+            tempo_grpc_server::serve_grpc(command_service, config.server, Arc::new(AlwaysReady))
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+fn log_start(service: MockService) {
+    tracing::warn!(
+        service = service.name(),
+        "serving MOCK {} service: responses are canned, no model is loaded",
+        service.name()
+    );
+}
@@ -0,0 +1,248 @@
+//! Startup checks for model files shared by the ASR and alignment services.
+//!
+//! Each configured file is described by a [`ModelArtifact`]. At startup the
+//! [`ModelManager`] makes sure the file exists, downloads it from its
+//! [`ModelSource`] when missing, and verifies its SHA-256 checksum when one is
+//! configured. A service then fails at boot instead of on its first request.
+
+mod checksum;
+mod source;
+
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+use tokio::io::AsyncWriteExt;
+
+pub use checksum::sha256_file;
+pub use source::ModelSource;
+
+#[derive(Debug, Error)]
+pub enum ModelError {
+    #[error("model `{name}` not found at {path} and no download source is configured")]
+    Missing { name: String, path: PathBuf },
+
+    #[error("model `{name}` at {path} has sha256 {actual}, expected {expected}")]
+    ChecksumMismatch {
+        name: String,
+        path: PathBuf,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("invalid model source `{0}`")]
+    InvalidSource(String),
+
+    #[error("failed to download {url}: {message}")]
+    Download { url: String, message: String },
+
+    #[error("i/o error on {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct ModelArtifact {
+    /// Label used in logs and errors, e.g. `whisper` or `wav2vec2-vocab`.
+    pub name: String,
+    pub path: PathBuf,
+    /// Expected lowercase hex SHA-256. Skips verification when `None`.
+    pub sha256: Option<String>,
+    pub source: Option<ModelSource>,
+}
+
+impl ModelArtifact {
+    pub fn new(name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        Self {
+            name: name.into(),
+            path: path.into(),
+            sha256: None,
+            source: None,
+        }
+    }
+
+    pub fn with_sha256(mut self, sha256: Option<String>) -> Self {
+        self.sha256 = sha256
+            .map(|value| value.trim().to_ascii_lowercase())
+            .filter(|value| !value.is_empty());
+        self
+    }
+
+    pub fn with_source(mut self, source: Option<ModelSource>) -> Self {
+        self.source = source;
+        self
+    }
+}
+
+pub struct ModelManager {
+    client: reqwest::Client,
+    download_missing: bool,
+}
+
+impl ModelManager {
+    /// `download_missing = false` turns every missing file into
+    /// [`ModelError::Missing`], even when a source is configured.
+    pub fn new(download_missing: bool) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            download_missing,
+        }
+    }
+
+    pub async fn ensure_all(&self, artifacts: &[ModelArtifact]) -> Result<(), ModelError> {
+        for artifact in artifacts {
+            self.ensure(artifact).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn ensure(&self, artifact: &ModelArtifact) -> Result<(), ModelError> {
+        if artifact.path.is_file() {
+            return verify(artifact, &artifact.path).await;
+        }
+
+        let source = artifact
+            .source
+            .as_ref()
+            .filter(|_| self.download_missing)
+            .ok_or_else(|| ModelError::Missing {
+                name: artifact.name.clone(),
+                path: artifact.path.clone(),
+            })?;
+        let partial = partial_path(&artifact.path);
+        self.download(&source.url(), &partial).await?;
+        if let Err(err) = verify(artifact, &partial).await {
+            let _ = tokio::fs::remove_file(&partial).await;
+            return Err(err);
+        }
+        tokio::fs::rename(&partial, &artifact.path)
+            .await
+            .map_err(|source| ModelError::Io {
+                path: artifact.path.clone(),
+                source,
+            })?;
+        tracing::info!(model = %artifact.name, path = %artifact.path.display(), "model downloaded");
+        Ok(())
+    }
+
+    async fn download(&self, url: &str, destination: &Path) -> Result<(), ModelError> {
+        let download_error = |message: String| ModelError::Download {
+            url: url.to_string(),
+            message,
+        };
+        let io_error = |source| ModelError::Io {
+            path: destination.to_path_buf(),
+            source,
+        };
+
+        tracing::info!(%url, path = %destination.display(), "downloading model");
+        let mut response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|err| download_error(err.to_string()))?;
+
+        if let Some(parent) = destination.parent().filter(|p| !p.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent).await.map_err(io_error)?;
+        }
+        let mut file = tokio::fs::File::create(destination)
+            .await
+            .map_err(io_error)?;
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|err| download_error(err.to_string()))?
+        {
+            file.write_all(&chunk).await.map_err(io_error)?;
+        }
+        file.flush().await.map_err(io_error)
+    }
+}
+
+async fn verify(artifact: &ModelArtifact, path: &Path) -> Result<(), ModelError> {
+    let Some(expected) = &artifact.sha256 else {
+        return Ok(());
+    };
+    let owned = path.to_path_buf();
+    let actual = tokio::task::spawn_blocking(move || sha256_file(&owned))
+        .await
+        .map_err(|err| ModelError::Io {
+            path: path.to_path_buf(),
+            source: std::io::Error::other(err),
+        })?
+        .map_err(|source| ModelError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+    if &actual != expected {
+        return Err(ModelError::ChecksumMismatch {
+            name: artifact.name.clone(),
+            path: artifact.path.clone(),
+            expected: expected.clone(),
+            actual,
+        });
+    }
+    Ok(())
+}
+
+fn partial_path(path: &Path) -> PathBuf {
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".part");
+    PathBuf::from(partial)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HELLO_SHA256: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+    fn temp_file(name: &str, contents: &[u8]) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("model-manager-{}-{name}", std::process::id()));
+        std::fs::write(&path, contents).expect("temp file should be writable");
+        path
+    }
+
+    #[tokio::test]
+    async fn existing_file_with_matching_checksum_passes() {
+        let path = temp_file("match", b"hello");
+        let artifact =
+            ModelArtifact::new("test", &path).with_sha256(Some(HELLO_SHA256.to_uppercase()));
+
+        ModelManager::new(false)
+            .ensure(&artifact)
+            .await
+            .expect("checksum should match");
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn checksum_mismatch_is_rejected() {
+        let path = temp_file("mismatch", b"hello!");
+        let artifact = ModelArtifact::new("test", &path).with_sha256(Some(HELLO_SHA256.into()));
+
+        let error = ModelManager::new(false)
+            .ensure(&artifact)
+            .await
+            .expect_err("checksum should not match");
+        assert!(matches!(error, ModelError::ChecksumMismatch { .. }));
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn missing_file_without_download_fails() {
+        let artifact = ModelArtifact::new("test", "/nonexistent/model.bin")
+            .with_source(Some(ModelSource::parse("https://example.invalid/model.bin").unwrap()));
+
+        let error = ModelManager::new(false)
+            .ensure(&artifact)
+            .await
+            .expect_err("download is disabled");
+        assert!(matches!(error, ModelError::Missing { .. }));
+    }
+}
@@ -0,0 +1,92 @@
+use crate::ModelError;
+
+const HUGGING_FACE_PREFIX: &str = "hf://";
+
+/// Where a missing model file is downloaded from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModelSource {
+    Url(String),
+    HuggingFace {
+        repo: String,
+        revision: String,
+        file: String,
+    },
+}
+
+impl ModelSource {
+    /// Parses `http(s)://...` URLs and `hf://owner/repo/path/to/file[@revision]`
+    /// references. The revision defaults to `main`.
+    pub fn parse(raw: &str) -> Result<Self, ModelError> {
+        let raw = raw.trim();
+        if let Some(reference) = raw.strip_prefix(HUGGING_FACE_PREFIX) {
+            let (path, revision) = match reference.rsplit_once('@') {
+                Some((path, revision)) if !revision.is_empty() => (path, revision),
+                _ => (reference, "main"),
+            };
+            let mut parts = path.splitn(3, '/');
+            return match (parts.next(), parts.next(), parts.next()) {
+                (Some(owner), Some(name), Some(file))
+                    if !owner.is_empty() && !name.is_empty() && !file.is_empty() =>
+                {
+                    Ok(Self::HuggingFace {
+                        repo: format!("{owner}/{name}"),
+                        revision: revision.to_string(),
+                        file: file.to_string(),
+                    })
+                }
+                _ => Err(ModelError::InvalidSource(raw.to_string())),
+            };
+        }
+        if raw.starts_with("http://") || raw.starts_with("https://") {
+            return Ok(Self::Url(raw.to_string()));
+        }
+        Err(ModelError::InvalidSource(raw.to_string()))
+    }
+
+    pub fn url(&self) -> String {
+        match self {
+            Self::Url(url) => url.clone(),
+            Self::HuggingFace {
+                repo,
+                revision,
+                file,
+            } => format!("https://huggingface.co/{repo}/resolve/{revision}/{file}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hugging_face_reference_resolves_to_download_url() {
+        let source = ModelSource::parse("hf://ggerganov/whisper.cpp/ggml-base.bin").unwrap();
+        assert_eq!(
+            source.url(),
+            "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base.bin"
+        );
+    }
+
+    #[test]
+    fn hugging_face_revision_is_honoured() {
+        let source = ModelSource::parse("hf://owner/repo/onnx/model.onnx@v2").unwrap();
+        assert_eq!(
+            source,
+            ModelSource::HuggingFace {
+                repo: "owner/repo".to_string(),
+                revision: "v2".to_string(),
+                file: "onnx/model.onnx".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn unknown_scheme_is_rejected() {
+        assert!(matches!(
+            ModelSource::parse("ftp://host/model.bin"),
+            Err(ModelError::InvalidSource(_))
+        ));
+        assert!(ModelSource::parse("hf://owner/repo").is_err());
+    }
+}
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rustycog_command::{Command, CommandError, CommandHandler};
+use uuid::Uuid;
+
+use crate::{CorrectTranscriptRequest, CorrectTranscriptResponse, CorrectionUseCase};
+
+#[derive(Debug, Clone)]
+pub struct CorrectTranscriptCommand {
+    id: Uuid,
+    pub request: CorrectTranscriptRequest,
+}
+
+impl CorrectTranscriptCommand {
+    pub fn new(request: CorrectTranscriptRequest) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            request,
+        }
+    }
+}
+
+impl Command for CorrectTranscriptCommand {
+    type Result = CorrectTranscriptResponse;
+
+    fn command_type(&self) -> &'static str {
+        "correct_transcript"
+    }
+
+    fn command_id(&self) -> Uuid {
+        self.id
+    }
+
+    fn validate(&self) -> Result<(), CommandError> {
+        if self.request.session_id.is_empty() {
+            return Err(CommandError::validation(
+                "session_id_missing",
+                "session_id is required",
+            ));
+        }
+        Ok(())
+    }
+}
+
+pub struct CorrectTranscriptCommandHandler {
+    usecase: Arc<dyn CorrectionUseCase>,
+}
+
+impl CorrectTranscriptCommandHandler {
+    pub fn new(usecase: Arc<dyn CorrectionUseCase>) -> Self {
+        Self { usecase }
+    }
+}
+
+#[async_trait]
+impl CommandHandler<CorrectTranscriptCommand> for CorrectTranscriptCommandHandler {
+    async fn handle(
+        &self,
+        command: CorrectTranscriptCommand,
+    ) -> Result<CorrectTranscriptResponse, CommandError> {
+        self.usecase
+            .correct(command.request)
+            .await
+            .map_err(CommandError::from)
+    }
+}
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rustycog_command::{Command, CommandError, CommandHandler};
+use uuid::Uuid;
+
+use crate::{PipelineCatalogUseCase, PipelineDescription};
+
+#[derive(Debug, Clone)]
+pub struct DescribePipelineCommand {
+    id: Uuid,
+    pub name: String,
+}
+
+impl DescribePipelineCommand {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name: name.into(),
+        }
+    }
+}
+
+impl Command for DescribePipelineCommand {
+    type Result = PipelineDescription;
+
+    fn command_type(&self) -> &'static str {
+        "describe_pipeline"
+    }
+
+    fn command_id(&self) -> Uuid {
+        self.id
+    }
+
+    fn validate(&self) -> Result<(), CommandError> {
+        if self.name.is_empty() {
+            return Err(CommandError::validation(
+                "pipeline_name_missing",
+                "pipeline name is required",
+            ));
+        }
+        Ok(())
+    }
+}
+
+pub struct DescribePipelineCommandHandler {
+    usecase: Arc<dyn PipelineCatalogUseCase>,
+}
+
+impl DescribePipelineCommandHandler {
+    pub fn new(usecase: Arc<dyn PipelineCatalogUseCase>) -> Self {
+        Self { usecase }
+    }
+}
+
+#[async_trait]
+impl CommandHandler<DescribePipelineCommand> for DescribePipelineCommandHandler {
+    async fn handle(
+        &self,
+        command: DescribePipelineCommand,
+    ) -> Result<PipelineDescription, CommandError> {
+        self.usecase
+            .describe(&command.name)
+            .await
+            .map_err(CommandError::from)
+    }
+}
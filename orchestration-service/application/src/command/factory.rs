@@ -4,14 +4,41 @@ use std::time::Duration;
 use rustycog_command::{CommandRegistry, CommandRegistryBuilder, RegistryConfig, RetryPolicy};
 
 use crate::{
-    AsrCommandErrorMapper, AsrUseCase, TranscribeAudioCommand, TranscribeAudioCommandHandler,
+    AsrCommandErrorMapper, AsrUseCase, CorrectTranscriptCommand, CorrectTranscriptCommandHandler,
+    CorrectionUseCase, DescribePipelineCommand, DescribePipelineCommandHandler, GetSessionCommand,
+    GetSessionCommandHandler, ListPipelinesCommand, ListPipelinesCommandHandler,
+    PipelineCatalogUseCase, TranscribeAudioCommand, TranscribeAudioCommandHandler,
+    TranscribeBatchCommand, TranscribeBatchCommandHandler,
 };
 
+/// Limits of `transcribe_batch` commands.
+#[derive(Debug, Clone, Copy)]
+pub struct BatchLimits {
+    pub max_items: usize,
+    pub max_concurrency: usize,
+}
+
 pub struct AsrCommandRegistryFactory;
 
 impl AsrCommandRegistryFactory {
-    pub fn create_registry(asr_usecase: Arc<dyn AsrUseCase>) -> CommandRegistry {
-        let handler = Arc::new(TranscribeAudioCommandHandler::new(asr_usecase));
+    pub fn create_registry(
+        asr_usecase: Arc<dyn AsrUseCase>,
+        correction_usecase: Arc<dyn CorrectionUseCase>,
+        pipeline_catalog: Arc<dyn PipelineCatalogUseCase>,
+        batch_limits: BatchLimits,
+    ) -> CommandRegistry {
+        let handler = Arc::new(TranscribeAudioCommandHandler::new(asr_usecase.clone()));
+        let batch_handler = Arc::new(TranscribeBatchCommandHandler::new(
+            asr_usecase,
+            batch_limits.max_items,
+            batch_limits.max_concurrency,
+        ));
+        let session_handler = Arc::new(GetSessionCommandHandler::new(correction_usecase.clone()));
+        let correction_handler = Arc::new(CorrectTranscriptCommandHandler::new(correction_usecase));
+        let list_pipelines_handler =
+            Arc::new(ListPipelinesCommandHandler::new(pipeline_catalog.clone()));
+        let describe_pipeline_handler =
+            Arc::new(DescribePipelineCommandHandler::new(pipeline_catalog));
         let error_mapper = Arc::new(AsrCommandErrorMapper);
 
         let config = RegistryConfig {
@@ -27,6 +54,31 @@ impl AsrCommandRegistryFactory {
             .register::<TranscribeAudioCommand, _>(
                 "transcribe_audio".to_string(),
                 handler,
+                error_mapper.clone(),
+            )
+            .register::<TranscribeBatchCommand, _>(
+                "transcribe_batch".to_string(),
+                batch_handler,
+                error_mapper.clone(),
+            )
+            .register::<CorrectTranscriptCommand, _>(
+                "correct_transcript".to_string(),
+                correction_handler,
+                error_mapper.clone(),
+            )
+            .register::<GetSessionCommand, _>(
+                "get_session".to_string(),
+                session_handler,
+                error_mapper.clone(),
+            )
+            .register::<ListPipelinesCommand, _>(
+                "list_pipelines".to_string(),
+                list_pipelines_handler,
+                error_mapper.clone(),
+            )
+            .register::<DescribePipelineCommand, _>(
+                "describe_pipeline".to_string(),
+                describe_pipeline_handler,
                 error_mapper,
             )
             .build()
@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rustycog_command::{Command, CommandError, CommandHandler};
+use uuid::Uuid;
+
+use crate::{CorrectTranscriptResponse, CorrectionUseCase};
+
+#[derive(Debug, Clone)]
+pub struct GetSessionCommand {
+    id: Uuid,
+    pub session_id: String,
+    pub tenant_id: Option<String>,
+}
+
+impl GetSessionCommand {
+    pub fn new(session_id: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            session_id: session_id.into(),
+            tenant_id: None,
+        }
+    }
+
+    /// Looks the session up among this tenant's sessions only.
+    pub fn with_tenant(mut self, tenant_id: Option<String>) -> Self {
+        self.tenant_id = tenant_id;
+        self
+    }
+}
+
+impl Command for GetSessionCommand {
+    type Result = CorrectTranscriptResponse;
+
+    fn command_type(&self) -> &'static str {
+        "get_session"
+    }
+
+    fn command_id(&self) -> Uuid {
+        self.id
+    }
+
+    fn validate(&self) -> Result<(), CommandError> {
+        if self.session_id.is_empty() {
+            return Err(CommandError::validation(
+                "session_id_missing",
+                "session_id is required",
+            ));
+        }
+        Ok(())
+    }
+}
+
+pub struct GetSessionCommandHandler {
+    usecase: Arc<dyn CorrectionUseCase>,
+}
+
+impl GetSessionCommandHandler {
+    pub fn new(usecase: Arc<dyn CorrectionUseCase>) -> Self {
+        Self { usecase }
+    }
+}
+
+#[async_trait]
+impl CommandHandler<GetSessionCommand> for GetSessionCommandHandler {
+    async fn handle(
+        &self,
+        command: GetSessionCommand,
+    ) -> Result<CorrectTranscriptResponse, CommandError> {
+        self.usecase
+            .session(command.tenant_id.as_deref(), &command.session_id)
+            .await
+            .map_err(CommandError::from)
+    }
+}
@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rustycog_command::{Command, CommandError, CommandHandler};
+use uuid::Uuid;
+
+use crate::{PipelineCatalog, PipelineCatalogUseCase};
+
+#[derive(Debug, Clone)]
+pub struct ListPipelinesCommand {
+    id: Uuid,
+}
+
+impl ListPipelinesCommand {
+    pub fn new() -> Self {
+        Self { id: Uuid::new_v4() }
+    }
+}
+
+impl Default for ListPipelinesCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Command for ListPipelinesCommand {
+    type Result = PipelineCatalog;
+
+    fn command_type(&self) -> &'static str {
+        "list_pipelines"
+    }
+
+    fn command_id(&self) -> Uuid {
+        self.id
+    }
+
+    fn validate(&self) -> Result<(), CommandError> {
+        Ok(())
+    }
+}
+
+pub struct ListPipelinesCommandHandler {
+    usecase: Arc<dyn PipelineCatalogUseCase>,
+}
+
+impl ListPipelinesCommandHandler {
+    pub fn new(usecase: Arc<dyn PipelineCatalogUseCase>) -> Self {
+        Self { usecase }
+    }
+}
+
+#[async_trait]
+impl CommandHandler<ListPipelinesCommand> for ListPipelinesCommandHandler {
+    async fn handle(
+        &self,
+        _command: ListPipelinesCommand,
+    ) -> Result<PipelineCatalog, CommandError> {
+        self.usecase.list().await.map_err(CommandError::from)
+    }
+}
@@ -1,7 +1,17 @@
+mod correct_transcript;
+mod describe_pipeline;
 mod factory;
+mod get_session;
+mod list_pipelines;
 mod transcribe_audio;
+mod transcribe_batch;
 
-pub use factory::AsrCommandRegistryFactory;
+pub use correct_transcript::{CorrectTranscriptCommand, CorrectTranscriptCommandHandler};
+pub use describe_pipeline::{DescribePipelineCommand, DescribePipelineCommandHandler};
+pub use factory::{AsrCommandRegistryFactory, BatchLimits};
+pub use get_session::{GetSessionCommand, GetSessionCommandHandler};
+pub use list_pipelines::{ListPipelinesCommand, ListPipelinesCommandHandler};
 pub use transcribe_audio::{
     AsrCommandErrorMapper, TranscribeAudioCommand, TranscribeAudioCommandHandler,
 };
+pub use transcribe_batch::{TranscribeBatchCommand, TranscribeBatchCommandHandler};
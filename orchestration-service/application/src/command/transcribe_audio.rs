@@ -33,12 +33,18 @@ impl Command for TranscribeAudioCommand {
     }
 
     fn validate(&self) -> Result<(), CommandError> {
-        if self.request.samples.is_empty() {
+        if self.request.samples.is_empty() && self.request.audio_url.is_none() {
             return Err(CommandError::validation(
                 "samples_missing",
                 "samples must contain at least one frame",
             ));
         }
+        if !self.request.samples.is_empty() && self.request.audio_url.is_some() {
+            return Err(CommandError::validation(
+                "samples_and_audio_url",
+                "send either `samples` or `audio_url`, not both",
+            ));
+        }
         Ok(())
     }
 }
@@ -0,0 +1,227 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use rustycog_command::{Command, CommandError, CommandHandler};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    ApplicationError, AsrUseCase, BatchItemError, TranscribeBatchItem, TranscribeBatchItemResult,
+    TranscribeBatchRequest, TranscribeBatchResponse,
+};
+
+#[derive(Debug, Clone)]
+pub struct TranscribeBatchCommand {
+    id: Uuid,
+    pub request: TranscribeBatchRequest,
+}
+
+impl TranscribeBatchCommand {
+    pub fn new(request: TranscribeBatchRequest) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            request,
+        }
+    }
+}
+
+impl Command for TranscribeBatchCommand {
+    type Result = TranscribeBatchResponse;
+
+    fn command_type(&self) -> &'static str {
+        "transcribe_batch"
+    }
+
+    fn command_id(&self) -> Uuid {
+        self.id
+    }
+
+    fn validate(&self) -> Result<(), CommandError> {
+        if self.request.items.is_empty() {
+            return Err(CommandError::validation(
+                "items_missing",
+                "batch must contain at least one item",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Runs the items' pipelines with at most `max_concurrency` in flight.
+pub struct TranscribeBatchCommandHandler {
+    usecase: Arc<dyn AsrUseCase>,
+    max_items: usize,
+    max_concurrency: usize,
+}
+
+impl TranscribeBatchCommandHandler {
+    pub fn new(usecase: Arc<dyn AsrUseCase>, max_items: usize, max_concurrency: usize) -> Self {
+        Self {
+            usecase,
+            max_items,
+            max_concurrency: max_concurrency.max(1),
+        }
+    }
+
+    async fn run_item(&self, item: TranscribeBatchItem) -> TranscribeBatchItemResult {
+        let outcome = match item.request.validate() {
+            Ok(()) => self.usecase.transcribe(item.request).await,
+            Err(err) => Err(ApplicationError::Validation(err.to_string())),
+        };
+        match outcome {
+            Ok(response) => TranscribeBatchItemResult {
+                id: item.id,
+                result: Some(response),
+                error: None,
+            },
+            Err(err) => {
+                tracing::warn!(item_id = %item.id, error = %err, "batch item failed");
+                TranscribeBatchItemResult {
+                    id: item.id,
+                    result: None,
+                    error: Some(BatchItemError {
+                        code: err.error_code().to_string(),
+                        message: err.to_string(),
+                    }),
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl CommandHandler<TranscribeBatchCommand> for TranscribeBatchCommandHandler {
+    async fn handle(
+        &self,
+        command: TranscribeBatchCommand,
+    ) -> Result<TranscribeBatchResponse, CommandError> {
+        let items = command.request.items;
+        if items.len() > self.max_items {
+            return Err(CommandError::validation(
+                "too_many_items",
+                format!("batch holds {} items; the limit is {}", items.len(), self.max_items),
+            ));
+        }
+
+        let results = stream::iter(items)
+            .map(|item| self.run_item(item))
+            .buffered(self.max_concurrency)
+            .collect()
+            .await;
+        Ok(TranscribeBatchResponse { results })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use orchestration_domain::{LanguageTag, Transcript};
+
+    use super::*;
+    use crate::{TranscribeAudioRequest, TranscribeAudioResponse};
+
+    #[derive(Default)]
+    struct CountingUseCase {
+        in_flight: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl AsrUseCase for CountingUseCase {
+        async fn transcribe(
+            &self,
+            request: TranscribeAudioRequest,
+        ) -> Result<TranscribeAudioResponse, ApplicationError> {
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(TranscribeAudioResponse {
+                session_id: request.session_id.unwrap_or_default(),
+                transcript: Transcript {
+                    language: LanguageTag::en(),
+                    segments: Vec::new(),
+                },
+                aligned_words: Vec::new(),
+                pauses: Vec::new(),
+                quality_issues: Vec::new(),
+                detected_language: None,
+                language_probability: None,
+                text: String::new(),
+                tts_output: None,
+                output_audio: None,
+                channels: Vec::new(),
+            })
+        }
+    }
+
+    fn item(id: &str, samples: Vec<f32>) -> TranscribeBatchItem {
+        TranscribeBatchItem {
+            id: id.to_string(),
+            request: TranscribeAudioRequest {
+                samples,
+                audio_url: None,
+                sample_rate_hz: Some(16_000),
+                language_hint: None,
+                session_id: Some(id.to_string()),
+                model: None,
+                channels: None,
+                pipeline: None,
+                transcript: None,
+                decode_profile: None,
+                grammar: None,
+                prompt: None,
+                time_offset_ms: None,
+                tenant_id: None,
+            },
+        }
+    }
+
+    #[test]
+    fn item_ids_are_validated_with_the_batch() {
+        let batch = |id: &str| TranscribeBatchRequest {
+            items: vec![item("ok", vec![0.0]), item(id, vec![0.0])],
+        };
+
+        assert!(batch(&"x".repeat(128)).validate().is_ok());
+        assert!(batch("").validate().is_err());
+        assert!(batch(&"x".repeat(129)).validate().is_err());
+        assert!(TranscribeBatchRequest { items: Vec::new() }.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn items_run_bounded_and_fail_individually() {
+        let usecase = Arc::new(CountingUseCase::default());
+        let handler = TranscribeBatchCommandHandler::new(usecase.clone(), 8, 2);
+        let mut items: Vec<_> = (0..5).map(|i| item(&format!("a{i}"), vec![0.0; 160])).collect();
+        items.insert(2, item("empty", Vec::new()));
+
+        let response = handler
+            .handle(TranscribeBatchCommand::new(TranscribeBatchRequest { items }))
+            .await
+            .expect("batch runs");
+
+        let ids: Vec<_> = response.results.iter().map(|result| result.id.as_str()).collect();
+        assert_eq!(ids, ["a0", "a1", "empty", "a2", "a3", "a4"]);
+        let failed = &response.results[2];
+        assert!(failed.result.is_none());
+        assert_eq!(failed.error.as_ref().map(|error| error.code.as_str()), Some("invalid_input"));
+        let succeeded = response.results.iter().filter(|result| result.result.is_some());
+        assert_eq!(succeeded.count(), 5);
+        assert!(usecase.peak.load(Ordering::SeqCst) <= 2);
+    }
+
+    #[tokio::test]
+    async fn oversized_batches_are_rejected() {
+        let usecase = Arc::new(CountingUseCase::default());
+        let handler = TranscribeBatchCommandHandler::new(usecase, 1, 1);
+        let items = vec![item("a", vec![0.0]), item("b", vec![0.0])];
+        let result = handler
+            .handle(TranscribeBatchCommand::new(TranscribeBatchRequest { items }))
+            .await;
+        assert!(result.is_err());
+    }
+}
@@ -1,27 +1,122 @@
 use serde::{Deserialize, Serialize};
-use validator::Validate;
+use utoipa::ToSchema;
+use validator::{Validate, ValidationError};
 
-use orchestration_domain::{AudioChunk, Transcript, TtsOutput, WordTiming};
+use orchestration_domain::{AudioChunk, Pause, Transcript, TtsOutput, WordTiming};
 
-#[derive(Debug, Clone, Deserialize, Validate)]
+/// Exactly one of `samples` and `audio_url` holds the audio.
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+#[validate(schema(function = "validate_audio_source"))]
 pub struct TranscribeAudioRequest {
-    #[validate(length(min = 1))]
+    #[serde(default)]
     pub samples: Vec<f32>,
+    /// WAV file fetched before the pipeline runs instead of inline
+    /// `samples`: `s3://bucket/key`, `http://` or `https://`. Its header sets
+    /// `sample_rate_hz`.
+    #[serde(default)]
+    #[validate(length(min = 1, max = 2048))]
+    pub audio_url: Option<String>,
     #[validate(range(min = 8_000, max = 192_000))]
     pub sample_rate_hz: Option<u32>,
     #[validate(length(min = 1, max = 16))]
     pub language_hint: Option<String>,
     #[validate(length(min = 1, max = 64))]
     pub session_id: Option<String>,
+    /// Whisper model registered on the ASR service, e.g. `tiny` for
+    /// latency-sensitive callers.
+    #[serde(default)]
+    #[validate(length(min = 1, max = 64))]
+    pub model: Option<String>,
+    /// Interleaved channel count of `samples`. Above 1 each channel runs
+    /// through its own pipeline, e.g. agent and customer of a call recording.
+    #[serde(default)]
+    #[validate(range(min = 1, max = 8))]
+    pub channels: Option<u16>,
+    /// Pipeline definition to run instead of the configured one, e.g.
+    /// `align_only`.
+    #[serde(default)]
+    #[validate(length(min = 1, max = 64))]
+    pub pipeline: Option<String>,
+    /// Transcript for pipelines that align instead of transcribing.
+    #[serde(default)]
+    pub transcript: Option<ProvidedTranscript>,
+    /// Whisper decode profile: `accurate` (default), or `low_latency` for
+    /// sub-second answers on short commands at some cost in accuracy.
+    #[serde(default)]
+    #[validate(length(min = 1, max = 32))]
+    pub decode_profile: Option<String>,
+    /// GBNF grammar with a `root` rule constraining the transcript to, e.g.,
+    /// a fixed set of voice commands.
+    #[serde(default)]
+    #[validate(length(min = 1, max = 65536))]
+    pub grammar: Option<String>,
+    /// Text preceding the audio, passed to Whisper as its prompt, e.g.
+    /// spellings of names. Takes precedence over `context_carry_over`.
+    #[serde(default)]
+    #[validate(length(min = 1, max = 65536))]
+    pub prompt: Option<String>,
+    /// Start of the audio on the stream's timeline. Response timings are
+    /// shifted by it, so chunk-wise callers get absolute timestamps.
+    #[serde(default)]
+    pub time_offset_ms: Option<u64>,
+    /// Customer the request runs for, taken from the `x-tenant-id` header.
+    #[serde(skip)]
+    pub tenant_id: Option<String>,
+}
+
+fn validate_audio_source(request: &TranscribeAudioRequest) -> Result<(), ValidationError> {
+    match (request.samples.is_empty(), &request.audio_url) {
+        (false, None) | (true, Some(_)) => Ok(()),
+        (true, None) => Err(ValidationError::new("samples_missing")),
+        (false, Some(_)) => Err(ValidationError::new("samples_and_audio_url")),
+    }
+}
+
+/// A full transcript, or plain text aligned as one segment spanning the
+/// whole clip.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(untagged)]
+pub enum ProvidedTranscript {
+    Transcript(Transcript),
+    Text(String),
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct TranscribeAudioResponse {
     pub session_id: String,
     pub transcript: Transcript,
     pub aligned_words: Vec<WordTiming>,
+    /// Gaps between `aligned_words`, when `service.pauses` is configured.
+    /// Multi-channel requests report them per channel only.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pauses: Vec<Pause>,
+    /// Checks the audio failed in the `audio_quality_gate` step when it only
+    /// flags them, e.g. `clipped`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub quality_issues: Vec<String>,
+    /// Language the ASR service identified, set only when the language was
+    /// auto-detected rather than hinted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detected_language: Option<String>,
+    /// The ASR service's probability for `detected_language`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language_probability: Option<f32>,
     pub text: String,
     pub tts_output: Option<TtsOutput>,
     #[serde(skip)]
     pub output_audio: Option<AudioChunk>,
+    /// Per-channel results of a multi-channel request; `transcript`,
+    /// `aligned_words` and `text` then hold all channels merged by time.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub channels: Vec<ChannelTranscription>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ChannelTranscription {
+    pub channel: u8,
+    pub transcript: Transcript,
+    pub aligned_words: Vec<WordTiming>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pauses: Vec<Pause>,
+    pub text: String,
 }
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::{Validate, ValidationError};
+
+use crate::{TranscribeAudioRequest, TranscribeAudioResponse};
+
+/// Item ids are validated with the batch; the rest of each item is
+/// validated when it runs, so an invalid item fails alone instead of
+/// rejecting the whole batch.
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+#[validate(schema(function = "validate_items"))]
+pub struct TranscribeBatchRequest {
+    #[validate(nested)]
+    pub items: Vec<TranscribeBatchItem>,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct TranscribeBatchItem {
+    /// Client-chosen id echoed in the item's result.
+    #[validate(length(min = 1, max = 128))]
+    pub id: String,
+    #[serde(flatten)]
+    pub request: TranscribeAudioRequest,
+}
+
+/// Results in request order.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TranscribeBatchResponse {
+    pub results: Vec<TranscribeBatchItemResult>,
+}
+
+/// Exactly one of `result` and `error` is set.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TranscribeBatchItemResult {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<TranscribeAudioResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<BatchItemError>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BatchItemError {
+    /// One of the `ErrorCode` strings, e.g. `invalid_input`.
+    pub code: String,
+    pub message: String,
+}
+
+/// At least one item, with distinct ids.
+fn validate_items(request: &TranscribeBatchRequest) -> Result<(), ValidationError> {
+    if request.items.is_empty() {
+        return Err(ValidationError::new("items_missing"));
+    }
+    let mut seen = std::collections::HashSet::new();
+    if request.items.iter().all(|item| seen.insert(item.id.as_str())) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("duplicate_item_id"))
+    }
+}
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use orchestration_domain::{Transcript, TranscriptSegment, WordTiming};
+
+/// Either `text`, which replaces the whole transcript, or `segments`, which
+/// replace the stored segments they overlap.
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CorrectTranscriptRequest {
+    /// Taken from the URL path.
+    #[serde(default)]
+    #[validate(length(min = 1, max = 64))]
+    pub session_id: String,
+    #[serde(default)]
+    #[validate(length(min = 1))]
+    pub text: Option<String>,
+    #[serde(default)]
+    #[validate(length(min = 1))]
+    pub segments: Option<Vec<TranscriptSegment>>,
+    /// Taken from the `x-tenant-id` header; only the tenant's own sessions
+    /// are found.
+    #[serde(skip)]
+    pub tenant_id: Option<String>,
+}
+
+/// A stored session's transcript, as returned by corrections and session
+/// lookups.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CorrectTranscriptResponse {
+    pub session_id: String,
+    pub transcript: Transcript,
+    pub aligned_words: Vec<WordTiming>,
+    pub text: String,
+}
@@ -1,3 +1,17 @@
 mod asr;
+mod batch;
+mod correction;
+mod pipeline;
 
-pub use asr::{TranscribeAudioRequest, TranscribeAudioResponse};
+pub use asr::{
+    ChannelTranscription, ProvidedTranscript, TranscribeAudioRequest, TranscribeAudioResponse,
+};
+pub use batch::{
+    BatchItemError, TranscribeBatchItem, TranscribeBatchItemResult, TranscribeBatchRequest,
+    TranscribeBatchResponse,
+};
+pub use correction::{CorrectTranscriptRequest, CorrectTranscriptResponse};
+pub use pipeline::{
+    EndpointDescription, PipelineCatalog, PipelineDescription, PipelinePhase,
+    PipelineStepDescription, StepAvailability, StepSource,
+};
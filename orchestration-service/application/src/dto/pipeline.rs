@@ -0,0 +1,71 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Every configured pipeline as resolved at startup, and the optional steps
+/// this deployment can run.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PipelineCatalog {
+    /// Pipeline of requests that name none.
+    pub selected: String,
+    /// Sorted by name.
+    pub pipelines: Vec<PipelineDescription>,
+    pub optional_steps: Vec<StepAvailability>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PipelineDescription {
+    pub name: String,
+    pub selected: bool,
+    /// In execution order.
+    pub steps: Vec<PipelineStepDescription>,
+    /// Downstream services the steps call, with the addresses used.
+    pub endpoints: Vec<EndpointDescription>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PipelineStepDescription {
+    pub phase: PipelinePhase,
+    /// Step name as configured.
+    pub name: String,
+    /// Stage the step runs, as named in stage events.
+    pub stage: String,
+    pub source: StepSource,
+    /// Downstream services the stage calls; empty when it runs in-process.
+    pub services: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelinePhase {
+    Pre,
+    Transcription,
+    Post,
+}
+
+/// Where a step name is defined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StepSource {
+    Builtin,
+    /// `service.audio_recipes`.
+    Recipe,
+    /// A library of `service.pipeline.plugin_dir`.
+    Plugin,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct EndpointDescription {
+    pub service: String,
+    pub targets: Vec<String>,
+}
+
+/// A step that needs a build feature, a config block or a plugin library.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StepAvailability {
+    pub name: String,
+    pub source: StepSource,
+    pub available: bool,
+    /// What makes the step available when it is not.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requires: Option<String>,
+}
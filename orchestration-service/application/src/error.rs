@@ -1,3 +1,4 @@
+use common_domain::ErrorCode;
 use rustycog_command::CommandError;
 use rustycog_core::error::DomainError;
 use thiserror::Error;
@@ -10,19 +11,50 @@ pub enum ApplicationError {
     #[error("Validation error: {0}")]
     Validation(String),
 
+    #[error("Audio quality: {0}")]
+    AudioQuality(String),
+
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("Quota exceeded: {0}")]
+    QuotaExceeded(String),
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
 
+impl ApplicationError {
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            ApplicationError::Domain(err) => ErrorCode::from(err),
+            ApplicationError::Validation(_) => ErrorCode::InvalidInput,
+            ApplicationError::AudioQuality(_) => ErrorCode::AudioQuality,
+            ApplicationError::NotFound(_) => ErrorCode::NotFound,
+            ApplicationError::QuotaExceeded(_) => ErrorCode::ResourceExhausted,
+            ApplicationError::Internal(_) => ErrorCode::Internal,
+        }
+    }
+}
+
 impl From<ApplicationError> for CommandError {
     fn from(error: ApplicationError) -> Self {
         match error {
             ApplicationError::Domain(err) => {
-                CommandError::business("domain_error", err.to_string())
+                CommandError::business(ErrorCode::from(&err).as_str(), err.to_string())
             }
             ApplicationError::Validation(message) => {
                 CommandError::validation("validation_error", message)
             }
+            ApplicationError::AudioQuality(message) => {
+                CommandError::business(ErrorCode::AudioQuality.as_str(), message)
+            }
+            ApplicationError::NotFound(message) => {
+                CommandError::business(ErrorCode::NotFound.as_str(), message)
+            }
+            ApplicationError::QuotaExceeded(message) => {
+                CommandError::business(ErrorCode::ResourceExhausted.as_str(), message)
+            }
             ApplicationError::Internal(message) => {
                 CommandError::infrastructure("internal_error", message)
             }
@@ -2,10 +2,17 @@ pub mod command;
 pub mod dto;
 pub mod error;
 pub mod pipeline;
+pub mod tenant;
+pub mod usage;
 pub mod usecase;
 
 pub use command::*;
 pub use dto::*;
 pub use error::*;
 pub use pipeline::{PipelineDefinition, PipelineEngine, PipelineStepLoader, PipelineStepSpec};
-pub use usecase::{AsrUseCase, AsrUseCaseImpl};
+pub use tenant::{parse_tenant_id, TenantPermit, TenantPolicy, TenantRoute, TENANT_ID_HEADER};
+pub use usage::{asr_seconds, UsageMeter};
+pub use usecase::{
+    AsrUseCase, AsrUseCaseImpl, CorrectionUseCase, CorrectionUseCaseImpl, PipelineCatalogUseCase,
+    PipelineCatalogUseCaseImpl,
+};
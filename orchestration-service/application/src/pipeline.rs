@@ -1,6 +1,8 @@
 use std::sync::Arc;
+use std::time::Instant;
 
-use orchestration_domain::{DomainError, PipelineContext, PipelineStage};
+use orchestration_domain::{DomainError, DomainEvent, PipelineContext, PipelineStage};
+use tracing::Instrument;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PipelineStepSpec {
@@ -36,12 +38,23 @@ pub trait PipelineStepLoader: Send + Sync {
 
 #[derive(Default)]
 pub struct PipelineEngine {
+    /// Pipeline definition name, logged with every stage.
+    name: Option<String>,
     stages: Vec<Arc<dyn PipelineStage>>,
 }
 
 impl PipelineEngine {
     pub fn new(stages: Vec<Arc<dyn PipelineStage>>) -> Self {
-        Self { stages }
+        Self { name: None, stages }
+    }
+
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
     }
 
     pub fn push_stage(&mut self, stage: Arc<dyn PipelineStage>) {
@@ -59,10 +72,45 @@ impl PipelineEngine {
         Ok(Self::new(stages))
     }
 
+    /// Runs every stage in order, recording `StageStarted` and then
+    /// `StageCompleted` or `StageFailed` around each one. Stops at the first
+    /// failure, or after a stage that calls
+    /// [`PipelineContext::finish_early`]. Runs in a `pipeline` span naming
+    /// the pipeline and session, each stage in a nested `stage` span.
     pub async fn run(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+        let span = tracing::info_span!(
+            "pipeline",
+            pipeline = self.name.as_deref().unwrap_or("default"),
+            session_id = %context.session_id,
+        );
+        self.run_stages(context).instrument(span).await
+    }
+
+    async fn run_stages(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
         for stage in &self.stages {
-            tracing::debug!("executing stage={}", stage.name());
-            stage.execute(context).await?;
+            let name = stage.name().to_string();
+            tracing::debug!("executing stage={}", name);
+            context.events.push(DomainEvent::StageStarted { stage: name.clone() });
+
+            let started = Instant::now();
+            let stage_span = tracing::info_span!("stage", stage = %name);
+            match stage.execute(context).instrument(stage_span).await {
+                Ok(()) => context.events.push(DomainEvent::StageCompleted {
+                    stage: name.clone(),
+                    duration_ms: started.elapsed().as_millis() as u64,
+                }),
+                Err(err) => {
+                    context.events.push(DomainEvent::StageFailed {
+                        stage: name,
+                        message: err.to_string(),
+                    });
+                    return Err(err);
+                }
+            }
+            if context.is_finished_early() {
+                tracing::debug!("stage={} finished the pipeline early", name);
+                break;
+            }
         }
         Ok(())
     }
@@ -95,12 +143,16 @@ mod tests {
         async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
             context.events.push(DomainEvent::FinalTranscript {
                 transcript: Transcript {
-                    language: LanguageTag::En,
+                    language: LanguageTag::en(),
                     segments: vec![TranscriptSegment {
                         text: self.id.to_string(),
                         start_ms: 0,
                         end_ms: 10,
                         tokens: Vec::new(),
+                        speaker: None,
+                        language: None,
+                        no_speech_prob: None,
+                        avg_logprob: None,
                     }],
                 },
             });
@@ -118,17 +170,87 @@ mod tests {
 
         pipeline.run(&mut context).await.expect("pipeline runs");
 
-        assert_eq!(context.events.len(), 2);
-        let first = match &context.events[0] {
-            DomainEvent::FinalTranscript { transcript } => &transcript.segments[0].text,
-            _ => panic!("unexpected event"),
-        };
-        let second = match &context.events[1] {
-            DomainEvent::FinalTranscript { transcript } => &transcript.segments[0].text,
-            _ => panic!("unexpected event"),
-        };
-        assert_eq!(first, "a");
-        assert_eq!(second, "b");
+        let transcripts: Vec<_> = context
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                DomainEvent::FinalTranscript { transcript } => {
+                    Some(transcript.segments[0].text.as_str())
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(transcripts, vec!["a", "b"]);
+    }
+
+    struct FailingStage;
+
+    #[async_trait]
+    impl PipelineStage for FailingStage {
+        fn name(&self) -> &'static str {
+            "broken"
+        }
+
+        async fn execute(&self, _context: &mut PipelineContext) -> Result<(), DomainError> {
+            Err(DomainError::internal_error("boom"))
+        }
+    }
+
+    #[tokio::test]
+    async fn stage_lifecycle_is_recorded() {
+        let pipeline = PipelineEngine::new(vec![
+            Arc::new(TestStage { id: "a" }),
+            Arc::new(FailingStage),
+            Arc::new(TestStage { id: "c" }),
+        ]);
+        let mut context = PipelineContext::new("session", None);
+
+        pipeline.run(&mut context).await.expect_err("second stage fails");
+
+        let lifecycle: Vec<String> = context
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                DomainEvent::StageStarted { stage } => Some(format!("start:{stage}")),
+                DomainEvent::StageCompleted { stage, .. } => Some(format!("done:{stage}")),
+                DomainEvent::StageFailed { stage, message } => {
+                    Some(format!("fail:{stage}:{}", message.contains("boom")))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(lifecycle, vec!["start:a", "done:a", "start:broken", "fail:broken:true"]);
+    }
+
+    struct FinishingStage;
+
+    #[async_trait]
+    impl PipelineStage for FinishingStage {
+        fn name(&self) -> &'static str {
+            "cached"
+        }
+
+        async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+            context.finish_early();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn finishing_early_skips_the_remaining_stages() {
+        let pipeline = PipelineEngine::new(vec![
+            Arc::new(FinishingStage),
+            Arc::new(TestStage { id: "b" }),
+        ]);
+        let mut context = PipelineContext::new("session", None);
+
+        pipeline.run(&mut context).await.expect("pipeline runs");
+
+        assert!(context.is_finished_early());
+        assert!(!context
+            .events
+            .iter()
+            .any(|event| matches!(event, DomainEvent::FinalTranscript { .. })));
     }
 
     struct TestLoader {
@@ -164,6 +286,11 @@ mod tests {
 
         pipeline.run(&mut context).await.expect("pipeline runs");
 
-        assert_eq!(context.events.len(), 3);
+        let completed = context
+            .events
+            .iter()
+            .filter(|event| matches!(event, DomainEvent::StageCompleted { .. }))
+            .count();
+        assert_eq!(completed, 3);
     }
 }
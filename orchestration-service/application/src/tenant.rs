@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::{ApplicationError, PipelineEngine};
+
+/// Header, and gRPC metadata key, naming the tenant a request or streaming
+/// session runs for.
+pub const TENANT_ID_HEADER: &str = "x-tenant-id";
+
+const MAX_TENANT_ID_LEN: usize = 64;
+
+/// Whether requests must name a tenant, and how many of a tenant's
+/// transcriptions may run at once. The default admits every request.
+/// Clones share the in-flight counts, so HTTP requests and streaming
+/// sessions count against the same limit.
+#[derive(Clone, Default)]
+pub struct TenantPolicy {
+    required: bool,
+    default_max_concurrent: Option<usize>,
+    max_concurrent: HashMap<String, usize>,
+    in_flight: Arc<Mutex<HashMap<String, usize>>>,
+}
+
+/// How a tenant's requests run when they name no pipeline or model.
+#[derive(Default)]
+pub struct TenantRoute {
+    /// Pipeline definition used instead of the configured one.
+    pub pipeline: Option<String>,
+    /// ASR model used instead of the ASR service's default.
+    pub model: Option<String>,
+    /// The tenant's own builds of pipelines, by definition name, e.g. wired
+    /// to dedicated ASR and alignment services. They take precedence over the
+    /// shared pipelines of the same name.
+    pub pipelines: HashMap<String, PipelineEngine>,
+}
+
+/// Holds one of a tenant's concurrent slots until dropped.
+pub struct TenantPermit {
+    tenant_id: String,
+    in_flight: Arc<Mutex<HashMap<String, usize>>>,
+}
+
+impl TenantPolicy {
+    /// Refuses requests without a tenant id.
+    pub fn require_tenant(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Concurrent transcriptions of a tenant without its own limit.
+    pub fn with_default_max_concurrent(mut self, limit: usize) -> Self {
+        self.default_max_concurrent = Some(limit);
+        self
+    }
+
+    pub fn with_max_concurrent(mut self, tenant_id: impl Into<String>, limit: usize) -> Self {
+        self.max_concurrent.insert(tenant_id.into(), limit);
+        self
+    }
+
+    /// Takes one of the tenant's slots. Requests without a tenant are only
+    /// checked against `require_tenant`.
+    pub fn admit(&self, tenant_id: Option<&str>) -> Result<Option<TenantPermit>, ApplicationError> {
+        let Some(tenant_id) = tenant_id else {
+            if self.required {
+                return Err(ApplicationError::Validation(
+                    "requests must name a tenant".to_string(),
+                ));
+            }
+            return Ok(None);
+        };
+        let limit = self
+            .max_concurrent
+            .get(tenant_id)
+            .copied()
+            .or(self.default_max_concurrent);
+
+        let mut in_flight = self
+            .in_flight
+            .lock()
+            .map_err(|_| ApplicationError::Internal("tenant quota lock poisoned".to_string()))?;
+        let running = in_flight.entry(tenant_id.to_string()).or_default();
+        if limit.is_some_and(|limit| *running >= limit) {
+            return Err(ApplicationError::QuotaExceeded(format!(
+                "tenant `{tenant_id}` already runs {running} transcriptions, its limit"
+            )));
+        }
+        *running += 1;
+        Ok(Some(TenantPermit {
+            tenant_id: tenant_id.to_string(),
+            in_flight: self.in_flight.clone(),
+        }))
+    }
+}
+
+/// Validates a tenant id sent in [`TENANT_ID_HEADER`]. It becomes part of
+/// session keys and object-store paths, so only ASCII letters, digits, `-`,
+/// `_` and `.` are accepted. The error is the message to report.
+pub fn parse_tenant_id(value: &str) -> Result<String, String> {
+    let tenant_id = value.trim();
+    let valid = (1..=MAX_TENANT_ID_LEN).contains(&tenant_id.len())
+        && tenant_id != "."
+        && tenant_id != ".."
+        && tenant_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(format!(
+            "{TENANT_ID_HEADER} must be 1 to {MAX_TENANT_ID_LEN} letters, digits, `-`, `_` or `.`"
+        ));
+    }
+    Ok(tenant_id.to_string())
+}
+
+impl Drop for TenantPermit {
+    fn drop(&mut self) {
+        if let Ok(mut in_flight) = self.in_flight.lock() {
+            if let Some(running) = in_flight.get_mut(&self.tenant_id) {
+                *running -= 1;
+                if *running == 0 {
+                    in_flight.remove(&self.tenant_id);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tenants_are_limited_separately() {
+        let policy = TenantPolicy::default()
+            .with_default_max_concurrent(1)
+            .with_max_concurrent("acme", 2);
+
+        let acme = [policy.admit(Some("acme")), policy.admit(Some("acme"))];
+        assert!(acme.iter().all(Result::is_ok));
+        let error = policy.admit(Some("acme")).err().expect("over the limit");
+        assert_eq!(error.error_code().as_str(), "resource_exhausted");
+
+        let globex = policy.admit(Some("globex")).expect("own limit");
+        assert!(policy.admit(Some("globex")).is_err());
+        drop(globex);
+        assert!(policy.admit(Some("globex")).is_ok());
+        assert!(policy.admit(None).expect("no tenant").is_none());
+    }
+
+    #[test]
+    fn required_tenant_refuses_anonymous_requests() {
+        let policy = TenantPolicy::default().require_tenant();
+        assert!(policy.admit(None).is_err());
+        assert!(policy.admit(Some("acme")).is_ok());
+    }
+}
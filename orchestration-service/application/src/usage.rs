@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use orchestration_domain::{PipelineContext, UsageRecord};
+
+/// Model name of requests that name none.
+pub const DEFAULT_MODEL: &str = "default";
+
+/// Tenant and model.
+type UsageKey = (Option<String>, String);
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Usage {
+    requests: u64,
+    audio_seconds: f64,
+    asr_seconds: f64,
+}
+
+impl Usage {
+    fn add(&mut self, other: Usage) {
+        self.requests += other.requests;
+        self.audio_seconds += other.audio_seconds;
+        self.asr_seconds += other.asr_seconds;
+    }
+}
+
+#[derive(Default)]
+struct Ledger {
+    totals: BTreeMap<UsageKey, Usage>,
+    unreported: BTreeMap<UsageKey, Usage>,
+}
+
+/// Usage of successful transcriptions per tenant and model since startup.
+/// Clones share the same ledger.
+#[derive(Clone, Default)]
+pub struct UsageMeter {
+    ledger: Arc<Mutex<Ledger>>,
+}
+
+impl UsageMeter {
+    /// Adds one finished request and the audio it covered.
+    pub fn record(
+        &self,
+        tenant_id: Option<&str>,
+        model: Option<&str>,
+        audio_seconds: f64,
+        asr_seconds: f64,
+    ) {
+        let key = (
+            tenant_id.map(str::to_string),
+            model.unwrap_or(DEFAULT_MODEL).to_string(),
+        );
+        let usage = Usage {
+            requests: 1,
+            audio_seconds,
+            asr_seconds,
+        };
+        // A poisoned ledger only loses accounting, never the request.
+        if let Ok(mut ledger) = self.ledger.lock() {
+            ledger.totals.entry(key.clone()).or_default().add(usage);
+            ledger.unreported.entry(key).or_default().add(usage);
+        }
+    }
+
+    /// Usage since startup, by tenant then model.
+    pub fn totals(&self) -> Vec<UsageRecord> {
+        self.ledger
+            .lock()
+            .map(|ledger| records(&ledger.totals))
+            .unwrap_or_default()
+    }
+
+    /// Usage since the previous call, for periodic usage events.
+    pub fn take_unreported(&self) -> Vec<UsageRecord> {
+        self.ledger
+            .lock()
+            .map(|mut ledger| records(&std::mem::take(&mut ledger.unreported)))
+            .unwrap_or_default()
+    }
+
+    /// Puts back usage a [`UsageMeter::take_unreported`] caller could not
+    /// deliver, so the next report includes it.
+    pub fn restore_unreported(&self, records: &[UsageRecord]) {
+        if let Ok(mut ledger) = self.ledger.lock() {
+            for record in records {
+                let key = (record.tenant_id.clone(), record.model.clone());
+                ledger.unreported.entry(key).or_default().add(Usage {
+                    requests: record.requests,
+                    audio_seconds: record.audio_seconds,
+                    asr_seconds: record.asr_seconds,
+                });
+            }
+        }
+    }
+}
+
+/// Time the pipeline spent waiting on the ASR service, which the ASR stage
+/// accumulates in `asr.compute_ms`.
+pub fn asr_seconds(context: &PipelineContext) -> f64 {
+    context
+        .extension("asr.compute_ms")
+        .and_then(|value| value.as_u64())
+        .map_or(0.0, |ms| ms as f64 / 1_000.0)
+}
+
+fn records(usage: &BTreeMap<UsageKey, Usage>) -> Vec<UsageRecord> {
+    usage
+        .iter()
+        .map(|((tenant_id, model), usage)| UsageRecord {
+            tenant_id: tenant_id.clone(),
+            model: model.clone(),
+            requests: usage.requests,
+            audio_seconds: usage.audio_seconds,
+            asr_seconds: usage.asr_seconds,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usage_adds_up_per_tenant_and_model() {
+        let meter = UsageMeter::default();
+        meter.record(Some("acme"), Some("large-v3"), 2.0, 0.5);
+        meter.record(Some("acme"), Some("large-v3"), 3.0, 0.25);
+        meter.record(Some("acme"), None, 1.0, 0.0);
+        meter.record(None, None, 4.0, 1.0);
+
+        let totals = meter.totals();
+        assert_eq!(totals.len(), 3);
+        assert_eq!(totals[0].tenant_id, None);
+        let large = &totals[2];
+        assert_eq!(
+            (large.tenant_id.as_deref(), large.model.as_str()),
+            (Some("acme"), "large-v3")
+        );
+        assert_eq!(large.requests, 2);
+        assert_eq!(large.audio_seconds, 5.0);
+        assert_eq!(large.asr_seconds, 0.75);
+    }
+
+    #[test]
+    fn unreported_usage_is_taken_once_unless_restored() {
+        let meter = UsageMeter::default();
+        meter.record(Some("acme"), None, 2.0, 0.5);
+
+        let first = meter.take_unreported();
+        assert_eq!(first.len(), 1);
+        assert!(meter.take_unreported().is_empty());
+
+        meter.restore_unreported(&first);
+        meter.record(Some("acme"), None, 1.0, 0.5);
+        let retried = meter.take_unreported();
+        assert_eq!(retried[0].requests, 2);
+        assert_eq!(meter.totals()[0].requests, 2);
+    }
+}
@@ -1,10 +1,19 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use serde_json::json;
 use uuid::Uuid;
 
-use orchestration_domain::{DomainEvent, LanguageTag, PipelineContext};
+use orchestration_domain::{
+    AudioChunk, AudioSourcePort, DomainEvent, LanguageTag, Pause, PipelineContext, Transcript,
+    TranscriptSegment, WordTiming,
+};
 
-use crate::{ApplicationError, PipelineEngine, TranscribeAudioRequest, TranscribeAudioResponse};
+use crate::{
+    asr_seconds, ApplicationError, ChannelTranscription, PipelineEngine, ProvidedTranscript,
+    TenantPolicy, TenantRoute, TranscribeAudioRequest, TranscribeAudioResponse, UsageMeter,
+};
 
 #[async_trait]
 pub trait AsrUseCase: Send + Sync {
@@ -16,15 +25,218 @@ pub trait AsrUseCase: Send + Sync {
 
 pub struct AsrUseCaseImpl {
     pipeline: PipelineEngine,
+    named: HashMap<String, PipelineEngine>,
     sample_rate_hz: u32,
+    audio_source: Option<Arc<dyn AudioSourcePort>>,
+    tenants: TenantPolicy,
+    routes: HashMap<String, TenantRoute>,
+    usage: UsageMeter,
 }
 
 impl AsrUseCaseImpl {
     pub fn new(pipeline: PipelineEngine, sample_rate_hz: u32) -> Self {
         Self {
             pipeline,
+            named: HashMap::new(),
             sample_rate_hz,
+            audio_source: None,
+            tenants: TenantPolicy::default(),
+            routes: HashMap::new(),
+            usage: UsageMeter::default(),
+        }
+    }
+
+    /// Makes the pipeline selectable per request through
+    /// `TranscribeAudioRequest::pipeline`.
+    pub fn with_pipeline(mut self, name: impl Into<String>, pipeline: PipelineEngine) -> Self {
+        self.named.insert(name.into(), pipeline);
+        self
+    }
+
+    /// Fetches the audio of requests naming an `audio_url`; without it those
+    /// requests are refused.
+    pub fn with_audio_source(mut self, audio_source: Arc<dyn AudioSourcePort>) -> Self {
+        self.audio_source = Some(audio_source);
+        self
+    }
+
+    /// Checked before anything else, so a tenant over its limit costs no
+    /// download or pipeline run.
+    pub fn with_tenant_policy(mut self, tenants: TenantPolicy) -> Self {
+        self.tenants = tenants;
+        self
+    }
+
+    /// Pipeline and model of the tenant's requests that name none.
+    pub fn with_tenant_route(mut self, tenant_id: impl Into<String>, route: TenantRoute) -> Self {
+        self.routes.insert(tenant_id.into(), route);
+        self
+    }
+
+    /// Where successful requests are accounted; a private meter otherwise.
+    pub fn with_usage_meter(mut self, usage: UsageMeter) -> Self {
+        self.usage = usage;
+        self
+    }
+
+    /// Replaces `audio_url` with the samples of the WAV file it references.
+    async fn fetch_audio(
+        &self,
+        mut request: TranscribeAudioRequest,
+    ) -> Result<TranscribeAudioRequest, ApplicationError> {
+        let Some(url) = request.audio_url.take() else {
+            return Ok(request);
+        };
+        let source = self.audio_source.as_ref().ok_or_else(|| {
+            ApplicationError::Validation(
+                "audio_url is not accepted; service.audio_fetch is not configured".to_string(),
+            )
+        })?;
+        let bytes = source.fetch(&url).await?;
+        let wav = wav_io::decode_wav(&bytes)
+            .map_err(|err| ApplicationError::Validation(format!("audio_url {url}: {err}")))?;
+        request.samples = wav.samples;
+        request.sample_rate_hz = Some(wav.sample_rate_hz);
+        request.channels = None;
+        Ok(request)
+    }
+
+    /// The requested pipeline, else the tenant's, else the configured one.
+    fn select_pipeline(
+        &self,
+        route: Option<&TenantRoute>,
+        name: Option<&str>,
+    ) -> Result<&PipelineEngine, ApplicationError> {
+        let name = name.or_else(|| route.and_then(|route| route.pipeline.as_deref()));
+        let Some(name) = name else {
+            return Ok(&self.pipeline);
+        };
+        if let Some(pipeline) = route.and_then(|route| route.pipelines.get(name)) {
+            return Ok(pipeline);
+        }
+        if self.pipeline.name() == Some(name) {
+            return Ok(&self.pipeline);
         }
+        self.named
+            .get(name)
+            .ok_or_else(|| ApplicationError::Validation(format!("unknown pipeline '{name}'")))
+    }
+
+    /// Runs `pipeline` on `audio` from a copy of `base`, the context naming
+    /// the session.
+    async fn run_pipeline(
+        &self,
+        pipeline: &PipelineEngine,
+        base: &PipelineContext,
+        audio: AudioChunk,
+        model: Option<String>,
+        transcript: Option<Transcript>,
+    ) -> Result<PipelineContext, ApplicationError> {
+        let mut context = base.clone();
+        context.set_extension("audio.request_sample_rate_hz", json!(audio.sample_rate_hz));
+        context.audio = audio;
+        if let Some(model) = model {
+            context.set_extension("asr.model", json!(model));
+        }
+        if let Some(transcript) = transcript {
+            context.set_extension("request.transcript", json!(transcript));
+        }
+        if let Err(err) = pipeline.run(&mut context).await {
+            if quality_rejected(&context) {
+                return Err(ApplicationError::AudioQuality(err.to_string()));
+            }
+            return Err(err.into());
+        }
+        Ok(context)
+    }
+
+    /// Runs one pipeline per channel concurrently and merges the results,
+    /// labelling unlabelled segments and words with their channel; pauses
+    /// of all channels are merged in time order. Also
+    /// returns the ASR time of all channels.
+    async fn transcribe_channels(
+        &self,
+        pipeline: &PipelineEngine,
+        base: &PipelineContext,
+        audio: Vec<AudioChunk>,
+        model: Option<String>,
+        offset_ms: i64,
+    ) -> Result<(TranscribeAudioResponse, f64), ApplicationError> {
+        let contexts = futures::future::try_join_all(
+            audio
+                .into_iter()
+                .map(|chunk| self.run_pipeline(pipeline, base, chunk, model.clone(), None)),
+        )
+        .await?;
+
+        let mut channels = Vec::with_capacity(contexts.len());
+        for context in &contexts {
+            let label = format!("channel_{}", context.audio.channel);
+            let mut transcript = required_transcript(context)?;
+            for segment in &mut transcript.segments {
+                segment.speaker.get_or_insert_with(|| label.clone());
+            }
+            let mut aligned_words = extract_alignment_words(context);
+            for word in &mut aligned_words {
+                word.speaker.get_or_insert_with(|| label.clone());
+            }
+            let mut pauses = extract_pauses(context);
+            shift_timings(
+                &mut transcript,
+                &mut aligned_words,
+                &mut pauses,
+                offset_ms,
+                context.audio.sample_rate_hz,
+            );
+            channels.push(ChannelTranscription {
+                channel: context.audio.channel,
+                text: transcript_text(&transcript),
+                transcript,
+                aligned_words,
+                pauses,
+            });
+        }
+
+        let mut segments: Vec<_> = channels
+            .iter()
+            .flat_map(|channel| channel.transcript.segments.iter().cloned())
+            .collect();
+        segments.sort_by_key(|segment| segment.start_ms);
+        let mut aligned_words: Vec<WordTiming> = channels
+            .iter()
+            .flat_map(|channel| channel.aligned_words.iter().cloned())
+            .collect();
+        aligned_words.sort_by_key(|word| word.start_ms);
+        let mut pauses: Vec<Pause> = channels
+            .iter()
+            .flat_map(|channel| channel.pauses.iter().cloned())
+            .collect();
+        pauses.sort_by_key(|pause| pause.start_ms);
+        let mut quality_issues: Vec<String> =
+            contexts.iter().flat_map(extract_quality_issues).collect();
+        quality_issues.sort();
+        quality_issues.dedup();
+        let transcript = Transcript {
+            language: channels[0].transcript.language.clone(),
+            segments,
+        };
+
+        let (detected_language, language_probability) = extract_detected_language(&contexts[0]);
+
+        let response = TranscribeAudioResponse {
+            session_id: contexts[0].session_id.clone(),
+            text: transcript_text(&transcript),
+            transcript,
+            aligned_words,
+            pauses,
+            quality_issues,
+            detected_language,
+            language_probability,
+            tts_output: None,
+            output_audio: None,
+            channels,
+        };
+        Ok((response, contexts.iter().map(asr_seconds).sum()))
     }
 }
 
@@ -34,7 +246,10 @@ impl AsrUseCase for AsrUseCaseImpl {
         &self,
         request: TranscribeAudioRequest,
     ) -> Result<TranscribeAudioResponse, ApplicationError> {
+        let _permit = self.tenants.admit(request.tenant_id.as_deref())?;
+        let request = self.fetch_audio(request).await?;
         tracing::debug!(
+            tenant_id = request.tenant_id.as_deref().unwrap_or("none"),
             sample_count = request.samples.len(),
             sample_rate_hz = request.sample_rate_hz.unwrap_or(self.sample_rate_hz),
             language_hint = request.language_hint.as_deref().unwrap_or("auto"),
@@ -43,39 +258,108 @@ impl AsrUseCase for AsrUseCaseImpl {
         );
 
         let input_sample_rate_hz = request.sample_rate_hz.unwrap_or(self.sample_rate_hz);
-        let mut context = PipelineContext::new(
-            request
-                .session_id
-                .clone()
-                .unwrap_or_else(|| Uuid::new_v4().to_string()),
-            parse_language_hint(request.language_hint.as_deref())?,
+        let session_id = request
+            .session_id
+            .clone()
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        let language_hint = parse_language_hint(request.language_hint.as_deref())?;
+        let route = request
+            .tenant_id
+            .as_deref()
+            .and_then(|tenant_id| self.routes.get(tenant_id));
+        let pipeline = self.select_pipeline(route, request.pipeline.as_deref())?;
+        let model = request
+            .model
+            .or_else(|| route.and_then(|route| route.model.clone()));
+        let mut base = PipelineContext::new(session_id, language_hint.clone());
+        base.tenant_id = request.tenant_id.clone();
+        if let Some(profile) = request.decode_profile.as_deref() {
+            base.set_extension("asr.decode_profile", json!(parse_decode_profile(profile)?));
+        }
+        if let Some(grammar) = &request.grammar {
+            base.set_extension("asr.grammar", json!(grammar));
+        }
+        if let Some(prompt) = &request.prompt {
+            base.set_extension("asr.prompt", json!(prompt));
+        }
+
+        let offset_ms = request
+            .time_offset_ms
+            .map_or(0, |offset_ms| i64::try_from(offset_ms).unwrap_or(i64::MAX));
+
+        let tenant_id = request.tenant_id.as_deref();
+        // Every channel is billed, as every channel is transcribed.
+        let audio_seconds = request.samples.len() as f64 / f64::from(input_sample_rate_hz.max(1));
+
+        let channels = request.channels.unwrap_or(1);
+        if channels > 1 && request.transcript.is_some() {
+            return Err(ApplicationError::Validation(
+                "a provided transcript only applies to single-channel audio".to_string(),
+            ));
+        }
+        if channels > 1 {
+            let audio = AudioChunk::deinterleave(input_sample_rate_hz, &request.samples, channels)
+                .ok_or_else(|| {
+                    ApplicationError::Validation(format!(
+                        "samples must hold whole frames of {channels} interleaved channels"
+                    ))
+                })?;
+            let (response, asr_seconds) = self
+                .transcribe_channels(pipeline, &base, audio, model.clone(), offset_ms)
+                .await?;
+            self.usage
+                .record(tenant_id, model.as_deref(), audio_seconds, asr_seconds);
+            tracing::debug!(
+                channel_count = response.channels.len(),
+                segment_count = response.transcript.segments.len(),
+                aligned_word_count = response.aligned_words.len(),
+                "multi-channel asr pipeline completed"
+            );
+            return Ok(response);
+        }
+
+        let audio = AudioChunk::mono(input_sample_rate_hz, request.samples);
+        let transcript = request
+            .transcript
+            .map(|provided| provided_transcript(provided, &audio, language_hint.clone()));
+        let context = self
+            .run_pipeline(pipeline, &base, audio, model.clone(), transcript)
+            .await?;
+        self.usage.record(
+            tenant_id,
+            model.as_deref(),
+            audio_seconds,
+            asr_seconds(&context),
         );
-        context.audio.sample_rate_hz = input_sample_rate_hz;
-        context.audio.samples = request.samples;
-        context.set_extension("audio.request_sample_rate_hz", json!(input_sample_rate_hz));
-        self.pipeline.run(&mut context).await?;
 
-        let transcript = context.transcript.clone().ok_or_else(|| {
-            ApplicationError::Internal("transcription pipeline returned no transcript".to_string())
-        })?;
-        let text = transcript
-            .segments
-            .iter()
-            .map(|segment| segment.text.trim())
-            .filter(|part| !part.is_empty())
-            .collect::<Vec<_>>()
-            .join(" ");
+        let mut transcript = required_transcript(&context)?;
+        let text = transcript_text(&transcript);
 
-        let aligned_words = extract_alignment_words(&context);
+        let mut aligned_words = extract_alignment_words(&context);
+        let mut pauses = extract_pauses(&context);
+        shift_timings(
+            &mut transcript,
+            &mut aligned_words,
+            &mut pauses,
+            offset_ms,
+            context.audio.sample_rate_hz,
+        );
+        let quality_issues = extract_quality_issues(&context);
+        let (detected_language, language_probability) = extract_detected_language(&context);
         let tts_output = context.tts_output.clone();
         let output_audio = Some(context.audio.clone());
         let response = TranscribeAudioResponse {
             session_id: context.session_id,
             transcript,
             aligned_words,
+            pauses,
+            quality_issues,
+            detected_language,
+            language_probability,
             text,
             tts_output,
             output_audio,
+            channels: Vec::new(),
         };
 
         tracing::debug!(
@@ -89,26 +373,94 @@ impl AsrUseCase for AsrUseCaseImpl {
     }
 }
 
+/// Moves a chunk's timings onto the stream's timeline.
+fn shift_timings(
+    transcript: &mut Transcript,
+    words: &mut [WordTiming],
+    pauses: &mut [Pause],
+    offset_ms: i64,
+    sample_rate_hz: u32,
+) {
+    if offset_ms == 0 {
+        return;
+    }
+    transcript.shift(offset_ms, sample_rate_hz);
+    for word in words {
+        word.shift(offset_ms, sample_rate_hz);
+    }
+    for pause in pauses {
+        pause.start_ms = pause.start_ms.saturating_add_signed(offset_ms);
+        pause.end_ms = pause.end_ms.saturating_add_signed(offset_ms);
+    }
+}
+
+/// Decode profiles of the ASR service.
+const DECODE_PROFILES: [&str; 2] = ["accurate", "low_latency"];
+
+fn parse_decode_profile(value: &str) -> Result<&str, ApplicationError> {
+    DECODE_PROFILES.contains(&value).then_some(value).ok_or_else(|| {
+        ApplicationError::Validation(format!(
+            "decode_profile: expected accurate or low_latency, got {value}"
+        ))
+    })
+}
+
 fn parse_language_hint(value: Option<&str>) -> Result<Option<LanguageTag>, ApplicationError> {
     let Some(language) = value else {
         return Ok(None);
     };
 
-    let parsed = match language.to_ascii_lowercase().as_str() {
-        "fr" => LanguageTag::Fr,
-        "en" => LanguageTag::En,
-        "auto" => LanguageTag::Auto,
-        other if !other.is_empty() => LanguageTag::Other(other.to_string()),
-        _ => {
-            return Err(ApplicationError::Validation(
-                "language_hint cannot be empty".to_string(),
-            ));
+    LanguageTag::parse(language)
+        .map(Some)
+        .map_err(|err| ApplicationError::Validation(format!("language_hint: {err}")))
+}
+
+/// Plain text becomes a single segment spanning the whole clip; the aligner
+/// finds the word boundaries inside it.
+fn provided_transcript(
+    provided: ProvidedTranscript,
+    audio: &AudioChunk,
+    language_hint: Option<LanguageTag>,
+) -> Transcript {
+    match provided {
+        ProvidedTranscript::Transcript(transcript) => transcript,
+        ProvidedTranscript::Text(text) => {
+            let end_ms =
+                audio.samples.len() as u64 * 1000 / u64::from(audio.sample_rate_hz.max(1));
+            Transcript {
+                language: language_hint.unwrap_or(LanguageTag::Auto),
+                segments: vec![TranscriptSegment {
+                    text,
+                    start_ms: 0,
+                    end_ms,
+                    tokens: Vec::new(),
+                    speaker: None,
+                    language: None,
+                    no_speech_prob: None,
+                    avg_logprob: None,
+                }],
+            }
         }
-    };
-    Ok(Some(parsed))
+    }
+}
+
+fn required_transcript(context: &PipelineContext) -> Result<Transcript, ApplicationError> {
+    context.transcript.clone().ok_or_else(|| {
+        ApplicationError::Internal("transcription pipeline returned no transcript".to_string())
+    })
 }
 
-fn extract_alignment_words(context: &PipelineContext) -> Vec<orchestration_domain::WordTiming> {
+pub(crate) fn transcript_text(transcript: &Transcript) -> String {
+    transcript
+        .segments
+        .iter()
+        .map(|segment| segment.text.trim())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn extract_alignment_words(context: &PipelineContext) -> Vec<WordTiming> {
     if !context.aligned_words.is_empty() {
         return context.aligned_words.clone();
     }
@@ -120,3 +472,35 @@ fn extract_alignment_words(context: &PipelineContext) -> Vec<orchestration_domai
     }
     Vec::new()
 }
+
+fn quality_rejected(context: &PipelineContext) -> bool {
+    context
+        .extension("audio.quality")
+        .is_some_and(|report| report["rejected"] == json!(true))
+}
+
+fn extract_quality_issues(context: &PipelineContext) -> Vec<String> {
+    context
+        .extension("audio.quality")
+        .and_then(|report| serde_json::from_value(report["issues"].clone()).ok())
+        .unwrap_or_default()
+}
+
+fn extract_detected_language(context: &PipelineContext) -> (Option<String>, Option<f32>) {
+    let language = context
+        .extension("asr.detected_language")
+        .and_then(|value| value.as_str())
+        .map(str::to_string);
+    let probability = context
+        .extension("asr.language_probability")
+        .and_then(|value| value.as_f64())
+        .map(|probability| probability as f32);
+    (language, probability)
+}
+
+fn extract_pauses(context: &PipelineContext) -> Vec<Pause> {
+    context
+        .extension("alignment.pauses")
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+        .unwrap_or_default()
+}
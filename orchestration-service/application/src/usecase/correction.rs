@@ -0,0 +1,297 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use orchestration_domain::{
+    PipelineContext, PipelineStage, SessionStore, StoredSession, Transcript, TranscriptSegment,
+};
+
+use super::asr::transcript_text;
+use crate::{ApplicationError, CorrectTranscriptRequest, CorrectTranscriptResponse};
+
+#[async_trait]
+pub trait CorrectionUseCase: Send + Sync {
+    async fn correct(
+        &self,
+        request: CorrectTranscriptRequest,
+    ) -> Result<CorrectTranscriptResponse, ApplicationError>;
+
+    /// The stored transcript and alignment of a tenant's session, as last
+    /// corrected.
+    async fn session(
+        &self,
+        tenant_id: Option<&str>,
+        session_id: &str,
+    ) -> Result<CorrectTranscriptResponse, ApplicationError>;
+}
+
+/// Applies a client's corrections to a stored session and realigns the
+/// corrected transcript against the session's audio.
+pub struct CorrectionUseCaseImpl {
+    sessions: Arc<dyn SessionStore>,
+    alignment: Arc<dyn PipelineStage>,
+}
+
+impl CorrectionUseCaseImpl {
+    pub fn new(sessions: Arc<dyn SessionStore>, alignment: Arc<dyn PipelineStage>) -> Self {
+        Self {
+            sessions,
+            alignment,
+        }
+    }
+
+    async fn load(
+        &self,
+        tenant_id: Option<&str>,
+        session_id: &str,
+    ) -> Result<StoredSession, ApplicationError> {
+        self.sessions
+            .load(tenant_id, session_id)
+            .await?
+            .ok_or_else(|| ApplicationError::NotFound(format!("session `{session_id}`")))
+    }
+}
+
+#[async_trait]
+impl CorrectionUseCase for CorrectionUseCaseImpl {
+    async fn correct(
+        &self,
+        request: CorrectTranscriptRequest,
+    ) -> Result<CorrectTranscriptResponse, ApplicationError> {
+        let session = self
+            .load(request.tenant_id.as_deref(), &request.session_id)
+            .await?;
+        let transcript = corrected_transcript(&session, request.text, request.segments)?;
+
+        let mut context = PipelineContext::new(session.session_id.clone(), None);
+        context.audio = session.audio.clone();
+        context.transcript = Some(transcript);
+        self.alignment.execute(&mut context).await?;
+
+        let transcript = context.transcript.clone().ok_or_else(|| {
+            ApplicationError::Internal("alignment returned no transcript".to_string())
+        })?;
+        let aligned_words = context.aligned_words.clone();
+        self.sessions
+            .save(StoredSession {
+                session_id: session.session_id.clone(),
+                tenant_id: session.tenant_id,
+                audio: session.audio,
+                transcript: transcript.clone(),
+                aligned_words: aligned_words.clone(),
+            })
+            .await?;
+        tracing::debug!(
+            session_id = %session.session_id,
+            aligned_word_count = aligned_words.len(),
+            "realigned corrected transcript"
+        );
+
+        Ok(CorrectTranscriptResponse {
+            session_id: session.session_id,
+            text: transcript_text(&transcript),
+            transcript,
+            aligned_words,
+        })
+    }
+
+    async fn session(
+        &self,
+        tenant_id: Option<&str>,
+        session_id: &str,
+    ) -> Result<CorrectTranscriptResponse, ApplicationError> {
+        let session = self.load(tenant_id, session_id).await?;
+        Ok(CorrectTranscriptResponse {
+            session_id: session.session_id,
+            text: transcript_text(&session.transcript),
+            transcript: session.transcript,
+            aligned_words: session.aligned_words,
+        })
+    }
+}
+
+fn corrected_transcript(
+    session: &StoredSession,
+    text: Option<String>,
+    segments: Option<Vec<TranscriptSegment>>,
+) -> Result<Transcript, ApplicationError> {
+    match (text, segments) {
+        (Some(text), None) => {
+            let stored = &session.transcript.segments;
+            let start_ms = stored.iter().map(|segment| segment.start_ms).min().unwrap_or(0);
+            let end_ms = stored
+                .iter()
+                .map(|segment| segment.end_ms)
+                .max()
+                .unwrap_or_else(|| audio_duration_ms(session));
+            Ok(Transcript {
+                language: session.transcript.language.clone(),
+                segments: vec![TranscriptSegment {
+                    text,
+                    start_ms,
+                    end_ms,
+                    tokens: Vec::new(),
+                    speaker: None,
+                    language: None,
+                    no_speech_prob: None,
+                    avg_logprob: None,
+                }],
+            })
+        }
+        (None, Some(segments)) => {
+            let mut transcript = session.transcript.clone();
+            transcript.splice(segments);
+            Ok(transcript)
+        }
+        _ => Err(ApplicationError::Validation(
+            "send either `text` or `segments`".to_string(),
+        )),
+    }
+}
+
+fn audio_duration_ms(session: &StoredSession) -> u64 {
+    let rate = u64::from(session.audio.sample_rate_hz.max(1));
+    session.audio.samples.len() as u64 * 1_000 / rate
+}
+
+#[cfg(test)]
+mod tests {
+    use orchestration_domain::{AudioChunk, DomainError, LanguageTag, WordTiming};
+
+    use super::*;
+
+    struct MemoryStore(std::sync::Mutex<Option<StoredSession>>);
+
+    #[async_trait]
+    impl SessionStore for MemoryStore {
+        async fn save(&self, session: StoredSession) -> Result<(), DomainError> {
+            *self.0.lock().unwrap() = Some(session);
+            Ok(())
+        }
+
+        async fn load(
+            &self,
+            tenant_id: Option<&str>,
+            session_id: &str,
+        ) -> Result<Option<StoredSession>, DomainError> {
+            let stored = self.0.lock().unwrap().clone();
+            Ok(stored.filter(|session| {
+                session.tenant_id.as_deref() == tenant_id && session.session_id == session_id
+            }))
+        }
+    }
+
+    /// One word per whitespace-separated token, 100 ms each.
+    struct WordPerTokenAligner;
+
+    #[async_trait]
+    impl PipelineStage for WordPerTokenAligner {
+        fn name(&self) -> &'static str {
+            "alignment_enrich"
+        }
+
+        async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+            let transcript = context.transcript.as_ref().expect("transcript");
+            context.aligned_words = transcript_text(transcript)
+                .split_whitespace()
+                .enumerate()
+                .map(|(index, word)| WordTiming {
+                    word: word.to_string(),
+                    start_ms: index as u64 * 100,
+                    end_ms: index as u64 * 100 + 100,
+                    confidence: 1.0,
+                    speaker: None,
+                    start_sample: None,
+                    end_sample: None,
+                    energy_rms: None,
+                    pitch_hz: None,
+                    out_of_band: false,
+                })
+                .collect();
+            Ok(())
+        }
+    }
+
+    fn segment(text: &str, start_ms: u64, end_ms: u64) -> TranscriptSegment {
+        TranscriptSegment {
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+            tokens: Vec::new(),
+            speaker: None,
+            language: None,
+            no_speech_prob: None,
+            avg_logprob: None,
+        }
+    }
+
+    fn usecase() -> (Arc<MemoryStore>, CorrectionUseCaseImpl) {
+        let store = Arc::new(MemoryStore(std::sync::Mutex::new(Some(StoredSession {
+            session_id: "s-1".to_string(),
+            tenant_id: None,
+            audio: AudioChunk::mono(16_000, vec![0.0; 16_000]),
+            transcript: Transcript {
+                language: LanguageTag::en(),
+                segments: vec![segment("helo", 0, 500), segment("wrld", 500, 1_000)],
+            },
+            aligned_words: Vec::new(),
+        }))));
+        let usecase = CorrectionUseCaseImpl::new(store.clone(), Arc::new(WordPerTokenAligner));
+        (store, usecase)
+    }
+
+    fn request(
+        text: Option<&str>,
+        segments: Option<Vec<TranscriptSegment>>,
+    ) -> CorrectTranscriptRequest {
+        CorrectTranscriptRequest {
+            session_id: "s-1".to_string(),
+            text: text.map(str::to_string),
+            segments,
+            tenant_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn corrected_segments_are_spliced_realigned_and_stored() {
+        let (store, usecase) = usecase();
+        let response = usecase
+            .correct(request(None, Some(vec![segment("world", 500, 1_000)])))
+            .await
+            .expect("corrects");
+
+        assert_eq!(response.text, "helo world");
+        assert_eq!(response.aligned_words.len(), 2);
+        let stored = store.load(None, "s-1").await.unwrap().expect("still stored");
+        assert_eq!(stored.aligned_words[1].word, "world");
+    }
+
+    #[tokio::test]
+    async fn text_replaces_the_transcript_and_unknown_sessions_fail() {
+        let (_, usecase) = usecase();
+        let response = usecase
+            .correct(request(Some("hello big world"), None))
+            .await
+            .expect("corrects");
+        assert_eq!(response.transcript.segments.len(), 1);
+        assert_eq!(response.transcript.segments[0].end_ms, 1_000);
+
+        let stored = usecase.session(None, "s-1").await.expect("stored");
+        assert_eq!(stored.text, "hello big world");
+        assert!(matches!(
+            usecase.session(None, "nope").await,
+            Err(ApplicationError::NotFound(_))
+        ));
+
+        let mut missing = request(Some("x"), None);
+        missing.session_id = "nope".to_string();
+        assert!(matches!(
+            usecase.correct(missing).await,
+            Err(ApplicationError::NotFound(_))
+        ));
+        assert!(matches!(
+            usecase.correct(request(None, None)).await,
+            Err(ApplicationError::Validation(_))
+        ));
+    }
+}
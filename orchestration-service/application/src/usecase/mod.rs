@@ -1,3 +1,7 @@
 mod asr;
+mod correction;
+mod pipeline;
 
 pub use asr::{AsrUseCase, AsrUseCaseImpl};
+pub use correction::{CorrectionUseCase, CorrectionUseCaseImpl};
+pub use pipeline::{PipelineCatalogUseCase, PipelineCatalogUseCaseImpl};
@@ -0,0 +1,69 @@
+use async_trait::async_trait;
+
+use crate::{ApplicationError, PipelineCatalog, PipelineDescription};
+
+/// Describes the configured pipelines without running them.
+#[async_trait]
+pub trait PipelineCatalogUseCase: Send + Sync {
+    async fn list(&self) -> Result<PipelineCatalog, ApplicationError>;
+
+    async fn describe(&self, name: &str) -> Result<PipelineDescription, ApplicationError>;
+}
+
+/// Serves the catalog resolved at startup; pipelines do not change while
+/// the orchestrator runs.
+pub struct PipelineCatalogUseCaseImpl {
+    catalog: PipelineCatalog,
+}
+
+impl PipelineCatalogUseCaseImpl {
+    pub fn new(catalog: PipelineCatalog) -> Self {
+        Self { catalog }
+    }
+}
+
+#[async_trait]
+impl PipelineCatalogUseCase for PipelineCatalogUseCaseImpl {
+    async fn list(&self) -> Result<PipelineCatalog, ApplicationError> {
+        Ok(self.catalog.clone())
+    }
+
+    async fn describe(&self, name: &str) -> Result<PipelineDescription, ApplicationError> {
+        self.catalog
+            .pipelines
+            .iter()
+            .find(|pipeline| pipeline.name == name)
+            .cloned()
+            .ok_or_else(|| ApplicationError::NotFound(format!("pipeline '{name}'")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn catalog() -> PipelineCatalog {
+        PipelineCatalog {
+            selected: "default".to_string(),
+            pipelines: vec![PipelineDescription {
+                name: "default".to_string(),
+                selected: true,
+                steps: Vec::new(),
+                endpoints: Vec::new(),
+            }],
+            optional_steps: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn pipelines_are_described_by_name() {
+        let usecase = PipelineCatalogUseCaseImpl::new(catalog());
+
+        assert_eq!(usecase.list().await.unwrap().pipelines.len(), 1);
+        assert!(usecase.describe("default").await.unwrap().selected);
+        assert!(matches!(
+            usecase.describe("missing").await,
+            Err(ApplicationError::NotFound(_))
+        ));
+    }
+}
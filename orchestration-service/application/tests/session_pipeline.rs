@@ -1,14 +1,81 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use orchestration_application::{AsrUseCase, AsrUseCaseImpl, PipelineEngine, TranscribeAudioRequest};
+use orchestration_application::{
+    AsrUseCase, AsrUseCaseImpl, PipelineEngine, ProvidedTranscript, TenantRoute,
+    TranscribeAudioRequest, UsageMeter,
+};
 use orchestration_domain::{
-    DomainError, DomainEvent, LanguageTag, PipelineContext, PipelineStage, Transcript,
-    TranscriptSegment, WordTiming,
+    AudioSourcePort, DomainError, DomainEvent, LanguageTag, Pause, PipelineContext, PipelineStage,
+    Transcript, TranscriptSegment, WordTiming,
 };
 use async_trait::async_trait;
 
 struct MockAsrStage;
 struct MockAlignStage;
+/// Transcribes each channel as its index, ending at its sample count.
+struct ChannelEchoAsrStage;
+/// Stands in for `ProvidedTranscriptStage`, which lives in the infra crate.
+struct RequestTranscriptStage;
+/// Records the pauses `alignment_enrich` reports when configured.
+struct PauseStage;
+/// Fails the request the way `audio_quality_gate` does in reject mode.
+struct RejectingGateStage;
+/// Records the language the ASR service reports when it auto-detects one.
+struct DetectedLanguageStage;
+/// Transcribes the audio as the Whisper prompt it was given.
+struct PromptEchoAsrStage;
+/// Serves a 22.05 kHz WAV file for any reference.
+struct WavSource;
+
+#[async_trait]
+impl AudioSourcePort for WavSource {
+    async fn fetch(&self, _uri: &str) -> Result<Vec<u8>, DomainError> {
+        Ok(test_audio::wav_bytes(&test_audio::tone(440.0, 22_050, 100), 22_050))
+    }
+}
+
+#[async_trait]
+impl PipelineStage for DetectedLanguageStage {
+    fn name(&self) -> &'static str {
+        "detected-language"
+    }
+
+    async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+        context.set_extension("asr.detected_language", serde_json::json!("en"));
+        context.set_extension("asr.language_probability", serde_json::json!(0.75));
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PipelineStage for PromptEchoAsrStage {
+    fn name(&self) -> &'static str {
+        "prompt-echo-asr"
+    }
+
+    async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+        let text = context
+            .extension("asr.prompt")
+            .and_then(|prompt| prompt.as_str())
+            .unwrap_or_default()
+            .to_string();
+        context.transcript = Some(Transcript {
+            language: LanguageTag::en(),
+            segments: vec![TranscriptSegment {
+                text,
+                start_ms: 0,
+                end_ms: 500,
+                tokens: Vec::new(),
+                speaker: None,
+                language: None,
+                no_speech_prob: None,
+                avg_logprob: None,
+            }],
+        });
+        Ok(())
+    }
+}
 
 #[async_trait]
 impl PipelineStage for MockAsrStage {
@@ -18,12 +85,16 @@ impl PipelineStage for MockAsrStage {
 
     async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
         let transcript = Transcript {
-            language: LanguageTag::En,
+            language: LanguageTag::en(),
             segments: vec![TranscriptSegment {
                 text: "hello world".to_string(),
                 start_ms: 0,
                 end_ms: 500,
                 tokens: Vec::new(),
+                speaker: None,
+                language: None,
+                no_speech_prob: None,
+                avg_logprob: None,
             }],
         };
         context.transcript = Some(transcript.clone());
@@ -46,6 +117,12 @@ impl PipelineStage for MockAlignStage {
             start_ms: 0,
             end_ms: 250,
             confidence: 0.9,
+            speaker: None,
+            start_sample: None,
+            end_sample: None,
+            energy_rms: None,
+            pitch_hz: None,
+            out_of_band: false,
         }];
         context.aligned_words = words.clone();
         context.events.push(DomainEvent::AlignmentUpdate { words });
@@ -53,16 +130,97 @@ impl PipelineStage for MockAlignStage {
     }
 }
 
+#[async_trait]
+impl PipelineStage for ChannelEchoAsrStage {
+    fn name(&self) -> &'static str {
+        "channel-echo-asr"
+    }
+
+    async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+        let channel = u64::from(context.audio.channel);
+        context.transcript = Some(Transcript {
+            language: LanguageTag::fr(),
+            segments: vec![TranscriptSegment {
+                text: format!("channel {channel}"),
+                start_ms: 100 - channel * 50,
+                end_ms: context.audio.samples.len() as u64,
+                tokens: Vec::new(),
+                speaker: None,
+                language: None,
+                no_speech_prob: None,
+                avg_logprob: None,
+            }],
+        });
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PipelineStage for RequestTranscriptStage {
+    fn name(&self) -> &'static str {
+        "request-transcript"
+    }
+
+    async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+        let value = context
+            .take_extension("request.transcript")
+            .ok_or_else(|| DomainError::invalid_input("no transcript"))?;
+        context.transcript = Some(serde_json::from_value(value).expect("transcript json"));
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PipelineStage for PauseStage {
+    fn name(&self) -> &'static str {
+        "pauses"
+    }
+
+    async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+        let pauses = vec![Pause {
+            start_ms: 250,
+            end_ms: 600,
+        }];
+        context.set_extension("alignment.pauses", serde_json::json!(pauses));
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PipelineStage for RejectingGateStage {
+    fn name(&self) -> &'static str {
+        "audio_quality_gate"
+    }
+
+    async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+        context.set_extension(
+            "audio.quality",
+            serde_json::json!({ "issues": ["silent"], "rejected": true }),
+        );
+        Err(DomainError::invalid_input("audio failed the quality gate: silent"))
+    }
+}
+
 #[tokio::test]
 async fn transcribe_command_flow_produces_transcript_and_alignment() {
     let pipeline = PipelineEngine::new(vec![Arc::new(MockAsrStage), Arc::new(MockAlignStage)]);
     let usecase: Arc<dyn AsrUseCase> = Arc::new(AsrUseCaseImpl::new(pipeline, 16_000));
     let response = usecase
         .transcribe(TranscribeAudioRequest {
-            samples: vec![0.1, 0.2, 0.3],
+            samples: test_audio::speech_like(16_000, 500),
+            audio_url: None,
             sample_rate_hz: Some(16_000),
             language_hint: Some("en".to_string()),
             session_id: Some("it-session".to_string()),
+            model: None,
+            channels: None,
+            pipeline: None,
+            transcript: None,
+            decode_profile: None,
+            grammar: None,
+            prompt: None,
+            time_offset_ms: None,
+            tenant_id: None,
         })
         .await
         .expect("pipeline succeeds");
@@ -72,3 +230,402 @@ async fn transcribe_command_flow_produces_transcript_and_alignment() {
     assert!(!response.aligned_words.is_empty());
     assert_eq!(response.text, "hello world");
 }
+
+#[tokio::test]
+async fn stereo_request_runs_one_pipeline_per_channel() {
+    let pipeline = PipelineEngine::new(vec![Arc::new(ChannelEchoAsrStage)]);
+    let usecase = AsrUseCaseImpl::new(pipeline, 16_000);
+    let response = usecase
+        .transcribe(TranscribeAudioRequest {
+            samples: vec![0.1, -0.1, 0.2, -0.2, 0.3, -0.3],
+            audio_url: None,
+            sample_rate_hz: Some(16_000),
+            language_hint: None,
+            session_id: Some("call".to_string()),
+            model: None,
+            channels: Some(2),
+            pipeline: None,
+            transcript: None,
+            decode_profile: None,
+            grammar: None,
+            prompt: None,
+            time_offset_ms: None,
+            tenant_id: None,
+        })
+        .await
+        .expect("pipeline succeeds");
+
+    assert_eq!(response.channels.len(), 2);
+    assert_eq!(response.channels[1].text, "channel 1");
+    assert_eq!(response.channels[1].transcript.segments[0].end_ms, 3);
+    assert_eq!(response.text, "channel 1 channel 0");
+    let speakers: Vec<_> = response
+        .transcript
+        .segments
+        .iter()
+        .map(|segment| segment.speaker.as_deref())
+        .collect();
+    assert_eq!(speakers, vec![Some("channel_1"), Some("channel_0")]);
+    assert!(response.output_audio.is_none());
+}
+
+#[tokio::test]
+async fn stereo_request_merges_the_pauses_of_every_channel() {
+    let pipeline = PipelineEngine::new(vec![Arc::new(ChannelEchoAsrStage), Arc::new(PauseStage)]);
+    let usecase = AsrUseCaseImpl::new(pipeline, 16_000);
+    let response = usecase
+        .transcribe(TranscribeAudioRequest {
+            samples: vec![0.1, -0.1, 0.2, -0.2],
+            audio_url: None,
+            sample_rate_hz: Some(16_000),
+            language_hint: None,
+            session_id: None,
+            model: None,
+            channels: Some(2),
+            pipeline: None,
+            transcript: None,
+            decode_profile: None,
+            grammar: None,
+            prompt: None,
+            time_offset_ms: Some(1_000),
+            tenant_id: None,
+        })
+        .await
+        .expect("pipeline succeeds");
+
+    let shifted = Pause {
+        start_ms: 1_250,
+        end_ms: 1_600,
+    };
+    for channel in &response.channels {
+        assert_eq!(channel.pauses, vec![shifted.clone()]);
+    }
+    assert_eq!(response.pauses, vec![shifted.clone(), shifted]);
+}
+
+#[tokio::test]
+async fn stereo_request_rejects_a_partial_frame() {
+    let pipeline = PipelineEngine::new(vec![Arc::new(ChannelEchoAsrStage)]);
+    let usecase = AsrUseCaseImpl::new(pipeline, 16_000);
+    let error = usecase
+        .transcribe(TranscribeAudioRequest {
+            samples: vec![0.1, -0.1, 0.2],
+            audio_url: None,
+            sample_rate_hz: Some(16_000),
+            language_hint: None,
+            session_id: None,
+            model: None,
+            channels: Some(2),
+            pipeline: None,
+            transcript: None,
+            decode_profile: None,
+            grammar: None,
+            prompt: None,
+            time_offset_ms: None,
+            tenant_id: None,
+        })
+        .await
+        .expect_err("three samples are not whole stereo frames");
+
+    assert!(error.to_string().contains("interleaved"), "{error}");
+}
+
+#[tokio::test]
+async fn align_only_pipeline_aligns_the_provided_text() {
+    let default = PipelineEngine::new(vec![Arc::new(MockAsrStage), Arc::new(MockAlignStage)]);
+    let align_only = PipelineEngine::new(vec![
+        Arc::new(RequestTranscriptStage),
+        Arc::new(MockAlignStage),
+    ]);
+    let usecase = AsrUseCaseImpl::new(default, 16_000).with_pipeline("align_only", align_only);
+    let request = |pipeline: &str| TranscribeAudioRequest {
+        samples: test_audio::speech_like(16_000, 2_000),
+        audio_url: None,
+        sample_rate_hz: Some(16_000),
+        language_hint: Some("en".to_string()),
+        session_id: None,
+        model: None,
+        channels: None,
+        pipeline: Some(pipeline.to_string()),
+        transcript: Some(ProvidedTranscript::Text("chapter one".to_string())),
+        decode_profile: None,
+        grammar: None,
+        prompt: None,
+        time_offset_ms: None,
+        tenant_id: None,
+    };
+
+    let response = usecase
+        .transcribe(request("align_only"))
+        .await
+        .expect("pipeline succeeds");
+    assert_eq!(response.text, "chapter one");
+    assert_eq!(response.transcript.segments[0].end_ms, 2_000);
+    assert_eq!(response.transcript.language, LanguageTag::en());
+    assert!(!response.aligned_words.is_empty());
+
+    let error = usecase
+        .transcribe(request("missing"))
+        .await
+        .expect_err("unknown pipeline");
+    assert!(error.to_string().contains("unknown pipeline"), "{error}");
+}
+
+#[tokio::test]
+async fn pauses_reported_by_alignment_reach_the_response() {
+    let pipeline = PipelineEngine::new(vec![
+        Arc::new(MockAsrStage),
+        Arc::new(MockAlignStage),
+        Arc::new(PauseStage),
+    ]);
+    let usecase = AsrUseCaseImpl::new(pipeline, 16_000);
+    let response = usecase
+        .transcribe(TranscribeAudioRequest {
+            samples: test_audio::speech_like(16_000, 1_000),
+            audio_url: None,
+            sample_rate_hz: Some(16_000),
+            language_hint: Some("en".to_string()),
+            session_id: None,
+            model: None,
+            channels: None,
+            pipeline: None,
+            transcript: None,
+            decode_profile: None,
+            grammar: None,
+            prompt: None,
+            time_offset_ms: None,
+            tenant_id: None,
+        })
+        .await
+        .expect("pipeline succeeds");
+
+    assert_eq!(
+        response.pauses,
+        vec![Pause {
+            start_ms: 250,
+            end_ms: 600,
+        }]
+    );
+}
+
+#[tokio::test]
+async fn quality_gate_rejection_has_its_own_error_code() {
+    let pipeline = PipelineEngine::new(vec![Arc::new(RejectingGateStage), Arc::new(MockAsrStage)]);
+    let usecase = AsrUseCaseImpl::new(pipeline, 16_000);
+    let error = usecase
+        .transcribe(TranscribeAudioRequest {
+            samples: vec![0.0; 1_600],
+            audio_url: None,
+            sample_rate_hz: Some(16_000),
+            language_hint: None,
+            session_id: None,
+            model: None,
+            channels: None,
+            pipeline: None,
+            transcript: None,
+            decode_profile: None,
+            grammar: None,
+            prompt: None,
+            time_offset_ms: None,
+            tenant_id: None,
+        })
+        .await
+        .expect_err("gate rejects silence");
+
+    assert_eq!(error.error_code().as_str(), "audio_quality");
+    assert!(error.to_string().contains("silent"), "{error}");
+}
+
+#[tokio::test]
+async fn referenced_audio_is_fetched_before_the_pipeline_runs() {
+    let request = || TranscribeAudioRequest {
+        samples: Vec::new(),
+        audio_url: Some("s3://calls/call.wav".to_string()),
+        sample_rate_hz: None,
+        language_hint: None,
+        session_id: None,
+        model: None,
+        channels: None,
+        pipeline: None,
+        transcript: None,
+        decode_profile: None,
+        grammar: None,
+        prompt: None,
+        time_offset_ms: None,
+        tenant_id: None,
+    };
+
+    let pipeline = || PipelineEngine::new(vec![Arc::new(ChannelEchoAsrStage)]);
+    let error = AsrUseCaseImpl::new(pipeline(), 16_000)
+        .transcribe(request())
+        .await
+        .expect_err("no audio source configured");
+    assert!(error.to_string().contains("audio_fetch"), "{error}");
+
+    let usecase = AsrUseCaseImpl::new(pipeline(), 16_000).with_audio_source(Arc::new(WavSource));
+    let response = usecase.transcribe(request()).await.expect("pipeline succeeds");
+    assert_eq!(response.transcript.segments[0].end_ms, 2_205);
+}
+
+#[tokio::test]
+async fn tenant_route_picks_the_pipeline_of_requests_naming_none() {
+    let echo = PipelineEngine::new(vec![Arc::new(ChannelEchoAsrStage)]);
+    let usecase = AsrUseCaseImpl::new(PipelineEngine::new(vec![Arc::new(MockAsrStage)]), 16_000)
+        .with_tenant_route(
+            "acme",
+            TenantRoute {
+                pipeline: Some("echo".to_string()),
+                model: Some("large-v3".to_string()),
+                pipelines: HashMap::from([("echo".to_string(), echo)]),
+            },
+        );
+    let request = |tenant_id: Option<&str>| TranscribeAudioRequest {
+        samples: vec![0.1; 160],
+        audio_url: None,
+        sample_rate_hz: Some(16_000),
+        language_hint: None,
+        session_id: None,
+        model: None,
+        channels: None,
+        pipeline: None,
+        transcript: None,
+        decode_profile: None,
+        grammar: None,
+        prompt: None,
+        time_offset_ms: None,
+        tenant_id: tenant_id.map(str::to_string),
+    };
+
+    let acme = usecase.transcribe(request(Some("acme"))).await.expect("acme");
+    assert_eq!(acme.text, "channel 0");
+    let globex = usecase.transcribe(request(Some("globex"))).await.expect("globex");
+    assert_eq!(globex.text, "hello world");
+    let anonymous = usecase.transcribe(request(None)).await.expect("no tenant");
+    assert_eq!(anonymous.text, "hello world");
+}
+
+#[tokio::test]
+async fn successful_requests_are_accounted_per_tenant() {
+    let meter = UsageMeter::default();
+    let pipeline = PipelineEngine::new(vec![Arc::new(ChannelEchoAsrStage)]);
+    let usecase = AsrUseCaseImpl::new(pipeline, 16_000).with_usage_meter(meter.clone());
+    let request = |channels: u16, samples: usize| TranscribeAudioRequest {
+        samples: vec![0.1; samples],
+        audio_url: None,
+        sample_rate_hz: Some(16_000),
+        language_hint: None,
+        session_id: None,
+        model: Some("tiny".to_string()),
+        channels: Some(channels),
+        pipeline: None,
+        transcript: None,
+        decode_profile: None,
+        grammar: None,
+        prompt: None,
+        time_offset_ms: None,
+        tenant_id: Some("acme".to_string()),
+    };
+
+    usecase.transcribe(request(1, 8_000)).await.expect("mono");
+    usecase.transcribe(request(2, 16_000)).await.expect("stereo");
+    assert!(usecase.transcribe(request(2, 3)).await.is_err());
+
+    let totals = meter.totals();
+    assert_eq!(totals.len(), 1);
+    assert_eq!(totals[0].tenant_id.as_deref(), Some("acme"));
+    assert_eq!(totals[0].model, "tiny");
+    assert_eq!(totals[0].requests, 2);
+    assert_eq!(totals[0].audio_seconds, 1.5);
+}
+
+#[tokio::test]
+async fn time_offset_places_response_timings_on_the_stream_timeline() {
+    let pipeline = PipelineEngine::new(vec![
+        Arc::new(MockAsrStage),
+        Arc::new(MockAlignStage),
+        Arc::new(PauseStage),
+    ]);
+    let usecase = AsrUseCaseImpl::new(pipeline, 16_000);
+    let response = usecase
+        .transcribe(TranscribeAudioRequest {
+            samples: test_audio::speech_like(16_000, 500),
+            audio_url: None,
+            sample_rate_hz: Some(16_000),
+            language_hint: None,
+            session_id: None,
+            model: None,
+            channels: None,
+            pipeline: None,
+            transcript: None,
+            decode_profile: None,
+            grammar: None,
+            prompt: None,
+            time_offset_ms: Some(60_000),
+            tenant_id: None,
+        })
+        .await
+        .expect("pipeline succeeds");
+
+    let segment = &response.transcript.segments[0];
+    assert_eq!((segment.start_ms, segment.end_ms), (60_000, 60_500));
+    assert_eq!(response.aligned_words[0].end_ms, 60_250);
+    assert_eq!(response.pauses[0].start_ms, 60_250);
+}
+
+#[tokio::test]
+async fn auto_detected_language_is_reported() {
+    let pipeline = PipelineEngine::new(vec![
+        Arc::new(MockAsrStage),
+        Arc::new(DetectedLanguageStage),
+    ]);
+    let usecase = AsrUseCaseImpl::new(pipeline, 16_000);
+    let response = usecase
+        .transcribe(TranscribeAudioRequest {
+            samples: test_audio::speech_like(16_000, 500),
+            audio_url: None,
+            sample_rate_hz: Some(16_000),
+            language_hint: None,
+            session_id: None,
+            model: None,
+            channels: None,
+            pipeline: None,
+            transcript: None,
+            decode_profile: None,
+            grammar: None,
+            prompt: None,
+            time_offset_ms: None,
+            tenant_id: None,
+        })
+        .await
+        .expect("pipeline succeeds");
+
+    assert_eq!(response.detected_language.as_deref(), Some("en"));
+    assert_eq!(response.language_probability, Some(0.75));
+}
+
+#[tokio::test]
+async fn request_prompt_reaches_the_asr_stage() {
+    let pipeline = PipelineEngine::new(vec![Arc::new(PromptEchoAsrStage)]);
+    let usecase = AsrUseCaseImpl::new(pipeline, 16_000);
+    let response = usecase
+        .transcribe(TranscribeAudioRequest {
+            samples: test_audio::speech_like(16_000, 500),
+            audio_url: None,
+            sample_rate_hz: Some(16_000),
+            language_hint: None,
+            session_id: None,
+            model: None,
+            channels: None,
+            pipeline: None,
+            transcript: None,
+            decode_profile: None,
+            grammar: None,
+            prompt: Some("Vocal Agent".to_string()),
+            time_offset_ms: None,
+            tenant_id: None,
+        })
+        .await
+        .expect("pipeline succeeds");
+
+    assert_eq!(response.text, "Vocal Agent");
+}
@@ -16,6 +16,10 @@ pub struct OrchestrationConfig {
     pub server: ServerConfig,
     #[serde(default)]
     pub logging: LoggingConfig,
+    /// Admin API listener (drain, reload, metrics, log level), off when
+    /// unset; bind it to an address the public cannot reach.
+    #[serde(default)]
+    pub admin: Option<ServerConfig>,
     #[serde(default)]
     pub queue: QueueConfig,
     #[serde(default)]
@@ -36,6 +40,227 @@ pub struct ServiceConfig {
     pub tempo: GrpcEndpointConfig,
     #[serde(default)]
     pub pipeline: PipelineConfig,
+    #[serde(default)]
+    pub batch: BatchConfig,
+    #[serde(default)]
+    pub sessions: SessionConfig,
+    #[serde(default)]
+    pub dedup: DedupConfig,
+    #[serde(default)]
+    pub carry_over: CarryOverConfig,
+    #[serde(default)]
+    pub quality_gate: QualityGateConfig,
+    #[serde(default)]
+    pub two_pass: TwoPassConfig,
+    #[serde(default)]
+    pub ensemble: EnsembleConfig,
+    #[serde(default)]
+    pub normalization: NormalizationConfig,
+    #[serde(default)]
+    pub pauses: PauseConfig,
+    #[serde(default)]
+    pub disfluency: DisfluencyConfig,
+    #[serde(default)]
+    pub log_sampling: LogSamplingConfig,
+    #[serde(default)]
+    pub streaming: StreamingConfig,
+    /// Extra audio steps by name, each running its own ordered list of
+    /// audio-service transform ops.
+    #[serde(default)]
+    pub audio_recipes: HashMap<String, Vec<String>>,
+    #[cfg(feature = "monolith")]
+    #[serde(default)]
+    pub monolith: MonolithConfig,
+    /// Module run by the `wasm_step` step; unset leaves the step unavailable.
+    #[serde(default)]
+    pub wasm_step: Option<WasmStepConfig>,
+    /// Webhook called by the `http_enrich` step; unset leaves the step
+    /// unavailable.
+    #[serde(default)]
+    pub http_enrich: Option<HttpEnrichConfig>,
+    /// Script run by the `script_step` step; unset leaves the step
+    /// unavailable.
+    #[serde(default)]
+    pub script_step: Option<ScriptStepConfig>,
+    /// Where the `record_session` step writes; unset leaves the step
+    /// unavailable.
+    #[serde(default)]
+    pub recording: Option<RecordingConfig>,
+    /// Where the `store_result` step writes; unset leaves the step
+    /// unavailable.
+    #[serde(default)]
+    pub result_store: Option<ResultStoreConfig>,
+    /// Downloads of requests naming an `audio_url`; unset refuses them.
+    #[serde(default)]
+    pub audio_fetch: Option<AudioFetchConfig>,
+    #[serde(default)]
+    pub tenancy: TenancyConfig,
+    /// Where usage events are sent; unset sends none.
+    #[serde(default)]
+    pub usage_events: Option<UsageEventsConfig>,
+}
+
+/// Rhai clean-up script, see `ScriptStepStage`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptStepConfig {
+    /// Defines `fn process(transcript)` returning the transcript.
+    pub script_path: String,
+    /// Operations one run may perform before it is stopped.
+    #[serde(default = "default_script_max_operations")]
+    pub max_operations: u64,
+}
+
+/// Webhook of the `http_enrich` step.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpEnrichConfig {
+    /// Receives the session id, transcript and extensions as a JSON POST and
+    /// answers a JSON object merged into the extensions.
+    pub url: String,
+    #[serde(default = "default_http_enrich_request_timeout_ms")]
+    pub request_timeout_ms: u64,
+    /// Sent with every request, e.g. `Authorization`.
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+/// Raw audio and events kept by the `record_session` step, see
+/// `RecordSessionStage`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingConfig {
+    pub store: ObjectStoreConfig,
+    /// Key path the sessions are recorded under.
+    #[serde(default = "default_recording_prefix")]
+    pub prefix: String,
+    /// Recordings are deleted this long after their last write; 0 keeps
+    /// them forever.
+    #[serde(default = "default_recording_retention_hours")]
+    pub retention_hours: u64,
+    /// How often expired recordings are looked for.
+    #[serde(default = "default_recording_sweep_interval_secs")]
+    pub sweep_interval_secs: u64,
+}
+
+/// Final transcripts written by the `store_result` step, see
+/// `StoreResultStage`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultStoreConfig {
+    pub store: ObjectStoreConfig,
+    /// Key path the results are written under, one directory per session.
+    #[serde(default = "default_result_store_prefix")]
+    pub prefix: String,
+    /// Any of `json`, `srt` and `vtt`.
+    #[serde(default = "default_result_store_formats")]
+    pub formats: Vec<String>,
+    /// Longer subtitle segments are split between words.
+    #[serde(default = "default_result_store_max_cue_ms")]
+    pub max_cue_ms: u64,
+    #[serde(default = "default_result_store_max_cue_chars")]
+    pub max_cue_chars: usize,
+}
+
+/// A local directory or an S3-compatible bucket.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ObjectStoreConfig {
+    Directory {
+        path: String,
+    },
+    /// Unset credentials and region are read from the `AWS_*` environment
+    /// variables.
+    S3 {
+        bucket: String,
+        /// e.g. `http://minio:9000`; AWS when unset.
+        #[serde(default)]
+        endpoint: Option<String>,
+        #[serde(default)]
+        region: Option<String>,
+        #[serde(default)]
+        access_key_id: Option<String>,
+        #[serde(default)]
+        secret_access_key: Option<String>,
+        #[serde(default)]
+        allow_http: bool,
+    },
+}
+
+/// Limits and S3 credentials for fetching `audio_url` references
+/// (`s3://bucket/key`, `http://`, `https://`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioFetchConfig {
+    /// Covers connecting and downloading the whole file.
+    #[serde(default = "default_audio_fetch_timeout_ms")]
+    pub timeout_ms: u64,
+    #[serde(default = "default_audio_fetch_max_bytes")]
+    pub max_bytes: u64,
+    /// S3-compatible endpoint, e.g. `http://minio:9000`; AWS when unset.
+    /// Unset credentials and region are read from the `AWS_*` environment
+    /// variables.
+    #[serde(default)]
+    pub s3_endpoint: Option<String>,
+    #[serde(default)]
+    pub s3_region: Option<String>,
+    #[serde(default)]
+    pub s3_access_key_id: Option<String>,
+    #[serde(default)]
+    pub s3_secret_access_key: Option<String>,
+    #[serde(default)]
+    pub s3_allow_http: bool,
+    /// Hosts `http://` and `https://` references may name; `*.example.com`
+    /// also allows its subdomains. Empty refuses every URL.
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
+    /// Buckets `s3://` references may name. Empty refuses every bucket.
+    #[serde(default)]
+    pub allowed_buckets: Vec<String>,
+}
+
+/// Periodic usage events for billing, see `UsageWebhook`. Usage is also
+/// served on the admin server's `/admin/usage`, configured or not.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageEventsConfig {
+    /// Receives `{"records": [...]}`, the usage accrued since the previous
+    /// event, as a JSON POST. Undelivered usage is sent with the next event.
+    pub webhook_url: String,
+    #[serde(default = "default_usage_events_interval_secs")]
+    pub interval_secs: u64,
+    #[serde(default = "default_http_enrich_request_timeout_ms")]
+    pub request_timeout_ms: u64,
+    /// Sent with every request, e.g. `Authorization`.
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+/// Sandboxed WebAssembly post-processing, see `WasmStepStage`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WasmStepConfig {
+    /// `.wasm` binary or `.wat` text.
+    pub module_path: String,
+    /// Instruction budget of one run.
+    #[serde(default = "default_wasm_fuel")]
+    pub fuel: u64,
+    #[serde(default = "default_wasm_max_memory_bytes")]
+    pub max_memory_bytes: usize,
+}
+
+/// Models the `monolith` build loads itself instead of calling the ASR and
+/// alignment services; same settings as those services' own config.
+#[cfg(feature = "monolith")]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MonolithConfig {
+    #[serde(default)]
+    pub asr: asr_configuration::AsrRuntimeConfig,
+    #[serde(default)]
+    pub alignment: alignment_configuration::AlignmentRuntimeConfig,
+}
+
+/// Limits of `POST /api/asr/transcribe-batch`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchConfig {
+    #[serde(default = "default_batch_max_items")]
+    pub max_items: usize,
+    /// Items whose pipelines run at the same time.
+    #[serde(default = "default_batch_max_concurrency")]
+    pub max_concurrency: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,6 +279,41 @@ pub struct GrpcEndpointConfig {
     pub max_decoding_message_bytes: usize,
     #[serde(default = "default_grpc_max_message_bytes")]
     pub max_encoding_message_bytes: usize,
+    #[serde(default = "default_grpc_pool_size")]
+    pub pool_size: usize,
+    #[serde(default = "default_grpc_reconnect_initial_backoff_ms")]
+    pub reconnect_initial_backoff_ms: u64,
+    #[serde(default = "default_grpc_reconnect_max_backoff_ms")]
+    pub reconnect_max_backoff_ms: u64,
+    #[serde(default = "default_grpc_resolve_dns_on_reconnect")]
+    pub resolve_dns_on_reconnect: bool,
+    /// Extra `host:port` replicas; when set, these replace `host`/`port`.
+    #[serde(default)]
+    pub replicas: Vec<String>,
+    /// Expand every target into one replica per address it resolves to at
+    /// startup. Replicas keep the target's host for TLS and, with
+    /// `resolve_dns_on_reconnect`, resolve it again when they reconnect.
+    #[serde(default)]
+    pub resolve_all_addresses: bool,
+    #[serde(default)]
+    pub load_balancing: LoadBalancingPolicy,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoadBalancingPolicy {
+    RoundRobin,
+    LeastLoaded,
+}
+
+impl GrpcEndpointConfig {
+    pub fn targets(&self) -> Vec<String> {
+        if self.replicas.is_empty() {
+            vec![format!("{}:{}", self.host, self.port)]
+        } else {
+            self.replicas.clone()
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -62,6 +322,10 @@ pub struct PipelineConfig {
     pub selected: String,
     #[serde(default = "default_pipeline_definitions")]
     pub definitions: HashMap<String, PipelineDefinitionConfig>,
+    /// Directory of plugin libraries adding pipeline steps; needs the
+    /// `dylib-plugins` feature.
+    #[serde(default)]
+    pub plugin_dir: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -95,6 +359,7 @@ impl Default for OrchestrationConfig {
         Self {
             server: ServerConfig::default(),
             logging: LoggingConfig::default(),
+            admin: None,
             queue: QueueConfig::default(),
             service: ServiceConfig::default(),
         }
@@ -110,6 +375,368 @@ impl Default for ServiceConfig {
             tts: default_tts_endpoint(),
             tempo: default_tempo_endpoint(),
             pipeline: PipelineConfig::default(),
+            batch: BatchConfig::default(),
+            sessions: SessionConfig::default(),
+            dedup: DedupConfig::default(),
+            carry_over: CarryOverConfig::default(),
+            quality_gate: QualityGateConfig::default(),
+            two_pass: TwoPassConfig::default(),
+            ensemble: EnsembleConfig::default(),
+            normalization: NormalizationConfig::default(),
+            pauses: PauseConfig::default(),
+            disfluency: DisfluencyConfig::default(),
+            log_sampling: LogSamplingConfig::default(),
+            streaming: StreamingConfig::default(),
+            audio_recipes: HashMap::new(),
+            #[cfg(feature = "monolith")]
+            monolith: MonolithConfig::default(),
+            wasm_step: None,
+            http_enrich: None,
+            script_step: None,
+            recording: None,
+            result_store: None,
+            audio_fetch: None,
+            tenancy: TenancyConfig::default(),
+            usage_events: None,
+        }
+    }
+}
+
+/// Sessions kept by the `store_session` stage for transcript corrections.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionConfig {
+    /// Older sessions are dropped once this many are stored.
+    #[serde(default = "default_max_sessions")]
+    pub max_sessions: usize,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            max_sessions: default_max_sessions(),
+        }
+    }
+}
+
+/// Audio fingerprints remembered by the `dedup_lookup` step.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DedupConfig {
+    /// Older fingerprints are dropped once this many are recorded.
+    #[serde(default = "default_max_fingerprints")]
+    pub max_fingerprints: usize,
+}
+
+impl Default for DedupConfig {
+    fn default() -> Self {
+        Self {
+            max_fingerprints: default_max_fingerprints(),
+        }
+    }
+}
+
+/// Previous transcript handed to Whisper by the `context_carry_over` step.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CarryOverConfig {
+    /// Characters kept from the end of the session's previous transcript;
+    /// the ASR service then trims them to its `prompt_max_tokens`.
+    #[serde(default = "default_carry_over_max_chars")]
+    pub max_chars: usize,
+}
+
+impl Default for CarryOverConfig {
+    fn default() -> Self {
+        Self {
+            max_chars: default_carry_over_max_chars(),
+        }
+    }
+}
+
+/// Customers served by one deployment, named by the `x-tenant-id` header.
+/// Their sessions, fingerprints and stored objects are kept apart.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TenancyConfig {
+    /// Refuse requests without a tenant id.
+    #[serde(default)]
+    pub required: bool,
+    /// Concurrent transcriptions of a tenant not listed in `tenants`;
+    /// unset leaves them unlimited.
+    #[serde(default)]
+    pub default_max_concurrent_requests: Option<usize>,
+    /// Limits of individual tenants, by tenant id.
+    #[serde(default)]
+    pub tenants: HashMap<String, TenantConfig>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TenantConfig {
+    /// Falls back to `default_max_concurrent_requests` when unset.
+    #[serde(default)]
+    pub max_concurrent_requests: Option<usize>,
+    /// Definition in `service.pipeline.definitions` run for the tenant's
+    /// requests that name no pipeline; unset runs `service.pipeline.selected`.
+    #[serde(default)]
+    pub pipeline: Option<String>,
+    /// ASR model of the tenant's requests that name none.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// ASR service of the tenant's `asr_transcribe` steps instead of
+    /// `service.asr`.
+    #[serde(default)]
+    pub asr: Option<GrpcEndpointConfig>,
+    /// Alignment service of the tenant's `alignment_enrich` steps instead of
+    /// `service.alignment`.
+    #[serde(default)]
+    pub alignment: Option<GrpcEndpointConfig>,
+}
+
+/// Checks of the `audio_quality_gate` step, run on the request audio as
+/// uploaded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QualityGateConfig {
+    /// Fail failing requests with `audio_quality`; otherwise they are only
+    /// flagged in the response.
+    #[serde(default = "default_quality_gate_reject")]
+    pub reject: bool,
+    #[serde(default = "default_quality_gate_min_duration_ms")]
+    pub min_duration_ms: u64,
+    /// Audio whose overall RMS is below this level counts as silent.
+    #[serde(default = "default_quality_gate_silence_rms_dbfs")]
+    pub silence_rms_dbfs: f32,
+    /// Largest share of samples at full scale.
+    #[serde(default = "default_quality_gate_max_clipped_ratio")]
+    pub max_clipped_ratio: f32,
+    /// Accepted upload rates; empty accepts any.
+    #[serde(default)]
+    pub allowed_sample_rates_hz: Vec<u32>,
+}
+
+impl Default for QualityGateConfig {
+    fn default() -> Self {
+        Self {
+            reject: default_quality_gate_reject(),
+            min_duration_ms: default_quality_gate_min_duration_ms(),
+            silence_rms_dbfs: default_quality_gate_silence_rms_dbfs(),
+            max_clipped_ratio: default_quality_gate_max_clipped_ratio(),
+            allowed_sample_rates_hz: Vec::new(),
+        }
+    }
+}
+
+/// Models of the `two_pass_transcribe` step. Both must be selectable on the
+/// ASR service (`service.asr.models`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TwoPassConfig {
+    /// Answers the request.
+    #[serde(default = "default_two_pass_fast_model")]
+    pub fast_model: String,
+    /// Re-transcribes in the background; its result replaces the stored
+    /// session and is sent to streaming sessions as their final transcript.
+    #[serde(default = "default_two_pass_accurate_model")]
+    pub accurate_model: String,
+    /// Background passes running at once; over it the fast transcript is
+    /// final.
+    #[serde(default = "default_two_pass_max_background_passes")]
+    pub max_background_passes: usize,
+}
+
+impl Default for TwoPassConfig {
+    fn default() -> Self {
+        Self {
+            fast_model: default_two_pass_fast_model(),
+            accurate_model: default_two_pass_accurate_model(),
+            max_background_passes: default_two_pass_max_background_passes(),
+        }
+    }
+}
+
+/// Models the `ensemble_transcribe` step runs side by side. Each must be
+/// selectable on the ASR service (`service.asr.models`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnsembleConfig {
+    /// The first model also provides the merged transcript's segments.
+    #[serde(default = "default_ensemble_models")]
+    pub models: Vec<String>,
+}
+
+impl Default for EnsembleConfig {
+    fn default() -> Self {
+        Self {
+            models: default_ensemble_models(),
+        }
+    }
+}
+
+/// Replacement dictionary of the `normalize_transcript` step, applied in
+/// order.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NormalizationConfig {
+    #[serde(default)]
+    pub rules: Vec<NormalizationRuleConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NormalizationRuleConfig {
+    /// Phrase to replace, or a regular expression when `regex` is set.
+    pub pattern: String,
+    /// May reference capture groups (`$1`) in regex rules.
+    pub replacement: String,
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default)]
+    pub ignore_case: bool,
+    /// BCP-47 tag; `en` also covers `en-US`. Rules without one apply to
+    /// every language.
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+/// Pause entries emitted by `alignment_enrich`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PauseConfig {
+    /// Gaps between aligned words longer than this are reported as pauses.
+    /// Unset disables pause detection.
+    #[serde(default)]
+    pub threshold_ms: Option<u64>,
+}
+
+/// Words the `tag_disfluencies` step flags.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisfluencyConfig {
+    /// Filler words by BCP-47 tag; `en` also covers `en-US`.
+    #[serde(default = "default_disfluency_fillers")]
+    pub fillers: HashMap<String, Vec<String>>,
+    /// Also flag a word repeated right after itself.
+    #[serde(default = "default_disfluency_tag_repetitions")]
+    pub tag_repetitions: bool,
+}
+
+impl Default for DisfluencyConfig {
+    fn default() -> Self {
+        Self {
+            fillers: default_disfluency_fillers(),
+            tag_repetitions: default_disfluency_tag_repetitions(),
+        }
+    }
+}
+
+/// Sampling of logs that would fire once per streamed frame.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogSamplingConfig {
+    /// One `audio_frame` in this many is logged per WebSocket session,
+    /// starting with the first; 0 logs none.
+    #[serde(default = "default_stream_frame_log_every")]
+    pub stream_frame_every: u64,
+}
+
+impl Default for LogSamplingConfig {
+    fn default() -> Self {
+        Self {
+            stream_frame_every: default_stream_frame_log_every(),
+        }
+    }
+}
+
+/// WebSocket streaming sessions, served on their own listener.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamingConfig {
+    #[serde(default = "default_stream_host")]
+    pub host: String,
+    #[serde(default = "default_stream_port")]
+    pub port: u16,
+    /// Port of the gRPC `StreamingService` on `host`, for native clients.
+    #[serde(default = "default_stream_grpc_port")]
+    pub grpc_port: u16,
+    /// Client messages larger than this close the connection.
+    #[serde(default = "default_stream_max_message_bytes")]
+    pub max_message_bytes: usize,
+    /// Audio all sessions may buffer together. Above it the least recently
+    /// active sessions are flushed through the pipeline and emptied.
+    #[serde(default = "default_stream_max_buffered_bytes")]
+    pub max_buffered_bytes: usize,
+    /// Messages queued per session for a client that reads slowly. When
+    /// full, the oldest partial transcript is dropped; finals never are.
+    #[serde(default = "default_stream_outbound_queue_len")]
+    pub outbound_queue_len: usize,
+    /// A client leaving its queue full, or a send pending, this long is
+    /// disconnected.
+    #[serde(default = "default_stream_stall_timeout_ms")]
+    pub stall_timeout_ms: u64,
+    #[serde(default)]
+    pub rtp: RtpConfig,
+}
+
+/// Telephony legs received as RTP on `service.streaming.host`, one
+/// streaming session per call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RtpConfig {
+    /// RTP carries no `flush`; the session is flushed this often.
+    #[serde(default = "default_rtp_flush_interval_ms")]
+    pub flush_interval_ms: u64,
+    /// Silence after audio that ends a call; the leg then waits for the next.
+    #[serde(default = "default_rtp_idle_timeout_ms")]
+    pub idle_timeout_ms: u64,
+    #[serde(default)]
+    pub legs: Vec<RtpLegConfig>,
+}
+
+/// A statically provisioned call leg, e.g. a trunk the PBX forwards to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RtpLegConfig {
+    /// Session id of the leg's calls.
+    pub call_id: String,
+    pub port: u16,
+    #[serde(default)]
+    pub codec: RtpCodecConfig,
+    #[serde(default)]
+    pub language_hint: Option<String>,
+    /// Tenant the leg's calls run for and count against.
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RtpCodecConfig {
+    /// G.711 µ-law, payload type 0.
+    #[default]
+    Pcmu,
+    /// G.711 A-law, payload type 8.
+    Pcma,
+    /// Opus on payload type 111; needs the `opus` feature of the
+    /// streaming crate.
+    Opus,
+}
+
+impl Default for StreamingConfig {
+    fn default() -> Self {
+        Self {
+            host: default_stream_host(),
+            port: default_stream_port(),
+            grpc_port: default_stream_grpc_port(),
+            max_message_bytes: default_stream_max_message_bytes(),
+            max_buffered_bytes: default_stream_max_buffered_bytes(),
+            outbound_queue_len: default_stream_outbound_queue_len(),
+            stall_timeout_ms: default_stream_stall_timeout_ms(),
+            rtp: RtpConfig::default(),
+        }
+    }
+}
+
+impl Default for RtpConfig {
+    fn default() -> Self {
+        Self {
+            flush_interval_ms: default_rtp_flush_interval_ms(),
+            idle_timeout_ms: default_rtp_idle_timeout_ms(),
+            legs: Vec::new(),
+        }
+    }
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            max_items: default_batch_max_items(),
+            max_concurrency: default_batch_max_concurrency(),
         }
     }
 }
@@ -124,15 +751,29 @@ impl Default for GrpcEndpointConfig {
             request_timeout_ms: default_grpc_request_timeout_ms(),
             max_decoding_message_bytes: default_grpc_max_message_bytes(),
             max_encoding_message_bytes: default_grpc_max_message_bytes(),
+            pool_size: default_grpc_pool_size(),
+            reconnect_initial_backoff_ms: default_grpc_reconnect_initial_backoff_ms(),
+            reconnect_max_backoff_ms: default_grpc_reconnect_max_backoff_ms(),
+            resolve_dns_on_reconnect: default_grpc_resolve_dns_on_reconnect(),
+            replicas: Vec::new(),
+            resolve_all_addresses: false,
+            load_balancing: LoadBalancingPolicy::default(),
         }
     }
 }
 
+impl Default for LoadBalancingPolicy {
+    fn default() -> Self {
+        LoadBalancingPolicy::RoundRobin
+    }
+}
+
 impl Default for PipelineConfig {
     fn default() -> Self {
         Self {
             selected: default_pipeline_name(),
             definitions: default_pipeline_definitions(),
+            plugin_dir: None,
         }
     }
 }
@@ -143,7 +784,9 @@ impl Default for PipelineDefinitionConfig {
             pre: vec![PipelineStepRef::Name("audio_transform".to_string())],
             transcription: default_pipeline_transcription_step(),
             post: vec![
+                PipelineStepRef::Name("normalize_transcript".to_string()),
                 PipelineStepRef::Name("alignment_enrich".to_string()),
+                PipelineStepRef::Name("store_session".to_string()),
                 PipelineStepRef::Name("snapshot_original_timings".to_string()),
                 PipelineStepRef::Name("tts_synthesize".to_string()),
                 PipelineStepRef::Name("swap_tts_audio".to_string()),
@@ -219,6 +862,22 @@ fn default_grpc_max_message_bytes() -> usize {
     64 * 1024 * 1024
 }
 
+fn default_grpc_pool_size() -> usize {
+    1
+}
+
+fn default_grpc_reconnect_initial_backoff_ms() -> u64 {
+    100
+}
+
+fn default_grpc_reconnect_max_backoff_ms() -> u64 {
+    5_000
+}
+
+fn default_grpc_resolve_dns_on_reconnect() -> bool {
+    true
+}
+
 fn default_audio_endpoint() -> GrpcEndpointConfig {
     GrpcEndpointConfig {
         port: 8081,
@@ -254,6 +913,110 @@ fn default_tempo_endpoint() -> GrpcEndpointConfig {
     }
 }
 
+fn default_batch_max_items() -> usize {
+    32
+}
+
+fn default_batch_max_concurrency() -> usize {
+    4
+}
+
+fn default_max_sessions() -> usize {
+    100
+}
+
+fn default_max_fingerprints() -> usize {
+    1_000
+}
+
+fn default_carry_over_max_chars() -> usize {
+    1_000
+}
+
+fn default_quality_gate_reject() -> bool {
+    true
+}
+
+fn default_quality_gate_min_duration_ms() -> u64 {
+    200
+}
+
+fn default_quality_gate_silence_rms_dbfs() -> f32 {
+    -60.0
+}
+
+fn default_quality_gate_max_clipped_ratio() -> f32 {
+    0.01
+}
+
+fn default_two_pass_fast_model() -> String {
+    "tiny".to_string()
+}
+
+fn default_two_pass_accurate_model() -> String {
+    "large-v3".to_string()
+}
+
+fn default_two_pass_max_background_passes() -> usize {
+    4
+}
+
+fn default_ensemble_models() -> Vec<String> {
+    vec!["base".to_string(), "large-v3".to_string()]
+}
+
+fn default_disfluency_fillers() -> HashMap<String, Vec<String>> {
+    let words = |words: &[&str]| words.iter().map(|word| word.to_string()).collect();
+    HashMap::from([
+        ("en".to_string(), words(&["um", "uh", "er", "erm", "hmm"])),
+        ("fr".to_string(), words(&["euh", "heu", "hum", "bah", "ben"])),
+    ])
+}
+
+fn default_disfluency_tag_repetitions() -> bool {
+    true
+}
+
+fn default_stream_frame_log_every() -> u64 {
+    100
+}
+
+fn default_stream_host() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_stream_port() -> u16 {
+    8091
+}
+
+fn default_stream_grpc_port() -> u16 {
+    8092
+}
+
+fn default_stream_max_message_bytes() -> usize {
+    4 * 1024 * 1024
+}
+
+fn default_stream_max_buffered_bytes() -> usize {
+    256 * 1024 * 1024
+}
+
+fn default_stream_outbound_queue_len() -> usize {
+    64
+}
+
+fn default_stream_stall_timeout_ms() -> u64 {
+    10_000
+}
+
+fn default_rtp_flush_interval_ms() -> u64 {
+    2_000
+}
+
+fn default_rtp_idle_timeout_ms() -> u64 {
+    5_000
+}
+
 fn default_pipeline_name() -> String {
     "default".to_string()
 }
@@ -261,6 +1024,36 @@ fn default_pipeline_name() -> String {
 fn default_pipeline_definitions() -> HashMap<String, PipelineDefinitionConfig> {
     let mut definitions = HashMap::new();
     definitions.insert(default_pipeline_name(), PipelineDefinitionConfig::default());
+    definitions.insert(
+        "align_only".to_string(),
+        PipelineDefinitionConfig {
+            pre: vec![PipelineStepRef::Name("audio_transform".to_string())],
+            transcription: PipelineStepRef::Name("provided_transcript".to_string()),
+            post: vec![
+                PipelineStepRef::Name("alignment_enrich".to_string()),
+                PipelineStepRef::Name("store_session".to_string()),
+            ],
+        },
+    );
+    definitions.insert(
+        "two_pass".to_string(),
+        PipelineDefinitionConfig {
+            pre: vec![PipelineStepRef::Name("audio_transform".to_string())],
+            transcription: PipelineStepRef::Name("two_pass_transcribe".to_string()),
+            post: vec![PipelineStepRef::Name("alignment_enrich".to_string())],
+        },
+    );
+    definitions.insert(
+        "ensemble".to_string(),
+        PipelineDefinitionConfig {
+            pre: vec![PipelineStepRef::Name("audio_transform".to_string())],
+            transcription: PipelineStepRef::Name("ensemble_transcribe".to_string()),
+            post: vec![
+                PipelineStepRef::Name("alignment_enrich".to_string()),
+                PipelineStepRef::Name("store_session".to_string()),
+            ],
+        },
+    );
     definitions
 }
 
@@ -268,6 +1061,62 @@ fn default_pipeline_transcription_step() -> PipelineStepRef {
     PipelineStepRef::Name("asr_transcribe".to_string())
 }
 
+fn default_http_enrich_request_timeout_ms() -> u64 {
+    5_000
+}
+
+fn default_recording_prefix() -> String {
+    "recordings".to_string()
+}
+
+fn default_recording_retention_hours() -> u64 {
+    24 * 30
+}
+
+fn default_recording_sweep_interval_secs() -> u64 {
+    3_600
+}
+
+fn default_result_store_prefix() -> String {
+    "results".to_string()
+}
+
+fn default_result_store_formats() -> Vec<String> {
+    vec!["json".to_string()]
+}
+
+fn default_result_store_max_cue_ms() -> u64 {
+    7_000
+}
+
+fn default_result_store_max_cue_chars() -> usize {
+    84
+}
+
+fn default_audio_fetch_timeout_ms() -> u64 {
+    30_000
+}
+
+fn default_audio_fetch_max_bytes() -> u64 {
+    100 * 1024 * 1024
+}
+
+fn default_usage_events_interval_secs() -> u64 {
+    300
+}
+
+fn default_script_max_operations() -> u64 {
+    1_000_000
+}
+
+fn default_wasm_fuel() -> u64 {
+    100_000_000
+}
+
+fn default_wasm_max_memory_bytes() -> usize {
+    64 * 1024 * 1024
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -281,5 +1130,20 @@ mod tests {
         assert_eq!(cfg.service.tts.port, 8084);
         assert_eq!(cfg.service.tempo.port, 8085);
         assert_eq!(cfg.server.port, 8080);
+        assert_eq!(cfg.service.streaming.port, 8091);
+        assert_eq!(cfg.service.streaming.grpc_port, 8092);
+        assert_eq!(cfg.service.asr.pool_size, 1);
+        assert!(cfg.service.asr.resolve_dns_on_reconnect);
+        assert_eq!(cfg.service.asr.load_balancing, LoadBalancingPolicy::RoundRobin);
+        assert_eq!(cfg.service.asr.targets(), vec!["127.0.0.1:8080".to_string()]);
+    }
+
+    #[test]
+    fn replicas_replace_host_and_port() {
+        let cfg = GrpcEndpointConfig {
+            replicas: vec!["asr-a:8080".to_string(), "asr-b:8080".to_string()],
+            ..GrpcEndpointConfig::default()
+        };
+        assert_eq!(cfg.targets(), vec!["asr-a:8080", "asr-b:8080"]);
     }
 }
@@ -0,0 +1,26 @@
+//! Fingerprints the compiler building this crate for the plugin ABI check,
+//! see `PLUGIN_RUSTC_HASH`.
+
+use std::env;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=RUSTC");
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let version = Command::new(&rustc)
+        .arg("-vV")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+        .unwrap_or_else(|| panic!("`{rustc} -vV` failed"));
+    let target = env::var("TARGET").unwrap_or_default();
+
+    // FNV-1a: stable across compilers, unlike `DefaultHasher`.
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in version.bytes().chain(target.bytes()) {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    println!("cargo:rustc-env=VOCAL_PLUGIN_RUSTC_HASH={hash:016x}");
+}
@@ -1,70 +1,24 @@
 use std::collections::HashMap;
 
+pub use common_domain::{
+    AudioChunk, LanguageTag, Pause, SynthesizedWordTiming, Transcript, TranscriptSegment,
+    TranscriptToken, TtsOutput, WordTiming,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub enum LanguageTag {
-    Fr,
-    En,
-    Auto,
-    Other(String),
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AudioChunk {
-    pub sample_rate_hz: u32,
-    pub samples: Vec<f32>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TranscriptToken {
-    pub text: String,
-    pub start_ms: u64,
-    pub end_ms: u64,
-    pub confidence: f32,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TranscriptSegment {
-    pub text: String,
-    pub start_ms: u64,
-    pub end_ms: u64,
-    pub tokens: Vec<TranscriptToken>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct WordTiming {
-    pub word: String,
-    pub start_ms: u64,
-    pub end_ms: u64,
-    pub confidence: f32,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SynthesizedWordTiming {
-    pub text: String,
-    pub start_ms: u64,
-    pub end_ms: u64,
-    pub fit_strategy: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TtsOutput {
-    pub samples: Vec<f32>,
-    pub sample_rate_hz: u32,
-    pub word_timings: Vec<SynthesizedWordTiming>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Transcript {
-    pub language: LanguageTag,
-    pub segments: Vec<TranscriptSegment>,
-}
-
+/// Persist with `serde_json` and restore through
+/// [`ContextMigrations::migrate`](crate::ContextMigrations::migrate) so older
+/// snapshots are upgraded first.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PipelineContext {
+    #[serde(default)]
+    pub context_version: u32,
     pub session_id: String,
+    /// Customer the session is served for, when the deployment is shared.
+    /// Stored state is kept apart per tenant.
+    #[serde(default)]
+    pub tenant_id: Option<String>,
     pub language_hint: Option<LanguageTag>,
     pub audio: AudioChunk,
     pub transcript: Option<Transcript>,
@@ -77,12 +31,11 @@ pub struct PipelineContext {
 impl PipelineContext {
     pub fn new(session_id: impl Into<String>, language_hint: Option<LanguageTag>) -> Self {
         Self {
+            context_version: crate::PIPELINE_CONTEXT_VERSION,
             session_id: session_id.into(),
+            tenant_id: None,
             language_hint,
-            audio: AudioChunk {
-                sample_rate_hz: 16_000,
-                samples: Vec::new(),
-            },
+            audio: AudioChunk::mono(16_000, Vec::new()),
             transcript: None,
             aligned_words: Vec::new(),
             tts_output: None,
@@ -106,12 +59,51 @@ impl PipelineContext {
     pub fn take_extension(&mut self, key: &str) -> Option<Value> {
         self.extensions.remove(key)
     }
+
+    /// Marks the context as fully answered, e.g. from a cached result; the
+    /// pipeline runs no further stages.
+    pub fn finish_early(&mut self) {
+        self.set_extension("pipeline.finished_early", Value::Bool(true));
+    }
+
+    pub fn is_finished_early(&self) -> bool {
+        self.extension("pipeline.finished_early")
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DomainEvent {
+    /// Transcript that a later pass may still replace, except up to
+    /// `stable_until_ms`: text ending by then is settled, so captions can
+    /// commit it. `None` when nothing is settled yet.
+    PartialTranscript {
+        transcript: Transcript,
+        stable_until_ms: Option<u64>,
+    },
     FinalTranscript { transcript: Transcript },
     AlignmentUpdate { words: Vec<WordTiming> },
+    StageStarted { stage: String },
+    StageCompleted { stage: String, duration_ms: u64 },
+    StageFailed { stage: String, message: String },
+}
+
+/// End of the leading segments `current` shares with the `previous`
+/// partial (same text and timings): decoding agreed on them twice, so they
+/// are taken as settled.
+pub fn stable_until_ms(previous: &Transcript, current: &Transcript) -> Option<u64> {
+    previous
+        .segments
+        .iter()
+        .zip(&current.segments)
+        .take_while(|(before, now)| {
+            before.text.trim() == now.text.trim()
+                && before.start_ms == now.start_ms
+                && before.end_ms == now.end_ms
+        })
+        .last()
+        .map(|(_, now)| now.end_ms)
 }
 
 #[derive(Debug, Clone)]
@@ -135,3 +127,76 @@ pub struct AlignmentRequest {
 pub struct AlignmentOutput {
     pub words: Vec<WordTiming>,
 }
+
+/// Audio and alignment of a finished session, kept so corrections can be
+/// realigned against the audio the transcript came from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredSession {
+    pub session_id: String,
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+    pub audio: AudioChunk,
+    pub transcript: Transcript,
+    pub aligned_words: Vec<WordTiming>,
+}
+
+/// Work done for one tenant with one ASR model, for billing. Audio of
+/// multi-channel requests counts once per channel.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UsageRecord {
+    /// `None` for requests without a tenant.
+    pub tenant_id: Option<String>,
+    /// Model the request asked for, `default` when it named none.
+    pub model: String,
+    pub requests: u64,
+    pub audio_seconds: f64,
+    /// Time spent waiting on the ASR service, which bounds the GPU time it
+    /// used.
+    pub asr_seconds: f64,
+}
+
+/// An object listed from an [`crate::ObjectStorePort`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredObject {
+    pub key: String,
+    pub modified_at: std::time::SystemTime,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transcript(segments: &[(&str, u64, u64)]) -> Transcript {
+        Transcript {
+            language: LanguageTag::en(),
+            segments: segments
+                .iter()
+                .map(|&(text, start_ms, end_ms)| TranscriptSegment {
+                    text: text.to_string(),
+                    start_ms,
+                    end_ms,
+                    tokens: Vec::new(),
+                    speaker: None,
+                    language: None,
+                    no_speech_prob: None,
+                    avg_logprob: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn shared_leading_segments_are_stable() {
+        let previous = transcript(&[("hello", 0, 500), ("wor", 500, 800)]);
+        let current = transcript(&[(" hello", 0, 500), ("world", 500, 900), ("again", 900, 1200)]);
+        assert_eq!(stable_until_ms(&previous, &current), Some(500));
+    }
+
+    #[test]
+    fn nothing_is_stable_when_the_first_segment_changed() {
+        let previous = transcript(&[("hollow", 0, 500)]);
+        let current = transcript(&[("hello", 0, 500)]);
+        assert_eq!(stable_until_ms(&previous, &current), None);
+    }
+}
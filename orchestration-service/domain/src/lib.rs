@@ -1,8 +1,15 @@
 pub mod entity;
+pub mod migration;
+pub mod plugin;
 pub mod port;
 pub mod service;
 
 pub use entity::*;
+pub use migration::{ContextMigration, ContextMigrations, PIPELINE_CONTEXT_VERSION};
+pub use plugin::{
+    PluginDeclaration, PluginRegistrar, PLUGIN_ABI_VERSION, PLUGIN_DECLARATION_SYMBOL,
+    PLUGIN_INTERFACE_VERSION, PLUGIN_RUSTC_HASH,
+};
 pub use port::*;
 pub use rustycog_core::error::DomainError;
 pub use service::*;
@@ -0,0 +1,141 @@
+//! Upgrades persisted [`PipelineContext`] JSON to the current schema.
+//!
+//! Each migration moves a context from one `context_version` to the next.
+//! Stages that persist their own extensions can register migrations for the
+//! same versions next to the built-in ones.
+
+use std::collections::BTreeMap;
+
+use serde_json::{Map, Value};
+
+use crate::{DomainError, PipelineContext};
+
+/// Schema version written by this build. Contexts persisted before the field
+/// existed read as version 0.
+pub const PIPELINE_CONTEXT_VERSION: u32 = 1;
+
+/// Rewrites a context object in place from version `n` to `n + 1`.
+pub type ContextMigration = fn(&mut Map<String, Value>) -> Result<(), DomainError>;
+
+pub struct ContextMigrations {
+    steps: BTreeMap<u32, Vec<ContextMigration>>,
+}
+
+impl Default for ContextMigrations {
+    fn default() -> Self {
+        let mut migrations = Self {
+            steps: BTreeMap::new(),
+        };
+        migrations.register(0, v0_mono_audio);
+        migrations
+    }
+}
+
+impl ContextMigrations {
+    /// Adds a migration run when upgrading from `from_version`. Migrations
+    /// for the same version run in registration order.
+    pub fn register(&mut self, from_version: u32, migration: ContextMigration) -> &mut Self {
+        self.steps.entry(from_version).or_default().push(migration);
+        self
+    }
+
+    pub fn migrate(&self, value: Value) -> Result<PipelineContext, DomainError> {
+        let Value::Object(mut context) = value else {
+            return Err(DomainError::invalid_input("pipeline context must be a JSON object"));
+        };
+        let mut version = match context.get("context_version") {
+            None => 0,
+            Some(version) => version
+                .as_u64()
+                .and_then(|version| u32::try_from(version).ok())
+                .ok_or_else(|| DomainError::invalid_input("context_version must be an integer"))?,
+        };
+        if version > PIPELINE_CONTEXT_VERSION {
+            return Err(DomainError::invalid_input(&format!(
+                "pipeline context version {version} is newer than supported version \
+                 {PIPELINE_CONTEXT_VERSION}"
+            )));
+        }
+
+        while version < PIPELINE_CONTEXT_VERSION {
+            for migration in self.steps.get(&version).into_iter().flatten() {
+                migration(&mut context)?;
+            }
+            version += 1;
+            context.insert("context_version".to_string(), Value::from(version));
+        }
+
+        serde_json::from_value(Value::Object(context)).map_err(|err| {
+            DomainError::invalid_input(&format!("invalid pipeline context: {err}"))
+        })
+    }
+}
+
+/// Version 0 audio had no channel layout; it was always mono.
+fn v0_mono_audio(context: &mut Map<String, Value>) -> Result<(), DomainError> {
+    if let Some(Value::Object(audio)) = context.get_mut("audio") {
+        audio.entry("channel").or_insert(Value::from(0));
+        audio.entry("channels").or_insert(Value::from(1));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn legacy_context() -> Value {
+        json!({
+            "session_id": "s-1",
+            "language_hint": "Fr",
+            "audio": { "sample_rate_hz": 16000, "samples": [0.0, 0.5] },
+            "transcript": null,
+            "aligned_words": [],
+            "tts_output": null,
+            "events": [],
+            "extensions": { "legacy.flag": true },
+        })
+    }
+
+    #[test]
+    fn unversioned_context_is_upgraded() {
+        let context = ContextMigrations::default()
+            .migrate(legacy_context())
+            .expect("legacy context migrates");
+        assert_eq!(context.context_version, PIPELINE_CONTEXT_VERSION);
+        assert_eq!((context.audio.channel, context.audio.channels), (0, 1));
+        assert_eq!(context.language_hint, Some(crate::LanguageTag::fr()));
+    }
+
+    #[test]
+    fn registered_hooks_run_for_their_version() {
+        fn rename_flag(context: &mut Map<String, Value>) -> Result<(), DomainError> {
+            if let Some(Value::Object(extensions)) = context.get_mut("extensions") {
+                if let Some(flag) = extensions.remove("legacy.flag") {
+                    extensions.insert("renamed.flag".to_string(), flag);
+                }
+            }
+            Ok(())
+        }
+
+        let mut migrations = ContextMigrations::default();
+        migrations.register(0, rename_flag);
+        let context = migrations.migrate(legacy_context()).expect("migrates");
+        assert!(context.extension("legacy.flag").is_none());
+        assert_eq!(context.extension("renamed.flag"), Some(&json!(true)));
+    }
+
+    #[test]
+    fn current_context_round_trips_and_newer_is_rejected() {
+        let context = PipelineContext::new("s-2", None);
+        let value = serde_json::to_value(&context).expect("serialize");
+        let restored = ContextMigrations::default().migrate(value.clone()).expect("current");
+        assert_eq!(restored.session_id, "s-2");
+
+        let mut newer = value;
+        newer["context_version"] = json!(PIPELINE_CONTEXT_VERSION + 1);
+        assert!(ContextMigrations::default().migrate(newer).is_err());
+    }
+}
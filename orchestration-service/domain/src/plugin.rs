@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use crate::PipelineStage;
+
+/// Version of [`PluginDeclaration`] and of the calling convention of its
+/// `register` function; the orchestrator refuses plugins built for another.
+pub const PLUGIN_ABI_VERSION: u32 = 2;
+
+/// Version of what crosses the library boundary as Rust types: the
+/// [`PipelineStage`] and [`PluginRegistrar`] traits, the pipeline context
+/// and everything it holds. Bump it with any change to them, whatever the
+/// crate version says.
+pub const PLUGIN_INTERFACE_VERSION: u32 = 1;
+
+/// Fingerprint of the compiler (`rustc -vV`) and target this crate was built
+/// with. Rust has no stable ABI, so trait objects and `std` types only match
+/// when both sides come from the same compiler.
+pub const PLUGIN_RUSTC_HASH: &str = env!("VOCAL_PLUGIN_RUSTC_HASH");
+
+/// Name of the static a plugin library exports, see
+/// [`export_pipeline_plugin!`](crate::export_pipeline_plugin).
+pub const PLUGIN_DECLARATION_SYMBOL: &[u8] = b"vocal_pipeline_plugin\0";
+
+/// Collects the steps of a plugin while it registers.
+pub trait PluginRegistrar {
+    /// Makes `stage` loadable as pipeline step `name`.
+    fn register_step(&mut self, name: &str, stage: Arc<dyn PipelineStage>);
+}
+
+/// Entry point of a pipeline plugin library.
+#[repr(C)]
+pub struct PluginDeclaration {
+    pub abi_version: u32,
+    pub interface_version: u32,
+    pub rustc_hash: &'static str,
+    pub register: unsafe extern "C" fn(&mut dyn PluginRegistrar),
+}
+
+/// Exports the entry point of a pipeline plugin built as a `cdylib`;
+/// `$register` is a `fn(&mut dyn PluginRegistrar)` adding the plugin steps.
+///
+/// ```ignore
+/// fn register(registrar: &mut dyn PluginRegistrar) {
+///     registrar.register_step("profanity_filter", Arc::new(ProfanityFilter));
+/// }
+///
+/// orchestration_domain::export_pipeline_plugin!(register);
+/// ```
+#[macro_export]
+macro_rules! export_pipeline_plugin {
+    ($register:path) => {
+        #[doc(hidden)]
+        #[no_mangle]
+        #[allow(non_upper_case_globals)]
+        pub static vocal_pipeline_plugin: $crate::PluginDeclaration = $crate::PluginDeclaration {
+            abi_version: $crate::PLUGIN_ABI_VERSION,
+            interface_version: $crate::PLUGIN_INTERFACE_VERSION,
+            rustc_hash: $crate::PLUGIN_RUSTC_HASH,
+            register: {
+                #[allow(improper_ctypes_definitions)]
+                unsafe extern "C" fn register(registrar: &mut dyn $crate::PluginRegistrar) {
+                    $register(registrar)
+                }
+                register
+            },
+        };
+    };
+}
@@ -1,8 +1,8 @@
 use async_trait::async_trait;
 
 use crate::{
-    AlignmentOutput, AlignmentRequest, DomainError, PipelineContext, TranscriptionOutput,
-    TranscriptionRequest,
+    AlignmentOutput, AlignmentRequest, DomainError, DomainEvent, PipelineContext, StoredObject,
+    StoredSession, TranscriptionOutput, TranscriptionRequest, UsageRecord,
 };
 
 #[async_trait]
@@ -23,3 +23,58 @@ pub trait TranscriptionPort: Send + Sync {
 pub trait AlignmentPort: Send + Sync {
     async fn align(&self, request: AlignmentRequest) -> Result<AlignmentOutput, DomainError>;
 }
+
+/// Sessions are keyed by tenant and id: a session saved for one tenant is
+/// not found under another.
+#[async_trait]
+pub trait SessionStore: Send + Sync {
+    /// Replaces any session stored under the same tenant and id.
+    async fn save(&self, session: StoredSession) -> Result<(), DomainError>;
+    async fn load(
+        &self,
+        tenant_id: Option<&str>,
+        session_id: &str,
+    ) -> Result<Option<StoredSession>, DomainError>;
+}
+
+/// Sessions by audio fingerprint, for answering repeated uploads of the same
+/// recording from the session store.
+#[async_trait]
+pub trait DuplicateIndex: Send + Sync {
+    async fn find(&self, fingerprint: &str) -> Result<Option<String>, DomainError>;
+    /// Replaces any session recorded under the same fingerprint.
+    async fn record(&self, fingerprint: &str, session_id: &str) -> Result<(), DomainError>;
+}
+
+/// Objects under `/`-separated keys, in a local directory or an
+/// S3-compatible bucket.
+#[async_trait]
+pub trait ObjectStorePort: Send + Sync {
+    /// Replaces any object stored under the same key.
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<(), DomainError>;
+    /// Every object under `prefix`, a `/`-separated path; `""` lists all.
+    async fn list(&self, prefix: &str) -> Result<Vec<StoredObject>, DomainError>;
+    async fn delete(&self, key: &str) -> Result<(), DomainError>;
+}
+
+/// Audio a request references instead of inlining it, e.g.
+/// `s3://bucket/call.wav` or `https://host/call.wav`.
+#[async_trait]
+pub trait AudioSourcePort: Send + Sync {
+    /// Bytes of the file at `uri`.
+    async fn fetch(&self, uri: &str) -> Result<Vec<u8>, DomainError>;
+}
+
+/// Receives the events a stage produces after its pipeline run returned,
+/// e.g. the final transcript of a background pass, for the session the run
+/// was for.
+pub trait LateEventSink: Send + Sync {
+    fn publish(&self, session_id: &str, events: Vec<DomainEvent>);
+}
+
+/// Receives the usage accrued since the previous report, e.g. a billing
+/// system's webhook.
+#[async_trait]
+pub trait UsageSink: Send + Sync {
+    async fn publish(&self, records: &[UsageRecord]) -> Result<(), DomainError>;
+}
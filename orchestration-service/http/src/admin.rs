@@ -0,0 +1,67 @@
+//! Orchestration routes of the admin server, next to the shared ones of
+//! `service-admin`.
+
+use axum::{
+    extract::{Query, State},
+    response::Json,
+    routing::get,
+    Router,
+};
+use orchestration_application::UsageMeter;
+use orchestration_domain::UsageRecord;
+use serde::{Deserialize, Serialize};
+
+pub const ADMIN_USAGE_PATH: &str = "/admin/usage";
+
+#[derive(Debug, Default, Deserialize)]
+pub struct UsageQuery {
+    /// Only this tenant's usage.
+    pub tenant_id: Option<String>,
+}
+
+/// Usage since startup, by tenant then model.
+#[derive(Debug, Serialize)]
+pub struct UsageReport {
+    pub records: Vec<UsageRecord>,
+}
+
+pub fn admin_routes(usage: UsageMeter) -> Router {
+    Router::new()
+        .route(ADMIN_USAGE_PATH, get(usage_report))
+        .with_state(usage)
+}
+
+async fn usage_report(
+    State(usage): State<UsageMeter>,
+    Query(query): Query<UsageQuery>,
+) -> Json<UsageReport> {
+    let records = usage
+        .totals()
+        .into_iter()
+        .filter(|record| {
+            query.tenant_id.is_none() || record.tenant_id.as_deref() == query.tenant_id.as_deref()
+        })
+        .collect();
+    Json(UsageReport { records })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn usage_can_be_narrowed_to_a_tenant() {
+        let usage = UsageMeter::default();
+        usage.record(Some("acme"), None, 2.0, 0.5);
+        usage.record(Some("globex"), Some("tiny"), 1.0, 0.25);
+
+        let Json(all) = usage_report(State(usage.clone()), Query(UsageQuery::default())).await;
+        assert_eq!(all.records.len(), 2);
+        let query = UsageQuery {
+            tenant_id: Some("globex".to_string()),
+        };
+        let Json(globex) = usage_report(State(usage), Query(query)).await;
+        assert_eq!(globex.records.len(), 1);
+        assert_eq!(globex.records[0].model, "tiny");
+    }
+}
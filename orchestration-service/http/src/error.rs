@@ -1,10 +1,10 @@
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
-    Json,
 };
+use common_domain::ErrorCode;
+use http_problem::{ProblemDetails, UNAUTHENTICATED};
 use rustycog_command::CommandError;
-use serde_json::json;
 
 #[derive(Debug)]
 pub enum HttpError {
@@ -12,26 +12,32 @@ pub enum HttpError {
     Unauthorized,
     Forbidden,
     NotFound,
+    Business { code: ErrorCode, message: String },
     Internal { message: String },
 }
 
+impl HttpError {
+    pub fn problem(self) -> ProblemDetails {
+        match self {
+            HttpError::Validation { message } => {
+                ProblemDetails::from_code(ErrorCode::InvalidInput, Some(message))
+            }
+            HttpError::Unauthorized => {
+                ProblemDetails::new(StatusCode::UNAUTHORIZED, UNAUTHENTICATED, None)
+            }
+            HttpError::Forbidden => ProblemDetails::from_code(ErrorCode::PermissionDenied, None),
+            HttpError::NotFound => ProblemDetails::from_code(ErrorCode::NotFound, None),
+            HttpError::Business { code, message } => ProblemDetails::from_code(code, Some(message)),
+            HttpError::Internal { message } => {
+                ProblemDetails::from_code(ErrorCode::Internal, Some(message))
+            }
+        }
+    }
+}
+
 impl IntoResponse for HttpError {
     fn into_response(self) -> Response {
-        let (status, message) = match self {
-            HttpError::Validation { message } => (StatusCode::UNPROCESSABLE_ENTITY, message),
-            HttpError::Unauthorized => (StatusCode::UNAUTHORIZED, "Unauthorized".to_string()),
-            HttpError::Forbidden => (StatusCode::FORBIDDEN, "Forbidden".to_string()),
-            HttpError::NotFound => (StatusCode::NOT_FOUND, "Not found".to_string()),
-            HttpError::Internal { message } => (StatusCode::INTERNAL_SERVER_ERROR, message),
-        };
-
-        (
-            status,
-            Json(json!({
-                "error": message,
-            })),
-        )
-            .into_response()
+        self.problem().into_response()
     }
 }
 
@@ -41,7 +47,8 @@ pub fn error_mapper(error: CommandError) -> HttpError {
             message: error.to_string(),
         },
         CommandError::Authentication { .. } => HttpError::Unauthorized,
-        CommandError::Business { .. } => HttpError::Validation {
+        CommandError::Business { .. } => HttpError::Business {
+            code: ErrorCode::from_code(error.error_code()),
             message: error.to_string(),
         },
         _ => HttpError::Internal {
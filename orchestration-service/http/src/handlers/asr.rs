@@ -1,24 +1,50 @@
 use axum::{
     extract::State,
-    http::{header, StatusCode},
+    http::{header, HeaderMap, StatusCode},
     response::Json,
 };
+use http_problem::ProblemDetails;
 use rustycog_command::CommandContext;
 use rustycog_http::{AppState, ValidatedJson};
 
-use orchestration_application::{TranscribeAudioCommand, TranscribeAudioRequest, TranscribeAudioResponse};
+use orchestration_application::{
+    TranscribeAudioCommand, TranscribeAudioRequest, TranscribeAudioResponse,
+    TranscribeBatchCommand, TranscribeBatchRequest, TranscribeBatchResponse,
+};
 
+use super::audio_body::{RawAudioQuery, TranscribeBody, TranscribeJsonBody};
 use crate::error::{error_mapper, HttpError};
+use crate::tenant::tenant_id;
 
+#[utoipa::path(
+    post,
+    path = "/api/asr/transcribe",
+    tag = "asr",
+    request_body(
+        description = "JSON, a WAV file, or raw little-endian f32 samples",
+        content(
+            (TranscribeJsonBody = "application/json"),
+            (Vec<u8> = "audio/wav"),
+            (Vec<u8> = "application/octet-stream"),
+        )
+    ),
+    params(RawAudioQuery),
+    responses(
+        (status = 200, description = "Transcript, aligned words and dub of the audio", body = TranscribeAudioResponse),
+        (status = 422, description = "Invalid request", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Pipeline failure", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
 pub async fn transcribe_audio(
     State(state): State<AppState>,
-    ValidatedJson(request): ValidatedJson<TranscribeAudioRequest>,
+    TranscribeBody(request): TranscribeBody,
 ) -> Result<(StatusCode, Json<TranscribeAudioResponse>), HttpError> {
     tracing::info!(
         sample_count = request.samples.len(),
         sample_rate_hz = request.sample_rate_hz.unwrap_or(0),
         language_hint = request.language_hint.as_deref().unwrap_or("auto"),
         session_id = request.session_id.as_deref().unwrap_or("auto"),
+        tenant_id = request.tenant_id.as_deref().unwrap_or("none"),
         "received transcribe request"
     );
 
@@ -39,9 +65,28 @@ pub async fn transcribe_audio(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/asr/redub",
+    tag = "asr",
+    request_body(
+        description = "JSON, a WAV file, or raw little-endian f32 samples",
+        content(
+            (TranscribeJsonBody = "application/json"),
+            (Vec<u8> = "audio/wav"),
+            (Vec<u8> = "application/octet-stream"),
+        )
+    ),
+    params(RawAudioQuery),
+    responses(
+        (status = 200, description = "Dubbed audio as a mono 32-bit float WAV file", body = Vec<u8>, content_type = "audio/wav"),
+        (status = 422, description = "Invalid request or more than one channel", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 500, description = "Pipeline failure or no output audio", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
 pub async fn redub_audio_wav(
     State(state): State<AppState>,
-    ValidatedJson(request): ValidatedJson<TranscribeAudioRequest>,
+    TranscribeBody(request): TranscribeBody,
 ) -> Result<
     (
         StatusCode,
@@ -57,6 +102,11 @@ pub async fn redub_audio_wav(
         session_id = request.session_id.as_deref().unwrap_or("auto"),
         "received redub wav request"
     );
+    if request.channels.is_some_and(|channels| channels > 1) {
+        return Err(HttpError::Validation {
+            message: "redub takes mono audio; transcribe channels separately".to_string(),
+        });
+    }
 
     let result = execute_transcribe(&state, request).await?;
     let (samples, sample_rate_hz) = if let Some(ref audio) = result.output_audio {
@@ -73,7 +123,7 @@ pub async fn redub_audio_wav(
         sample_rate_hz,
         "redub: encoding output audio"
     );
-    let wav = encode_wav_f32_mono(samples, sample_rate_hz);
+    let wav = wav_io::encode_wav_f32_mono(samples, sample_rate_hz);
 
     Ok((
         StatusCode::OK,
@@ -88,7 +138,52 @@ pub async fn redub_audio_wav(
     ))
 }
 
-async fn execute_transcribe(
+/// Item failures are reported in the item's `error`; the request itself only
+/// fails when the batch is malformed or over the configured size.
+#[utoipa::path(
+    post,
+    path = "/api/asr/transcribe-batch",
+    tag = "asr",
+    request_body = TranscribeBatchRequest,
+    responses(
+        (status = 200, description = "One result or error per item, in request order", body = TranscribeBatchResponse),
+        (status = 422, description = "Empty, oversized or duplicate-id batch", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn transcribe_batch(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    ValidatedJson(mut request): ValidatedJson<TranscribeBatchRequest>,
+) -> Result<(StatusCode, Json<TranscribeBatchResponse>), HttpError> {
+    let tenant_id = tenant_id(&headers)?;
+    for item in &mut request.items {
+        item.request.tenant_id = tenant_id.clone();
+    }
+    tracing::info!(item_count = request.items.len(), "received transcribe batch request");
+
+    let command = TranscribeBatchCommand::new(request);
+    let result = state
+        .command_service
+        .execute(command, CommandContext::new())
+        .await
+        .map_err(error_mapper);
+    match result {
+        Ok(result) => {
+            tracing::info!(
+                item_count = result.results.len(),
+                failed_count = result.results.iter().filter(|item| item.error.is_some()).count(),
+                "transcribe batch request completed"
+            );
+            Ok((StatusCode::OK, Json(result)))
+        }
+        Err(error) => {
+            tracing::error!(error = ?error, "transcribe batch request failed");
+            Err(error)
+        }
+    }
+}
+
+pub(crate) async fn execute_transcribe(
     state: &AppState,
     request: TranscribeAudioRequest,
 ) -> Result<TranscribeAudioResponse, HttpError> {
@@ -100,33 +195,3 @@ async fn execute_transcribe(
         .await
         .map_err(error_mapper)
 }
-
-fn encode_wav_f32_mono(samples: &[f32], sample_rate_hz: u32) -> Vec<u8> {
-    let channels: u16 = 1;
-    let bits_per_sample: u16 = 32;
-    let bytes_per_sample = (bits_per_sample / 8) as u32;
-    let byte_rate = sample_rate_hz * channels as u32 * bytes_per_sample;
-    let block_align = channels * (bits_per_sample / 8);
-    let data_chunk_size = samples.len() as u32 * bytes_per_sample;
-    let riff_chunk_size = 36 + data_chunk_size;
-
-    let mut out = Vec::with_capacity((44 + data_chunk_size) as usize);
-    out.extend_from_slice(b"RIFF");
-    out.extend_from_slice(&riff_chunk_size.to_le_bytes());
-    out.extend_from_slice(b"WAVE");
-    out.extend_from_slice(b"fmt ");
-    out.extend_from_slice(&16u32.to_le_bytes());
-    out.extend_from_slice(&3u16.to_le_bytes()); // IEEE float
-    out.extend_from_slice(&channels.to_le_bytes());
-    out.extend_from_slice(&sample_rate_hz.to_le_bytes());
-    out.extend_from_slice(&byte_rate.to_le_bytes());
-    out.extend_from_slice(&block_align.to_le_bytes());
-    out.extend_from_slice(&bits_per_sample.to_le_bytes());
-    out.extend_from_slice(b"data");
-    out.extend_from_slice(&data_chunk_size.to_le_bytes());
-
-    for sample in samples {
-        out.extend_from_slice(&sample.to_le_bytes());
-    }
-    out
-}
@@ -0,0 +1,207 @@
+//! Request body of the transcribe and redub endpoints, negotiated on
+//! `Content-Type`: the JSON request with `samples` or a base64 WAV, a raw WAV
+//! file, or raw little-endian `f32` samples.
+
+use axum::{
+    body::Bytes,
+    extract::{FromRequest, Query, Request},
+    http::header,
+    response::{IntoResponse, Json, Response},
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rustycog_http::AppState;
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+use wav_io::decode_wav;
+
+use orchestration_application::TranscribeAudioRequest;
+
+use crate::error::HttpError;
+use crate::tenant::tenant_id;
+
+/// JSON body: [`TranscribeAudioRequest`] with the audio either in `samples`
+/// or as a base64 WAV file in `audio_base64`.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TranscribeJsonBody {
+    /// WAV file, base64 encoded. Its header sets `sample_rate_hz`.
+    #[serde(default)]
+    pub audio_base64: Option<String>,
+    #[serde(flatten)]
+    pub request: TranscribeAudioRequest,
+}
+
+/// Query parameters of a raw audio body.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RawAudioQuery {
+    /// Rate of raw `f32` samples; a WAV header takes precedence.
+    pub sample_rate_hz: Option<u32>,
+    pub language_hint: Option<String>,
+    pub session_id: Option<String>,
+    pub model: Option<String>,
+    /// Interleaved channel count of raw `f32` samples.
+    pub channels: Option<u16>,
+    /// `accurate` (default) or `low_latency`.
+    pub decode_profile: Option<String>,
+    /// Start of the audio on the stream's timeline.
+    pub time_offset_ms: Option<u64>,
+}
+
+/// Extracts and validates a [`TranscribeAudioRequest`] from any of the
+/// supported bodies, with the tenant of the `x-tenant-id` header.
+pub struct TranscribeBody(pub TranscribeAudioRequest);
+
+impl FromRequest<AppState> for TranscribeBody {
+    type Rejection = Response;
+
+    async fn from_request(request: Request, state: &AppState) -> Result<Self, Self::Rejection> {
+        let media_type = request
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(';').next())
+            .map(|value| value.trim().to_ascii_lowercase())
+            .unwrap_or_default();
+        let tenant_id = tenant_id(request.headers()).map_err(IntoResponse::into_response)?;
+
+        let request = match media_type.as_str() {
+            "audio/wav" | "audio/wave" | "audio/x-wav" | "application/octet-stream" => {
+                let Query(query) = Query::<RawAudioQuery>::try_from_uri(request.uri())
+                    .map_err(IntoResponse::into_response)?;
+                let bytes = Bytes::from_request(request, state)
+                    .await
+                    .map_err(IntoResponse::into_response)?;
+                let is_wav = media_type != "application/octet-stream" || bytes.starts_with(b"RIFF");
+                raw_request(&bytes, is_wav, query)
+            }
+            _ => {
+                let Json(body) = Json::<TranscribeJsonBody>::from_request(request, state)
+                    .await
+                    .map_err(IntoResponse::into_response)?;
+                json_request(body)
+            }
+        };
+
+        request
+            .and_then(|mut request| {
+                request.tenant_id = tenant_id;
+                request.validate().map_err(|err| invalid(err.to_string()))?;
+                Ok(Self(request))
+            })
+            .map_err(IntoResponse::into_response)
+    }
+}
+
+fn invalid(message: String) -> HttpError {
+    HttpError::Validation { message }
+}
+
+fn json_request(body: TranscribeJsonBody) -> Result<TranscribeAudioRequest, HttpError> {
+    let mut request = body.request;
+    if let Some(encoded) = body.audio_base64 {
+        if !request.samples.is_empty() {
+            return Err(invalid("send either `samples` or `audio_base64`, not both".into()));
+        }
+        let bytes = STANDARD
+            .decode(encoded.trim())
+            .map_err(|err| invalid(format!("audio_base64: {err}")))?;
+        let wav = decode_wav(&bytes).map_err(|err| invalid(format!("audio_base64: {err}")))?;
+        request.samples = wav.samples;
+        request.sample_rate_hz = Some(wav.sample_rate_hz);
+        request.channels = None;
+    }
+    Ok(request)
+}
+
+fn raw_request(
+    bytes: &[u8],
+    is_wav: bool,
+    query: RawAudioQuery,
+) -> Result<TranscribeAudioRequest, HttpError> {
+    let (samples, sample_rate_hz, channels) = if is_wav {
+        let wav = decode_wav(bytes).map_err(|err| invalid(format!("body: {err}")))?;
+        (wav.samples, Some(wav.sample_rate_hz), None)
+    } else {
+        if !bytes.len().is_multiple_of(4) {
+            return Err(invalid("raw body must hold little-endian f32 samples".into()));
+        }
+        let samples = bytes
+            .chunks_exact(4)
+            .map(|sample| f32::from_le_bytes([sample[0], sample[1], sample[2], sample[3]]))
+            .collect();
+        (samples, query.sample_rate_hz, query.channels)
+    };
+
+    Ok(TranscribeAudioRequest {
+        samples,
+        audio_url: None,
+        sample_rate_hz,
+        language_hint: query.language_hint,
+        session_id: query.session_id,
+        model: query.model,
+        channels,
+        pipeline: None,
+        transcript: None,
+        decode_profile: query.decode_profile,
+        grammar: None,
+        prompt: None,
+        time_offset_ms: query.time_offset_ms,
+        tenant_id: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wav_body_takes_its_rate_from_the_header() {
+        let wav = test_audio::wav_bytes(&test_audio::tone(440.0, 22_050, 100), 22_050);
+        let query = RawAudioQuery {
+            sample_rate_hz: Some(8_000),
+            language_hint: Some("fr".to_string()),
+            ..RawAudioQuery::default()
+        };
+
+        let request = raw_request(&wav, true, query).expect("decodes");
+        assert_eq!(request.sample_rate_hz, Some(22_050));
+        assert_eq!(request.samples.len(), 2_205);
+        assert_eq!(request.language_hint.as_deref(), Some("fr"));
+    }
+
+    #[test]
+    fn octet_stream_without_header_is_raw_f32() {
+        let bytes: Vec<u8> = [0.5f32, -0.25].iter().flat_map(|s| s.to_le_bytes()).collect();
+        let query = RawAudioQuery {
+            sample_rate_hz: Some(16_000),
+            ..RawAudioQuery::default()
+        };
+
+        let request = raw_request(&bytes, false, query).expect("decodes");
+        assert_eq!(request.samples, vec![0.5, -0.25]);
+        assert_eq!(request.sample_rate_hz, Some(16_000));
+        assert!(raw_request(&bytes[..5], false, RawAudioQuery::default()).is_err());
+    }
+
+    #[test]
+    fn base64_json_replaces_samples() {
+        let wav = test_audio::wav_bytes(&test_audio::silence(16_000, 50), 16_000);
+        let body: TranscribeJsonBody = serde_json::from_value(serde_json::json!({
+            "audio_base64": STANDARD.encode(wav),
+            "language_hint": "en",
+        }))
+        .expect("valid body");
+
+        let request = json_request(body).expect("decodes");
+        assert_eq!(request.samples.len(), 800);
+        assert_eq!(request.sample_rate_hz, Some(16_000));
+
+        let both: TranscribeJsonBody = serde_json::from_value(serde_json::json!({
+            "audio_base64": "UklGRg==",
+            "samples": [0.0],
+        }))
+        .expect("valid body");
+        assert!(json_request(both).is_err());
+    }
+}
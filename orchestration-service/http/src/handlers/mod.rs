@@ -1,3 +1,11 @@
-mod asr;
+pub(crate) mod asr;
+pub(crate) mod audio_body;
+pub(crate) mod openai;
+pub(crate) mod pipelines;
+pub(crate) mod sessions;
 
-pub use asr::{redub_audio_wav, transcribe_audio};
+pub use asr::{redub_audio_wav, transcribe_audio, transcribe_batch};
+pub use audio_body::{RawAudioQuery, TranscribeBody, TranscribeJsonBody};
+pub use openai::{create_transcription, ResponseFormat, TranscriptionForm, OPENAI_DEFAULT_MODEL};
+pub use pipelines::{describe_pipeline, list_pipelines};
+pub use sessions::{correct_transcript, get_session};
@@ -0,0 +1,420 @@
+//! OpenAI Whisper API compatible transcription, so OpenAI SDKs and tools can
+//! use this service by changing their base URL.
+
+use axum::{
+    extract::{multipart::MultipartRejection, Multipart, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use rustycog_http::AppState;
+use serde::Serialize;
+use utoipa::ToSchema;
+use validator::Validate;
+use wav_io::decode_wav;
+
+use orchestration_application::{TranscribeAudioRequest, TranscribeAudioResponse};
+
+use super::asr::execute_transcribe;
+use crate::error::HttpError;
+use crate::tenant::tenant_id;
+
+/// Model name OpenAI clients send by default. It selects the ASR service's
+/// default model; any other name must be registered on the ASR service.
+pub const OPENAI_DEFAULT_MODEL: &str = "whisper-1";
+
+/// `multipart/form-data` body of `POST /v1/audio/transcriptions`.
+#[derive(Debug, Default, ToSchema)]
+pub struct TranscriptionForm {
+    /// WAV file to transcribe.
+    #[schema(value_type = String, format = Binary)]
+    pub file: Vec<u8>,
+    pub model: Option<String>,
+    /// ISO-639-1 code or BCP-47 tag of the audio's language.
+    pub language: Option<String>,
+    /// Passed to Whisper as its prompt.
+    pub prompt: Option<String>,
+    pub response_format: ResponseFormat,
+    /// Accepted for compatibility and ignored; reported back in
+    /// `verbose_json` segments.
+    pub temperature: Option<f32>,
+    /// Sent as repeated `timestamp_granularities[]` parts. `word` adds the
+    /// aligned words to a `verbose_json` response.
+    pub timestamp_granularities: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseFormat {
+    #[default]
+    Json,
+    Text,
+    Srt,
+    Vtt,
+    VerboseJson,
+}
+
+impl ResponseFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "json" => Some(Self::Json),
+            "text" => Some(Self::Text),
+            "srt" => Some(Self::Srt),
+            "vtt" => Some(Self::Vtt),
+            "verbose_json" => Some(Self::VerboseJson),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TranscriptionJson {
+    pub text: String,
+}
+
+/// `verbose_json` response. `language` is the detected BCP-47 tag rather than
+/// OpenAI's English language name.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VerboseTranscription {
+    pub task: String,
+    pub language: String,
+    /// Seconds of audio.
+    pub duration: f64,
+    pub text: String,
+    pub segments: Vec<VerboseSegment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub words: Option<Vec<VerboseWord>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VerboseSegment {
+    pub id: u32,
+    /// Start of the segment in 10 ms frames.
+    pub seek: u64,
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+    pub tokens: Vec<u32>,
+    pub temperature: f32,
+    pub avg_logprob: f32,
+    /// Not computed; always 0.
+    pub compression_ratio: f32,
+    pub no_speech_prob: f32,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VerboseWord {
+    pub word: String,
+    pub start: f64,
+    pub end: f64,
+}
+
+/// Error body in the OpenAI API shape, which OpenAI SDKs parse.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OpenAiErrorBody {
+    pub error: OpenAiError,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OpenAiError {
+    pub message: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub param: Option<String>,
+    pub code: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/audio/transcriptions",
+    tag = "openai",
+    request_body(content = TranscriptionForm, content_type = "multipart/form-data"),
+    responses(
+        (
+            status = 200,
+            description = "`json` and `verbose_json` return JSON; `text`, `srt` and `vtt` return plain text",
+            content(
+                (TranscriptionJson = "application/json"),
+                (String = "text/plain"),
+                (String = "text/vtt"),
+            )
+        ),
+        (status = 400, description = "Invalid form or audio", body = OpenAiErrorBody),
+        (status = 500, description = "Pipeline failure", body = OpenAiErrorBody),
+    )
+)]
+pub async fn create_transcription(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    multipart: Result<Multipart, MultipartRejection>,
+) -> Response {
+    let form = match multipart {
+        Ok(multipart) => read_form(multipart).await,
+        Err(rejection) => Err(invalid(rejection.body_text())),
+    };
+    let result = match (form, tenant_id(&headers)) {
+        (Ok(form), Ok(tenant_id)) => transcribe_form(&state, form, tenant_id).await,
+        (Err(error), _) | (_, Err(error)) => Err(error),
+    };
+
+    result.unwrap_or_else(|error| {
+        tracing::error!(error = ?error, "openai transcription request failed");
+        openai_error(error)
+    })
+}
+
+fn invalid(message: String) -> HttpError {
+    HttpError::Validation { message }
+}
+
+async fn read_form(mut multipart: Multipart) -> Result<TranscriptionForm, HttpError> {
+    let mut form = TranscriptionForm::default();
+    let mut file = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|err| invalid(format!("invalid multipart body: {err}")))?
+    {
+        let name = field.name().unwrap_or_default().to_string();
+        if name == "file" {
+            let bytes = field
+                .bytes()
+                .await
+                .map_err(|err| invalid(format!("cannot read `file`: {err}")))?;
+            file = Some(bytes.to_vec());
+            continue;
+        }
+
+        let text = field
+            .text()
+            .await
+            .map_err(|err| invalid(format!("cannot read `{name}`: {err}")))?;
+        match name.as_str() {
+            "model" => form.model = Some(text),
+            "language" => form.language = Some(text),
+            "prompt" => form.prompt = Some(text),
+            "response_format" => {
+                form.response_format = ResponseFormat::parse(&text)
+                    .ok_or_else(|| invalid(format!("unsupported response_format `{text}`")))?;
+            }
+            "temperature" => {
+                form.temperature = Some(
+                    text.parse()
+                        .map_err(|_| invalid(format!("invalid temperature `{text}`")))?,
+                );
+            }
+            "timestamp_granularities[]" | "timestamp_granularities" => {
+                form.timestamp_granularities.push(text);
+            }
+            _ => {}
+        }
+    }
+
+    form.file = file.ok_or_else(|| invalid("multipart body needs a `file` part".into()))?;
+    Ok(form)
+}
+
+async fn transcribe_form(
+    state: &AppState,
+    form: TranscriptionForm,
+    tenant_id: Option<String>,
+) -> Result<Response, HttpError> {
+    let audio = decode_wav(&form.file).map_err(|err| invalid(format!("file: {err}")))?;
+    let request = TranscribeAudioRequest {
+        samples: audio.samples,
+        audio_url: None,
+        sample_rate_hz: Some(audio.sample_rate_hz),
+        language_hint: form.language.clone(),
+        session_id: None,
+        model: form.model.clone().filter(|model| model != OPENAI_DEFAULT_MODEL),
+        channels: None,
+        pipeline: None,
+        transcript: None,
+        decode_profile: None,
+        grammar: None,
+        prompt: form.prompt.clone(),
+        time_offset_ms: None,
+        tenant_id,
+    };
+    request.validate().map_err(|err| invalid(err.to_string()))?;
+    let duration = request.samples.len() as f64 / f64::from(audio.sample_rate_hz.max(1));
+
+    tracing::info!(
+        sample_count = request.samples.len(),
+        sample_rate_hz = audio.sample_rate_hz,
+        response_format = ?form.response_format,
+        "received openai transcription request"
+    );
+    let response = execute_transcribe(state, request).await?;
+    Ok(render(&form, &response, duration))
+}
+
+fn render(form: &TranscriptionForm, response: &TranscribeAudioResponse, duration: f64) -> Response {
+    let plain = |content_type: &'static str, body: String| {
+        ([(header::CONTENT_TYPE, content_type)], body).into_response()
+    };
+    match form.response_format {
+        ResponseFormat::Json => Json(TranscriptionJson {
+            text: response.text.clone(),
+        })
+        .into_response(),
+        ResponseFormat::Text => plain("text/plain; charset=utf-8", response.text.clone()),
+        ResponseFormat::Srt => plain("text/plain; charset=utf-8", response.transcript.to_srt()),
+        ResponseFormat::Vtt => plain("text/vtt; charset=utf-8", response.transcript.to_webvtt()),
+        ResponseFormat::VerboseJson => Json(verbose(form, response, duration)).into_response(),
+    }
+}
+
+fn seconds(ms: u64) -> f64 {
+    ms as f64 / 1_000.0
+}
+
+fn verbose(
+    form: &TranscriptionForm,
+    response: &TranscribeAudioResponse,
+    duration: f64,
+) -> VerboseTranscription {
+    let segments = response
+        .transcript
+        .segments
+        .iter()
+        .enumerate()
+        .map(|(id, segment)| VerboseSegment {
+            id: id as u32,
+            seek: segment.start_ms / 10,
+            start: seconds(segment.start_ms),
+            end: seconds(segment.end_ms),
+            text: segment.text.clone(),
+            tokens: Vec::new(),
+            temperature: form.temperature.unwrap_or(0.0),
+            avg_logprob: segment.avg_logprob.unwrap_or(0.0),
+            compression_ratio: 0.0,
+            no_speech_prob: segment.no_speech_prob.unwrap_or(0.0),
+        })
+        .collect();
+    let words = form
+        .timestamp_granularities
+        .iter()
+        .any(|granularity| granularity == "word")
+        .then(|| {
+            response
+                .aligned_words
+                .iter()
+                .map(|word| VerboseWord {
+                    word: word.word.clone(),
+                    start: seconds(word.start_ms),
+                    end: seconds(word.end_ms),
+                })
+                .collect()
+        });
+
+    VerboseTranscription {
+        task: "transcribe".to_string(),
+        language: response.transcript.language.to_string(),
+        duration,
+        text: response.text.clone(),
+        segments,
+        words,
+    }
+}
+
+fn openai_error(error: HttpError) -> Response {
+    let problem = error.problem();
+    let status = problem.status_code();
+    let message = problem.detail.unwrap_or(problem.title);
+    // OpenAI reports malformed requests as 400 rather than 422.
+    let (status, kind) = if status == StatusCode::UNPROCESSABLE_ENTITY {
+        (StatusCode::BAD_REQUEST, "invalid_request_error")
+    } else if status.is_client_error() {
+        (status, "invalid_request_error")
+    } else {
+        (status, "server_error")
+    };
+    let body = OpenAiErrorBody {
+        error: OpenAiError {
+            message,
+            kind: kind.to_string(),
+            param: None,
+            code: None,
+        },
+    };
+    (status, Json(body)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use common_domain::{LanguageTag, Transcript, TranscriptSegment, WordTiming};
+
+    use super::*;
+
+    fn response() -> TranscribeAudioResponse {
+        TranscribeAudioResponse {
+            session_id: "s-1".to_string(),
+            transcript: Transcript {
+                language: LanguageTag::en(),
+                segments: vec![TranscriptSegment {
+                    text: " Hello world".to_string(),
+                    start_ms: 250,
+                    end_ms: 1_500,
+                    tokens: Vec::new(),
+                    speaker: None,
+                    language: None,
+                    no_speech_prob: Some(0.1),
+                    avg_logprob: Some(-0.2),
+                }],
+            },
+            aligned_words: vec![WordTiming {
+                word: "Hello".to_string(),
+                start_ms: 250,
+                end_ms: 700,
+                confidence: 0.9,
+                speaker: None,
+                start_sample: None,
+                end_sample: None,
+                energy_rms: None,
+                pitch_hz: None,
+                out_of_band: false,
+            }],
+            pauses: Vec::new(),
+            quality_issues: Vec::new(),
+            detected_language: None,
+            language_probability: None,
+            text: "Hello world".to_string(),
+            tts_output: None,
+            output_audio: None,
+            channels: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn verbose_json_reports_segments_and_requested_words() {
+        let mut form = TranscriptionForm {
+            response_format: ResponseFormat::VerboseJson,
+            ..TranscriptionForm::default()
+        };
+        let without_words = serde_json::to_value(verbose(&form, &response(), 2.0)).unwrap();
+        assert_eq!(without_words["language"], "en");
+        assert_eq!(without_words["segments"][0]["start"], 0.25);
+        assert_eq!(without_words["segments"][0]["seek"], 25);
+        assert!(without_words.get("words").is_none());
+
+        form.timestamp_granularities.push("word".to_string());
+        let with_words = serde_json::to_value(verbose(&form, &response(), 2.0)).unwrap();
+        assert_eq!(with_words["words"][0]["end"], 0.7);
+    }
+
+    #[test]
+    fn response_formats_follow_the_openai_names() {
+        for name in ["json", "text", "srt", "vtt", "verbose_json"] {
+            let format = ResponseFormat::parse(name).expect("known format");
+            assert_eq!(serde_json::to_value(format).unwrap(), name);
+        }
+        assert!(ResponseFormat::parse("xml").is_none());
+    }
+
+    #[test]
+    fn validation_errors_use_the_openai_shape() {
+        let response = openai_error(invalid("file: not a RIFF/WAVE file".to_string()));
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
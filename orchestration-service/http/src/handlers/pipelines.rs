@@ -0,0 +1,65 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use http_problem::ProblemDetails;
+use rustycog_command::CommandContext;
+use rustycog_http::AppState;
+
+use orchestration_application::{
+    DescribePipelineCommand, ListPipelinesCommand, PipelineCatalog, PipelineDescription,
+};
+
+use crate::error::{error_mapper, HttpError};
+
+/// Every configured pipeline with the stages its steps resolved to, and
+/// which optional steps this deployment can run. Nothing is executed.
+#[utoipa::path(
+    get,
+    path = "/api/asr/pipelines",
+    tag = "pipelines",
+    responses(
+        (status = 200, description = "Resolved pipelines and optional step availability", body = PipelineCatalog),
+    )
+)]
+pub async fn list_pipelines(
+    State(state): State<AppState>,
+) -> Result<(StatusCode, Json<PipelineCatalog>), HttpError> {
+    let result = state
+        .command_service
+        .execute(ListPipelinesCommand::new(), CommandContext::new())
+        .await
+        .map_err(error_mapper)?;
+    Ok((StatusCode::OK, Json(result)))
+}
+
+/// One configured pipeline: its steps in execution order and the endpoints
+/// they call.
+#[utoipa::path(
+    get,
+    path = "/api/asr/pipelines/{name}",
+    tag = "pipelines",
+    params(("name" = String, Path, description = "Pipeline name of `service.pipeline.definitions`")),
+    responses(
+        (status = 200, description = "Resolved steps and endpoint targets", body = PipelineDescription),
+        (status = 404, description = "No pipeline of that name", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn describe_pipeline(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<(StatusCode, Json<PipelineDescription>), HttpError> {
+    match state
+        .command_service
+        .execute(DescribePipelineCommand::new(name), CommandContext::new())
+        .await
+        .map_err(error_mapper)
+    {
+        Ok(result) => Ok((StatusCode::OK, Json(result))),
+        Err(error) => {
+            tracing::debug!(error = ?error, "pipeline lookup failed");
+            Err(error)
+        }
+    }
+}
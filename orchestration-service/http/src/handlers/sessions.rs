@@ -0,0 +1,98 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use http_problem::ProblemDetails;
+use rustycog_command::CommandContext;
+use rustycog_http::{AppState, ValidatedJson};
+
+use orchestration_application::{
+    CorrectTranscriptCommand, CorrectTranscriptRequest, CorrectTranscriptResponse,
+    GetSessionCommand,
+};
+
+use crate::error::{error_mapper, HttpError};
+use crate::tenant::tenant_id;
+
+/// Transcript and alignment stored for a session, including the result of a
+/// background second pass once it has finished.
+#[utoipa::path(
+    get,
+    path = "/api/sessions/{session_id}",
+    tag = "sessions",
+    params(("session_id" = String, Path, description = "Session of an earlier transcribe request")),
+    responses(
+        (status = 200, description = "Stored transcript with aligned words", body = CorrectTranscriptResponse),
+        (status = 404, description = "Session unknown to the tenant, not stored yet or no longer stored", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_session(
+    State(state): State<AppState>,
+    Path(session_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<(StatusCode, Json<CorrectTranscriptResponse>), HttpError> {
+    let command = GetSessionCommand::new(session_id).with_tenant(tenant_id(&headers)?);
+    match state
+        .command_service
+        .execute(command, CommandContext::new())
+        .await
+        .map_err(error_mapper)
+    {
+        Ok(result) => Ok((StatusCode::OK, Json(result))),
+        Err(error) => {
+            tracing::debug!(error = ?error, "session lookup failed");
+            Err(error)
+        }
+    }
+}
+
+/// Realigns a corrected transcript against the audio of a session finished
+/// by a pipeline with the `store_session` stage.
+#[utoipa::path(
+    post,
+    path = "/api/sessions/{session_id}/correction",
+    tag = "sessions",
+    params(("session_id" = String, Path, description = "Session of an earlier transcribe request")),
+    request_body = CorrectTranscriptRequest,
+    responses(
+        (status = 200, description = "Corrected transcript with realigned words", body = CorrectTranscriptResponse),
+        (status = 404, description = "Session unknown or no longer stored", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Neither or both of `text` and `segments`", body = ProblemDetails, content_type = "application/problem+json"),
+    )
+)]
+pub async fn correct_transcript(
+    State(state): State<AppState>,
+    Path(session_id): Path<String>,
+    headers: HeaderMap,
+    ValidatedJson(mut request): ValidatedJson<CorrectTranscriptRequest>,
+) -> Result<(StatusCode, Json<CorrectTranscriptResponse>), HttpError> {
+    request.session_id = session_id;
+    request.tenant_id = tenant_id(&headers)?;
+    tracing::info!(
+        session_id = %request.session_id,
+        has_text = request.text.is_some(),
+        segment_count = request.segments.as_ref().map_or(0, Vec::len),
+        "received transcript correction"
+    );
+
+    let command = CorrectTranscriptCommand::new(request);
+    match state
+        .command_service
+        .execute(command, CommandContext::new())
+        .await
+        .map_err(error_mapper)
+    {
+        Ok(result) => {
+            tracing::info!(
+                aligned_word_count = result.aligned_words.len(),
+                "transcript correction completed"
+            );
+            Ok((StatusCode::OK, Json(result)))
+        }
+        Err(error) => {
+            tracing::error!(error = ?error, "transcript correction failed");
+            Err(error)
+        }
+    }
+}
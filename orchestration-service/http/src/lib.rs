@@ -1,22 +1,71 @@
-use axum::{extract::DefaultBodyLimit, routing::post};
+use std::sync::Arc;
+
+use axum::{
+    extract::DefaultBodyLimit,
+    middleware,
+    routing::{get, post},
+};
+use log_context::http_request_span;
 use rustycog_config::ServerConfig;
 use rustycog_http::{AppState, RouteBuilder};
+use service_health::{liveness, readiness, ReadinessCheck, LIVENESS_PATH, READINESS_PATH};
 
+pub mod admin;
 pub mod error;
 pub mod handlers;
+pub mod openapi;
+pub mod tenant;
 
+pub use admin::{admin_routes, ADMIN_USAGE_PATH};
 pub use error::{error_mapper, HttpError};
 pub use handlers::*;
+pub use openapi::{openapi_json, ApiDoc};
+pub use tenant::{tenant_id, TENANT_ID_HEADER};
+
+pub const TRANSCRIBE_PATH: &str = "/api/asr/transcribe";
+pub const REDUB_PATH: &str = "/api/asr/redub";
+pub const TRANSCRIBE_BATCH_PATH: &str = "/api/asr/transcribe-batch";
+pub const OPENAI_TRANSCRIPTIONS_PATH: &str = "/v1/audio/transcriptions";
+pub const PIPELINES_PATH: &str = "/api/asr/pipelines";
+pub const PIPELINE_PATH: &str = "/api/asr/pipelines/{name}";
+pub const SESSION_PATH: &str = "/api/sessions/{session_id}";
+pub const CORRECTION_PATH: &str = "/api/sessions/{session_id}/correction";
+pub const OPENAPI_PATH: &str = "/api/docs/openapi.json";
 
-pub async fn create_app_routes(state: AppState, config: ServerConfig) -> anyhow::Result<()> {
+pub async fn create_app_routes(
+    state: AppState,
+    config: ServerConfig,
+    readiness_check: Arc<dyn ReadinessCheck>,
+) -> anyhow::Result<()> {
+    // Every API call is logged under its request id.
+    let traced = middleware::from_fn(http_request_span);
     // WAV payloads serialized as float arrays can be large; raise route body limit.
-    let transcribe_route = post(transcribe_audio).layer(DefaultBodyLimit::max(64 * 1024 * 1024));
-    let redub_route = post(redub_audio_wav).layer(DefaultBodyLimit::max(64 * 1024 * 1024));
+    let transcribe_route = post(transcribe_audio)
+        .layer(DefaultBodyLimit::max(64 * 1024 * 1024))
+        .layer(traced.clone());
+    let redub_route = post(redub_audio_wav)
+        .layer(DefaultBodyLimit::max(64 * 1024 * 1024))
+        .layer(traced.clone());
+    let batch_route = post(transcribe_batch)
+        .layer(DefaultBodyLimit::max(256 * 1024 * 1024))
+        .layer(traced.clone());
+    let openai_route = post(create_transcription)
+        .layer(DefaultBodyLimit::max(64 * 1024 * 1024))
+        .layer(traced.clone());
 
     RouteBuilder::new(state)
         .health_check()
-        .route("/api/asr/transcribe", transcribe_route)
-        .route("/api/asr/redub", redub_route)
+        .route(LIVENESS_PATH, get(liveness))
+        .route(READINESS_PATH, get(move || readiness(readiness_check.clone())))
+        .route(TRANSCRIBE_PATH, transcribe_route)
+        .route(REDUB_PATH, redub_route)
+        .route(TRANSCRIBE_BATCH_PATH, batch_route)
+        .route(OPENAI_TRANSCRIPTIONS_PATH, openai_route)
+        .route(PIPELINES_PATH, get(list_pipelines).layer(traced.clone()))
+        .route(PIPELINE_PATH, get(describe_pipeline).layer(traced.clone()))
+        .route(SESSION_PATH, get(get_session).layer(traced.clone()))
+        .route(CORRECTION_PATH, post(correct_transcript).layer(traced))
+        .route(OPENAPI_PATH, get(openapi_json))
         .build(config)
         .await
 }
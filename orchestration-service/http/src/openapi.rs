@@ -0,0 +1,92 @@
+//! OpenAPI 3 document of the orchestration HTTP API, served at
+//! [`OPENAPI_PATH`](crate::OPENAPI_PATH). Routes added to
+//! [`create_app_routes`](crate::create_app_routes) are listed in `paths` here.
+
+use axum::Json;
+use http_problem::ProblemDetails;
+use utoipa::OpenApi;
+
+use orchestration_application::{
+    ChannelTranscription, EndpointDescription, PipelineCatalog, PipelineDescription,
+    PipelinePhase, PipelineStepDescription, ProvidedTranscript, StepAvailability, StepSource,
+    TranscribeAudioRequest, TranscribeAudioResponse, TranscribeBatchRequest,
+    TranscribeBatchResponse,
+};
+
+use crate::handlers::{asr, openai, pipelines, sessions, TranscribeJsonBody};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Vocal agent orchestration API"),
+    paths(
+        asr::transcribe_audio,
+        asr::redub_audio_wav,
+        asr::transcribe_batch,
+        openai::create_transcription,
+        pipelines::list_pipelines,
+        pipelines::describe_pipeline,
+        sessions::get_session,
+        sessions::correct_transcript,
+    ),
+    components(schemas(
+        TranscribeAudioRequest,
+        TranscribeJsonBody,
+        TranscribeAudioResponse,
+        ChannelTranscription,
+        ProvidedTranscript,
+        TranscribeBatchRequest,
+        TranscribeBatchResponse,
+        PipelineCatalog,
+        PipelineDescription,
+        PipelineStepDescription,
+        PipelinePhase,
+        StepSource,
+        EndpointDescription,
+        StepAvailability,
+        ProblemDetails,
+        openai::VerboseTranscription,
+    )),
+    tags(
+        (name = "asr", description = "Transcription, alignment and dubbing pipeline"),
+        (name = "openai", description = "OpenAI Whisper API compatible transcription"),
+        (name = "pipelines", description = "Configured pipelines, resolved without running them"),
+        (name = "sessions", description = "Stored sessions and their corrections"),
+    )
+)]
+pub struct ApiDoc;
+
+pub async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        CORRECTION_PATH, OPENAI_TRANSCRIPTIONS_PATH, OPENAPI_PATH, PIPELINES_PATH, PIPELINE_PATH,
+        REDUB_PATH, SESSION_PATH, TRANSCRIBE_BATCH_PATH, TRANSCRIBE_PATH,
+    };
+
+    #[test]
+    fn document_lists_every_api_route() {
+        let document = ApiDoc::openapi();
+        for path in [
+            TRANSCRIBE_PATH,
+            REDUB_PATH,
+            TRANSCRIBE_BATCH_PATH,
+            OPENAI_TRANSCRIPTIONS_PATH,
+            PIPELINES_PATH,
+            PIPELINE_PATH,
+            SESSION_PATH,
+            CORRECTION_PATH,
+        ] {
+            assert!(document.paths.paths.contains_key(path), "{path} is undocumented");
+        }
+        assert!(!document.paths.paths.contains_key(OPENAPI_PATH));
+
+        let schemas = document.components.expect("components").schemas;
+        for schema in ["TranscribeAudioResponse", "Transcript", "LanguageTag", "TtsOutput"] {
+            assert!(schemas.contains_key(schema), "{schema} schema is missing");
+        }
+    }
+}
@@ -0,0 +1,39 @@
+//! The tenant a request runs for, named by the `x-tenant-id` header. Requests
+//! without it run for no tenant unless `service.tenancy.required` is set.
+
+use axum::http::HeaderMap;
+use orchestration_application::parse_tenant_id;
+pub use orchestration_application::TENANT_ID_HEADER;
+
+use crate::error::HttpError;
+
+/// The request's tenant id, validated by [`parse_tenant_id`].
+pub fn tenant_id(headers: &HeaderMap) -> Result<Option<String>, HttpError> {
+    let Some(value) = headers.get(TENANT_ID_HEADER) else {
+        return Ok(None);
+    };
+    // A non-ASCII value parses as empty, which is refused.
+    let value = value.to_str().unwrap_or_default();
+    let tenant_id = parse_tenant_id(value).map_err(|message| HttpError::Validation { message })?;
+    Ok(Some(tenant_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(TENANT_ID_HEADER, value.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn tenant_ids_are_safe_path_segments() {
+        assert_eq!(tenant_id(&HeaderMap::new()).unwrap(), None);
+        assert_eq!(tenant_id(&headers("acme-eu.1")).unwrap().as_deref(), Some("acme-eu.1"));
+        for value in ["", "..", "acme/../globex", "a b", &"x".repeat(65)] {
+            assert!(tenant_id(&headers(value)).is_err(), "{value:?}");
+        }
+    }
+}
@@ -1,32 +1,37 @@
+use std::sync::Arc;
 use std::time::Duration;
 
 use alignment_grpc_server::{pb, AlignmentServiceClient};
 use async_trait::async_trait;
-use orchestration_domain::{
-    DomainError, DomainEvent, LanguageTag, PipelineContext, PipelineStage, Transcript,
-    TranscriptSegment, TranscriptToken, WordTiming,
-};
+use log_context::traced_request;
+use orchestration_domain::{DomainError, DomainEvent, Pause, PipelineContext, PipelineStage};
+use orchestration_infra_grpc::GrpcChannelPool;
 use serde_json::json;
-use tonic::transport::{Channel, Endpoint};
-use tonic::Request;
 
-const LANGUAGE_TAG_CODE_FR: i32 = 1;
-const LANGUAGE_TAG_CODE_EN: i32 = 2;
-const LANGUAGE_TAG_CODE_AUTO: i32 = 3;
-const LANGUAGE_TAG_CODE_OTHER: i32 = 4;
+vocal_proto_mappings::transcript_mappings!(pb, orchestration_domain);
+vocal_proto_mappings::word_timing_mappings!(pb, orchestration_domain);
 
 pub struct AlignmentEnrichStage {
-    client: AlignmentServiceClient<Channel>,
+    channels: Arc<GrpcChannelPool>,
     request_timeout: Duration,
+    pause_threshold_ms: Option<u64>,
 }
 
 impl AlignmentEnrichStage {
-    pub fn new(client: AlignmentServiceClient<Channel>, request_timeout: Duration) -> Self {
+    pub fn new(channels: Arc<GrpcChannelPool>, request_timeout: Duration) -> Self {
         Self {
-            client,
+            channels,
             request_timeout,
+            pause_threshold_ms: None,
         }
     }
+
+    /// Records the gaps between aligned words longer than `threshold_ms` in
+    /// the `alignment.pauses` extension.
+    pub fn with_pause_threshold_ms(mut self, threshold_ms: Option<u64>) -> Self {
+        self.pause_threshold_ms = threshold_ms;
+        self
+    }
 }
 
 #[async_trait]
@@ -40,34 +45,40 @@ impl PipelineStage for AlignmentEnrichStage {
             .transcript
             .clone()
             .ok_or_else(|| DomainError::internal_error("no transcript available"))?;
-        let mut client = self.client.clone();
         let request = pb::EnrichTranscriptRequest {
             samples: context.audio.samples.clone(),
             sample_rate_hz: Some(context.audio.sample_rate_hz),
-            transcript: Some(map_transcript_to_proto(transcript)),
+            transcript: Some(transcript_to_proto(transcript)),
             session_id: Some(context.session_id.clone()),
+            time_offset_ms: None,
         };
-        let rpc = client.enrich_transcript(Request::new(request));
+        let pooled = self.channels.checkout().await?;
+        let mut client = AlignmentServiceClient::new(pooled.channel())
+            .max_decoding_message_size(self.channels.max_decoding_message_bytes())
+            .max_encoding_message_size(self.channels.max_encoding_message_bytes());
+        let rpc = client.enrich_transcript(traced_request(request));
         let response = tokio::time::timeout(self.request_timeout, rpc)
             .await
             .map_err(|_| DomainError::external_service_error("alignment", "gRPC request timed out"))?
-            .map_err(|status| map_status("alignment", status))?
+            .map_err(|status| {
+                self.channels.report_status(&pooled, &status);
+                map_status("alignment", status)
+            })?
             .into_inner();
 
         let transcript = response
             .transcript
             .ok_or_else(|| DomainError::internal_error("alignment response missing transcript"))
-            .and_then(map_transcript_from_proto)?;
+            .and_then(|transcript| transcript_from_proto(transcript).map_err(map_mapping_error))?;
         let words = response
             .aligned_words
             .into_iter()
-            .map(|word| WordTiming {
-                word: word.word,
-                start_ms: word.start_ms,
-                end_ms: word.end_ms,
-                confidence: word.confidence,
-            })
+            .map(word_timing_from_proto)
             .collect::<Vec<_>>();
+        if let Some(threshold_ms) = self.pause_threshold_ms {
+            let pauses = Pause::between(&words, threshold_ms);
+            context.set_extension("alignment.pauses", json!(pauses));
+        }
         context.session_id = response.session_id;
         context.transcript = Some(transcript);
         context.aligned_words = words.clone();
@@ -77,111 +88,8 @@ impl PipelineStage for AlignmentEnrichStage {
     }
 }
 
-pub async fn connect_alignment_client(
-    endpoint_uri: &str,
-    connect_timeout: Duration,
-    max_decoding_message_bytes: usize,
-    max_encoding_message_bytes: usize,
-) -> Result<AlignmentServiceClient<Channel>, DomainError> {
-    let endpoint = Endpoint::from_shared(endpoint_uri.to_string())
-        .map_err(|err| DomainError::internal_error(&format!("invalid alignment endpoint: {err}")))?
-        .connect_timeout(connect_timeout);
-    let channel = endpoint.connect().await.map_err(|err| {
-        DomainError::external_service_error("alignment", &format!("failed to connect: {err}"))
-    })?;
-    Ok(AlignmentServiceClient::new(channel)
-        .max_decoding_message_size(max_decoding_message_bytes)
-        .max_encoding_message_size(max_encoding_message_bytes))
-}
-
-fn map_transcript_to_proto(transcript: Transcript) -> pb::Transcript {
-    pb::Transcript {
-        language: Some(map_language_to_proto(transcript.language)),
-        segments: transcript
-            .segments
-            .into_iter()
-            .map(|segment| pb::TranscriptSegment {
-                text: segment.text,
-                start_ms: segment.start_ms,
-                end_ms: segment.end_ms,
-                tokens: segment
-                    .tokens
-                    .into_iter()
-                    .map(|token| pb::TranscriptToken {
-                        text: token.text,
-                        start_ms: token.start_ms,
-                        end_ms: token.end_ms,
-                        confidence: token.confidence,
-                    })
-                    .collect(),
-            })
-            .collect(),
-    }
-}
-
-fn map_transcript_from_proto(transcript: pb::Transcript) -> Result<Transcript, DomainError> {
-    Ok(Transcript {
-        language: map_language_from_proto(transcript.language)?,
-        segments: transcript
-            .segments
-            .into_iter()
-            .map(|segment| TranscriptSegment {
-                text: segment.text,
-                start_ms: segment.start_ms,
-                end_ms: segment.end_ms,
-                tokens: segment
-                    .tokens
-                    .into_iter()
-                    .map(|token| TranscriptToken {
-                        text: token.text,
-                        start_ms: token.start_ms,
-                        end_ms: token.end_ms,
-                        confidence: token.confidence,
-                    })
-                    .collect(),
-            })
-            .collect(),
-    })
-}
-
-fn map_language_to_proto(language: LanguageTag) -> pb::LanguageTag {
-    match language {
-        LanguageTag::Fr => pb::LanguageTag {
-            code: LANGUAGE_TAG_CODE_FR,
-            other: None,
-        },
-        LanguageTag::En => pb::LanguageTag {
-            code: LANGUAGE_TAG_CODE_EN,
-            other: None,
-        },
-        LanguageTag::Auto => pb::LanguageTag {
-            code: LANGUAGE_TAG_CODE_AUTO,
-            other: None,
-        },
-        LanguageTag::Other(value) => pb::LanguageTag {
-            code: LANGUAGE_TAG_CODE_OTHER,
-            other: Some(value),
-        },
-    }
-}
-
-fn map_language_from_proto(language: Option<pb::LanguageTag>) -> Result<LanguageTag, DomainError> {
-    let language = language.ok_or_else(|| DomainError::internal_error("missing language tag"))?;
-    match language.code {
-        LANGUAGE_TAG_CODE_FR => Ok(LanguageTag::Fr),
-        LANGUAGE_TAG_CODE_EN => Ok(LanguageTag::En),
-        LANGUAGE_TAG_CODE_AUTO => Ok(LanguageTag::Auto),
-        LANGUAGE_TAG_CODE_OTHER => {
-            let value = language.other.unwrap_or_default();
-            if value.trim().is_empty() {
-                return Err(DomainError::internal_error(
-                    "language.other is required when code is OTHER",
-                ));
-            }
-            Ok(LanguageTag::Other(value))
-        }
-        _ => Err(DomainError::internal_error("invalid language tag code")),
-    }
+fn map_mapping_error(error: vocal_proto_mappings::MappingError) -> DomainError {
+    DomainError::internal_error(&error.to_string())
 }
 
 fn map_status(service: &str, status: tonic::Status) -> DomainError {
@@ -194,22 +102,20 @@ fn map_status(service: &str, status: tonic::Status) -> DomainError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use orchestration_domain::{LanguageTag, Transcript, TranscriptSegment, TranscriptToken};
 
     #[test]
     fn language_mapping_round_trips() {
-        let tag = LanguageTag::Other("es".to_string());
-        let proto = map_language_to_proto(tag.clone());
-        let mapped = map_language_from_proto(Some(proto)).expect("language should map");
-        match mapped {
-            LanguageTag::Other(value) => assert_eq!(value, "es"),
-            _ => panic!("expected Other language"),
-        }
+        let tag = LanguageTag::parse("es-419").expect("valid tag");
+        let proto = language_to_proto(tag.clone());
+        let mapped = language_from_proto(Some(proto)).expect("language should map");
+        assert_eq!(mapped, tag);
     }
 
     #[test]
     fn transcript_round_trip_preserves_tokens() {
         let transcript = Transcript {
-            language: LanguageTag::En,
+            language: LanguageTag::en(),
             segments: vec![TranscriptSegment {
                 text: "hello".to_string(),
                 start_ms: 0,
@@ -219,11 +125,18 @@ mod tests {
                     start_ms: 0,
                     end_ms: 100,
                     confidence: 0.9,
+                    start_sample: None,
+                    end_sample: None,
+                    disfluency: false,
                 }],
+                speaker: None,
+                language: None,
+                no_speech_prob: None,
+                avg_logprob: None,
             }],
         };
 
-        let mapped = map_transcript_from_proto(map_transcript_to_proto(transcript.clone()))
+        let mapped = transcript_from_proto(transcript_to_proto(transcript.clone()))
             .expect("transcript should map");
         assert_eq!(mapped.segments.len(), 1);
         assert_eq!(mapped.segments[0].tokens.len(), 1);
@@ -1,30 +1,40 @@
+use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
 use audio_grpc_server::{pb, AudioServiceClient};
+use log_context::traced_request;
 use orchestration_domain::{DomainError, PipelineContext, PipelineStage};
+use orchestration_infra_grpc::GrpcChannelPool;
 use serde_json::json;
-use tonic::transport::{Channel, Endpoint};
-use tonic::Request;
 
 pub struct AudioTransformStage {
-    client: AudioServiceClient<Channel>,
+    channels: Arc<GrpcChannelPool>,
     request_timeout: Duration,
     target_sample_rate_hz: Option<u32>,
+    ops: Vec<String>,
 }
 
 impl AudioTransformStage {
     pub fn new(
-        client: AudioServiceClient<Channel>,
+        channels: Arc<GrpcChannelPool>,
         request_timeout: Duration,
         target_sample_rate_hz: Option<u32>,
     ) -> Self {
         Self {
-            client,
+            channels,
             request_timeout,
             target_sample_rate_hz,
+            ops: Vec::new(),
         }
     }
+
+    /// Transform ops the audio service runs, in order; empty keeps its
+    /// default clamp and resample.
+    pub fn with_ops(mut self, ops: Vec<String>) -> Self {
+        self.ops = ops;
+        self
+    }
 }
 
 #[async_trait]
@@ -34,18 +44,28 @@ impl PipelineStage for AudioTransformStage {
     }
 
     async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
-        let mut client = self.client.clone();
         let request = pb::TransformAudioRequest {
             samples: context.audio.samples.clone(),
             sample_rate_hz: Some(context.audio.sample_rate_hz),
             target_sample_rate_hz: self.target_sample_rate_hz,
             session_id: Some(context.session_id.clone()),
+            channel: Some(u32::from(context.audio.channel)),
+            channels: Some(u32::from(context.audio.channels)),
+            encoded_audio: None,
+            ops: self.ops.clone(),
         };
-        let rpc = client.transform_audio(Request::new(request));
+        let pooled = self.channels.checkout().await?;
+        let mut client = AudioServiceClient::new(pooled.channel())
+            .max_decoding_message_size(self.channels.max_decoding_message_bytes())
+            .max_encoding_message_size(self.channels.max_encoding_message_bytes());
+        let rpc = client.transform_audio(traced_request(request));
         let response = tokio::time::timeout(self.request_timeout, rpc)
             .await
             .map_err(|_| DomainError::external_service_error("audio", "gRPC request timed out"))?
-            .map_err(|status| map_status("audio", status))?
+            .map_err(|status| {
+                self.channels.report_status(&pooled, &status);
+                map_status("audio", status)
+            })?
             .into_inner();
 
         context.session_id = response.session_id;
@@ -56,6 +76,7 @@ impl PipelineStage for AudioTransformStage {
             sample_count = context.audio.samples.len(),
             "audio_transform: received new audio"
         );
+        context.set_extension("audio.fingerprint", json!(context.audio.fingerprint()));
         if let Some(metadata) = response.metadata {
             context.set_extension(
                 "audio.transform",
@@ -66,6 +87,13 @@ impl PipelineStage for AudioTransformStage {
                     "output_sample_count": metadata.output_sample_count,
                     "source_sample_rate_hz": metadata.source_sample_rate_hz,
                     "target_sample_rate_hz": metadata.target_sample_rate_hz,
+                    "peak_level": metadata.peak_level,
+                    "rms": metadata.rms,
+                    "clipped_sample_count": metadata.clipped_sample_count,
+                    "estimated_snr_db": metadata.estimated_snr_db,
+                    "detected_sample_rate_hz": metadata.detected_sample_rate_hz,
+                    "detected_channels": metadata.detected_channels,
+                    "applied_ops": metadata.applied_ops,
                 }),
             );
         }
@@ -73,23 +101,6 @@ impl PipelineStage for AudioTransformStage {
     }
 }
 
-pub async fn connect_audio_client(
-    endpoint_uri: &str,
-    connect_timeout: Duration,
-    max_decoding_message_bytes: usize,
-    max_encoding_message_bytes: usize,
-) -> Result<AudioServiceClient<Channel>, DomainError> {
-    let endpoint = Endpoint::from_shared(endpoint_uri.to_string())
-        .map_err(|err| DomainError::internal_error(&format!("invalid audio endpoint: {err}")))?
-        .connect_timeout(connect_timeout);
-    let channel = endpoint.connect().await.map_err(|err| {
-        DomainError::external_service_error("audio", &format!("failed to connect: {err}"))
-    })?;
-    Ok(AudioServiceClient::new(channel)
-        .max_decoding_message_size(max_decoding_message_bytes)
-        .max_encoding_message_size(max_encoding_message_bytes))
-}
-
 fn map_status(service: &str, status: tonic::Status) -> DomainError {
     DomainError::external_service_error(
         service,
@@ -0,0 +1,527 @@
+mod readiness;
+
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use orchestration_domain::DomainError;
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+use tonic::{Code, Status};
+
+pub use readiness::DownstreamReadiness;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BalancingPolicy {
+    RoundRobin,
+    LeastLoaded,
+}
+
+/// One replica of a downstream service.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrpcTarget {
+    /// Configured `host:port`; TLS verifies the replica under this host.
+    pub authority: String,
+    /// For a replica made by [`expand_targets`], which of the authority's
+    /// addresses, in sorted order, it connects to.
+    pub address_index: Option<usize>,
+}
+
+impl GrpcTarget {
+    pub fn new(authority: impl Into<String>) -> Self {
+        Self {
+            authority: authority.into(),
+            address_index: None,
+        }
+    }
+
+    /// Host of the authority, without its port or IPv6 brackets.
+    fn host(&self) -> &str {
+        let host = self
+            .authority
+            .rsplit_once(':')
+            .map_or(self.authority.as_str(), |(host, _)| host);
+        host.trim_start_matches('[').trim_end_matches(']')
+    }
+}
+
+impl fmt::Display for GrpcTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.address_index {
+            Some(index) => write!(f, "{} (address {index})", self.authority),
+            None => f.write_str(&self.authority),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GrpcPoolConfig {
+    /// Replicas. Each replica gets `pool_size` slots.
+    pub targets: Vec<GrpcTarget>,
+    pub tls_enabled: bool,
+    pub connect_timeout: Duration,
+    pub pool_size: usize,
+    pub policy: BalancingPolicy,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// When false, each target is resolved once and every reconnection reuses
+    /// that address; when true, each reconnection resolves the host again,
+    /// so an expanded replica follows its address when it changes.
+    pub resolve_dns_on_reconnect: bool,
+    pub max_decoding_message_bytes: usize,
+    pub max_encoding_message_bytes: usize,
+}
+
+/// Lazily connected channels to the replicas of one downstream service.
+///
+/// Slots are handed out according to the configured [`BalancingPolicy`]. A
+/// slot whose channel reported a connection failure is dropped and reconnected
+/// on a later checkout, after an exponential backoff.
+pub struct GrpcChannelPool {
+    service: &'static str,
+    config: GrpcPoolConfig,
+    slots: Vec<Slot>,
+    pinned_authorities: Vec<Mutex<Option<String>>>,
+    next_slot: AtomicUsize,
+}
+
+struct Slot {
+    target: usize,
+    in_flight: Arc<AtomicUsize>,
+    state: Mutex<ChannelSlot>,
+}
+
+#[derive(Default)]
+struct ChannelSlot {
+    channel: Option<Channel>,
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+/// A checked-out channel. Counts as one in-flight call on its slot until
+/// dropped.
+pub struct PooledChannel {
+    slot: usize,
+    channel: Channel,
+    in_flight: Arc<AtomicUsize>,
+}
+
+impl PooledChannel {
+    pub fn channel(&self) -> Channel {
+        self.channel.clone()
+    }
+}
+
+impl Drop for PooledChannel {
+    fn drop(&mut self) {
+        self.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl GrpcChannelPool {
+    pub fn new(service: &'static str, config: GrpcPoolConfig) -> Self {
+        let pool_size = config.pool_size.max(1);
+        let slots = (0..config.targets.len())
+            .flat_map(|target| (0..pool_size).map(move |_| target))
+            .map(|target| Slot {
+                target,
+                in_flight: Arc::new(AtomicUsize::new(0)),
+                state: Mutex::new(ChannelSlot::default()),
+            })
+            .collect();
+        let pinned_authorities = config.targets.iter().map(|_| Mutex::new(None)).collect();
+        Self {
+            service,
+            config,
+            slots,
+            pinned_authorities,
+            next_slot: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn service(&self) -> &'static str {
+        self.service
+    }
+
+    pub fn targets(&self) -> &[GrpcTarget] {
+        &self.config.targets
+    }
+
+    pub fn tls_enabled(&self) -> bool {
+        self.config.tls_enabled
+    }
+
+    pub fn max_decoding_message_bytes(&self) -> usize {
+        self.config.max_decoding_message_bytes
+    }
+
+    pub fn max_encoding_message_bytes(&self) -> usize {
+        self.config.max_encoding_message_bytes
+    }
+
+    /// Eagerly opens every slot, ignoring any pending backoff. Used at
+    /// startup to fail fast on a misconfigured endpoint; request paths rely on
+    /// `checkout` instead.
+    pub async fn connect(&self) -> Result<(), DomainError> {
+        for slot in 0..self.slots.len() {
+            self.ensure_slot(slot, false).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn checkout(&self) -> Result<PooledChannel, DomainError> {
+        let mut last_error = None;
+        for slot in self.candidate_order() {
+            match self.ensure_slot(slot, true).await {
+                Ok(channel) => {
+                    let in_flight = self.slots[slot].in_flight.clone();
+                    in_flight.fetch_add(1, Ordering::Relaxed);
+                    return Ok(PooledChannel {
+                        slot,
+                        channel,
+                        in_flight,
+                    });
+                }
+                Err(err) => last_error = Some(err),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| {
+            DomainError::external_service_error(self.service, "no channel available")
+        }))
+    }
+
+    /// Drops the channel behind `pooled` when `status` indicates the
+    /// connection itself is gone, so the next checkout reconnects.
+    pub fn report_status(&self, pooled: &PooledChannel, status: &Status) {
+        if is_connection_failure(status) {
+            tracing::warn!(
+                service = self.service,
+                slot = pooled.slot,
+                target = %self.config.targets[self.slots[pooled.slot].target],
+                code = %status.code(),
+                "grpc channel failed; scheduling reconnect"
+            );
+            self.record_failure(pooled.slot, true);
+        }
+    }
+
+    /// Slots in the order `checkout` should try them: rotated for round-robin,
+    /// sorted by in-flight calls (ties rotated) for least-loaded.
+    fn candidate_order(&self) -> Vec<usize> {
+        let len = self.slots.len();
+        if len == 0 {
+            return Vec::new();
+        }
+        let start = self.next_slot.fetch_add(1, Ordering::Relaxed);
+        let mut order: Vec<usize> = (0..len).map(|offset| (start + offset) % len).collect();
+        if self.config.policy == BalancingPolicy::LeastLoaded {
+            order.sort_by_key(|slot| self.slots[*slot].in_flight.load(Ordering::Relaxed));
+        }
+        order
+    }
+
+    async fn ensure_slot(&self, slot: usize, respect_backoff: bool) -> Result<Channel, DomainError> {
+        {
+            let state = self.lock_slot(slot)?;
+            if let Some(channel) = &state.channel {
+                return Ok(channel.clone());
+            }
+            if let Some(retry_at) = state.retry_at.filter(|_| respect_backoff) {
+                if Instant::now() < retry_at {
+                    return Err(DomainError::external_service_error(
+                        self.service,
+                        "reconnect backoff in effect",
+                    ));
+                }
+            }
+        }
+
+        match self.open_channel(self.slots[slot].target).await {
+            Ok(channel) => {
+                let mut state = self.lock_slot(slot)?;
+                state.channel = Some(channel.clone());
+                state.failures = 0;
+                state.retry_at = None;
+                Ok(channel)
+            }
+            Err(err) => {
+                self.record_failure(slot, false);
+                Err(err)
+            }
+        }
+    }
+
+    async fn open_channel(&self, target: usize) -> Result<Channel, DomainError> {
+        let scheme = if self.config.tls_enabled { "https" } else { "http" };
+        let authority = self.authority(target).await?;
+        let invalid = |err: tonic::transport::Error| {
+            DomainError::internal_error(&format!("invalid {} endpoint: {err}", self.service))
+        };
+        let mut endpoint = Endpoint::from_shared(format!("{scheme}://{authority}"))
+            .map_err(invalid)?
+            .connect_timeout(self.config.connect_timeout);
+        if self.config.tls_enabled {
+            // The authority may be a resolved address; the certificate
+            // names the configured host.
+            let tls = ClientTlsConfig::new()
+                .domain_name(self.config.targets[target].host())
+                .with_native_roots();
+            endpoint = endpoint.tls_config(tls).map_err(invalid)?;
+        }
+        endpoint.connect().await.map_err(|err| {
+            DomainError::external_service_error(
+                self.service,
+                &format!("failed to connect to {authority}: {err}"),
+            )
+        })
+    }
+
+    /// Where `target` is connected to: its host, left to resolve on every
+    /// connection, or one of its addresses, resolved again on every
+    /// connection or pinned, per `resolve_dns_on_reconnect`.
+    async fn authority(&self, target: usize) -> Result<String, DomainError> {
+        let replica = &self.config.targets[target];
+        if self.config.resolve_dns_on_reconnect && replica.address_index.is_none() {
+            return Ok(replica.authority.clone());
+        }
+
+        if let Some(pinned) = self.lock_pinned(target)?.clone() {
+            return Ok(pinned);
+        }
+        let addresses = resolve_all(self.service, &replica.authority).await?;
+        if addresses.is_empty() {
+            return Err(DomainError::external_service_error(
+                self.service,
+                &format!("no address resolved for `{}`", replica.authority),
+            ));
+        }
+        // A host now resolving to fewer addresses shares them out.
+        let resolved = addresses[replica.address_index.unwrap_or(0) % addresses.len()].clone();
+        if !self.config.resolve_dns_on_reconnect {
+            *self.lock_pinned(target)? = Some(resolved.clone());
+        }
+        Ok(resolved)
+    }
+
+    fn record_failure(&self, slot: usize, drop_channel: bool) {
+        let Ok(mut state) = self.lock_slot(slot) else {
+            return;
+        };
+        if drop_channel {
+            state.channel = None;
+        }
+        state.failures = state.failures.saturating_add(1);
+        state.retry_at = Some(
+            Instant::now()
+                + backoff_delay(
+                    self.config.initial_backoff,
+                    self.config.max_backoff,
+                    state.failures,
+                ),
+        );
+    }
+
+    fn lock_slot(&self, slot: usize) -> Result<std::sync::MutexGuard<'_, ChannelSlot>, DomainError> {
+        self.slots[slot]
+            .state
+            .lock()
+            .map_err(|_| DomainError::internal_error("grpc channel slot lock poisoned"))
+    }
+
+    fn lock_pinned(
+        &self,
+        target: usize,
+    ) -> Result<std::sync::MutexGuard<'_, Option<String>>, DomainError> {
+        self.pinned_authorities[target]
+            .lock()
+            .map_err(|_| DomainError::internal_error("grpc pinned address lock poisoned"))
+    }
+}
+
+/// Expands every `host:port` target into one replica per address it resolves
+/// to now, so a DNS name backed by several A records becomes several
+/// replicas. Replicas keep their host: they connect under it over TLS, and
+/// resolve it again as they reconnect.
+pub async fn expand_targets(
+    service: &str,
+    targets: &[String],
+) -> Result<Vec<GrpcTarget>, DomainError> {
+    let mut expanded = Vec::new();
+    for target in targets {
+        for address_index in 0..resolve_all(service, target).await?.len() {
+            let replica = GrpcTarget {
+                address_index: Some(address_index),
+                ..GrpcTarget::new(target.as_str())
+            };
+            if !expanded.contains(&replica) {
+                expanded.push(replica);
+            }
+        }
+    }
+    Ok(expanded)
+}
+
+pub fn is_connection_failure(status: &Status) -> bool {
+    status.code() == Code::Unavailable
+}
+
+async fn resolve_all(service: &str, host_port: &str) -> Result<Vec<String>, DomainError> {
+    let addresses = tokio::net::lookup_host(host_port).await.map_err(|err| {
+        DomainError::external_service_error(
+            service,
+            &format!("failed to resolve `{host_port}`: {err}"),
+        )
+    })?;
+    let mut addresses: Vec<String> = addresses.map(|address| address.to_string()).collect();
+    // Sorted and deduplicated, so an expanded replica's index keeps naming
+    // the same address while the records do not change.
+    addresses.sort();
+    addresses.dedup();
+    Ok(addresses)
+}
+
+fn backoff_delay(initial: Duration, max: Duration, failures: u32) -> Duration {
+    let exponent = failures.saturating_sub(1).min(16);
+    initial.saturating_mul(1u32 << exponent).min(max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unreachable_config() -> GrpcPoolConfig {
+        GrpcPoolConfig {
+            targets: vec![GrpcTarget::new("127.0.0.1:1")],
+            tls_enabled: false,
+            connect_timeout: Duration::from_millis(200),
+            pool_size: 2,
+            policy: BalancingPolicy::RoundRobin,
+            initial_backoff: Duration::from_secs(30),
+            max_backoff: Duration::from_secs(60),
+            resolve_dns_on_reconnect: false,
+            max_decoding_message_bytes: 1024,
+            max_encoding_message_bytes: 1024,
+        }
+    }
+
+    #[test]
+    fn backoff_grows_exponentially_and_caps() {
+        let initial = Duration::from_millis(100);
+        let max = Duration::from_millis(1_000);
+        assert_eq!(backoff_delay(initial, max, 1), Duration::from_millis(100));
+        assert_eq!(backoff_delay(initial, max, 2), Duration::from_millis(200));
+        assert_eq!(backoff_delay(initial, max, 3), Duration::from_millis(400));
+        assert_eq!(backoff_delay(initial, max, 10), max);
+    }
+
+    #[test]
+    fn only_unavailable_is_a_connection_failure() {
+        assert!(is_connection_failure(&Status::unavailable("gone")));
+        assert!(!is_connection_failure(&Status::invalid_argument("bad")));
+        assert!(!is_connection_failure(&Status::internal("boom")));
+    }
+
+    #[test]
+    fn slots_are_allocated_per_target() {
+        let mut config = unreachable_config();
+        config.targets = vec![GrpcTarget::new("127.0.0.1:1"), GrpcTarget::new("127.0.0.1:2")];
+        let pool = GrpcChannelPool::new("test", config);
+
+        let targets: Vec<usize> = pool.slots.iter().map(|slot| slot.target).collect();
+        assert_eq!(targets, vec![0, 0, 1, 1]);
+    }
+
+    #[test]
+    fn round_robin_rotates_start_slot() {
+        let pool = GrpcChannelPool::new("test", unreachable_config());
+        assert_eq!(pool.candidate_order(), vec![0, 1]);
+        assert_eq!(pool.candidate_order(), vec![1, 0]);
+    }
+
+    #[test]
+    fn least_loaded_prefers_idle_slot() {
+        let mut config = unreachable_config();
+        config.policy = BalancingPolicy::LeastLoaded;
+        let pool = GrpcChannelPool::new("test", config);
+        pool.slots[0].in_flight.store(3, Ordering::Relaxed);
+
+        assert_eq!(pool.candidate_order()[0], 1);
+        assert_eq!(pool.candidate_order()[0], 1);
+    }
+
+    #[tokio::test]
+    async fn failed_connect_puts_slots_in_backoff() {
+        let pool = GrpcChannelPool::new("test", unreachable_config());
+
+        let first = pool.checkout().await.err().expect("connect should fail");
+        assert!(first.to_string().contains("failed to connect"));
+
+        let second = pool.checkout().await.err().expect("slots should be backing off");
+        assert!(second.to_string().contains("backoff"));
+    }
+
+    #[tokio::test]
+    async fn pinned_address_is_resolved_once() {
+        let mut config = unreachable_config();
+        config.targets = vec![GrpcTarget::new("localhost:1")];
+        let pool = GrpcChannelPool::new("test", config);
+
+        let first = pool.authority(0).await.expect("localhost resolves");
+        let second = pool.authority(0).await.expect("pinned address reused");
+        assert_eq!(first, second);
+        assert!(!first.starts_with("localhost"));
+    }
+
+    #[tokio::test]
+    async fn expand_targets_deduplicates_addresses() {
+        let expanded = expand_targets(
+            "test",
+            &["127.0.0.1:9".to_string(), "127.0.0.1:9".to_string()],
+        )
+        .await
+        .expect("literal addresses resolve");
+        assert_eq!(
+            expanded,
+            vec![GrpcTarget {
+                address_index: Some(0),
+                ..GrpcTarget::new("127.0.0.1:9")
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn expanded_replicas_keep_their_host_and_resolve_it_again() {
+        let mut config = unreachable_config();
+        config.resolve_dns_on_reconnect = true;
+        config.targets = expand_targets("test", &["localhost:1".to_string()])
+            .await
+            .expect("localhost resolves");
+        let pool = GrpcChannelPool::new("test", config);
+
+        let replica = &pool.targets()[0];
+        assert_eq!(replica.authority, "localhost:1");
+        assert_eq!(replica.host(), "localhost");
+        let address = pool.authority(0).await.expect("localhost resolves");
+        assert!(!address.starts_with("localhost"));
+        assert!(pool.lock_pinned(0).unwrap().is_none());
+    }
+
+    #[test]
+    fn tls_names_the_host_of_the_target() {
+        assert_eq!(GrpcTarget::new("asr.internal:50051").host(), "asr.internal");
+        assert_eq!(GrpcTarget::new("[::1]:50051").host(), "::1");
+    }
+
+    #[tokio::test]
+    async fn unreachable_replicas_are_not_ready() {
+        use service_health::ReadinessCheck;
+
+        let pool = Arc::new(GrpcChannelPool::new("test", unreachable_config()));
+        let readiness = DownstreamReadiness::new(vec![pool], Duration::from_millis(200));
+
+        let statuses = readiness.check().await;
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].name, "test");
+        assert!(!statuses[0].ready);
+    }
+}
@@ -0,0 +1,61 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use service_health::{DependencyStatus, ReadinessCheck, READINESS_PATH};
+
+use crate::GrpcChannelPool;
+
+/// Aggregates the `/readyz` of every downstream service. A service counts as
+/// ready as soon as one of its replicas answers `200`.
+pub struct DownstreamReadiness {
+    pools: Vec<Arc<GrpcChannelPool>>,
+    client: reqwest::Client,
+}
+
+impl DownstreamReadiness {
+    pub fn new(pools: Vec<Arc<GrpcChannelPool>>, timeout: Duration) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .unwrap_or_default();
+        Self { pools, client }
+    }
+
+    async fn probe(&self, pool: &GrpcChannelPool) -> DependencyStatus {
+        let scheme = if pool.tls_enabled() { "https" } else { "http" };
+        let mut failures = Vec::new();
+        let mut authorities: Vec<&str> = Vec::new();
+        // Expanded replicas share their authority; it is probed once.
+        for target in pool.targets() {
+            let target = target.authority.as_str();
+            if authorities.contains(&target) {
+                continue;
+            }
+            authorities.push(target);
+            let url = format!("{scheme}://{target}{READINESS_PATH}");
+            match self.client.get(&url).send().await {
+                Ok(response) if response.status().is_success() => {
+                    return DependencyStatus::ready(pool.service());
+                }
+                Ok(response) => failures.push(format!("{target}: {}", response.status())),
+                Err(err) => failures.push(format!("{target}: {err}")),
+            }
+        }
+        if failures.is_empty() {
+            return DependencyStatus::not_ready(pool.service(), "no replicas configured");
+        }
+        DependencyStatus::not_ready(pool.service(), failures.join("; "))
+    }
+}
+
+#[async_trait]
+impl ReadinessCheck for DownstreamReadiness {
+    async fn check(&self) -> Vec<DependencyStatus> {
+        let mut statuses = Vec::with_capacity(self.pools.len());
+        for pool in &self.pools {
+            statuses.push(self.probe(pool).await);
+        }
+        statuses
+    }
+}
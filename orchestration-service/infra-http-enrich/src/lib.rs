@@ -0,0 +1,166 @@
+use std::{collections::HashMap, time::Duration};
+
+use async_trait::async_trait;
+use orchestration_domain::{DomainError, PipelineContext, PipelineStage, Transcript};
+use reqwest::Client;
+use serde::Serialize;
+use serde_json::Value;
+
+mod usage;
+
+pub use usage::UsageWebhook;
+
+/// Body posted to the webhook.
+#[derive(Serialize)]
+struct EnrichRequest<'a> {
+    session_id: &'a str,
+    transcript: &'a Option<Transcript>,
+    extensions: &'a HashMap<String, Value>,
+}
+
+/// POSTs the session id, transcript and extensions as JSON to a configured
+/// URL and merges the keys of the JSON object it answers into the
+/// extensions, so external enrichment services need no Rust.
+pub struct HttpEnrichStage {
+    client: Client,
+    url: String,
+    headers: Vec<(String, String)>,
+    request_timeout: Duration,
+}
+
+impl HttpEnrichStage {
+    pub fn new(url: impl Into<String>, request_timeout: Duration) -> Self {
+        Self {
+            client: Client::new(),
+            url: url.into(),
+            headers: Vec::new(),
+            request_timeout,
+        }
+    }
+
+    /// Headers sent with every request, e.g. an `Authorization` token.
+    pub fn with_headers(mut self, headers: impl IntoIterator<Item = (String, String)>) -> Self {
+        self.headers = headers.into_iter().collect();
+        self
+    }
+}
+
+#[async_trait]
+impl PipelineStage for HttpEnrichStage {
+    fn name(&self) -> &'static str {
+        "http_enrich"
+    }
+
+    async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+        let payload = EnrichRequest {
+            session_id: &context.session_id,
+            transcript: &context.transcript,
+            extensions: &context.extensions,
+        };
+        let mut request = self.client.post(&self.url).json(&payload);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+
+        let response = tokio::time::timeout(self.request_timeout, request.send())
+            .await
+            .map_err(|_| webhook_error("HTTP request timed out"))?
+            .map_err(|err| webhook_error(&format!("HTTP request failed: {err}")))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(webhook_error(&format!(
+                "HTTP {} from {}: {}",
+                status.as_u16(),
+                self.url,
+                truncate_text(&body, 300)
+            )));
+        }
+        let body = tokio::time::timeout(self.request_timeout, response.json::<Value>())
+            .await
+            .map_err(|_| webhook_error("timed out reading HTTP response body"))?
+            .map_err(|err| webhook_error(&format!("invalid JSON response: {err}")))?;
+
+        let Value::Object(fields) = body else {
+            return Err(webhook_error("response is not a JSON object"));
+        };
+        tracing::debug!(keys = fields.len(), url = %self.url, "http_enrich: merged response");
+        for (key, value) in fields {
+            context.set_extension(key, value);
+        }
+        Ok(())
+    }
+}
+
+fn webhook_error(message: &str) -> DomainError {
+    DomainError::external_service_error("http_enrich", message)
+}
+
+fn truncate_text(text: &str, max_chars: usize) -> String {
+    let mut chars = text.chars();
+    let truncated: String = chars.by_ref().take(max_chars).collect();
+    if chars.next().is_some() {
+        format!("{truncated}...")
+    } else {
+        truncated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::HeaderMap, routing::post, Json, Router};
+    use serde_json::json;
+
+    /// Serves `router` on a free local port and returns its base URL.
+    async fn serve(router: Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let address = listener.local_addr().expect("local address");
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        format!("http://{address}")
+    }
+
+    #[tokio::test]
+    async fn response_fields_are_merged_into_the_extensions() {
+        let router = Router::new().route(
+            "/enrich",
+            post(|headers: HeaderMap, Json(body): Json<Value>| async move {
+                Json(json!({
+                    "webhook.seen": body["extensions"]["asr.text"],
+                    "webhook.session": body["session_id"],
+                    "webhook.token": headers["x-token"].to_str().unwrap(),
+                }))
+            }),
+        );
+        let url = format!("{}/enrich", serve(router).await);
+        let stage = HttpEnrichStage::new(url, Duration::from_secs(5))
+            .with_headers([("x-token".to_string(), "secret".to_string())]);
+        let mut context = PipelineContext::new("session-1", None);
+        context.set_extension("asr.text", json!("hello"));
+
+        stage.execute(&mut context).await.expect("webhook answers");
+
+        assert_eq!(context.extension("webhook.seen"), Some(&json!("hello")));
+        assert_eq!(context.extension("webhook.session"), Some(&json!("session-1")));
+        assert_eq!(context.extension("webhook.token"), Some(&json!("secret")));
+        assert_eq!(context.extension("asr.text"), Some(&json!("hello")));
+    }
+
+    #[tokio::test]
+    async fn non_object_responses_fail_the_step() {
+        let router = Router::new().route("/enrich", post(|| async { Json(json!([1, 2])) }));
+        let url = format!("{}/enrich", serve(router).await);
+        let stage = HttpEnrichStage::new(url, Duration::from_secs(5));
+        let mut context = PipelineContext::new("session-1", None);
+
+        let error = stage.execute(&mut context).await.expect_err("array response");
+        assert!(error.to_string().contains("JSON object"), "{error}");
+        assert!(context.extensions.is_empty());
+    }
+
+    #[test]
+    fn truncate_text_marks_cut_bodies() {
+        assert_eq!(truncate_text("abcdef", 3), "abc...");
+        assert_eq!(truncate_text("abc", 3), "abc");
+    }
+}
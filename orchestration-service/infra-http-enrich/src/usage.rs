@@ -0,0 +1,132 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use orchestration_domain::{DomainError, UsageRecord, UsageSink};
+use reqwest::Client;
+use serde::Serialize;
+
+use crate::truncate_text;
+
+/// Body posted to the usage webhook.
+#[derive(Serialize)]
+struct UsageEvent<'a> {
+    records: &'a [UsageRecord],
+}
+
+/// POSTs usage events as JSON (`{"records": [...]}`) to a billing or
+/// chargeback system.
+pub struct UsageWebhook {
+    client: Client,
+    url: String,
+    headers: Vec<(String, String)>,
+    request_timeout: Duration,
+}
+
+impl UsageWebhook {
+    pub fn new(url: impl Into<String>, request_timeout: Duration) -> Self {
+        Self {
+            client: Client::new(),
+            url: url.into(),
+            headers: Vec::new(),
+            request_timeout,
+        }
+    }
+
+    /// Headers sent with every request, e.g. an `Authorization` token.
+    pub fn with_headers(mut self, headers: impl IntoIterator<Item = (String, String)>) -> Self {
+        self.headers = headers.into_iter().collect();
+        self
+    }
+}
+
+#[async_trait]
+impl UsageSink for UsageWebhook {
+    async fn publish(&self, records: &[UsageRecord]) -> Result<(), DomainError> {
+        let mut request = self.client.post(&self.url).json(&UsageEvent { records });
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+
+        let response = tokio::time::timeout(self.request_timeout, request.send())
+            .await
+            .map_err(|_| usage_error("HTTP request timed out"))?
+            .map_err(|err| usage_error(&format!("HTTP request failed: {err}")))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(usage_error(&format!(
+                "HTTP {} from {}: {}",
+                status.as_u16(),
+                self.url,
+                truncate_text(&body, 300)
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn usage_error(message: &str) -> DomainError {
+    DomainError::external_service_error("usage_webhook", message)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use axum::{http::StatusCode, routing::post, Json, Router};
+    use serde_json::Value;
+
+    /// Serves `router` on a free local port and returns its base URL.
+    async fn serve(router: Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let address = listener.local_addr().expect("local address");
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        format!("http://{address}")
+    }
+
+    fn record() -> UsageRecord {
+        UsageRecord {
+            tenant_id: Some("acme".to_string()),
+            model: "tiny".to_string(),
+            requests: 3,
+            audio_seconds: 12.5,
+            asr_seconds: 1.25,
+        }
+    }
+
+    #[tokio::test]
+    async fn records_are_posted_as_one_event() {
+        let received = Arc::new(Mutex::new(Value::Null));
+        let sink = received.clone();
+        let router = Router::new().route(
+            "/usage",
+            post(move |Json(body): Json<Value>| async move {
+                *sink.lock().unwrap() = body;
+                StatusCode::NO_CONTENT
+            }),
+        );
+        let url = format!("{}/usage", serve(router).await);
+
+        UsageWebhook::new(url, Duration::from_secs(5))
+            .publish(&[record()])
+            .await
+            .expect("webhook accepts");
+
+        let body = received.lock().unwrap().clone();
+        assert_eq!(body["records"][0]["tenant_id"], "acme");
+        assert_eq!(body["records"][0]["audio_seconds"], 12.5);
+    }
+
+    #[tokio::test]
+    async fn rejected_events_are_errors() {
+        let router = Router::new().route("/usage", post(|| async { StatusCode::BAD_GATEWAY }));
+        let url = format!("{}/usage", serve(router).await);
+
+        let error = UsageWebhook::new(url, Duration::from_secs(5))
+            .publish(&[record()])
+            .await
+            .expect_err("502");
+        assert!(error.to_string().contains("502"), "{error}");
+    }
+}
@@ -0,0 +1,278 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use object_store::path::Path;
+use object_store::ObjectStore;
+use orchestration_domain::{AudioSourcePort, DomainError};
+use reqwest::redirect::Policy;
+use reqwest::{Client, Url};
+
+use crate::{s3_builder, S3Settings};
+
+/// Limits and credentials of [`RemoteAudioSource`].
+#[derive(Debug, Clone)]
+pub struct AudioFetchSettings {
+    /// Covers connecting and reading the whole file.
+    pub timeout: Duration,
+    /// Larger files are refused before or while they are read.
+    pub max_bytes: u64,
+    /// Endpoint and credentials of `s3://` references; the bucket comes from
+    /// the reference and `bucket` is ignored.
+    pub s3: S3Settings,
+    /// Hosts `http://` and `https://` references may name, e.g.
+    /// `media.example.com`; `*.example.com` also allows its subdomains.
+    /// Empty refuses every URL.
+    pub allowed_hosts: Vec<String>,
+    /// Buckets `s3://` references may name. Empty refuses every bucket.
+    pub allowed_buckets: Vec<String>,
+}
+
+/// Downloads `s3://bucket/key`, `http://` and `https://` references to the
+/// allowed buckets and hosts; redirects are followed only to allowed hosts.
+/// Other schemes, `file://` included, are refused so requests cannot read
+/// the server's disk.
+pub struct RemoteAudioSource {
+    client: Client,
+    settings: AudioFetchSettings,
+}
+
+/// Redirects followed before a download is refused.
+const MAX_REDIRECTS: usize = 5;
+
+impl RemoteAudioSource {
+    /// Fails when the HTTP client cannot be built; a default client would
+    /// follow redirects to any host.
+    pub fn new(settings: AudioFetchSettings) -> Result<Self, DomainError> {
+        let allowed_hosts = settings.allowed_hosts.clone();
+        let redirects = Policy::custom(move |attempt| {
+            if attempt.previous().len() >= MAX_REDIRECTS {
+                return attempt.error(format!("more than {MAX_REDIRECTS} redirects"));
+            }
+            match attempt.url().host_str() {
+                Some(host) if host_allowed(&allowed_hosts, host) => attempt.follow(),
+                host => {
+                    let host = host.unwrap_or_default().to_string();
+                    attempt.error(format!("redirect to `{host}`, which is not allowed"))
+                }
+            }
+        });
+        let client = Client::builder()
+            .redirect(redirects)
+            .build()
+            .map_err(|err| DomainError::internal_error(&format!("audio fetch client: {err}")))?;
+        Ok(Self { client, settings })
+    }
+
+    async fn fetch_s3(&self, bucket: &str, key: &str) -> Result<Vec<u8>, DomainError> {
+        if !self.settings.allowed_buckets.iter().any(|allowed| allowed == bucket) {
+            return Err(DomainError::invalid_input(&format!(
+                "bucket `{bucket}` is not in service.audio_fetch.allowed_buckets"
+            )));
+        }
+        let settings = S3Settings {
+            bucket: bucket.to_string(),
+            ..self.settings.s3.clone()
+        };
+        let store = s3_builder(&settings)
+            .build()
+            .map_err(|err| fetch_error(&format!("s3://{bucket}: {err}")))?;
+        let object = store
+            .get(&Path::from(key))
+            .await
+            .map_err(|err| fetch_error(&format!("s3://{bucket}/{key}: {err}")))?;
+        self.check_size(object.meta.size)?;
+        let bytes = object
+            .bytes()
+            .await
+            .map_err(|err| fetch_error(&format!("s3://{bucket}/{key}: {err}")))?;
+        Ok(bytes.to_vec())
+    }
+
+    async fn fetch_http(&self, url: &str) -> Result<Vec<u8>, DomainError> {
+        let host = Url::parse(url)
+            .ok()
+            .and_then(|parsed| parsed.host_str().map(str::to_string))
+            .ok_or_else(|| DomainError::invalid_input(&format!("`{url}` has no host")))?;
+        if !host_allowed(&self.settings.allowed_hosts, &host) {
+            return Err(DomainError::invalid_input(&format!(
+                "host `{host}` is not in service.audio_fetch.allowed_hosts"
+            )));
+        }
+        let mut response = self.client.get(url).send().await.map_err(|err| {
+            // A refused redirect says why in its source.
+            let reason = std::error::Error::source(&err)
+                .map_or_else(|| err.to_string(), |source| format!("{err}: {source}"));
+            fetch_error(&format!("{url}: {reason}"))
+        })?;
+        if !response.status().is_success() {
+            return Err(fetch_error(&format!(
+                "{url}: HTTP {}",
+                response.status().as_u16()
+            )));
+        }
+        if let Some(length) = response.content_length() {
+            self.check_size(length)?;
+        }
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|err| fetch_error(&format!("{url}: {err}")))?
+        {
+            bytes.extend_from_slice(&chunk);
+            self.check_size(bytes.len() as u64)?;
+        }
+        Ok(bytes)
+    }
+
+    fn check_size(&self, size: u64) -> Result<(), DomainError> {
+        if size > self.settings.max_bytes {
+            return Err(DomainError::invalid_input(&format!(
+                "referenced audio exceeds {} bytes",
+                self.settings.max_bytes
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AudioSourcePort for RemoteAudioSource {
+    async fn fetch(&self, uri: &str) -> Result<Vec<u8>, DomainError> {
+        let fetch = async {
+            if let Some(location) = uri.strip_prefix("s3://") {
+                let (bucket, key) = location
+                    .split_once('/')
+                    .filter(|(bucket, key)| !bucket.is_empty() && !key.is_empty())
+                    .ok_or_else(|| {
+                        DomainError::invalid_input(&format!("`{uri}` is not s3://bucket/key"))
+                    })?;
+                self.fetch_s3(bucket, key).await
+            } else if uri.starts_with("http://") || uri.starts_with("https://") {
+                self.fetch_http(uri).await
+            } else {
+                Err(DomainError::invalid_input(&format!(
+                    "`{uri}` is not an s3://, http:// or https:// reference"
+                )))
+            }
+        };
+        let bytes = tokio::time::timeout(self.settings.timeout, fetch)
+            .await
+            .map_err(|_| fetch_error(&format!("{uri}: timed out")))??;
+        tracing::debug!(uri, byte_count = bytes.len(), "fetched referenced audio");
+        Ok(bytes)
+    }
+}
+
+/// Whether `host` is one of `allowed`, or a subdomain of a `*.` entry.
+fn host_allowed(allowed: &[String], host: &str) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    allowed.iter().any(|entry| {
+        let entry = entry.to_ascii_lowercase();
+        match entry.strip_prefix("*.") {
+            Some(domain) => host
+                .strip_suffix(domain)
+                .is_some_and(|subdomain| subdomain.ends_with('.')),
+            None => host == entry,
+        }
+    })
+}
+
+fn fetch_error(message: &str) -> DomainError {
+    DomainError::external_service_error("audio_fetch", message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{response::Redirect, routing::get, Router};
+
+    /// Serves `router` on a free local port and returns its base URL.
+    async fn serve(router: Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let address = listener.local_addr().expect("local address");
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        format!("http://{address}")
+    }
+
+    fn source(max_bytes: u64) -> RemoteAudioSource {
+        RemoteAudioSource::new(settings(max_bytes, &["127.0.0.1"], &["calls"])).expect("client")
+    }
+
+    fn settings(max_bytes: u64, hosts: &[&str], buckets: &[&str]) -> AudioFetchSettings {
+        AudioFetchSettings {
+            timeout: Duration::from_secs(5),
+            max_bytes,
+            s3: S3Settings::default(),
+            allowed_hosts: hosts.iter().map(|host| host.to_string()).collect(),
+            allowed_buckets: buckets.iter().map(|bucket| bucket.to_string()).collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn http_references_are_downloaded_up_to_the_limit() {
+        let router = Router::new().route("/call.wav", get(|| async { vec![7u8; 64] }));
+        let url = format!("{}/call.wav", serve(router).await);
+
+        assert_eq!(source(64).fetch(&url).await.expect("fetched"), vec![7u8; 64]);
+        let error = source(63).fetch(&url).await.expect_err("too large");
+        assert!(error.to_string().contains("exceeds 63 bytes"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn local_and_malformed_references_are_refused() {
+        for uri in ["file:///etc/passwd", "/etc/passwd", "s3://bucket-only", "s3:///key"] {
+            assert!(source(1024).fetch(uri).await.is_err(), "{uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn hosts_and_buckets_must_be_allowed() {
+        let router = Router::new().route("/call.wav", get(|| async { vec![7u8; 8] }));
+        let url = format!("{}/call.wav", serve(router).await);
+
+        let elsewhere = RemoteAudioSource::new(settings(1024, &["media.example.com"], &[]))
+            .expect("client");
+        let error = elsewhere.fetch(&url).await.expect_err("host not allowed");
+        assert!(error.to_string().contains("allowed_hosts"), "{error}");
+
+        let nothing = RemoteAudioSource::new(settings(1024, &[], &[])).expect("client");
+        assert!(nothing.fetch(&url).await.is_err(), "empty allowlist denies");
+
+        let error = source(1024)
+            .fetch("s3://other-bucket/call.wav")
+            .await
+            .expect_err("bucket not allowed");
+        assert!(error.to_string().contains("allowed_buckets"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn redirects_to_other_hosts_are_refused() {
+        let target = Router::new().route("/call.wav", get(|| async { vec![7u8; 8] }));
+        let target_url = format!("{}/call.wav", serve(target).await);
+        let outside = target_url.replace("127.0.0.1", "localhost");
+        let inside = target_url.clone();
+        let hops = Router::new()
+            .route("/outside", get(move || async move { Redirect::temporary(&outside) }))
+            .route("/inside", get(move || async move { Redirect::temporary(&inside) }));
+        let base = serve(hops).await;
+
+        let error = source(1024)
+            .fetch(&format!("{base}/outside"))
+            .await
+            .expect_err("redirect leaves the allowlist");
+        assert!(error.to_string().contains("not allowed"), "{error}");
+        let bytes = source(1024).fetch(&format!("{base}/inside")).await.expect("fetched");
+        assert_eq!(bytes, vec![7u8; 8]);
+    }
+
+    #[test]
+    fn wildcard_entries_allow_subdomains_only() {
+        let allowed = vec!["*.example.com".to_string(), "media.test".to_string()];
+        assert!(host_allowed(&allowed, "cdn.example.com"));
+        assert!(host_allowed(&allowed, "MEDIA.test"));
+        assert!(!host_allowed(&allowed, "example.com"));
+        assert!(!host_allowed(&allowed, "evilexample.com"));
+        assert!(!host_allowed(&allowed, "media.test.evil"));
+    }
+}
@@ -0,0 +1,131 @@
+use std::path::Path as FsPath;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use object_store::aws::AmazonS3Builder;
+use object_store::local::LocalFileSystem;
+use object_store::path::Path;
+use object_store::ObjectStore;
+use orchestration_domain::{DomainError, ObjectStorePort, StoredObject};
+
+mod fetch;
+
+pub use fetch::{AudioFetchSettings, RemoteAudioSource};
+
+/// Bucket of an S3-compatible service (AWS, MinIO, R2, ...). Unset
+/// credentials and region come from the usual `AWS_*` environment variables.
+#[derive(Debug, Clone, Default)]
+pub struct S3Settings {
+    pub bucket: String,
+    /// e.g. `http://minio:9000`; AWS when unset.
+    pub endpoint: Option<String>,
+    pub region: Option<String>,
+    pub access_key_id: Option<String>,
+    pub secret_access_key: Option<String>,
+    /// Allows a plain `http://` endpoint.
+    pub allow_http: bool,
+}
+
+/// [`ObjectStorePort`] over the `object_store` crate, so a local directory
+/// and an S3 bucket behave the same.
+pub struct ObjectStoreAdapter {
+    store: Arc<dyn ObjectStore>,
+}
+
+impl ObjectStoreAdapter {
+    /// Objects as files under `root`, created if missing.
+    pub fn directory(root: impl AsRef<FsPath>) -> Result<Self, DomainError> {
+        let root = root.as_ref();
+        std::fs::create_dir_all(root).map_err(|err| {
+            DomainError::internal_error(&format!("object store {}: {err}", root.display()))
+        })?;
+        let store = LocalFileSystem::new_with_prefix(root).map_err(store_error)?;
+        Ok(Self {
+            store: Arc::new(store),
+        })
+    }
+
+    pub fn s3(settings: &S3Settings) -> Result<Self, DomainError> {
+        Ok(Self {
+            store: Arc::new(s3_builder(settings).build().map_err(store_error)?),
+        })
+    }
+}
+
+fn s3_builder(settings: &S3Settings) -> AmazonS3Builder {
+    let mut builder = AmazonS3Builder::from_env()
+        .with_bucket_name(&settings.bucket)
+        .with_allow_http(settings.allow_http);
+    if let Some(endpoint) = &settings.endpoint {
+        builder = builder.with_endpoint(endpoint);
+    }
+    if let Some(region) = &settings.region {
+        builder = builder.with_region(region);
+    }
+    if let Some(access_key_id) = &settings.access_key_id {
+        builder = builder.with_access_key_id(access_key_id);
+    }
+    if let Some(secret_access_key) = &settings.secret_access_key {
+        builder = builder.with_secret_access_key(secret_access_key);
+    }
+    builder
+}
+
+#[async_trait]
+impl ObjectStorePort for ObjectStoreAdapter {
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<(), DomainError> {
+        self.store
+            .put(&Path::from(key), bytes.into())
+            .await
+            .map_err(store_error)?;
+        Ok(())
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<StoredObject>, DomainError> {
+        let prefix = Path::from(prefix);
+        self.store
+            .list(Some(&prefix))
+            .map_ok(|meta| StoredObject {
+                key: meta.location.to_string(),
+                modified_at: meta.last_modified.into(),
+            })
+            .try_collect()
+            .await
+            .map_err(store_error)
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), DomainError> {
+        match self.store.delete(&Path::from(key)).await {
+            Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(err) => Err(store_error(err)),
+        }
+    }
+}
+
+fn store_error(err: object_store::Error) -> DomainError {
+    DomainError::internal_error(&format!("object store: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn directory_store_round_trip() {
+        let root = std::env::temp_dir().join(format!("object-store-{}", uuid::Uuid::new_v4()));
+        let store = ObjectStoreAdapter::directory(&root).expect("store");
+
+        store.put("recordings/s1/audio.wav", vec![1, 2, 3]).await.expect("put");
+        store.put("other/key", vec![4]).await.expect("put");
+        let listed = store.list("recordings").await.expect("list");
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].key, "recordings/s1/audio.wav");
+
+        store.delete("recordings/s1/audio.wav").await.expect("delete");
+        store.delete("recordings/s1/audio.wav").await.expect("deleting twice is fine");
+        assert!(store.list("recordings").await.expect("list").is_empty());
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,23 @@
+use std::path::{Path, PathBuf};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let protoc = protoc_bin_vendored::protoc_bin_path()?;
+    std::env::set_var("PROTOC", protoc);
+    let descriptor = PathBuf::from(std::env::var("OUT_DIR")?).join("streaming_descriptor.bin");
+
+    tonic_prost_build::configure()
+        .build_client(true)
+        .build_server(true)
+        .extern_path(".common.v1", "::common_proto::v1")
+        .file_descriptor_set_path(&descriptor)
+        .compile_protos(&["../proto/streaming.proto"], &["../proto", "../../common-proto/proto"])?;
+
+    println!("cargo:rerun-if-changed=../proto/streaming.proto");
+    println!("cargo:rerun-if-changed=../../common-proto/proto/common/v1/common.proto");
+    proto_compat::enforce(
+        &descriptor,
+        "orchestration.streaming.v1",
+        Path::new("../proto/orchestration.streaming.v1.lock"),
+    )?;
+    Ok(())
+}
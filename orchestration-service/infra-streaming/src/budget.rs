@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use serde::Serialize;
+use tokio::sync::Notify;
+
+/// Audio buffered by all WebSocket sessions, capped at `max_bytes`. Going
+/// over the cap asks the least recently active sessions to flush: they run
+/// the pipeline on what they hold and drop it, until the total fits again.
+pub struct StreamBudget {
+    max_bytes: usize,
+    inner: Mutex<BudgetInner>,
+}
+
+#[derive(Default)]
+struct BudgetInner {
+    next_id: u64,
+    sessions: HashMap<u64, SessionEntry>,
+}
+
+struct SessionEntry {
+    bytes: usize,
+    last_active: Instant,
+    /// Asked to flush and not done yet; its bytes count as released.
+    evicting: bool,
+    flush: Arc<Notify>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct BudgetSnapshot {
+    pub active_sessions: usize,
+    pub buffered_bytes: usize,
+    pub max_buffered_bytes: usize,
+}
+
+impl StreamBudget {
+    pub fn new(max_bytes: usize) -> Arc<Self> {
+        Arc::new(Self {
+            max_bytes,
+            inner: Mutex::new(BudgetInner::default()),
+        })
+    }
+
+    /// Tracks a new session until the lease is dropped.
+    pub fn register(self: &Arc<Self>) -> BudgetLease {
+        let flush = Arc::new(Notify::new());
+        let mut inner = self.lock();
+        let id = inner.next_id;
+        inner.next_id += 1;
+        inner.sessions.insert(
+            id,
+            SessionEntry {
+                bytes: 0,
+                last_active: Instant::now(),
+                evicting: false,
+                flush: flush.clone(),
+            },
+        );
+        BudgetLease {
+            budget: self.clone(),
+            id,
+            flush,
+        }
+    }
+
+    /// Gauge of the audio held by every session.
+    pub fn snapshot(&self) -> BudgetSnapshot {
+        let inner = self.lock();
+        BudgetSnapshot {
+            active_sessions: inner.sessions.len(),
+            buffered_bytes: inner.sessions.values().map(|entry| entry.bytes).sum(),
+            max_buffered_bytes: self.max_bytes,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BudgetInner> {
+        self.inner.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+/// A session's share of the [`StreamBudget`].
+pub struct BudgetLease {
+    budget: Arc<StreamBudget>,
+    id: u64,
+    flush: Arc<Notify>,
+}
+
+impl BudgetLease {
+    /// Records `bytes` more audio held by this session, then asks the least
+    /// recently active sessions to flush while the cap is exceeded. Returns
+    /// `true` when this session is one of them and must flush now.
+    pub fn add(&self, bytes: usize) -> bool {
+        let mut inner = self.budget.lock();
+        if let Some(entry) = inner.sessions.get_mut(&self.id) {
+            entry.bytes += bytes;
+            entry.last_active = Instant::now();
+        }
+
+        let mut retained: usize = inner
+            .sessions
+            .values()
+            .filter(|entry| !entry.evicting)
+            .map(|entry| entry.bytes)
+            .sum();
+        let mut flush_self = false;
+        while retained > self.budget.max_bytes {
+            let Some((&id, entry)) = inner
+                .sessions
+                .iter_mut()
+                .filter(|(_, entry)| !entry.evicting && entry.bytes > 0)
+                .min_by_key(|(_, entry)| entry.last_active)
+            else {
+                break;
+            };
+            entry.evicting = true;
+            retained -= entry.bytes;
+            if id == self.id {
+                flush_self = true;
+            } else {
+                entry.flush.notify_one();
+            }
+            tracing::warn!(
+                evicted_bytes = entry.bytes,
+                retained_bytes = retained,
+                max_bytes = self.budget.max_bytes,
+                "stream buffer budget exceeded, flushing least recently active session"
+            );
+        }
+        flush_self
+    }
+
+    /// The session dropped its buffered audio.
+    pub fn clear(&self) {
+        if let Some(entry) = self.budget.lock().sessions.get_mut(&self.id) {
+            entry.bytes = 0;
+            entry.evicting = false;
+        }
+    }
+
+    /// Resolves once another session's [`BudgetLease::add`] asks this one to
+    /// flush.
+    pub async fn flush_requested(&self) {
+        self.flush.notified().await;
+    }
+}
+
+impl Drop for BudgetLease {
+    fn drop(&mut self) {
+        self.budget.lock().sessions.remove(&self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn under_the_cap_nothing_flushes() {
+        let budget = StreamBudget::new(100);
+        let lease = budget.register();
+        assert!(!lease.add(60));
+        assert!(!lease.add(40));
+        assert_eq!(budget.snapshot().buffered_bytes, 100);
+    }
+
+    #[tokio::test]
+    async fn least_recently_active_session_is_asked_to_flush() {
+        let budget = StreamBudget::new(100);
+        let idle = budget.register();
+        let busy = budget.register();
+        assert!(!idle.add(60));
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(!busy.add(50), "the idle session flushes, not the busy one");
+
+        tokio::time::timeout(Duration::from_secs(1), idle.flush_requested())
+            .await
+            .expect("idle session asked to flush");
+        idle.clear();
+        assert_eq!(budget.snapshot().buffered_bytes, 50);
+    }
+
+    #[test]
+    fn a_lone_session_over_the_cap_flushes_itself() {
+        let budget = StreamBudget::new(100);
+        let lease = budget.register();
+        assert!(lease.add(150));
+        lease.clear();
+        assert!(!lease.add(10));
+    }
+
+    #[test]
+    fn dropped_sessions_release_their_bytes() {
+        let budget = StreamBudget::new(100);
+        let lease = budget.register();
+        lease.add(30);
+        drop(lease);
+        assert_eq!(
+            budget.snapshot(),
+            BudgetSnapshot {
+                active_sessions: 0,
+                buffered_bytes: 0,
+                max_buffered_bytes: 100,
+            }
+        );
+    }
+}
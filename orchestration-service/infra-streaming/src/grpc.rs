@@ -0,0 +1,318 @@
+//! The streaming protocol over a bidirectional gRPC stream, for native
+//! clients. Frames map one to one onto the WebSocket messages and run
+//! through the same [`SessionDriver`], so both transports behave alike.
+
+use std::net::SocketAddr;
+use std::pin::Pin;
+
+use orchestration_application::{ApplicationError, TENANT_ID_HEADER};
+use orchestration_domain::{DomainError, LanguageTag};
+use futures::{Stream, StreamExt};
+use log_context::{request_id_or_new, with_request_id, REQUEST_ID_HEADER};
+use tonic::{transport::Server, Request, Response, Status, Streaming};
+use tracing::{error, info, Instrument};
+
+use crate::protocol::{ClientEnvelope, ClientMessage, ServerEnvelope, ServerMessage, Verbosity};
+use crate::{admitted_driver, OutboundQueue, SessionDriver, StreamingState};
+
+pub mod pb {
+    tonic::include_proto!("orchestration.streaming.v1");
+
+    // The shared messages moved to `common.v1`; re-exported so code written
+    // against this package's former copies keeps compiling.
+    pub use common_proto::v1::{
+        LanguageTag, LanguageTagCode, Transcript, TranscriptSegment, TranscriptToken, WordTiming,
+    };
+}
+
+pub use pb::streaming_service_client::StreamingServiceClient;
+pub use pb::streaming_service_server::StreamingServiceServer;
+
+vocal_proto_mappings::transcript_mappings!(pb, orchestration_domain);
+vocal_proto_mappings::word_timing_mappings!(pb, orchestration_domain);
+
+type EventStream = Pin<Box<dyn Stream<Item = Result<pb::ServerEvent, Status>> + Send>>;
+
+pub struct GrpcStreamingService {
+    state: StreamingState,
+}
+
+impl GrpcStreamingService {
+    pub fn new(state: StreamingState) -> Self {
+        Self { state }
+    }
+}
+
+/// Server for [`GrpcStreamingService`], with the message size limit of the
+/// WebSocket listener.
+pub fn grpc_service(state: StreamingState) -> StreamingServiceServer<GrpcStreamingService> {
+    let max_message_bytes = state.max_message_bytes;
+    StreamingServiceServer::new(GrpcStreamingService::new(state))
+        .max_decoding_message_size(max_message_bytes)
+        .max_encoding_message_size(max_message_bytes)
+}
+
+pub async fn run_grpc_server(state: StreamingState, bind_addr: &str) -> Result<(), DomainError> {
+    let address: SocketAddr = bind_addr
+        .parse()
+        .map_err(|err| DomainError::internal_error(&format!("invalid bind address: {err}")))?;
+    info!("gRPC streaming server listening on {}", bind_addr);
+    Server::builder()
+        .add_service(grpc_service(state))
+        .serve(address)
+        .await
+        .map_err(|err| DomainError::internal_error(&format!("server error: {err}")))
+}
+
+#[tonic::async_trait]
+impl pb::streaming_service_server::StreamingService for GrpcStreamingService {
+    type StreamStream = EventStream;
+
+    async fn stream(
+        &self,
+        request: Request<Streaming<pb::ClientFrame>>,
+    ) -> Result<Response<Self::StreamStream>, Status> {
+        let request_id = request_id_or_new(
+            request
+                .metadata()
+                .get(REQUEST_ID_HEADER)
+                .and_then(|value| value.to_str().ok()),
+        );
+        // `session_id` is recorded once the client sends `start`.
+        let span = tracing::info_span!(
+            "grpc_stream_session",
+            request_id = %request_id,
+            session_id = tracing::field::Empty,
+        );
+        let out = OutboundQueue::new(self.state.outbound_queue_len, self.state.stall_timeout);
+        let driver = admitted_driver(
+            self.state.clone(),
+            out.clone(),
+            request
+                .metadata()
+                .get(TENANT_ID_HEADER)
+                .map(|value| value.to_str().unwrap_or_default()),
+        )
+        .map_err(refusal)?;
+        let reader = read_frames(request.into_inner(), driver, out.clone());
+        tokio::spawn(with_request_id(request_id, reader.instrument(span)));
+
+        let events = futures::stream::unfold(out, |out| async move {
+            let envelope = out.pop().await?;
+            Some((Ok(event_to_proto(envelope)), out))
+        });
+        Ok(Response::new(Box::pin(events)))
+    }
+}
+
+/// Status refusing a stream, matching the HTTP API's status for the error.
+fn refusal(err: ApplicationError) -> Status {
+    match err {
+        ApplicationError::QuotaExceeded(_) => Status::resource_exhausted(err.to_string()),
+        _ => Status::invalid_argument(err.to_string()),
+    }
+}
+
+/// Feeds the client's frames to the session until the client half-closes
+/// or a frame fails; closes `out` so the response stream ends once drained.
+async fn read_frames(
+    mut frames: Streaming<pb::ClientFrame>,
+    mut driver: SessionDriver,
+    out: std::sync::Arc<OutboundQueue>,
+) {
+    loop {
+        let frame = tokio::select! {
+            frame = frames.next() => match frame {
+                Some(Ok(frame)) => frame,
+                Some(Err(status)) => {
+                    error!("grpc transport error: {}", status);
+                    break;
+                }
+                None => break,
+            },
+            () = driver.flush_requested() => {
+                if let Err(err) = driver.evict().await {
+                    error!("session error: {}", err);
+                    break;
+                }
+                continue;
+            }
+        };
+        let result = match frame_from_proto(frame) {
+            Ok(envelope) => driver.handle(envelope).await,
+            Err(err) => Err(err),
+        };
+        if let Err(err) = result {
+            error!("session error: {}", err);
+            let _ = driver
+                .send(
+                    None,
+                    ServerMessage::Error {
+                        message: err.to_string(),
+                    },
+                )
+                .await;
+            break;
+        }
+    }
+    out.close();
+    if out.dropped_partials() > 0 {
+        info!(dropped_partials = out.dropped_partials(), "slow client skipped partials");
+    }
+}
+
+fn frame_from_proto(frame: pb::ClientFrame) -> Result<ClientEnvelope, DomainError> {
+    use pb::client_frame::Message;
+
+    let message = match frame.message {
+        Some(Message::Start(start)) => {
+            let defaults = Verbosity::default();
+            let language_hint = start
+                .language_hint
+                .map(|tag| LanguageTag::parse(&tag))
+                .transpose()
+                .map_err(|err| {
+                    DomainError::invalid_input(&format!("invalid language_hint: {err}"))
+                })?;
+            ClientMessage::Start {
+                session_id: start.session_id,
+                language_hint,
+                verbosity: Verbosity {
+                    emit_tokens: start.emit_tokens.unwrap_or(defaults.emit_tokens),
+                    emit_alignment: start.emit_alignment.unwrap_or(defaults.emit_alignment),
+                    emit_partials: start.emit_partials.unwrap_or(defaults.emit_partials),
+                },
+            }
+        }
+        Some(Message::AudioFrame(frame)) => ClientMessage::AudioFrame {
+            pcm_f32: frame.pcm_f32,
+        },
+        Some(Message::Flush(_)) => ClientMessage::Flush,
+        Some(Message::Stop(_)) => ClientMessage::Stop,
+        Some(Message::Ping(_)) => ClientMessage::Ping,
+        None => return Err(DomainError::invalid_input("frame carries no message")),
+    };
+    Ok(ClientEnvelope {
+        version: frame.version,
+        track: frame.track,
+        message,
+    })
+}
+
+fn event_to_proto(envelope: ServerEnvelope) -> pb::ServerEvent {
+    use pb::server_event::Event;
+
+    let event = match envelope.message {
+        ServerMessage::Ready { session_id } => Event::Ready(pb::Ready { session_id }),
+        ServerMessage::PartialTranscript {
+            transcript,
+            stable_until_ms,
+        } => Event::PartialTranscript(pb::PartialTranscript {
+            transcript: Some(transcript_to_proto(transcript)),
+            stable_until_ms,
+        }),
+        ServerMessage::FinalTranscript { transcript } => {
+            Event::FinalTranscript(pb::FinalTranscript {
+                transcript: Some(transcript_to_proto(transcript)),
+            })
+        }
+        ServerMessage::AlignmentUpdate { words } => Event::AlignmentUpdate(pb::AlignmentUpdate {
+            words: words.into_iter().map(word_timing_to_proto).collect(),
+        }),
+        ServerMessage::StageStarted { stage } => Event::StageStarted(pb::StageStarted { stage }),
+        ServerMessage::StageCompleted { stage, duration_ms } => {
+            Event::StageCompleted(pb::StageCompleted { stage, duration_ms })
+        }
+        ServerMessage::StageFailed { stage, message } => {
+            Event::StageFailed(pb::StageFailed { stage, message })
+        }
+        ServerMessage::Error { message } => Event::Error(pb::Error { message }),
+        ServerMessage::LatencyReport {
+            pipeline_ms,
+            buffered_audio_ms,
+            received_at_ms,
+            sent_at_ms,
+        } => Event::LatencyReport(pb::LatencyReport {
+            pipeline_ms,
+            buffered_audio_ms,
+            received_at_ms,
+            sent_at_ms,
+        }),
+        ServerMessage::Pong => Event::Pong(pb::Pong {}),
+    };
+    pb::ServerEvent {
+        version: envelope.version,
+        track: envelope.track,
+        event: Some(event),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::PROTOCOL_VERSION;
+
+    #[test]
+    fn start_frame_defaults_to_every_event() {
+        let envelope = frame_from_proto(pb::ClientFrame {
+            version: PROTOCOL_VERSION,
+            track: Some("mic".to_string()),
+            message: Some(pb::client_frame::Message::Start(pb::Start {
+                session_id: Some("native".to_string()),
+                language_hint: Some("fr".to_string()),
+                emit_partials: Some(false),
+                ..Default::default()
+            })),
+        })
+        .expect("maps");
+
+        assert_eq!(envelope.track.as_deref(), Some("mic"));
+        let ClientMessage::Start {
+            session_id,
+            language_hint,
+            verbosity,
+        } = envelope.message
+        else {
+            panic!("expected start");
+        };
+        assert_eq!(session_id.as_deref(), Some("native"));
+        assert_eq!(language_hint, Some(LanguageTag::fr()));
+        assert_eq!(
+            verbosity,
+            Verbosity {
+                emit_partials: false,
+                ..Verbosity::default()
+            }
+        );
+    }
+
+    #[test]
+    fn empty_frames_are_rejected() {
+        let frame = pb::ClientFrame {
+            version: PROTOCOL_VERSION,
+            track: None,
+            message: None,
+        };
+        assert!(frame_from_proto(frame).is_err());
+    }
+
+    #[test]
+    fn events_keep_their_track() {
+        let event = event_to_proto(
+            ServerEnvelope::new(ServerMessage::StageCompleted {
+                stage: "asr".to_string(),
+                duration_ms: 42,
+            })
+            .with_track(Some("system".to_string())),
+        );
+        assert_eq!(event.version, PROTOCOL_VERSION);
+        assert_eq!(event.track.as_deref(), Some("system"));
+        assert_eq!(
+            event.event,
+            Some(pb::server_event::Event::StageCompleted(pb::StageCompleted {
+                stage: "asr".to_string(),
+                duration_ms: 42,
+            }))
+        );
+    }
+}
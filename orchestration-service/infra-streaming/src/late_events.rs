@@ -0,0 +1,117 @@
+//! Events stages publish after a flush was answered, e.g. the refined
+//! transcript of `two_pass_transcribe`, routed to the session that asked.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use orchestration_domain::{DomainEvent, LateEventSink};
+use tokio::sync::mpsc;
+use tracing::warn;
+
+/// Late deliveries a slow session may hold before newer ones are dropped.
+const SUBSCRIPTION_CAPACITY: usize = 16;
+
+/// Events of one late delivery and the session they were published for.
+pub type LateDelivery = (String, Vec<DomainEvent>);
+
+/// Routes [`LateEventSink`] deliveries to the streaming sessions subscribed
+/// to them. Clones share the same subscribers; deliveries for sessions
+/// nobody subscribed to are dropped.
+#[derive(Clone, Default)]
+pub struct LateEvents {
+    subscribers: Arc<Mutex<HashMap<String, mpsc::Sender<LateDelivery>>>>,
+}
+
+impl LateEvents {
+    /// Deliveries for `session_id` and for its tracks, `session_id:<track>`,
+    /// until the subscription is dropped.
+    pub fn subscribe(&self, session_id: &str) -> LateSubscription {
+        let (sender, receiver) = mpsc::channel(SUBSCRIPTION_CAPACITY);
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.insert(session_id.to_string(), sender.clone());
+        }
+        LateSubscription {
+            session_id: session_id.to_string(),
+            sender,
+            receiver,
+            events: self.clone(),
+        }
+    }
+
+    fn subscriber(&self, session_id: &str) -> Option<mpsc::Sender<LateDelivery>> {
+        let subscribers = self.subscribers.lock().ok()?;
+        // Track ids hold no `:`, so the last one ends the session's own id.
+        let root = session_id.rsplit_once(':').map_or(session_id, |(root, _)| root);
+        subscribers
+            .get(session_id)
+            .or_else(|| subscribers.get(root))
+            .cloned()
+    }
+}
+
+impl LateEventSink for LateEvents {
+    fn publish(&self, session_id: &str, events: Vec<DomainEvent>) {
+        let Some(sender) = self.subscriber(session_id) else {
+            return;
+        };
+        if let Err(mpsc::error::TrySendError::Full(_)) =
+            sender.try_send((session_id.to_string(), events))
+        {
+            warn!(session_id, "session is not reading late events; dropping them");
+        }
+    }
+}
+
+pub struct LateSubscription {
+    session_id: String,
+    /// Tells this subscription's entry apart from a newer one's.
+    sender: mpsc::Sender<LateDelivery>,
+    receiver: mpsc::Receiver<LateDelivery>,
+    events: LateEvents,
+}
+
+impl LateSubscription {
+    pub async fn recv(&mut self) -> Option<LateDelivery> {
+        self.receiver.recv().await
+    }
+}
+
+impl Drop for LateSubscription {
+    fn drop(&mut self) {
+        if let Ok(mut subscribers) = self.events.subscribers.lock() {
+            // A newer subscription to the same id keeps its own sender.
+            if subscribers
+                .get(&self.session_id)
+                .is_some_and(|sender| sender.same_channel(&self.sender))
+            {
+                subscribers.remove(&self.session_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stage_started() -> Vec<DomainEvent> {
+        vec![DomainEvent::StageStarted {
+            stage: "asr_transcribe".to_string(),
+        }]
+    }
+
+    #[tokio::test]
+    async fn deliveries_reach_the_session_and_its_tracks() {
+        let events = LateEvents::default();
+        let mut subscription = events.subscribe("call");
+
+        events.publish("call", stage_started());
+        events.publish("call:mic", stage_started());
+        events.publish("other", stage_started());
+
+        assert_eq!(subscription.recv().await.unwrap().0, "call");
+        assert_eq!(subscription.recv().await.unwrap().0, "call:mic");
+        drop(subscription);
+        assert!(events.subscriber("call").is_none());
+    }
+}
@@ -1,82 +1,217 @@
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
-use asr_application::AsrSessionUseCase;
-use asr_domain::{DomainError, DomainEvent, PipelineContext};
+use orchestration_application::{
+    parse_tenant_id, ApplicationError, PipelineEngine, TenantPolicy, UsageMeter, TENANT_ID_HEADER,
+};
+use orchestration_domain::DomainError;
 use axum::{
     extract::{
         ws::{Message, WebSocket, WebSocketUpgrade},
         State,
     },
-    response::Response,
+    http::HeaderMap,
+    response::{IntoResponse, Json, Response},
     routing::get,
     Router,
 };
-use futures::StreamExt;
+use futures::{stream::SplitStream, StreamExt};
+use http_problem::ProblemDetails;
+use log_context::{request_id_or_new, with_request_id, REQUEST_ID_HEADER};
 use tokio::net::TcpListener;
-use tracing::{error, info};
-use uuid::Uuid;
+use tracing::{error, info, Instrument};
 
+pub mod budget;
+pub mod grpc;
+pub mod late_events;
+pub mod outbound;
+mod pcm;
 pub mod protocol;
+pub mod rtp;
+pub mod session;
+#[cfg(feature = "webrtc")]
+pub mod webrtc;
 
-use protocol::{ClientEnvelope, ClientMessage, ServerEnvelope, ServerMessage, PROTOCOL_VERSION};
+pub use budget::{BudgetLease, BudgetSnapshot, StreamBudget};
+pub use grpc::{grpc_service, run_grpc_server, GrpcStreamingService};
+pub use late_events::{LateEvents, LateSubscription};
+pub use outbound::OutboundQueue;
+pub use rtp::{run_rtp_leg, serve_rtp_legs, CallEvent, RtpCodec, RtpLeg, RtpSettings};
+use protocol::{ClientEnvelope, ServerMessage};
+pub use session::{SessionDriver, StreamingRoute};
 
 #[derive(Clone)]
 pub struct StreamingState {
-    pub usecase: Arc<AsrSessionUseCase>,
+    /// Runs on a track's buffered audio at every flush, unless the session's
+    /// tenant has a route of its own.
+    pub pipeline: Arc<PipelineEngine>,
     pub max_message_bytes: usize,
+    /// One `audio_frame` in this many is logged per session; 0 logs none.
+    pub frame_log_every: u64,
+    /// Audio all sessions may buffer together.
+    pub budget: Arc<StreamBudget>,
+    /// Messages a session queues for a slow client, see [`OutboundQueue`].
+    pub outbound_queue_len: usize,
+    /// How long a client may leave the queue full before it is disconnected.
+    pub stall_timeout: Duration,
+    /// Events the pipeline's stages publish after a flush was answered,
+    /// forwarded to the session they are for.
+    pub late_events: LateEvents,
+    /// Admits sessions per tenant, against the quota HTTP requests use.
+    pub tenants: TenantPolicy,
+    /// Pipelines and models of the tenants configured with their own, by
+    /// tenant id.
+    pub tenant_routes: Arc<HashMap<String, StreamingRoute>>,
+    /// Where every flush is accounted to the session's tenant and model.
+    pub usage: UsageMeter,
 }
 
 pub fn build_router(state: StreamingState) -> Router {
-    Router::new().route("/ws", get(ws_handler)).with_state(state)
+    let router = Router::new()
+        .route("/ws", get(ws_handler))
+        .route("/ws/stats", get(stats_handler));
+    #[cfg(feature = "webrtc")]
+    let router = router.route("/webrtc/offer", axum::routing::post(webrtc::offer_handler));
+    router.with_state(state)
 }
 
 pub async fn run_server(router: Router, bind_addr: &str) -> Result<(), DomainError> {
     let listener = TcpListener::bind(bind_addr)
         .await
-        .map_err(|err| DomainError::Streaming(format!("bind failed: {err}")))?;
+        .map_err(|err| DomainError::internal_error(&format!("bind failed: {err}")))?;
     info!("websocket server listening on {}", bind_addr);
     axum::serve(listener, router)
         .await
-        .map_err(|err| DomainError::Streaming(format!("server error: {err}")))
+        .map_err(|err| DomainError::internal_error(&format!("server error: {err}")))
 }
 
 async fn ws_handler(
     ws: WebSocketUpgrade,
+    headers: HeaderMap,
     State(state): State<StreamingState>,
 ) -> Response {
-    ws.max_message_size(state.max_message_bytes)
-        .on_upgrade(move |socket| handle_socket(socket, state))
+    let request_id = request_id_or_new(
+        headers
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok()),
+    );
+    let tenant = headers
+        .get(TENANT_ID_HEADER)
+        .map(|value| value.to_str().unwrap_or_default());
+    // Admitted before the upgrade, so a refused client gets a status code.
+    let out = OutboundQueue::new(state.outbound_queue_len, state.stall_timeout);
+    let max_message_bytes = state.max_message_bytes;
+    let driver = match admitted_driver(state, out.clone(), tenant) {
+        Ok(driver) => driver,
+        Err(err) => return refusal(&err),
+    };
+    ws.max_message_size(max_message_bytes)
+        .on_upgrade(move |socket| {
+            // `session_id` is recorded once the client sends `start`.
+            let span = tracing::info_span!(
+                "ws_session",
+                request_id = %request_id,
+                session_id = tracing::field::Empty,
+            );
+            with_request_id(request_id, handle_socket(socket, driver, out).instrument(span))
+        })
+}
+
+/// Session for the tenant named by a connection's `x-tenant-id` header or
+/// metadata value, holding one of the tenant's concurrent slots. A value
+/// that is not ASCII is passed as empty, which is refused.
+pub(crate) fn admitted_driver(
+    state: StreamingState,
+    out: Arc<OutboundQueue>,
+    tenant: Option<&str>,
+) -> Result<SessionDriver, ApplicationError> {
+    let tenant_id = tenant
+        .map(parse_tenant_id)
+        .transpose()
+        .map_err(ApplicationError::Validation)?;
+    let mut driver = SessionDriver::new(state, out).with_tenant(tenant_id);
+    driver.admit()?;
+    Ok(driver)
 }
 
-async fn handle_socket(mut socket: WebSocket, state: StreamingState) {
-    let mut context: Option<PipelineContext> = None;
-    while let Some(msg_result) = socket.next().await {
+/// Refuses a connection with the problem response the HTTP API sends for
+/// the same error: 422 `invalid_input`, 429 `resource_exhausted`.
+pub(crate) fn refusal(err: &ApplicationError) -> Response {
+    ProblemDetails::from_code(err.error_code(), Some(err.to_string())).into_response()
+}
+
+/// Gauge of the audio buffered by the open sessions.
+async fn stats_handler(State(state): State<StreamingState>) -> Json<BudgetSnapshot> {
+    Json(state.budget.snapshot())
+}
+
+async fn handle_socket(socket: WebSocket, mut driver: SessionDriver, out: Arc<OutboundQueue>) {
+    let (sink, mut stream) = socket.split();
+    let writer = tokio::spawn(outbound::write_outbound(sink, out.clone()).in_current_span());
+    read_socket(&mut stream, &mut driver).await;
+    out.close();
+    let _ = writer.await;
+    if out.dropped_partials() > 0 {
+        info!(dropped_partials = out.dropped_partials(), "slow client skipped partials");
+    }
+}
+
+async fn read_socket(stream: &mut SplitStream<WebSocket>, driver: &mut SessionDriver) {
+    loop {
+        let msg_result = tokio::select! {
+            msg = stream.next() => match msg {
+                Some(msg) => msg,
+                None => return,
+            },
+            () = driver.flush_requested() => {
+                if let Err(err) = driver.evict().await {
+                    error!("session error: {}", err);
+                    return;
+                }
+                continue;
+            }
+        };
         match msg_result {
             Ok(Message::Text(raw)) => {
-                if let Err(err) = process_text_message(&mut socket, &state, &mut context, raw.as_str()).await {
+                let result = match serde_json::from_str::<ClientEnvelope>(raw.as_str()) {
+                    Ok(envelope) => driver.handle(envelope).await,
+                    Err(err) => {
+                        Err(DomainError::invalid_input(&format!("invalid message: {err}")))
+                    }
+                };
+                if let Err(err) = result {
                     error!("session error: {}", err);
-                    let _ = send_message(
-                        &mut socket,
+                    let _ = driver
+                        .send(
+                            None,
+                            ServerMessage::Error {
+                                message: err.to_string(),
+                            },
+                        )
+                        .await;
+                    return;
+                }
+            }
+            Ok(Message::Binary(_)) => {
+                let sent = driver
+                    .send(
+                        None,
                         ServerMessage::Error {
-                            message: err.to_string(),
+                            message: "binary frames are not supported; use JSON audio_frame"
+                                .to_string(),
                         },
                     )
                     .await;
+                if sent.is_err() {
                     return;
                 }
             }
-            Ok(Message::Binary(_)) => {
-                let _ = send_message(
-                    &mut socket,
-                    ServerMessage::Error {
-                        message: "binary frames are not supported; use JSON audio_frame".to_string(),
-                    },
-                )
-                .await;
-            }
             Ok(Message::Close(_)) => return,
             Ok(Message::Ping(_)) => {
-                let _ = send_message(&mut socket, ServerMessage::Pong).await;
+                if driver.send(None, ServerMessage::Pong).await.is_err() {
+                    return;
+                }
             }
             Ok(_) => {}
             Err(err) => {
@@ -86,72 +221,3 @@ async fn handle_socket(mut socket: WebSocket, state: StreamingState) {
         }
     }
 }
-
-async fn process_text_message(
-    socket: &mut WebSocket,
-    state: &StreamingState,
-    context: &mut Option<PipelineContext>,
-    raw: &str,
-) -> Result<(), DomainError> {
-    let envelope: ClientEnvelope = serde_json::from_str(raw)
-        .map_err(|err| DomainError::Streaming(format!("invalid message: {err}")))?;
-    if envelope.version != PROTOCOL_VERSION {
-        return Err(DomainError::Streaming(format!(
-            "unsupported protocol version {}, expected {}",
-            envelope.version, PROTOCOL_VERSION
-        )));
-    }
-
-    match envelope.message {
-        ClientMessage::Start {
-            session_id,
-            language_hint,
-        } => {
-            let sid = session_id.unwrap_or_else(|| Uuid::new_v4().to_string());
-            *context = Some(PipelineContext::new(sid.clone(), language_hint));
-            send_message(socket, ServerMessage::Ready { session_id: sid }).await?;
-        }
-        ClientMessage::AudioFrame { pcm_f32 } => {
-            let ctx = context
-                .as_mut()
-                .ok_or_else(|| DomainError::Streaming("start must be sent first".to_string()))?;
-            ctx.audio.samples.extend(pcm_f32);
-        }
-        ClientMessage::Flush | ClientMessage::Stop => {
-            let ctx = context
-                .as_mut()
-                .ok_or_else(|| DomainError::Streaming("start must be sent first".to_string()))?;
-            state.usecase.process_existing_context(ctx).await?;
-            let events = std::mem::take(&mut ctx.events);
-            for event in events {
-                send_message(socket, ServerMessage::from(event)).await?;
-            }
-        }
-        ClientMessage::Ping => {
-            send_message(socket, ServerMessage::Pong).await?;
-        }
-    }
-    Ok(())
-}
-
-async fn send_message(socket: &mut WebSocket, message: ServerMessage) -> Result<(), DomainError> {
-    let payload = serde_json::to_string(&ServerEnvelope::new(message))
-        .map_err(|err| DomainError::Streaming(format!("serialization error: {err}")))?;
-    socket
-        .send(Message::Text(payload.into()))
-        .await
-        .map_err(|err| DomainError::Streaming(format!("send error: {err}")))
-}
-
-pub struct JsonStreamingProtocol;
-
-impl asr_domain::StreamingProtocolPort for JsonStreamingProtocol {
-    fn version(&self) -> u32 {
-        PROTOCOL_VERSION
-    }
-
-    fn to_wire(&self, event: &DomainEvent) -> Result<String, DomainError> {
-        serde_json::to_string(&ServerEnvelope::new(ServerMessage::from(event.clone())))
-            .map_err(|err| DomainError::Streaming(format!("serialization error: {err}")))
-    }
-}
@@ -0,0 +1,217 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use orchestration_domain::DomainError;
+use axum::extract::ws::{Message, WebSocket};
+use futures::{stream::SplitSink, SinkExt};
+use tokio::sync::Notify;
+use tracing::{error, warn};
+
+use crate::protocol::{ServerEnvelope, ServerMessage};
+
+/// Messages waiting for a session's socket, so a slow reader never blocks
+/// the pipeline. Holds at most `capacity` messages: when full, the oldest
+/// partial transcript makes room (a newer one supersedes it); finals and
+/// every other message wait for room. A client that takes longer than
+/// `stall_timeout` to accept a message, or to make room, is disconnected.
+pub struct OutboundQueue {
+    capacity: usize,
+    stall_timeout: Duration,
+    messages: Mutex<VecDeque<ServerEnvelope>>,
+    /// Signalled on push and close, for the writer.
+    queued: Notify,
+    /// Signalled on pop, for a producer waiting for room.
+    room: Notify,
+    closed: AtomicBool,
+    dropped_partials: AtomicU64,
+}
+
+impl OutboundQueue {
+    pub fn new(capacity: usize, stall_timeout: Duration) -> Arc<Self> {
+        Arc::new(Self {
+            capacity: capacity.max(1),
+            stall_timeout,
+            messages: Mutex::new(VecDeque::new()),
+            queued: Notify::new(),
+            room: Notify::new(),
+            closed: AtomicBool::new(false),
+            dropped_partials: AtomicU64::new(0),
+        })
+    }
+
+    pub async fn push(&self, message: ServerEnvelope) -> Result<(), DomainError> {
+        loop {
+            if self.closed.load(Ordering::Acquire) {
+                return Err(DomainError::internal_error("session closed"));
+            }
+            {
+                let mut messages = self.lock();
+                if messages.len() < self.capacity {
+                    messages.push_back(message);
+                    self.queued.notify_one();
+                    return Ok(());
+                }
+                let oldest_partial = messages.iter().position(is_partial);
+                if let Some(index) = oldest_partial {
+                    messages.remove(index);
+                    messages.push_back(message);
+                    self.dropped_partials.fetch_add(1, Ordering::Relaxed);
+                    self.queued.notify_one();
+                    return Ok(());
+                }
+                if is_partial(&message) {
+                    self.dropped_partials.fetch_add(1, Ordering::Relaxed);
+                    return Ok(());
+                }
+            }
+            if tokio::time::timeout(self.stall_timeout, self.room.notified())
+                .await
+                .is_err()
+            {
+                self.close();
+                return Err(DomainError::internal_error(&format!(
+                    "client stalled: outbound queue full for {} ms",
+                    self.stall_timeout.as_millis()
+                )));
+            }
+        }
+    }
+
+    /// Next message to send; `None` once closed and drained.
+    pub async fn pop(&self) -> Option<ServerEnvelope> {
+        loop {
+            if let Some(message) = self.lock().pop_front() {
+                self.room.notify_one();
+                return Some(message);
+            }
+            if self.closed.load(Ordering::Acquire) {
+                return None;
+            }
+            self.queued.notified().await;
+        }
+    }
+
+    /// Refuses further messages; those queued are still delivered.
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.queued.notify_one();
+        self.room.notify_one();
+    }
+
+    /// Partial transcripts superseded before the client read them.
+    pub fn dropped_partials(&self) -> u64 {
+        self.dropped_partials.load(Ordering::Relaxed)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<ServerEnvelope>> {
+        self.messages.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+fn is_partial(envelope: &ServerEnvelope) -> bool {
+    matches!(envelope.message, ServerMessage::PartialTranscript { .. })
+}
+
+/// Sends the queued messages until the queue is closed and drained, or the
+/// client stops accepting them; closes the queue and the socket either way.
+pub(crate) async fn write_outbound(
+    mut sink: SplitSink<WebSocket, Message>,
+    queue: Arc<OutboundQueue>,
+) {
+    while let Some(message) = queue.pop().await {
+        let payload = match serde_json::to_string(&message) {
+            Ok(payload) => payload,
+            Err(err) => {
+                error!("serialization error: {}", err);
+                continue;
+            }
+        };
+        match tokio::time::timeout(queue.stall_timeout, sink.send(Message::Text(payload.into())))
+            .await
+        {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => {
+                error!("send error: {}", err);
+                break;
+            }
+            Err(_) => {
+                warn!(
+                    stall_timeout_ms = queue.stall_timeout.as_millis() as u64,
+                    "client stalled, disconnecting"
+                );
+                break;
+            }
+        }
+    }
+    queue.close();
+    let _ = sink.close().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use orchestration_domain::{LanguageTag, Transcript};
+
+    use super::*;
+
+    fn partial() -> ServerEnvelope {
+        ServerEnvelope::new(ServerMessage::PartialTranscript {
+            transcript: Transcript {
+                language: LanguageTag::en(),
+                segments: Vec::new(),
+            },
+            stable_until_ms: None,
+        })
+    }
+
+    fn final_transcript() -> ServerEnvelope {
+        ServerEnvelope::new(ServerMessage::FinalTranscript {
+            transcript: Transcript {
+                language: LanguageTag::en(),
+                segments: Vec::new(),
+            },
+        })
+    }
+
+    fn message(envelope: Option<ServerEnvelope>) -> Option<ServerMessage> {
+        envelope.map(|envelope| envelope.message)
+    }
+
+    #[tokio::test]
+    async fn full_queue_drops_the_oldest_partial() {
+        let queue = OutboundQueue::new(2, Duration::from_millis(50));
+        queue.push(partial()).await.unwrap();
+        queue.push(final_transcript()).await.unwrap();
+        queue.push(ServerEnvelope::new(ServerMessage::Pong)).await.unwrap();
+
+        assert_eq!(queue.dropped_partials(), 1);
+        assert!(matches!(message(queue.pop().await), Some(ServerMessage::FinalTranscript { .. })));
+        assert!(matches!(message(queue.pop().await), Some(ServerMessage::Pong)));
+    }
+
+    #[tokio::test]
+    async fn finals_are_never_dropped() {
+        let queue = OutboundQueue::new(1, Duration::from_millis(20));
+        queue.push(final_transcript()).await.unwrap();
+        queue.push(partial()).await.unwrap();
+        assert_eq!(queue.dropped_partials(), 1);
+
+        let stalled = queue.push(final_transcript()).await;
+        assert!(stalled.is_err(), "a full queue of finals stalls the client");
+        assert!(matches!(message(queue.pop().await), Some(ServerMessage::FinalTranscript { .. })));
+        assert!(queue.pop().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn a_pop_makes_room_for_a_waiting_push() {
+        let queue = OutboundQueue::new(1, Duration::from_secs(1));
+        queue.push(final_transcript()).await.unwrap();
+        let consumer = {
+            let queue = queue.clone();
+            tokio::spawn(async move { queue.pop().await })
+        };
+        queue.push(final_transcript()).await.unwrap();
+        assert!(consumer.await.unwrap().is_some());
+    }
+}
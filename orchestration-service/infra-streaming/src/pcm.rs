@@ -0,0 +1,166 @@
+//! Decoding of the codecs media transports carry into the 16 kHz mono
+//! `f32` samples sessions take.
+
+use crate::protocol::STREAM_SAMPLE_RATE_HZ;
+
+const G711_RATE_HZ: u32 = 8_000;
+const OPUS_RATE_HZ: u32 = 48_000;
+
+/// Narrowband G.711 packets to stream samples, interpolating every other
+/// sample. Keeps the last sample so packets join without a seam.
+#[derive(Debug, Default)]
+pub(crate) struct G711Decoder {
+    alaw: bool,
+    previous: f32,
+}
+
+impl G711Decoder {
+    pub(crate) fn ulaw() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn alaw() -> Self {
+        Self {
+            alaw: true,
+            previous: 0.0,
+        }
+    }
+
+    pub(crate) fn decode(&mut self, payload: &[u8]) -> Vec<f32> {
+        const UPSAMPLING: usize = (STREAM_SAMPLE_RATE_HZ / G711_RATE_HZ) as usize;
+        let mut samples = Vec::with_capacity(payload.len() * UPSAMPLING);
+        for &byte in payload {
+            let linear = if self.alaw {
+                alaw_to_linear(byte)
+            } else {
+                ulaw_to_linear(byte)
+            };
+            let sample = f32::from(linear) / 32_768.0;
+            samples.push((self.previous + sample) / 2.0);
+            samples.push(sample);
+            self.previous = sample;
+        }
+        samples
+    }
+}
+
+fn ulaw_to_linear(byte: u8) -> i16 {
+    let byte = !byte;
+    let exponent = (byte >> 4) & 0x07;
+    let mantissa = i16::from(byte & 0x0F);
+    let magnitude = (((mantissa << 3) + 0x84) << exponent) - 0x84;
+    if byte & 0x80 != 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+fn alaw_to_linear(byte: u8) -> i16 {
+    let byte = byte ^ 0x55;
+    let exponent = (byte >> 4) & 0x07;
+    let mantissa = i16::from(byte & 0x0F);
+    let magnitude = if exponent == 0 {
+        (mantissa << 4) + 8
+    } else {
+        ((mantissa << 4) + 0x108) << (exponent - 1)
+    };
+    if byte & 0x80 != 0 {
+        magnitude
+    } else {
+        -magnitude
+    }
+}
+
+/// Opus packets to stream samples.
+#[cfg(feature = "opus")]
+pub(crate) struct OpusDecoder {
+    decoder: audiopus::coder::Decoder,
+    channels: usize,
+    decoded: Vec<f32>,
+}
+
+#[cfg(feature = "opus")]
+impl OpusDecoder {
+    /// Longest Opus frame (120 ms) at 48 kHz.
+    const MAX_FRAME_SAMPLES: usize = 5_760;
+
+    pub(crate) fn new(stereo: bool) -> Result<Self, audiopus::Error> {
+        let channels = if stereo {
+            audiopus::Channels::Stereo
+        } else {
+            audiopus::Channels::Mono
+        };
+        let decoder = audiopus::coder::Decoder::new(audiopus::SampleRate::Hz48000, channels)?;
+        let channels = channels as usize;
+        Ok(Self {
+            decoder,
+            channels,
+            decoded: vec![0.0; Self::MAX_FRAME_SAMPLES * channels],
+        })
+    }
+
+    pub(crate) fn decode(&mut self, payload: &[u8]) -> Result<Vec<f32>, audiopus::Error> {
+        let samples = self
+            .decoder
+            .decode_float(Some(payload), &mut self.decoded[..], false)?;
+        let mono = downmix(&self.decoded[..samples * self.channels], self.channels);
+        Ok(decimate_48k(&mono))
+    }
+}
+
+pub(crate) fn downmix(samples: &[f32], channels: usize) -> Vec<f32> {
+    if channels <= 1 {
+        return samples.to_vec();
+    }
+    samples
+        .chunks_exact(channels)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect()
+}
+
+/// 48 kHz to 16 kHz by averaging each group of three samples, which also
+/// filters out most of what would alias.
+pub(crate) fn decimate_48k(samples: &[f32]) -> Vec<f32> {
+    const DECIMATION: usize = (OPUS_RATE_HZ / STREAM_SAMPLE_RATE_HZ) as usize;
+    samples
+        .chunks(DECIMATION)
+        .map(|group| group.iter().sum::<f32>() / group.len() as f32)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wideband_output_is_downmixed_and_decimated() {
+        let stereo = [0.2, 0.4, 0.2, 0.4, 0.2, 0.4];
+        let mono = downmix(&stereo, 2);
+        assert_eq!(mono.len(), 3);
+        assert!(mono.iter().all(|sample| (sample - 0.3).abs() < 1e-6));
+
+        let at_48k: Vec<f32> = (0..960).map(|_| 0.5).collect();
+        let at_16k = decimate_48k(&at_48k);
+        assert_eq!(at_16k.len(), 320);
+        assert!(at_16k.iter().all(|sample| (sample - 0.5).abs() < 1e-6));
+    }
+
+    #[test]
+    fn g711_silence_and_extremes() {
+        assert_eq!(ulaw_to_linear(0xFF), 0);
+        assert_eq!(ulaw_to_linear(0x80), 32_124);
+        assert_eq!(ulaw_to_linear(0x00), -32_124);
+        assert_eq!(alaw_to_linear(0xD5), 8);
+        assert_eq!(alaw_to_linear(0xAA), 32_256);
+        assert_eq!(alaw_to_linear(0x2A), -32_256);
+    }
+
+    #[test]
+    fn g711_packets_double_in_length() {
+        let mut decoder = G711Decoder::ulaw();
+        let samples = decoder.decode(&[0xFF; 160]);
+        assert_eq!(samples.len(), 320);
+        assert!(samples.iter().all(|sample| *sample == 0.0));
+    }
+}
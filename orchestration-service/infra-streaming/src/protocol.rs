@@ -1,99 +1,53 @@
-use asr_domain::{DomainEvent, LanguageTag, Transcript, WordTiming};
-use serde::{Deserialize, Serialize};
-
-pub const PROTOCOL_VERSION: u32 = 1;
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ClientEnvelope {
-    pub version: u32,
-    #[serde(flatten)]
-    pub message: ClientMessage,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type", content = "payload", rename_all = "snake_case")]
-pub enum ClientMessage {
-    Start {
-        session_id: Option<String>,
-        language_hint: Option<LanguageTag>,
-    },
-    AudioFrame {
-        pcm_f32: Vec<f32>,
-    },
-    Flush,
-    Stop,
-    Ping,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ServerEnvelope {
-    pub version: u32,
-    #[serde(flatten)]
-    pub message: ServerMessage,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type", content = "payload", rename_all = "snake_case")]
-pub enum ServerMessage {
-    Ready {
-        session_id: String,
-    },
-    PartialTranscript {
-        transcript: Transcript,
-    },
-    FinalTranscript {
-        transcript: Transcript,
-    },
-    AlignmentUpdate {
-        words: Vec<WordTiming>,
-    },
-    Error {
-        message: String,
-    },
-    Pong,
-}
-
-impl From<DomainEvent> for ServerMessage {
-    fn from(value: DomainEvent) -> Self {
-        match value {
-            DomainEvent::Ready { session_id } => ServerMessage::Ready { session_id },
-            DomainEvent::PartialTranscript { transcript } => {
-                ServerMessage::PartialTranscript { transcript }
-            }
-            DomainEvent::FinalTranscript { transcript } => ServerMessage::FinalTranscript { transcript },
-            DomainEvent::AlignmentUpdate { words } => ServerMessage::AlignmentUpdate { words },
-            DomainEvent::Error { message } => ServerMessage::Error { message },
+//! Messages of the WebSocket streaming protocol. They are declared once in
+//! `vocal-ws-protocol`, which `vocal-agent-client` uses too, and published
+//! as `vocal-agent-client/schema/ws-protocol.json`.
+
+use orchestration_domain::DomainEvent;
+
+pub use vocal_ws_protocol::{
+    ClientEnvelope, ClientMessage, ServerEnvelope, ServerMessage, Verbosity, PROTOCOL_VERSION,
+    STREAM_SAMPLE_RATE_HZ,
+};
+
+/// What a session sends its client for a pipeline event.
+pub fn event_message(event: DomainEvent) -> ServerMessage {
+    match event {
+        DomainEvent::PartialTranscript {
+            transcript,
+            stable_until_ms,
+        } => ServerMessage::PartialTranscript {
+            transcript,
+            stable_until_ms,
+        },
+        DomainEvent::FinalTranscript { transcript } => {
+            ServerMessage::FinalTranscript { transcript }
         }
-    }
-}
-
-impl ServerEnvelope {
-    pub fn new(message: ServerMessage) -> Self {
-        Self {
-            version: PROTOCOL_VERSION,
-            message,
+        DomainEvent::AlignmentUpdate { words } => ServerMessage::AlignmentUpdate { words },
+        DomainEvent::StageStarted { stage } => ServerMessage::StageStarted { stage },
+        DomainEvent::StageCompleted { stage, duration_ms } => {
+            ServerMessage::StageCompleted { stage, duration_ms }
+        }
+        DomainEvent::StageFailed { stage, message } => {
+            ServerMessage::StageFailed { stage, message }
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{ClientEnvelope, ClientMessage, PROTOCOL_VERSION, ServerEnvelope, ServerMessage};
+    use orchestration_domain::DomainEvent;
 
-    #[test]
-    fn protocol_round_trip() {
-        let raw = serde_json::to_string(&ClientEnvelope {
-            version: PROTOCOL_VERSION,
-            message: ClientMessage::Ping,
-        })
-        .expect("serializes");
-        let decoded: ClientEnvelope = serde_json::from_str(&raw).expect("deserializes");
-        assert_eq!(decoded.version, PROTOCOL_VERSION);
-    }
+    use super::{event_message, ServerMessage};
 
     #[test]
-    fn outbound_has_version() {
-        let env = ServerEnvelope::new(ServerMessage::Pong);
-        assert_eq!(env.version, PROTOCOL_VERSION);
+    fn stage_events_keep_their_fields() {
+        let message = event_message(DomainEvent::StageCompleted {
+            stage: "asr".to_string(),
+            duration_ms: 42,
+        });
+        assert!(matches!(
+            message,
+            ServerMessage::StageCompleted { ref stage, duration_ms: 42 } if stage == "asr"
+        ));
     }
 }
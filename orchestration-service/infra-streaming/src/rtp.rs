@@ -0,0 +1,425 @@
+//! Telephony ingestion: each call leg is an RTP stream (G.711, or Opus with
+//! the `opus` feature) received on its own UDP port and transcribed as one
+//! streaming session. SIP signalling stays with the PBX or SBC: it hands the
+//! leg's SDP offer to [`RtpLeg::from_offer`] and returns [`RtpLeg::answer`],
+//! or legs are set up statically with [`RtpLeg::new`].
+//!
+//! RTP carries no `flush`, so the session is flushed every `flush_interval`
+//! and once more when the leg goes quiet for `idle_timeout` (end of call).
+//! Static legs are reopened for the next call by [`serve_rtp_legs`].
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use orchestration_application::ApplicationError;
+use orchestration_domain::{DomainError, LanguageTag};
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc;
+use tokio::time::{Instant, MissedTickBehavior};
+use tracing::{error, info, warn, Instrument};
+
+use crate::pcm::G711Decoder;
+use crate::protocol::{
+    ClientEnvelope, ClientMessage, ServerEnvelope, ServerMessage, Verbosity, PROTOCOL_VERSION,
+};
+use crate::{OutboundQueue, SessionDriver, StreamingState};
+
+const MAX_DATAGRAM_BYTES: usize = 1_500;
+const RTP_VERSION: u8 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RtpCodec {
+    /// G.711 µ-law, static payload type 0.
+    Pcmu,
+    /// G.711 A-law, static payload type 8.
+    Pcma,
+    /// 48 kHz Opus, dynamic payload type.
+    Opus { stereo: bool },
+}
+
+impl RtpCodec {
+    fn rtpmap(self) -> &'static str {
+        match self {
+            Self::Pcmu => "PCMU/8000",
+            Self::Pcma => "PCMA/8000",
+            Self::Opus { .. } => "opus/48000/2",
+        }
+    }
+}
+
+/// One call leg: where its RTP arrives and how it is encoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RtpLeg {
+    /// Session id of the leg, e.g. the SIP Call-ID and a leg suffix.
+    pub call_id: String,
+    pub local_addr: SocketAddr,
+    pub payload_type: u8,
+    pub codec: RtpCodec,
+    pub language_hint: Option<LanguageTag>,
+    /// Tenant the leg's calls run for.
+    pub tenant_id: Option<String>,
+}
+
+impl RtpLeg {
+    pub fn new(call_id: impl Into<String>, local_addr: SocketAddr, codec: RtpCodec) -> Self {
+        let payload_type = match codec {
+            RtpCodec::Pcmu => 0,
+            RtpCodec::Pcma => 8,
+            RtpCodec::Opus { .. } => 111,
+        };
+        Self {
+            call_id: call_id.into(),
+            local_addr,
+            payload_type,
+            codec,
+            language_hint: None,
+            tenant_id: None,
+        }
+    }
+
+    pub fn with_language_hint(mut self, language_hint: Option<LanguageTag>) -> Self {
+        self.language_hint = language_hint;
+        self
+    }
+
+    pub fn with_tenant_id(mut self, tenant_id: Option<String>) -> Self {
+        self.tenant_id = tenant_id;
+        self
+    }
+
+    /// Leg for the first audio format of `offer` this module decodes, in
+    /// the offer's order of preference.
+    pub fn from_offer(
+        call_id: impl Into<String>,
+        offer: &str,
+        local_addr: SocketAddr,
+    ) -> Result<Self, DomainError> {
+        let media = offer
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("m=audio "))
+            .ok_or_else(|| DomainError::invalid_input("offer has no audio media"))?;
+        let formats = media.split_whitespace().skip(2);
+        let rtpmap = |payload_type: &str| {
+            let prefix = format!("a=rtpmap:{payload_type} ");
+            offer
+                .lines()
+                .find_map(|line| line.trim().strip_prefix(prefix.as_str()).map(str::to_string))
+        };
+
+        for format in formats {
+            let Ok(payload_type) = format.parse::<u8>() else {
+                continue;
+            };
+            let encoding = rtpmap(format).unwrap_or_default().to_ascii_lowercase();
+            let codec = match (payload_type, encoding.as_str()) {
+                (0, _) => Some(RtpCodec::Pcmu),
+                (8, _) => Some(RtpCodec::Pcma),
+                #[cfg(feature = "opus")]
+                (_, encoding) if encoding.starts_with("opus/48000") => Some(RtpCodec::Opus {
+                    stereo: encoding.ends_with("/2"),
+                }),
+                _ => None,
+            };
+            if let Some(codec) = codec {
+                return Ok(Self {
+                    payload_type,
+                    ..Self::new(call_id, local_addr, codec)
+                });
+            }
+        }
+        Err(DomainError::invalid_input(
+            "offer has no supported audio format (PCMU, PCMA or opus)",
+        ))
+    }
+
+    /// Receive-only SDP answer naming this leg's address and format.
+    pub fn answer(&self) -> String {
+        let ip = self.local_addr.ip();
+        let family = if ip.is_ipv4() { "IP4" } else { "IP6" };
+        format!(
+            "v=0\r\n\
+             o=- 0 0 IN {family} {ip}\r\n\
+             s=vocal-agent\r\n\
+             c=IN {family} {ip}\r\n\
+             t=0 0\r\n\
+             m=audio {port} RTP/AVP {pt}\r\n\
+             a=rtpmap:{pt} {rtpmap}\r\n\
+             a=recvonly\r\n",
+            port = self.local_addr.port(),
+            pt = self.payload_type,
+            rtpmap = self.codec.rtpmap(),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RtpSettings {
+    pub flush_interval: Duration,
+    pub idle_timeout: Duration,
+}
+
+/// A session event of a call leg.
+#[derive(Debug, Clone)]
+pub struct CallEvent {
+    pub call_id: String,
+    pub envelope: ServerEnvelope,
+}
+
+/// Transcribes `leg` until it goes quiet after its first packet, sending its
+/// events to `events`. While the leg's tenant is over its quota the leg is
+/// not opened: this returns after `settings.idle_timeout`, for the caller
+/// to try again.
+pub async fn run_rtp_leg(
+    state: StreamingState,
+    leg: RtpLeg,
+    settings: RtpSettings,
+    events: mpsc::Sender<CallEvent>,
+) -> Result<(), DomainError> {
+    let out = OutboundQueue::new(state.outbound_queue_len, state.stall_timeout);
+    let mut driver = SessionDriver::new(state, out.clone()).with_tenant(leg.tenant_id.clone());
+    match driver.admit() {
+        Ok(()) => {}
+        Err(err @ ApplicationError::QuotaExceeded(_)) => {
+            warn!(call_id = %leg.call_id, "rtp leg not opened: {}", err);
+            tokio::time::sleep(settings.idle_timeout).await;
+            return Ok(());
+        }
+        Err(err) => return Err(DomainError::invalid_input(&err.to_string())),
+    }
+    let socket = UdpSocket::bind(leg.local_addr)
+        .await
+        .map_err(|err| DomainError::internal_error(&format!("bind failed: {err}")))?;
+    let span = tracing::info_span!(
+        "rtp_leg",
+        call_id = %leg.call_id,
+        session_id = tracing::field::Empty,
+    );
+    info!(parent: &span, local_addr = %leg.local_addr, codec = ?leg.codec, "rtp leg open");
+
+    let forwarder = tokio::spawn(forward_events(leg.call_id.clone(), out.clone(), events));
+    let result = receive(socket, &leg, settings, driver)
+        .instrument(span)
+        .await;
+    out.close();
+    let _ = forwarder.await;
+    result
+}
+
+async fn receive(
+    socket: UdpSocket,
+    leg: &RtpLeg,
+    settings: RtpSettings,
+    mut driver: SessionDriver,
+) -> Result<(), DomainError> {
+    let mut decoder = LegDecoder::new(leg.codec)?;
+    driver
+        .handle(envelope(ClientMessage::Start {
+            session_id: Some(leg.call_id.clone()),
+            language_hint: leg.language_hint.clone(),
+            verbosity: Verbosity::default(),
+        }))
+        .await?;
+
+    let mut flush = tokio::time::interval_at(
+        Instant::now() + settings.flush_interval,
+        settings.flush_interval,
+    );
+    flush.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    // A leg waits for its call; only silence after audio ends it.
+    let mut heard = false;
+    let mut last_packet = Instant::now();
+    let mut datagram = vec![0u8; MAX_DATAGRAM_BYTES];
+    loop {
+        tokio::select! {
+            received = socket.recv(&mut datagram) => {
+                let len = received
+                    .map_err(|err| {
+                        DomainError::internal_error(&format!("receive failed: {err}"))
+                    })?;
+                let Some((payload_type, payload)) = rtp_payload(&datagram[..len]) else {
+                    continue;
+                };
+                // Other payload types are e.g. DTMF or comfort noise.
+                if payload_type != leg.payload_type || payload.is_empty() {
+                    continue;
+                }
+                heard = true;
+                last_packet = Instant::now();
+                match decoder.decode(payload) {
+                    Ok(pcm_f32) => {
+                        driver.handle(envelope(ClientMessage::AudioFrame { pcm_f32 })).await?;
+                    }
+                    Err(err) => warn!("dropping undecodable rtp packet: {}", err),
+                }
+            }
+            _ = flush.tick() => driver.handle(envelope(ClientMessage::Flush)).await?,
+            () = driver.flush_requested() => driver.evict().await?,
+            () = tokio::time::sleep_until(last_packet + settings.idle_timeout), if heard => {
+                info!("rtp leg idle, closing");
+                return driver.handle(envelope(ClientMessage::Stop)).await;
+            }
+        }
+    }
+}
+
+/// Transcribes static `legs` call after call, logging their transcripts.
+/// Returns when a leg cannot be opened.
+pub async fn serve_rtp_legs(
+    state: StreamingState,
+    legs: Vec<RtpLeg>,
+    settings: RtpSettings,
+) -> Result<(), DomainError> {
+    let (events, received) = mpsc::channel(state.outbound_queue_len.max(1));
+    let logger = tokio::spawn(log_call_events(received));
+    let mut calls = tokio::task::JoinSet::new();
+    for leg in legs {
+        let (state, events) = (state.clone(), events.clone());
+        calls.spawn(async move {
+            loop {
+                run_rtp_leg(state.clone(), leg.clone(), settings, events.clone()).await?;
+            }
+        });
+    }
+    drop(events);
+    let result = match calls.join_next().await {
+        Some(Ok(result)) => result,
+        Some(Err(err)) => Err(DomainError::internal_error(&format!("rtp leg panicked: {err}"))),
+        None => Ok(()),
+    };
+    calls.abort_all();
+    logger.abort();
+    result
+}
+
+async fn log_call_events(mut events: mpsc::Receiver<CallEvent>) {
+    while let Some(CallEvent { call_id, envelope }) = events.recv().await {
+        match envelope.message {
+            ServerMessage::FinalTranscript { transcript } => {
+                let text: Vec<&str> = transcript
+                    .segments
+                    .iter()
+                    .map(|segment| segment.text.as_str())
+                    .collect();
+                info!(%call_id, transcript = %text.join(" "), "call transcript");
+            }
+            ServerMessage::Error { message } => error!(%call_id, "rtp session error: {}", message),
+            _ => {}
+        }
+    }
+}
+
+async fn forward_events(
+    call_id: String,
+    out: Arc<OutboundQueue>,
+    events: mpsc::Sender<CallEvent>,
+) {
+    while let Some(envelope) = out.pop().await {
+        let event = CallEvent {
+            call_id: call_id.clone(),
+            envelope,
+        };
+        if events.send(event).await.is_err() {
+            break;
+        }
+    }
+    out.close();
+}
+
+fn envelope(message: ClientMessage) -> ClientEnvelope {
+    ClientEnvelope {
+        version: PROTOCOL_VERSION,
+        track: None,
+        message,
+    }
+}
+
+enum LegDecoder {
+    G711(G711Decoder),
+    #[cfg(feature = "opus")]
+    Opus(crate::pcm::OpusDecoder),
+}
+
+impl LegDecoder {
+    fn new(codec: RtpCodec) -> Result<Self, DomainError> {
+        match codec {
+            RtpCodec::Pcmu => Ok(Self::G711(G711Decoder::ulaw())),
+            RtpCodec::Pcma => Ok(Self::G711(G711Decoder::alaw())),
+            #[cfg(feature = "opus")]
+            RtpCodec::Opus { stereo } => crate::pcm::OpusDecoder::new(stereo)
+                .map(Self::Opus)
+                .map_err(|err| DomainError::internal_error(&format!("opus decoder: {err}"))),
+            #[cfg(not(feature = "opus"))]
+            RtpCodec::Opus { .. } => {
+                Err(DomainError::invalid_input("opus legs need the `opus` feature"))
+            }
+        }
+    }
+
+    fn decode(&mut self, payload: &[u8]) -> Result<Vec<f32>, String> {
+        match self {
+            Self::G711(decoder) => Ok(decoder.decode(payload)),
+            #[cfg(feature = "opus")]
+            Self::Opus(decoder) => decoder.decode(payload).map_err(|err| err.to_string()),
+        }
+    }
+}
+
+/// Payload type and payload of an RTP packet, skipping CSRCs, the header
+/// extension and padding; `None` for anything that is not RTP v2.
+fn rtp_payload(packet: &[u8]) -> Option<(u8, &[u8])> {
+    if packet.len() < 12 || packet[0] >> 6 != RTP_VERSION {
+        return None;
+    }
+    let padded = packet[0] & 0x20 != 0;
+    let extended = packet[0] & 0x10 != 0;
+    let csrc_count = usize::from(packet[0] & 0x0F);
+    let payload_type = packet[1] & 0x7F;
+
+    let mut start = 12 + 4 * csrc_count;
+    if extended {
+        let words = packet.get(start + 2..start + 4)?;
+        start += 4 + 4 * usize::from(u16::from_be_bytes([words[0], words[1]]));
+    }
+    let mut end = packet.len();
+    if padded {
+        end = end.checked_sub(usize::from(*packet.last()?))?;
+    }
+    (start <= end).then(|| (payload_type, &packet[start..end]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr() -> SocketAddr {
+        "127.0.0.1:40000".parse().unwrap()
+    }
+
+    #[test]
+    fn offer_picks_the_first_supported_format() {
+        let offer = "v=0\r\nm=audio 5004 RTP/AVP 18 8 0 101\r\n\
+                     a=rtpmap:18 G729/8000\r\na=rtpmap:101 telephone-event/8000\r\n";
+        let leg = RtpLeg::from_offer("call-1", offer, addr()).unwrap();
+        assert_eq!(leg.codec, RtpCodec::Pcma);
+        assert_eq!(leg.payload_type, 8);
+        assert!(leg.answer().contains("m=audio 40000 RTP/AVP 8\r\n"));
+        assert!(leg.answer().contains("a=rtpmap:8 PCMA/8000\r\n"));
+
+        let unsupported = "m=audio 5004 RTP/AVP 18\r\na=rtpmap:18 G729/8000\r\n";
+        assert!(RtpLeg::from_offer("call-2", unsupported, addr()).is_err());
+    }
+
+    #[test]
+    fn rtp_header_fields_are_skipped() {
+        let mut packet = vec![0x80 | 0x20 | 0x10 | 0x01, 0x80, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0];
+        packet.extend([0, 0, 0, 0]); // CSRC
+        packet.extend([0xBE, 0xDE, 0, 1, 9, 9, 9, 9]); // one-word extension
+        packet.extend([0xFF, 0xFF]); // payload
+        packet.extend([0, 0, 3]); // padding
+        assert_eq!(rtp_payload(&packet), Some((0, &[0xFF, 0xFF][..])));
+
+        assert_eq!(rtp_payload(&[0x40; 12]), None, "RTP v1");
+        assert_eq!(rtp_payload(&[0x80; 4]), None, "truncated");
+    }
+}
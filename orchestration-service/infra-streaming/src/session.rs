@@ -0,0 +1,564 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use orchestration_application::{asr_seconds, ApplicationError, PipelineEngine, TenantPermit};
+use orchestration_domain::{DomainError, LanguageTag, PipelineContext};
+use log_context::LogSampler;
+use serde_json::json;
+use uuid::Uuid;
+
+use tokio::task::JoinHandle;
+use tracing::Instrument;
+
+use crate::budget::BudgetLease;
+use crate::late_events::LateSubscription;
+use crate::outbound::OutboundQueue;
+use crate::protocol::{
+    event_message, ClientEnvelope, ClientMessage, ServerEnvelope, ServerMessage, Verbosity,
+    PROTOCOL_VERSION, STREAM_SAMPLE_RATE_HZ,
+};
+use crate::StreamingState;
+
+/// Tracks one session may open, the unnamed one included.
+pub const MAX_TRACKS: usize = 8;
+const MAX_TRACK_ID_LEN: usize = 32;
+
+/// Runs the streaming protocol for one client, whatever carries it: reads
+/// decoded client envelopes, queues server envelopes on `out`.
+pub struct SessionDriver {
+    state: StreamingState,
+    out: Arc<OutboundQueue>,
+    lease: BudgetLease,
+    frame_log: LogSampler,
+    session: Option<Session>,
+    /// Forwards the session's late events to `out`.
+    late_forwarder: Option<JoinHandle<()>>,
+    /// Tenant the session runs for, see [`SessionDriver::with_tenant`].
+    tenant_id: Option<String>,
+    /// One of the tenant's concurrent slots, held while the session is open.
+    permit: Option<TenantPermit>,
+}
+
+/// How a tenant's streaming sessions run, resolved when a session starts.
+#[derive(Clone, Default)]
+pub struct StreamingRoute {
+    /// Pipeline run instead of [`StreamingState::pipeline`].
+    pub pipeline: Option<Arc<PipelineEngine>>,
+    /// ASR model asked for instead of the ASR service's default.
+    pub model: Option<String>,
+}
+
+/// A started session and its tracks. Each track (`None` is the unnamed one
+/// of clients that send no `track`) accumulates audio in its own pipeline
+/// context.
+struct Session {
+    session_id: String,
+    tenant_id: Option<String>,
+    /// The tenant's pipeline, else the shared one.
+    pipeline: Arc<PipelineEngine>,
+    /// The tenant's model, set on every track.
+    model: Option<String>,
+    language_hint: Option<LanguageTag>,
+    verbosity: Verbosity,
+    tracks: BTreeMap<Option<String>, PipelineContext>,
+    /// Samples each track received since its last accounted flush.
+    unbilled_samples: BTreeMap<Option<String>, usize>,
+}
+
+impl SessionDriver {
+    pub fn new(state: StreamingState, out: Arc<OutboundQueue>) -> Self {
+        Self {
+            frame_log: LogSampler::new(state.frame_log_every),
+            lease: state.budget.register(),
+            state,
+            out,
+            session: None,
+            late_forwarder: None,
+            tenant_id: None,
+            permit: None,
+        }
+    }
+
+    /// Runs the session for `tenant_id`, e.g. from the `x-tenant-id` header
+    /// of the connection; see [`SessionDriver::admit`].
+    pub fn with_tenant(mut self, tenant_id: Option<String>) -> Self {
+        self.tenant_id = tenant_id;
+        self
+    }
+
+    /// Takes one of the tenant's concurrent slots for as long as the
+    /// session is open. Transports admit the connection before reading
+    /// from it, so a tenant over its quota, or missing while
+    /// `service.tenancy.required` is set, is refused with the error the
+    /// HTTP API returns.
+    pub fn admit(&mut self) -> Result<(), ApplicationError> {
+        self.permit = self.state.tenants.admit(self.tenant_id.as_deref())?;
+        Ok(())
+    }
+
+    /// Resolves when the [`crate::StreamBudget`] asks this session to
+    /// flush; answer with [`SessionDriver::evict`].
+    pub async fn flush_requested(&self) {
+        self.lease.flush_requested().await;
+    }
+
+    pub async fn handle(&mut self, envelope: ClientEnvelope) -> Result<(), DomainError> {
+        if envelope.version != PROTOCOL_VERSION {
+            return Err(DomainError::invalid_input(&format!(
+                "unsupported protocol version {}, expected {}",
+                envelope.version, PROTOCOL_VERSION
+            )));
+        }
+
+        let track = envelope.track;
+        match envelope.message {
+            ClientMessage::Start {
+                session_id,
+                language_hint,
+                verbosity,
+            } => {
+                // Restarting would silently drop the audio buffered so far.
+                if self.session.is_some() {
+                    return Err(DomainError::invalid_input("session already started"));
+                }
+                let route = self
+                    .tenant_id
+                    .as_deref()
+                    .and_then(|tenant_id| self.state.tenant_routes.get(tenant_id))
+                    .cloned()
+                    .unwrap_or_default();
+                let sid = session_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+                tracing::Span::current().record("session_id", sid.as_str());
+                self.late_forwarder = Some(tokio::spawn(
+                    forward_late_events(
+                        self.state.late_events.subscribe(&sid),
+                        sid.clone(),
+                        verbosity,
+                        self.out.clone(),
+                    )
+                    .in_current_span(),
+                ));
+                self.session = Some(Session {
+                    session_id: sid.clone(),
+                    tenant_id: self.tenant_id.clone(),
+                    pipeline: route.pipeline.unwrap_or_else(|| self.state.pipeline.clone()),
+                    model: route.model,
+                    language_hint,
+                    verbosity,
+                    tracks: BTreeMap::new(),
+                    unbilled_samples: BTreeMap::new(),
+                });
+                self.send(None, ServerMessage::Ready { session_id: sid }).await?;
+            }
+            ClientMessage::AudioFrame { pcm_f32 } => {
+                let log_frame = self.frame_log.sample();
+                let session = self.started()?;
+                let ctx = session.track(track.clone())?;
+                if log_frame {
+                    tracing::debug!(
+                        track = track.as_deref().unwrap_or_default(),
+                        frame_samples = pcm_f32.len(),
+                        buffered_samples = ctx.audio.samples.len() + pcm_f32.len(),
+                        "audio frame received"
+                    );
+                }
+                let frame_samples = pcm_f32.len();
+                ctx.audio.samples.extend(pcm_f32);
+                *session.unbilled_samples.entry(track).or_default() += frame_samples;
+                let frame_bytes = frame_samples * std::mem::size_of::<f32>();
+                if self.lease.add(frame_bytes) {
+                    self.evict().await?;
+                }
+            }
+            ClientMessage::Flush | ClientMessage::Stop => {
+                let session = self.started()?;
+                let tracks: Vec<_> = match track {
+                    Some(_) => vec![track],
+                    // A session that sent no audio yet still gets its events.
+                    None if session.tracks.is_empty() => vec![None],
+                    None => session.tracks.keys().cloned().collect(),
+                };
+                for track in tracks {
+                    self.run_pipeline(track).await?;
+                }
+            }
+            ClientMessage::Ping => {
+                self.send(None, ServerMessage::Pong).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Flushes every track to give the session's buffer back to the
+    /// [`crate::StreamBudget`]: runs the pipeline on the audio held so far,
+    /// then drops that audio, so later transcripts cover what the client
+    /// sends from here on.
+    pub async fn evict(&mut self) -> Result<(), DomainError> {
+        let tracks: Vec<_> = self
+            .session
+            .as_ref()
+            .map(|session| session.tracks.keys().cloned().collect())
+            .unwrap_or_default();
+        for track in tracks {
+            self.run_pipeline(track.clone()).await?;
+            if let Some(ctx) = self.started()?.tracks.get_mut(&track) {
+                ctx.audio.samples.clear();
+                ctx.audio.samples.shrink_to_fit();
+            }
+        }
+        self.lease.clear();
+        Ok(())
+    }
+
+    /// Reports `message` to the client, e.g. the error ending the session.
+    pub async fn send(
+        &self,
+        track: Option<String>,
+        message: ServerMessage,
+    ) -> Result<(), DomainError> {
+        self.out.push(ServerEnvelope::new(message).with_track(track)).await
+    }
+
+    fn started(&mut self) -> Result<&mut Session, DomainError> {
+        self.session
+            .as_mut()
+            .ok_or_else(|| DomainError::invalid_input("start must be sent first"))
+    }
+
+    async fn run_pipeline(&mut self, track: Option<String>) -> Result<(), DomainError> {
+        let usage = self.state.usage.clone();
+        let session = self.started()?;
+        let pipeline = session.pipeline.clone();
+        let verbosity = session.verbosity;
+        let ctx = session.track(track.clone())?;
+        let received_at_ms = unix_ms();
+        let started = Instant::now();
+        pipeline.run(ctx).await?;
+        let pipeline_ms = started.elapsed().as_millis() as u64;
+        let events = std::mem::take(&mut ctx.events);
+        let buffered_audio_ms =
+            ctx.audio.samples.len() as u64 * 1000 / u64::from(STREAM_SAMPLE_RATE_HZ);
+        // The track's audio stays buffered across flushes, so only what
+        // arrived since the last one is accounted; the ASR stage adds to
+        // `asr.compute_ms` at every run, so it is taken.
+        let asr_seconds = asr_seconds(ctx);
+        ctx.take_extension("asr.compute_ms");
+        let samples = session.unbilled_samples.remove(&track).unwrap_or(0);
+        if samples > 0 || asr_seconds > 0.0 {
+            usage.record(
+                session.tenant_id.as_deref(),
+                session.model.as_deref(),
+                samples as f64 / f64::from(STREAM_SAMPLE_RATE_HZ),
+                asr_seconds,
+            );
+        }
+        for event in events {
+            if let Some(message) = verbosity.apply(event_message(event)) {
+                self.send(track.clone(), message).await?;
+            }
+        }
+        self.send(
+            track,
+            ServerMessage::LatencyReport {
+                pipeline_ms,
+                buffered_audio_ms,
+                received_at_ms,
+                sent_at_ms: unix_ms(),
+            },
+        )
+        .await
+    }
+}
+
+impl Drop for SessionDriver {
+    fn drop(&mut self) {
+        if let Some(forwarder) = self.late_forwarder.take() {
+            forwarder.abort();
+        }
+    }
+}
+
+/// Queues the events published for session `session_id` and its tracks,
+/// e.g. a refined final transcript, as they arrive.
+async fn forward_late_events(
+    mut subscription: LateSubscription,
+    session_id: String,
+    verbosity: Verbosity,
+    out: Arc<OutboundQueue>,
+) {
+    let track_prefix = format!("{session_id}:");
+    while let Some((published_for, events)) = subscription.recv().await {
+        let track = published_for
+            .strip_prefix(track_prefix.as_str())
+            .map(str::to_string);
+        for event in events {
+            let Some(message) = verbosity.apply(event_message(event)) else {
+                continue;
+            };
+            let envelope = ServerEnvelope::new(message).with_track(track.clone());
+            if out.push(envelope).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+impl Session {
+    /// Context of `track`, opened on first use.
+    fn track(&mut self, track: Option<String>) -> Result<&mut PipelineContext, DomainError> {
+        if !self.tracks.contains_key(&track) {
+            if let Some(id) = &track {
+                validate_track_id(id)?;
+            }
+            if self.tracks.len() >= MAX_TRACKS {
+                return Err(DomainError::invalid_input(&format!(
+                    "a session carries at most {MAX_TRACKS} tracks"
+                )));
+            }
+            // Tracks are separate sessions downstream, e.g. for corrections.
+            let session_id = match &track {
+                Some(id) => format!("{}:{id}", self.session_id),
+                None => self.session_id.clone(),
+            };
+            let mut context = PipelineContext::new(session_id, self.language_hint.clone());
+            // Session stores and recordings are keyed by tenant.
+            context.tenant_id = self.tenant_id.clone();
+            if let Some(model) = &self.model {
+                context.set_extension("asr.model", json!(model));
+            }
+            self.tracks.insert(track.clone(), context);
+        }
+        Ok(self.tracks.get_mut(&track).expect("track opened above"))
+    }
+}
+
+fn validate_track_id(id: &str) -> Result<(), DomainError> {
+    let valid = !id.is_empty()
+        && id.len() <= MAX_TRACK_ID_LEN
+        && id
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'-');
+    if valid {
+        Ok(())
+    } else {
+        Err(DomainError::invalid_input(&format!(
+            "invalid track `{id}`: use up to {MAX_TRACK_ID_LEN} letters, digits, `_` or `-`"
+        )))
+    }
+}
+
+fn unix_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use orchestration_domain::LateEventSink;
+
+    use orchestration_application::{TenantPolicy, UsageMeter};
+
+    use super::*;
+
+    fn state(tenants: TenantPolicy, late_events: crate::LateEvents) -> StreamingState {
+        StreamingState {
+            pipeline: Arc::new(PipelineEngine::default().with_name("shared")),
+            max_message_bytes: 1024,
+            frame_log_every: 0,
+            budget: crate::StreamBudget::new(1024 * 1024),
+            outbound_queue_len: 8,
+            stall_timeout: std::time::Duration::from_secs(1),
+            late_events,
+            tenants,
+            tenant_routes: Arc::default(),
+            usage: UsageMeter::default(),
+        }
+    }
+
+    fn start(session_id: &str) -> ClientEnvelope {
+        ClientEnvelope {
+            version: PROTOCOL_VERSION,
+            track: None,
+            message: ClientMessage::Start {
+                session_id: Some(session_id.to_string()),
+                language_hint: None,
+                verbosity: Verbosity::default(),
+            },
+        }
+    }
+
+    #[test]
+    fn track_ids_are_short_identifiers() {
+        assert!(validate_track_id("mic").is_ok());
+        assert!(validate_track_id("system-audio_2").is_ok());
+        assert!(validate_track_id("").is_err());
+        assert!(validate_track_id("a b").is_err());
+        assert!(validate_track_id(&"x".repeat(33)).is_err());
+    }
+
+    #[test]
+    fn tracks_get_their_own_context() {
+        let mut session = Session {
+            session_id: "s".to_string(),
+            tenant_id: Some("acme".to_string()),
+            pipeline: Arc::new(PipelineEngine::default()),
+            model: Some("large-v3".to_string()),
+            language_hint: None,
+            verbosity: Verbosity::default(),
+            tracks: BTreeMap::new(),
+            unbilled_samples: BTreeMap::new(),
+        };
+        session.track(None).unwrap().audio.samples.push(0.1);
+        session.track(Some("mic".to_string())).unwrap().audio.samples.push(0.2);
+
+        assert_eq!(session.track(None).unwrap().session_id, "s");
+        let mic = session.track(Some("mic".to_string())).unwrap();
+        assert_eq!(mic.session_id, "s:mic");
+        assert_eq!(mic.tenant_id.as_deref(), Some("acme"));
+        assert_eq!(mic.extension("asr.model"), Some(&json!("large-v3")));
+        assert_eq!(mic.audio.samples, vec![0.2]);
+
+        for index in 2..MAX_TRACKS {
+            session.track(Some(format!("t{index}"))).unwrap();
+        }
+        assert!(session.track(Some("one-too-many".to_string())).is_err());
+    }
+
+    #[tokio::test]
+    async fn a_second_start_keeps_the_session_and_its_audio() {
+        let state = state(TenantPolicy::default(), crate::LateEvents::default());
+        let out = OutboundQueue::new(state.outbound_queue_len, state.stall_timeout);
+        let mut driver = SessionDriver::new(state, out);
+        driver.handle(start("first")).await.unwrap();
+        driver
+            .handle(ClientEnvelope {
+                version: PROTOCOL_VERSION,
+                track: None,
+                message: ClientMessage::AudioFrame {
+                    pcm_f32: vec![0.1, 0.2],
+                },
+            })
+            .await
+            .unwrap();
+        let error = driver.handle(start("second")).await.unwrap_err();
+        assert!(error.to_string().contains("session already started"), "{error}");
+
+        let session = driver.started().unwrap();
+        assert_eq!(session.session_id, "first");
+        assert_eq!(session.track(None).unwrap().audio.samples, vec![0.1, 0.2]);
+    }
+
+    #[tokio::test]
+    async fn late_events_reach_the_track_they_were_published_for() {
+        let late_events = crate::LateEvents::default();
+        let state = state(TenantPolicy::default(), late_events.clone());
+        let out = OutboundQueue::new(state.outbound_queue_len, state.stall_timeout);
+        let mut driver = SessionDriver::new(state, out.clone());
+        driver.handle(start("call")).await.unwrap();
+        assert!(matches!(out.pop().await.unwrap().message, ServerMessage::Ready { .. }));
+
+        late_events.publish(
+            "call:mic",
+            vec![orchestration_domain::DomainEvent::StageStarted {
+                stage: "asr_transcribe".to_string(),
+            }],
+        );
+        let envelope = out.pop().await.unwrap();
+        assert_eq!(envelope.track.as_deref(), Some("mic"));
+        assert!(matches!(envelope.message, ServerMessage::StageStarted { .. }));
+    }
+
+    #[tokio::test]
+    async fn sessions_run_for_their_tenant_within_its_quota() {
+        let state = state(
+            TenantPolicy::default().require_tenant().with_max_concurrent("acme", 1),
+            crate::LateEvents::default(),
+        );
+        let session = |tenant_id: Option<&str>| {
+            let out = OutboundQueue::new(state.outbound_queue_len, state.stall_timeout);
+            SessionDriver::new(state.clone(), out).with_tenant(tenant_id.map(str::to_string))
+        };
+
+        let mut acme = session(Some("acme"));
+        acme.admit().unwrap();
+        acme.handle(start("first")).await.unwrap();
+        let ctx = acme.started().unwrap().track(None).unwrap();
+        assert_eq!(ctx.tenant_id.as_deref(), Some("acme"));
+
+        let error = session(Some("acme")).admit().unwrap_err();
+        assert!(matches!(error, ApplicationError::QuotaExceeded(_)), "{error}");
+        let error = session(None).admit().unwrap_err();
+        assert!(matches!(error, ApplicationError::Validation(_)), "{error}");
+
+        drop(acme);
+        session(Some("acme")).admit().unwrap();
+    }
+
+    #[tokio::test]
+    async fn sessions_run_the_pipeline_and_model_of_their_tenant() {
+        let mut state = state(TenantPolicy::default(), crate::LateEvents::default());
+        let route = StreamingRoute {
+            pipeline: Some(Arc::new(PipelineEngine::default().with_name("two_pass"))),
+            model: Some("large-v3".to_string()),
+        };
+        state.tenant_routes = Arc::new([("acme".to_string(), route)].into());
+        let session = |tenant_id: &str| {
+            let out = OutboundQueue::new(state.outbound_queue_len, state.stall_timeout);
+            SessionDriver::new(state.clone(), out).with_tenant(Some(tenant_id.to_string()))
+        };
+
+        let mut acme = session("acme");
+        acme.handle(start("first")).await.unwrap();
+        let started = acme.started().unwrap();
+        assert_eq!(started.pipeline.name(), Some("two_pass"));
+        let ctx = started.track(None).unwrap();
+        assert_eq!(ctx.extension("asr.model"), Some(&json!("large-v3")));
+
+        let mut globex = session("globex");
+        globex.handle(start("second")).await.unwrap();
+        let started = globex.started().unwrap();
+        assert_eq!(started.pipeline.name(), Some("shared"));
+        assert!(started.track(None).unwrap().extension("asr.model").is_none());
+    }
+
+    #[tokio::test]
+    async fn flushes_account_the_audio_received_since_the_last_one() {
+        let mut state = state(TenantPolicy::default(), crate::LateEvents::default());
+        let route = StreamingRoute {
+            pipeline: None,
+            model: Some("large-v3".to_string()),
+        };
+        state.tenant_routes = Arc::new([("acme".to_string(), route)].into());
+        let usage = state.usage.clone();
+        let out = OutboundQueue::new(state.outbound_queue_len, state.stall_timeout);
+        let mut driver = SessionDriver::new(state, out).with_tenant(Some("acme".to_string()));
+        let envelope = |message| ClientEnvelope {
+            version: PROTOCOL_VERSION,
+            track: None,
+            message,
+        };
+        let half_second = vec![0.1; STREAM_SAMPLE_RATE_HZ as usize / 2];
+
+        driver.handle(start("call")).await.unwrap();
+        for _ in 0..2 {
+            driver
+                .handle(envelope(ClientMessage::AudioFrame {
+                    pcm_f32: half_second.clone(),
+                }))
+                .await
+                .unwrap();
+            driver.handle(envelope(ClientMessage::Flush)).await.unwrap();
+        }
+        driver.handle(envelope(ClientMessage::Flush)).await.unwrap();
+
+        let totals = usage.totals();
+        assert_eq!(totals.len(), 1);
+        assert_eq!(totals[0].tenant_id.as_deref(), Some("acme"));
+        assert_eq!(totals[0].model, "large-v3");
+        assert_eq!(totals[0].requests, 2);
+        assert_eq!(totals[0].audio_seconds, 1.0);
+    }
+}
@@ -0,0 +1,306 @@
+//! WebRTC ingestion: a browser sends its microphone as an Opus track on a
+//! peer connection instead of JSON `audio_frame`s. Signalling is a single
+//! raw SDP exchange on `POST /webrtc/offer`; the `events` data channel the
+//! client opens carries the JSON protocol both ways (control messages in,
+//! session events out), minus audio.
+
+use std::sync::Arc;
+
+use orchestration_application::TENANT_ID_HEADER;
+use orchestration_domain::DomainError;
+use axum::{
+    extract::{Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use tracing::{error, info, warn, Instrument};
+use webrtc::api::interceptor_registry::register_default_interceptors;
+use webrtc::api::media_engine::MediaEngine;
+use webrtc::api::APIBuilder;
+use webrtc::data_channel::data_channel_message::DataChannelMessage;
+use webrtc::data_channel::RTCDataChannel;
+use webrtc::interceptor::registry::Registry;
+use webrtc::peer_connection::configuration::RTCConfiguration;
+use webrtc::peer_connection::peer_connection_state::RTCPeerConnectionState;
+use webrtc::peer_connection::sdp::session_description::RTCSessionDescription;
+use webrtc::peer_connection::RTCPeerConnection;
+use webrtc::rtp_transceiver::rtp_codec::RTPCodecType;
+use webrtc::rtp_transceiver::rtp_transceiver_direction::RTCRtpTransceiverDirection;
+use webrtc::rtp_transceiver::RTCRtpTransceiverInit;
+use webrtc::track::track_remote::TrackRemote;
+
+use crate::protocol::{ClientEnvelope, ClientMessage, ServerMessage, Verbosity, PROTOCOL_VERSION};
+use crate::pcm::OpusDecoder;
+use crate::{admitted_driver, refusal, OutboundQueue, SessionDriver, StreamingState};
+
+/// Decoded audio is handed to the session in chunks of this many 16 kHz
+/// samples (100 ms) rather than per 20 ms packet.
+const FRAME_SAMPLES: usize = 1_600;
+
+/// Session options, as query parameters of the offer.
+#[derive(Debug, Default, Deserialize)]
+pub struct OfferParams {
+    pub session_id: Option<String>,
+    pub language_hint: Option<String>,
+}
+
+enum Input {
+    Audio(Vec<f32>),
+    Control(ClientEnvelope),
+    Closed,
+}
+
+/// `POST /webrtc/offer`: answers the SDP offer in the body and starts a
+/// session fed by the offer's audio track, for the tenant of its
+/// `x-tenant-id` header; a tenant over its quota is refused with 429.
+pub(crate) async fn offer_handler(
+    State(state): State<StreamingState>,
+    headers: HeaderMap,
+    Query(params): Query<OfferParams>,
+    offer_sdp: String,
+) -> Response {
+    let out = OutboundQueue::new(state.outbound_queue_len, state.stall_timeout);
+    let tenant = headers
+        .get(TENANT_ID_HEADER)
+        .map(|value| value.to_str().unwrap_or_default());
+    let driver = match admitted_driver(state, out.clone(), tenant) {
+        Ok(driver) => driver,
+        Err(err) => {
+            warn!("webrtc offer refused: {}", err);
+            return refusal(&err);
+        }
+    };
+    match accept_offer(driver, out, params, offer_sdp).await {
+        Ok(answer_sdp) => (
+            StatusCode::CREATED,
+            [(header::CONTENT_TYPE, "application/sdp")],
+            answer_sdp,
+        )
+            .into_response(),
+        Err(err) => {
+            warn!("webrtc offer rejected: {}", err);
+            (StatusCode::BAD_REQUEST, err.to_string()).into_response()
+        }
+    }
+}
+
+async fn accept_offer(
+    driver: SessionDriver,
+    out: Arc<OutboundQueue>,
+    params: OfferParams,
+    offer_sdp: String,
+) -> Result<String, DomainError> {
+    let language_hint = params
+        .language_hint
+        .map(|tag| orchestration_domain::LanguageTag::parse(&tag))
+        .transpose()
+        .map_err(|err| DomainError::invalid_input(&format!("invalid language_hint: {err}")))?;
+
+    let peer = new_peer_connection().await?;
+    peer.add_transceiver_from_kind(
+        RTPCodecType::Audio,
+        Some(RTCRtpTransceiverInit {
+            direction: RTCRtpTransceiverDirection::Recvonly,
+            send_encodings: Vec::new(),
+        }),
+    )
+    .await
+    .map_err(webrtc_error)?;
+
+    let (input_tx, input_rx) = mpsc::channel(64);
+    wire_peer(&peer, input_tx, out.clone());
+
+    let offer = RTCSessionDescription::offer(offer_sdp).map_err(webrtc_error)?;
+    peer.set_remote_description(offer).await.map_err(webrtc_error)?;
+    let answer = peer.create_answer(None).await.map_err(webrtc_error)?;
+    let mut gathered = peer.gathering_complete_promise().await;
+    peer.set_local_description(answer).await.map_err(webrtc_error)?;
+    let _ = gathered.recv().await;
+    let answer_sdp = peer
+        .local_description()
+        .await
+        .map(|description| description.sdp)
+        .ok_or_else(|| DomainError::internal_error("no local description"))?;
+
+    let start = ClientEnvelope {
+        version: PROTOCOL_VERSION,
+        track: None,
+        message: ClientMessage::Start {
+            session_id: params.session_id,
+            language_hint,
+            verbosity: Verbosity::default(),
+        },
+    };
+    let span = tracing::info_span!("webrtc_session", session_id = tracing::field::Empty);
+    tokio::spawn(run_session(driver, peer, out, start, input_rx).instrument(span));
+    Ok(answer_sdp)
+}
+
+async fn new_peer_connection() -> Result<Arc<RTCPeerConnection>, DomainError> {
+    let mut media = MediaEngine::default();
+    media.register_default_codecs().map_err(webrtc_error)?;
+    let registry =
+        register_default_interceptors(Registry::new(), &mut media).map_err(webrtc_error)?;
+    let api = APIBuilder::new()
+        .with_media_engine(media)
+        .with_interceptor_registry(registry)
+        .build();
+    let peer = api
+        .new_peer_connection(RTCConfiguration::default())
+        .await
+        .map_err(webrtc_error)?;
+    Ok(Arc::new(peer))
+}
+
+/// Routes the peer's audio track and `events` data channel to the session.
+fn wire_peer(peer: &RTCPeerConnection, input: mpsc::Sender<Input>, out: Arc<OutboundQueue>) {
+    let audio_input = input.clone();
+    peer.on_track(Box::new(move |track, _receiver, _transceiver| {
+        let input = audio_input.clone();
+        Box::pin(async move {
+            tokio::spawn(read_track(track, input).in_current_span());
+        })
+    }));
+
+    let control_input = input.clone();
+    peer.on_data_channel(Box::new(move |channel: Arc<RTCDataChannel>| {
+        let input = control_input.clone();
+        let out = out.clone();
+        Box::pin(async move {
+            channel.on_message(Box::new(move |message: DataChannelMessage| {
+                let input = input.clone();
+                Box::pin(async move {
+                    let envelope = serde_json::from_slice::<ClientEnvelope>(&message.data);
+                    match envelope {
+                        Ok(envelope) => {
+                            let _ = input.send(Input::Control(envelope)).await;
+                        }
+                        Err(err) => warn!("invalid data channel message: {}", err),
+                    }
+                })
+            }));
+            tokio::spawn(write_events(channel, out).in_current_span());
+        })
+    }));
+
+    peer.on_peer_connection_state_change(Box::new(move |connection_state| {
+        let input = input.clone();
+        Box::pin(async move {
+            if matches!(
+                connection_state,
+                RTCPeerConnectionState::Disconnected
+                    | RTCPeerConnectionState::Failed
+                    | RTCPeerConnectionState::Closed
+            ) {
+                let _ = input.send(Input::Closed).await;
+            }
+        })
+    }));
+}
+
+/// Decodes the Opus track to 16 kHz mono and forwards it in
+/// [`FRAME_SAMPLES`] chunks.
+async fn read_track(track: Arc<TrackRemote>, input: mpsc::Sender<Input>) {
+    let stereo = track.codec().capability.channels == 2;
+    let mut decoder = match OpusDecoder::new(stereo) {
+        Ok(decoder) => decoder,
+        Err(err) => {
+            error!("opus decoder: {}", err);
+            return;
+        }
+    };
+    let mut pending = Vec::with_capacity(FRAME_SAMPLES);
+    while let Ok((packet, _)) = track.read_rtp().await {
+        if packet.payload.is_empty() {
+            continue;
+        }
+        match decoder.decode(&packet.payload) {
+            Ok(samples) => pending.extend(samples),
+            Err(err) => {
+                warn!("dropping undecodable opus packet: {}", err);
+                continue;
+            }
+        }
+        if pending.len() >= FRAME_SAMPLES {
+            let frame = std::mem::replace(&mut pending, Vec::with_capacity(FRAME_SAMPLES));
+            if input.send(Input::Audio(frame)).await.is_err() {
+                return;
+            }
+        }
+    }
+    if !pending.is_empty() {
+        let _ = input.send(Input::Audio(pending)).await;
+    }
+}
+
+/// Sends the session's events on the data channel as JSON envelopes.
+async fn write_events(channel: Arc<RTCDataChannel>, out: Arc<OutboundQueue>) {
+    while let Some(envelope) = out.pop().await {
+        let payload = match serde_json::to_string(&envelope) {
+            Ok(payload) => payload,
+            Err(err) => {
+                error!("serialization error: {}", err);
+                continue;
+            }
+        };
+        if let Err(err) = channel.send_text(payload).await {
+            error!("data channel send error: {}", err);
+            break;
+        }
+    }
+    out.close();
+}
+
+async fn run_session(
+    mut driver: SessionDriver,
+    peer: Arc<RTCPeerConnection>,
+    out: Arc<OutboundQueue>,
+    start: ClientEnvelope,
+    mut input: mpsc::Receiver<Input>,
+) {
+    let mut result = driver.handle(start).await;
+    while result.is_ok() {
+        let next = tokio::select! {
+            next = input.recv() => next.unwrap_or(Input::Closed),
+            () = driver.flush_requested() => {
+                result = driver.evict().await;
+                continue;
+            }
+        };
+        result = match next {
+            Input::Audio(pcm_f32) => {
+                driver
+                    .handle(ClientEnvelope {
+                        version: PROTOCOL_VERSION,
+                        track: None,
+                        message: ClientMessage::AudioFrame { pcm_f32 },
+                    })
+                    .await
+            }
+            Input::Control(envelope) => driver.handle(envelope).await,
+            Input::Closed => break,
+        };
+    }
+    if let Err(err) = result {
+        error!("session error: {}", err);
+        let _ = driver
+            .send(
+                None,
+                ServerMessage::Error {
+                    message: err.to_string(),
+                },
+            )
+            .await;
+    }
+    out.close();
+    if let Err(err) = peer.close().await {
+        warn!("closing peer connection: {}", err);
+    }
+    info!("webrtc session closed");
+}
+
+fn webrtc_error(err: webrtc::Error) -> DomainError {
+    DomainError::internal_error(&format!("webrtc: {err}"))
+}
@@ -0,0 +1,200 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use orchestration_application::{PipelineEngine, TenantPolicy};
+use orchestration_domain::{
+    DomainError, DomainEvent, LanguageTag, PipelineContext, PipelineStage, Transcript,
+    TranscriptSegment,
+};
+use orchestration_infra_streaming::grpc::pb;
+use orchestration_infra_streaming::{
+    grpc_service, LateEvents, StreamBudget, StreamingServiceClient, StreamingState,
+};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use tonic::transport::server::TcpIncoming;
+use tonic::transport::Server;
+
+struct MockAsrStage;
+
+#[async_trait]
+impl PipelineStage for MockAsrStage {
+    fn name(&self) -> &'static str {
+        "mock-asr"
+    }
+
+    async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+        let transcript = Transcript {
+            language: LanguageTag::en(),
+            segments: vec![TranscriptSegment {
+                text: "hello".to_string(),
+                start_ms: 0,
+                end_ms: 300,
+                tokens: Vec::new(),
+                speaker: None,
+                language: None,
+                no_speech_prob: None,
+                avg_logprob: None,
+            }],
+        };
+        context
+            .events
+            .push(DomainEvent::FinalTranscript { transcript });
+        Ok(())
+    }
+}
+
+async fn spawn_server() -> (SocketAddr, JoinHandle<()>) {
+    spawn_server_for(TenantPolicy::default()).await
+}
+
+async fn spawn_server_for(tenants: TenantPolicy) -> (SocketAddr, JoinHandle<()>) {
+    let service = grpc_service(StreamingState {
+        pipeline: Arc::new(PipelineEngine::new(vec![Arc::new(MockAsrStage)])),
+        max_message_bytes: 1024 * 1024,
+        frame_log_every: 0,
+        budget: StreamBudget::new(16 * 1024 * 1024),
+        outbound_queue_len: 16,
+        stall_timeout: Duration::from_secs(5),
+        late_events: LateEvents::default(),
+        tenants,
+        tenant_routes: Default::default(),
+        usage: Default::default(),
+    });
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+    let addr = listener.local_addr().expect("local addr");
+    let server = tokio::spawn(async move {
+        Server::builder()
+            .add_service(service)
+            .serve_with_incoming(TcpIncoming::from(listener))
+            .await
+            .expect("server run");
+    });
+    (addr, server)
+}
+
+fn frame(track: Option<&str>, message: pb::client_frame::Message) -> pb::ClientFrame {
+    pb::ClientFrame {
+        version: 1,
+        track: track.map(str::to_string),
+        message: Some(message),
+    }
+}
+
+#[tokio::test]
+async fn stream_runs_a_session_until_the_client_half_closes() {
+    use pb::client_frame::Message;
+    use pb::server_event::Event;
+
+    let (addr, server) = spawn_server().await;
+    let mut client = StreamingServiceClient::connect(format!("http://{addr}"))
+        .await
+        .expect("connect");
+
+    let frames = futures::stream::iter(vec![
+        frame(
+            None,
+            Message::Start(pb::Start {
+                session_id: Some("native".to_string()),
+                ..Default::default()
+            }),
+        ),
+        frame(
+            Some("mic"),
+            Message::AudioFrame(pb::AudioFrame {
+                pcm_f32: vec![0.0, 0.1, 0.2],
+            }),
+        ),
+        frame(None, Message::Flush(pb::Flush {})),
+    ]);
+    let events: Vec<pb::ServerEvent> = client
+        .stream(frames)
+        .await
+        .expect("stream")
+        .into_inner()
+        .map(|event| event.expect("event"))
+        .collect()
+        .await;
+
+    assert_eq!(
+        events.first().and_then(|event| event.event.clone()),
+        Some(Event::Ready(pb::Ready {
+            session_id: "native".to_string(),
+        }))
+    );
+    let finals: Vec<_> = events
+        .iter()
+        .filter_map(|event| match &event.event {
+            Some(Event::FinalTranscript(transcript)) => Some((event.track.as_deref(), transcript)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(finals.len(), 1);
+    let (track, transcript) = finals[0];
+    assert_eq!(track, Some("mic"));
+    let segments = &transcript.transcript.as_ref().expect("transcript").segments;
+    assert_eq!(segments[0].text, "hello");
+    assert!(events
+        .iter()
+        .any(|event| matches!(event.event, Some(Event::LatencyReport(_)))));
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn frames_before_start_end_the_stream_with_an_error() {
+    use pb::client_frame::Message;
+    use pb::server_event::Event;
+
+    let (addr, server) = spawn_server().await;
+    let mut client = StreamingServiceClient::connect(format!("http://{addr}"))
+        .await
+        .expect("connect");
+
+    let frames = futures::stream::iter(vec![frame(None, Message::Flush(pb::Flush {}))]);
+    let events: Vec<pb::ServerEvent> = client
+        .stream(frames)
+        .await
+        .expect("stream")
+        .into_inner()
+        .map(|event| event.expect("event"))
+        .collect()
+        .await;
+
+    let [event] = events.as_slice() else {
+        panic!("expected one event, got {events:?}");
+    };
+    let Some(Event::Error(error)) = &event.event else {
+        panic!("expected an error, got {event:?}");
+    };
+    assert!(error.message.contains("start must be sent first"), "{}", error.message);
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn tenant_over_quota_is_refused_with_resource_exhausted() {
+    let (addr, server) =
+        spawn_server_for(TenantPolicy::default().with_max_concurrent("acme", 1)).await;
+    let mut client = StreamingServiceClient::connect(format!("http://{addr}"))
+        .await
+        .expect("connect");
+    let request = || {
+        let mut request = tonic::Request::new(futures::stream::pending::<pb::ClientFrame>());
+        request
+            .metadata_mut()
+            .insert("x-tenant-id", "acme".parse().expect("metadata value"));
+        request
+    };
+
+    let first = client.stream(request()).await.expect("first stream admitted");
+    let status = client.stream(request()).await.expect_err("second stream refused");
+    assert_eq!(status.code(), tonic::Code::ResourceExhausted, "{status}");
+
+    drop(first);
+    server.abort();
+}
@@ -0,0 +1,134 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use orchestration_application::{PipelineEngine, TenantPolicy};
+use orchestration_domain::{
+    DomainError, DomainEvent, LanguageTag, PipelineContext, PipelineStage, Transcript,
+    TranscriptSegment,
+};
+use orchestration_infra_streaming::protocol::ServerMessage;
+use orchestration_infra_streaming::{
+    run_rtp_leg, CallEvent, LateEvents, RtpCodec, RtpLeg, RtpSettings, StreamBudget,
+    StreamingState,
+};
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc;
+
+/// Transcribes a flush as the number of samples it was given.
+struct SampleCountStage;
+
+#[async_trait]
+impl PipelineStage for SampleCountStage {
+    fn name(&self) -> &'static str {
+        "sample-count"
+    }
+
+    async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+        let transcript = Transcript {
+            language: LanguageTag::en(),
+            segments: vec![TranscriptSegment {
+                text: format!("{} samples", context.audio.samples.len()),
+                start_ms: 0,
+                end_ms: 0,
+                tokens: Vec::new(),
+                speaker: None,
+                language: None,
+                no_speech_prob: None,
+                avg_logprob: None,
+            }],
+        };
+        context
+            .events
+            .push(DomainEvent::FinalTranscript { transcript });
+        Ok(())
+    }
+}
+
+fn state() -> StreamingState {
+    StreamingState {
+        pipeline: Arc::new(PipelineEngine::new(vec![Arc::new(SampleCountStage)])),
+        max_message_bytes: 1024 * 1024,
+        frame_log_every: 0,
+        budget: StreamBudget::new(16 * 1024 * 1024),
+        outbound_queue_len: 16,
+        stall_timeout: Duration::from_secs(5),
+        late_events: LateEvents::default(),
+        tenants: TenantPolicy::default(),
+        tenant_routes: Default::default(),
+        usage: Default::default(),
+    }
+}
+
+fn free_udp_addr() -> SocketAddr {
+    std::net::UdpSocket::bind("127.0.0.1:0")
+        .and_then(|socket| socket.local_addr())
+        .expect("free port")
+}
+
+fn rtp_packet(payload_type: u8, sequence: u16, payload: &[u8]) -> Vec<u8> {
+    let mut packet = vec![0x80, payload_type];
+    packet.extend(sequence.to_be_bytes());
+    packet.extend((u32::from(sequence) * 160).to_be_bytes());
+    packet.extend(0x1234_5678u32.to_be_bytes());
+    packet.extend(payload);
+    packet
+}
+
+#[tokio::test]
+async fn g711_packets_are_transcribed_when_the_call_goes_quiet() {
+    let local_addr = free_udp_addr();
+    let leg = RtpLeg::new("call-1", local_addr, RtpCodec::Pcmu);
+    let settings = RtpSettings {
+        flush_interval: Duration::from_secs(60),
+        idle_timeout: Duration::from_millis(200),
+    };
+    let (events, mut received) = mpsc::channel(16);
+    let call = tokio::spawn(run_rtp_leg(state(), leg, settings, events));
+
+    let CallEvent { call_id, envelope } = received.recv().await.expect("ready");
+    assert_eq!(call_id, "call-1");
+    assert!(matches!(envelope.message, ServerMessage::Ready { .. }), "{envelope:?}");
+
+    let sender = UdpSocket::bind("127.0.0.1:0").await.expect("bind sender");
+    // 20 ms of µ-law silence per packet; DTMF (payload type 101) is skipped.
+    for sequence in 0..3 {
+        let packet = rtp_packet(0, sequence, &[0xFF; 160]);
+        sender.send_to(&packet, local_addr).await.expect("send");
+    }
+    sender
+        .send_to(&rtp_packet(101, 3, &[0; 4]), local_addr)
+        .await
+        .expect("send");
+
+    tokio::time::timeout(Duration::from_secs(5), call)
+        .await
+        .expect("the leg ends once idle")
+        .expect("join")
+        .expect("leg");
+    let mut finals = Vec::new();
+    while let Some(event) = received.recv().await {
+        if let ServerMessage::FinalTranscript { transcript } = event.envelope.message {
+            finals.push(transcript.segments[0].text.clone());
+        }
+    }
+    // 8 kHz G.711 is upsampled to the 16 kHz stream rate.
+    assert_eq!(finals, vec!["960 samples".to_string()]);
+}
+
+#[tokio::test]
+async fn a_leg_without_audio_waits_for_its_call() {
+    let leg = RtpLeg::new("call-2", free_udp_addr(), RtpCodec::Pcma);
+    let settings = RtpSettings {
+        flush_interval: Duration::from_secs(60),
+        idle_timeout: Duration::from_millis(50),
+    };
+    let (events, mut received) = mpsc::channel(16);
+    let call = tokio::spawn(run_rtp_leg(state(), leg, settings, events));
+    received.recv().await.expect("ready");
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert!(!call.is_finished(), "idle_timeout only counts after audio");
+    call.abort();
+}
@@ -0,0 +1,127 @@
+//! `POST /webrtc/offer` on a running listener, with offers from a real peer
+//! connection.
+#![cfg(feature = "webrtc")]
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::serve;
+use orchestration_application::{PipelineEngine, TenantPolicy};
+use orchestration_infra_streaming::{build_router, LateEvents, StreamBudget, StreamingState};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use webrtc::api::interceptor_registry::register_default_interceptors;
+use webrtc::api::media_engine::MediaEngine;
+use webrtc::api::APIBuilder;
+use webrtc::interceptor::registry::Registry;
+use webrtc::peer_connection::configuration::RTCConfiguration;
+use webrtc::peer_connection::sdp::session_description::RTCSessionDescription;
+use webrtc::peer_connection::RTCPeerConnection;
+use webrtc::rtp_transceiver::rtp_codec::RTPCodecType;
+use webrtc::rtp_transceiver::rtp_transceiver_direction::RTCRtpTransceiverDirection;
+use webrtc::rtp_transceiver::RTCRtpTransceiverInit;
+
+async fn spawn_server() -> (SocketAddr, JoinHandle<()>) {
+    let app = build_router(StreamingState {
+        pipeline: Arc::new(PipelineEngine::default()),
+        max_message_bytes: 1024 * 1024,
+        frame_log_every: 0,
+        budget: StreamBudget::new(16 * 1024 * 1024),
+        outbound_queue_len: 16,
+        stall_timeout: Duration::from_secs(5),
+        late_events: LateEvents::default(),
+        tenants: TenantPolicy::default(),
+        tenant_routes: Default::default(),
+        usage: Default::default(),
+    });
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+    let addr = listener.local_addr().expect("local addr");
+    let server = tokio::spawn(async move {
+        serve(listener, app).await.expect("server run");
+    });
+    (addr, server)
+}
+
+/// Status and body of `POST /webrtc/offer` with `sdp`.
+async fn post_offer(addr: SocketAddr, sdp: &str) -> (u16, String) {
+    let mut stream = TcpStream::connect(addr).await.expect("connect");
+    let request = format!(
+        "POST /webrtc/offer?session_id=browser HTTP/1.1\r\nHost: {addr}\r\n\
+         Content-Type: application/sdp\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{sdp}",
+        sdp.len()
+    );
+    stream.write_all(request.as_bytes()).await.expect("send");
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.expect("read");
+
+    let (head, body) = response.split_once("\r\n\r\n").expect("http response");
+    let status = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .expect("status code");
+    (status, body.to_string())
+}
+
+/// A browser-like peer: one outgoing audio track and the `events` channel.
+async fn browser_peer() -> RTCPeerConnection {
+    let mut media = MediaEngine::default();
+    media.register_default_codecs().expect("codecs");
+    let registry = register_default_interceptors(Registry::new(), &mut media).expect("registry");
+    let api = APIBuilder::new()
+        .with_media_engine(media)
+        .with_interceptor_registry(registry)
+        .build();
+    let peer = api
+        .new_peer_connection(RTCConfiguration::default())
+        .await
+        .expect("peer");
+    peer.add_transceiver_from_kind(
+        RTPCodecType::Audio,
+        Some(RTCRtpTransceiverInit {
+            direction: RTCRtpTransceiverDirection::Sendonly,
+            send_encodings: Vec::new(),
+        }),
+    )
+    .await
+    .expect("audio transceiver");
+    peer.create_data_channel("events", None)
+        .await
+        .expect("data channel");
+    peer
+}
+
+#[tokio::test]
+async fn offers_are_answered_with_a_receiving_audio_section() {
+    let (addr, server) = spawn_server().await;
+    let peer = browser_peer().await;
+    let offer = peer.create_offer(None).await.expect("offer");
+    let mut gathered = peer.gathering_complete_promise().await;
+    peer.set_local_description(offer).await.expect("local description");
+    let _ = gathered.recv().await;
+    let offer_sdp = peer.local_description().await.expect("offer sdp").sdp;
+
+    let (status, answer_sdp) = post_offer(addr, &offer_sdp).await;
+    assert_eq!(status, 201, "{answer_sdp}");
+    assert!(answer_sdp.contains("m=audio"), "{answer_sdp}");
+    assert!(answer_sdp.contains("a=recvonly"), "{answer_sdp}");
+    let answer = RTCSessionDescription::answer(answer_sdp).expect("answer");
+    peer.set_remote_description(answer)
+        .await
+        .expect("the peer accepts the answer");
+
+    peer.close().await.expect("close");
+    server.abort();
+}
+
+#[tokio::test]
+async fn malformed_offers_are_rejected() {
+    let (addr, server) = spawn_server().await;
+    let (status, body) = post_offer(addr, "not sdp").await;
+    assert_eq!(status, 400);
+    assert!(body.contains("webrtc"), "{body}");
+    server.abort();
+}
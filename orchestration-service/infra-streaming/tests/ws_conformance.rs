@@ -0,0 +1,253 @@
+//! Executable spec of the WebSocket protocol's error handling, as documented
+//! under "Protocol errors" in the README: each test sends a sequence a
+//! client may get wrong and checks the events and whether the server hung
+//! up.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use orchestration_application::{PipelineEngine, TenantPolicy};
+use orchestration_domain::{
+    DomainError, DomainEvent, LanguageTag, PipelineContext, PipelineStage, Transcript,
+    TranscriptSegment,
+};
+use orchestration_infra_streaming::{build_router, LateEvents, StreamBudget, StreamingState};
+use async_trait::async_trait;
+use axum::serve;
+use futures::{SinkExt, StreamExt};
+use serde_json::Value;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+const MAX_MESSAGE_BYTES: usize = 64 * 1024;
+/// How long the server may stay silent before an exchange is considered
+/// over.
+const QUIET: Duration = Duration::from_millis(500);
+
+const START: &str = r#"{"version":1,"type":"start","payload":{"session_id":"spec"}}"#;
+const FRAME: &str = r#"{"version":1,"type":"audio_frame","payload":{"pcm_f32":[0.0,0.1]}}"#;
+const FLUSH: &str = r#"{"version":1,"type":"flush"}"#;
+const PING: &str = r#"{"version":1,"type":"ping"}"#;
+
+struct MockAsrStage;
+
+#[async_trait]
+impl PipelineStage for MockAsrStage {
+    fn name(&self) -> &'static str {
+        "mock-asr"
+    }
+
+    async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+        let transcript = Transcript {
+            language: LanguageTag::en(),
+            segments: vec![TranscriptSegment {
+                text: "hello".to_string(),
+                start_ms: 0,
+                end_ms: 300,
+                tokens: Vec::new(),
+                speaker: None,
+                language: None,
+                no_speech_prob: None,
+                avg_logprob: None,
+            }],
+        };
+        context
+            .events
+            .push(DomainEvent::FinalTranscript { transcript });
+        Ok(())
+    }
+}
+
+async fn spawn_server() -> (SocketAddr, JoinHandle<()>) {
+    let pipeline = Arc::new(PipelineEngine::new(vec![Arc::new(MockAsrStage)]));
+    let app = build_router(StreamingState {
+        pipeline,
+        max_message_bytes: MAX_MESSAGE_BYTES,
+        frame_log_every: 0,
+        budget: StreamBudget::new(16 * 1024 * 1024),
+        outbound_queue_len: 16,
+        stall_timeout: Duration::from_secs(5),
+        late_events: LateEvents::default(),
+        tenants: TenantPolicy::default(),
+        tenant_routes: Default::default(),
+        usage: Default::default(),
+    });
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+    let addr = listener.local_addr().expect("local addr");
+    let server = tokio::spawn(async move {
+        serve(listener, app).await.expect("server run");
+    });
+    (addr, server)
+}
+
+enum Sent {
+    Text(String),
+    Binary,
+}
+
+fn text(raw: &str) -> Sent {
+    Sent::Text(raw.to_string())
+}
+
+/// What the server answered to a sequence of client messages.
+struct Exchange {
+    events: Vec<Value>,
+    closed: bool,
+}
+
+impl Exchange {
+    /// Sends `messages` in order, then reads until the server hangs up or
+    /// stays quiet for [`QUIET`].
+    async fn run(messages: Vec<Sent>) -> Self {
+        let (addr, server) = spawn_server().await;
+        let (mut socket, _) = connect_async(format!("ws://{addr}/ws"))
+            .await
+            .expect("connect");
+        for message in messages {
+            let message = match message {
+                Sent::Text(raw) => Message::Text(raw.into()),
+                Sent::Binary => Message::Binary(vec![0, 1, 2, 3].into()),
+            };
+            // The server may already have hung up on an earlier message.
+            if socket.send(message).await.is_err() {
+                break;
+            }
+        }
+
+        let mut events = Vec::new();
+        let closed = loop {
+            match tokio::time::timeout(QUIET, socket.next()).await {
+                Err(_) => break false,
+                Ok(Some(Ok(Message::Text(raw)))) => {
+                    events.push(serde_json::from_str(raw.as_str()).expect("server sends JSON"));
+                }
+                Ok(Some(Ok(Message::Close(_)) | Err(_)) | None) => break true,
+                Ok(Some(Ok(_))) => {}
+            }
+        };
+        server.abort();
+        Self { events, closed }
+    }
+
+    fn types(&self) -> Vec<&str> {
+        self.events
+            .iter()
+            .map(|event| event["type"].as_str().expect("typed event"))
+            .collect()
+    }
+
+    fn count(&self, event_type: &str) -> usize {
+        self.types().iter().filter(|seen| **seen == event_type).count()
+    }
+
+    fn error_message(&self) -> &str {
+        self.events
+            .iter()
+            .rev()
+            .find(|event| event["type"] == "error")
+            .and_then(|event| event["payload"]["message"].as_str())
+            .expect("an error event")
+    }
+}
+
+#[tokio::test]
+async fn audio_before_start_is_rejected_and_closes() {
+    let exchange = Exchange::run(vec![text(FRAME)]).await;
+    assert_eq!(exchange.types(), ["error"]);
+    assert!(exchange.error_message().contains("start must be sent first"));
+    assert!(exchange.closed);
+}
+
+#[tokio::test]
+async fn flush_before_start_is_rejected_and_closes() {
+    let exchange = Exchange::run(vec![text(FLUSH)]).await;
+    assert_eq!(exchange.types(), ["error"]);
+    assert!(exchange.error_message().contains("start must be sent first"));
+    assert!(exchange.closed);
+}
+
+#[tokio::test]
+async fn malformed_json_is_rejected_and_closes() {
+    let exchange = Exchange::run(vec![text(START), text("{not json")]).await;
+    assert_eq!(exchange.types(), ["ready", "error"]);
+    assert!(exchange.error_message().contains("invalid message: "));
+    assert!(exchange.closed);
+}
+
+#[tokio::test]
+async fn unknown_message_types_are_rejected_and_close() {
+    let exchange = Exchange::run(vec![
+        text(START),
+        text(r#"{"version":1,"type":"rewind","payload":{"ms":500}}"#),
+    ])
+    .await;
+    assert_eq!(exchange.types(), ["ready", "error"]);
+    assert!(exchange.error_message().contains("invalid message: "));
+    assert!(exchange.closed);
+}
+
+#[tokio::test]
+async fn unsupported_versions_are_rejected_and_close() {
+    let exchange = Exchange::run(vec![text(
+        r#"{"version":2,"type":"start","payload":{"session_id":null}}"#,
+    )])
+    .await;
+    assert_eq!(exchange.types(), ["error"]);
+    assert!(exchange.error_message().contains("unsupported protocol version 2, expected 1"));
+    assert!(exchange.closed);
+}
+
+#[tokio::test]
+async fn binary_frames_are_rejected_without_ending_the_session() {
+    let exchange = Exchange::run(vec![text(START), Sent::Binary, text(PING)]).await;
+    assert_eq!(exchange.types(), ["ready", "error", "pong"]);
+    assert!(exchange
+        .error_message()
+        .contains("binary frames are not supported; use JSON audio_frame"));
+    assert!(!exchange.closed);
+}
+
+#[tokio::test]
+async fn oversized_messages_close_the_connection() {
+    let samples = vec!["0.5"; MAX_MESSAGE_BYTES / 2].join(",");
+    let oversized =
+        format!(r#"{{"version":1,"type":"audio_frame","payload":{{"pcm_f32":[{samples}]}}}}"#);
+    assert!(oversized.len() > MAX_MESSAGE_BYTES);
+
+    let exchange = Exchange::run(vec![text(START), Sent::Text(oversized), text(PING)]).await;
+    assert_eq!(exchange.types(), ["ready"]);
+    assert!(exchange.closed);
+}
+
+#[tokio::test]
+async fn duplicate_start_is_rejected_and_closes() {
+    let exchange = Exchange::run(vec![text(START), text(FRAME), text(START)]).await;
+    assert_eq!(exchange.types(), ["ready", "error"]);
+    assert!(exchange.error_message().contains("session already started"));
+    assert!(exchange.closed);
+}
+
+#[tokio::test]
+async fn duplicate_flushes_are_each_answered() {
+    let exchange =
+        Exchange::run(vec![text(START), text(FRAME), text(FLUSH), text(FLUSH)]).await;
+    assert_eq!(exchange.count("final_transcript"), 2);
+    assert_eq!(exchange.count("latency_report"), 2);
+    assert_eq!(exchange.count("error"), 0);
+    assert!(!exchange.closed);
+}
+
+#[tokio::test]
+async fn invalid_track_ids_are_rejected_and_close() {
+    let exchange = Exchange::run(vec![
+        text(START),
+        text(r#"{"version":1,"track":"a b","type":"audio_frame","payload":{"pcm_f32":[0.1]}}"#),
+    ])
+    .await;
+    assert_eq!(exchange.types(), ["ready", "error"]);
+    assert!(exchange.error_message().contains("invalid track `a b`"));
+    assert!(exchange.closed);
+}
@@ -1,16 +1,20 @@
+use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
-use asr_application::{AsrSessionUseCase, PipelineEngine};
-use asr_domain::{
+use orchestration_application::{PipelineEngine, TenantPolicy};
+use orchestration_domain::{
     DomainError, DomainEvent, LanguageTag, PipelineContext, PipelineStage, Transcript,
     TranscriptSegment, WordTiming,
 };
-use asr_infra_streaming::{build_router, StreamingState};
+use orchestration_infra_streaming::{build_router, LateEvents, StreamBudget, StreamingState};
 use async_trait::async_trait;
 use axum::serve;
 use futures::{SinkExt, StreamExt};
 use tokio::net::TcpListener;
-use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::{self, client::IntoClientRequest, Message};
+use tokio_tungstenite::connect_async;
 
 struct MockAsrStage;
 struct MockAlignStage;
@@ -23,12 +27,16 @@ impl PipelineStage for MockAsrStage {
 
     async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
         let transcript = Transcript {
-            language: LanguageTag::En,
+            language: LanguageTag::en(),
             segments: vec![TranscriptSegment {
                 text: "bonjour world".to_string(),
                 start_ms: 0,
                 end_ms: 700,
                 tokens: Vec::new(),
+                speaker: None,
+                language: None,
+                no_speech_prob: None,
+                avg_logprob: None,
             }],
         };
         context
@@ -51,21 +59,38 @@ impl PipelineStage for MockAlignStage {
                 start_ms: 0,
                 end_ms: 350,
                 confidence: 0.95,
+                speaker: None,
+                start_sample: None,
+                end_sample: None,
+                energy_rms: None,
+                pitch_hz: None,
+                out_of_band: false,
             }],
         });
         Ok(())
     }
 }
 
-#[tokio::test]
-async fn websocket_session_emits_transcript_and_alignment() {
-    let usecase = Arc::new(AsrSessionUseCase::new(PipelineEngine::new(vec![
+async fn spawn_server() -> (SocketAddr, JoinHandle<()>) {
+    spawn_server_for(TenantPolicy::default()).await
+}
+
+async fn spawn_server_for(tenants: TenantPolicy) -> (SocketAddr, JoinHandle<()>) {
+    let pipeline = Arc::new(PipelineEngine::new(vec![
         Arc::new(MockAsrStage),
         Arc::new(MockAlignStage),
-    ])));
+    ]));
     let app = build_router(StreamingState {
-        usecase,
+        pipeline,
         max_message_bytes: 1024 * 1024,
+        frame_log_every: 1,
+        budget: StreamBudget::new(16 * 1024 * 1024),
+        outbound_queue_len: 16,
+        stall_timeout: Duration::from_secs(5),
+        late_events: LateEvents::default(),
+        tenants,
+        tenant_routes: Default::default(),
+        usage: Default::default(),
     });
 
     let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
@@ -73,6 +98,12 @@ async fn websocket_session_emits_transcript_and_alignment() {
     let server = tokio::spawn(async move {
         serve(listener, app).await.expect("server run");
     });
+    (addr, server)
+}
+
+#[tokio::test]
+async fn websocket_session_emits_transcript_and_alignment() {
+    let (addr, server) = spawn_server().await;
 
     let ws_url = format!("ws://{}/ws", addr);
     let (mut socket, _) = connect_async(ws_url).await.expect("connect");
@@ -102,7 +133,8 @@ async fn websocket_session_emits_transcript_and_alignment() {
     let mut got_final = false;
     let mut got_align = false;
 
-    for _ in 0..4 {
+    // Stage events surround the transcript and the alignment.
+    for _ in 0..16 {
         let Some(Ok(msg)) = socket.next().await else {
             continue;
         };
@@ -128,3 +160,86 @@ async fn websocket_session_emits_transcript_and_alignment() {
 
     server.abort();
 }
+
+#[tokio::test]
+async fn tracks_are_transcribed_separately() {
+    let (addr, server) = spawn_server().await;
+    let (mut socket, _) = connect_async(format!("ws://{}/ws", addr))
+        .await
+        .expect("connect");
+
+    for raw in [
+        r#"{"version":1,"type":"start","payload":{"session_id":"meeting"}}"#,
+        r#"{"version":1,"track":"mic","type":"audio_frame","payload":{"pcm_f32":[0.1]}}"#,
+        r#"{"version":1,"track":"system","type":"audio_frame","payload":{"pcm_f32":[0.2]}}"#,
+        r#"{"version":1,"type":"flush"}"#,
+    ] {
+        socket
+            .send(Message::Text(raw.to_string().into()))
+            .await
+            .expect("send");
+    }
+
+    let mut final_tracks = Vec::new();
+    while final_tracks.len() < 2 {
+        let Some(Ok(Message::Text(raw))) = socket.next().await else {
+            panic!("socket closed before both tracks were transcribed");
+        };
+        let event: serde_json::Value = serde_json::from_str(raw.as_str()).expect("json");
+        if event["type"] == "final_transcript" {
+            final_tracks.push(event["track"].as_str().expect("tagged").to_string());
+        }
+    }
+    final_tracks.sort();
+    assert_eq!(final_tracks, ["mic", "system"]);
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn invalid_tenant_header_is_refused_before_the_upgrade() {
+    let (addr, server) = spawn_server().await;
+    let mut request = format!("ws://{addr}/ws")
+        .into_client_request()
+        .expect("request");
+    request
+        .headers_mut()
+        .insert("x-tenant-id", "acme/../globex".parse().expect("header value"));
+
+    let error = connect_async(request).await.expect_err("tenant refused");
+    assert!(
+        matches!(&error, tungstenite::Error::Http(response) if response.status() == 422),
+        "{error}"
+    );
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn tenant_over_quota_is_refused_with_resource_exhausted() {
+    let (addr, server) =
+        spawn_server_for(TenantPolicy::default().with_max_concurrent("acme", 1)).await;
+    let request = || {
+        let mut request = format!("ws://{addr}/ws")
+            .into_client_request()
+            .expect("request");
+        request
+            .headers_mut()
+            .insert("x-tenant-id", "acme".parse().expect("header value"));
+        request
+    };
+
+    let (first, _) = connect_async(request()).await.expect("first session admitted");
+    let error = connect_async(request()).await.expect_err("second session refused");
+    let tungstenite::Error::Http(response) = &error else {
+        panic!("expected an HTTP refusal, got {error}");
+    };
+    assert_eq!(response.status(), 429);
+    assert_eq!(
+        response.headers()["content-type"],
+        "application/problem+json"
+    );
+
+    drop(first);
+    server.abort();
+}
@@ -1,20 +1,21 @@
+use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
+use log_context::traced_request;
 use orchestration_domain::{DomainError, PipelineContext, PipelineStage};
+use orchestration_infra_grpc::GrpcChannelPool;
 use tempo_grpc_server::{pb, TempoServiceClient};
-use tonic::transport::{Channel, Endpoint};
-use tonic::Request;
 
 pub struct TempoMatchStage {
-    client: TempoServiceClient<Channel>,
+    channels: Arc<GrpcChannelPool>,
     request_timeout: Duration,
 }
 
 impl TempoMatchStage {
-    pub fn new(client: TempoServiceClient<Channel>, request_timeout: Duration) -> Self {
+    pub fn new(channels: Arc<GrpcChannelPool>, request_timeout: Duration) -> Self {
         Self {
-            client,
+            channels,
             request_timeout,
         }
     }
@@ -63,12 +64,18 @@ impl PipelineStage for TempoMatchStage {
             session_id: Some(context.session_id.clone()),
         };
 
-        let mut client = self.client.clone();
-        let rpc = client.match_tempo(Request::new(request));
+        let pooled = self.channels.checkout().await?;
+        let mut client = TempoServiceClient::new(pooled.channel())
+            .max_decoding_message_size(self.channels.max_decoding_message_bytes())
+            .max_encoding_message_size(self.channels.max_encoding_message_bytes());
+        let rpc = client.match_tempo(traced_request(request));
         let response = tokio::time::timeout(self.request_timeout, rpc)
             .await
             .map_err(|_| DomainError::external_service_error("tempo", "gRPC request timed out"))?
-            .map_err(|status| map_status("tempo", status))?
+            .map_err(|status| {
+                self.channels.report_status(&pooled, &status);
+                map_status("tempo", status)
+            })?
             .into_inner();
 
         tracing::debug!(
@@ -85,23 +92,6 @@ impl PipelineStage for TempoMatchStage {
     }
 }
 
-pub async fn connect_tempo_client(
-    endpoint_uri: &str,
-    connect_timeout: Duration,
-    max_decoding_message_bytes: usize,
-    max_encoding_message_bytes: usize,
-) -> Result<TempoServiceClient<Channel>, DomainError> {
-    let endpoint = Endpoint::from_shared(endpoint_uri.to_string())
-        .map_err(|err| DomainError::internal_error(&format!("invalid tempo endpoint: {err}")))?
-        .connect_timeout(connect_timeout);
-    let channel = endpoint.connect().await.map_err(|err| {
-        DomainError::external_service_error("tempo", &format!("failed to connect: {err}"))
-    })?;
-    Ok(TempoServiceClient::new(channel)
-        .max_decoding_message_size(max_decoding_message_bytes)
-        .max_encoding_message_size(max_encoding_message_bytes))
-}
-
 fn map_orch_to_proto_timings(words: &[orchestration_domain::WordTiming]) -> Vec<pb::WordTiming> {
     words
         .iter()
@@ -110,6 +100,12 @@ fn map_orch_to_proto_timings(words: &[orchestration_domain::WordTiming]) -> Vec<
             start_ms: w.start_ms,
             end_ms: w.end_ms,
             confidence: w.confidence,
+            speaker: w.speaker.clone(),
+            start_sample: w.start_sample,
+            end_sample: w.end_sample,
+            energy_rms: w.energy_rms,
+            pitch_hz: w.pitch_hz,
+            out_of_band: w.out_of_band,
         })
         .collect()
 }
@@ -154,6 +150,12 @@ mod tests {
             start_ms: 100,
             end_ms: 200,
             confidence: 0.85,
+            speaker: None,
+            start_sample: None,
+            end_sample: None,
+            energy_rms: None,
+            pitch_hz: None,
+            out_of_band: false,
         }];
 
         let mapped = map_orch_to_proto_timings(&orch);
@@ -179,6 +181,12 @@ mod tests {
             start_ms: 0,
             end_ms: 500,
             confidence: 0.95,
+            speaker: None,
+            start_sample: None,
+            end_sample: None,
+            energy_rms: None,
+            pitch_hz: None,
+            out_of_band: false,
         }];
         let json = serde_json::to_value(&words).expect("serialize");
         context.set_extension("original.timings", json);
@@ -286,7 +286,7 @@ mod tests {
 
     fn sample_transcript() -> Transcript {
         Transcript {
-            language: LanguageTag::En,
+            language: LanguageTag::en(),
             segments: vec![
                 TranscriptSegment {
                     text: "hello".to_string(),
@@ -297,7 +297,14 @@ mod tests {
                         start_ms: 0,
                         end_ms: 100,
                         confidence: 0.99,
+                        start_sample: None,
+                        end_sample: None,
+                        disfluency: false,
                     }],
+                    speaker: None,
+                    language: None,
+                    no_speech_prob: None,
+                    avg_logprob: None,
                 },
                 TranscriptSegment {
                     text: "world".to_string(),
@@ -308,7 +315,14 @@ mod tests {
                         start_ms: 100,
                         end_ms: 200,
                         confidence: 0.98,
+                        start_sample: None,
+                        end_sample: None,
+                        disfluency: false,
                     }],
+                    speaker: None,
+                    language: None,
+                    no_speech_prob: None,
+                    avg_logprob: None,
                 },
             ],
         }
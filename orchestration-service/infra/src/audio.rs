@@ -1,6 +1,7 @@
 use orchestration_domain::{DomainError, PipelineContext, PipelineStage};
 use async_trait::async_trait;
 use serde_json::json;
+use vocal_dsp::{clamp_samples, resample_linear};
 
 pub struct AudioPreprocessStage;
 
@@ -17,9 +18,7 @@ impl PipelineStage for AudioPreprocessStage {
     }
 
     async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
-        for sample in &mut context.audio.samples {
-            *sample = sample.clamp(-1.0, 1.0);
-        }
+        clamp_samples(&mut context.audio.samples, 1.0);
         Ok(())
     }
 }
@@ -78,47 +77,6 @@ impl PipelineStage for ResampleStage {
     }
 }
 
-pub(crate) fn resample_linear(samples: &[f32], source_rate_hz: u32, target_rate_hz: u32) -> Vec<f32> {
-    if source_rate_hz == target_rate_hz {
-        return samples.to_vec();
-    }
-    if samples.len() <= 1 {
-        return samples.to_vec();
-    }
-
-    let output_len = ((samples.len() as u64 * target_rate_hz as u64) / source_rate_hz as u64)
-        .max(1) as usize;
-    if output_len <= 1 {
-        return vec![samples[0]];
-    }
-
-    let mut output = Vec::with_capacity(output_len);
-    let max_source_idx = samples.len() - 1;
-
-    for out_idx in 0..output_len {
-        let source_pos = out_idx as f64 * source_rate_hz as f64 / target_rate_hz as f64;
-        let left_idx = source_pos.floor() as usize;
-        let right_idx = (left_idx + 1).min(max_source_idx);
-        let frac = (source_pos - left_idx as f64) as f32;
-
-        let left = samples[left_idx];
-        let right = samples[right_idx];
-        output.push(left * (1.0 - frac) + right * frac);
-    }
-
-    output
-}
-
-pub fn pcm16le_bytes_to_f32(samples: &[u8]) -> Vec<f32> {
-    samples
-        .chunks_exact(2)
-        .map(|chunk| {
-            let value = i16::from_le_bytes([chunk[0], chunk[1]]);
-            f32::from(value) / f32::from(i16::MAX)
-        })
-        .collect()
-}
-
 #[cfg(test)]
 mod tests {
     use super::{AudioPreprocessStage, ResampleStage};
@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use orchestration_domain::{DomainError, PipelineContext, PipelineStage, SessionStore};
+use serde_json::json;
+
+/// Hands the end of the session's previous transcript to `asr_transcribe`
+/// as Whisper's prompt (`asr.prompt`), so chunks sent one after another
+/// under the same `session_id` decode as one continuous text. Place it
+/// before `asr_transcribe` in a pipeline that also runs `store_session`,
+/// which keeps each chunk's transcript for the next. The ASR service trims
+/// the prompt to its token budget.
+pub struct ContextCarryOverStage {
+    sessions: Arc<dyn SessionStore>,
+    max_chars: usize,
+}
+
+impl ContextCarryOverStage {
+    pub fn new(sessions: Arc<dyn SessionStore>, max_chars: usize) -> Self {
+        Self {
+            sessions,
+            max_chars,
+        }
+    }
+}
+
+#[async_trait]
+impl PipelineStage for ContextCarryOverStage {
+    fn name(&self) -> &'static str {
+        "context_carry_over"
+    }
+
+    async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+        if self.max_chars == 0 || context.extension("asr.prompt").is_some() {
+            return Ok(());
+        }
+        let tenant_id = context.tenant_id.as_deref();
+        let Some(previous) = self.sessions.load(tenant_id, &context.session_id).await? else {
+            return Ok(());
+        };
+
+        let text = previous
+            .transcript
+            .segments
+            .iter()
+            .map(|segment| segment.text.trim())
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let prompt = text_tail(&text, self.max_chars);
+        if !prompt.is_empty() {
+            tracing::debug!(
+                session_id = %context.session_id,
+                prompt_chars = prompt.chars().count(),
+                "carrying the previous transcript over as prompt"
+            );
+            context.set_extension("asr.prompt", json!(prompt));
+        }
+        Ok(())
+    }
+}
+
+/// The last `max_chars` characters of `text`, starting on a word.
+fn text_tail(text: &str, max_chars: usize) -> &str {
+    let count = text.chars().count();
+    if count <= max_chars {
+        return text;
+    }
+    let start = text
+        .char_indices()
+        .nth(count - max_chars)
+        .map_or(text.len(), |(index, _)| index);
+    let tail = &text[start..];
+    // Starting mid-word would hand Whisper a word fragment.
+    let starts_on_word = text[..start].ends_with(char::is_whitespace);
+    if starts_on_word {
+        return tail.trim_start();
+    }
+    tail.split_once(char::is_whitespace)
+        .map_or("", |(_, rest)| rest.trim_start())
+}
+
+#[cfg(test)]
+mod tests {
+    use orchestration_domain::{
+        AudioChunk, LanguageTag, StoredSession, Transcript, TranscriptSegment,
+    };
+
+    use super::*;
+    use crate::InMemorySessionStore;
+
+    fn stored(session_id: &str, texts: &[&str]) -> StoredSession {
+        StoredSession {
+            session_id: session_id.to_string(),
+            tenant_id: None,
+            audio: AudioChunk::mono(16_000, Vec::new()),
+            transcript: Transcript {
+                language: LanguageTag::en(),
+                segments: texts
+                    .iter()
+                    .map(|text| TranscriptSegment {
+                        text: text.to_string(),
+                        start_ms: 0,
+                        end_ms: 1_000,
+                        tokens: Vec::new(),
+                        speaker: None,
+                        language: None,
+                        no_speech_prob: None,
+                        avg_logprob: None,
+                    })
+                    .collect(),
+            },
+            aligned_words: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn tail_starts_on_a_word() {
+        assert_eq!(text_tail("the quick brown fox", 100), "the quick brown fox");
+        assert_eq!(text_tail("the quick brown fox", 9), "brown fox");
+        assert_eq!(text_tail("the quick brown fox", 10), "brown fox");
+        assert_eq!(text_tail("the quick brown fox", 2), "");
+    }
+
+    #[tokio::test]
+    async fn previous_transcript_of_the_session_becomes_the_prompt() {
+        let sessions = Arc::new(InMemorySessionStore::new(8));
+        sessions
+            .save(stored("call", &[" Hello there.", " How are you?"]))
+            .await
+            .unwrap();
+        let stage = ContextCarryOverStage::new(sessions, 100);
+
+        let mut next = PipelineContext::new("call", None);
+        stage.execute(&mut next).await.expect("carries over");
+        assert_eq!(
+            next.extension("asr.prompt"),
+            Some(&json!("Hello there. How are you?"))
+        );
+
+        let mut other = PipelineContext::new("other", None);
+        stage.execute(&mut other).await.expect("no previous chunk");
+        assert!(other.extension("asr.prompt").is_none());
+    }
+}
@@ -0,0 +1,266 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use orchestration_domain::{
+    DomainError, DomainEvent, DuplicateIndex, PipelineContext, PipelineStage, SessionStore,
+};
+use serde_json::{json, Map, Value};
+
+/// Keeps the most recent `max_entries` fingerprints in memory; recording one
+/// more drops the oldest.
+pub struct InMemoryDuplicateIndex {
+    max_entries: usize,
+    inner: Mutex<Entries>,
+}
+
+#[derive(Default)]
+struct Entries {
+    by_fingerprint: HashMap<String, String>,
+    order: VecDeque<String>,
+}
+
+impl InMemoryDuplicateIndex {
+    pub fn new(max_entries: usize) -> Self {
+        Self {
+            max_entries: max_entries.max(1),
+            inner: Mutex::new(Entries::default()),
+        }
+    }
+}
+
+#[async_trait]
+impl DuplicateIndex for InMemoryDuplicateIndex {
+    async fn find(&self, fingerprint: &str) -> Result<Option<String>, DomainError> {
+        let entries = self
+            .inner
+            .lock()
+            .map_err(|_| DomainError::internal_error("duplicate index lock poisoned"))?;
+        Ok(entries.by_fingerprint.get(fingerprint).cloned())
+    }
+
+    async fn record(&self, fingerprint: &str, session_id: &str) -> Result<(), DomainError> {
+        let mut entries = self
+            .inner
+            .lock()
+            .map_err(|_| DomainError::internal_error("duplicate index lock poisoned"))?;
+        let previous = entries
+            .by_fingerprint
+            .insert(fingerprint.to_string(), session_id.to_string());
+        if previous.is_some() {
+            entries.order.retain(|stored| stored != fingerprint);
+        }
+        entries.order.push_back(fingerprint.to_string());
+        while entries.order.len() > self.max_entries {
+            if let Some(oldest) = entries.order.pop_front() {
+                entries.by_fingerprint.remove(&oldest);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Answers a request from the session store when its audio fingerprint
+/// (`audio.fingerprint`, set by `audio_transform`) and decode parameters
+/// match an earlier session, and finishes the pipeline early. Otherwise records the
+/// fingerprint under the current session, so place it after
+/// `audio_transform` in a pipeline that also runs `store_session`. The
+/// cached session id is reported as `dedup.session_id`.
+pub struct DuplicateLookupStage {
+    index: Arc<dyn DuplicateIndex>,
+    sessions: Arc<dyn SessionStore>,
+}
+
+impl DuplicateLookupStage {
+    pub fn new(index: Arc<dyn DuplicateIndex>, sessions: Arc<dyn SessionStore>) -> Self {
+        Self { index, sessions }
+    }
+}
+
+#[async_trait]
+impl PipelineStage for DuplicateLookupStage {
+    fn name(&self) -> &'static str {
+        "dedup_lookup"
+    }
+
+    async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+        let Some(fingerprint) = context
+            .extension("audio.fingerprint")
+            .and_then(|value| value.as_str())
+            .map(str::to_string)
+        else {
+            tracing::debug!("no audio fingerprint, skipping duplicate lookup");
+            return Ok(());
+        };
+
+        let fingerprint = duplicate_key(context, &fingerprint);
+        if let Some(session_id) = self.index.find(&fingerprint).await? {
+            // The session may have been evicted since, or never stored if its
+            // pipeline failed after this stage.
+            let tenant_id = context.tenant_id.as_deref();
+            if let Some(session) = self.sessions.load(tenant_id, &session_id).await? {
+                tracing::debug!(
+                    session_id = %context.session_id,
+                    cached_session_id = %session_id,
+                    "answering duplicate upload from cache"
+                );
+                context.set_extension("dedup.session_id", json!(session_id));
+                context.transcript = Some(session.transcript.clone());
+                context.aligned_words = session.aligned_words.clone();
+                context.events.push(DomainEvent::FinalTranscript {
+                    transcript: session.transcript,
+                });
+                context.events.push(DomainEvent::AlignmentUpdate {
+                    words: session.aligned_words,
+                });
+                context.finish_early();
+                return Ok(());
+            }
+        }
+        self.index.record(&fingerprint, &context.session_id).await
+    }
+}
+
+/// Extensions that change what `asr_transcribe` decodes from the same audio.
+const DECODE_EXTENSIONS: [&str; 4] =
+    ["asr.model", "asr.grammar", "asr.prompt", "asr.decode_profile"];
+
+/// Index key of `fingerprint` in `context`: tenants never answer each
+/// other's uploads, and an upload decoded with another model, language
+/// hint, grammar or prompt is transcribed again.
+fn duplicate_key(context: &PipelineContext, fingerprint: &str) -> String {
+    let key = match &context.tenant_id {
+        Some(tenant_id) => format!("{tenant_id}/{fingerprint}"),
+        None => fingerprint.to_string(),
+    };
+    let mut params = Map::new();
+    if let Some(language_hint) = &context.language_hint {
+        params.insert("language_hint".to_string(), json!(language_hint));
+    }
+    for name in DECODE_EXTENSIONS {
+        if let Some(value) = context.extension(name) {
+            params.insert(name.to_string(), value.clone());
+        }
+    }
+    if params.is_empty() {
+        key
+    } else {
+        format!("{key}?{}", Value::Object(params))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use orchestration_domain::{AudioChunk, LanguageTag, StoredSession, Transcript};
+
+    use super::*;
+    use crate::InMemorySessionStore;
+
+    fn context(session_id: &str, fingerprint: &str) -> PipelineContext {
+        let mut context = PipelineContext::new(session_id, None);
+        context.set_extension("audio.fingerprint", json!(fingerprint));
+        context
+    }
+
+    #[tokio::test]
+    async fn repeated_fingerprint_is_answered_from_the_stored_session() {
+        let index = Arc::new(InMemoryDuplicateIndex::new(8));
+        let sessions = Arc::new(InMemorySessionStore::new(8));
+        let stage = DuplicateLookupStage::new(index.clone(), sessions.clone());
+
+        let mut first = context("first", "abc");
+        stage.execute(&mut first).await.expect("records");
+        assert!(!first.is_finished_early());
+        assert_eq!(index.find("abc").await.unwrap().as_deref(), Some("first"));
+
+        // Not stored yet: still a miss, and the newer session takes over.
+        let mut second = context("second", "abc");
+        stage.execute(&mut second).await.expect("records");
+        assert!(!second.is_finished_early());
+
+        sessions
+            .save(StoredSession {
+                session_id: "second".to_string(),
+                tenant_id: None,
+                audio: AudioChunk::mono(16_000, Vec::new()),
+                transcript: Transcript {
+                    language: LanguageTag::en(),
+                    segments: Vec::new(),
+                },
+                aligned_words: Vec::new(),
+            })
+            .await
+            .unwrap();
+        let mut third = context("third", "abc");
+        stage.execute(&mut third).await.expect("answers");
+        assert!(third.is_finished_early());
+        assert!(third.transcript.is_some());
+        assert_eq!(third.extension("dedup.session_id"), Some(&json!("second")));
+    }
+
+    #[tokio::test]
+    async fn fingerprints_are_recorded_per_tenant() {
+        let index = Arc::new(InMemoryDuplicateIndex::new(8));
+        let sessions = Arc::new(InMemorySessionStore::new(8));
+        let stage = DuplicateLookupStage::new(index.clone(), sessions);
+        for (tenant, session_id) in [("acme", "a-1"), ("globex", "g-1")] {
+            let mut context = context(session_id, "abc");
+            context.tenant_id = Some(tenant.to_string());
+            stage.execute(&mut context).await.expect("records");
+        }
+
+        assert_eq!(index.find("acme/abc").await.unwrap().as_deref(), Some("a-1"));
+        assert_eq!(index.find("globex/abc").await.unwrap().as_deref(), Some("g-1"));
+        assert_eq!(index.find("abc").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn other_decode_parameters_miss_the_cache() {
+        let index = Arc::new(InMemoryDuplicateIndex::new(8));
+        let sessions = Arc::new(InMemorySessionStore::new(8));
+        let stage = DuplicateLookupStage::new(index.clone(), sessions.clone());
+        let with_model = |session_id: &str, model: &str| {
+            let mut context = context(session_id, "abc");
+            context.set_extension("asr.model", json!(model));
+            context
+        };
+
+        stage.execute(&mut with_model("first", "base")).await.expect("records");
+        sessions
+            .save(StoredSession {
+                session_id: "first".to_string(),
+                tenant_id: None,
+                audio: AudioChunk::mono(16_000, Vec::new()),
+                transcript: Transcript {
+                    language: LanguageTag::en(),
+                    segments: Vec::new(),
+                },
+                aligned_words: Vec::new(),
+            })
+            .await
+            .unwrap();
+
+        let mut other_model = with_model("second", "large-v3");
+        stage.execute(&mut other_model).await.expect("records");
+        assert!(!other_model.is_finished_early());
+
+        let mut other_hint = with_model("third", "base");
+        other_hint.language_hint = Some(LanguageTag::en());
+        stage.execute(&mut other_hint).await.expect("records");
+        assert!(!other_hint.is_finished_early());
+
+        let mut same = with_model("fourth", "base");
+        stage.execute(&mut same).await.expect("answers");
+        assert!(same.is_finished_early());
+        assert_eq!(same.extension("dedup.session_id"), Some(&json!("first")));
+    }
+
+    #[tokio::test]
+    async fn oldest_fingerprint_is_evicted_first() {
+        let index = InMemoryDuplicateIndex::new(1);
+        index.record("a", "s1").await.unwrap();
+        index.record("b", "s2").await.unwrap();
+        assert_eq!(index.find("a").await.unwrap(), None);
+        assert_eq!(index.find("b").await.unwrap().as_deref(), Some("s2"));
+    }
+}
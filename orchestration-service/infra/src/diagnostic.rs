@@ -189,6 +189,12 @@ mod tests {
             start_ms: 0,
             end_ms: 250,
             confidence: 0.95,
+            speaker: None,
+            start_sample: None,
+            end_sample: None,
+            energy_rms: None,
+            pitch_hz: None,
+            out_of_band: false,
         }];
 
         stage.execute(&mut context).await.expect("dump should succeed");
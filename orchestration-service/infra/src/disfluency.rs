@@ -0,0 +1,180 @@
+use std::collections::{HashMap, HashSet};
+
+use async_trait::async_trait;
+use orchestration_domain::{
+    DomainError, LanguageTag, PipelineContext, PipelineStage, TranscriptToken,
+};
+
+/// Sets `disfluency` on the tokens of filler words ("euh", "um") and of words
+/// repeated back to back ("I I think" tags the first "I"), keeping them in
+/// the transcript for speech-coaching consumers. Fillers are listed per
+/// BCP-47 tag and the most specific entry of the segment language wins.
+/// Segments without tokens are left as they are.
+pub struct DisfluencyTaggingStage {
+    fillers: HashMap<String, HashSet<String>>,
+    tag_repetitions: bool,
+}
+
+impl DisfluencyTaggingStage {
+    pub fn new(fillers: HashMap<String, Vec<String>>, tag_repetitions: bool) -> Self {
+        Self {
+            fillers: fillers
+                .into_iter()
+                .map(|(language, words)| {
+                    (language, words.iter().map(|word| word_key(word)).collect())
+                })
+                .collect(),
+            tag_repetitions,
+        }
+    }
+
+    fn fillers_for(&self, language: &LanguageTag) -> Option<&HashSet<String>> {
+        language
+            .lookup_chain()
+            .iter()
+            .find_map(|tag| self.fillers.get(tag))
+    }
+}
+
+#[async_trait]
+impl PipelineStage for DisfluencyTaggingStage {
+    fn name(&self) -> &'static str {
+        "tag_disfluencies"
+    }
+
+    async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+        let Some(transcript) = context.transcript.as_mut() else {
+            return Ok(());
+        };
+        let languages: Vec<LanguageTag> = transcript
+            .segments
+            .iter()
+            .map(|segment| transcript.segment_language(segment).clone())
+            .collect();
+        let mut tagged = 0usize;
+        for (segment, language) in transcript.segments.iter_mut().zip(languages) {
+            let fillers = self.fillers_for(&language);
+            let words = word_ranges(&segment.tokens);
+            let keys: Vec<String> = words
+                .iter()
+                .map(|range| word_key(&word_text(&segment.tokens[range.clone()])))
+                .collect();
+            for (index, range) in words.iter().enumerate() {
+                let key = &keys[index];
+                let filler = fillers.is_some_and(|fillers| fillers.contains(key));
+                let repeated = self.tag_repetitions
+                    && !key.is_empty()
+                    && keys.get(index + 1).is_some_and(|next| next == key);
+                if filler || repeated {
+                    for token in &mut segment.tokens[range.clone()] {
+                        token.disfluency = true;
+                    }
+                    tagged += 1;
+                }
+            }
+        }
+        tracing::debug!(disfluent_word_count = tagged, "tagged disfluencies");
+        Ok(())
+    }
+}
+
+/// Token ranges of the words of a segment; a token starting with whitespace
+/// starts a word.
+fn word_ranges(tokens: &[TranscriptToken]) -> Vec<std::ops::Range<usize>> {
+    let mut ranges: Vec<std::ops::Range<usize>> = Vec::new();
+    for (index, token) in tokens.iter().enumerate() {
+        match ranges.last_mut() {
+            Some(range) if !token.text.starts_with(char::is_whitespace) => range.end = index + 1,
+            _ => ranges.push(index..index + 1),
+        }
+    }
+    ranges
+}
+
+fn word_text(tokens: &[TranscriptToken]) -> String {
+    tokens.iter().map(|token| token.text.as_str()).collect()
+}
+
+/// Lowercase alphanumerics, so "Um," matches the filler "um".
+fn word_key(word: &str) -> String {
+    word.chars()
+        .filter(|character| character.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use orchestration_domain::{Transcript, TranscriptSegment};
+
+    use super::*;
+
+    fn segment(tokens: &[&str], language: Option<LanguageTag>) -> TranscriptSegment {
+        TranscriptSegment {
+            text: tokens.concat(),
+            start_ms: 0,
+            end_ms: 1_000,
+            tokens: tokens
+                .iter()
+                .map(|text| TranscriptToken {
+                    text: text.to_string(),
+                    start_ms: 0,
+                    end_ms: 1_000,
+                    confidence: 1.0,
+                    start_sample: None,
+                    end_sample: None,
+                    disfluency: false,
+                })
+                .collect(),
+            speaker: None,
+            language,
+            no_speech_prob: None,
+            avg_logprob: None,
+        }
+    }
+
+    fn tagged(segment: &TranscriptSegment) -> Vec<&str> {
+        segment
+            .tokens
+            .iter()
+            .filter(|token| token.disfluency)
+            .map(|token| token.text.as_str())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn fillers_are_tagged_per_language_and_repeats_once() {
+        let stage = DisfluencyTaggingStage::new(
+            HashMap::from([
+                ("en".to_string(), vec!["um".to_string()]),
+                ("fr".to_string(), vec!["euh".to_string()]),
+            ]),
+            true,
+        );
+        let mut context = PipelineContext::new("s", None);
+        context.transcript = Some(Transcript {
+            language: LanguageTag::parse("en-GB").unwrap(),
+            segments: vec![
+                segment(&[" Um", ",", " I", " I", " think", " euh"], None),
+                segment(&[" euh", " je", " pense", " um"], Some(LanguageTag::fr())),
+            ],
+        });
+        stage.execute(&mut context).await.expect("tags");
+
+        let segments = context.transcript.unwrap().segments;
+        assert_eq!(tagged(&segments[0]), vec![" Um", ",", " I"]);
+        assert_eq!(tagged(&segments[1]), vec![" euh"]);
+    }
+
+    #[tokio::test]
+    async fn repetitions_can_be_left_untagged() {
+        let stage = DisfluencyTaggingStage::new(HashMap::new(), false);
+        let mut context = PipelineContext::new("s", None);
+        context.transcript = Some(Transcript {
+            language: LanguageTag::en(),
+            segments: vec![segment(&[" the", " the", " end"], None)],
+        });
+        stage.execute(&mut context).await.expect("tags");
+        assert!(tagged(&context.transcript.unwrap().segments[0]).is_empty());
+    }
+}
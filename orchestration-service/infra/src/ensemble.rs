@@ -0,0 +1,165 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use orchestration_domain::{DomainError, DomainEvent, PipelineContext, PipelineStage, Transcript};
+use serde_json::json;
+
+/// One recognizer of an [`EnsembleTranscribeStage`]: a transcription stage
+/// and the model it is asked for, if any.
+#[derive(Clone)]
+pub struct EnsembleMember {
+    pub stage: Arc<dyn PipelineStage>,
+    pub model: Option<String>,
+}
+
+/// Runs every member on the same audio concurrently and merges their
+/// transcripts with [`Transcript::rover`]. Members that fail are left out of
+/// the vote; the stage fails only when all of them do.
+pub struct EnsembleTranscribeStage {
+    members: Vec<EnsembleMember>,
+}
+
+impl EnsembleTranscribeStage {
+    pub fn new(members: Vec<EnsembleMember>) -> Self {
+        Self { members }
+    }
+}
+
+#[async_trait]
+impl PipelineStage for EnsembleTranscribeStage {
+    fn name(&self) -> &'static str {
+        "ensemble_transcribe"
+    }
+
+    async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+        let runs = self.members.iter().map(|member| {
+            let mut run = context.clone();
+            run.events.clear();
+            if let Some(model) = &member.model {
+                run.set_extension("asr.model", json!(model));
+            }
+            async move {
+                let result = member.stage.execute(&mut run).await;
+                (member, result.map(|()| run))
+            }
+        });
+
+        let mut hypotheses: Vec<Transcript> = Vec::new();
+        let mut session_id = None;
+        let mut last_error = None;
+        for (member, result) in futures::future::join_all(runs).await {
+            match result {
+                Ok(run) => {
+                    session_id.get_or_insert(run.session_id);
+                    hypotheses.extend(run.transcript);
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        stage = member.stage.name(),
+                        model = member.model.as_deref().unwrap_or("default"),
+                        error = %err,
+                        "ensemble member failed"
+                    );
+                    last_error = Some(err);
+                }
+            }
+        }
+
+        let Some(transcript) = Transcript::rover(&hypotheses) else {
+            return Err(last_error.unwrap_or_else(|| {
+                DomainError::internal_error("ensemble has no members to transcribe with")
+            }));
+        };
+        tracing::debug!(
+            hypothesis_count = hypotheses.len(),
+            segment_count = transcript.segments.len(),
+            "merged ensemble transcripts"
+        );
+        if let Some(session_id) = session_id {
+            context.session_id = session_id;
+        }
+        context.transcript = Some(transcript.clone());
+        context.events.push(DomainEvent::FinalTranscript { transcript });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use orchestration_domain::{LanguageTag, TranscriptSegment};
+
+    use super::*;
+
+    /// Transcribes as a fixed text, or as the requested model's name.
+    struct FixedStage(Option<&'static str>);
+
+    #[async_trait]
+    impl PipelineStage for FixedStage {
+        fn name(&self) -> &'static str {
+            "fixed"
+        }
+
+        async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+            let text = match self.0 {
+                Some(text) => text.to_string(),
+                None => context
+                    .extension("asr.model")
+                    .and_then(|value| value.as_str())
+                    .ok_or_else(|| DomainError::internal_error("no model"))?
+                    .to_string(),
+            };
+            context.transcript = Some(Transcript {
+                language: LanguageTag::en(),
+                segments: vec![TranscriptSegment {
+                    text,
+                    start_ms: 0,
+                    end_ms: 1_000,
+                    tokens: Vec::new(),
+                    speaker: None,
+                    language: None,
+                    no_speech_prob: None,
+                    avg_logprob: None,
+                }],
+            });
+            Ok(())
+        }
+    }
+
+    fn member(text: Option<&'static str>, model: Option<&str>) -> EnsembleMember {
+        EnsembleMember {
+            stage: Arc::new(FixedStage(text)),
+            model: model.map(str::to_string),
+        }
+    }
+
+    #[tokio::test]
+    async fn majority_of_members_wins() {
+        let stage = EnsembleTranscribeStage::new(vec![
+            member(Some("run kube control"), None),
+            member(None, Some("run kubectl")),
+            member(Some("run kubectl"), None),
+        ]);
+        let mut context = PipelineContext::new("s", None);
+        stage.execute(&mut context).await.expect("merges");
+
+        assert_eq!(context.transcript.expect("merged").segments[0].text, "run kubectl");
+        assert!(matches!(
+            context.events.as_slice(),
+            [DomainEvent::FinalTranscript { .. }]
+        ));
+    }
+
+    #[tokio::test]
+    async fn failed_members_are_skipped_until_none_are_left() {
+        let stage = EnsembleTranscribeStage::new(vec![
+            member(None, None),
+            member(Some("hello"), None),
+        ]);
+        let mut context = PipelineContext::new("s", None);
+        stage.execute(&mut context).await.expect("one member left");
+        assert_eq!(context.transcript.expect("merged").segments[0].text, "hello");
+
+        let failing = EnsembleTranscribeStage::new(vec![member(None, None)]);
+        assert!(failing.execute(&mut PipelineContext::new("s", None)).await.is_err());
+    }
+}
@@ -1,9 +1,39 @@
 pub mod audio;
+pub mod carry_over;
+pub mod dedup;
 pub mod diagnostic;
+pub mod disfluency;
+pub mod ensemble;
+pub mod normalization;
+pub mod provided_transcript;
+pub mod quality_gate;
+pub mod recording;
+pub mod result_store;
+#[cfg(feature = "scripting")]
+pub mod script_step;
+pub mod session_store;
 pub mod snapshot;
 pub mod swap_tts_audio;
+pub mod two_pass;
+#[cfg(feature = "wasm")]
+pub mod wasm_step;
 
 pub use audio::{AudioPreprocessStage, ResampleStage};
+pub use carry_over::ContextCarryOverStage;
+pub use dedup::{DuplicateLookupStage, InMemoryDuplicateIndex};
 pub use diagnostic::DiagnosticDumpStage;
+pub use disfluency::DisfluencyTaggingStage;
+pub use ensemble::{EnsembleMember, EnsembleTranscribeStage};
+pub use normalization::{ReplacementRule, TranscriptNormalizationStage};
+pub use provided_transcript::ProvidedTranscriptStage;
+pub use quality_gate::{AudioQualityGateStage, QualityThresholds};
+pub use recording::{RecordSessionStage, RecordingRetention};
+pub use result_store::{CueLimits, ResultFormat, StoreResultStage};
+#[cfg(feature = "scripting")]
+pub use script_step::ScriptStepStage;
+pub use session_store::{InMemorySessionStore, StoreSessionStage};
 pub use snapshot::SnapshotOriginalTimingsStage;
 pub use swap_tts_audio::SwapTtsAudioStage;
+pub use two_pass::TwoPassTranscribeStage;
+#[cfg(feature = "wasm")]
+pub use wasm_step::{WasmLimits, WasmStepStage};
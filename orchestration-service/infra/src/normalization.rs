@@ -0,0 +1,186 @@
+use async_trait::async_trait;
+use orchestration_domain::{DomainError, LanguageTag, PipelineContext, PipelineStage};
+use regex::{NoExpand, Regex, RegexBuilder};
+
+/// One entry of a replacement dictionary.
+#[derive(Debug, Clone)]
+pub struct ReplacementRule {
+    pattern: Regex,
+    replacement: String,
+    /// Whether `replacement` may reference capture groups (`$1`).
+    expand: bool,
+    /// Lookup-chain entry the segment language must contain, e.g. `en`
+    /// matches `en` and `en-US`. `None` applies to every language.
+    language: Option<String>,
+}
+
+impl ReplacementRule {
+    /// Replaces occurrences of `phrase` that do not start or end inside a
+    /// word.
+    pub fn exact(
+        phrase: &str,
+        replacement: impl Into<String>,
+        ignore_case: bool,
+        language: Option<&LanguageTag>,
+    ) -> Result<Self, DomainError> {
+        let is_word = |c: char| c.is_alphanumeric() || c == '_';
+        let start = if phrase.starts_with(is_word) { r"\b" } else { "" };
+        let end = if phrase.ends_with(is_word) { r"\b" } else { "" };
+        let pattern = format!("{start}{}{end}", regex::escape(phrase));
+        Ok(Self {
+            pattern: compile(&pattern, ignore_case)?,
+            replacement: replacement.into(),
+            expand: false,
+            language: language.map(ToString::to_string),
+        })
+    }
+
+    /// Replaces matches of a regular expression; `replacement` may use
+    /// capture groups.
+    pub fn regex(
+        pattern: &str,
+        replacement: impl Into<String>,
+        ignore_case: bool,
+        language: Option<&LanguageTag>,
+    ) -> Result<Self, DomainError> {
+        Ok(Self {
+            pattern: compile(pattern, ignore_case)?,
+            replacement: replacement.into(),
+            expand: true,
+            language: language.map(ToString::to_string),
+        })
+    }
+
+    fn applies_to(&self, language: &LanguageTag) -> bool {
+        self.language
+            .as_ref()
+            .is_none_or(|wanted| language.lookup_chain().contains(wanted))
+    }
+
+    fn apply(&self, text: &str) -> String {
+        if self.expand {
+            self.pattern.replace_all(text, self.replacement.as_str()).into_owned()
+        } else {
+            self.pattern
+                .replace_all(text, NoExpand(&self.replacement))
+                .into_owned()
+        }
+    }
+}
+
+fn compile(pattern: &str, ignore_case: bool) -> Result<Regex, DomainError> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(ignore_case)
+        .build()
+        .map_err(|err| {
+            DomainError::invalid_input(&format!("invalid replacement pattern `{pattern}`: {err}"))
+        })
+}
+
+/// Applies a replacement dictionary to the transcript, in rule order, so
+/// deployments can fix systematic mis-recognitions without retraining. Place
+/// it before `alignment_enrich` so the aligner sees the corrected words.
+/// Tokens of changed segments are dropped because they no longer spell the
+/// segment text.
+pub struct TranscriptNormalizationStage {
+    rules: Vec<ReplacementRule>,
+}
+
+impl TranscriptNormalizationStage {
+    pub fn new(rules: Vec<ReplacementRule>) -> Self {
+        Self { rules }
+    }
+}
+
+#[async_trait]
+impl PipelineStage for TranscriptNormalizationStage {
+    fn name(&self) -> &'static str {
+        "normalize_transcript"
+    }
+
+    async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+        let Some(transcript) = context.transcript.as_mut() else {
+            return Ok(());
+        };
+        let languages: Vec<LanguageTag> = transcript
+            .segments
+            .iter()
+            .map(|segment| transcript.segment_language(segment).clone())
+            .collect();
+        let mut changed = 0usize;
+        for (segment, language) in transcript.segments.iter_mut().zip(languages) {
+            let text = self
+                .rules
+                .iter()
+                .filter(|rule| rule.applies_to(&language))
+                .fold(segment.text.clone(), |text, rule| rule.apply(&text));
+            if text != segment.text {
+                segment.text = text;
+                segment.tokens.clear();
+                changed += 1;
+            }
+        }
+        tracing::debug!(changed_segment_count = changed, "normalized transcript");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use orchestration_domain::{Transcript, TranscriptSegment, TranscriptToken};
+
+    use super::*;
+
+    fn segment(text: &str, language: Option<LanguageTag>) -> TranscriptSegment {
+        TranscriptSegment {
+            text: text.to_string(),
+            start_ms: 0,
+            end_ms: 1_000,
+            tokens: vec![TranscriptToken {
+                text: text.to_string(),
+                start_ms: 0,
+                end_ms: 1_000,
+                confidence: 1.0,
+                start_sample: None,
+                end_sample: None,
+                disfluency: false,
+            }],
+            speaker: None,
+            language,
+            no_speech_prob: None,
+            avg_logprob: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn rules_apply_per_language_and_in_order() {
+        let en = LanguageTag::en();
+        let stage = TranscriptNormalizationStage::new(vec![
+            ReplacementRule::exact("kube control", "kubectl", true, Some(&en)).unwrap(),
+            ReplacementRule::regex(r"(\d+) percent", "$1%", false, None).unwrap(),
+        ]);
+        let mut context = PipelineContext::new("s", None);
+        context.transcript = Some(Transcript {
+            language: LanguageTag::parse("en-US").unwrap(),
+            segments: vec![
+                segment("run Kube Control get pods", None),
+                segment("kube controller at 90 percent", None),
+                segment("kube control à 90 percent", Some(LanguageTag::fr())),
+            ],
+        });
+        stage.execute(&mut context).await.expect("normalizes");
+
+        let segments = context.transcript.unwrap().segments;
+        assert_eq!(segments[0].text, "run kubectl get pods");
+        assert!(segments[0].tokens.is_empty());
+        assert_eq!(segments[1].text, "kube controller at 90%");
+        assert_eq!(segments[2].text, "kube control à 90%");
+    }
+
+    #[test]
+    fn invalid_patterns_are_rejected_and_exact_phrases_are_literal() {
+        assert!(ReplacementRule::regex("(", "x", false, None).is_err());
+        let literal = ReplacementRule::exact("c++", "$0", false, None).unwrap();
+        assert_eq!(literal.apply("learn c++ today"), "learn $0 today");
+    }
+}
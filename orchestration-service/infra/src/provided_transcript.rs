@@ -0,0 +1,60 @@
+use async_trait::async_trait;
+use orchestration_domain::{DomainError, DomainEvent, PipelineContext, PipelineStage, Transcript};
+
+/// Transcription step of `align_only` pipelines: takes the transcript the
+/// client sent, stored in the `request.transcript` extension, instead of
+/// calling the ASR service.
+pub struct ProvidedTranscriptStage;
+
+impl ProvidedTranscriptStage {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl PipelineStage for ProvidedTranscriptStage {
+    fn name(&self) -> &'static str {
+        "provided_transcript"
+    }
+
+    async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+        let value = context.take_extension("request.transcript").ok_or_else(|| {
+            DomainError::invalid_input("this pipeline aligns a transcript sent with the request")
+        })?;
+        let transcript: Transcript = serde_json::from_value(value).map_err(|err| {
+            DomainError::invalid_input(&format!("invalid provided transcript: {err}"))
+        })?;
+        tracing::debug!(
+            segment_count = transcript.segments.len(),
+            "using client-provided transcript"
+        );
+        context.transcript = Some(transcript.clone());
+        context.events.push(DomainEvent::FinalTranscript { transcript });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use orchestration_domain::LanguageTag;
+    use serde_json::json;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn provided_transcript_becomes_the_context_transcript() {
+        let stage = ProvidedTranscriptStage::new();
+        let mut context = PipelineContext::new("s", None);
+        assert!(stage.execute(&mut context).await.is_err());
+
+        let transcript = Transcript {
+            language: LanguageTag::fr(),
+            segments: Vec::new(),
+        };
+        context.set_extension("request.transcript", json!(transcript));
+        stage.execute(&mut context).await.expect("uses transcript");
+        assert_eq!(context.transcript.expect("set").language, LanguageTag::fr());
+        assert!(context.extension("request.transcript").is_none());
+    }
+}
@@ -0,0 +1,143 @@
+use async_trait::async_trait;
+use orchestration_domain::{AudioChunk, DomainError, PipelineContext, PipelineStage};
+use serde_json::json;
+
+/// Samples at or beyond this magnitude count as clipped.
+const CLIP_LEVEL: f32 = 0.999;
+
+/// Limits of [`AudioQualityGateStage`].
+#[derive(Debug, Clone)]
+pub struct QualityThresholds {
+    pub min_duration_ms: u64,
+    /// Audio whose RMS is below this level is silent.
+    pub silence_rms_dbfs: f32,
+    /// Largest share of samples at full scale, e.g. `0.01`.
+    pub max_clipped_ratio: f32,
+    /// Accepted input rates; empty accepts any.
+    pub allowed_sample_rates_hz: Vec<u32>,
+}
+
+/// Checks the request audio before any service sees it. Failed checks are
+/// recorded in the `audio.quality` extension as `issues` (`too_short`,
+/// `silent`, `clipped`, `unsupported_sample_rate`); when `reject` is set the
+/// stage also fails the pipeline, which the application reports with the
+/// `audio_quality` error code. Place it first in `pre`, before
+/// `audio_transform` resamples the audio.
+pub struct AudioQualityGateStage {
+    thresholds: QualityThresholds,
+    reject: bool,
+}
+
+impl AudioQualityGateStage {
+    pub fn new(thresholds: QualityThresholds, reject: bool) -> Self {
+        Self { thresholds, reject }
+    }
+
+    fn issues(&self, audio: &AudioChunk) -> Vec<&'static str> {
+        let limits = &self.thresholds;
+        let mut issues = Vec::new();
+        let duration_ms =
+            audio.samples.len() as u64 * 1_000 / u64::from(audio.sample_rate_hz.max(1));
+        if duration_ms < limits.min_duration_ms {
+            issues.push("too_short");
+        }
+        let mean_square = audio.samples.iter().map(|sample| sample * sample).sum::<f32>()
+            / audio.samples.len().max(1) as f32;
+        let rms_dbfs = 10.0 * mean_square.log10();
+        if rms_dbfs < limits.silence_rms_dbfs {
+            issues.push("silent");
+        }
+        let clipped = audio
+            .samples
+            .iter()
+            .filter(|sample| sample.abs() >= CLIP_LEVEL)
+            .count();
+        if clipped as f32 > limits.max_clipped_ratio * audio.samples.len() as f32 {
+            issues.push("clipped");
+        }
+        if !limits.allowed_sample_rates_hz.is_empty()
+            && !limits.allowed_sample_rates_hz.contains(&audio.sample_rate_hz)
+        {
+            issues.push("unsupported_sample_rate");
+        }
+        issues
+    }
+}
+
+#[async_trait]
+impl PipelineStage for AudioQualityGateStage {
+    fn name(&self) -> &'static str {
+        "audio_quality_gate"
+    }
+
+    async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+        let issues = self.issues(&context.audio);
+        let rejected = self.reject && !issues.is_empty();
+        context.set_extension("audio.quality", json!({ "issues": issues, "rejected": rejected }));
+        if issues.is_empty() {
+            return Ok(());
+        }
+        tracing::warn!(
+            session_id = %context.session_id,
+            issues = %issues.join(","),
+            rejected,
+            "audio failed the quality gate"
+        );
+        if rejected {
+            return Err(DomainError::invalid_input(&format!(
+                "audio failed the quality gate: {}",
+                issues.join(", ")
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stage(reject: bool) -> AudioQualityGateStage {
+        AudioQualityGateStage::new(
+            QualityThresholds {
+                min_duration_ms: 500,
+                silence_rms_dbfs: -60.0,
+                max_clipped_ratio: 0.01,
+                allowed_sample_rates_hz: vec![16_000, 48_000],
+            },
+            reject,
+        )
+    }
+
+    fn with_audio(audio: AudioChunk) -> PipelineContext {
+        let mut context = PipelineContext::new("s", None);
+        context.audio = audio;
+        context
+    }
+
+    fn issues(context: &PipelineContext) -> serde_json::Value {
+        context.extension("audio.quality").expect("report")["issues"].clone()
+    }
+
+    #[tokio::test]
+    async fn good_audio_passes() {
+        let speech = test_audio::speech_like(16_000, 1_000);
+        let mut context = with_audio(AudioChunk::mono(16_000, speech));
+        stage(true).execute(&mut context).await.expect("passes");
+        assert_eq!(issues(&context), json!([]));
+    }
+
+    #[tokio::test]
+    async fn every_failed_check_is_reported() {
+        let mut samples = vec![0.0; 4_000];
+        samples.extend([1.0; 100]);
+        let mut context = with_audio(AudioChunk::mono(8_000, samples));
+        stage(false).execute(&mut context).await.expect("only flags");
+        assert_eq!(issues(&context), json!(["clipped", "unsupported_sample_rate"]));
+
+        let mut silent = with_audio(AudioChunk::mono(16_000, vec![0.0; 1_600]));
+        stage(true).execute(&mut silent).await.expect_err("rejects");
+        assert_eq!(issues(&silent), json!(["too_short", "silent"]));
+        assert_eq!(silent.extension("audio.quality").unwrap()["rejected"], json!(true));
+    }
+}
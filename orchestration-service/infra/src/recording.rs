@@ -0,0 +1,269 @@
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use orchestration_domain::{
+    DomainError, DomainEvent, ObjectStorePort, PipelineContext, PipelineStage, Transcript,
+};
+use serde::Serialize;
+
+/// Writes the session's raw audio (`<prefix>/<session>/audio.wav`) and the
+/// events of the run (`<prefix>/<session>/events-<unix_ms>.json`) to an
+/// object store, for audit and model improvement; a tenant's sessions go
+/// under `<prefix>/<tenant>/<session>`. Run it last in `post` so it sees
+/// every event. Streaming sessions record once per flush: the WAV
+/// is replaced with the audio held so far and each flush adds an events file.
+/// A failed write is logged and does not fail the pipeline.
+pub struct RecordSessionStage {
+    store: Arc<dyn ObjectStorePort>,
+    prefix: String,
+}
+
+/// Contents of an events file.
+#[derive(Serialize)]
+struct RecordedRun<'a> {
+    session_id: &'a str,
+    recorded_at_ms: u64,
+    sample_rate_hz: u32,
+    transcript: &'a Option<Transcript>,
+    events: &'a [DomainEvent],
+}
+
+impl RecordSessionStage {
+    pub fn new(store: Arc<dyn ObjectStorePort>, prefix: impl Into<String>) -> Self {
+        Self {
+            store,
+            prefix: prefix.into(),
+        }
+    }
+
+    async fn record(&self, context: &PipelineContext) -> Result<(), DomainError> {
+        let dir = session_dir(&self.prefix, context);
+        let recorded_at_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or_default();
+
+        let wav = wav_io::encode_wav_f32_mono(&context.audio.samples, context.audio.sample_rate_hz);
+        self.store.put(&format!("{dir}/audio.wav"), wav).await?;
+
+        let run = RecordedRun {
+            session_id: &context.session_id,
+            recorded_at_ms,
+            sample_rate_hz: context.audio.sample_rate_hz,
+            transcript: &context.transcript,
+            events: &context.events,
+        };
+        let events = serde_json::to_vec_pretty(&run).map_err(|err| {
+            DomainError::internal_error(&format!("failed to serialize events: {err}"))
+        })?;
+        self.store
+            .put(&format!("{dir}/events-{recorded_at_ms}.json"), events)
+            .await
+    }
+}
+
+#[async_trait]
+impl PipelineStage for RecordSessionStage {
+    fn name(&self) -> &'static str {
+        "record_session"
+    }
+
+    async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+        if let Err(err) = self.record(context).await {
+            tracing::warn!(
+                error = %err,
+                session_id = %context.session_id,
+                "failed to record session"
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Deletes recordings older than `max_age`, judged by each object's last
+/// write.
+pub struct RecordingRetention {
+    store: Arc<dyn ObjectStorePort>,
+    prefix: String,
+    max_age: Duration,
+}
+
+impl RecordingRetention {
+    pub fn new(
+        store: Arc<dyn ObjectStorePort>,
+        prefix: impl Into<String>,
+        max_age: Duration,
+    ) -> Self {
+        Self {
+            store,
+            prefix: prefix.into(),
+            max_age,
+        }
+    }
+
+    /// Number of objects deleted.
+    pub async fn purge_expired(&self) -> Result<usize, DomainError> {
+        let Some(cutoff) = SystemTime::now().checked_sub(self.max_age) else {
+            return Ok(0);
+        };
+        let mut deleted = 0;
+        for object in self.store.list(self.prefix.trim_end_matches('/')).await? {
+            if object.modified_at < cutoff {
+                self.store.delete(&object.key).await?;
+                deleted += 1;
+            }
+        }
+        Ok(deleted)
+    }
+
+    /// Purges every `interval` until the runtime shuts down.
+    pub fn spawn(self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticks = tokio::time::interval(interval);
+            loop {
+                ticks.tick().await;
+                match self.purge_expired().await {
+                    Ok(0) => {}
+                    Ok(deleted) => tracing::info!(deleted, "purged expired recordings"),
+                    Err(err) => tracing::warn!(error = %err, "recording retention sweep failed"),
+                }
+            }
+        })
+    }
+}
+
+/// `<prefix>/<session>`, or `<prefix>/<tenant>/<session>` for a tenant's
+/// session.
+pub(crate) fn session_dir(prefix: &str, context: &PipelineContext) -> String {
+    let prefix = prefix.trim_end_matches('/');
+    let session = key_segment(&context.session_id);
+    match &context.tenant_id {
+        Some(tenant_id) => format!("{prefix}/{}/{session}", key_segment(tenant_id)),
+        None => format!("{prefix}/{session}"),
+    }
+}
+
+/// `session_id` as a single key segment: no `/` and no `..`, whatever the
+/// client sent.
+fn key_segment(session_id: &str) -> String {
+    let segment: String = session_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if segment.is_empty() || segment.chars().all(|c| c == '.') {
+        "_".to_string()
+    } else {
+        segment
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use orchestration_domain::StoredObject;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct MemoryStore {
+        objects: Mutex<HashMap<String, (Vec<u8>, SystemTime)>>,
+    }
+
+    #[async_trait]
+    impl ObjectStorePort for MemoryStore {
+        async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<(), DomainError> {
+            let mut objects = self.objects.lock().unwrap();
+            objects.insert(key.to_string(), (bytes, SystemTime::now()));
+            Ok(())
+        }
+
+        async fn list(&self, prefix: &str) -> Result<Vec<StoredObject>, DomainError> {
+            let objects = self.objects.lock().unwrap();
+            Ok(objects
+                .iter()
+                .filter(|(key, _)| key.starts_with(prefix))
+                .map(|(key, (_, modified_at))| StoredObject {
+                    key: key.clone(),
+                    modified_at: *modified_at,
+                })
+                .collect())
+        }
+
+        async fn delete(&self, key: &str) -> Result<(), DomainError> {
+            self.objects.lock().unwrap().remove(key);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn records_audio_and_events_per_session() {
+        let store = Arc::new(MemoryStore::default());
+        let stage = RecordSessionStage::new(store.clone(), "recordings/");
+        let mut context = PipelineContext::new("call/../1", None);
+        context.audio.sample_rate_hz = 16_000;
+        context.audio.samples = vec![0.1, -0.1];
+        context.events.push(DomainEvent::StageStarted {
+            stage: "asr".to_string(),
+        });
+
+        stage.execute(&mut context).await.expect("stage runs");
+
+        let objects = store.objects.lock().unwrap();
+        let wav = &objects["recordings/call_.._1/audio.wav"].0;
+        assert_eq!(wav_io::decode_wav(wav).unwrap().samples, vec![0.1, -0.1]);
+        let (key, (events, _)) = objects
+            .iter()
+            .find(|(key, _)| key.contains("/events-"))
+            .expect("events recorded");
+        assert!(key.starts_with("recordings/call_.._1/"));
+        let events: serde_json::Value = serde_json::from_slice(events).unwrap();
+        assert_eq!(events["events"][0]["StageStarted"]["stage"], "asr");
+    }
+
+    #[tokio::test]
+    async fn retention_deletes_only_expired_recordings() {
+        let store = Arc::new(MemoryStore::default());
+        store.put("recordings/new/audio.wav", Vec::new()).await.unwrap();
+        store.objects.lock().unwrap().insert(
+            "recordings/old/audio.wav".to_string(),
+            (Vec::new(), SystemTime::now() - Duration::from_secs(7200)),
+        );
+        store.objects.lock().unwrap().insert(
+            "results/old.json".to_string(),
+            (Vec::new(), SystemTime::now() - Duration::from_secs(7200)),
+        );
+
+        let retention =
+            RecordingRetention::new(store.clone(), "recordings/", Duration::from_secs(3600));
+        assert_eq!(retention.purge_expired().await.unwrap(), 1);
+
+        let objects = store.objects.lock().unwrap();
+        assert!(objects.contains_key("recordings/new/audio.wav"));
+        assert!(objects.contains_key("results/old.json"));
+    }
+
+    #[test]
+    fn tenant_sessions_get_their_own_directory() {
+        let mut context = PipelineContext::new("s1", None);
+        assert_eq!(session_dir("recordings/", &context), "recordings/s1");
+        context.tenant_id = Some("acme/eu".to_string());
+        assert_eq!(session_dir("recordings", &context), "recordings/acme_eu/s1");
+    }
+
+    #[test]
+    fn session_ids_stay_one_segment() {
+        assert_eq!(key_segment("abc-1:mic"), "abc-1:mic");
+        assert_eq!(key_segment("../etc"), ".._etc");
+        assert_eq!(key_segment(".."), "_");
+        assert_eq!(key_segment(""), "_");
+    }
+}
@@ -0,0 +1,249 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use orchestration_domain::{
+    DomainError, DomainEvent, ObjectStorePort, PipelineContext, PipelineStage, Transcript,
+    WordTiming,
+};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::recording::session_dir;
+
+/// Files [`StoreResultStage`] writes per session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResultFormat {
+    /// `transcript.json`: session id, transcript and aligned words.
+    Json,
+    /// `transcript.srt`
+    Srt,
+    /// `transcript.vtt`
+    Vtt,
+}
+
+impl ResultFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "json" => Some(Self::Json),
+            "srt" => Some(Self::Srt),
+            "vtt" => Some(Self::Vtt),
+            _ => None,
+        }
+    }
+
+    fn file_name(self) -> &'static str {
+        match self {
+            Self::Json => "transcript.json",
+            Self::Srt => "transcript.srt",
+            Self::Vtt => "transcript.vtt",
+        }
+    }
+}
+
+/// Longest subtitle cue, in time and characters.
+#[derive(Debug, Clone, Copy)]
+pub struct CueLimits {
+    pub max_ms: u64,
+    pub max_chars: usize,
+}
+
+/// Contents of `transcript.json`.
+#[derive(Serialize)]
+struct StoredResult<'a> {
+    session_id: &'a str,
+    transcript: &'a Transcript,
+    aligned_words: &'a [WordTiming],
+}
+
+/// Writes the final transcript of a session under
+/// `<prefix>/<session>/transcript.<ext>` in each configured format, so
+/// clients can collect results from the store instead of holding the
+/// request open. The keys written are listed in the `result_store.keys`
+/// extension. Unlike `record_session`, a failed write fails the step: the
+/// stored result is the output.
+pub struct StoreResultStage {
+    store: Arc<dyn ObjectStorePort>,
+    prefix: String,
+    formats: Vec<ResultFormat>,
+    cue_limits: CueLimits,
+}
+
+impl StoreResultStage {
+    pub fn new(
+        store: Arc<dyn ObjectStorePort>,
+        prefix: impl Into<String>,
+        formats: Vec<ResultFormat>,
+        cue_limits: CueLimits,
+    ) -> Self {
+        Self {
+            store,
+            prefix: prefix.into(),
+            formats,
+            cue_limits,
+        }
+    }
+
+    fn render(
+        &self,
+        format: ResultFormat,
+        context: &PipelineContext,
+        transcript: &Transcript,
+    ) -> Result<Vec<u8>, DomainError> {
+        let cues = || transcript.resegment(self.cue_limits.max_ms, self.cue_limits.max_chars);
+        match format {
+            ResultFormat::Json => {
+                let result = StoredResult {
+                    session_id: &context.session_id,
+                    transcript,
+                    aligned_words: aligned_words(context),
+                };
+                serde_json::to_vec_pretty(&result).map_err(|err| {
+                    DomainError::internal_error(&format!("failed to serialize result: {err}"))
+                })
+            }
+            ResultFormat::Srt => Ok(cues().to_srt().into_bytes()),
+            ResultFormat::Vtt => Ok(cues().to_webvtt().into_bytes()),
+        }
+    }
+}
+
+#[async_trait]
+impl PipelineStage for StoreResultStage {
+    fn name(&self) -> &'static str {
+        "store_result"
+    }
+
+    async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+        let transcript = context
+            .transcript
+            .as_ref()
+            .ok_or_else(|| DomainError::internal_error("store_result: no transcript to store"))?;
+        let dir = session_dir(&self.prefix, context);
+
+        let mut keys = Vec::with_capacity(self.formats.len());
+        for format in &self.formats {
+            let key = format!("{dir}/{}", format.file_name());
+            let bytes = self.render(*format, context, transcript)?;
+            self.store.put(&key, bytes).await?;
+            keys.push(key);
+        }
+        tracing::debug!(session_id = %context.session_id, ?keys, "stored session result");
+        context.set_extension("result_store.keys", json!(keys));
+        Ok(())
+    }
+}
+
+/// Words of the alignment step, wherever it left them.
+fn aligned_words(context: &PipelineContext) -> &[WordTiming] {
+    if !context.aligned_words.is_empty() {
+        return &context.aligned_words;
+    }
+    context
+        .events
+        .iter()
+        .find_map(|event| match event {
+            DomainEvent::AlignmentUpdate { words } => Some(words.as_slice()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use orchestration_domain::{LanguageTag, StoredObject, TranscriptSegment};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct MemoryStore {
+        objects: Mutex<HashMap<String, Vec<u8>>>,
+    }
+
+    #[async_trait]
+    impl ObjectStorePort for MemoryStore {
+        async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<(), DomainError> {
+            self.objects.lock().unwrap().insert(key.to_string(), bytes);
+            Ok(())
+        }
+
+        async fn list(&self, _prefix: &str) -> Result<Vec<StoredObject>, DomainError> {
+            Ok(Vec::new())
+        }
+
+        async fn delete(&self, key: &str) -> Result<(), DomainError> {
+            self.objects.lock().unwrap().remove(key);
+            Ok(())
+        }
+    }
+
+    fn stage(store: Arc<MemoryStore>, formats: Vec<ResultFormat>) -> StoreResultStage {
+        let limits = CueLimits {
+            max_ms: 7_000,
+            max_chars: 84,
+        };
+        StoreResultStage::new(store, "results", formats, limits)
+    }
+
+    #[tokio::test]
+    async fn writes_each_format_under_the_session() {
+        let store = Arc::new(MemoryStore::default());
+        let stage = stage(
+            store.clone(),
+            vec![ResultFormat::Json, ResultFormat::Srt, ResultFormat::Vtt],
+        );
+        let mut context = PipelineContext::new("s1", None);
+        context.transcript = Some(Transcript {
+            language: LanguageTag::en(),
+            segments: vec![TranscriptSegment {
+                text: " Hello.".to_string(),
+                start_ms: 0,
+                end_ms: 1_200,
+                tokens: Vec::new(),
+                speaker: None,
+                language: None,
+                no_speech_prob: None,
+                avg_logprob: None,
+            }],
+        });
+
+        stage.execute(&mut context).await.expect("stage runs");
+
+        let objects = store.objects.lock().unwrap();
+        let json: serde_json::Value =
+            serde_json::from_slice(&objects["results/s1/transcript.json"]).unwrap();
+        assert_eq!(json["session_id"], "s1");
+        assert_eq!(
+            String::from_utf8_lossy(&objects["results/s1/transcript.srt"]),
+            "1\n00:00:00,000 --> 00:00:01,200\nHello.\n\n"
+        );
+        assert!(objects["results/s1/transcript.vtt"].starts_with(b"WEBVTT"));
+        assert_eq!(
+            context.extension("result_store.keys"),
+            Some(&json!([
+                "results/s1/transcript.json",
+                "results/s1/transcript.srt",
+                "results/s1/transcript.vtt",
+            ]))
+        );
+    }
+
+    #[tokio::test]
+    async fn fails_without_a_transcript() {
+        let store = Arc::new(MemoryStore::default());
+        let mut context = PipelineContext::new("s1", None);
+        assert!(stage(store.clone(), vec![ResultFormat::Json])
+            .execute(&mut context)
+            .await
+            .is_err());
+        assert!(store.objects.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn formats_parse_from_their_extension() {
+        assert_eq!(ResultFormat::parse("vtt"), Some(ResultFormat::Vtt));
+        assert_eq!(ResultFormat::parse("docx"), None);
+    }
+}
@@ -0,0 +1,196 @@
+use async_trait::async_trait;
+use orchestration_domain::{DomainError, PipelineContext, PipelineStage, Transcript};
+use regex::Regex;
+use rhai::{Dynamic, Engine, EvalAltResult, Scope, AST};
+use serde_json::json;
+
+/// Function a script defines; it takes the transcript and returns it.
+const ENTRY_POINT: &str = "process";
+
+/// Runs an operator-provided [Rhai](https://rhai.rs) script over the
+/// transcript, for clean-up rules that should not need a rebuild.
+///
+/// The script defines `fn process(transcript)` and returns the transcript,
+/// edited in place or rebuilt; `transcript.segments` is an array of maps
+/// with `text`, `start_ms`, `end_ms`, `tokens`, .. as in the JSON API. On
+/// top of the Rhai built-ins, `regex_replace(text, pattern, replacement)`
+/// replaces every match (`$1` expands capture groups). Scripts cannot reach
+/// files or the network and stop after `max_operations`.
+///
+/// ```rhai
+/// fn process(transcript) {
+///     for i in 0..transcript.segments.len() {
+///         let text = transcript.segments[i].text;
+///         transcript.segments[i].text = regex_replace(text, "\\b(um|uh)\\b ?", "");
+///     }
+///     transcript
+/// }
+/// ```
+pub struct ScriptStepStage {
+    engine: Engine,
+    ast: AST,
+}
+
+impl ScriptStepStage {
+    /// Compiles `source`; fails when it does not parse or defines no
+    /// `process(transcript)`.
+    pub fn new(source: &str, max_operations: u64) -> Result<Self, DomainError> {
+        let mut engine = Engine::new();
+        engine.set_max_operations(max_operations);
+        engine.register_fn("regex_replace", regex_replace);
+        let ast = engine
+            .compile(source)
+            .map_err(|err| DomainError::invalid_input(&format!("script_step: {err}")))?;
+        if !ast
+            .iter_functions()
+            .any(|function| function.name == ENTRY_POINT && function.params.len() == 1)
+        {
+            return Err(DomainError::invalid_input(
+                "script_step: the script defines no `fn process(transcript)`",
+            ));
+        }
+        Ok(Self { engine, ast })
+    }
+}
+
+#[async_trait]
+impl PipelineStage for ScriptStepStage {
+    fn name(&self) -> &'static str {
+        "script_step"
+    }
+
+    async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+        let transcript = context
+            .transcript
+            .as_ref()
+            .ok_or_else(|| DomainError::internal_error("no transcript available"))?;
+        let input = rhai::serde::to_dynamic(transcript).map_err(script_error)?;
+        let output: Dynamic = self
+            .engine
+            .call_fn(&mut Scope::new(), &self.ast, ENTRY_POINT, (input,))
+            .map_err(script_error)?;
+        let transcript: Transcript = rhai::serde::from_dynamic(&output).map_err(script_error)?;
+
+        context.set_extension("script.segment_count", json!(transcript.segments.len()));
+        context.transcript = Some(transcript);
+        Ok(())
+    }
+}
+
+fn regex_replace(
+    text: &str,
+    pattern: &str,
+    replacement: &str,
+) -> Result<String, Box<EvalAltResult>> {
+    let regex = Regex::new(pattern).map_err(|err| format!("invalid pattern `{pattern}`: {err}"))?;
+    Ok(regex.replace_all(text, replacement).into_owned())
+}
+
+fn script_error(error: Box<EvalAltResult>) -> DomainError {
+    DomainError::internal_error(&format!("script_step: {error}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use orchestration_domain::{LanguageTag, TranscriptSegment};
+
+    fn segment(text: &str, start_ms: u64, end_ms: u64) -> TranscriptSegment {
+        TranscriptSegment {
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+            tokens: Vec::new(),
+            speaker: None,
+            language: None,
+            no_speech_prob: None,
+            avg_logprob: None,
+        }
+    }
+
+    fn context_with(segments: Vec<TranscriptSegment>) -> PipelineContext {
+        let mut context = PipelineContext::new("session", None);
+        context.transcript = Some(Transcript {
+            language: LanguageTag::en(),
+            segments,
+        });
+        context
+    }
+
+    #[tokio::test]
+    async fn script_rewrites_segment_text_with_regexes() {
+        let stage = ScriptStepStage::new(
+            r#"
+            fn process(transcript) {
+                for i in 0..transcript.segments.len() {
+                    let text = transcript.segments[i].text;
+                    transcript.segments[i].text = regex_replace(text, "\\b(um|uh)\\b ?", "");
+                }
+                transcript
+            }
+            "#,
+            100_000,
+        )
+        .expect("script compiles");
+        let mut context = context_with(vec![segment("um hello uh world", 0, 900)]);
+
+        stage.execute(&mut context).await.expect("script runs");
+
+        let transcript = context.transcript.expect("transcript");
+        assert_eq!(transcript.segments[0].text, "hello world");
+        assert_eq!(transcript.segments[0].end_ms, 900);
+        assert_eq!(transcript.language, LanguageTag::en());
+    }
+
+    #[tokio::test]
+    async fn script_can_merge_segments() {
+        let stage = ScriptStepStage::new(
+            r#"
+            fn process(transcript) {
+                let merged = [];
+                for segment in transcript.segments {
+                    if merged.len() > 0 && segment.start_ms - merged[-1].end_ms < 200 {
+                        merged[-1].text += " " + segment.text;
+                        merged[-1].end_ms = segment.end_ms;
+                        merged[-1].tokens += segment.tokens;
+                    } else {
+                        merged.push(segment);
+                    }
+                }
+                transcript.segments = merged;
+                transcript
+            }
+            "#,
+            100_000,
+        )
+        .expect("script compiles");
+        let mut context = context_with(vec![
+            segment("hello", 0, 400),
+            segment("world", 450, 900),
+            segment("again", 2_000, 2_400),
+        ]);
+
+        stage.execute(&mut context).await.expect("script runs");
+
+        let segments = context.transcript.expect("transcript").segments;
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].text, "hello world");
+        assert_eq!((segments[0].start_ms, segments[0].end_ms), (0, 900));
+        assert_eq!(context.extensions["script.segment_count"], json!(2));
+    }
+
+    #[tokio::test]
+    async fn runaway_scripts_are_stopped() {
+        let stage = ScriptStepStage::new("fn process(transcript) { loop {} }", 10_000)
+            .expect("script compiles");
+        let mut context = context_with(vec![segment("hello", 0, 400)]);
+
+        assert!(stage.execute(&mut context).await.is_err());
+    }
+
+    #[test]
+    fn scripts_without_an_entry_point_are_refused() {
+        assert!(ScriptStepStage::new("let x = 1;", 10_000).is_err());
+        assert!(ScriptStepStage::new("fn process(transcript {", 10_000).is_err());
+    }
+}
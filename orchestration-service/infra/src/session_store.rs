@@ -0,0 +1,161 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use orchestration_domain::{
+    DomainError, PipelineContext, PipelineStage, SessionStore, StoredSession,
+};
+
+/// Keeps the most recent `max_sessions` sessions in memory, all tenants
+/// together; saving one more drops the oldest.
+pub struct InMemorySessionStore {
+    max_sessions: usize,
+    inner: Mutex<Sessions>,
+}
+
+/// Tenant and session id.
+type SessionKey = (Option<String>, String);
+
+#[derive(Default)]
+struct Sessions {
+    by_id: HashMap<SessionKey, StoredSession>,
+    order: VecDeque<SessionKey>,
+}
+
+impl InMemorySessionStore {
+    pub fn new(max_sessions: usize) -> Self {
+        Self {
+            max_sessions: max_sessions.max(1),
+            inner: Mutex::new(Sessions::default()),
+        }
+    }
+}
+
+#[async_trait]
+impl SessionStore for InMemorySessionStore {
+    async fn save(&self, session: StoredSession) -> Result<(), DomainError> {
+        let mut sessions = self
+            .inner
+            .lock()
+            .map_err(|_| DomainError::internal_error("session store lock poisoned"))?;
+        let id = (session.tenant_id.clone(), session.session_id.clone());
+        if sessions.by_id.insert(id.clone(), session).is_some() {
+            sessions.order.retain(|stored| stored != &id);
+        }
+        sessions.order.push_back(id);
+        while sessions.order.len() > self.max_sessions {
+            if let Some(oldest) = sessions.order.pop_front() {
+                sessions.by_id.remove(&oldest);
+            }
+        }
+        Ok(())
+    }
+
+    async fn load(
+        &self,
+        tenant_id: Option<&str>,
+        session_id: &str,
+    ) -> Result<Option<StoredSession>, DomainError> {
+        let sessions = self
+            .inner
+            .lock()
+            .map_err(|_| DomainError::internal_error("session store lock poisoned"))?;
+        let key = (tenant_id.map(str::to_string), session_id.to_string());
+        Ok(sessions.by_id.get(&key).cloned())
+    }
+}
+
+/// Saves the context's audio, transcript and aligned words. Place it right
+/// after `alignment_enrich`, before stages that swap in synthesized audio.
+pub struct StoreSessionStage {
+    store: Arc<dyn SessionStore>,
+}
+
+impl StoreSessionStage {
+    pub fn new(store: Arc<dyn SessionStore>) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl PipelineStage for StoreSessionStage {
+    fn name(&self) -> &'static str {
+        "store_session"
+    }
+
+    async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+        let transcript = context
+            .transcript
+            .clone()
+            .ok_or_else(|| DomainError::internal_error("store_session requires a transcript"))?;
+        self.store
+            .save(StoredSession {
+                session_id: context.session_id.clone(),
+                tenant_id: context.tenant_id.clone(),
+                audio: context.audio.clone(),
+                transcript,
+                aligned_words: context.aligned_words.clone(),
+            })
+            .await?;
+        tracing::debug!(session_id = %context.session_id, "stored session for corrections");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use orchestration_domain::{AudioChunk, LanguageTag, Transcript};
+
+    use super::*;
+
+    fn session(id: &str) -> StoredSession {
+        StoredSession {
+            session_id: id.to_string(),
+            tenant_id: None,
+            audio: AudioChunk::mono(16_000, vec![0.0; 16]),
+            transcript: Transcript {
+                language: LanguageTag::en(),
+                segments: Vec::new(),
+            },
+            aligned_words: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn oldest_session_is_evicted_first() {
+        let store = InMemorySessionStore::new(2);
+        store.save(session("a")).await.unwrap();
+        store.save(session("b")).await.unwrap();
+        // Saving `a` again makes `b` the oldest.
+        store.save(session("a")).await.unwrap();
+        store.save(session("c")).await.unwrap();
+
+        assert!(store.load(None, "a").await.unwrap().is_some());
+        assert!(store.load(None, "b").await.unwrap().is_none());
+        assert!(store.load(None, "c").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn tenants_do_not_see_each_others_sessions() {
+        let store = InMemorySessionStore::new(4);
+        let mut acme = session("s-1");
+        acme.tenant_id = Some("acme".to_string());
+        store.save(acme).await.unwrap();
+
+        assert!(store.load(Some("acme"), "s-1").await.unwrap().is_some());
+        assert!(store.load(Some("globex"), "s-1").await.unwrap().is_none());
+        assert!(store.load(None, "s-1").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn stage_saves_the_context() {
+        let store = Arc::new(InMemorySessionStore::new(4));
+        let stage = StoreSessionStage::new(store.clone());
+        let mut context = PipelineContext::new("s-1", None);
+        assert!(stage.execute(&mut context).await.is_err());
+
+        context.transcript = Some(session("s-1").transcript);
+        stage.execute(&mut context).await.expect("stores");
+        assert!(store.load(None, "s-1").await.unwrap().is_some());
+    }
+}
@@ -63,14 +63,24 @@ mod tests {
             start_ms: 0,
             end_ms: 500,
             confidence: 0.95,
+            speaker: None,
+            start_sample: None,
+            end_sample: None,
+            energy_rms: None,
+            pitch_hz: None,
+            out_of_band: false,
         }];
         context.transcript = Some(Transcript {
-            language: LanguageTag::En,
+            language: LanguageTag::en(),
             segments: vec![TranscriptSegment {
                 text: "hello world".to_string(),
                 start_ms: 0,
                 end_ms: 1000,
                 tokens: vec![],
+                speaker: None,
+                language: None,
+                no_speech_prob: None,
+                avg_logprob: None,
             }],
         });
 
@@ -1,7 +1,6 @@
 use async_trait::async_trait;
 use orchestration_domain::{DomainError, PipelineContext, PipelineStage};
-
-use crate::audio::resample_linear;
+use vocal_dsp::resample_linear;
 
 pub struct SwapTtsAudioStage;
 
@@ -79,6 +78,12 @@ mod tests {
             start_ms: 0,
             end_ms: 100,
             confidence: 0.9,
+            speaker: None,
+            start_sample: None,
+            end_sample: None,
+            energy_rms: None,
+            pitch_hz: None,
+            out_of_band: false,
         }];
         context.tts_output = Some(TtsOutput {
             samples: vec![0.5; 240],
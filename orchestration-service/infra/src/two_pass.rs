@@ -0,0 +1,251 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use orchestration_domain::{
+    DomainError, DomainEvent, LateEventSink, PipelineContext, PipelineStage,
+};
+use serde_json::json;
+use tokio::sync::Semaphore;
+
+/// Background passes running at once unless configured otherwise.
+const DEFAULT_MAX_BACKGROUND_PASSES: usize = 4;
+
+/// Transcribes with a fast model, returning its transcript as a
+/// [`DomainEvent::PartialTranscript`], then re-runs the transcription with the
+/// accurate model in the background. The background pass runs `follow_up` on
+/// its transcript, e.g. `alignment_enrich` and `store_session` so the refined
+/// session can be fetched once it is ready, and publishes its events, the
+/// final transcript first, to the [`LateEventSink`].
+///
+/// When `max_background_passes` are already running, the fast transcript is
+/// the final one and no background pass starts.
+pub struct TwoPassTranscribeStage {
+    asr: Arc<dyn PipelineStage>,
+    follow_up: Vec<Arc<dyn PipelineStage>>,
+    fast_model: String,
+    accurate_model: String,
+    late_events: Option<Arc<dyn LateEventSink>>,
+    background: Arc<Semaphore>,
+}
+
+impl TwoPassTranscribeStage {
+    pub fn new(
+        asr: Arc<dyn PipelineStage>,
+        follow_up: Vec<Arc<dyn PipelineStage>>,
+        fast_model: impl Into<String>,
+        accurate_model: impl Into<String>,
+    ) -> Self {
+        Self {
+            asr,
+            follow_up,
+            fast_model: fast_model.into(),
+            accurate_model: accurate_model.into(),
+            late_events: None,
+            background: Arc::new(Semaphore::new(DEFAULT_MAX_BACKGROUND_PASSES)),
+        }
+    }
+
+    pub fn with_late_events(mut self, late_events: Arc<dyn LateEventSink>) -> Self {
+        self.late_events = Some(late_events);
+        self
+    }
+
+    pub fn with_max_background_passes(mut self, max_background_passes: usize) -> Self {
+        self.background = Arc::new(Semaphore::new(max_background_passes));
+        self
+    }
+}
+
+#[async_trait]
+impl PipelineStage for TwoPassTranscribeStage {
+    fn name(&self) -> &'static str {
+        "two_pass_transcribe"
+    }
+
+    async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+        // The second pass starts from the same preprocessed audio; the client's
+        // own model choice is replaced by the configured pair.
+        let mut refine = context.clone();
+        refine.events.clear();
+        refine.set_extension("asr.model", json!(self.accurate_model));
+
+        context.set_extension("asr.model", json!(self.fast_model));
+        let first_event = context.events.len();
+        self.asr.execute(context).await?;
+        let Ok(permit) = self.background.clone().try_acquire_owned() else {
+            tracing::warn!(
+                session_id = %context.session_id,
+                "too many second transcription passes running; keeping the fast transcript"
+            );
+            return Ok(());
+        };
+        for event in &mut context.events[first_event..] {
+            if let DomainEvent::FinalTranscript { transcript } = event {
+                // The accurate pass may rewrite any of it.
+                *event = DomainEvent::PartialTranscript {
+                    transcript: transcript.clone(),
+                    stable_until_ms: None,
+                };
+            }
+        }
+
+        let asr = self.asr.clone();
+        let follow_up = self.follow_up.clone();
+        let late_events = self.late_events.clone();
+        tokio::spawn(async move {
+            let _permit = permit;
+            match run_second_pass(asr.as_ref(), &follow_up, &mut refine).await {
+                Ok(()) => {
+                    if let Some(late_events) = late_events {
+                        late_events.publish(&refine.session_id, std::mem::take(&mut refine.events));
+                    }
+                }
+                Err(err) => tracing::warn!(
+                    session_id = %refine.session_id,
+                    error = %err,
+                    "second transcription pass failed"
+                ),
+            }
+        });
+        Ok(())
+    }
+}
+
+async fn run_second_pass(
+    asr: &dyn PipelineStage,
+    follow_up: &[Arc<dyn PipelineStage>],
+    context: &mut PipelineContext,
+) -> Result<(), DomainError> {
+    asr.execute(context).await?;
+    for stage in follow_up {
+        stage.execute(context).await?;
+    }
+    tracing::debug!(
+        session_id = %context.session_id,
+        aligned_word_count = context.aligned_words.len(),
+        "second transcription pass completed"
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use orchestration_domain::{LanguageTag, Transcript, TranscriptSegment};
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    /// Transcribes as the name of the model it was asked for.
+    struct ModelEchoStage;
+
+    #[async_trait]
+    impl PipelineStage for ModelEchoStage {
+        fn name(&self) -> &'static str {
+            "asr_transcribe"
+        }
+
+        async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+            let model = context
+                .extension("asr.model")
+                .and_then(|value| value.as_str())
+                .unwrap_or_default()
+                .to_string();
+            let transcript = Transcript {
+                language: LanguageTag::en(),
+                segments: vec![TranscriptSegment {
+                    text: model,
+                    start_ms: 0,
+                    end_ms: 100,
+                    tokens: Vec::new(),
+                    speaker: None,
+                    language: None,
+                    no_speech_prob: None,
+                    avg_logprob: None,
+                }],
+            };
+            context.transcript = Some(transcript.clone());
+            context.events.push(DomainEvent::FinalTranscript { transcript });
+            Ok(())
+        }
+    }
+
+    /// Keeps the context it last ran on.
+    #[derive(Default)]
+    struct CaptureStage(Mutex<Option<PipelineContext>>);
+
+    #[async_trait]
+    impl PipelineStage for CaptureStage {
+        fn name(&self) -> &'static str {
+            "capture"
+        }
+
+        async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+            *self.0.lock().unwrap() = Some(context.clone());
+            Ok(())
+        }
+    }
+
+    /// Hands what the background pass publishes to the test.
+    struct ChannelSink(mpsc::UnboundedSender<(String, Vec<DomainEvent>)>);
+
+    impl LateEventSink for ChannelSink {
+        fn publish(&self, session_id: &str, events: Vec<DomainEvent>) {
+            let _ = self.0.send((session_id.to_string(), events));
+        }
+    }
+
+    #[tokio::test]
+    async fn fast_transcript_is_partial_and_accurate_one_follows() {
+        let capture = Arc::new(CaptureStage::default());
+        let follow_up: Arc<dyn PipelineStage> = capture.clone();
+        let (sender, mut published) = mpsc::unbounded_channel();
+        let stage = TwoPassTranscribeStage::new(
+            Arc::new(ModelEchoStage),
+            vec![follow_up],
+            "tiny",
+            "large-v3",
+        )
+        .with_late_events(Arc::new(ChannelSink(sender)));
+        let mut context = PipelineContext::new("s", None);
+        stage.execute(&mut context).await.expect("first pass");
+
+        assert_eq!(context.transcript.as_ref().unwrap().segments[0].text, "tiny");
+        assert!(matches!(
+            context.events.as_slice(),
+            [DomainEvent::PartialTranscript { .. }]
+        ));
+
+        let (session_id, events) = tokio::time::timeout(Duration::from_secs(5), published.recv())
+            .await
+            .expect("second pass ran")
+            .expect("events published");
+        assert_eq!(session_id, "s");
+        let [DomainEvent::FinalTranscript { transcript }] = events.as_slice() else {
+            panic!("expected the refined final transcript, got {events:?}");
+        };
+        assert_eq!(transcript.segments[0].text, "large-v3");
+        let refined = capture.0.lock().unwrap().take().expect("follow-up ran");
+        assert_eq!(refined.transcript.unwrap().segments[0].text, "large-v3");
+    }
+
+    #[tokio::test]
+    async fn fast_transcript_is_final_when_no_background_pass_is_free() {
+        let (sender, mut published) = mpsc::unbounded_channel();
+        let stage =
+            TwoPassTranscribeStage::new(Arc::new(ModelEchoStage), Vec::new(), "tiny", "large-v3")
+                .with_late_events(Arc::new(ChannelSink(sender)))
+                .with_max_background_passes(0);
+        let mut context = PipelineContext::new("s", None);
+        stage.execute(&mut context).await.expect("first pass");
+
+        let [DomainEvent::FinalTranscript { transcript }] = context.events.as_slice() else {
+            panic!("expected the fast final transcript, got {:?}", context.events);
+        };
+        assert_eq!(transcript.segments[0].text, "tiny");
+        drop(stage);
+        assert!(published.recv().await.is_none(), "no background pass started");
+    }
+}
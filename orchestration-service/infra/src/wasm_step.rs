@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use orchestration_domain::{DomainError, PipelineContext, PipelineStage, Transcript};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use wasmtime::{Config, Engine, Instance, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+/// Bounds of one run of the module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WasmLimits {
+    /// Instruction budget; the run traps when it is spent.
+    pub fuel: u64,
+    /// Largest linear memory the module may grow to.
+    pub max_memory_bytes: usize,
+}
+
+/// What the module sees and may hand back: the transcript and the
+/// extensions, never the audio.
+#[derive(Debug, Serialize)]
+struct WasmInput<'a> {
+    transcript: &'a Option<Transcript>,
+    extensions: &'a HashMap<String, Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct WasmOutput {
+    /// Replaces the transcript when present.
+    #[serde(default)]
+    transcript: Option<Transcript>,
+    /// Merged into the context extensions.
+    #[serde(default)]
+    extensions: HashMap<String, Value>,
+}
+
+/// Runs a user-provided WebAssembly module over the transcript and
+/// extensions. The module gets no imports, so it cannot reach files, the
+/// network or the clock, and each run starts from a fresh instance bounded
+/// by [`WasmLimits`].
+///
+/// The module exports `memory`, `alloc(len: i32) -> i32` returning where to
+/// write `len` input bytes, and `process(ptr: i32, len: i32) -> i64` taking
+/// the input JSON `{"transcript": .., "extensions": {..}}` and returning the
+/// output JSON location as `ptr << 32 | len`. The output has the same shape;
+/// both fields are optional. An output outside the module's memory fails the
+/// step.
+#[derive(Clone)]
+pub struct WasmStepStage {
+    engine: Engine,
+    module: Module,
+    limits: WasmLimits,
+}
+
+impl WasmStepStage {
+    /// Compiles a `.wasm` binary or its `.wat` text.
+    pub fn new(module: impl AsRef<[u8]>, limits: WasmLimits) -> Result<Self, DomainError> {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config).map_err(wasm_error)?;
+        let module = Module::new(&engine, module).map_err(wasm_error)?;
+        Ok(Self {
+            engine,
+            module,
+            limits,
+        })
+    }
+
+    fn run(&self, input: &[u8]) -> Result<Vec<u8>, wasmtime::Error> {
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(self.limits.max_memory_bytes)
+            .instances(1)
+            .build();
+        let mut store = Store::new(&self.engine, limits);
+        store.limiter(|limits: &mut StoreLimits| limits);
+        store.set_fuel(self.limits.fuel)?;
+        let instance = Instance::new(&mut store, &self.module, &[])?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| wasmtime::Error::msg("module exports no `memory`"))?;
+        let alloc = instance.get_typed_func::<i32, i32>(&mut store, "alloc")?;
+        let process = instance.get_typed_func::<(i32, i32), i64>(&mut store, "process")?;
+
+        let input_len = i32::try_from(input.len())?;
+        let input_ptr = alloc.call(&mut store, input_len)?;
+        memory.write(&mut store, input_ptr as u32 as usize, input)?;
+        let packed = process.call(&mut store, (input_ptr, input_len))?;
+        let output_ptr = (packed as u64 >> 32) as usize;
+        let output_len = (packed as u64 & u64::from(u32::MAX)) as usize;
+        // The length is the module's word; only copy what its memory holds.
+        let data = memory.data(&store);
+        let output = output_ptr
+            .checked_add(output_len)
+            .filter(|end| *end <= data.len() && output_len <= self.limits.max_memory_bytes)
+            .and_then(|end| data.get(output_ptr..end))
+            .ok_or_else(|| {
+                wasmtime::Error::msg(format!(
+                    "output of {output_len} bytes at {output_ptr} is outside the module's \
+                     {} byte memory",
+                    data.len()
+                ))
+            })?;
+        Ok(output.to_vec())
+    }
+}
+
+#[async_trait]
+impl PipelineStage for WasmStepStage {
+    fn name(&self) -> &'static str {
+        "wasm_step"
+    }
+
+    async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+        let input = serde_json::to_vec(&WasmInput {
+            transcript: &context.transcript,
+            extensions: &context.extensions,
+        })
+        .map_err(|err| DomainError::internal_error(&format!("wasm_step input: {err}")))?;
+        // The run is synchronous; keep it off the async workers.
+        let stage = self.clone();
+        let output = tokio::task::spawn_blocking(move || stage.run(&input))
+            .await
+            .map_err(|err| DomainError::internal_error(&format!("wasm_step: {err}")))?
+            .map_err(wasm_error)?;
+        let output: WasmOutput = serde_json::from_slice(&output)
+            .map_err(|err| DomainError::internal_error(&format!("wasm_step output: {err}")))?;
+
+        if let Some(transcript) = output.transcript {
+            context.transcript = Some(transcript);
+        }
+        for (key, value) in output.extensions {
+            context.set_extension(key, value);
+        }
+        Ok(())
+    }
+}
+
+fn wasm_error(error: wasmtime::Error) -> DomainError {
+    DomainError::internal_error(&format!("wasm_step: {error:#}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const LIMITS: WasmLimits = WasmLimits {
+        fuel: 1_000_000,
+        max_memory_bytes: 1 << 20,
+    };
+
+    /// Ignores its input and answers with a fixed extension.
+    const TAGGING_MODULE: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (data (i32.const 0) "{\"extensions\":{\"wasm.checked\":true}}")
+          (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+          (func (export "process") (param i32 i32) (result i64) (i64.const 36)))
+    "#;
+
+    /// Hands its input back unchanged.
+    const ECHO_MODULE: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+          (func (export "process") (param $ptr i32) (param $len i32) (result i64)
+            (i64.or
+              (i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32))
+              (i64.extend_i32_u (local.get $len)))))
+    "#;
+
+    const SPINNING_MODULE: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+          (func (export "process") (param i32 i32) (result i64)
+            (loop $forever (br $forever))
+            (i64.const 0)))
+    "#;
+
+    /// Claims an output of `u32::MAX` bytes from a 64 KiB memory.
+    const HOSTILE_LENGTH_MODULE: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+          (func (export "process") (param i32 i32) (result i64) (i64.const 0xffffffff)))
+    "#;
+
+    /// Claims 16 bytes starting 8 bytes before the end of its memory.
+    const OVERRUNNING_MODULE: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+          (func (export "process") (param i32 i32) (result i64)
+            (i64.const 0x0000fff800000010)))
+    "#;
+
+    #[tokio::test]
+    async fn module_output_is_merged_into_the_extensions() {
+        let stage = WasmStepStage::new(TAGGING_MODULE, LIMITS).expect("module compiles");
+        let mut context = PipelineContext::new("session", None);
+        context.set_extension("asr.text", json!("hello"));
+
+        stage.execute(&mut context).await.expect("module runs");
+
+        assert_eq!(context.extension("wasm.checked"), Some(&json!(true)));
+        assert_eq!(context.extension("asr.text"), Some(&json!("hello")));
+    }
+
+    #[tokio::test]
+    async fn module_sees_the_extensions_as_json() {
+        let stage = WasmStepStage::new(ECHO_MODULE, LIMITS).expect("module compiles");
+        let mut context = PipelineContext::new("session", None);
+        context.set_extension("asr.text", json!("hello"));
+
+        stage.execute(&mut context).await.expect("module runs");
+
+        assert_eq!(context.extensions.len(), 1);
+        assert_eq!(context.extension("asr.text"), Some(&json!("hello")));
+        assert!(context.transcript.is_none());
+    }
+
+    #[tokio::test]
+    async fn runaway_modules_are_stopped_by_the_fuel_limit() {
+        let stage = WasmStepStage::new(SPINNING_MODULE, LIMITS).expect("module compiles");
+        let mut context = PipelineContext::new("session", None);
+
+        let error = stage.execute(&mut context).await.expect_err("fuel runs out");
+        assert!(error.to_string().contains("wasm_step"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn outputs_outside_the_module_memory_are_refused() {
+        for module in [HOSTILE_LENGTH_MODULE, OVERRUNNING_MODULE] {
+            let stage = WasmStepStage::new(module, LIMITS).expect("module compiles");
+            let mut context = PipelineContext::new("session", None);
+
+            let error = stage.execute(&mut context).await.expect_err("output out of bounds");
+            assert!(error.to_string().contains("outside the module's"), "{error}");
+        }
+    }
+}
@@ -0,0 +1,32 @@
+//! Example pipeline plugin: registers `shout`, which upper-cases the
+//! transcript. Build it with the orchestrator's toolchain and lock file and
+//! drop the library in `service.pipeline.plugin_dir`.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use orchestration_domain::{DomainError, PipelineContext, PipelineStage, PluginRegistrar};
+
+struct ShoutStage;
+
+#[async_trait]
+impl PipelineStage for ShoutStage {
+    fn name(&self) -> &'static str {
+        "shout"
+    }
+
+    async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+        if let Some(transcript) = &mut context.transcript {
+            for segment in &mut transcript.segments {
+                segment.text = segment.text.to_uppercase();
+            }
+        }
+        Ok(())
+    }
+}
+
+fn register(registrar: &mut dyn PluginRegistrar) {
+    registrar.register_step("shout", Arc::new(ShoutStage));
+}
+
+orchestration_domain::export_pipeline_plugin!(register);
@@ -1,24 +1,64 @@
-use std::{future::Future, sync::Arc, time::Duration};
+use std::{collections::HashMap, future::Future, sync::Arc, time::Duration};
 
 use anyhow::{anyhow, Error};
 use orchestration_application::{
-    AsrCommandRegistryFactory, AsrUseCase, AsrUseCaseImpl, PipelineDefinition, PipelineEngine,
-    PipelineStepLoader, PipelineStepSpec,
+    parse_tenant_id, AsrCommandRegistryFactory, AsrUseCase, AsrUseCaseImpl, BatchLimits,
+    CorrectionUseCase, CorrectionUseCaseImpl, EndpointDescription, PipelineCatalog,
+    PipelineCatalogUseCase, PipelineCatalogUseCaseImpl, PipelineDefinition, PipelineDescription,
+    PipelineEngine, PipelinePhase, PipelineStepDescription, PipelineStepLoader, PipelineStepSpec,
+    StepAvailability, StepSource, TenantPolicy, TenantRoute, UsageMeter,
 };
-use orchestration_configuration::{AppConfig, GrpcEndpointConfig, PipelineDefinitionConfig};
-use orchestration_domain::{DomainError, PipelineStage};
-use orchestration_http_server::create_app_routes;
+use orchestration_configuration::{
+    load_config, AppConfig, AudioFetchConfig, GrpcEndpointConfig, LoadBalancingPolicy,
+    NormalizationConfig, ObjectStoreConfig, PipelineConfig, PipelineDefinitionConfig,
+    RecordingConfig, ResultStoreConfig, RtpCodecConfig, RtpConfig, ScriptStepConfig,
+    ServiceConfig, TenancyConfig, UsageEventsConfig, WasmStepConfig,
+};
+use orchestration_domain::{
+    DomainError, LanguageTag, ObjectStorePort, PipelineStage, SessionStore, UsageSink,
+};
+use orchestration_http_server::{admin_routes, create_app_routes};
 use orchestration_infra::DiagnosticDumpStage;
 use orchestration_infra::SnapshotOriginalTimingsStage;
 use orchestration_infra::SwapTtsAudioStage;
-use orchestration_infra_alignment::{connect_alignment_client, AlignmentEnrichStage};
-use orchestration_infra_asr::{connect_asr_client, AsrTranscribeStage};
-use orchestration_infra_audio::{connect_audio_client, AudioTransformStage};
-use orchestration_infra_tempo::{connect_tempo_client, TempoMatchStage};
+use orchestration_infra::{
+    AudioPreprocessStage, AudioQualityGateStage, ContextCarryOverStage, DisfluencyTaggingStage,
+    DuplicateLookupStage, EnsembleMember, EnsembleTranscribeStage, InMemoryDuplicateIndex,
+    InMemorySessionStore, CueLimits, ProvidedTranscriptStage, QualityThresholds, RecordSessionStage,
+    RecordingRetention, ReplacementRule, ResampleStage, ResultFormat, StoreResultStage,
+    StoreSessionStage, TranscriptNormalizationStage, TwoPassTranscribeStage,
+};
+#[cfg(not(feature = "monolith"))]
+use orchestration_infra_alignment::AlignmentEnrichStage;
+#[cfg(not(feature = "monolith"))]
+use orchestration_infra_asr::AsrTranscribeStage;
+use orchestration_infra_audio::AudioTransformStage;
+use orchestration_infra_grpc::{
+    expand_targets, BalancingPolicy, DownstreamReadiness, GrpcChannelPool, GrpcPoolConfig,
+    GrpcTarget,
+};
+use orchestration_infra_http_enrich::{HttpEnrichStage, UsageWebhook};
+use orchestration_infra_object_store::{
+    AudioFetchSettings, ObjectStoreAdapter, RemoteAudioSource, S3Settings,
+};
+use orchestration_infra_streaming::{
+    build_router, run_grpc_server, run_server, serve_rtp_legs, LateEvents, RtpCodec, RtpLeg,
+    RtpSettings, StreamBudget, StreamingRoute, StreamingState,
+};
+use orchestration_infra_tempo::TempoMatchStage;
 use orchestration_infra_tts_rest::TtsRestSynthesizeStage;
 use rustycog_command::GenericCommandService;
 use rustycog_config::ServerConfig;
 use rustycog_http::{AppState, UserIdExtractor};
+use service_admin::{spawn_admin_with_routes, ConfigReloader};
+use service_health::ReadinessCheck;
+
+#[cfg(feature = "monolith")]
+use crate::monolith::model_stages;
+
+const READINESS_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+/// Rate `local_resample` converts to, the one the ASR service expects.
+const LOCAL_RESAMPLE_RATE_HZ: u32 = 16_000;
 
 pub async fn build_and_run(config: AppConfig, server_config: ServerConfig) -> Result<(), Error> {
     let app = Application::new(config).await?;
@@ -28,6 +68,14 @@ pub async fn build_and_run(config: AppConfig, server_config: ServerConfig) -> Re
 pub struct Application {
     pub config: AppConfig,
     pub state: AppState,
+    pub readiness: Arc<dyn ReadinessCheck>,
+    /// Served on the admin server's `/admin/usage`.
+    pub usage: UsageMeter,
+    /// Sessions of the WebSocket and gRPC streaming listeners, on the
+    /// selected pipeline.
+    pub streaming: StreamingState,
+    /// Static telephony legs of `service.streaming.rtp`.
+    pub rtp_legs: Vec<RtpLeg>,
 }
 
 impl Application {
@@ -41,53 +89,107 @@ impl Application {
             .ok_or_else(|| anyhow!("missing pipeline definition `{selected}`"))?;
         let pipeline_definition = build_pipeline_definition(definition);
 
-        let audio_client = connect_with_retry("audio", || async {
-            connect_audio_client(
-                &grpc_endpoint_uri(&config.service.audio),
-                connect_timeout(&config.service.audio),
-                config.service.audio.max_decoding_message_bytes,
-                config.service.audio.max_encoding_message_bytes,
-            )
-            .await
-        })
-        .await?;
-        let asr_client = connect_with_retry("asr", || async {
-            connect_asr_client(
-                &grpc_endpoint_uri(&config.service.asr),
-                connect_timeout(&config.service.asr),
-                config.service.asr.max_decoding_message_bytes,
-                config.service.asr.max_encoding_message_bytes,
-            )
-            .await
-        })
-        .await?;
-        let alignment_client = connect_with_retry("alignment", || async {
-            connect_alignment_client(
-                &grpc_endpoint_uri(&config.service.alignment),
-                connect_timeout(&config.service.alignment),
-                config.service.alignment.max_decoding_message_bytes,
-                config.service.alignment.max_encoding_message_bytes,
-            )
-            .await
-        })
-        .await?;
+        let audio_channels = channel_pool("audio", &config.service.audio).await?;
+        let audio_service_used = uses_audio_service(&config.service);
+        if audio_service_used {
+            connect_with_retry("audio", || audio_channels.connect()).await?;
+        } else {
+            tracing::info!("no pipeline calls the audio service; not connecting to it");
+        }
+        let models = model_stages(&config.service).await?;
+        let mut tenant_models = tenant_model_stages(&config.service).await?;
+        let tempo_channels = channel_pool("tempo", &config.service.tempo).await?;
+        connect_with_retry("tempo", || tempo_channels.connect()).await?;
+        let mut probed = models.channels;
+        for tenant in tenant_models.values_mut() {
+            probed.append(&mut tenant.channels);
+        }
+        probed.push(tempo_channels.clone());
+        if audio_service_used {
+            probed.insert(0, audio_channels.clone());
+        }
+        let readiness: Arc<dyn ReadinessCheck> =
+            Arc::new(DownstreamReadiness::new(probed, READINESS_PROBE_TIMEOUT));
+        let audio_recipes = config
+            .service
+            .audio_recipes
+            .iter()
+            .map(|(name, ops)| {
+                let stage: Arc<dyn PipelineStage> = Arc::new(
+                    AudioTransformStage::new(
+                        audio_channels.clone(),
+                        request_timeout(&config.service.audio),
+                        None,
+                    )
+                    .with_ops(ops.clone()),
+                );
+                (name.clone(), stage)
+            })
+            .collect();
         let audio_stage: Arc<dyn PipelineStage> = Arc::new(AudioTransformStage::new(
-            audio_client,
+            audio_channels,
             request_timeout(&config.service.audio),
             None,
         ));
-        let asr_stage: Arc<dyn PipelineStage> = Arc::new(AsrTranscribeStage::new(
-            asr_client,
-            request_timeout(&config.service.asr),
-        ));
-        let alignment_stage: Arc<dyn PipelineStage> = Arc::new(AlignmentEnrichStage::new(
-            alignment_client,
-            request_timeout(&config.service.alignment),
-        ));
+        let asr_stage = models.asr;
+        let alignment_stage = models.alignment;
         let tts_stage: Arc<dyn PipelineStage> = Arc::new(TtsRestSynthesizeStage::new(
             format!("{}/v1/audio/speech", grpc_endpoint_uri(&config.service.tts)),
             request_timeout(&config.service.tts),
         ));
+        let session_store: Arc<dyn SessionStore> = Arc::new(InMemorySessionStore::new(
+            config.service.sessions.max_sessions,
+        ));
+        let store_session_stage: Arc<dyn PipelineStage> =
+            Arc::new(StoreSessionStage::new(session_store.clone()));
+        let dedup_stage: Arc<dyn PipelineStage> = Arc::new(DuplicateLookupStage::new(
+            Arc::new(InMemoryDuplicateIndex::new(config.service.dedup.max_fingerprints)),
+            session_store.clone(),
+        ));
+        let carry_over_stage: Arc<dyn PipelineStage> = Arc::new(ContextCarryOverStage::new(
+            session_store.clone(),
+            config.service.carry_over.max_chars,
+        ));
+        let quality_gate = &config.service.quality_gate;
+        let quality_gate_stage: Arc<dyn PipelineStage> = Arc::new(AudioQualityGateStage::new(
+            QualityThresholds {
+                min_duration_ms: quality_gate.min_duration_ms,
+                silence_rms_dbfs: quality_gate.silence_rms_dbfs,
+                max_clipped_ratio: quality_gate.max_clipped_ratio,
+                allowed_sample_rates_hz: quality_gate.allowed_sample_rates_hz.clone(),
+            },
+            quality_gate.reject,
+        ));
+        let late_events = LateEvents::default();
+        let two_pass_stage: Arc<dyn PipelineStage> = Arc::new(
+            TwoPassTranscribeStage::new(
+                asr_stage.clone(),
+                vec![alignment_stage.clone(), store_session_stage.clone()],
+                config.service.two_pass.fast_model.clone(),
+                config.service.two_pass.accurate_model.clone(),
+            )
+            .with_late_events(Arc::new(late_events.clone()))
+            .with_max_background_passes(config.service.two_pass.max_background_passes),
+        );
+        let ensemble_stage: Arc<dyn PipelineStage> = Arc::new(EnsembleTranscribeStage::new(
+            config
+                .service
+                .ensemble
+                .models
+                .iter()
+                .map(|model| EnsembleMember {
+                    stage: asr_stage.clone(),
+                    model: Some(model.clone()),
+                })
+                .collect(),
+        ));
+        let normalization_stage: Arc<dyn PipelineStage> = Arc::new(
+            TranscriptNormalizationStage::new(replacement_rules(&config.service.normalization)?),
+        );
+        let disfluency_stage: Arc<dyn PipelineStage> = Arc::new(DisfluencyTaggingStage::new(
+            config.service.disfluency.fillers.clone(),
+            config.service.disfluency.tag_repetitions,
+        ));
         let snapshot_stage: Arc<dyn PipelineStage> =
             Arc::new(SnapshotOriginalTimingsStage::new());
         let swap_stage: Arc<dyn PipelineStage> = Arc::new(SwapTtsAudioStage::new());
@@ -102,24 +204,39 @@ impl Application {
             Arc::new(DiagnosticDumpStage::new("04_tempo_result", &dump_dir));
         let dump_final: Arc<dyn PipelineStage> =
             Arc::new(DiagnosticDumpStage::new("05_final", &dump_dir));
-        let tempo_client = connect_with_retry("tempo", || async {
-            connect_tempo_client(
-                &grpc_endpoint_uri(&config.service.tempo),
-                connect_timeout(&config.service.tempo),
-                config.service.tempo.max_decoding_message_bytes,
-                config.service.tempo.max_encoding_message_bytes,
-            )
-            .await
-        })
-        .await?;
         let tempo_stage: Arc<dyn PipelineStage> = Arc::new(TempoMatchStage::new(
-            tempo_client,
+            tempo_channels,
             request_timeout(&config.service.tempo),
         ));
         let loader = GrpcPipelineStepLoader {
             audio_transform: audio_stage,
+            audio_recipes,
+            plugins: plugin_steps(&config.service.pipeline)?,
+            wasm_step: wasm_step(config.service.wasm_step.as_ref())?,
+            script_step: script_step(config.service.script_step.as_ref())?,
+            http_enrich: config.service.http_enrich.as_ref().map(|webhook| {
+                let stage = HttpEnrichStage::new(
+                    webhook.url.clone(),
+                    Duration::from_millis(webhook.request_timeout_ms),
+                )
+                .with_headers(webhook.headers.clone());
+                Arc::new(stage) as Arc<dyn PipelineStage>
+            }),
+            record_session: record_session(config.service.recording.as_ref())?,
+            store_result: store_result(config.service.result_store.as_ref())?,
+            local_audio_clamp: Arc::new(AudioPreprocessStage::new()),
+            local_resample: Arc::new(ResampleStage::new(LOCAL_RESAMPLE_RATE_HZ)),
             asr_transcribe: asr_stage,
-            alignment_enrich: alignment_stage,
+            alignment_enrich: alignment_stage.clone(),
+            store_session: store_session_stage,
+            dedup_lookup: dedup_stage,
+            context_carry_over: carry_over_stage,
+            audio_quality_gate: quality_gate_stage,
+            provided_transcript: Arc::new(ProvidedTranscriptStage::new()),
+            two_pass_transcribe: two_pass_stage,
+            ensemble_transcribe: ensemble_stage,
+            normalize_transcript: normalization_stage,
+            tag_disfluencies: disfluency_stage,
             tts_synthesize: tts_stage,
             snapshot_original_timings: snapshot_stage,
             swap_tts_audio: swap_stage,
@@ -130,27 +247,330 @@ impl Application {
             dump_tempo_result,
             dump_final,
         };
-        let pipeline = PipelineEngine::from_definition(&pipeline_definition, &loader)?;
+        let pipeline = PipelineEngine::from_definition(&pipeline_definition, &loader)?
+            .with_name(selected.clone());
+        let tenants = tenant_policy(&config.service.tenancy);
+        let rtp_legs = rtp_legs(&config.service.streaming.host, &config.service.streaming.rtp)?;
+        let usage = UsageMeter::default();
+        if let Some(events) = &config.service.usage_events {
+            spawn_usage_events(events, usage.clone());
+        }
+        let mut asr_usecase = AsrUseCaseImpl::new(pipeline, 16_000)
+            .with_tenant_policy(tenants.clone())
+            .with_usage_meter(usage.clone());
+        if let Some(fetch) = &config.service.audio_fetch {
+            asr_usecase = asr_usecase.with_audio_source(Arc::new(audio_source(fetch)?));
+        }
+        for (name, definition) in &config.service.pipeline.definitions {
+            if *name == selected {
+                continue;
+            }
+            let engine =
+                PipelineEngine::from_definition(&build_pipeline_definition(definition), &loader)?
+                    .with_name(name.clone());
+            asr_usecase = asr_usecase.with_pipeline(name.clone(), engine);
+        }
+        let mut streaming_routes = HashMap::new();
+        for (tenant_id, tenant) in &config.service.tenancy.tenants {
+            let mut route = TenantRoute {
+                pipeline: tenant.pipeline.clone(),
+                model: tenant.model.clone(),
+                pipelines: HashMap::new(),
+            };
+            if let Some(name) = &route.pipeline {
+                if !config.service.pipeline.definitions.contains_key(name) {
+                    return Err(anyhow!("tenant `{tenant_id}` runs unknown pipeline `{name}`"));
+                }
+            }
+            let tenant_loader = tenant_models.remove(tenant_id).map(|models| {
+                GrpcPipelineStepLoader {
+                    asr_transcribe: models.asr,
+                    alignment_enrich: models.alignment,
+                    ..loader.clone()
+                }
+            });
+            if let Some(tenant_loader) = &tenant_loader {
+                for (name, definition) in &config.service.pipeline.definitions {
+                    let engine = PipelineEngine::from_definition(
+                        &build_pipeline_definition(definition),
+                        tenant_loader,
+                    )?
+                    .with_name(name.clone());
+                    route.pipelines.insert(name.clone(), engine);
+                }
+                route.pipeline.get_or_insert_with(|| selected.clone());
+            }
+            // Streaming sessions get their own build, as they do of the
+            // selected pipeline.
+            let pipeline = match &route.pipeline {
+                Some(name) => Some(Arc::new(
+                    PipelineEngine::from_definition(
+                        &build_pipeline_definition(&config.service.pipeline.definitions[name]),
+                        tenant_loader.as_ref().unwrap_or(&loader),
+                    )?
+                    .with_name(name.clone()),
+                )),
+                None => None,
+            };
+            streaming_routes.insert(
+                tenant_id.clone(),
+                StreamingRoute {
+                    pipeline,
+                    model: route.model.clone(),
+                },
+            );
+            asr_usecase = asr_usecase.with_tenant_route(tenant_id.clone(), route);
+        }
+        let streaming = streaming_state(
+            &config.service,
+            PipelineEngine::from_definition(&pipeline_definition, &loader)?
+                .with_name(selected.clone()),
+            late_events,
+            tenants,
+            streaming_routes,
+            usage.clone(),
+        );
 
-        let usecase: Arc<dyn AsrUseCase> = Arc::new(AsrUseCaseImpl::new(pipeline, 16_000));
-        let registry = AsrCommandRegistryFactory::create_registry(usecase);
+        let usecase: Arc<dyn AsrUseCase> = Arc::new(asr_usecase);
+        let batch_limits = BatchLimits {
+            max_items: config.service.batch.max_items,
+            max_concurrency: config.service.batch.max_concurrency,
+        };
+        let correction: Arc<dyn CorrectionUseCase> =
+            Arc::new(CorrectionUseCaseImpl::new(session_store, alignment_stage));
+        let pipeline_catalog: Arc<dyn PipelineCatalogUseCase> = Arc::new(
+            PipelineCatalogUseCaseImpl::new(pipeline_catalog(&config.service, &loader)?),
+        );
+        let registry = AsrCommandRegistryFactory::create_registry(
+            usecase,
+            correction,
+            pipeline_catalog,
+            batch_limits,
+        );
         let command_service = Arc::new(GenericCommandService::new(Arc::new(registry)));
         let state = AppState::new(command_service, UserIdExtractor::new());
 
-        Ok(Self { config, state })
+        Ok(Self {
+            config,
+            state,
+            readiness,
+            usage,
+            streaming,
+            rtp_legs,
+        })
     }
 
     pub async fn run(self, server_config: ServerConfig) -> Result<(), Error> {
-        create_app_routes(self.state, server_config)
-            .await
-            .map_err(|err| anyhow!("orchestration http server failed: {err}"))
+        let readiness = spawn_admin_with_routes(
+            "orchestration",
+            self.config.admin.clone(),
+            self.readiness,
+            ConfigReloader::new(&self.config, load_config),
+            admin_routes(self.usage),
+        );
+        let listener = &self.config.service.streaming;
+        let streaming_addr = format!("{}:{}", listener.host, listener.port);
+        let grpc_streaming_addr = format!("{}:{}", listener.host, listener.grpc_port);
+        let streaming = self.streaming;
+        let rtp_settings = rtp_settings(&listener.rtp);
+        let rtp_server = {
+            let (streaming, legs) = (streaming.clone(), self.rtp_legs);
+            async move {
+                if legs.is_empty() {
+                    return Ok(());
+                }
+                serve_rtp_legs(streaming, legs, rtp_settings)
+                    .await
+                    .map_err(|err| anyhow!("orchestration rtp legs failed: {err}"))
+            }
+        };
+        let grpc_streaming_server = {
+            let streaming = streaming.clone();
+            async move {
+                run_grpc_server(streaming, &grpc_streaming_addr)
+                    .await
+                    .map_err(|err| anyhow!("orchestration gRPC streaming server failed: {err}"))
+            }
+        };
+        let streaming_server = async move {
+            run_server(build_router(streaming), &streaming_addr)
+                .await
+                .map_err(|err| anyhow!("orchestration streaming server failed: {err}"))
+        };
+        let state = self.state;
+        let http_server = async move {
+            create_app_routes(state, server_config, readiness)
+                .await
+                .map_err(|err| anyhow!("orchestration http server failed: {err}"))
+        };
+        tokio::try_join!(http_server, streaming_server, grpc_streaming_server, rtp_server)
+            .map(|_| ())
     }
 }
 
+/// Streaming sessions run `pipeline` within the limits of
+/// `service.streaming`, logging frames as `service.log_sampling` says, and
+/// receive the `late_events` its stages publish for them. `tenants` is the
+/// HTTP use case's policy, so both count against one quota; a tenant in
+/// `tenant_routes` runs its own pipeline and model. Flushes are accounted
+/// in `usage`, the HTTP use case's meter.
+fn streaming_state(
+    config: &ServiceConfig,
+    pipeline: PipelineEngine,
+    late_events: LateEvents,
+    tenants: TenantPolicy,
+    tenant_routes: HashMap<String, StreamingRoute>,
+    usage: UsageMeter,
+) -> StreamingState {
+    let streaming = &config.streaming;
+    StreamingState {
+        pipeline: Arc::new(pipeline),
+        max_message_bytes: streaming.max_message_bytes,
+        frame_log_every: config.log_sampling.stream_frame_every,
+        budget: StreamBudget::new(streaming.max_buffered_bytes),
+        outbound_queue_len: streaming.outbound_queue_len,
+        stall_timeout: Duration::from_millis(streaming.stall_timeout_ms),
+        late_events,
+        tenants,
+        tenant_routes: Arc::new(tenant_routes),
+        usage,
+    }
+}
+
+/// Legs of `config`, each receiving on its port of `host`.
+fn rtp_legs(host: &str, config: &RtpConfig) -> Result<Vec<RtpLeg>, Error> {
+    config
+        .legs
+        .iter()
+        .map(|leg| {
+            let local_addr = format!("{host}:{}", leg.port)
+                .parse()
+                .map_err(|err| anyhow!("rtp leg `{}`: {err}", leg.call_id))?;
+            let codec = match leg.codec {
+                RtpCodecConfig::Pcmu => RtpCodec::Pcmu,
+                RtpCodecConfig::Pcma => RtpCodec::Pcma,
+                RtpCodecConfig::Opus => RtpCodec::Opus { stereo: false },
+            };
+            let language_hint = leg
+                .language_hint
+                .as_deref()
+                .map(LanguageTag::parse)
+                .transpose()
+                .map_err(|err| anyhow!("rtp leg `{}`: {err}", leg.call_id))?;
+            let tenant_id = leg
+                .tenant_id
+                .as_deref()
+                .map(parse_tenant_id)
+                .transpose()
+                .map_err(|err| anyhow!("rtp leg `{}`: {err}", leg.call_id))?;
+            Ok(RtpLeg::new(leg.call_id.clone(), local_addr, codec)
+                .with_language_hint(language_hint)
+                .with_tenant_id(tenant_id))
+        })
+        .collect()
+}
+
+fn rtp_settings(config: &RtpConfig) -> RtpSettings {
+    RtpSettings {
+        flush_interval: Duration::from_millis(config.flush_interval_ms.max(1)),
+        idle_timeout: Duration::from_millis(config.idle_timeout_ms.max(1)),
+    }
+}
+
+/// `asr_transcribe` and `alignment_enrich`, and the pools `/readyz` probes
+/// for them.
+pub(crate) struct ModelStages {
+    pub(crate) asr: Arc<dyn PipelineStage>,
+    pub(crate) alignment: Arc<dyn PipelineStage>,
+    pub(crate) channels: Vec<Arc<GrpcChannelPool>>,
+}
+
+/// [`ModelStages`] of the tenants with their own ASR or alignment service,
+/// by tenant id. The tenant's `two_pass_transcribe` and `ensemble_transcribe`
+/// steps still call the shared ASR service.
+async fn tenant_model_stages(
+    config: &ServiceConfig,
+) -> Result<HashMap<String, ModelStages>, Error> {
+    let mut stages = HashMap::new();
+    for (tenant_id, tenant) in &config.tenancy.tenants {
+        if tenant.asr.is_none() && tenant.alignment.is_none() {
+            continue;
+        }
+        if cfg!(feature = "monolith") {
+            return Err(anyhow!(
+                "tenant `{tenant_id}` names its own asr or alignment service, \
+                 which a monolith build does not call"
+            ));
+        }
+        let tenant_config = ServiceConfig {
+            asr: tenant.asr.clone().unwrap_or_else(|| config.asr.clone()),
+            alignment: tenant
+                .alignment
+                .clone()
+                .unwrap_or_else(|| config.alignment.clone()),
+            ..config.clone()
+        };
+        stages.insert(tenant_id.clone(), model_stages(&tenant_config).await?);
+    }
+    Ok(stages)
+}
+
+/// Stages calling the ASR and alignment services over gRPC.
+#[cfg(not(feature = "monolith"))]
+async fn model_stages(config: &ServiceConfig) -> Result<ModelStages, Error> {
+    let asr_channels = channel_pool("asr", &config.asr).await?;
+    connect_with_retry("asr", || asr_channels.connect()).await?;
+    let alignment_channels = channel_pool("alignment", &config.alignment).await?;
+    connect_with_retry("alignment", || alignment_channels.connect()).await?;
+    Ok(ModelStages {
+        asr: Arc::new(AsrTranscribeStage::new(
+            asr_channels.clone(),
+            request_timeout(&config.asr),
+        )),
+        alignment: Arc::new(
+            AlignmentEnrichStage::new(
+                alignment_channels.clone(),
+                request_timeout(&config.alignment),
+            )
+            .with_pause_threshold_ms(config.pauses.threshold_ms),
+        ),
+        channels: vec![asr_channels, alignment_channels],
+    })
+}
+
+#[derive(Clone)]
 struct GrpcPipelineStepLoader {
     audio_transform: Arc<dyn PipelineStage>,
+    /// `audio_transform` stages with their own ops, by step name.
+    audio_recipes: HashMap<String, Arc<dyn PipelineStage>>,
+    /// Steps of the libraries in `service.pipeline.plugin_dir`, by name;
+    /// built-in names take precedence.
+    plugins: HashMap<String, Arc<dyn PipelineStage>>,
+    /// Module of `service.wasm_step`, when configured.
+    wasm_step: Option<Arc<dyn PipelineStage>>,
+    /// Script of `service.script_step`, when configured.
+    script_step: Option<Arc<dyn PipelineStage>>,
+    /// Webhook of `service.http_enrich`, when configured.
+    http_enrich: Option<Arc<dyn PipelineStage>>,
+    /// Recorder of `service.recording`, when configured.
+    record_session: Option<Arc<dyn PipelineStage>>,
+    /// Writer of `service.result_store`, when configured.
+    store_result: Option<Arc<dyn PipelineStage>>,
+    /// In-process clamp and resample, for deployments without an audio
+    /// service.
+    local_audio_clamp: Arc<dyn PipelineStage>,
+    local_resample: Arc<dyn PipelineStage>,
     asr_transcribe: Arc<dyn PipelineStage>,
     alignment_enrich: Arc<dyn PipelineStage>,
+    store_session: Arc<dyn PipelineStage>,
+    dedup_lookup: Arc<dyn PipelineStage>,
+    context_carry_over: Arc<dyn PipelineStage>,
+    audio_quality_gate: Arc<dyn PipelineStage>,
+    provided_transcript: Arc<dyn PipelineStage>,
+    two_pass_transcribe: Arc<dyn PipelineStage>,
+    ensemble_transcribe: Arc<dyn PipelineStage>,
+    normalize_transcript: Arc<dyn PipelineStage>,
+    tag_disfluencies: Arc<dyn PipelineStage>,
     tts_synthesize: Arc<dyn PipelineStage>,
     snapshot_original_timings: Arc<dyn PipelineStage>,
     swap_tts_audio: Arc<dyn PipelineStage>,
@@ -166,12 +586,23 @@ impl PipelineStepLoader for GrpcPipelineStepLoader {
     fn load_step(&self, step: &PipelineStepSpec) -> Result<Arc<dyn PipelineStage>, DomainError> {
         match step.name.as_str() {
             "audio_transform" => Ok(self.audio_transform.clone()),
+            "local_audio_clamp" => Ok(self.local_audio_clamp.clone()),
+            "local_resample" => Ok(self.local_resample.clone()),
             "asr_transcribe" | "asr_transcribe_tts" | "asr_transcribe_result" => {
                 Ok(self.asr_transcribe.clone())
             }
             "alignment_enrich" | "alignment_enrich_tts" | "alignment_enrich_result" => {
                 Ok(self.alignment_enrich.clone())
             }
+            "store_session" => Ok(self.store_session.clone()),
+            "dedup_lookup" => Ok(self.dedup_lookup.clone()),
+            "context_carry_over" => Ok(self.context_carry_over.clone()),
+            "audio_quality_gate" => Ok(self.audio_quality_gate.clone()),
+            "provided_transcript" => Ok(self.provided_transcript.clone()),
+            "two_pass_transcribe" => Ok(self.two_pass_transcribe.clone()),
+            "ensemble_transcribe" => Ok(self.ensemble_transcribe.clone()),
+            "normalize_transcript" => Ok(self.normalize_transcript.clone()),
+            "tag_disfluencies" => Ok(self.tag_disfluencies.clone()),
             "tts_synthesize" => Ok(self.tts_synthesize.clone()),
             "snapshot_original_timings" => Ok(self.snapshot_original_timings.clone()),
             "swap_tts_audio" => Ok(self.swap_tts_audio.clone()),
@@ -181,12 +612,410 @@ impl PipelineStepLoader for GrpcPipelineStepLoader {
             "dump_tts_aligned" => Ok(self.dump_tts_aligned.clone()),
             "dump_tempo_result" => Ok(self.dump_tempo_result.clone()),
             "dump_final" => Ok(self.dump_final.clone()),
-            _ => Err(DomainError::internal_error(&format!(
-                "unknown pipeline step `{}`",
-                step.name
-            ))),
+            "wasm_step" => self.wasm_step.clone().ok_or_else(|| {
+                DomainError::internal_error("step `wasm_step` needs service.wasm_step")
+            }),
+            "script_step" => self.script_step.clone().ok_or_else(|| {
+                DomainError::internal_error("step `script_step` needs service.script_step")
+            }),
+            "http_enrich" => self.http_enrich.clone().ok_or_else(|| {
+                DomainError::internal_error("step `http_enrich` needs service.http_enrich")
+            }),
+            "record_session" => self.record_session.clone().ok_or_else(|| {
+                DomainError::internal_error("step `record_session` needs service.recording")
+            }),
+            "store_result" => self.store_result.clone().ok_or_else(|| {
+                DomainError::internal_error("step `store_result` needs service.result_store")
+            }),
+            name => self
+                .audio_recipes
+                .get(name)
+                .or_else(|| self.plugins.get(name))
+                .cloned()
+                .ok_or_else(|| {
+                    DomainError::internal_error(&format!("unknown pipeline step `{name}`"))
+                }),
+        }
+    }
+}
+
+impl GrpcPipelineStepLoader {
+    /// Where the loaded `stage` of step `name` comes from; built-in names
+    /// shadow recipes and plugins, so only an identical stage counts.
+    fn step_source(&self, name: &str, stage: &Arc<dyn PipelineStage>) -> StepSource {
+        let defines = |steps: &HashMap<String, Arc<dyn PipelineStage>>| {
+            steps.get(name).is_some_and(|step| Arc::ptr_eq(step, stage))
+        };
+        if defines(&self.audio_recipes) {
+            StepSource::Recipe
+        } else if defines(&self.plugins) {
+            StepSource::Plugin
+        } else {
+            StepSource::Builtin
+        }
+    }
+
+    /// Steps that exist only with a build feature, a config block, a recipe
+    /// or a plugin library.
+    fn optional_steps(&self) -> Vec<StepAvailability> {
+        let builtin = |name: &str, stage: &Option<Arc<dyn PipelineStage>>, requires: &str| {
+            StepAvailability {
+                name: name.to_string(),
+                source: StepSource::Builtin,
+                available: stage.is_some(),
+                requires: stage.is_none().then(|| requires.to_string()),
+            }
+        };
+        let mut steps = vec![
+            builtin(
+                "wasm_step",
+                &self.wasm_step,
+                "feature `wasm-steps` and service.wasm_step",
+            ),
+            builtin(
+                "script_step",
+                &self.script_step,
+                "feature `script-steps` and service.script_step",
+            ),
+            builtin("http_enrich", &self.http_enrich, "service.http_enrich"),
+            builtin("record_session", &self.record_session, "service.recording"),
+            builtin("store_result", &self.store_result, "service.result_store"),
+        ];
+        for (source, names) in [
+            (StepSource::Recipe, sorted_names(&self.audio_recipes)),
+            (StepSource::Plugin, sorted_names(&self.plugins)),
+        ] {
+            steps.extend(names.into_iter().map(|name| StepAvailability {
+                name,
+                source,
+                available: true,
+                requires: None,
+            }));
+        }
+        steps
+    }
+}
+
+fn sorted_names(steps: &HashMap<String, Arc<dyn PipelineStage>>) -> Vec<String> {
+    let mut names: Vec<String> = steps.keys().cloned().collect();
+    names.sort();
+    names
+}
+
+/// Every configured pipeline resolved through `loader`, served by
+/// `GET /api/asr/pipelines`.
+fn pipeline_catalog(
+    config: &ServiceConfig,
+    loader: &GrpcPipelineStepLoader,
+) -> Result<PipelineCatalog, DomainError> {
+    let mut pipelines = config
+        .pipeline
+        .definitions
+        .iter()
+        .map(|(name, definition)| {
+            describe_pipeline(config, loader, name, &build_pipeline_definition(definition))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    pipelines.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(PipelineCatalog {
+        selected: config.pipeline.selected.clone(),
+        pipelines,
+        optional_steps: loader.optional_steps(),
+    })
+}
+
+fn describe_pipeline(
+    config: &ServiceConfig,
+    loader: &GrpcPipelineStepLoader,
+    name: &str,
+    definition: &PipelineDefinition,
+) -> Result<PipelineDescription, DomainError> {
+    let phases = definition
+        .pre
+        .iter()
+        .map(|step| (PipelinePhase::Pre, step))
+        .chain(std::iter::once((PipelinePhase::Transcription, &definition.transcription)))
+        .chain(definition.post.iter().map(|step| (PipelinePhase::Post, step)));
+
+    let mut steps = Vec::new();
+    let mut endpoints: Vec<EndpointDescription> = Vec::new();
+    for (phase, step) in phases {
+        let stage = loader.load_step(step)?;
+        let services = stage_services(stage.name());
+        for service in services {
+            if !endpoints.iter().any(|endpoint| endpoint.service == *service) {
+                endpoints.push(EndpointDescription {
+                    service: service.to_string(),
+                    targets: service_targets(config, service),
+                });
+            }
+        }
+        steps.push(PipelineStepDescription {
+            phase,
+            name: step.name.clone(),
+            stage: stage.name().to_string(),
+            source: loader.step_source(&step.name, &stage),
+            services: services.iter().map(ToString::to_string).collect(),
+        });
+    }
+    Ok(PipelineDescription {
+        name: name.to_string(),
+        selected: name == config.pipeline.selected,
+        steps,
+        endpoints,
+    })
+}
+
+/// Downstream services a stage calls, by stage name.
+fn stage_services(stage: &str) -> &'static [&'static str] {
+    let in_process_models = cfg!(feature = "monolith");
+    match stage {
+        "audio_transform" => &["audio"],
+        "asr_transcribe" | "ensemble_transcribe" if !in_process_models => &["asr"],
+        "alignment_enrich" if !in_process_models => &["alignment"],
+        "two_pass_transcribe" if !in_process_models => &["asr", "alignment"],
+        "tts_synthesize" => &["tts"],
+        "tempo_match" => &["tempo"],
+        "http_enrich" => &["http_enrich"],
+        _ => &[],
+    }
+}
+
+fn service_targets(config: &ServiceConfig, service: &str) -> Vec<String> {
+    match service {
+        "audio" => config.audio.targets(),
+        "asr" => config.asr.targets(),
+        "alignment" => config.alignment.targets(),
+        "tts" => config.tts.targets(),
+        "tempo" => config.tempo.targets(),
+        "http_enrich" => config
+            .http_enrich
+            .iter()
+            .map(|webhook| webhook.url.clone())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(feature = "dylib-plugins")]
+fn plugin_steps(config: &PipelineConfig) -> Result<HashMap<String, Arc<dyn PipelineStage>>, Error> {
+    match &config.plugin_dir {
+        Some(dir) => crate::plugins::load_plugin_dir(std::path::Path::new(dir)),
+        None => Ok(HashMap::new()),
+    }
+}
+
+#[cfg(not(feature = "dylib-plugins"))]
+fn plugin_steps(config: &PipelineConfig) -> Result<HashMap<String, Arc<dyn PipelineStage>>, Error> {
+    match &config.plugin_dir {
+        Some(dir) => Err(anyhow!(
+            "service.pipeline.plugin_dir is set to `{dir}` but orchestration-setup was built \
+             without the `dylib-plugins` feature"
+        )),
+        None => Ok(HashMap::new()),
+    }
+}
+
+#[cfg(feature = "wasm-steps")]
+fn wasm_step(config: Option<&WasmStepConfig>) -> Result<Option<Arc<dyn PipelineStage>>, Error> {
+    let Some(config) = config else {
+        return Ok(None);
+    };
+    let module = std::fs::read(&config.module_path)
+        .map_err(|err| anyhow!("wasm_step module {}: {err}", config.module_path))?;
+    let limits = orchestration_infra::WasmLimits {
+        fuel: config.fuel,
+        max_memory_bytes: config.max_memory_bytes,
+    };
+    let stage = orchestration_infra::WasmStepStage::new(module, limits)
+        .map_err(|err| anyhow!("wasm_step module {}: {err}", config.module_path))?;
+    tracing::info!(module = %config.module_path, "loaded wasm_step module");
+    Ok(Some(Arc::new(stage)))
+}
+
+#[cfg(not(feature = "wasm-steps"))]
+fn wasm_step(config: Option<&WasmStepConfig>) -> Result<Option<Arc<dyn PipelineStage>>, Error> {
+    match config {
+        Some(config) => Err(anyhow!(
+            "service.wasm_step is set to `{}` but orchestration-setup was built without the \
+             `wasm-steps` feature",
+            config.module_path
+        )),
+        None => Ok(None),
+    }
+}
+
+#[cfg(feature = "script-steps")]
+fn script_step(
+    config: Option<&ScriptStepConfig>,
+) -> Result<Option<Arc<dyn PipelineStage>>, Error> {
+    let Some(config) = config else {
+        return Ok(None);
+    };
+    let source = std::fs::read_to_string(&config.script_path)
+        .map_err(|err| anyhow!("script_step script {}: {err}", config.script_path))?;
+    let stage = orchestration_infra::ScriptStepStage::new(&source, config.max_operations)
+        .map_err(|err| anyhow!("script_step script {}: {err}", config.script_path))?;
+    tracing::info!(script = %config.script_path, "loaded script_step script");
+    Ok(Some(Arc::new(stage)))
+}
+
+#[cfg(not(feature = "script-steps"))]
+fn script_step(
+    config: Option<&ScriptStepConfig>,
+) -> Result<Option<Arc<dyn PipelineStage>>, Error> {
+    match config {
+        Some(config) => Err(anyhow!(
+            "service.script_step is set to `{}` but orchestration-setup was built without the \
+             `script-steps` feature",
+            config.script_path
+        )),
+        None => Ok(None),
+    }
+}
+
+/// `record_session` stage of `service.recording`; also starts the sweeper
+/// deleting expired recordings.
+fn record_session(
+    config: Option<&RecordingConfig>,
+) -> Result<Option<Arc<dyn PipelineStage>>, Error> {
+    let Some(config) = config else {
+        return Ok(None);
+    };
+    let store = object_store(&config.store)?;
+    if config.retention_hours > 0 {
+        RecordingRetention::new(
+            store.clone(),
+            config.prefix.clone(),
+            Duration::from_secs(config.retention_hours * 3600),
+        )
+        .spawn(Duration::from_secs(config.sweep_interval_secs.max(1)));
+    }
+    tracing::info!(prefix = %config.prefix, "recording sessions");
+    Ok(Some(Arc::new(RecordSessionStage::new(store, config.prefix.clone()))))
+}
+
+fn store_result(
+    config: Option<&ResultStoreConfig>,
+) -> Result<Option<Arc<dyn PipelineStage>>, Error> {
+    let Some(config) = config else {
+        return Ok(None);
+    };
+    let formats = config
+        .formats
+        .iter()
+        .map(|format| {
+            ResultFormat::parse(format).ok_or_else(|| {
+                anyhow!("service.result_store.formats: unknown format `{format}`")
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let limits = CueLimits {
+        max_ms: config.max_cue_ms,
+        max_chars: config.max_cue_chars,
+    };
+    let store = object_store(&config.store)?;
+    Ok(Some(Arc::new(StoreResultStage::new(store, config.prefix.clone(), formats, limits))))
+}
+
+fn object_store(config: &ObjectStoreConfig) -> Result<Arc<dyn ObjectStorePort>, Error> {
+    let store = match config {
+        ObjectStoreConfig::Directory { path } => ObjectStoreAdapter::directory(path),
+        ObjectStoreConfig::S3 {
+            bucket,
+            endpoint,
+            region,
+            access_key_id,
+            secret_access_key,
+            allow_http,
+        } => ObjectStoreAdapter::s3(&S3Settings {
+            bucket: bucket.clone(),
+            endpoint: endpoint.clone(),
+            region: region.clone(),
+            access_key_id: access_key_id.clone(),
+            secret_access_key: secret_access_key.clone(),
+            allow_http: *allow_http,
+        }),
+    };
+    Ok(Arc::new(store.map_err(|err| anyhow!("{err}"))?))
+}
+
+fn audio_source(config: &AudioFetchConfig) -> Result<RemoteAudioSource, Error> {
+    RemoteAudioSource::new(AudioFetchSettings {
+        timeout: Duration::from_millis(config.timeout_ms),
+        max_bytes: config.max_bytes,
+        s3: S3Settings {
+            bucket: String::new(),
+            endpoint: config.s3_endpoint.clone(),
+            region: config.s3_region.clone(),
+            access_key_id: config.s3_access_key_id.clone(),
+            secret_access_key: config.s3_secret_access_key.clone(),
+            allow_http: config.s3_allow_http,
+        },
+        allowed_hosts: config.allowed_hosts.clone(),
+        allowed_buckets: config.allowed_buckets.clone(),
+    })
+    .map_err(|err| anyhow!("orchestration audio fetch: {err}"))
+}
+
+fn tenant_policy(config: &TenancyConfig) -> TenantPolicy {
+    let mut policy = TenantPolicy::default();
+    if config.required {
+        policy = policy.require_tenant();
+    }
+    if let Some(limit) = config.default_max_concurrent_requests {
+        policy = policy.with_default_max_concurrent(limit);
+    }
+    for (tenant_id, tenant) in &config.tenants {
+        if let Some(limit) = tenant.max_concurrent_requests {
+            policy = policy.with_max_concurrent(tenant_id.clone(), limit);
         }
     }
+    policy
+}
+
+/// Sends the usage accrued since the previous event every `interval_secs`;
+/// usage the webhook does not take is kept for the next event.
+fn spawn_usage_events(config: &UsageEventsConfig, usage: UsageMeter) {
+    let sink = UsageWebhook::new(
+        config.webhook_url.clone(),
+        Duration::from_millis(config.request_timeout_ms),
+    )
+    .with_headers(config.headers.clone());
+    let interval = Duration::from_secs(config.interval_secs.max(1));
+    tokio::spawn(async move {
+        let mut ticks = tokio::time::interval(interval);
+        // The first tick is immediate and has nothing to report.
+        ticks.tick().await;
+        loop {
+            ticks.tick().await;
+            let records = usage.take_unreported();
+            if records.is_empty() {
+                continue;
+            }
+            match sink.publish(&records).await {
+                Ok(()) => tracing::debug!(record_count = records.len(), "sent usage event"),
+                Err(err) => {
+                    tracing::warn!(error = %err, "usage event failed; retrying next interval");
+                    usage.restore_unreported(&records);
+                }
+            }
+        }
+    });
+}
+
+/// Whether any configured pipeline has a step served by the audio service.
+fn uses_audio_service(config: &ServiceConfig) -> bool {
+    config.pipeline.definitions.values().any(|definition| {
+        definition
+            .pre
+            .iter()
+            .chain(std::iter::once(&definition.transcription))
+            .chain(&definition.post)
+            .any(|step| {
+                step.name() == "audio_transform" || config.audio_recipes.contains_key(step.name())
+            })
+    })
 }
 
 fn build_pipeline_definition(definition: &PipelineDefinitionConfig) -> PipelineDefinition {
@@ -205,11 +1034,68 @@ fn build_pipeline_definition(definition: &PipelineDefinitionConfig) -> PipelineD
     }
 }
 
+fn replacement_rules(config: &NormalizationConfig) -> Result<Vec<ReplacementRule>, Error> {
+    config
+        .rules
+        .iter()
+        .map(|rule| {
+            let language = rule
+                .language
+                .as_deref()
+                .map(LanguageTag::parse)
+                .transpose()
+                .map_err(|err| anyhow!("normalization rule `{}`: {err}", rule.pattern))?;
+            let (pattern, replacement) = (rule.pattern.as_str(), rule.replacement.clone());
+            let language = language.as_ref();
+            let built = if rule.regex {
+                ReplacementRule::regex(pattern, replacement, rule.ignore_case, language)
+            } else {
+                ReplacementRule::exact(pattern, replacement, rule.ignore_case, language)
+            };
+            built.map_err(|err| anyhow!("normalization rule `{}`: {err}", rule.pattern))
+        })
+        .collect()
+}
+
 fn grpc_endpoint_uri(config: &GrpcEndpointConfig) -> String {
     let scheme = if config.tls_enabled { "https" } else { "http" };
     format!("{scheme}://{}:{}", config.host, config.port)
 }
 
+async fn channel_pool(
+    service: &'static str,
+    config: &GrpcEndpointConfig,
+) -> Result<Arc<GrpcChannelPool>, Error> {
+    let targets = if config.resolve_all_addresses {
+        expand_targets(service, &config.targets()).await?
+    } else {
+        config.targets().into_iter().map(GrpcTarget::new).collect()
+    };
+    if targets.is_empty() {
+        return Err(anyhow!("no {service} replicas configured"));
+    }
+    tracing::info!(service, replicas = ?targets, "configured gRPC replicas");
+
+    Ok(Arc::new(GrpcChannelPool::new(
+        service,
+        GrpcPoolConfig {
+            targets,
+            tls_enabled: config.tls_enabled,
+            connect_timeout: connect_timeout(config),
+            pool_size: config.pool_size,
+            policy: match config.load_balancing {
+                LoadBalancingPolicy::RoundRobin => BalancingPolicy::RoundRobin,
+                LoadBalancingPolicy::LeastLoaded => BalancingPolicy::LeastLoaded,
+            },
+            initial_backoff: Duration::from_millis(config.reconnect_initial_backoff_ms.max(1)),
+            max_backoff: Duration::from_millis(config.reconnect_max_backoff_ms.max(1)),
+            resolve_dns_on_reconnect: config.resolve_dns_on_reconnect,
+            max_decoding_message_bytes: config.max_decoding_message_bytes,
+            max_encoding_message_bytes: config.max_encoding_message_bytes,
+        },
+    )))
+}
+
 fn connect_timeout(config: &GrpcEndpointConfig) -> Duration {
     Duration::from_millis(config.connect_timeout_ms.max(1))
 }
@@ -243,7 +1129,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use orchestration_configuration::{PipelineDefinitionConfig, PipelineStepRef};
+    use orchestration_configuration::{PipelineDefinitionConfig, PipelineStepRef, RtpLegConfig};
 
     use super::*;
 
@@ -292,8 +1178,32 @@ mod tests {
     fn make_test_loader() -> GrpcPipelineStepLoader {
         GrpcPipelineStepLoader {
             audio_transform: make_fake_stage("audio_transform"),
+            audio_recipes: HashMap::from([(
+                "audio_cleanup".to_string(),
+                make_fake_stage("audio_transform"),
+            )]),
+            plugins: HashMap::from([
+                ("profanity_filter".to_string(), make_fake_stage("profanity_filter")),
+                ("audio_cleanup".to_string(), make_fake_stage("plugin_audio_cleanup")),
+            ]),
+            wasm_step: None,
+            script_step: None,
+            http_enrich: None,
+            record_session: None,
+            store_result: None,
+            local_audio_clamp: make_fake_stage("local_audio_clamp"),
+            local_resample: make_fake_stage("local_resample"),
             asr_transcribe: make_fake_stage("asr_transcribe"),
             alignment_enrich: make_fake_stage("alignment_enrich"),
+            store_session: make_fake_stage("store_session"),
+            dedup_lookup: make_fake_stage("dedup_lookup"),
+            context_carry_over: make_fake_stage("context_carry_over"),
+            audio_quality_gate: make_fake_stage("audio_quality_gate"),
+            provided_transcript: make_fake_stage("provided_transcript"),
+            two_pass_transcribe: make_fake_stage("two_pass_transcribe"),
+            ensemble_transcribe: make_fake_stage("ensemble_transcribe"),
+            normalize_transcript: make_fake_stage("normalize_transcript"),
+            tag_disfluencies: make_fake_stage("tag_disfluencies"),
             tts_synthesize: make_fake_stage("tts_synthesize"),
             snapshot_original_timings: make_fake_stage("snapshot_original_timings"),
             swap_tts_audio: make_fake_stage("swap_tts_audio"),
@@ -364,6 +1274,240 @@ mod tests {
             .is_err());
     }
 
+    #[test]
+    fn local_audio_steps_load_without_the_audio_service() {
+        let loader = make_test_loader();
+        for name in ["local_audio_clamp", "local_resample"] {
+            assert_eq!(
+                loader.load_step(&PipelineStepSpec::new(name)).unwrap().name(),
+                name
+            );
+        }
+
+        let mut config = ServiceConfig::default();
+        assert!(uses_audio_service(&config));
+        for definition in config.pipeline.definitions.values_mut() {
+            definition.pre = vec![
+                PipelineStepRef::Name("local_audio_clamp".to_string()),
+                PipelineStepRef::Name("local_resample".to_string()),
+            ];
+        }
+        assert!(!uses_audio_service(&config));
+        config
+            .audio_recipes
+            .insert("audio_cleanup".to_string(), vec!["resample".to_string()]);
+        for definition in config.pipeline.definitions.values_mut() {
+            definition.pre = vec![PipelineStepRef::Name("audio_cleanup".to_string())];
+        }
+        assert!(uses_audio_service(&config));
+    }
+
+    #[test]
+    fn loader_resolves_audio_recipes_by_step_name() {
+        let loader = make_test_loader();
+
+        let recipe = loader
+            .load_step(&PipelineStepSpec::new("audio_cleanup"))
+            .unwrap();
+        assert_eq!(recipe.name(), "audio_transform");
+        let default = loader
+            .load_step(&PipelineStepSpec::new("audio_transform"))
+            .unwrap();
+        assert!(!Arc::ptr_eq(&recipe, &default));
+    }
+
+    #[test]
+    fn loader_falls_back_to_plugin_steps() {
+        let loader = make_test_loader();
+
+        let plugin = loader
+            .load_step(&PipelineStepSpec::new("profanity_filter"))
+            .unwrap();
+        assert_eq!(plugin.name(), "profanity_filter");
+        let recipe = loader
+            .load_step(&PipelineStepSpec::new("audio_cleanup"))
+            .unwrap();
+        assert_eq!(recipe.name(), "audio_transform");
+    }
+
+    #[test]
+    fn streaming_state_follows_the_streaming_config() {
+        let mut config = ServiceConfig::default();
+        config.streaming.max_buffered_bytes = 1024;
+        config.streaming.stall_timeout_ms = 250;
+        config.log_sampling.stream_frame_every = 7;
+
+        let state = streaming_state(
+            &config,
+            PipelineEngine::default(),
+            LateEvents::default(),
+            TenantPolicy::default(),
+            HashMap::new(),
+            UsageMeter::default(),
+        );
+        assert_eq!(state.frame_log_every, 7);
+        assert_eq!(state.budget.snapshot().max_buffered_bytes, 1024);
+        assert_eq!(state.stall_timeout, Duration::from_millis(250));
+    }
+
+    #[test]
+    fn rtp_legs_follow_the_streaming_config() {
+        let mut config = ServiceConfig::default();
+        config.streaming.rtp.legs = vec![RtpLegConfig {
+            call_id: "trunk-1".to_string(),
+            port: 40_000,
+            codec: RtpCodecConfig::Pcma,
+            language_hint: Some("fr".to_string()),
+            tenant_id: Some("acme".to_string()),
+        }];
+
+        let legs = rtp_legs("127.0.0.1", &config.streaming.rtp).unwrap();
+        assert_eq!(legs.len(), 1);
+        assert_eq!(legs[0].local_addr, "127.0.0.1:40000".parse().unwrap());
+        assert_eq!(legs[0].codec, RtpCodec::Pcma);
+        assert_eq!(legs[0].payload_type, 8);
+        assert_eq!(legs[0].language_hint, Some(LanguageTag::fr()));
+        assert_eq!(legs[0].tenant_id.as_deref(), Some("acme"));
+
+        config.streaming.rtp.legs[0].tenant_id = Some("acme/..".to_string());
+        assert!(rtp_legs("127.0.0.1", &config.streaming.rtp).is_err());
+        config.streaming.rtp.legs[0].tenant_id = None;
+        config.streaming.rtp.legs[0].language_hint = Some("not a tag!".to_string());
+        assert!(rtp_legs("127.0.0.1", &config.streaming.rtp).is_err());
+    }
+
+    #[test]
+    fn wasm_step_needs_a_configured_module() {
+        let mut loader = make_test_loader();
+        let error = match loader.load_step(&PipelineStepSpec::new("wasm_step")) {
+            Ok(_) => panic!("no module configured"),
+            Err(error) => error,
+        };
+        assert!(error.to_string().contains("service.wasm_step"), "{error}");
+
+        loader.wasm_step = Some(make_fake_stage("wasm_step"));
+        assert_eq!(
+            loader.load_step(&PipelineStepSpec::new("wasm_step")).unwrap().name(),
+            "wasm_step"
+        );
+    }
+
+    #[test]
+    fn http_enrich_needs_a_configured_webhook() {
+        let mut loader = make_test_loader();
+        assert!(loader
+            .load_step(&PipelineStepSpec::new("http_enrich"))
+            .is_err());
+
+        loader.http_enrich = Some(make_fake_stage("http_enrich"));
+        assert_eq!(
+            loader.load_step(&PipelineStepSpec::new("http_enrich")).unwrap().name(),
+            "http_enrich"
+        );
+    }
+
+    #[test]
+    fn record_session_needs_recording_config() {
+        let mut loader = make_test_loader();
+        assert!(loader
+            .load_step(&PipelineStepSpec::new("record_session"))
+            .is_err());
+
+        loader.record_session = Some(make_fake_stage("record_session"));
+        assert_eq!(
+            loader.load_step(&PipelineStepSpec::new("record_session")).unwrap().name(),
+            "record_session"
+        );
+    }
+
+    #[test]
+    fn store_result_needs_result_store_config() {
+        let mut loader = make_test_loader();
+        assert!(loader
+            .load_step(&PipelineStepSpec::new("store_result"))
+            .is_err());
+
+        loader.store_result = Some(make_fake_stage("store_result"));
+        assert_eq!(
+            loader.load_step(&PipelineStepSpec::new("store_result")).unwrap().name(),
+            "store_result"
+        );
+    }
+
+    #[test]
+    fn script_step_needs_a_configured_script() {
+        let mut loader = make_test_loader();
+        assert!(loader
+            .load_step(&PipelineStepSpec::new("script_step"))
+            .is_err());
+
+        loader.script_step = Some(make_fake_stage("script_step"));
+        assert_eq!(
+            loader.load_step(&PipelineStepSpec::new("script_step")).unwrap().name(),
+            "script_step"
+        );
+    }
+
+    #[test]
+    fn catalog_resolves_every_configured_pipeline() {
+        let loader = make_test_loader();
+        let mut config = ServiceConfig::default();
+        config
+            .pipeline
+            .definitions
+            .get_mut("align_only")
+            .expect("align_only pipeline")
+            .pre = vec![PipelineStepRef::Name("audio_cleanup".to_string())];
+
+        let catalog = pipeline_catalog(&config, &loader).expect("catalog");
+
+        assert_eq!(catalog.selected, "default");
+        assert_eq!(catalog.pipelines.len(), config.pipeline.definitions.len());
+        assert!(catalog.pipelines.windows(2).all(|pair| pair[0].name < pair[1].name));
+        let align_only = catalog
+            .pipelines
+            .iter()
+            .find(|pipeline| pipeline.name == "align_only")
+            .expect("align_only described");
+        assert!(!align_only.selected);
+        let steps: Vec<_> = align_only
+            .steps
+            .iter()
+            .map(|step| (step.phase, step.name.as_str(), step.source))
+            .collect();
+        assert_eq!(
+            steps,
+            vec![
+                (PipelinePhase::Pre, "audio_cleanup", StepSource::Recipe),
+                (PipelinePhase::Transcription, "provided_transcript", StepSource::Builtin),
+                (PipelinePhase::Post, "alignment_enrich", StepSource::Builtin),
+                (PipelinePhase::Post, "store_session", StepSource::Builtin),
+            ]
+        );
+        let services: Vec<_> = align_only
+            .endpoints
+            .iter()
+            .map(|endpoint| endpoint.service.as_str())
+            .collect();
+        if cfg!(feature = "monolith") {
+            assert_eq!(services, vec!["audio"]);
+        } else {
+            assert_eq!(services, vec!["audio", "alignment"]);
+            assert_eq!(align_only.endpoints[1].targets, config.alignment.targets());
+        }
+
+        let wasm_step = catalog
+            .optional_steps
+            .iter()
+            .find(|step| step.name == "wasm_step")
+            .expect("wasm_step listed");
+        assert!(!wasm_step.available);
+        assert!(catalog
+            .optional_steps
+            .iter()
+            .any(|step| step.name == "profanity_filter" && step.source == StepSource::Plugin));
+    }
+
     #[test]
     fn loader_aliases_reuse_same_stage() {
         let loader = make_test_loader();
@@ -0,0 +1,18 @@
+//! The orchestrator with Whisper and wav2vec2 linked in: one process serves
+//! what otherwise takes the orchestration, ASR and alignment services.
+
+use orchestration_configuration::{load_config, setup_logging};
+use orchestration_setup::build_and_run;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let config = load_config()?;
+    if config.admin.is_some() {
+        service_admin::install_logging(&config);
+    } else {
+        setup_logging(&config);
+    }
+    let server_config = config.server.clone();
+    build_and_run(config, server_config).await?;
+    Ok(())
+}
@@ -1,3 +1,7 @@
 pub mod app;
+#[cfg(feature = "monolith")]
+mod monolith;
+#[cfg(feature = "dylib-plugins")]
+mod plugins;
 
 pub use app::{build_and_run, Application};
@@ -0,0 +1,134 @@
+//! `monolith` build: Whisper and wav2vec2 run inside the orchestrator through
+//! the ASR and alignment use cases, so no gRPC hop and no separate process.
+
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use orchestration_configuration::ServiceConfig;
+use orchestration_domain::{DomainError, DomainEvent, Pause, PipelineContext, PipelineStage};
+use serde_json::json;
+
+use alignment_application::{AlignTranscriptUseCase, EnrichTranscriptRequest};
+use asr_application::{AsrUseCase, TranscribeAudioRequest};
+
+use crate::app::ModelStages;
+
+/// Fallback rate of the ASR use case; the stage always sends the real one.
+const ASR_SAMPLE_RATE_HZ: u32 = 16_000;
+
+/// Loads the models of `service.monolith`; nothing is probed by `/readyz`
+/// since startup fails when a model does not load.
+pub(crate) async fn model_stages(config: &ServiceConfig) -> Result<ModelStages, Error> {
+    tracing::info!("monolith build: running ASR and alignment in-process");
+    let (asr, _) = asr_setup::build_usecase(&config.monolith.asr, ASR_SAMPLE_RATE_HZ).await?;
+    let alignment = alignment_setup::build_usecase(&config.monolith.alignment).await?;
+    Ok(ModelStages {
+        asr: Arc::new(InProcessAsrStage { usecase: asr }),
+        alignment: Arc::new(InProcessAlignmentStage {
+            usecase: alignment,
+            pause_threshold_ms: config.pauses.threshold_ms,
+        }),
+        channels: Vec::new(),
+    })
+}
+
+/// `asr_transcribe` calling the ASR use case directly.
+pub struct InProcessAsrStage {
+    usecase: Arc<dyn AsrUseCase>,
+}
+
+#[async_trait]
+impl PipelineStage for InProcessAsrStage {
+    fn name(&self) -> &'static str {
+        "asr_transcribe"
+    }
+
+    async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+        let response = self
+            .usecase
+            .transcribe(TranscribeAudioRequest {
+                samples: context.audio.samples.clone(),
+                sample_rate_hz: Some(context.audio.sample_rate_hz),
+                language_hint: context.language_hint.as_ref().map(ToString::to_string),
+                session_id: Some(context.session_id.clone()),
+                model: context
+                    .extension("asr.model")
+                    .and_then(|value| value.as_str())
+                    .map(str::to_string),
+                reference_text: None,
+                decode_profile: context
+                    .extension("asr.decode_profile")
+                    .and_then(|value| value.as_str())
+                    .map(str::to_string),
+                grammar: context
+                    .extension("asr.grammar")
+                    .and_then(|value| value.as_str())
+                    .map(str::to_string),
+                time_offset_ms: None,
+                prompt: context
+                    .extension("asr.prompt")
+                    .and_then(|value| value.as_str())
+                    .map(str::to_string),
+            })
+            .await
+            .map_err(|err| DomainError::external_service_error("asr", &err.to_string()))?;
+
+        context.session_id = response.session_id;
+        context.transcript = Some(response.transcript.clone());
+        context.events.push(DomainEvent::FinalTranscript {
+            transcript: response.transcript,
+        });
+        context.set_extension("asr.text", json!(response.text));
+        if let Some(language) = response.detected_language {
+            context.set_extension("asr.detected_language", json!(language));
+        }
+        if let Some(probability) = response.language_probability {
+            context.set_extension("asr.language_probability", json!(probability));
+        }
+        Ok(())
+    }
+}
+
+/// `alignment_enrich` calling the alignment use case directly.
+pub struct InProcessAlignmentStage {
+    usecase: Arc<dyn AlignTranscriptUseCase>,
+    pause_threshold_ms: Option<u64>,
+}
+
+#[async_trait]
+impl PipelineStage for InProcessAlignmentStage {
+    fn name(&self) -> &'static str {
+        "alignment_enrich"
+    }
+
+    async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+        let transcript = context
+            .transcript
+            .clone()
+            .ok_or_else(|| DomainError::internal_error("no transcript available"))?;
+        let response = self
+            .usecase
+            .enrich_transcript(EnrichTranscriptRequest {
+                samples: context.audio.samples.clone(),
+                sample_rate_hz: Some(context.audio.sample_rate_hz),
+                transcript,
+                session_id: Some(context.session_id.clone()),
+                time_offset_ms: None,
+            })
+            .await
+            .map_err(|err| DomainError::external_service_error("alignment", &err.to_string()))?;
+
+        let words = response.aligned_words;
+        if let Some(threshold_ms) = self.pause_threshold_ms {
+            let pauses = Pause::between(&words, threshold_ms);
+            context.set_extension("alignment.pauses", json!(pauses));
+        }
+        context.session_id = response.session_id;
+        context.transcript = Some(response.transcript);
+        context.aligned_words = words.clone();
+        context.events.push(DomainEvent::AlignmentUpdate { words });
+        context.set_extension("alignment.text", json!(response.text));
+        Ok(())
+    }
+}
@@ -0,0 +1,234 @@
+//! Pipeline steps loaded from plugin libraries (`dylib-plugins` feature).
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Error};
+use async_trait::async_trait;
+use libloading::Library;
+use orchestration_domain::{
+    DomainError, PipelineContext, PipelineStage, PluginDeclaration, PluginRegistrar,
+    PLUGIN_ABI_VERSION, PLUGIN_DECLARATION_SYMBOL, PLUGIN_INTERFACE_VERSION, PLUGIN_RUSTC_HASH,
+};
+
+/// Loads every library of `dir` (`.so`, `.dylib` or `.dll` depending on the
+/// platform) and returns the steps they register, by name.
+pub(crate) fn load_plugin_dir(
+    dir: &Path,
+) -> Result<HashMap<String, Arc<dyn PipelineStage>>, Error> {
+    let mut paths = std::fs::read_dir(dir)
+        .map_err(|err| anyhow!("plugin directory {}: {err}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == std::env::consts::DLL_EXTENSION)
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    let mut steps = HashMap::new();
+    for path in paths {
+        for (name, stage) in load_plugin(&path)? {
+            tracing::info!(step = %name, plugin = %path.display(), "loaded plugin step");
+            if steps.insert(name.clone(), stage).is_some() {
+                return Err(anyhow!("plugin step `{name}` is registered twice"));
+            }
+        }
+    }
+    Ok(steps)
+}
+
+fn load_plugin(path: &Path) -> Result<Vec<(String, Arc<dyn PipelineStage>)>, Error> {
+    // SAFETY: loading runs the library initializers; plugin directories are
+    // trusted operator configuration, like the binary itself.
+    let library = unsafe { Library::new(path) }
+        .map_err(|err| anyhow!("plugin {}: {err}", path.display()))?;
+    let library = Arc::new(library);
+    // SAFETY: the symbol is the static exported by `export_pipeline_plugin!`.
+    let declaration = unsafe {
+        let symbol = library
+            .get::<*const PluginDeclaration>(PLUGIN_DECLARATION_SYMBOL)
+            .map_err(|err| anyhow!("plugin {}: {err}", path.display()))?;
+        &**symbol
+    };
+    check_declaration(declaration).map_err(|err| anyhow!("plugin {}: {err}", path.display()))?;
+
+    let mut registrar = StepRegistrar::default();
+    // SAFETY: the ABI and interface versions and the compiler match, so the
+    // registrar and the stages it receives have the layout the plugin was
+    // compiled for.
+    unsafe { (declaration.register)(&mut registrar) };
+    Ok(registrar
+        .steps
+        .into_iter()
+        .map(|(name, stage)| {
+            let stage: Arc<dyn PipelineStage> = Arc::new(PluginStage {
+                stage,
+                _library: library.clone(),
+            });
+            (name, stage)
+        })
+        .collect())
+}
+
+fn check_declaration(declaration: &PluginDeclaration) -> Result<(), Error> {
+    if declaration.abi_version != PLUGIN_ABI_VERSION {
+        return Err(anyhow!(
+            "built for plugin ABI {}, this orchestrator speaks {PLUGIN_ABI_VERSION}",
+            declaration.abi_version
+        ));
+    }
+    if declaration.interface_version != PLUGIN_INTERFACE_VERSION {
+        return Err(anyhow!(
+            "built for plugin interface {}, this orchestrator uses {PLUGIN_INTERFACE_VERSION}",
+            declaration.interface_version
+        ));
+    }
+    if declaration.rustc_hash != PLUGIN_RUSTC_HASH {
+        return Err(anyhow!(
+            "built with another compiler (rustc {}, this orchestrator {PLUGIN_RUSTC_HASH}); \
+             rebuild the plugin with the orchestrator's toolchain",
+            declaration.rustc_hash
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+struct StepRegistrar {
+    steps: Vec<(String, Arc<dyn PipelineStage>)>,
+}
+
+impl PluginRegistrar for StepRegistrar {
+    fn register_step(&mut self, name: &str, stage: Arc<dyn PipelineStage>) {
+        self.steps.push((name.to_string(), stage));
+    }
+}
+
+/// Keeps the library of a plugin stage loaded while the stage exists; the
+/// stage is declared first so it is dropped before the library.
+struct PluginStage {
+    stage: Arc<dyn PipelineStage>,
+    _library: Arc<Library>,
+}
+
+#[async_trait]
+impl PipelineStage for PluginStage {
+    fn name(&self) -> &'static str {
+        self.stage.name()
+    }
+
+    async fn execute(&self, context: &mut PipelineContext) -> Result<(), DomainError> {
+        self.stage.execute(context).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(improper_ctypes_definitions)]
+    unsafe extern "C" fn register_nothing(_registrar: &mut dyn PluginRegistrar) {}
+
+    fn declaration(
+        abi_version: u32,
+        interface_version: u32,
+        rustc_hash: &'static str,
+    ) -> PluginDeclaration {
+        PluginDeclaration {
+            abi_version,
+            interface_version,
+            rustc_hash,
+            register: register_nothing,
+        }
+    }
+
+    #[test]
+    fn declarations_from_other_versions_are_refused() {
+        let current = declaration(PLUGIN_ABI_VERSION, PLUGIN_INTERFACE_VERSION, PLUGIN_RUSTC_HASH);
+        assert!(check_declaration(&current).is_ok());
+
+        let newer_abi =
+            declaration(PLUGIN_ABI_VERSION + 1, PLUGIN_INTERFACE_VERSION, PLUGIN_RUSTC_HASH);
+        let error = check_declaration(&newer_abi).expect_err("newer ABI");
+        assert!(error.to_string().contains("plugin ABI"), "{error}");
+
+        let older_interface =
+            declaration(PLUGIN_ABI_VERSION, PLUGIN_INTERFACE_VERSION - 1, PLUGIN_RUSTC_HASH);
+        let error = check_declaration(&older_interface).expect_err("older interface");
+        assert!(error.to_string().contains("plugin interface"), "{error}");
+
+        let other_compiler =
+            declaration(PLUGIN_ABI_VERSION, PLUGIN_INTERFACE_VERSION, "0000000000000000");
+        let error = check_declaration(&other_compiler).expect_err("other compiler");
+        assert!(error.to_string().contains("another compiler"), "{error}");
+    }
+
+    #[test]
+    fn only_platform_libraries_are_loaded() {
+        let dir = std::env::temp_dir().join(format!("vocal-plugins-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("notes.txt"), "not a plugin").unwrap();
+        let steps = load_plugin_dir(&dir).expect("empty plugin directory");
+        assert!(steps.is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(load_plugin_dir(&dir).is_err());
+    }
+
+    /// Builds `plugin-example` in a target directory of its own: the one of
+    /// the running test stays locked by cargo.
+    fn build_example_plugin() -> std::path::PathBuf {
+        let target_dir = std::env::var_os("CARGO_TARGET_DIR")
+            .map(std::path::PathBuf::from)
+            .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("../../target"))
+            .join("plugin-tests");
+        let status = std::process::Command::new(env!("CARGO"))
+            .args(["build", "--quiet", "-p", "orchestration-plugin-example", "--target-dir"])
+            .arg(&target_dir)
+            .status()
+            .expect("cargo runs");
+        assert!(status.success(), "plugin-example builds");
+        target_dir.join("debug").join(format!(
+            "{}orchestration_plugin_example{}",
+            std::env::consts::DLL_PREFIX,
+            std::env::consts::DLL_SUFFIX
+        ))
+    }
+
+    #[tokio::test]
+    async fn plugin_libraries_register_their_steps() {
+        use orchestration_domain::{LanguageTag, Transcript, TranscriptSegment};
+
+        let library = build_example_plugin();
+        let dir = std::env::temp_dir().join(format!("vocal-plugin-load-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::copy(&library, dir.join(library.file_name().unwrap())).unwrap();
+
+        let steps = load_plugin_dir(&dir).expect("plugin loads");
+        assert_eq!(steps.keys().collect::<Vec<_>>(), vec!["shout"]);
+        let stage = &steps["shout"];
+        assert_eq!(stage.name(), "shout");
+
+        let mut context = PipelineContext::new("session", None);
+        context.transcript = Some(Transcript {
+            language: LanguageTag::en(),
+            segments: vec![TranscriptSegment {
+                text: "hello".to_string(),
+                start_ms: 0,
+                end_ms: 100,
+                tokens: Vec::new(),
+                speaker: None,
+                language: None,
+                no_speech_prob: None,
+                avg_logprob: None,
+            }],
+        });
+        stage.execute(&mut context).await.expect("plugin step runs");
+        assert_eq!(context.transcript.unwrap().segments[0].text, "HELLO");
+
+        drop(steps);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
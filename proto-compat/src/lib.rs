@@ -0,0 +1,377 @@
+//! Breaking-change detection for the service protos, run by the gRPC crates'
+//! build scripts in the spirit of `buf breaking`.
+//!
+//! Every package keeps a lock file next to its proto listing the messages,
+//! fields, enum values and RPCs it has published. The build fails when one of
+//! them is removed or changes name, number, type or cardinality; a field or
+//! enum value may only go once its number is `reserved`. Additions pass, with
+//! a warning until they are recorded by building with `PROTO_LOCK_UPDATE=1`.
+//! A missing lock fails the build too; only `PROTO_LOCK_UPDATE=1` creates it.
+//! Anything else belongs in a new package version, e.g. `asr.v2` served next
+//! to `asr.v1`.
+
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::path::Path;
+use std::{env, fs, io};
+
+use prost::Message;
+use prost_types::field_descriptor_proto::{Label, Type};
+use prost_types::{
+    DescriptorProto, EnumDescriptorProto, FieldDescriptorProto, FileDescriptorSet,
+    ServiceDescriptorProto,
+};
+
+/// Set to anything but `0` to rewrite the lock files from the protos, e.g.
+/// after adding fields, or to accept a break in a package that has not
+/// shipped yet.
+pub const UPDATE_ENV: &str = "PROTO_LOCK_UPDATE";
+
+const LOCK_HEADER: &str = "\
+# Published surface of the package, checked on every build by proto-compat.
+# Rebuild with PROTO_LOCK_UPDATE=1 to record additions.
+";
+
+/// Checks `package` in the descriptor set written at `descriptor_path`
+/// against the lock file at `lock_path`. The lock is only written, or
+/// created, when [`UPDATE_ENV`] is set.
+pub fn enforce(
+    descriptor_path: &Path,
+    package: &str,
+    lock_path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    println!("cargo:rerun-if-changed={}", lock_path.display());
+    println!("cargo:rerun-if-env-changed={UPDATE_ENV}");
+
+    let set = FileDescriptorSet::decode(fs::read(descriptor_path)?.as_slice())?;
+    let update = env::var(UPDATE_ENV).is_ok_and(|value| value != "0");
+    check_lock(&Surface::of(&set, package), package, lock_path, update)
+}
+
+fn check_lock(
+    current: &Surface,
+    package: &str,
+    lock_path: &Path,
+    update: bool,
+) -> Result<(), Box<dyn Error>> {
+    if update {
+        return write_lock(lock_path, current);
+    }
+    let locked = match fs::read_to_string(lock_path) {
+        Ok(text) => parse_lock(&text),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            return Err(format!(
+                "{} is missing; create it with {UPDATE_ENV}=1 and commit it",
+                lock_path.display()
+            )
+            .into())
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    let breaking = current.breaking_changes(&locked);
+    if !breaking.is_empty() {
+        return Err(format!(
+            "breaking changes to `{package}` against {}:\n  {}\nReserve the numbers of \
+             removed fields, or make the change in a new package version.",
+            lock_path.display(),
+            breaking.join("\n  ")
+        )
+        .into());
+    }
+    if current.entries != locked {
+        println!(
+            "cargo:warning=`{package}` has additions missing from {}; rebuild with \
+             {UPDATE_ENV}=1 to record them",
+            lock_path.display()
+        );
+    }
+    Ok(())
+}
+
+fn write_lock(lock_path: &Path, surface: &Surface) -> Result<(), Box<dyn Error>> {
+    let mut text = LOCK_HEADER.to_string();
+    for entry in &surface.entries {
+        text.push_str(entry);
+        text.push('\n');
+    }
+    fs::write(lock_path, text)?;
+    Ok(())
+}
+
+fn parse_lock(text: &str) -> BTreeSet<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// What a package exposes to its clients, one line per element.
+#[derive(Debug, Default)]
+pub struct Surface {
+    entries: BTreeSet<String>,
+    /// Numbers retired with `reserved`: owner, start and exclusive end.
+    reserved: Vec<(String, i64, i64)>,
+}
+
+impl Surface {
+    pub fn of(set: &FileDescriptorSet, package: &str) -> Self {
+        let mut surface = Self::default();
+        for file in set.file.iter().filter(|file| file.package() == package) {
+            for message in &file.message_type {
+                surface.add_message(package, message);
+            }
+            for enumeration in &file.enum_type {
+                surface.add_enum(package, enumeration);
+            }
+            for service in &file.service {
+                surface.add_service(package, service);
+            }
+        }
+        surface
+    }
+
+    pub fn entries(&self) -> &BTreeSet<String> {
+        &self.entries
+    }
+
+    /// Locked entries the current protos no longer honour, described.
+    pub fn breaking_changes(&self, locked: &BTreeSet<String>) -> Vec<String> {
+        locked
+            .difference(&self.entries)
+            .filter_map(|entry| {
+                let mut parts = entry.split(' ');
+                let kind = parts.next().unwrap_or_default();
+                if kind != "field" && kind != "value" {
+                    return Some(format!("`{entry}` was removed or changed"));
+                }
+                let owner = parts.next().unwrap_or_default();
+                let number = parts.next().unwrap_or_default();
+                if number
+                    .parse::<i64>()
+                    .is_ok_and(|number| self.is_reserved(owner, number))
+                {
+                    return None;
+                }
+                let prefix = format!("{kind} {owner} {number} ");
+                match self.entries.iter().find(|now| now.starts_with(&prefix)) {
+                    Some(now) => Some(format!("`{entry}` is now `{now}`")),
+                    None => Some(format!("`{entry}` was removed")),
+                }
+            })
+            .collect()
+    }
+
+    fn is_reserved(&self, owner: &str, number: i64) -> bool {
+        self.reserved
+            .iter()
+            .any(|(name, start, end)| name == owner && (*start..*end).contains(&number))
+    }
+
+    fn add_message(&mut self, scope: &str, message: &DescriptorProto) {
+        let name = format!("{scope}.{}", message.name());
+        self.entries.insert(format!("message {name}"));
+        for field in &message.field {
+            self.entries.insert(format!(
+                "field {name} {} {} {} {}",
+                field.number(),
+                field.name(),
+                cardinality(message, field),
+                field_type(field)
+            ));
+        }
+        for range in &message.reserved_range {
+            self.reserved
+                .push((name.clone(), i64::from(range.start()), i64::from(range.end())));
+        }
+        for nested in &message.nested_type {
+            self.add_message(&name, nested);
+        }
+        for enumeration in &message.enum_type {
+            self.add_enum(&name, enumeration);
+        }
+    }
+
+    fn add_enum(&mut self, scope: &str, enumeration: &EnumDescriptorProto) {
+        let name = format!("{scope}.{}", enumeration.name());
+        self.entries.insert(format!("enum {name}"));
+        for value in &enumeration.value {
+            self.entries
+                .insert(format!("value {name} {} {}", value.number(), value.name()));
+        }
+        // Enum reserved ranges include their end.
+        for range in &enumeration.reserved_range {
+            self.reserved.push((
+                name.clone(),
+                i64::from(range.start()),
+                i64::from(range.end()) + 1,
+            ));
+        }
+    }
+
+    fn add_service(&mut self, package: &str, service: &ServiceDescriptorProto) {
+        let name = format!("{package}.{}", service.name());
+        self.entries.insert(format!("service {name}"));
+        for method in &service.method {
+            self.entries.insert(format!(
+                "rpc {name}.{} {} {}",
+                method.name(),
+                streamed(method.client_streaming(), method.input_type()),
+                streamed(method.server_streaming(), method.output_type())
+            ));
+        }
+    }
+}
+
+fn cardinality(message: &DescriptorProto, field: &FieldDescriptorProto) -> String {
+    match field.label() {
+        Label::Repeated => return "repeated".to_string(),
+        Label::Required => return "required".to_string(),
+        Label::Optional => {}
+    }
+    // `optional` fields sit in a synthetic oneof of their own.
+    if field.proto3_optional() {
+        return "optional".to_string();
+    }
+    match field
+        .oneof_index
+        .and_then(|index| message.oneof_decl.get(index as usize))
+    {
+        Some(oneof) => format!("oneof:{}", oneof.name()),
+        None => "singular".to_string(),
+    }
+}
+
+fn field_type(field: &FieldDescriptorProto) -> String {
+    match field.r#type() {
+        Type::Message | Type::Enum | Type::Group => {
+            field.type_name().trim_start_matches('.').to_string()
+        }
+        scalar => scalar
+            .as_str_name()
+            .trim_start_matches("TYPE_")
+            .to_ascii_lowercase(),
+    }
+}
+
+fn streamed(streaming: bool, type_name: &str) -> String {
+    let type_name = type_name.trim_start_matches('.');
+    if streaming {
+        format!("stream {type_name}")
+    } else {
+        type_name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prost_types::descriptor_proto::ReservedRange;
+    use prost_types::FileDescriptorProto;
+
+    use super::*;
+
+    fn field(name: &str, number: i32, field_type: Type) -> FieldDescriptorProto {
+        FieldDescriptorProto {
+            name: Some(name.to_string()),
+            number: Some(number),
+            label: Some(Label::Optional as i32),
+            r#type: Some(field_type as i32),
+            ..Default::default()
+        }
+    }
+
+    fn package(fields: Vec<FieldDescriptorProto>, reserved: Vec<i32>) -> FileDescriptorSet {
+        FileDescriptorSet {
+            file: vec![FileDescriptorProto {
+                package: Some("demo.v1".to_string()),
+                message_type: vec![DescriptorProto {
+                    name: Some("Request".to_string()),
+                    field: fields,
+                    reserved_range: reserved
+                        .into_iter()
+                        .map(|number| ReservedRange {
+                            start: Some(number),
+                            end: Some(number + 1),
+                        })
+                        .collect(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        }
+    }
+
+    fn published() -> BTreeSet<String> {
+        let set = package(
+            vec![field("text", 1, Type::String), field("rate", 2, Type::Uint32)],
+            Vec::new(),
+        );
+        Surface::of(&set, "demo.v1").entries().clone()
+    }
+
+    #[test]
+    fn fields_are_listed_with_number_cardinality_and_type() {
+        assert!(published().contains("field demo.v1.Request 2 rate singular uint32"));
+    }
+
+    #[test]
+    fn additions_are_compatible() {
+        let set = package(
+            vec![
+                field("text", 1, Type::String),
+                field("rate", 2, Type::Uint32),
+                field("speaker", 3, Type::String),
+            ],
+            Vec::new(),
+        );
+        assert!(Surface::of(&set, "demo.v1").breaking_changes(&published()).is_empty());
+    }
+
+    #[test]
+    fn removed_and_retyped_fields_break() {
+        let removed = package(vec![field("text", 1, Type::String)], Vec::new());
+        let breaking = Surface::of(&removed, "demo.v1").breaking_changes(&published());
+        assert_eq!(breaking, vec!["`field demo.v1.Request 2 rate singular uint32` was removed"]);
+
+        let retyped = package(
+            vec![field("text", 1, Type::String), field("rate", 2, Type::Float)],
+            Vec::new(),
+        );
+        let breaking = Surface::of(&retyped, "demo.v1").breaking_changes(&published());
+        assert_eq!(breaking.len(), 1);
+        assert!(breaking[0].contains("is now `field demo.v1.Request 2 rate singular float`"));
+    }
+
+    #[test]
+    fn reserved_numbers_may_be_dropped() {
+        let set = package(vec![field("text", 1, Type::String)], vec![2]);
+        assert!(Surface::of(&set, "demo.v1").breaking_changes(&published()).is_empty());
+    }
+
+    #[test]
+    fn lock_comments_and_blank_lines_are_ignored() {
+        let mut text = LOCK_HEADER.to_string();
+        for entry in published() {
+            text.push_str(&format!("\n{entry}\n"));
+        }
+        assert_eq!(parse_lock(&text), published());
+    }
+
+    #[test]
+    fn missing_locks_fail_unless_updating() {
+        let dir = env::temp_dir().join(format!("proto-compat-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let lock_path = dir.join("demo.v1.lock");
+        let set = package(vec![field("text", 1, Type::String)], Vec::new());
+        let surface = Surface::of(&set, "demo.v1");
+
+        let error = check_lock(&surface, "demo.v1", &lock_path, false).expect_err("no lock");
+        assert!(error.to_string().contains(UPDATE_ENV), "{error}");
+        assert!(!lock_path.exists(), "a failing check writes nothing");
+
+        check_lock(&surface, "demo.v1", &lock_path, true).expect("lock written");
+        check_lock(&surface, "demo.v1", &lock_path, false).expect("lock matches");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,68 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use service_health::{DependencyStatus, ReadinessCheck};
+
+/// Whether the service was asked to stop taking new work. Draining only
+/// fails readiness, so load balancers route elsewhere while requests in
+/// flight finish; nothing is rejected.
+#[derive(Debug, Clone, Default)]
+pub struct Drain {
+    draining: Arc<AtomicBool>,
+}
+
+impl Drain {
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::Relaxed)
+    }
+
+    pub fn set_draining(&self, draining: bool) {
+        self.draining.store(draining, Ordering::Relaxed);
+    }
+
+    /// `inner` plus a `drain` dependency that is not ready while draining.
+    pub fn readiness(&self, inner: Arc<dyn ReadinessCheck>) -> Arc<dyn ReadinessCheck> {
+        Arc::new(DrainAwareReadiness {
+            inner,
+            drain: self.clone(),
+        })
+    }
+}
+
+struct DrainAwareReadiness {
+    inner: Arc<dyn ReadinessCheck>,
+    drain: Drain,
+}
+
+#[async_trait]
+impl ReadinessCheck for DrainAwareReadiness {
+    async fn check(&self) -> Vec<DependencyStatus> {
+        let mut dependencies = self.inner.check().await;
+        if self.drain.is_draining() {
+            dependencies.push(DependencyStatus::not_ready("drain", "draining on admin request"));
+        }
+        dependencies
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use service_health::{readiness_report, AlwaysReady};
+
+    #[tokio::test]
+    async fn draining_fails_readiness_until_cleared() {
+        let drain = Drain::default();
+        let readiness = drain.readiness(Arc::new(AlwaysReady));
+        assert!(readiness_report(readiness.as_ref()).await.ready);
+
+        drain.set_draining(true);
+        let report = readiness_report(readiness.as_ref()).await;
+        assert!(!report.ready);
+        assert_eq!(report.dependencies[0].name, "drain");
+
+        drain.set_draining(false);
+        assert!(readiness_report(readiness.as_ref()).await.ready);
+    }
+}
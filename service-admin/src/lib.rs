@@ -0,0 +1,253 @@
+//! Admin API shared by every service: drain, config reload, a metrics
+//! snapshot and the log level at runtime.
+//!
+//! It is served on its own address (the `admin` config block, off when
+//! unset) so it can be kept off the network the public API is exposed on;
+//! the routes themselves do not authenticate.
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+    routing::{get, post},
+    Router,
+};
+use rustycog_config::ServerConfig;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use service_health::{readiness_report, ReadinessCheck, ReadinessReport};
+
+mod drain;
+mod logging;
+mod reload;
+
+pub use drain::Drain;
+pub use logging::{install_logging, log_filter, set_log_filter};
+pub use reload::{ConfigReloader, ReloadReport};
+
+pub const ADMIN_DRAIN_PATH: &str = "/admin/drain";
+pub const ADMIN_RELOAD_PATH: &str = "/admin/reload";
+pub const ADMIN_METRICS_PATH: &str = "/admin/metrics";
+pub const ADMIN_LOG_LEVEL_PATH: &str = "/admin/log-level";
+
+#[derive(Clone)]
+pub struct AdminState {
+    service: &'static str,
+    started: Instant,
+    drain: Drain,
+    readiness: Arc<dyn ReadinessCheck>,
+    reloader: Arc<ConfigReloader>,
+}
+
+impl AdminState {
+    /// `readiness` is reported in the metrics snapshot as is; pass the
+    /// drain-aware check from [`Drain::readiness`].
+    pub fn new(
+        service: &'static str,
+        drain: Drain,
+        readiness: Arc<dyn ReadinessCheck>,
+        reloader: ConfigReloader,
+    ) -> Self {
+        Self {
+            service,
+            started: Instant::now(),
+            drain,
+            readiness,
+            reloader: Arc::new(reloader),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricsSnapshot {
+    pub service: String,
+    pub uptime_s: u64,
+    pub draining: bool,
+    /// `None` when the service logs through `rustycog_logger`.
+    pub log_filter: Option<String>,
+    /// Resident set size; `None` where `/proc` is unavailable.
+    pub resident_memory_bytes: Option<u64>,
+    pub readiness: ReadinessReport,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogLevelBody {
+    /// `EnvFilter` directives, e.g. `debug` or `info,audio_infra=trace`.
+    pub filter: String,
+}
+
+pub fn admin_router(state: AdminState) -> Router {
+    Router::new()
+        .route(ADMIN_DRAIN_PATH, post(start_drain).delete(stop_drain))
+        .route(ADMIN_RELOAD_PATH, post(reload_config))
+        .route(ADMIN_METRICS_PATH, get(metrics))
+        .route(ADMIN_LOG_LEVEL_PATH, get(log_level).put(set_log_level))
+        .with_state(state)
+}
+
+/// Serves [`admin_router`] on `config.host:config.port` until the process
+/// exits.
+pub async fn serve_admin(config: ServerConfig, state: AdminState) -> std::io::Result<()> {
+    serve_admin_with_routes(config, state, Router::new()).await
+}
+
+/// [`serve_admin`] with service-specific admin `routes` next to the shared
+/// ones.
+pub async fn serve_admin_with_routes(
+    config: ServerConfig,
+    state: AdminState,
+    routes: Router,
+) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind((config.host.as_str(), config.port)).await?;
+    tracing::info!(
+        service = state.service,
+        host = %config.host,
+        port = config.port,
+        "starting admin server"
+    );
+    axum::serve(listener, admin_router(state).merge(routes)).await
+}
+
+/// Starts the admin server in the background when `config` is set, and
+/// returns the readiness the public listeners should serve: `readiness`,
+/// failing while drained.
+pub fn spawn_admin(
+    service: &'static str,
+    config: Option<ServerConfig>,
+    readiness: Arc<dyn ReadinessCheck>,
+    reloader: ConfigReloader,
+) -> Arc<dyn ReadinessCheck> {
+    spawn_admin_with_routes(service, config, readiness, reloader, Router::new())
+}
+
+/// [`spawn_admin`] with service-specific admin `routes`, e.g. usage reports.
+pub fn spawn_admin_with_routes(
+    service: &'static str,
+    config: Option<ServerConfig>,
+    readiness: Arc<dyn ReadinessCheck>,
+    reloader: ConfigReloader,
+    routes: Router,
+) -> Arc<dyn ReadinessCheck> {
+    let Some(config) = config else {
+        return readiness;
+    };
+    let drain = Drain::default();
+    let readiness = drain.readiness(readiness);
+    let state = AdminState::new(service, drain, readiness.clone(), reloader);
+    tokio::spawn(async move {
+        if let Err(err) = serve_admin_with_routes(config, state, routes).await {
+            tracing::error!(service, error = %err, "admin server failed");
+        }
+    });
+    readiness
+}
+
+async fn start_drain(State(state): State<AdminState>) -> Json<serde_json::Value> {
+    state.drain.set_draining(true);
+    tracing::warn!(service = state.service, "draining: readiness now fails");
+    Json(json!({ "draining": true }))
+}
+
+async fn stop_drain(State(state): State<AdminState>) -> Json<serde_json::Value> {
+    state.drain.set_draining(false);
+    tracing::info!(service = state.service, "drain cleared");
+    Json(json!({ "draining": false }))
+}
+
+async fn reload_config(State(state): State<AdminState>) -> Response {
+    match state.reloader.reload() {
+        Ok(report) => Json(report).into_response(),
+        Err(message) => {
+            tracing::error!(error = %message, "configuration reload failed");
+            admin_error(StatusCode::UNPROCESSABLE_ENTITY, message)
+        }
+    }
+}
+
+async fn metrics(State(state): State<AdminState>) -> Json<MetricsSnapshot> {
+    Json(MetricsSnapshot {
+        service: state.service.to_string(),
+        uptime_s: state.started.elapsed().as_secs(),
+        draining: state.drain.is_draining(),
+        log_filter: log_filter(),
+        resident_memory_bytes: resident_memory_bytes(),
+        readiness: readiness_report(state.readiness.as_ref()).await,
+    })
+}
+
+async fn log_level() -> Response {
+    match log_filter() {
+        Some(filter) => Json(LogLevelBody { filter }).into_response(),
+        None => admin_error(StatusCode::CONFLICT, "log level is not adjustable at runtime"),
+    }
+}
+
+async fn set_log_level(Json(body): Json<LogLevelBody>) -> Response {
+    if log_filter().is_none() {
+        return admin_error(StatusCode::CONFLICT, "log level is not adjustable at runtime");
+    }
+    match set_log_filter(&body.filter) {
+        Ok(()) => {
+            tracing::info!(filter = %body.filter, "log filter changed");
+            Json(body).into_response()
+        }
+        Err(message) => admin_error(StatusCode::UNPROCESSABLE_ENTITY, message),
+    }
+}
+
+fn admin_error(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, Json(json!({ "error": message.into() }))).into_response()
+}
+
+fn resident_memory_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    parse_vm_rss(&status)
+}
+
+/// `VmRSS:    12345 kB` of `/proc/self/status`, in bytes.
+fn parse_vm_rss(status: &str) -> Option<u64> {
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use service_health::AlwaysReady;
+
+    fn state() -> AdminState {
+        let drain = Drain::default();
+        AdminState::new(
+            "test",
+            drain.clone(),
+            drain.readiness(Arc::new(AlwaysReady)),
+            ConfigReloader::new(&json!({}), || Ok::<_, String>(json!({}))),
+        )
+    }
+
+    #[tokio::test]
+    async fn metrics_reflect_the_drain() {
+        let state = state();
+        start_drain(State(state.clone())).await;
+
+        let Json(snapshot) = metrics(State(state.clone())).await;
+        assert_eq!(snapshot.service, "test");
+        assert!(snapshot.draining);
+        assert!(!snapshot.readiness.ready);
+
+        stop_drain(State(state.clone())).await;
+        let Json(snapshot) = metrics(State(state)).await;
+        assert!(snapshot.readiness.ready);
+    }
+
+    #[test]
+    fn resident_memory_is_read_from_proc_status() {
+        let status = "Name:\tservice\nVmPeak:\t  20000 kB\nVmRSS:\t   1200 kB\n";
+        assert_eq!(parse_vm_rss(status), Some(1200 * 1024));
+        assert_eq!(parse_vm_rss("Name:\tservice\n"), None);
+    }
+}
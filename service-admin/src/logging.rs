@@ -0,0 +1,65 @@
+use std::sync::OnceLock;
+
+use serde::Serialize;
+use serde_json::Value;
+use tracing_subscriber::{fmt, layer::SubscriberExt, reload, util::SubscriberInitExt};
+use tracing_subscriber::{EnvFilter, Registry};
+
+static LOG_FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Installs the global subscriber with a filter the admin API can replace;
+/// services without an admin API keep `rustycog_logger::setup_logging`.
+/// `RUST_LOG`, when set, takes precedence over `logging.level` of `config`.
+pub fn install_logging<C: Serialize>(config: &C) {
+    let config = serde_json::to_value(config).unwrap_or_default();
+    let level = configured_level(&config).unwrap_or("info");
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(level));
+    let (filter, handle) = reload::Layer::new(filter);
+    if tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer())
+        .try_init()
+        .is_ok()
+    {
+        let _ = LOG_FILTER.set(handle);
+    }
+}
+
+/// Directives of the current filter, e.g. `info,hyper=warn`; `None` when
+/// [`install_logging`] did not install the subscriber.
+pub fn log_filter() -> Option<String> {
+    LOG_FILTER
+        .get()
+        .and_then(|handle| handle.with_current(ToString::to_string).ok())
+}
+
+/// Replaces the filter with `directives` (`EnvFilter` syntax).
+pub fn set_log_filter(directives: &str) -> Result<(), String> {
+    let handle = LOG_FILTER
+        .get()
+        .ok_or("logging was not installed with a reloadable filter")?;
+    let filter = EnvFilter::try_new(directives)
+        .map_err(|err| format!("invalid log filter `{directives}`: {err}"))?;
+    handle.reload(filter).map_err(|err| err.to_string())
+}
+
+pub(crate) fn configured_level(config: &Value) -> Option<&str> {
+    config.get("logging")?.get("level")?.as_str()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn level_is_read_from_the_logging_section() {
+        assert_eq!(configured_level(&json!({"logging": {"level": "debug"}})), Some("debug"));
+        assert_eq!(configured_level(&json!({"server": {}})), None);
+    }
+
+    #[test]
+    fn invalid_filters_are_refused() {
+        assert!(set_log_filter("info,=[").is_err());
+    }
+}
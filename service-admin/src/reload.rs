@@ -0,0 +1,116 @@
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::logging::{configured_level, set_log_filter};
+
+type LoadConfig = Box<dyn Fn() -> Result<Value, String> + Send + Sync>;
+
+/// Re-reads the service configuration on request. Only `logging` applies
+/// without a restart; other changed sections are reported so operators know
+/// a restart is due.
+pub struct ConfigReloader {
+    /// Config as running: the startup one with the sections applied since.
+    current: Mutex<Value>,
+    load: LoadConfig,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct ReloadReport {
+    /// Sections now running with the new values.
+    pub applied: Vec<String>,
+    /// Changed sections that take effect on the next start.
+    pub restart_required: Vec<String>,
+}
+
+impl ConfigReloader {
+    /// `current` is the config the service started with; `load` reads it
+    /// again, usually the service's `load_config`.
+    pub fn new<C, E, F>(current: &C, load: F) -> Self
+    where
+        C: Serialize,
+        E: Display,
+        F: Fn() -> Result<C, E> + Send + Sync + 'static,
+    {
+        Self {
+            current: Mutex::new(serde_json::to_value(current).unwrap_or_default()),
+            load: Box::new(move || {
+                let config = load().map_err(|err| err.to_string())?;
+                serde_json::to_value(config).map_err(|err| err.to_string())
+            }),
+        }
+    }
+
+    pub fn reload(&self) -> Result<ReloadReport, String> {
+        let fresh = (self.load)()?;
+        let mut current = self.current.lock().unwrap_or_else(|err| err.into_inner());
+        let mut report = ReloadReport::default();
+        for section in changed_sections(&current, &fresh) {
+            let applied = section == "logging"
+                && configured_level(&fresh).is_some_and(|level| set_log_filter(level).is_ok());
+            if applied {
+                current[&section] = fresh[&section].clone();
+                report.applied.push(section);
+            } else {
+                report.restart_required.push(section);
+            }
+        }
+        tracing::info!(
+            applied = ?report.applied,
+            restart_required = ?report.restart_required,
+            "configuration reloaded"
+        );
+        Ok(report)
+    }
+}
+
+/// Top-level keys whose value differs, sorted.
+fn changed_sections(current: &Value, fresh: &Value) -> Vec<String> {
+    let keys = |value: &Value| {
+        value
+            .as_object()
+            .map(|object| object.keys().cloned().collect::<BTreeSet<_>>())
+            .unwrap_or_default()
+    };
+    keys(current)
+        .union(&keys(fresh))
+        .filter(|key| current.get(key.as_str()) != fresh.get(key.as_str()))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn unchanged_config_reports_nothing() {
+        let config = json!({"server": {"port": 8080}});
+        let reloader = ConfigReloader::new(&config, {
+            let config = config.clone();
+            move || Ok::<_, String>(config.clone())
+        });
+        assert_eq!(reloader.reload().unwrap(), ReloadReport::default());
+    }
+
+    #[test]
+    fn changed_sections_need_a_restart() {
+        let reloader = ConfigReloader::new(
+            &json!({"server": {"port": 8080}, "service": {"a": 1}}),
+            || Ok::<_, String>(json!({"server": {"port": 9090}, "service": {"a": 1}, "new": {}})),
+        );
+        let report = reloader.reload().unwrap();
+        assert!(report.applied.is_empty());
+        assert_eq!(report.restart_required, vec!["new", "server"]);
+    }
+
+    #[test]
+    fn load_failures_are_reported() {
+        let reloader = ConfigReloader::new(&json!({}), || Err::<Value, _>("missing file"));
+        assert_eq!(reloader.reload().unwrap_err(), "missing file");
+    }
+}
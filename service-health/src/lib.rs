@@ -0,0 +1,132 @@
+//! `/healthz` (liveness) and `/readyz` (readiness) endpoints shared by every
+//! service.
+//!
+//! Liveness only says the process answers HTTP. Readiness runs the service's
+//! [`ReadinessCheck`] and answers `503` while any dependency is not ready.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::{http::StatusCode, response::Json, routing::get, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+pub const LIVENESS_PATH: &str = "/healthz";
+pub const READINESS_PATH: &str = "/readyz";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DependencyStatus {
+    pub name: String,
+    pub ready: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl DependencyStatus {
+    pub fn ready(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ready: true,
+            detail: None,
+        }
+    }
+
+    pub fn not_ready(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ready: false,
+            detail: Some(detail.into()),
+        }
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReadinessReport {
+    pub ready: bool,
+    pub dependencies: Vec<DependencyStatus>,
+}
+
+#[async_trait]
+pub trait ReadinessCheck: Send + Sync {
+    async fn check(&self) -> Vec<DependencyStatus>;
+}
+
+/// Readiness for services with no dependency beyond their own process.
+pub struct AlwaysReady;
+
+#[async_trait]
+impl ReadinessCheck for AlwaysReady {
+    async fn check(&self) -> Vec<DependencyStatus> {
+        Vec::new()
+    }
+}
+
+pub async fn readiness_report(check: &dyn ReadinessCheck) -> ReadinessReport {
+    let dependencies = check.check().await;
+    ReadinessReport {
+        ready: dependencies.iter().all(|dependency| dependency.ready),
+        dependencies,
+    }
+}
+
+pub async fn liveness() -> Json<Value> {
+    Json(json!({ "status": "ok" }))
+}
+
+pub async fn readiness(check: Arc<dyn ReadinessCheck>) -> (StatusCode, Json<ReadinessReport>) {
+    let report = readiness_report(check.as_ref()).await;
+    let status = if report.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report))
+}
+
+pub fn health_router<S>(check: Arc<dyn ReadinessCheck>) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new()
+        .route(LIVENESS_PATH, get(liveness))
+        .route(READINESS_PATH, get(move || readiness(check.clone())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedCheck(Vec<DependencyStatus>);
+
+    #[async_trait]
+    impl ReadinessCheck for FixedCheck {
+        async fn check(&self) -> Vec<DependencyStatus> {
+            self.0.clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn no_dependencies_is_ready() {
+        let report = readiness_report(&AlwaysReady).await;
+        assert!(report.ready);
+        assert!(report.dependencies.is_empty());
+    }
+
+    #[tokio::test]
+    async fn any_unready_dependency_fails_readiness() {
+        let check: Arc<dyn ReadinessCheck> = Arc::new(FixedCheck(vec![
+            DependencyStatus::ready("model"),
+            DependencyStatus::not_ready("asr", "connection refused"),
+        ]));
+
+        let (status, Json(report)) = readiness(check).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(!report.ready);
+        assert_eq!(report.dependencies.len(), 2);
+    }
+}
@@ -1,3 +1,4 @@
+use common_domain::ErrorCode;
 use rustycog_command::CommandError;
 use tempo_domain::DomainError;
 use thiserror::Error;
@@ -18,7 +19,7 @@ impl From<ApplicationError> for CommandError {
     fn from(error: ApplicationError) -> Self {
         match error {
             ApplicationError::Domain(err) => {
-                CommandError::business("domain_error", err.to_string())
+                CommandError::business(ErrorCode::from(&err).as_str(), err.to_string())
             }
             ApplicationError::Validation(message) => {
                 CommandError::validation("validation_error", message)
@@ -1,13 +1,6 @@
+pub use common_domain::WordTiming;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct WordTiming {
-    pub word: String,
-    pub start_ms: u64,
-    pub end_ms: u64,
-    pub confidence: f32,
-}
-
 #[derive(Debug, Clone)]
 pub struct TempoMatchRequest {
     pub tts_samples: Vec<f32>,
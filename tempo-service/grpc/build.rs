@@ -1,12 +1,17 @@
+use std::path::{Path, PathBuf};
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let protoc = protoc_bin_vendored::protoc_bin_path()?;
     std::env::set_var("PROTOC", protoc);
+    let descriptor = PathBuf::from(std::env::var("OUT_DIR")?).join("tempo_descriptor.bin");
 
     tonic_prost_build::configure()
         .build_client(true)
         .build_server(true)
+        .file_descriptor_set_path(&descriptor)
         .compile_protos(&["../proto/tempo.proto"], &["../proto"])?;
 
     println!("cargo:rerun-if-changed=../proto/tempo.proto");
+    proto_compat::enforce(&descriptor, "tempo.v1", Path::new("../proto/tempo.v1.lock"))?;
     Ok(())
 }
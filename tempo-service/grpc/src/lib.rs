@@ -6,9 +6,13 @@ use std::{
 use anyhow::Context;
 use tempo_application::{MatchTempoCommand, MatchTempoRequest, MatchTempoResponse};
 use tempo_domain::WordTiming;
+use common_domain::ErrorCode;
+use log_context::grpc_request_span;
 use rustycog_command::{CommandContext, CommandError, GenericCommandService};
 use rustycog_config::ServerConfig;
-use tonic::{transport::Server, Request, Response, Status};
+use service_health::{health_router, ReadinessCheck};
+use tonic::{service::Routes, transport::Server, Request, Response, Status};
+use tracing::Instrument;
 
 const MAX_MESSAGE_BYTES: usize = 64 * 1024 * 1024;
 
@@ -22,6 +26,7 @@ pub use pb::tempo_service_server::TempoServiceServer;
 pub async fn serve_grpc(
     command_service: Arc<GenericCommandService>,
     server_config: ServerConfig,
+    readiness: Arc<dyn ReadinessCheck>,
 ) -> anyhow::Result<()> {
     let address = resolve_bind_addr(&server_config)?;
     let service = TempoGrpcService { command_service };
@@ -32,12 +37,18 @@ pub async fn serve_grpc(
         "starting tempo gRPC server"
     );
 
+    // Health probes are plain HTTP/1.1 GETs served on the gRPC port.
+    let routes = Routes::new(
+        TempoServiceServer::new(service)
+            .max_decoding_message_size(MAX_MESSAGE_BYTES)
+            .max_encoding_message_size(MAX_MESSAGE_BYTES),
+    )
+    .into_axum_router()
+    .merge(health_router(readiness));
+
     Server::builder()
-        .add_service(
-            TempoServiceServer::new(service)
-                .max_decoding_message_size(MAX_MESSAGE_BYTES)
-                .max_encoding_message_size(MAX_MESSAGE_BYTES),
-        )
+        .accept_http1(true)
+        .add_routes(Routes::from(routes))
         .serve(address)
         .await
         .context("tempo gRPC server failed")
@@ -54,16 +65,25 @@ impl pb::tempo_service_server::TempoService for TempoGrpcService {
         &self,
         request: Request<pb::MatchTempoRequest>,
     ) -> Result<Response<pb::MatchTempoResponse>, Status> {
-        let request = map_match_request(request.into_inner())?;
-        let command = MatchTempoCommand::new(request);
-        let context = CommandContext::new();
-        let result = self
-            .command_service
-            .execute(command, context)
-            .await
-            .map_err(map_command_error)?;
-
-        Ok(Response::new(map_match_response(result)))
+        let span = grpc_request_span(
+            "tempo.v1.TempoService/MatchTempo",
+            request.metadata(),
+            request.get_ref().session_id.as_deref(),
+        );
+        async move {
+            let request = map_match_request(request.into_inner())?;
+            let command = MatchTempoCommand::new(request);
+            let context = CommandContext::new();
+            let result = self
+                .command_service
+                .execute(command, context)
+                .await
+                .map_err(map_command_error)?;
+
+            Ok(Response::new(map_match_response(result)))
+        }
+        .instrument(span)
+        .await
     }
 }
 
@@ -130,6 +150,12 @@ fn map_word_timing_from_proto(word: pb::WordTiming) -> WordTiming {
         start_ms: word.start_ms,
         end_ms: word.end_ms,
         confidence: word.confidence,
+        speaker: word.speaker,
+        start_sample: word.start_sample,
+        end_sample: word.end_sample,
+        energy_rms: word.energy_rms,
+        pitch_hz: word.pitch_hz,
+        out_of_band: word.out_of_band,
     }
 }
 
@@ -138,13 +164,18 @@ fn map_command_error(error: CommandError) -> Status {
         CommandError::Validation { .. } => Status::invalid_argument(error.to_string()),
         CommandError::Authentication { .. } => Status::unauthenticated(error.to_string()),
         CommandError::Business { .. } => {
-            let message = error.message().to_ascii_lowercase();
-            if message.contains("not found") {
-                Status::not_found(error.to_string())
-            } else if message.contains("permission") || message.contains("forbidden") {
-                Status::permission_denied(error.to_string())
-            } else {
-                Status::failed_precondition(error.to_string())
+            let message = error.to_string();
+            match ErrorCode::from_code(error.error_code()) {
+                ErrorCode::InvalidInput | ErrorCode::AudioQuality => {
+                    Status::invalid_argument(message)
+                }
+                ErrorCode::NotFound => Status::not_found(message),
+                ErrorCode::AlreadyExists => Status::already_exists(message),
+                ErrorCode::PermissionDenied => Status::permission_denied(message),
+                ErrorCode::FailedPrecondition => Status::failed_precondition(message),
+                ErrorCode::ResourceExhausted => Status::resource_exhausted(message),
+                ErrorCode::Unavailable => Status::unavailable(message),
+                ErrorCode::Internal => Status::internal(message),
             }
         }
         CommandError::Infrastructure { .. }
@@ -15,6 +15,7 @@ impl TempoPipelineEngine {
 
         for (index, stage) in self.stages.iter().enumerate() {
             let name = stage.name();
+            let _stage_span = tracing::info_span!("stage", stage = name).entered();
             tracing::debug!(stage = name, index, "stage_start");
             let stage_start = Instant::now();
             match stage.execute(context) {
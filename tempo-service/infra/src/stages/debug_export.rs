@@ -529,8 +529,8 @@ mod tests {
         let mut ctx = TempoPipelineContext::new(
             vec![0.5; 1600],
             16_000,
-            vec![WordTiming { word: "hello".into(), start_ms: 0, end_ms: 600, confidence: 0.95 }],
-            vec![WordTiming { word: "hello".into(), start_ms: 0, end_ms: 500, confidence: 0.90 }],
+            vec![WordTiming { word: "hello".into(), start_ms: 0, end_ms: 600, confidence: 0.95, speaker: None, start_sample: None, end_sample: None, energy_rms: None, pitch_hz: None, out_of_band: false }],
+            vec![WordTiming { word: "hello".into(), start_ms: 0, end_ms: 500, confidence: 0.90, speaker: None, start_sample: None, end_sample: None, energy_rms: None, pitch_hz: None, out_of_band: false }],
         );
         ctx.segment_plans = vec![SegmentPlan {
             kind: SegmentKind::Word,
@@ -1,6 +1,7 @@
 use tempo_domain::{
     DomainError, SegmentAudio, SegmentKind, TempoPipelineContext, TempoPipelineStage,
 };
+use vocal_dsp::stretch_linear;
 
 const DEFAULT_MARGIN_MS: u64 = 10;
 const GAP_SILENCE_RMS_THRESHOLD: f32 = 1e-4;
@@ -55,8 +56,8 @@ impl TempoPipelineStage for SegmentExtractionStage {
                 if rms < GAP_SILENCE_RMS_THRESHOLD {
                     vec![0.0f32; plan.target_duration_samples]
                 } else {
-                    // Simple linear resample for non-silent gaps
-                    resample_linear(useful_slice, plan.target_duration_samples)
+                    // Stretch non-silent gaps to the planned length
+                    stretch_linear(useful_slice, plan.target_duration_samples)
                 }
             } else {
                 Vec::new()
@@ -88,28 +89,6 @@ impl TempoPipelineStage for SegmentExtractionStage {
     }
 }
 
-/// Naive linear interpolation resample from `src` to a buffer of `target_len`.
-fn resample_linear(src: &[f32], target_len: usize) -> Vec<f32> {
-    if target_len == 0 || src.is_empty() {
-        return vec![0.0; target_len];
-    }
-    if src.len() == 1 {
-        return vec![src[0]; target_len];
-    }
-
-    let ratio = (src.len() - 1) as f64 / (target_len - 1).max(1) as f64;
-    (0..target_len)
-        .map(|i| {
-            let pos = i as f64 * ratio;
-            let idx = pos.floor() as usize;
-            let frac = pos - idx as f64;
-            let a = src[idx.min(src.len() - 1)];
-            let b = src[(idx + 1).min(src.len() - 1)];
-            a + (b - a) * frac as f32
-        })
-        .collect()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -229,23 +208,4 @@ mod tests {
         let stage = SegmentExtractionStage;
         assert!(stage.execute(&mut ctx).is_err());
     }
-
-    #[test]
-    fn resample_linear_identity() {
-        let src = vec![1.0, 2.0, 3.0, 4.0];
-        let out = resample_linear(&src, 4);
-        for (a, b) in src.iter().zip(out.iter()) {
-            assert!((a - b).abs() < 1e-6);
-        }
-    }
-
-    #[test]
-    fn resample_linear_stretch() {
-        let src = vec![0.0, 1.0];
-        let out = resample_linear(&src, 5);
-        assert_eq!(out.len(), 5);
-        assert!((out[0] - 0.0).abs() < 1e-6);
-        assert!((out[4] - 1.0).abs() < 1e-6);
-        assert!((out[2] - 0.5).abs() < 1e-6);
-    }
 }
@@ -228,6 +228,12 @@ mod tests {
             start_ms,
             end_ms,
             confidence: 1.0,
+            speaker: None,
+            start_sample: None,
+            end_sample: None,
+            energy_rms: None,
+            pitch_hz: None,
+            out_of_band: false,
         }
     }
 
@@ -8,6 +8,7 @@ use tempo_grpc_server::serve_grpc;
 use tempo_infra::TempoMatchAdapter;
 use rustycog_command::GenericCommandService;
 use rustycog_config::ServerConfig;
+use service_health::AlwaysReady;
 
 pub async fn build_and_run(config: AppConfig, server_config: ServerConfig) -> Result<(), Error> {
     let app = Application::new(config).await?;
@@ -49,7 +50,7 @@ impl Application {
             "starting tempo gRPC server"
         );
 
-        serve_grpc(self.command_service, server_config)
+        serve_grpc(self.command_service, server_config, Arc::new(AlwaysReady))
             .await
             .map_err(|err| anyhow::anyhow!("server startup failed: {err}"))
     }
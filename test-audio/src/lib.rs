@@ -0,0 +1,296 @@
+//! Deterministic PCM for tests.
+//!
+//! ```
+//! use test_audio::Signal;
+//!
+//! let samples = Signal::new(16_000)
+//!     .silence(200)
+//!     .speech_like(1_000)
+//!     .silence(300)
+//!     .tone(440.0, 250)
+//!     .build();
+//! assert_eq!(samples.len(), 16 * (200 + 1_000 + 300 + 250));
+//! ```
+//!
+//! The same builder calls always produce the same samples, so tests can use
+//! real-looking audio instead of hand-rolled `vec![0.1, 0.2, 0.3]` and still
+//! be reproducible.
+
+use std::f32::consts::PI;
+
+pub const DEFAULT_SAMPLE_RATE_HZ: u32 = 16_000;
+const DEFAULT_AMPLITUDE: f32 = 0.5;
+const DEFAULT_SEED: u64 = 0x5EED;
+
+/// Syllables per second in [`Signal::speech_like`].
+const SYLLABLE_RATE_HZ: f32 = 4.0;
+
+/// Builds mono f32 PCM in `[-1, 1]` segment by segment.
+#[derive(Debug, Clone)]
+pub struct Signal {
+    sample_rate_hz: u32,
+    amplitude: f32,
+    rng: SplitMix64,
+    samples: Vec<f32>,
+}
+
+impl Signal {
+    pub fn new(sample_rate_hz: u32) -> Self {
+        assert!(sample_rate_hz > 0, "sample rate must be non-zero");
+        Self {
+            sample_rate_hz,
+            amplitude: DEFAULT_AMPLITUDE,
+            rng: SplitMix64(DEFAULT_SEED),
+            samples: Vec::new(),
+        }
+    }
+
+    /// Peak amplitude of the segments added afterwards (default 0.5).
+    pub fn amplitude(mut self, amplitude: f32) -> Self {
+        self.amplitude = amplitude;
+        self
+    }
+
+    /// Reseeds the generator behind [`Self::noise`] and [`Self::speech_like`].
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = SplitMix64(seed);
+        self
+    }
+
+    pub fn silence(mut self, duration_ms: u32) -> Self {
+        let count = self.sample_count(duration_ms);
+        self.samples.resize(self.samples.len() + count, 0.0);
+        self
+    }
+
+    pub fn tone(mut self, frequency_hz: f32, duration_ms: u32) -> Self {
+        let rate = self.sample_rate_hz as f32;
+        let amplitude = self.amplitude;
+        let count = self.sample_count(duration_ms);
+        self.samples.extend(
+            (0..count).map(|i| amplitude * (2.0 * PI * frequency_hz * i as f32 / rate).sin()),
+        );
+        self
+    }
+
+    /// Uniform white noise.
+    pub fn noise(mut self, duration_ms: u32) -> Self {
+        let amplitude = self.amplitude;
+        for _ in 0..self.sample_count(duration_ms) {
+            let value = amplitude * self.rng.next_signed();
+            self.samples.push(value);
+        }
+        self
+    }
+
+    /// Voiced, syllable-paced audio: a harmonic source around 120 Hz shaped
+    /// by two formants that move every syllable, under a ~4 Hz envelope with
+    /// short gaps, plus a little breath noise. It is not intelligible, but it
+    /// has the energy, pitch and pause structure VAD, pitch tracking and
+    /// chunking code expect from speech.
+    pub fn speech_like(mut self, duration_ms: u32) -> Self {
+        let rate = self.sample_rate_hz as f32;
+        let count = self.sample_count(duration_ms);
+        let syllable_len = ((rate / SYLLABLE_RATE_HZ) as usize).max(1);
+        let nyquist = rate / 2.0;
+
+        let mut phase = 0.0f32;
+        let mut syllable = Syllable::random(&mut self.rng);
+        for i in 0..count {
+            let position = i % syllable_len;
+            if position == 0 && i > 0 {
+                syllable = Syllable::random(&mut self.rng);
+            }
+            let progress = position as f32 / syllable_len as f32;
+            // Raised-cosine envelope over the first 80%, then a gap.
+            let envelope = if progress < 0.8 {
+                0.5 - 0.5 * (2.0 * PI * progress / 0.8).cos()
+            } else {
+                0.0
+            };
+
+            // Slight pitch glide inside each syllable.
+            let f0 = syllable.f0_hz * (1.0 + 0.05 * (1.0 - 2.0 * progress));
+            phase = (phase + 2.0 * PI * f0 / rate) % (2.0 * PI);
+
+            let mut voiced = 0.0;
+            let mut harmonic = 1;
+            while f0 * harmonic as f32 <= nyquist.min(4_000.0) {
+                let frequency = f0 * harmonic as f32;
+                let weight = formant_gain(frequency, syllable.f1_hz, 90.0)
+                    + 0.6 * formant_gain(frequency, syllable.f2_hz, 120.0)
+                    + 0.02;
+                voiced += weight * (harmonic as f32 * phase).sin();
+                harmonic += 1;
+            }
+            let breath = 0.02 * self.rng.next_signed();
+            let value = self.amplitude * envelope * (0.3 * voiced + breath);
+            self.samples.push(value.clamp(-1.0, 1.0));
+        }
+        self
+    }
+
+    /// Appends already generated samples, e.g. a recorded fixture.
+    pub fn samples(mut self, samples: &[f32]) -> Self {
+        self.samples.extend_from_slice(samples);
+        self
+    }
+
+    pub fn sample_rate_hz(&self) -> u32 {
+        self.sample_rate_hz
+    }
+
+    pub fn build(self) -> Vec<f32> {
+        self.samples
+    }
+
+    /// 16-bit PCM mono WAV file bytes.
+    pub fn to_wav_bytes(&self) -> Vec<u8> {
+        wav_bytes(&self.samples, self.sample_rate_hz)
+    }
+
+    fn sample_count(&self, duration_ms: u32) -> usize {
+        (u64::from(self.sample_rate_hz) * u64::from(duration_ms) / 1_000) as usize
+    }
+}
+
+impl Default for Signal {
+    fn default() -> Self {
+        Self::new(DEFAULT_SAMPLE_RATE_HZ)
+    }
+}
+
+/// `duration_ms` of a sine at `frequency_hz`, amplitude 0.5.
+pub fn tone(frequency_hz: f32, sample_rate_hz: u32, duration_ms: u32) -> Vec<f32> {
+    Signal::new(sample_rate_hz)
+        .tone(frequency_hz, duration_ms)
+        .build()
+}
+
+pub fn silence(sample_rate_hz: u32, duration_ms: u32) -> Vec<f32> {
+    Signal::new(sample_rate_hz).silence(duration_ms).build()
+}
+
+/// See [`Signal::speech_like`].
+pub fn speech_like(sample_rate_hz: u32, duration_ms: u32) -> Vec<f32> {
+    Signal::new(sample_rate_hz).speech_like(duration_ms).build()
+}
+
+/// Encodes mono f32 samples as a 16-bit PCM WAV file.
+pub fn wav_bytes(samples: &[f32], sample_rate_hz: u32) -> Vec<u8> {
+    let data_len = (samples.len() * 2) as u32;
+    let mut bytes = Vec::with_capacity(44 + data_len as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // mono
+    bytes.extend_from_slice(&sample_rate_hz.to_le_bytes());
+    bytes.extend_from_slice(&(sample_rate_hz * 2).to_le_bytes());
+    bytes.extend_from_slice(&2u16.to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        let value = (sample.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16;
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Syllable {
+    f0_hz: f32,
+    f1_hz: f32,
+    f2_hz: f32,
+}
+
+impl Syllable {
+    fn random(rng: &mut SplitMix64) -> Self {
+        Self {
+            f0_hz: 105.0 + 35.0 * rng.next_unit(),
+            f1_hz: 350.0 + 450.0 * rng.next_unit(),
+            f2_hz: 1_000.0 + 1_200.0 * rng.next_unit(),
+        }
+    }
+}
+
+fn formant_gain(frequency_hz: f32, center_hz: f32, bandwidth_hz: f32) -> f32 {
+    let distance = (frequency_hz - center_hz) / bandwidth_hz;
+    (-0.5 * distance * distance).exp()
+}
+
+/// Small, platform-independent PRNG; good enough for test signals.
+#[derive(Debug, Clone)]
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`.
+    fn next_unit(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Uniform in `[-1, 1)`.
+    fn next_signed(&mut self) -> f32 {
+        2.0 * self.next_unit() - 1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn segment_lengths_follow_the_sample_rate() {
+        let samples = Signal::new(8_000).silence(250).tone(440.0, 125).build();
+        assert_eq!(samples.len(), 2_000 + 1_000);
+        assert!(samples[..2_000].iter().all(|s| *s == 0.0));
+    }
+
+    #[test]
+    fn tone_peaks_at_the_amplitude() {
+        let samples = Signal::new(16_000).amplitude(0.8).tone(400.0, 100).build();
+        let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        assert!((peak - 0.8).abs() < 1e-3);
+    }
+
+    #[test]
+    fn output_is_deterministic_per_seed() {
+        let a = Signal::default().speech_like(500).noise(50).build();
+        let b = Signal::default().speech_like(500).noise(50).build();
+        let c = Signal::default().seed(7).speech_like(500).noise(50).build();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn speech_like_has_syllables_and_gaps() {
+        let samples = speech_like(16_000, 1_000);
+        assert!(samples.iter().all(|s| (-1.0..=1.0).contains(s)));
+        // 250 ms syllables: the middle of the first is loud, its tail is a gap.
+        assert!(rms(&samples[1_200..2_000]) > 0.05);
+        assert!(rms(&samples[3_300..3_900]) < 0.01);
+    }
+
+    #[test]
+    fn wav_header_describes_the_samples() {
+        let bytes = wav_bytes(&[0.0, 1.0, -1.0], 22_050);
+        assert_eq!(bytes.len(), 44 + 6);
+        assert_eq!(&bytes[0..4], b"RIFF");
+        assert_eq!(u32::from_le_bytes(bytes[24..28].try_into().unwrap()), 22_050);
+        assert_eq!(i16::from_le_bytes([bytes[46], bytes[47]]), i16::MAX);
+    }
+}
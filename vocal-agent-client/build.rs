@@ -0,0 +1,36 @@
+//! Generates the clients of the downstream gRPC services from their protos,
+//! so the SDK does not link the servers. The shared `common.v1` messages come
+//! from `common-proto`, as in the services.
+
+const PROTOS: [&str; 4] = [
+    "../asr-service/proto/asr.proto",
+    "../alignment-service/proto/alignment.proto",
+    "../audio-service/proto/audio.proto",
+    "../tempo-service/proto/tempo.proto",
+];
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let protoc = protoc_bin_vendored::protoc_bin_path()?;
+    std::env::set_var("PROTOC", protoc);
+
+    tonic_prost_build::configure()
+        .build_client(true)
+        .build_server(false)
+        .extern_path(".common.v1", "::common_proto::v1")
+        .compile_protos(
+            &PROTOS,
+            &[
+                "../asr-service/proto",
+                "../alignment-service/proto",
+                "../audio-service/proto",
+                "../tempo-service/proto",
+                "../common-proto/proto",
+            ],
+        )?;
+
+    for proto in PROTOS {
+        println!("cargo:rerun-if-changed={proto}");
+    }
+    println!("cargo:rerun-if-changed=../common-proto/proto/common/v1/common.proto");
+    Ok(())
+}
@@ -0,0 +1,20 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("cannot connect to {url}: {message}")]
+    Connect { url: String, message: String },
+
+    #[error("transport error: {0}")]
+    Transport(String),
+
+    /// A non-2xx HTTP answer; `body` is the problem document as sent.
+    #[error("server answered {status}: {body}")]
+    Status { status: u16, body: String },
+
+    #[error("invalid server message: {0}")]
+    Decode(String),
+
+    #[error("grpc call failed: {}: {}", .0.code(), .0.message())]
+    Grpc(#[from] tonic::Status),
+}
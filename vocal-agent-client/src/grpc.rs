@@ -0,0 +1,95 @@
+//! Clients of the downstream gRPC services with their `pb` types, generated
+//! by this crate's build script, so callers need neither the protos nor a
+//! build script of their own.
+
+use tonic::transport::{Channel, Endpoint};
+
+use crate::ClientError;
+
+/// Audio travels as raw samples; tonic's 4 MiB default fits about a minute.
+pub const MAX_MESSAGE_BYTES: usize = 64 * 1024 * 1024;
+
+pub mod asr {
+    pub mod pb {
+        tonic::include_proto!("asr.v1");
+
+        pub use common_proto::v1::{
+            LanguageTag, LanguageTagCode, Transcript, TranscriptSegment, TranscriptToken,
+        };
+    }
+
+    pub use pb::asr_service_client::AsrServiceClient;
+
+    use super::{channel, Channel, ClientError, MAX_MESSAGE_BYTES};
+
+    pub async fn connect(url: &str) -> Result<AsrServiceClient<Channel>, ClientError> {
+        Ok(AsrServiceClient::new(channel(url).await?)
+            .max_decoding_message_size(MAX_MESSAGE_BYTES)
+            .max_encoding_message_size(MAX_MESSAGE_BYTES))
+    }
+}
+
+pub mod alignment {
+    pub mod pb {
+        tonic::include_proto!("alignment.v1");
+
+        pub use common_proto::v1::{
+            LanguageTag, LanguageTagCode, Transcript, TranscriptSegment, TranscriptToken,
+            WordTiming,
+        };
+    }
+
+    pub use pb::alignment_service_client::AlignmentServiceClient;
+
+    use super::{channel, Channel, ClientError, MAX_MESSAGE_BYTES};
+
+    pub async fn connect(url: &str) -> Result<AlignmentServiceClient<Channel>, ClientError> {
+        Ok(AlignmentServiceClient::new(channel(url).await?)
+            .max_decoding_message_size(MAX_MESSAGE_BYTES)
+            .max_encoding_message_size(MAX_MESSAGE_BYTES))
+    }
+}
+
+pub mod audio {
+    pub mod pb {
+        tonic::include_proto!("audio.v1");
+    }
+
+    pub use pb::audio_service_client::AudioServiceClient;
+
+    use super::{channel, Channel, ClientError, MAX_MESSAGE_BYTES};
+
+    pub async fn connect(url: &str) -> Result<AudioServiceClient<Channel>, ClientError> {
+        Ok(AudioServiceClient::new(channel(url).await?)
+            .max_decoding_message_size(MAX_MESSAGE_BYTES)
+            .max_encoding_message_size(MAX_MESSAGE_BYTES))
+    }
+}
+
+pub mod tempo {
+    pub mod pb {
+        tonic::include_proto!("tempo.v1");
+    }
+
+    pub use pb::tempo_service_client::TempoServiceClient;
+
+    use super::{channel, Channel, ClientError, MAX_MESSAGE_BYTES};
+
+    pub async fn connect(url: &str) -> Result<TempoServiceClient<Channel>, ClientError> {
+        Ok(TempoServiceClient::new(channel(url).await?)
+            .max_decoding_message_size(MAX_MESSAGE_BYTES)
+            .max_encoding_message_size(MAX_MESSAGE_BYTES))
+    }
+}
+
+async fn channel(url: &str) -> Result<Channel, ClientError> {
+    let connect_error = |message: String| ClientError::Connect {
+        url: url.to_string(),
+        message,
+    };
+    Endpoint::from_shared(url.to_string())
+        .map_err(|err| connect_error(err.to_string()))?
+        .connect()
+        .await
+        .map_err(|err| connect_error(err.to_string()))
+}
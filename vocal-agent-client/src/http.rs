@@ -0,0 +1,351 @@
+//! Client of the orchestration HTTP API. The types are the wire shapes of
+//! the orchestration DTOs, seen from the caller's side.
+
+use common_domain::{Pause, Transcript, TranscriptSegment, TtsOutput, WordTiming};
+use reqwest::RequestBuilder;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::ClientError;
+
+pub const TRANSCRIBE_PATH: &str = "/api/asr/transcribe";
+pub const TRANSCRIBE_BATCH_PATH: &str = "/api/asr/transcribe-batch";
+pub const PIPELINES_PATH: &str = "/api/asr/pipelines";
+pub const SESSIONS_PATH: &str = "/api/sessions";
+pub const TENANT_ID_HEADER: &str = "x-tenant-id";
+
+/// Exactly one of `samples` and `audio_url` holds the audio; see
+/// [`TranscribeRequest::samples`] and [`TranscribeRequest::audio_url`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TranscribeRequest {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub samples: Vec<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audio_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sample_rate_hz: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language_hint: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channels: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pipeline: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transcript: Option<ProvidedTranscript>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decode_profile: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grammar: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_offset_ms: Option<u64>,
+}
+
+impl TranscribeRequest {
+    pub fn samples(samples: Vec<f32>, sample_rate_hz: u32) -> Self {
+        Self {
+            samples,
+            sample_rate_hz: Some(sample_rate_hz),
+            ..Self::default()
+        }
+    }
+
+    /// WAV fetched by the server: `s3://bucket/key`, `http://` or `https://`.
+    pub fn audio_url(url: impl Into<String>) -> Self {
+        Self {
+            audio_url: Some(url.into()),
+            ..Self::default()
+        }
+    }
+
+    /// A BCP-47 tag such as `fr` or `pt-BR`, or `auto`.
+    pub fn with_language_hint(mut self, language: impl Into<String>) -> Self {
+        self.language_hint = Some(language.into());
+        self
+    }
+
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    pub fn with_pipeline(mut self, pipeline: impl Into<String>) -> Self {
+        self.pipeline = Some(pipeline.into());
+        self
+    }
+
+    pub fn with_session_id(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+}
+
+/// Transcript for pipelines that align instead of transcribing.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum ProvidedTranscript {
+    Transcript(Transcript),
+    Text(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscribeResponse {
+    pub session_id: String,
+    pub transcript: Transcript,
+    pub aligned_words: Vec<WordTiming>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pauses: Vec<Pause>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub quality_issues: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detected_language: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language_probability: Option<f32>,
+    pub text: String,
+    #[serde(default)]
+    pub tts_output: Option<TtsOutput>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channels: Vec<ChannelTranscription>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelTranscription {
+    pub channel: u8,
+    pub transcript: Transcript,
+    pub aligned_words: Vec<WordTiming>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pauses: Vec<Pause>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscribeBatchItem {
+    /// Echoed in the item's result.
+    pub id: String,
+    #[serde(flatten)]
+    pub request: TranscribeRequest,
+}
+
+/// Exactly one of `result` and `error` is set.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TranscribeBatchItemResult {
+    pub id: String,
+    #[serde(default)]
+    pub result: Option<TranscribeResponse>,
+    #[serde(default)]
+    pub error: Option<BatchItemError>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BatchItemError {
+    /// One of the `ErrorCode` strings, e.g. `invalid_input`.
+    pub code: String,
+    pub message: String,
+}
+
+/// Either `text`, which replaces the whole transcript, or `segments`, which
+/// replace the stored segments they overlap.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CorrectTranscriptRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub segments: Option<Vec<TranscriptSegment>>,
+}
+
+/// A stored session's transcript, as returned by lookups and corrections.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SessionTranscript {
+    pub session_id: String,
+    pub transcript: Transcript,
+    pub aligned_words: Vec<WordTiming>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PipelineCatalog {
+    pub selected: String,
+    pub pipelines: Vec<PipelineDescription>,
+    pub optional_steps: Vec<StepAvailability>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PipelineDescription {
+    pub name: String,
+    pub selected: bool,
+    pub steps: Vec<PipelineStepDescription>,
+    pub endpoints: Vec<EndpointDescription>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PipelineStepDescription {
+    /// `pre`, `transcription` or `post`.
+    pub phase: String,
+    pub name: String,
+    pub stage: String,
+    /// `builtin`, `recipe` or `plugin`.
+    pub source: String,
+    pub services: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EndpointDescription {
+    pub service: String,
+    pub targets: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StepAvailability {
+    pub name: String,
+    pub source: String,
+    pub available: bool,
+    #[serde(default)]
+    pub requires: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    client: reqwest::Client,
+    base_url: String,
+    tenant_id: Option<String>,
+}
+
+impl HttpClient {
+    /// `base_url` is the orchestration listener, e.g. `http://127.0.0.1:8090`.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            tenant_id: None,
+        }
+    }
+
+    /// Sends `x-tenant-id` with every request.
+    pub fn with_tenant_id(mut self, tenant_id: impl Into<String>) -> Self {
+        self.tenant_id = Some(tenant_id.into());
+        self
+    }
+
+    /// Uses `client`, e.g. one with timeouts or a proxy, for the requests.
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    pub async fn transcribe(
+        &self,
+        request: &TranscribeRequest,
+    ) -> Result<TranscribeResponse, ClientError> {
+        self.send(self.client.post(self.url(TRANSCRIBE_PATH)).json(request))
+            .await
+    }
+
+    /// Results come back in item order; a failed item does not fail the
+    /// batch.
+    pub async fn transcribe_batch(
+        &self,
+        items: &[TranscribeBatchItem],
+    ) -> Result<Vec<TranscribeBatchItemResult>, ClientError> {
+        #[derive(Deserialize)]
+        struct Results {
+            results: Vec<TranscribeBatchItemResult>,
+        }
+
+        let body = json!({ "items": items });
+        let response: Results = self
+            .send(self.client.post(self.url(TRANSCRIBE_BATCH_PATH)).json(&body))
+            .await?;
+        Ok(response.results)
+    }
+
+    pub async fn pipelines(&self) -> Result<PipelineCatalog, ClientError> {
+        self.send(self.client.get(self.url(PIPELINES_PATH))).await
+    }
+
+    pub async fn pipeline(&self, name: &str) -> Result<PipelineDescription, ClientError> {
+        let path = format!("{PIPELINES_PATH}/{name}");
+        self.send(self.client.get(self.url(&path))).await
+    }
+
+    /// A session stored by a pipeline with the `store_session` stage.
+    pub async fn session(&self, session_id: &str) -> Result<SessionTranscript, ClientError> {
+        let path = format!("{SESSIONS_PATH}/{session_id}");
+        self.send(self.client.get(self.url(&path))).await
+    }
+
+    /// Realigns a corrected transcript against the session's stored audio.
+    pub async fn correct(
+        &self,
+        session_id: &str,
+        request: &CorrectTranscriptRequest,
+    ) -> Result<SessionTranscript, ClientError> {
+        let path = format!("{SESSIONS_PATH}/{session_id}/correction");
+        self.send(self.client.post(self.url(&path)).json(request))
+            .await
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.base_url)
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, ClientError> {
+        let request = match &self.tenant_id {
+            Some(tenant_id) => request.header(TENANT_ID_HEADER, tenant_id),
+            None => request,
+        };
+        let response = request
+            .send()
+            .await
+            .map_err(|err| ClientError::Transport(err.to_string()))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ClientError::Status {
+                status: status.as_u16(),
+                body,
+            });
+        }
+        response
+            .json()
+            .await
+            .map_err(|err| ClientError::Decode(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unset_request_fields_are_left_out() {
+        let request = TranscribeRequest::samples(vec![0.0; 2], 16_000).with_model("tiny");
+        let raw = serde_json::to_value(&request).expect("serializes");
+        assert_eq!(
+            raw,
+            json!({ "samples": [0.0, 0.0], "sample_rate_hz": 16_000, "model": "tiny" })
+        );
+    }
+
+    #[test]
+    fn batch_items_carry_the_request_inline() {
+        let item = TranscribeBatchItem {
+            id: "a".to_string(),
+            request: TranscribeRequest::audio_url("s3://bucket/a.wav"),
+        };
+        let raw = serde_json::to_value(&item).expect("serializes");
+        assert_eq!(raw, json!({ "id": "a", "audio_url": "s3://bucket/a.wav" }));
+    }
+
+    #[test]
+    fn base_url_trailing_slash_is_dropped() {
+        let client = HttpClient::new("http://localhost:8080/");
+        assert_eq!(
+            client.url(TRANSCRIBE_PATH),
+            "http://localhost:8080/api/asr/transcribe"
+        );
+    }
+}
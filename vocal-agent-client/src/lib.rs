@@ -0,0 +1,20 @@
+//! Typed async clients for vocal-agent: the orchestration HTTP API
+//! ([`HttpClient`]), the WebSocket streaming protocol ([`ws::connect`]) and
+//! the downstream gRPC services ([`grpc`]), whose types this crate generates
+//! so consumers don't copy the protos. It depends on no server crate. Clients
+//! in other languages generate theirs from the protocol's JSON Schema
+//! ([`schema`]).
+
+pub mod error;
+pub mod grpc;
+pub mod http;
+pub mod schema;
+pub mod ws;
+
+pub use common_domain::{LanguageTag, Transcript, TranscriptSegment, WordTiming};
+pub use error::ClientError;
+pub use http::{
+    CorrectTranscriptRequest, HttpClient, SessionTranscript, TranscribeBatchItem,
+    TranscribeBatchItemResult, TranscribeRequest, TranscribeResponse,
+};
+pub use ws::{ServerEnvelope, ServerMessage, WsReceiver, WsSender};
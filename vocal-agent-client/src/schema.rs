@@ -0,0 +1,44 @@
+//! JSON Schema of the WebSocket streaming protocol, for clients in other
+//! languages. It is generated from the envelopes the server and this crate
+//! share, committed at `schema/ws-protocol.json`, and `tests/ws_schema.rs`
+//! fails when the file is missing or the envelopes drift from it.
+
+use utoipa::OpenApi;
+use vocal_ws_protocol::{ClientEnvelope, ClientMessage, ServerEnvelope, ServerMessage, Verbosity};
+
+/// Writes `schema/ws-protocol.json` when set to anything but `0`.
+pub const UPDATE_ENV: &str = "WS_SCHEMA_UPDATE";
+
+/// OpenAPI 3.1 document holding the envelopes as `components.schemas`, so
+/// the usual generators, e.g. `openapi-typescript`, turn it into types.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Vocal agent streaming protocol"),
+    components(schemas(ClientEnvelope, ClientMessage, Verbosity, ServerEnvelope, ServerMessage))
+)]
+pub struct WsProtocolDoc;
+
+/// The document as committed: pretty-printed with a trailing newline.
+pub fn ws_protocol_json() -> String {
+    let mut json = WsProtocolDoc::openapi()
+        .to_pretty_json()
+        .expect("schema serializes");
+    json.push('\n');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn both_directions_are_described() {
+        let schemas = WsProtocolDoc::openapi()
+            .components
+            .expect("components")
+            .schemas;
+        for schema in ["ClientEnvelope", "ServerEnvelope", "Transcript", "WordTiming"] {
+            assert!(schemas.contains_key(schema), "{schema} schema is missing");
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! Client half of the WebSocket streaming protocol. The envelopes are the
+//! server's own, from `vocal-ws-protocol`, and are published as a JSON
+//! Schema by [`crate::schema`].
+
+use std::time::Duration;
+
+use common_domain::{LanguageTag, Transcript};
+use futures::stream::{SplitSink, SplitStream};
+use futures::{SinkExt, StreamExt};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+use crate::ClientError;
+
+pub use vocal_ws_protocol::{
+    ClientEnvelope, ClientMessage, ServerEnvelope, ServerMessage, Verbosity, PROTOCOL_VERSION,
+    STREAM_SAMPLE_RATE_HZ,
+};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Sending half of a streaming session.
+pub struct WsSender {
+    sink: SplitSink<Socket, Message>,
+    track: Option<String>,
+}
+
+/// Receiving half of a streaming session. Yields decoded server envelopes.
+pub struct WsReceiver {
+    stream: SplitStream<Socket>,
+}
+
+pub async fn connect(url: &str) -> Result<(WsSender, WsReceiver), ClientError> {
+    let (socket, _) = connect_async(url)
+        .await
+        .map_err(|err| ClientError::Connect {
+            url: url.to_string(),
+            message: err.to_string(),
+        })?;
+    let (sink, stream) = socket.split();
+    Ok((WsSender { sink, track: None }, WsReceiver { stream }))
+}
+
+impl WsSender {
+    /// Tags the messages sent from now on with `track`.
+    pub fn with_track(mut self, track: impl Into<String>) -> Self {
+        self.track = Some(track.into());
+        self
+    }
+
+    pub async fn start(&mut self, language_hint: Option<LanguageTag>) -> Result<(), ClientError> {
+        self.start_with(None, language_hint, Verbosity::default())
+            .await
+    }
+
+    /// `start` resuming `session_id` or with fewer events.
+    pub async fn start_with(
+        &mut self,
+        session_id: Option<String>,
+        language_hint: Option<LanguageTag>,
+        verbosity: Verbosity,
+    ) -> Result<(), ClientError> {
+        self.send(ClientMessage::Start {
+            session_id,
+            language_hint,
+            verbosity,
+        })
+        .await
+    }
+
+    pub async fn audio_frame(&mut self, pcm_f32: &[f32]) -> Result<(), ClientError> {
+        self.send(ClientMessage::AudioFrame {
+            pcm_f32: pcm_f32.to_vec(),
+        })
+        .await
+    }
+
+    pub async fn flush(&mut self) -> Result<(), ClientError> {
+        self.send(ClientMessage::Flush).await
+    }
+
+    pub async fn stop(&mut self) -> Result<(), ClientError> {
+        self.send(ClientMessage::Stop).await
+    }
+
+    pub async fn ping(&mut self) -> Result<(), ClientError> {
+        self.send(ClientMessage::Ping).await
+    }
+
+    pub async fn close(mut self) {
+        let _ = self.sink.close().await;
+    }
+
+    pub async fn send(&mut self, message: ClientMessage) -> Result<(), ClientError> {
+        let envelope = ClientEnvelope::new(message).with_track(self.track.clone());
+        let raw = serde_json::to_string(&envelope)
+            .map_err(|err| ClientError::Transport(err.to_string()))?;
+        self.sink
+            .send(Message::Text(raw.into()))
+            .await
+            .map_err(|err| ClientError::Transport(format!("send failed: {err}")))
+    }
+}
+
+impl WsReceiver {
+    /// Next message from the server, or `None` when the socket closes or
+    /// nothing arrives within `timeout`. Cancel-safe.
+    pub async fn next(
+        &mut self,
+        timeout: Option<Duration>,
+    ) -> Result<Option<ServerEnvelope>, ClientError> {
+        let next = async {
+            loop {
+                match self.stream.next().await {
+                    Some(Ok(Message::Text(raw))) => {
+                        return serde_json::from_str(raw.as_str())
+                            .map(Some)
+                            .map_err(|err| ClientError::Decode(err.to_string()));
+                    }
+                    Some(Ok(Message::Close(_))) | None => return Ok(None),
+                    Some(Ok(_)) => continue,
+                    Some(Err(err)) => {
+                        return Err(ClientError::Transport(format!("websocket error: {err}")))
+                    }
+                }
+            }
+        };
+        match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, next).await.unwrap_or(Ok(None)),
+            None => next.await,
+        }
+    }
+}
+
+/// Joined segment texts of a transcript.
+pub fn transcript_text(transcript: &Transcript) -> String {
+    transcript
+        .segments
+        .iter()
+        .map(|segment| segment.text.trim())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn transcript_text_joins_segments() {
+        let transcript: Transcript = serde_json::from_value(json!({
+            "language": "en",
+            "segments": [
+                { "text": " hello ", "start_ms": 0, "end_ms": 500, "tokens": [] },
+                { "text": "", "start_ms": 500, "end_ms": 600, "tokens": [] },
+                { "text": "world", "start_ms": 600, "end_ms": 900, "tokens": [] }
+            ]
+        }))
+        .expect("transcript");
+        assert_eq!(transcript_text(&transcript), "hello world");
+    }
+}
@@ -0,0 +1,29 @@
+//! Keeps `schema/ws-protocol.json` in step with the protocol envelopes.
+
+use std::path::Path;
+use std::{env, fs};
+
+use vocal_agent_client::schema::{ws_protocol_json, UPDATE_ENV};
+
+#[test]
+fn committed_schema_matches_the_envelopes() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("schema/ws-protocol.json");
+    let generated = ws_protocol_json();
+    if env::var(UPDATE_ENV).is_ok_and(|value| value != "0") {
+        fs::create_dir_all(path.parent().expect("schema dir")).expect("create schema dir");
+        fs::write(&path, generated).expect("write schema");
+        return;
+    }
+
+    let committed = fs::read_to_string(&path).unwrap_or_else(|err| {
+        panic!(
+            "{} is unreadable ({err}); generate it with {UPDATE_ENV}=1 and commit it",
+            path.display()
+        )
+    });
+    assert!(
+        committed == generated,
+        "{} is out of date with the protocol; rerun with {UPDATE_ENV}=1 and commit it",
+        path.display()
+    );
+}
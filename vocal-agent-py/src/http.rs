@@ -0,0 +1,77 @@
+use pyo3::prelude::*;
+use vocal_agent_client::{HttpClient, TranscribeRequest};
+
+use crate::{client_error, language_tag, runtime, to_python};
+
+/// Client of the orchestration HTTP API.
+#[pyclass(module = "vocal_agent")]
+pub struct Client {
+    http: HttpClient,
+}
+
+#[pymethods]
+impl Client {
+    #[new]
+    #[pyo3(signature = (http_url = "http://127.0.0.1:8090", tenant_id = None))]
+    fn new(http_url: &str, tenant_id: Option<String>) -> Self {
+        let http = HttpClient::new(http_url);
+        Self {
+            http: match tenant_id {
+                Some(tenant_id) => http.with_tenant_id(tenant_id),
+                None => http,
+            },
+        }
+    }
+
+    /// Runs the pipeline on mono `samples` and returns the response
+    /// document: `text`, `transcript`, `aligned_words`, ...
+    #[pyo3(signature = (samples, sample_rate_hz, language = None, model = None, pipeline = None))]
+    fn transcribe(
+        &self,
+        py: Python<'_>,
+        samples: Vec<f32>,
+        sample_rate_hz: u32,
+        language: Option<&str>,
+        model: Option<String>,
+        pipeline: Option<String>,
+    ) -> PyResult<Py<PyAny>> {
+        let request = TranscribeRequest::samples(samples, sample_rate_hz);
+        self.run(py, request, language, model, pipeline)
+    }
+
+    /// Like `transcribe`, on a WAV the server fetches: `s3://bucket/key`,
+    /// `http://` or `https://`.
+    #[pyo3(signature = (audio_url, language = None, model = None, pipeline = None))]
+    fn transcribe_url(
+        &self,
+        py: Python<'_>,
+        audio_url: String,
+        language: Option<&str>,
+        model: Option<String>,
+        pipeline: Option<String>,
+    ) -> PyResult<Py<PyAny>> {
+        self.run(py, TranscribeRequest::audio_url(audio_url), language, model, pipeline)
+    }
+}
+
+impl Client {
+    fn run(
+        &self,
+        py: Python<'_>,
+        request: TranscribeRequest,
+        language: Option<&str>,
+        model: Option<String>,
+        pipeline: Option<String>,
+    ) -> PyResult<Py<PyAny>> {
+        let request = TranscribeRequest {
+            language_hint: language_tag(language)?.map(|tag| tag.to_string()),
+            model,
+            pipeline,
+            ..request
+        };
+        let response = py
+            .detach(|| runtime().block_on(self.http.transcribe(&request)))
+            .map_err(client_error)?;
+        to_python(py, &response)
+    }
+}
@@ -0,0 +1,65 @@
+//! `vocal_agent` Python module: the transcribe call and the streaming
+//! client of `vocal-agent-client`, as blocking methods that release the GIL
+//! while they wait. Results are the JSON documents of the wire protocol,
+//! decoded into `dict`s.
+
+use std::sync::OnceLock;
+
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+use serde::Serialize;
+use tokio::runtime::Runtime;
+use vocal_agent_client::{ClientError, LanguageTag};
+
+mod http;
+mod stream;
+
+pub use http::Client;
+pub use stream::StreamSession;
+
+create_exception!(
+    vocal_agent,
+    VocalAgentError,
+    PyException,
+    "A call to a vocal-agent service failed."
+);
+
+/// Shared by every client of the process; the module has no async API.
+fn runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .expect("tokio runtime")
+    })
+}
+
+fn client_error(err: ClientError) -> PyErr {
+    VocalAgentError::new_err(err.to_string())
+}
+
+fn language_tag(language: Option<&str>) -> PyResult<Option<LanguageTag>> {
+    language
+        .map(LanguageTag::parse)
+        .transpose()
+        .map_err(|err| PyValueError::new_err(err.to_string()))
+}
+
+/// `value` as Python objects, through the `json` module so the shapes match
+/// what the HTTP API documents.
+fn to_python<T: Serialize>(py: Python<'_>, value: &T) -> PyResult<Py<PyAny>> {
+    let raw =
+        serde_json::to_string(value).map_err(|err| VocalAgentError::new_err(err.to_string()))?;
+    Ok(py.import("json")?.call_method1("loads", (raw,))?.unbind())
+}
+
+#[pymodule]
+fn vocal_agent(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Client>()?;
+    m.add_class::<StreamSession>()?;
+    m.add("VocalAgentError", m.py().get_type::<VocalAgentError>())?;
+    m.add("STREAM_SAMPLE_RATE_HZ", vocal_agent_client::ws::STREAM_SAMPLE_RATE_HZ)?;
+    Ok(())
+}
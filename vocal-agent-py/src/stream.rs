@@ -0,0 +1,116 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use vocal_agent_client::ws::{self, WsReceiver, WsSender};
+use vocal_agent_client::ClientError;
+
+use crate::{client_error, language_tag, runtime, to_python, VocalAgentError};
+
+/// A WebSocket streaming session, started on construction. Usable as a
+/// context manager, which closes it on exit.
+#[pyclass(module = "vocal_agent")]
+pub struct StreamSession {
+    /// `None` once closed.
+    sender: Mutex<Option<WsSender>>,
+    receiver: Mutex<WsReceiver>,
+}
+
+#[pymethods]
+impl StreamSession {
+    #[new]
+    #[pyo3(signature = (ws_url = "ws://127.0.0.1:8091/ws", language = None, track = None))]
+    fn new(
+        py: Python<'_>,
+        ws_url: &str,
+        language: Option<&str>,
+        track: Option<String>,
+    ) -> PyResult<Self> {
+        let language = language_tag(language)?;
+        let (sender, receiver) = py
+            .detach(|| {
+                runtime().block_on(async {
+                    let (sender, receiver) = ws::connect(ws_url).await?;
+                    let mut sender = match track {
+                        Some(track) => sender.with_track(track),
+                        None => sender,
+                    };
+                    sender.start(language).await?;
+                    Ok::<_, ClientError>((sender, receiver))
+                })
+            })
+            .map_err(client_error)?;
+        Ok(Self {
+            sender: Mutex::new(Some(sender)),
+            receiver: Mutex::new(receiver),
+        })
+    }
+
+    /// Sends mono `samples` at `STREAM_SAMPLE_RATE_HZ`.
+    fn send_audio(&self, py: Python<'_>, samples: Vec<f32>) -> PyResult<()> {
+        self.with_sender(py, |sender| runtime().block_on(sender.audio_frame(&samples)))
+    }
+
+    /// Asks for the transcript of the audio buffered so far.
+    fn flush(&self, py: Python<'_>) -> PyResult<()> {
+        self.with_sender(py, |sender| runtime().block_on(sender.flush()))
+    }
+
+    /// Ends the session; its final events still arrive through `recv`.
+    fn stop(&self, py: Python<'_>) -> PyResult<()> {
+        self.with_sender(py, |sender| runtime().block_on(sender.stop()))
+    }
+
+    /// Next server event as a `dict` with `type` and `payload`, or `None`
+    /// once the connection closes or nothing arrives within `timeout`
+    /// seconds.
+    #[pyo3(signature = (timeout = None))]
+    fn recv(&self, py: Python<'_>, timeout: Option<f64>) -> PyResult<Option<Py<PyAny>>> {
+        let timeout = timeout
+            .map(Duration::try_from_secs_f64)
+            .transpose()
+            .map_err(|err| PyValueError::new_err(format!("invalid timeout: {err}")))?;
+        let envelope = py
+            .detach(|| {
+                let mut receiver = self.receiver.lock().expect("receiver lock");
+                runtime().block_on(receiver.next(timeout))
+            })
+            .map_err(client_error)?;
+        envelope
+            .map(|envelope| to_python(py, &envelope))
+            .transpose()
+    }
+
+    fn close(&self, py: Python<'_>) {
+        let sender = self.sender.lock().expect("sender lock").take();
+        if let Some(sender) = sender {
+            py.detach(|| runtime().block_on(sender.close()));
+        }
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    #[pyo3(signature = (*_args))]
+    fn __exit__(&self, py: Python<'_>, _args: &Bound<'_, pyo3::types::PyTuple>) {
+        self.close(py);
+    }
+}
+
+impl StreamSession {
+    fn with_sender(
+        &self,
+        py: Python<'_>,
+        send: impl FnOnce(&mut WsSender) -> Result<(), ClientError> + Send,
+    ) -> PyResult<()> {
+        py.detach(|| {
+            let mut sender = self.sender.lock().expect("sender lock");
+            match sender.as_mut() {
+                Some(sender) => send(sender).map_err(client_error),
+                None => Err(VocalAgentError::new_err("the session is closed")),
+            }
+        })
+    }
+}
@@ -0,0 +1,46 @@
+from types import TracebackType
+from typing import Any, Optional, Sequence, Type
+
+STREAM_SAMPLE_RATE_HZ: int
+
+class VocalAgentError(Exception): ...
+
+class Client:
+    def __init__(
+        self, http_url: str = "http://127.0.0.1:8090", tenant_id: Optional[str] = None
+    ) -> None: ...
+    def transcribe(
+        self,
+        samples: Sequence[float],
+        sample_rate_hz: int,
+        language: Optional[str] = None,
+        model: Optional[str] = None,
+        pipeline: Optional[str] = None,
+    ) -> dict[str, Any]: ...
+    def transcribe_url(
+        self,
+        audio_url: str,
+        language: Optional[str] = None,
+        model: Optional[str] = None,
+        pipeline: Optional[str] = None,
+    ) -> dict[str, Any]: ...
+
+class StreamSession:
+    def __init__(
+        self,
+        ws_url: str = "ws://127.0.0.1:8091/ws",
+        language: Optional[str] = None,
+        track: Optional[str] = None,
+    ) -> None: ...
+    def send_audio(self, samples: Sequence[float]) -> None: ...
+    def flush(self) -> None: ...
+    def stop(self) -> None: ...
+    def recv(self, timeout: Optional[float] = None) -> Optional[dict[str, Any]]: ...
+    def close(self) -> None: ...
+    def __enter__(self) -> "StreamSession": ...
+    def __exit__(
+        self,
+        exc_type: Optional[Type[BaseException]],
+        exc: Optional[BaseException],
+        traceback: Optional[TracebackType],
+    ) -> None: ...
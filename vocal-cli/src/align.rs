@@ -0,0 +1,73 @@
+use serde_json::json;
+use vocal_agent_client::grpc::alignment::{self, pb};
+use vocal_proto_mappings::WireLanguageTag;
+use wav_io::read_wav;
+
+use crate::args::{language_tag, AlignArgs, Endpoints};
+
+pub async fn run(endpoints: &Endpoints, args: AlignArgs) -> Result<(), String> {
+    let audio = read_wav(&args.file)?;
+    let text = std::fs::read_to_string(&args.transcript)
+        .map_err(|err| format!("cannot read `{}`: {err}", args.transcript.display()))?;
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        return Err(format!("`{}` is empty", args.transcript.display()));
+    }
+
+    let language = language_tag(&args.language)?;
+    let (code, other) = WireLanguageTag::from_tag(&language.to_string()).into_parts();
+    let request = pb::EnrichTranscriptRequest {
+        sample_rate_hz: Some(audio.sample_rate_hz),
+        transcript: Some(pb::Transcript {
+            language: Some(pb::LanguageTag { code, other }),
+            segments: vec![pb::TranscriptSegment {
+                text: text.clone(),
+                start_ms: 0,
+                end_ms: audio.duration_ms(),
+                tokens: Vec::new(),
+                speaker: None,
+                language: None,
+                no_speech_prob: None,
+                avg_logprob: None,
+            }],
+        }),
+        samples: audio.samples,
+        session_id: None,
+        time_offset_ms: None,
+    };
+
+    let mut client = alignment::connect(&endpoints.alignment_url)
+        .await
+        .map_err(|err| err.to_string())?;
+    let response = client
+        .enrich_transcript(request)
+        .await
+        .map_err(|status| format!("alignment failed: {}: {}", status.code(), status.message()))?
+        .into_inner();
+
+    if args.json {
+        let words: Vec<_> = response
+            .aligned_words
+            .iter()
+            .map(|word| {
+                json!({
+                    "word": word.word,
+                    "start_ms": word.start_ms,
+                    "end_ms": word.end_ms,
+                    "confidence": word.confidence,
+                })
+            })
+            .collect();
+        let body = json!({ "session_id": response.session_id, "words": words });
+        println!("{}", serde_json::to_string_pretty(&body).unwrap_or_default());
+    } else {
+        for word in &response.aligned_words {
+            println!(
+                "{:>8} {:>8}  {:.2}  {}",
+                word.start_ms, word.end_ms, word.confidence, word.word
+            );
+        }
+    }
+    Ok(())
+}
+
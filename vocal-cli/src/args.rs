@@ -0,0 +1,246 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use vocal_agent_client::LanguageTag;
+
+const SWITCHES: &[&str] = &["help", "json", "mic"];
+/// `/ws` on the orchestrator's streaming listener (`service.streaming.port`),
+/// not on its HTTP API.
+const DEFAULT_WS_URL: &str = "ws://127.0.0.1:8091/ws";
+
+pub struct Endpoints {
+    pub http_url: String,
+    pub alignment_url: String,
+    pub ws_url: String,
+}
+
+pub struct TranscribeArgs {
+    pub file: PathBuf,
+    pub language: Option<String>,
+    pub model: Option<String>,
+    pub json: bool,
+}
+
+pub struct AlignArgs {
+    pub file: PathBuf,
+    pub transcript: PathBuf,
+    pub language: String,
+    pub json: bool,
+}
+
+pub enum StreamSource {
+    File(PathBuf),
+    Mic,
+}
+
+pub struct StreamArgs {
+    pub source: StreamSource,
+    pub language: Option<String>,
+    pub chunk_ms: u32,
+    /// Interval between `flush` messages while capturing live audio.
+    pub flush_ms: u32,
+}
+
+pub enum Command {
+    Transcribe(TranscribeArgs),
+    Align(AlignArgs),
+    Stream(StreamArgs),
+    Help,
+}
+
+pub struct Options {
+    pub endpoints: Endpoints,
+    pub command: Command,
+}
+
+impl Options {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut raw = RawArgs::split(args)?;
+        let endpoints = Endpoints {
+            http_url: raw
+                .value("http-url")
+                .unwrap_or_else(|| "http://127.0.0.1:8090".to_string()),
+            alignment_url: raw
+                .value("alignment-url")
+                .unwrap_or_else(|| "http://127.0.0.1:8083".to_string()),
+            ws_url: raw
+                .value("ws-url")
+                .unwrap_or_else(|| DEFAULT_WS_URL.to_string()),
+        };
+        if raw.switch("help") || raw.positional.is_empty() {
+            return Ok(Self {
+                endpoints,
+                command: Command::Help,
+            });
+        }
+
+        let name = raw.positional.remove(0);
+        let command = match name.as_str() {
+            "transcribe" => Command::Transcribe(TranscribeArgs {
+                file: raw.file()?,
+                language: raw.value("language"),
+                model: raw.value("model"),
+                json: raw.switch("json"),
+            }),
+            "align" => Command::Align(AlignArgs {
+                file: raw.file()?,
+                transcript: raw
+                    .value("transcript")
+                    .map(PathBuf::from)
+                    .ok_or_else(|| "align requires --transcript".to_string())?,
+                language: raw.value("language").unwrap_or_else(|| "fr".to_string()),
+                json: raw.switch("json"),
+            }),
+            "stream" => Command::Stream(StreamArgs {
+                source: if raw.switch("mic") {
+                    StreamSource::Mic
+                } else {
+                    StreamSource::File(raw.file()?)
+                },
+                language: raw.value("language"),
+                chunk_ms: raw.positive("chunk-ms", 500)?,
+                flush_ms: raw.positive("flush-ms", 3_000)?,
+            }),
+            other => return Err(format!("unknown command `{other}`")),
+        };
+        raw.finish()?;
+
+        Ok(Self { endpoints, command })
+    }
+}
+
+/// Parses a `--language` value: a BCP-47 tag such as `fr` or `pt-BR`, or `auto`.
+pub fn language_tag(language: &str) -> Result<LanguageTag, String> {
+    LanguageTag::parse(language).map_err(|err| err.to_string())
+}
+
+/// Positional arguments plus `--key=value`, `--key value` and `--switch`
+/// options, consumed as each command reads them.
+struct RawArgs {
+    positional: Vec<String>,
+    values: HashMap<String, String>,
+    switches: HashSet<String>,
+}
+
+impl RawArgs {
+    fn split(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut raw = Self {
+            positional: Vec::new(),
+            values: HashMap::new(),
+            switches: HashSet::new(),
+        };
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg == "-h" {
+                raw.switches.insert("help".to_string());
+            } else if let Some(option) = arg.strip_prefix("--") {
+                if let Some((key, value)) = option.split_once('=') {
+                    raw.values.insert(key.to_string(), value.to_string());
+                } else if SWITCHES.contains(&option) {
+                    raw.switches.insert(option.to_string());
+                } else {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| format!("missing value for `--{option}`"))?;
+                    raw.values.insert(option.to_string(), value);
+                }
+            } else {
+                raw.positional.push(arg);
+            }
+        }
+        Ok(raw)
+    }
+
+    fn value(&mut self, key: &str) -> Option<String> {
+        self.values.remove(key)
+    }
+
+    fn positive(&mut self, key: &str, default: u32) -> Result<u32, String> {
+        match self.value(key) {
+            Some(value) => value
+                .parse()
+                .ok()
+                .filter(|parsed| *parsed > 0)
+                .ok_or_else(|| format!("invalid --{key} `{value}`")),
+            None => Ok(default),
+        }
+    }
+
+    fn switch(&mut self, key: &str) -> bool {
+        self.switches.remove(key)
+    }
+
+    fn file(&mut self) -> Result<PathBuf, String> {
+        if self.positional.is_empty() {
+            return Err("missing input file".to_string());
+        }
+        Ok(PathBuf::from(self.positional.remove(0)))
+    }
+
+    fn finish(self) -> Result<(), String> {
+        if let Some(extra) = self.positional.first() {
+            return Err(format!("unexpected argument `{extra}`"));
+        }
+        if let Some(key) = self.values.keys().chain(self.switches.iter()).next() {
+            return Err(format!("unknown option `--{key}`"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        Options::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn align_accepts_spaced_and_inline_values() {
+        let options = parse(&["align", "in.wav", "--transcript", "t.txt", "--language=en"])
+            .expect("arguments should parse");
+        let Command::Align(args) = options.command else {
+            panic!("expected align");
+        };
+        assert_eq!(args.file, PathBuf::from("in.wav"));
+        assert_eq!(args.transcript, PathBuf::from("t.txt"));
+        assert_eq!(args.language, "en");
+    }
+
+    #[test]
+    fn global_endpoints_are_overridable() {
+        let options = parse(&["--http-url=http://host:1", "transcribe", "in.wav", "--json"])
+            .expect("arguments should parse");
+        assert_eq!(options.endpoints.http_url, "http://host:1");
+        assert!(matches!(options.command, Command::Transcribe(TranscribeArgs { json: true, .. })));
+    }
+
+    #[test]
+    fn streams_default_to_the_streaming_listener() {
+        let options = parse(&["stream", "in.wav"]).expect("arguments should parse");
+        assert_eq!(options.endpoints.ws_url, "ws://127.0.0.1:8091/ws");
+        assert_eq!(options.endpoints.http_url, "http://127.0.0.1:8090");
+    }
+
+    #[test]
+    fn mic_streams_need_no_file() {
+        let options =
+            parse(&["stream", "--mic", "--flush-ms=2000"]).expect("arguments should parse");
+        assert_eq!(options.endpoints.ws_url, "ws://127.0.0.1:8091/ws");
+        let Command::Stream(args) = options.command else {
+            panic!("expected stream");
+        };
+        assert!(matches!(args.source, StreamSource::Mic));
+        assert_eq!(args.flush_ms, 2_000);
+    }
+
+    #[test]
+    fn unknown_options_are_rejected() {
+        let error = parse(&["transcribe", "in.wav", "--speed=2"])
+            .err()
+            .expect("unknown option");
+        assert!(error.contains("--speed"));
+        assert!(parse(&["stream", "--mic", "--chunk-ms=0"]).is_err());
+    }
+}
@@ -0,0 +1,225 @@
+//! Microphone capture on the default input device.
+//!
+//! Audio is downmixed to mono, cut into frames of `frame_ms` and resampled to
+//! the requested rate before being handed to the receiver, so frames can be
+//! forwarded to the streaming endpoint as-is.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc as std_mpsc, Arc};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, SampleFormat, SizedSample, StreamConfig};
+use thiserror::Error;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use vocal_dsp::resample_linear;
+
+#[derive(Debug, Error)]
+pub enum CaptureError {
+    #[error("no default input device")]
+    NoInputDevice,
+
+    #[error("input device error: {0}")]
+    Device(String),
+
+    #[error("unsupported sample format {0}")]
+    UnsupportedFormat(SampleFormat),
+}
+
+/// A running capture. Recording stops when this is dropped.
+pub struct MicCapture {
+    device_name: String,
+    device_sample_rate_hz: u32,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MicCapture {
+    /// Starts recording and returns frames of `frame_ms` at `target_rate_hz`.
+    pub fn start(
+        frame_ms: u32,
+        target_rate_hz: u32,
+    ) -> Result<(Self, UnboundedReceiver<Vec<f32>>), CaptureError> {
+        let (frames_tx, frames_rx) = unbounded_channel();
+        let (ready_tx, ready_rx) = std_mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+
+        // cpal streams are not `Send` on every platform, so the stream lives
+        // on its own thread for the whole capture.
+        let thread = thread::spawn(move || {
+            let stream = match open_stream(frame_ms, target_rate_hz, frames_tx) {
+                Ok((stream, name, rate)) => {
+                    let _ = ready_tx.send(Ok((name, rate)));
+                    stream
+                }
+                Err(err) => {
+                    let _ = ready_tx.send(Err(err));
+                    return;
+                }
+            };
+            while !thread_stop.load(Ordering::Relaxed) {
+                thread::sleep(Duration::from_millis(50));
+            }
+            drop(stream);
+        });
+
+        let (device_name, device_sample_rate_hz) = ready_rx
+            .recv()
+            .map_err(|_| CaptureError::Device("capture thread exited".to_string()))??;
+        Ok((
+            Self {
+                device_name,
+                device_sample_rate_hz,
+                stop,
+                thread: Some(thread),
+            },
+            frames_rx,
+        ))
+    }
+
+    pub fn device_name(&self) -> &str {
+        &self.device_name
+    }
+
+    pub fn device_sample_rate_hz(&self) -> u32 {
+        self.device_sample_rate_hz
+    }
+}
+
+impl Drop for MicCapture {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn open_stream(
+    frame_ms: u32,
+    target_rate_hz: u32,
+    frames: UnboundedSender<Vec<f32>>,
+) -> Result<(cpal::Stream, String, u32), CaptureError> {
+    let device = cpal::default_host()
+        .default_input_device()
+        .ok_or(CaptureError::NoInputDevice)?;
+    let name = device.name().unwrap_or_else(|_| "unknown".to_string());
+    let supported = device
+        .default_input_config()
+        .map_err(|err| CaptureError::Device(err.to_string()))?;
+    let format = supported.sample_format();
+    let config: StreamConfig = supported.into();
+    let rate = config.sample_rate.0;
+    let framer = Framer::new(
+        usize::from(config.channels),
+        rate,
+        frame_ms,
+        target_rate_hz,
+        frames,
+    );
+
+    let stream = match format {
+        SampleFormat::F32 => build_stream::<f32>(&device, &config, framer),
+        SampleFormat::I16 => build_stream::<i16>(&device, &config, framer),
+        SampleFormat::U16 => build_stream::<u16>(&device, &config, framer),
+        other => return Err(CaptureError::UnsupportedFormat(other)),
+    }?;
+    stream
+        .play()
+        .map_err(|err| CaptureError::Device(err.to_string()))?;
+    Ok((stream, name, rate))
+}
+
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &StreamConfig,
+    mut framer: Framer,
+) -> Result<cpal::Stream, CaptureError>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    device
+        .build_input_stream(
+            config,
+            move |data: &[T], _: &cpal::InputCallbackInfo| {
+                framer.push(data.iter().map(|sample| f32::from_sample(*sample)));
+            },
+            |err| eprintln!("microphone stream error: {err}"),
+            None,
+        )
+        .map_err(|err| CaptureError::Device(err.to_string()))
+}
+
+/// Downmixes interleaved device samples and emits fixed-length frames.
+struct Framer {
+    channels: usize,
+    device_rate_hz: u32,
+    target_rate_hz: u32,
+    frame_len: usize,
+    pending_channel: usize,
+    pending_sum: f32,
+    buffer: Vec<f32>,
+    frames: UnboundedSender<Vec<f32>>,
+}
+
+impl Framer {
+    fn new(
+        channels: usize,
+        device_rate_hz: u32,
+        frame_ms: u32,
+        target_rate_hz: u32,
+        frames: UnboundedSender<Vec<f32>>,
+    ) -> Self {
+        let frame_len = (device_rate_hz as usize * frame_ms as usize / 1_000).max(1);
+        Self {
+            channels: channels.max(1),
+            device_rate_hz,
+            target_rate_hz,
+            frame_len,
+            pending_channel: 0,
+            pending_sum: 0.0,
+            buffer: Vec::with_capacity(frame_len),
+            frames,
+        }
+    }
+
+    fn push(&mut self, samples: impl Iterator<Item = f32>) {
+        for sample in samples {
+            self.pending_sum += sample;
+            self.pending_channel += 1;
+            if self.pending_channel < self.channels {
+                continue;
+            }
+            self.buffer.push(self.pending_sum / self.channels as f32);
+            self.pending_channel = 0;
+            self.pending_sum = 0.0;
+
+            if self.buffer.len() == self.frame_len {
+                let frame =
+                    resample_linear(&self.buffer, self.device_rate_hz, self.target_rate_hz);
+                self.buffer.clear();
+                // The receiver is gone once the capture is being torn down.
+                let _ = self.frames.send(frame);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn framer_downmixes_and_emits_full_frames() {
+        let (tx, mut rx) = unbounded_channel();
+        // 2 channels at 1 kHz, 2 ms frames -> 2 mono samples per frame.
+        let mut framer = Framer::new(2, 1_000, 2, 1_000, tx);
+
+        framer.push([1.0, 0.0, 0.5, 0.5, 1.0].into_iter());
+        assert_eq!(rx.try_recv().unwrap(), vec![0.5, 0.5]);
+        assert!(rx.try_recv().is_err());
+    }
+}
@@ -0,0 +1,6 @@
+//! Client-side building blocks shared by the `vocal-cli` binary: behind the
+//! `mic` feature, microphone capture. WAV decoding comes from `wav-io` and
+//! the service clients from `vocal-agent-client`.
+
+#[cfg(feature = "mic")]
+pub mod capture;
@@ -0,0 +1,43 @@
+//! Command-line client for exercising the stack without writing one.
+//!
+//! `transcribe` posts a WAV file to the orchestrator HTTP API, `align` calls
+//! the alignment gRPC service directly and `stream` replays audio over the
+//! WebSocket protocol.
+
+mod align;
+mod args;
+mod stream;
+mod transcribe;
+
+use std::env;
+use std::process;
+
+use args::{Command, Options};
+
+const USAGE: &str = "usage: vocal-cli [--http-url=URL] [--alignment-url=URL] [--ws-url=URL] <command>
+
+commands:
+  transcribe <file.wav> [--language=fr|en|auto|..] [--model=NAME] [--json]
+  align <file.wav> --transcript=<file.txt> [--language=fr|en|..] [--json]
+  stream (<file.wav> | --mic) [--language=..] [--chunk-ms=500] [--flush-ms=3000]";
+
+#[tokio::main]
+async fn main() {
+    if let Err(error) = run().await {
+        eprintln!("vocal-cli failed: {error}");
+        process::exit(1);
+    }
+}
+
+async fn run() -> Result<(), String> {
+    let options = Options::parse(env::args().skip(1)).map_err(|err| format!("{err}\n{USAGE}"))?;
+    match options.command {
+        Command::Transcribe(args) => transcribe::run(&options.endpoints, args).await,
+        Command::Align(args) => align::run(&options.endpoints, args).await,
+        Command::Stream(args) => stream::run(&options.endpoints, args).await,
+        Command::Help => {
+            println!("{USAGE}");
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use std::time::Duration;
+
+use vocal_agent_client::ws::{self, transcript_text, WsReceiver, STREAM_SAMPLE_RATE_HZ};
+use vocal_agent_client::{ServerEnvelope, ServerMessage};
+use vocal_dsp::resample_linear;
+use wav_io::read_wav;
+
+use crate::args::{language_tag, Endpoints, StreamArgs, StreamSource};
+
+/// How long to keep reading once results stop arriving after `stop`.
+const RESULT_IDLE_TIMEOUT: Duration = Duration::from_secs(2);
+const RESULT_TIMEOUT: Duration = Duration::from_secs(120);
+
+pub async fn run(endpoints: &Endpoints, args: StreamArgs) -> Result<(), String> {
+    let (mut sender, mut receiver) = ws::connect(&endpoints.ws_url)
+        .await
+        .map_err(|err| err.to_string())?;
+    sender
+        .start(args.language.as_deref().map(language_tag).transpose()?)
+        .await
+        .map_err(|err| err.to_string())?;
+    let ready = receiver
+        .next(Some(RESULT_TIMEOUT))
+        .await
+        .map_err(|err| err.to_string())?
+        .ok_or("connection closed before ready")?;
+    print_message(&ready);
+
+    match &args.source {
+        StreamSource::File(path) => {
+            let audio = read_wav(path)?;
+            let samples =
+                resample_linear(&audio.samples, audio.sample_rate_hz, STREAM_SAMPLE_RATE_HZ);
+            let chunk_len = (STREAM_SAMPLE_RATE_HZ * args.chunk_ms / 1_000).max(1) as usize;
+            let pace = Duration::from_millis(u64::from(args.chunk_ms));
+            for chunk in samples.chunks(chunk_len) {
+                sender.audio_frame(chunk).await.map_err(|err| err.to_string())?;
+                tokio::time::sleep(pace).await;
+            }
+        }
+        StreamSource::Mic => stream_mic(&mut sender, &mut receiver, &args).await?,
+    }
+    sender.stop().await.map_err(|err| err.to_string())?;
+
+    drain_results(&mut receiver).await?;
+    sender.close().await;
+    Ok(())
+}
+
+#[cfg(feature = "mic")]
+async fn stream_mic(
+    sender: &mut ws::WsSender,
+    receiver: &mut WsReceiver,
+    args: &StreamArgs,
+) -> Result<(), String> {
+    use vocal_cli::capture::MicCapture;
+
+    let (capture, mut frames) = MicCapture::start(args.chunk_ms, STREAM_SAMPLE_RATE_HZ)
+        .map_err(|err| err.to_string())?;
+    eprintln!(
+        "recording from `{}` ({} Hz); press Ctrl+C to stop",
+        capture.device_name(),
+        capture.device_sample_rate_hz()
+    );
+
+    let mut flush = tokio::time::interval(Duration::from_millis(u64::from(args.flush_ms)));
+    flush.tick().await;
+    loop {
+        tokio::select! {
+            frame = frames.recv() => match frame {
+                Some(frame) => {
+                    sender.audio_frame(&frame).await.map_err(|err| err.to_string())?
+                }
+                None => return Err("microphone stream ended".to_string()),
+            },
+            _ = flush.tick() => sender.flush().await.map_err(|err| err.to_string())?,
+            message = receiver.next(None) => match message.map_err(|err| err.to_string())? {
+                Some(message) => print_message(&message),
+                None => return Err("server closed the connection".to_string()),
+            },
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+    drop(capture);
+    Ok(())
+}
+
+#[cfg(not(feature = "mic"))]
+async fn stream_mic(
+    _sender: &mut ws::WsSender,
+    _receiver: &mut WsReceiver,
+    _args: &StreamArgs,
+) -> Result<(), String> {
+    Err("microphone capture requires building vocal-cli with `--features mic`".to_string())
+}
+
+async fn drain_results(receiver: &mut WsReceiver) -> Result<(), String> {
+    let mut timeout = RESULT_TIMEOUT;
+    while let Some(envelope) = receiver
+        .next(Some(timeout))
+        .await
+        .map_err(|err| err.to_string())?
+    {
+        print_message(&envelope);
+        if matches!(envelope.message, ServerMessage::Error { .. }) {
+            break;
+        }
+        timeout = RESULT_IDLE_TIMEOUT;
+    }
+    Ok(())
+}
+
+fn print_message(envelope: &ServerEnvelope) {
+    match &envelope.message {
+        ServerMessage::Ready { session_id } => eprintln!("session {session_id}"),
+        ServerMessage::PartialTranscript { transcript, .. } => {
+            println!("~ {}", transcript_text(transcript))
+        }
+        ServerMessage::FinalTranscript { transcript } => {
+            println!("{}", transcript_text(transcript))
+        }
+        ServerMessage::AlignmentUpdate { words } => {
+            for word in words {
+                println!("{:>8} {:>8}  {}", word.start_ms, word.end_ms, word.word);
+            }
+        }
+        ServerMessage::StageStarted { stage } => eprintln!("{stage}..."),
+        ServerMessage::StageCompleted { stage, duration_ms } => {
+            eprintln!("{stage} done in {duration_ms} ms")
+        }
+        ServerMessage::StageFailed { stage, message } => eprintln!("{stage} failed: {message}"),
+        ServerMessage::Error { message } => eprintln!("server error: {message}"),
+        ServerMessage::LatencyReport {
+            pipeline_ms,
+            buffered_audio_ms,
+            ..
+        } => eprintln!("pipeline {pipeline_ms} ms over {buffered_audio_ms} ms of audio"),
+        ServerMessage::Pong => {}
+    }
+}
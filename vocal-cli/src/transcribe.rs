@@ -0,0 +1,32 @@
+use vocal_agent_client::{HttpClient, TranscribeRequest};
+use wav_io::read_wav;
+
+use crate::args::{Endpoints, TranscribeArgs};
+
+pub async fn run(endpoints: &Endpoints, args: TranscribeArgs) -> Result<(), String> {
+    let audio = read_wav(&args.file)?;
+    eprintln!(
+        "transcribing {} ({} ms at {} Hz) via {}",
+        args.file.display(),
+        audio.duration_ms(),
+        audio.sample_rate_hz,
+        endpoints.http_url
+    );
+
+    let request = TranscribeRequest {
+        language_hint: args.language,
+        model: args.model,
+        ..TranscribeRequest::samples(audio.samples, audio.sample_rate_hz)
+    };
+    let response = HttpClient::new(&endpoints.http_url)
+        .transcribe(&request)
+        .await
+        .map_err(|err| format!("{}: {err}", endpoints.http_url))?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&response).unwrap_or_default());
+    } else {
+        println!("{}", response.text);
+    }
+    Ok(())
+}
@@ -0,0 +1,56 @@
+//! Throughput of the per-sample kernels and resamplers on one minute of
+//! 48 kHz audio. Run with `cargo bench -p vocal-dsp`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use vocal_dsp::{
+    clamp_samples, pcm16le_bytes_to_f32, resample_linear, soft_limit_samples, stretch_linear,
+};
+use wav_io::downmix;
+
+const SAMPLE_RATE_HZ: u32 = 48_000;
+const ITERATIONS: u32 = 20;
+
+fn main() {
+    let minute = test_audio::speech_like(SAMPLE_RATE_HZ, 60_000);
+    let loud: Vec<f32> = minute.iter().map(|sample| sample * 2.5).collect();
+    let pcm: Vec<u8> = minute
+        .iter()
+        .flat_map(|sample| ((sample * f32::from(i16::MAX)) as i16).to_le_bytes())
+        .collect();
+    let stereo: Vec<f32> = minute.iter().flat_map(|sample| [*sample, -*sample]).collect();
+
+    report("clamp_samples", minute.len(), || {
+        let mut samples = loud.clone();
+        black_box(clamp_samples(&mut samples, 1.0));
+    });
+    report("soft_limit_samples", minute.len(), || {
+        let mut samples = loud.clone();
+        black_box(soft_limit_samples(&mut samples, 0.8, 1.0));
+    });
+    report("pcm16le_bytes_to_f32", minute.len(), || {
+        black_box(pcm16le_bytes_to_f32(black_box(&pcm)));
+    });
+    report("downmix stereo", minute.len(), || {
+        black_box(downmix(stereo.clone(), 2));
+    });
+    report("resample_linear 48k->16k", minute.len(), || {
+        black_box(resample_linear(black_box(&minute), SAMPLE_RATE_HZ, 16_000));
+    });
+    report("stretch_linear x1.25", minute.len(), || {
+        black_box(stretch_linear(black_box(&minute), minute.len() * 5 / 4));
+    });
+}
+
+fn report(name: &str, samples: usize, mut run: impl FnMut()) {
+    run();
+    let mut best = Duration::MAX;
+    for _ in 0..ITERATIONS {
+        let started = Instant::now();
+        run();
+        best = best.min(started.elapsed());
+    }
+    let rate = samples as f64 / best.as_secs_f64() / 1e6;
+    println!("{name:<24} {:>10.3} ms  {rate:>8.1} Msamples/s", best.as_secs_f64() * 1e3);
+}
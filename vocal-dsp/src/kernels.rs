@@ -0,0 +1,114 @@
+//! Per-sample loops of the audio paths, written over fixed-width lanes so the
+//! compiler vectorizes them on stable Rust. Each kernel runs over every
+//! sample of a request.
+
+/// Samples processed together; eight `f32` fill one AVX register.
+pub const LANES: usize = 8;
+
+/// Clamps every sample to `[-ceiling, ceiling]` and tells whether any
+/// changed.
+pub fn clamp_samples(samples: &mut [f32], ceiling: f32) -> bool {
+    let mut changed = [false; LANES];
+    let mut chunks = samples.chunks_exact_mut(LANES);
+    for chunk in &mut chunks {
+        for (lane, sample) in chunk.iter_mut().enumerate() {
+            let clamped = sample.clamp(-ceiling, ceiling);
+            changed[lane] |= clamped != *sample;
+            *sample = clamped;
+        }
+    }
+    let mut changed_any = changed.iter().any(|lane| *lane);
+    for sample in chunks.into_remainder() {
+        let clamped = sample.clamp(-ceiling, ceiling);
+        changed_any |= clamped != *sample;
+        *sample = clamped;
+    }
+    changed_any
+}
+
+/// Leaves samples up to `threshold` untouched and maps louder magnitudes to
+/// `threshold + knee * tanh((|x| - threshold) / knee)`, where `knee` is
+/// `ceiling - threshold`: the curve keeps unit slope at the threshold and
+/// approaches the ceiling. Tells whether any sample changed. Only loud
+/// samples take the `tanh`, so this is rarely the hot path.
+pub fn soft_limit_samples(samples: &mut [f32], threshold: f32, ceiling: f32) -> bool {
+    let knee = ceiling - threshold;
+    let mut changed = false;
+    for sample in samples {
+        let magnitude = sample.abs();
+        if magnitude > threshold {
+            let limited = threshold + knee * ((magnitude - threshold) / knee).tanh();
+            *sample = limited.copysign(*sample);
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Converts little-endian signed 16-bit PCM to `f32`; a trailing odd byte is
+/// ignored.
+pub fn pcm16le_bytes_to_f32(bytes: &[u8]) -> Vec<f32> {
+    const SCALE: f32 = 1.0 / i16::MAX as f32;
+    let mut output = Vec::with_capacity(bytes.len() / 2);
+    let mut chunks = bytes.chunks_exact(2 * LANES);
+    for chunk in &mut chunks {
+        let mut lanes = [0.0f32; LANES];
+        for (lane, pair) in lanes.iter_mut().zip(chunk.chunks_exact(2)) {
+            *lane = f32::from(i16::from_le_bytes([pair[0], pair[1]])) * SCALE;
+        }
+        output.extend_from_slice(&lanes);
+    }
+    output.extend(
+        chunks
+            .remainder()
+            .chunks_exact(2)
+            .map(|pair| f32::from(i16::from_le_bytes([pair[0], pair[1]])) * SCALE),
+    );
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamp_covers_full_lanes_and_the_remainder() {
+        let mut samples: Vec<f32> = (0..19).map(|index| index as f32 * 0.2 - 1.8).collect();
+        let expected: Vec<f32> = samples.iter().map(|sample| sample.clamp(-1.0, 1.0)).collect();
+        assert!(clamp_samples(&mut samples, 1.0));
+        assert_eq!(samples, expected);
+
+        let mut in_range = vec![0.5; 17];
+        assert!(!clamp_samples(&mut in_range, 1.0));
+        assert!(clamp_samples(&mut in_range, 0.4));
+        assert_eq!(in_range, vec![0.4; 17]);
+        let mut loud_tail = vec![0.0; 17];
+        loud_tail[16] = 1.5;
+        assert!(clamp_samples(&mut loud_tail, 1.0));
+    }
+
+    #[test]
+    fn soft_limiter_bends_loud_samples_below_the_ceiling() {
+        let mut samples = vec![0.5, -0.8, 0.9, -1.5, 4.0];
+        assert!(soft_limit_samples(&mut samples, 0.8, 1.0));
+        assert_eq!(&samples[..2], &[0.5, -0.8]);
+        assert!(samples[2] > 0.8 && samples[2] < 0.9);
+        assert!(samples[3] < -0.8 && samples[3] > -1.0);
+        assert!(samples[4] > samples[2] && samples[4] <= 1.0);
+
+        let mut quiet = vec![0.1, -0.7];
+        assert!(!soft_limit_samples(&mut quiet, 0.8, 1.0));
+    }
+
+    #[test]
+    fn pcm16_conversion_matches_the_scalar_formula() {
+        let values: Vec<i16> = (0..21).map(|index| (index * 3_000 - 30_000) as i16).collect();
+        let mut bytes: Vec<u8> = values.iter().flat_map(|value| value.to_le_bytes()).collect();
+        bytes.push(0x7f);
+        let samples = pcm16le_bytes_to_f32(&bytes);
+        assert_eq!(samples.len(), values.len());
+        for (sample, value) in samples.iter().zip(&values) {
+            assert!((sample - f32::from(*value) / f32::from(i16::MAX)).abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,9 @@
+//! Sample-level DSP shared by the services and tools: PCM conversion,
+//! limiting and resampling. New filters belong here rather than in one
+//! service's infra crate.
+
+pub mod kernels;
+pub mod resampler;
+
+pub use kernels::{clamp_samples, pcm16le_bytes_to_f32, soft_limit_samples};
+pub use resampler::{resample_linear, stretch_linear, StreamingResampler};
@@ -0,0 +1,364 @@
+/// Linear resampler fed block by block. Memory stays bounded by the block
+/// size plus a few samples of history, whatever the total input length, and
+/// the output does not depend on how the input is split.
+///
+/// Output sample `i` sits at input position `i * source / target`. The
+/// position is stepped as an exact fraction of the reduced rate ratio, so it
+/// never drifts on hour-long inputs the way repeated float products do.
+///
+/// Call [`StreamingResampler::process`] for every input block, then
+/// [`StreamingResampler::finish`] once to flush the last output samples.
+pub struct StreamingResampler {
+    ratio: Ratio,
+    /// Input samples seen so far.
+    consumed: u64,
+    /// Output samples emitted so far.
+    produced: u64,
+    /// Input position of the next output sample.
+    position: Position,
+    /// Last input samples, kept because an output sample may interpolate
+    /// between the end of one block and the start of the next.
+    history: Vec<f32>,
+    history_len: usize,
+}
+
+/// `source / target` reduced to lowest terms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Ratio {
+    source: u64,
+    target: u64,
+}
+
+impl Ratio {
+    fn new(source_rate_hz: u32, target_rate_hz: u32) -> Self {
+        let source = u64::from(source_rate_hz.max(1));
+        let target = u64::from(target_rate_hz.max(1));
+        let divisor = gcd(source, target);
+        Self {
+            source: source / divisor,
+            target: target / divisor,
+        }
+    }
+
+    fn is_identity(self) -> bool {
+        self.source == self.target
+    }
+
+    /// Output length for `input_len` input samples.
+    fn output_len(self, input_len: u64) -> u64 {
+        input_len * self.target / self.source
+    }
+}
+
+/// Input position `index + numerator / ratio.target`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Position {
+    index: u64,
+    numerator: u64,
+}
+
+impl Position {
+    fn advance(&mut self, ratio: Ratio) {
+        self.numerator += ratio.source;
+        self.index += self.numerator / ratio.target;
+        self.numerator %= ratio.target;
+    }
+
+    fn fraction(self, ratio: Ratio) -> f32 {
+        (self.numerator as f64 / ratio.target as f64) as f32
+    }
+}
+
+impl StreamingResampler {
+    pub fn new(source_rate_hz: u32, target_rate_hz: u32) -> Self {
+        let ratio = Ratio::new(source_rate_hz, target_rate_hz);
+        Self {
+            ratio,
+            consumed: 0,
+            produced: 0,
+            position: Position::default(),
+            history: Vec::new(),
+            history_len: (ratio.source / ratio.target) as usize + 2,
+        }
+    }
+
+    /// Appends to `output` every sample that `block` completes.
+    pub fn process(&mut self, block: &[f32], output: &mut Vec<f32>) {
+        if self.ratio.is_identity() {
+            output.extend_from_slice(block);
+            self.consumed += block.len() as u64;
+            self.produced += block.len() as u64;
+            return;
+        }
+
+        let offset = self.consumed;
+        self.consumed += block.len() as u64;
+        // Never run ahead of the length resampling would give if the input
+        // ended here.
+        let limit = self.ratio.output_len(self.consumed);
+        while self.produced < limit && self.position.index + 1 < self.consumed {
+            let left = self.sample_at(block, offset, self.position.index);
+            let right = self.sample_at(block, offset, self.position.index + 1);
+            output.push(self.interpolate(left, right));
+        }
+        self.remember(block);
+    }
+
+    /// Appends the samples held back for lack of a right neighbour; the
+    /// resampler is empty afterwards and can be reused for a new input.
+    pub fn finish(&mut self, output: &mut Vec<f32>) {
+        let total = self.consumed;
+        if !self.ratio.is_identity() {
+            if total == 1 && self.produced == 0 {
+                output.push(self.history[0]);
+            } else if total > 1 {
+                let output_len = self.ratio.output_len(total).max(1);
+                while self.produced < output_len {
+                    let right_index = (self.position.index + 1).min(total - 1);
+                    let left = self.sample_at(&[], total, self.position.index);
+                    let right = self.sample_at(&[], total, right_index);
+                    output.push(self.interpolate(left, right));
+                }
+            }
+        }
+        self.consumed = 0;
+        self.produced = 0;
+        self.position = Position::default();
+        self.history.clear();
+    }
+
+    /// Output sample at the current position; moves to the next one.
+    fn interpolate(&mut self, left: f32, right: f32) -> f32 {
+        let fraction = self.position.fraction(self.ratio);
+        self.position.advance(self.ratio);
+        self.produced += 1;
+        // Rounding must not push the result past either neighbour.
+        (left + (right - left) * fraction).clamp(left.min(right), left.max(right))
+    }
+
+    /// Input sample at absolute index `index`, from `block` (starting at
+    /// `offset`) or from the history before it.
+    fn sample_at(&self, block: &[f32], offset: u64, index: u64) -> f32 {
+        if index >= offset {
+            return block[(index - offset) as usize];
+        }
+        let history_start = offset - self.history.len() as u64;
+        self.history[(index - history_start) as usize]
+    }
+
+    fn remember(&mut self, block: &[f32]) {
+        if block.len() >= self.history_len {
+            self.history.clear();
+            self.history.extend_from_slice(&block[block.len() - self.history_len..]);
+            return;
+        }
+        self.history.extend_from_slice(block);
+        let excess = self.history.len().saturating_sub(self.history_len);
+        self.history.drain(..excess);
+    }
+}
+
+/// Input block size [`resample_linear`] feeds the [`StreamingResampler`].
+const RESAMPLE_BLOCK_SAMPLES: usize = 4_096;
+
+/// Resamples a whole buffer from `source_rate_hz` to `target_rate_hz` with a
+/// [`StreamingResampler`]. A zero rate leaves the samples untouched.
+pub fn resample_linear(samples: &[f32], source_rate_hz: u32, target_rate_hz: u32) -> Vec<f32> {
+    if source_rate_hz == target_rate_hz || source_rate_hz == 0 || target_rate_hz == 0 {
+        return samples.to_vec();
+    }
+    let mut resampler = StreamingResampler::new(source_rate_hz, target_rate_hz);
+    let expected_len = samples.len() as u64 * u64::from(target_rate_hz) / u64::from(source_rate_hz);
+    let mut output = Vec::with_capacity(expected_len as usize + 1);
+    for block in samples.chunks(RESAMPLE_BLOCK_SAMPLES) {
+        resampler.process(block, &mut output);
+    }
+    resampler.finish(&mut output);
+    output
+}
+
+/// Stretches or squeezes `samples` to exactly `target_len` samples by linear
+/// interpolation, keeping the first and last sample in place. Empty input
+/// gives silence.
+pub fn stretch_linear(samples: &[f32], target_len: usize) -> Vec<f32> {
+    if target_len == 0 || samples.is_empty() {
+        return vec![0.0; target_len];
+    }
+    if samples.len() == 1 {
+        return vec![samples[0]; target_len];
+    }
+
+    let last = samples.len() - 1;
+    let ratio = last as f64 / (target_len - 1).max(1) as f64;
+    (0..target_len)
+        .map(|index| {
+            let position = index as f64 * ratio;
+            let left = (position.floor() as usize).min(last);
+            let right = (left + 1).min(last);
+            let fraction = (position - left as f64) as f32;
+            samples[left] + (samples[right] - samples[left]) * fraction
+        })
+        .collect()
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn resample_in_blocks(samples: &[f32], source: u32, target: u32, block: usize) -> Vec<f32> {
+        let mut resampler = StreamingResampler::new(source, target);
+        let mut output = Vec::new();
+        for chunk in samples.chunks(block) {
+            resampler.process(chunk, &mut output);
+        }
+        resampler.finish(&mut output);
+        output
+    }
+
+    #[test]
+    fn block_size_does_not_change_the_output() {
+        let samples: Vec<f32> = (0..1_001).map(|index| (index as f32 * 0.1).sin()).collect();
+        for (source, target) in [(48_000, 16_000), (16_000, 44_100), (44_100, 8_000)] {
+            let whole = resample_in_blocks(&samples, source, target, samples.len());
+            assert_eq!(
+                whole.len() as u64,
+                samples.len() as u64 * u64::from(target) / u64::from(source)
+            );
+            for block in [1, 3, 7, 160] {
+                assert_eq!(
+                    resample_in_blocks(&samples, source, target, block),
+                    whole,
+                    "{source} -> {target} in blocks of {block}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn upsampling_interpolates_across_block_edges() {
+        let output = resample_in_blocks(&[0.0, 1.0, 0.0], 8_000, 16_000, 1);
+        assert_eq!(output, vec![0.0, 0.5, 1.0, 0.5, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn a_single_sample_is_kept() {
+        assert_eq!(resample_in_blocks(&[0.25], 48_000, 16_000, 1), vec![0.25]);
+        assert!(resample_in_blocks(&[], 48_000, 16_000, 1).is_empty());
+    }
+
+    #[test]
+    fn positions_land_exactly_on_whole_seconds() {
+        let ratio = Ratio::new(44_100, 16_000);
+        let mut position = Position::default();
+        for _ in 0..16_000 * 60 {
+            position.advance(ratio);
+        }
+        assert_eq!(
+            position,
+            Position {
+                index: 44_100 * 60,
+                numerator: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn whole_buffers_resample_in_one_call() {
+        let upsampled = resample_linear(&[0.0, 1.0, 0.0, -1.0], 8_000, 16_000);
+        assert_eq!(upsampled.len(), 8);
+        assert_eq!(upsampled[1], 0.5);
+        assert_eq!(resample_linear(&[0.5; 3], 16_000, 16_000), vec![0.5; 3]);
+        assert_eq!(resample_linear(&[0.5; 3], 0, 16_000), vec![0.5; 3]);
+
+        let samples: Vec<f32> = (0..10_000).map(|index| (index as f32 * 0.01).sin()).collect();
+        assert_eq!(
+            resample_linear(&samples, 44_100, 16_000),
+            resample_in_blocks(&samples, 44_100, 16_000, 64)
+        );
+    }
+
+    #[test]
+    fn stretching_keeps_the_ends_and_interpolates_between() {
+        let same = stretch_linear(&[1.0, 2.0, 3.0, 4.0], 4);
+        assert_eq!(same, vec![1.0, 2.0, 3.0, 4.0]);
+
+        let stretched = stretch_linear(&[0.0, 1.0], 5);
+        assert_eq!(stretched, vec![0.0, 0.25, 0.5, 0.75, 1.0]);
+        assert_eq!(stretch_linear(&[0.3], 3), vec![0.3; 3]);
+        assert_eq!(stretch_linear(&[], 2), vec![0.0; 2]);
+    }
+
+    fn rates() -> impl Strategy<Value = u32> {
+        prop::sample::select(vec![8_000, 11_025, 16_000, 22_050, 44_100, 48_000, 96_000])
+    }
+
+    proptest! {
+        #[test]
+        fn output_length_follows_the_rate_ratio(
+            len in 2usize..2_000,
+            source in rates(),
+            target in rates(),
+        ) {
+            let output = resample_in_blocks(&vec![0.0; len], source, target, 64);
+            let expected = (len as u64 * u64::from(target) / u64::from(source)).max(1);
+            prop_assert_eq!(output.len() as u64, expected);
+        }
+
+        #[test]
+        fn output_stays_within_the_input_range(
+            samples in prop::collection::vec(-1.0f32..1.0, 1..500),
+            source in rates(),
+            target in rates(),
+            block in 1usize..100,
+        ) {
+            let low = samples.iter().copied().fold(f32::INFINITY, f32::min);
+            let high = samples.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+            for sample in resample_in_blocks(&samples, source, target, block) {
+                prop_assert!(
+                    (low..=high).contains(&sample),
+                    "{} outside {}..={}",
+                    sample,
+                    low,
+                    high
+                );
+            }
+        }
+
+        #[test]
+        fn rising_input_gives_rising_output(
+            steps in prop::collection::vec(0.0f32..0.01, 2..500),
+            source in rates(),
+            target in rates(),
+        ) {
+            let samples: Vec<f32> = steps
+                .iter()
+                .scan(-1.0f32, |level, step| {
+                    *level += step;
+                    Some(*level)
+                })
+                .collect();
+            let output = resample_in_blocks(&samples, source, target, 37);
+            prop_assert!(output.windows(2).all(|pair| pair[0] <= pair[1]));
+        }
+
+        #[test]
+        fn splitting_the_input_does_not_change_the_output(
+            samples in prop::collection::vec(-1.0f32..1.0, 0..500),
+            source in rates(),
+            target in rates(),
+            block in 1usize..100,
+        ) {
+            let whole = resample_in_blocks(&samples, source, target, samples.len().max(1));
+            prop_assert_eq!(resample_in_blocks(&samples, source, target, block), whole);
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use approx::assert_relative_eq;
+use test_audio::Signal;
 use vocal_features::energy::rms_energy;
 
 #[test]
@@ -18,8 +19,6 @@ fn constant_signal_rms_equals_value() {
 
 #[test]
 fn sine_rms_is_one_over_sqrt2() {
-    let audio: Vec<f32> = (0..16_000)
-        .map(|i| (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 16_000.0).sin())
-        .collect();
+    let audio = Signal::new(16_000).amplitude(1.0).tone(440.0, 1_000).build();
     assert_relative_eq!(rms_energy(&audio), 1.0 / 2.0f32.sqrt(), epsilon = 0.01);
 }
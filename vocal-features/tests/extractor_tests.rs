@@ -1,4 +1,5 @@
 use approx::assert_relative_eq;
+use test_audio::Signal;
 use vocal_features::{
     extractor::{FeatureExtractor, FeatureExtractorConfig},
     types::WordBoundary,
@@ -13,16 +14,12 @@ fn two_words_with_different_pitches() {
     };
     let extractor = FeatureExtractor::new(config);
 
-    let total_samples = sample_rate as usize;
-    let mut audio = vec![0.0f32; total_samples];
-    let boundary_500ms = 8_000;
-    let boundary_600ms = 9_600;
-    for i in 0..boundary_500ms {
-        audio[i] = (2.0 * std::f32::consts::PI * 200.0 * i as f32 / sample_rate as f32).sin();
-    }
-    for i in boundary_600ms..total_samples {
-        audio[i] = (2.0 * std::f32::consts::PI * 400.0 * i as f32 / sample_rate as f32).sin();
-    }
+    let audio = Signal::new(sample_rate)
+        .amplitude(1.0)
+        .tone(200.0, 500)
+        .silence(100)
+        .tone(400.0, 400)
+        .build();
 
     let words = vec![
         WordBoundary {
@@ -83,9 +80,7 @@ fn extract_segment_matches_extract_all_for_single_word() {
     };
     let extractor = FeatureExtractor::new(config);
 
-    let audio: Vec<f32> = (0..8_000)
-        .map(|i| (2.0 * std::f32::consts::PI * 300.0 * i as f32 / sample_rate as f32).sin())
-        .collect();
+    let audio = Signal::new(sample_rate).amplitude(1.0).tone(300.0, 500).build();
 
     let via_segment = extractor.extract_segment(&audio);
     let via_all = extractor.extract_all(
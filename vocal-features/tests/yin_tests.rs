@@ -1,13 +1,12 @@
 use approx::assert_relative_eq;
+use test_audio::Signal;
 use vocal_features::yin::{estimate_f0, estimate_mean_f0, YinConfig};
 
 #[test]
 fn sine_440hz_returns_correct_f0() {
     let sample_rate = 16_000u32;
     let config = YinConfig::with_sample_rate(sample_rate);
-    let audio: Vec<f32> = (0..sample_rate as usize)
-        .map(|i| (2.0 * std::f32::consts::PI * 440.0 * i as f32 / sample_rate as f32).sin())
-        .collect();
+    let audio = Signal::new(sample_rate).amplitude(1.0).tone(440.0, 1_000).build();
 
     let frames = estimate_f0(&audio, &config);
     let voiced: Vec<f32> = frames.iter().filter_map(|f| f.f0_hz).collect();
@@ -22,9 +21,7 @@ fn sine_440hz_returns_correct_f0() {
 fn sine_100hz_returns_correct_f0() {
     let sample_rate = 16_000u32;
     let config = YinConfig::with_sample_rate(sample_rate);
-    let audio: Vec<f32> = (0..sample_rate as usize)
-        .map(|i| (2.0 * std::f32::consts::PI * 100.0 * i as f32 / sample_rate as f32).sin())
-        .collect();
+    let audio = Signal::new(sample_rate).amplitude(1.0).tone(100.0, 1_000).build();
 
     let mean = estimate_mean_f0(&audio, &config);
     assert!(mean.is_some());
@@ -63,14 +60,11 @@ fn white_noise_mostly_unvoiced() {
 fn two_tones_detected() {
     let sample_rate = 16_000u32;
     let config = YinConfig::with_sample_rate(sample_rate);
-    let half = sample_rate as usize / 2;
-    let mut audio = Vec::with_capacity(sample_rate as usize);
-    for i in 0..half {
-        audio.push((2.0 * std::f32::consts::PI * 200.0 * i as f32 / sample_rate as f32).sin());
-    }
-    for i in 0..half {
-        audio.push((2.0 * std::f32::consts::PI * 400.0 * i as f32 / sample_rate as f32).sin());
-    }
+    let audio = Signal::new(sample_rate)
+        .amplitude(1.0)
+        .tone(200.0, 500)
+        .tone(400.0, 500)
+        .build();
 
     let frames = estimate_f0(&audio, &config);
     let mid_frame = frames.len() / 2;
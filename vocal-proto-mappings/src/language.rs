@@ -0,0 +1,122 @@
+use common_proto::v1::LanguageTagCode;
+
+use crate::MappingError;
+
+/// Wire-level view of a `common.v1.LanguageTag` message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WireLanguageTag {
+    Fr,
+    En,
+    Auto,
+    Other(String),
+}
+
+impl WireLanguageTag {
+    pub fn from_parts(code: i32, other: Option<String>) -> Result<Self, MappingError> {
+        match LanguageTagCode::try_from(code) {
+            Ok(LanguageTagCode::Fr) => Ok(Self::Fr),
+            Ok(LanguageTagCode::En) => Ok(Self::En),
+            Ok(LanguageTagCode::Auto) => Ok(Self::Auto),
+            Ok(LanguageTagCode::Other) => {
+                let value = other.unwrap_or_default();
+                if value.trim().is_empty() {
+                    return Err(MappingError::MissingOtherLanguage);
+                }
+                Ok(Self::Other(value))
+            }
+            Ok(LanguageTagCode::Unspecified) | Err(_) => {
+                Err(MappingError::InvalidLanguageCode(code))
+            }
+        }
+    }
+
+    /// Maps a canonical BCP-47 string (or `auto`) onto the wire enum; bare
+    /// `fr` and `en` keep their dedicated codes.
+    pub fn from_tag(tag: &str) -> Self {
+        match tag {
+            "fr" => Self::Fr,
+            "en" => Self::En,
+            "auto" => Self::Auto,
+            other => Self::Other(other.to_string()),
+        }
+    }
+
+    pub fn as_tag(&self) -> &str {
+        match self {
+            Self::Fr => "fr",
+            Self::En => "en",
+            Self::Auto => "auto",
+            Self::Other(value) => value,
+        }
+    }
+
+    pub fn code(&self) -> LanguageTagCode {
+        match self {
+            Self::Fr => LanguageTagCode::Fr,
+            Self::En => LanguageTagCode::En,
+            Self::Auto => LanguageTagCode::Auto,
+            Self::Other(_) => LanguageTagCode::Other,
+        }
+    }
+
+    /// The `code` and `other` fields of the message.
+    pub fn into_parts(self) -> (i32, Option<String>) {
+        let code = self.code() as i32;
+        match self {
+            Self::Other(value) => (code, Some(value)),
+            _ => (code, None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_codes_round_trip() {
+        for tag in [
+            WireLanguageTag::Fr,
+            WireLanguageTag::En,
+            WireLanguageTag::Auto,
+            WireLanguageTag::Other("de".to_string()),
+        ] {
+            let (code, other) = tag.clone().into_parts();
+            assert_eq!(WireLanguageTag::from_parts(code, other).unwrap(), tag);
+        }
+    }
+
+    #[test]
+    fn tags_keep_dedicated_codes() {
+        assert_eq!(WireLanguageTag::from_tag("fr"), WireLanguageTag::Fr);
+        assert_eq!(
+            WireLanguageTag::from_tag("fr-CA"),
+            WireLanguageTag::Other("fr-CA".to_string())
+        );
+        assert_eq!(WireLanguageTag::from_tag("auto").as_tag(), "auto");
+    }
+
+    #[test]
+    fn other_requires_value() {
+        let blank = Some("  ".to_string());
+        let error = WireLanguageTag::from_parts(LanguageTagCode::Other as i32, blank)
+            .expect_err("blank other should be rejected");
+        assert!(error.to_string().contains("language.other"));
+    }
+
+    #[test]
+    fn unknown_codes_are_rejected() {
+        assert_eq!(
+            WireLanguageTag::from_parts(42, None),
+            Err(MappingError::InvalidLanguageCode(42))
+        );
+    }
+
+    #[test]
+    fn unspecified_code_is_rejected() {
+        assert_eq!(
+            WireLanguageTag::from_parts(LanguageTagCode::Unspecified as i32, None),
+            Err(MappingError::InvalidLanguageCode(0))
+        );
+    }
+}
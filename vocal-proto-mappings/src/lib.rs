@@ -0,0 +1,41 @@
+//! Conversions between the per-service protobuf messages and the domain
+//! `Transcript` / `LanguageTag` / `WordTiming` entities.
+//!
+//! The `Transcript`, `TranscriptSegment`, `TranscriptToken`, `WordTiming` and
+//! `LanguageTag` messages are declared once in `common.v1` and re-exported by
+//! every service's `pb` module, and every domain crate declares matching
+//! entities. The macros in this crate expand the field-by-field mapping once,
+//! in the calling module, so a new field only has to be added here.
+
+mod language;
+mod transcript;
+
+use thiserror::Error;
+
+pub use common_proto::v1::LanguageTagCode;
+pub use language::WireLanguageTag;
+
+/// Integer values of [`LanguageTagCode`], from before the shared enum.
+#[deprecated(note = "use `LanguageTagCode::Fr as i32`")]
+pub const LANGUAGE_TAG_CODE_FR: i32 = LanguageTagCode::Fr as i32;
+#[deprecated(note = "use `LanguageTagCode::En as i32`")]
+pub const LANGUAGE_TAG_CODE_EN: i32 = LanguageTagCode::En as i32;
+#[deprecated(note = "use `LanguageTagCode::Auto as i32`")]
+pub const LANGUAGE_TAG_CODE_AUTO: i32 = LanguageTagCode::Auto as i32;
+#[deprecated(note = "use `LanguageTagCode::Other as i32`")]
+pub const LANGUAGE_TAG_CODE_OTHER: i32 = LanguageTagCode::Other as i32;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum MappingError {
+    #[error("language tag is missing")]
+    MissingLanguage,
+
+    #[error("language.other is required when code is OTHER")]
+    MissingOtherLanguage,
+
+    #[error("invalid language tag code {0}")]
+    InvalidLanguageCode(i32),
+
+    #[error("{0}")]
+    InvalidLanguageTag(String),
+}
@@ -0,0 +1,317 @@
+/// Expands `language_to_proto`, `language_from_proto`, `transcript_to_proto`
+/// and `transcript_from_proto` in the calling module.
+///
+/// `$pb` is the generated protobuf module and `$domain` the crate (or module)
+/// exposing `LanguageTag`, `Transcript`, `TranscriptSegment` and
+/// `TranscriptToken`. `LanguageTag` needs `parse(&str)` and `Display`, as
+/// `common_domain::LanguageTag` provides.
+#[macro_export]
+macro_rules! transcript_mappings {
+    ($pb:ident, $domain:ident) => {
+        #[allow(dead_code)]
+        fn language_to_proto(language: $domain::LanguageTag) -> $pb::LanguageTag {
+            let (code, other) =
+                $crate::WireLanguageTag::from_tag(&language.to_string()).into_parts();
+            $pb::LanguageTag { code, other }
+        }
+
+        #[allow(dead_code)]
+        fn language_from_proto(
+            language: Option<$pb::LanguageTag>,
+        ) -> Result<$domain::LanguageTag, $crate::MappingError> {
+            let language = language.ok_or($crate::MappingError::MissingLanguage)?;
+            let wire = $crate::WireLanguageTag::from_parts(language.code, language.other)?;
+            $domain::LanguageTag::parse(wire.as_tag())
+                .map_err(|err| $crate::MappingError::InvalidLanguageTag(err.to_string()))
+        }
+
+        #[allow(dead_code)]
+        fn transcript_to_proto(transcript: $domain::Transcript) -> $pb::Transcript {
+            $pb::Transcript {
+                language: Some(language_to_proto(transcript.language)),
+                segments: transcript
+                    .segments
+                    .into_iter()
+                    .map(|segment| $pb::TranscriptSegment {
+                        text: segment.text,
+                        start_ms: segment.start_ms,
+                        end_ms: segment.end_ms,
+                        tokens: segment
+                            .tokens
+                            .into_iter()
+                            .map(|token| $pb::TranscriptToken {
+                                text: token.text,
+                                start_ms: token.start_ms,
+                                end_ms: token.end_ms,
+                                confidence: token.confidence,
+                                start_sample: token.start_sample,
+                                end_sample: token.end_sample,
+                                disfluency: token.disfluency,
+                            })
+                            .collect(),
+                        speaker: segment.speaker,
+                        language: segment.language.map(language_to_proto),
+                        no_speech_prob: segment.no_speech_prob,
+                        avg_logprob: segment.avg_logprob,
+                    })
+                    .collect(),
+            }
+        }
+
+        #[allow(dead_code)]
+        fn transcript_from_proto(
+            transcript: $pb::Transcript,
+        ) -> Result<$domain::Transcript, $crate::MappingError> {
+            Ok($domain::Transcript {
+                language: language_from_proto(transcript.language)?,
+                segments: transcript
+                    .segments
+                    .into_iter()
+                    .map(|segment| {
+                        Ok($domain::TranscriptSegment {
+                            text: segment.text,
+                            start_ms: segment.start_ms,
+                            end_ms: segment.end_ms,
+                            tokens: segment
+                                .tokens
+                                .into_iter()
+                                .map(|token| $domain::TranscriptToken {
+                                    text: token.text,
+                                    start_ms: token.start_ms,
+                                    end_ms: token.end_ms,
+                                    confidence: token.confidence,
+                                    start_sample: token.start_sample,
+                                    end_sample: token.end_sample,
+                                    disfluency: token.disfluency,
+                                })
+                                .collect(),
+                            speaker: segment.speaker,
+                            language: segment
+                                .language
+                                .map(|language| language_from_proto(Some(language)))
+                                .transpose()?,
+                            no_speech_prob: segment.no_speech_prob,
+                            avg_logprob: segment.avg_logprob,
+                        })
+                    })
+                    .collect::<Result<_, $crate::MappingError>>()?,
+            })
+        }
+    };
+}
+
+/// Expands `word_timing_to_proto` and `word_timing_from_proto` in the calling
+/// module.
+#[macro_export]
+macro_rules! word_timing_mappings {
+    ($pb:ident, $domain:ident) => {
+        #[allow(dead_code)]
+        fn word_timing_to_proto(word: $domain::WordTiming) -> $pb::WordTiming {
+            $pb::WordTiming {
+                word: word.word,
+                start_ms: word.start_ms,
+                end_ms: word.end_ms,
+                confidence: word.confidence,
+                speaker: word.speaker,
+                start_sample: word.start_sample,
+                end_sample: word.end_sample,
+                energy_rms: word.energy_rms,
+                pitch_hz: word.pitch_hz,
+                out_of_band: word.out_of_band,
+            }
+        }
+
+        #[allow(dead_code)]
+        fn word_timing_from_proto(word: $pb::WordTiming) -> $domain::WordTiming {
+            $domain::WordTiming {
+                word: word.word,
+                start_ms: word.start_ms,
+                end_ms: word.end_ms,
+                confidence: word.confidence,
+                speaker: word.speaker,
+                start_sample: word.start_sample,
+                end_sample: word.end_sample,
+                energy_rms: word.energy_rms,
+                pitch_hz: word.pitch_hz,
+                out_of_band: word.out_of_band,
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    mod pb {
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct LanguageTag {
+            pub code: i32,
+            pub other: Option<String>,
+        }
+
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct Transcript {
+            pub language: Option<LanguageTag>,
+            pub segments: Vec<TranscriptSegment>,
+        }
+
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct TranscriptSegment {
+            pub text: String,
+            pub start_ms: u64,
+            pub end_ms: u64,
+            pub tokens: Vec<TranscriptToken>,
+            pub speaker: Option<String>,
+            pub language: Option<LanguageTag>,
+            pub no_speech_prob: Option<f32>,
+            pub avg_logprob: Option<f32>,
+        }
+
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct TranscriptToken {
+            pub text: String,
+            pub start_ms: u64,
+            pub end_ms: u64,
+            pub confidence: f32,
+            pub start_sample: Option<u64>,
+            pub end_sample: Option<u64>,
+            pub disfluency: bool,
+        }
+
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct WordTiming {
+            pub word: String,
+            pub start_ms: u64,
+            pub end_ms: u64,
+            pub confidence: f32,
+            pub speaker: Option<String>,
+            pub start_sample: Option<u64>,
+            pub end_sample: Option<u64>,
+            pub energy_rms: Option<f32>,
+            pub pitch_hz: Option<f32>,
+            pub out_of_band: bool,
+        }
+    }
+
+    mod domain {
+        pub use common_domain::LanguageTag;
+
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct Transcript {
+            pub language: LanguageTag,
+            pub segments: Vec<TranscriptSegment>,
+        }
+
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct TranscriptSegment {
+            pub text: String,
+            pub start_ms: u64,
+            pub end_ms: u64,
+            pub tokens: Vec<TranscriptToken>,
+            pub speaker: Option<String>,
+            pub language: Option<LanguageTag>,
+            pub no_speech_prob: Option<f32>,
+            pub avg_logprob: Option<f32>,
+        }
+
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct TranscriptToken {
+            pub text: String,
+            pub start_ms: u64,
+            pub end_ms: u64,
+            pub confidence: f32,
+            pub start_sample: Option<u64>,
+            pub end_sample: Option<u64>,
+            pub disfluency: bool,
+        }
+
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct WordTiming {
+            pub word: String,
+            pub start_ms: u64,
+            pub end_ms: u64,
+            pub confidence: f32,
+            pub speaker: Option<String>,
+            pub start_sample: Option<u64>,
+            pub end_sample: Option<u64>,
+            pub energy_rms: Option<f32>,
+            pub pitch_hz: Option<f32>,
+            pub out_of_band: bool,
+        }
+    }
+
+    crate::transcript_mappings!(pb, domain);
+    crate::word_timing_mappings!(pb, domain);
+
+    #[test]
+    fn transcript_round_trip_preserves_tokens() {
+        let transcript = domain::Transcript {
+            language: domain::LanguageTag::parse("es-MX").expect("valid tag"),
+            segments: vec![domain::TranscriptSegment {
+                text: "hola".to_string(),
+                start_ms: 0,
+                end_ms: 100,
+                tokens: vec![domain::TranscriptToken {
+                    text: "hola".to_string(),
+                    start_ms: 0,
+                    end_ms: 100,
+                    confidence: 0.9,
+                    start_sample: Some(0),
+                    end_sample: Some(1_600),
+                    disfluency: false,
+                }],
+                speaker: Some("SPEAKER_01".to_string()),
+                language: Some(domain::LanguageTag::en()),
+                no_speech_prob: Some(0.02),
+                avg_logprob: Some(-0.3),
+            }],
+        };
+
+        let mapped = transcript_from_proto(transcript_to_proto(transcript.clone()))
+            .expect("transcript should map");
+        assert_eq!(mapped, transcript);
+    }
+
+    #[test]
+    fn bare_french_uses_its_dedicated_code() {
+        let proto = language_to_proto(domain::LanguageTag::fr());
+        assert_eq!(proto.code, crate::LanguageTagCode::Fr as i32);
+        assert_eq!(proto.other, None);
+    }
+
+    #[test]
+    fn malformed_other_tag_is_rejected() {
+        let error = language_from_proto(Some(pb::LanguageTag {
+            code: crate::LanguageTagCode::Other as i32,
+            other: Some("not a tag".to_string()),
+        }))
+        .expect_err("spaces are not valid in a tag");
+        assert!(matches!(error, crate::MappingError::InvalidLanguageTag(_)));
+    }
+
+    #[test]
+    fn missing_language_is_rejected() {
+        let error = transcript_from_proto(pb::Transcript {
+            language: None,
+            segments: Vec::new(),
+        })
+        .expect_err("language is required");
+        assert_eq!(error, crate::MappingError::MissingLanguage);
+    }
+
+    #[test]
+    fn word_timing_round_trip() {
+        let word = domain::WordTiming {
+            word: "hello".to_string(),
+            start_ms: 10,
+            end_ms: 40,
+            confidence: 0.8,
+            speaker: Some("agent".to_string()),
+            start_sample: Some(160),
+            end_sample: None,
+            energy_rms: Some(0.25),
+            pitch_hz: None,
+            out_of_band: false,
+        };
+        assert_eq!(word_timing_from_proto(word_timing_to_proto(word.clone())), word);
+    }
+}
@@ -0,0 +1,331 @@
+//! Messages of the WebSocket streaming protocol, shared by the server
+//! (`orchestration-infra-streaming`) and the SDK (`vocal-agent-client`), so
+//! the two cannot drift. With the `openapi` feature they derive
+//! `utoipa::ToSchema`; the SDK publishes them as
+//! `vocal-agent-client/schema/ws-protocol.json`.
+
+use common_domain::{LanguageTag, Transcript, WordTiming};
+use serde::{Deserialize, Serialize};
+
+pub const PROTOCOL_VERSION: u32 = 1;
+/// `audio_frame` samples are mono at this rate.
+pub const STREAM_SAMPLE_RATE_HZ: u32 = 16_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ClientEnvelope {
+    pub version: u32,
+    /// Audio track the message is about, e.g. `mic` or `system`. Frames
+    /// without one go to the session's unnamed track; `flush` and `stop`
+    /// without one apply to every track.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track: Option<String>,
+    #[serde(flatten)]
+    pub message: ClientMessage,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(tag = "type", content = "payload", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Start {
+        session_id: Option<String>,
+        language_hint: Option<LanguageTag>,
+        #[serde(flatten)]
+        verbosity: Verbosity,
+    },
+    AudioFrame {
+        pcm_f32: Vec<f32>,
+    },
+    Flush,
+    Stop,
+    Ping,
+}
+
+impl ClientEnvelope {
+    pub fn new(message: ClientMessage) -> Self {
+        Self {
+            version: PROTOCOL_VERSION,
+            track: None,
+            message,
+        }
+    }
+
+    pub fn with_track(mut self, track: Option<String>) -> Self {
+        self.track = track;
+        self
+    }
+}
+
+/// Events a session receives, set on `start`; everything by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Verbosity {
+    /// Keep the tokens of transcript segments.
+    #[serde(default = "enabled")]
+    pub emit_tokens: bool,
+    /// Send `alignment_update` events.
+    #[serde(default = "enabled")]
+    pub emit_alignment: bool,
+    /// Send `partial_transcript` events.
+    #[serde(default = "enabled")]
+    pub emit_partials: bool,
+}
+
+impl Default for Verbosity {
+    fn default() -> Self {
+        Self {
+            emit_tokens: true,
+            emit_alignment: true,
+            emit_partials: true,
+        }
+    }
+}
+
+impl Verbosity {
+    /// `message` as this session wants it, or `None` when it opted out.
+    pub fn apply(&self, message: ServerMessage) -> Option<ServerMessage> {
+        match message {
+            ServerMessage::PartialTranscript { .. } if !self.emit_partials => None,
+            ServerMessage::AlignmentUpdate { .. } if !self.emit_alignment => None,
+            ServerMessage::PartialTranscript {
+                transcript,
+                stable_until_ms,
+            } => Some(ServerMessage::PartialTranscript {
+                transcript: self.trim(transcript),
+                stable_until_ms,
+            }),
+            ServerMessage::FinalTranscript { transcript } => Some(ServerMessage::FinalTranscript {
+                transcript: self.trim(transcript),
+            }),
+            message => Some(message),
+        }
+    }
+
+    fn trim(&self, mut transcript: Transcript) -> Transcript {
+        if !self.emit_tokens {
+            for segment in &mut transcript.segments {
+                segment.tokens.clear();
+            }
+        }
+        transcript
+    }
+}
+
+fn enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ServerEnvelope {
+    pub version: u32,
+    /// Track the event comes from; absent for session-wide events and the
+    /// unnamed track.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track: Option<String>,
+    #[serde(flatten)]
+    pub message: ServerMessage,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(tag = "type", content = "payload", rename_all = "snake_case")]
+pub enum ServerMessage {
+    Ready {
+        session_id: String,
+    },
+    PartialTranscript {
+        transcript: Transcript,
+        /// Text ending by this time will not change in later partials or
+        /// the final transcript; absent while nothing is settled.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        stable_until_ms: Option<u64>,
+    },
+    FinalTranscript {
+        transcript: Transcript,
+    },
+    AlignmentUpdate {
+        words: Vec<WordTiming>,
+    },
+    StageStarted {
+        stage: String,
+    },
+    StageCompleted {
+        stage: String,
+        duration_ms: u64,
+    },
+    StageFailed {
+        stage: String,
+        message: String,
+    },
+    Error {
+        message: String,
+    },
+    /// Sent after the events of each flush.
+    LatencyReport {
+        /// Time the pipeline took on this flush.
+        pipeline_ms: u64,
+        /// Audio the session holds, all of which the flush transcribed.
+        buffered_audio_ms: u64,
+        /// Server clock (Unix ms) when the flush request was read.
+        received_at_ms: u64,
+        /// Server clock (Unix ms) when this report was queued.
+        sent_at_ms: u64,
+    },
+    Pong,
+}
+
+impl ServerEnvelope {
+    pub fn new(message: ServerMessage) -> Self {
+        Self {
+            version: PROTOCOL_VERSION,
+            track: None,
+            message,
+        }
+    }
+
+    pub fn with_track(mut self, track: Option<String>) -> Self {
+        self.track = track;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_domain::{LanguageTag, Transcript, WordTiming};
+
+    use super::{
+        ClientEnvelope, ClientMessage, PROTOCOL_VERSION, ServerEnvelope, ServerMessage, Verbosity,
+    };
+
+    #[test]
+    fn protocol_round_trip() {
+        let raw = serde_json::to_string(&ClientEnvelope::new(ClientMessage::Ping))
+            .expect("serializes");
+        let decoded: ClientEnvelope = serde_json::from_str(&raw).expect("deserializes");
+        assert_eq!(decoded.version, PROTOCOL_VERSION);
+    }
+
+    #[test]
+    fn start_is_sent_with_version_and_flat_verbosity() {
+        let raw = serde_json::to_value(
+            ClientEnvelope::new(ClientMessage::Start {
+                session_id: None,
+                language_hint: None,
+                verbosity: Verbosity {
+                    emit_partials: false,
+                    ..Verbosity::default()
+                },
+            })
+            .with_track(Some("mic".to_string())),
+        )
+        .expect("serializes");
+        assert_eq!(raw["version"], PROTOCOL_VERSION);
+        assert_eq!(raw["type"], "start");
+        assert_eq!(raw["track"], "mic");
+        assert_eq!(raw["payload"]["emit_partials"], false);
+    }
+
+    #[test]
+    fn stage_events_use_snake_case_types() {
+        let raw = serde_json::to_value(ServerEnvelope::new(ServerMessage::StageCompleted {
+            stage: "asr".to_string(),
+            duration_ms: 42,
+        }))
+        .expect("serializes");
+        assert_eq!(raw["type"], "stage_completed");
+        assert_eq!(raw["payload"]["duration_ms"], 42);
+    }
+
+    #[test]
+    fn latency_report_is_a_flat_payload() {
+        let raw = serde_json::to_value(ServerEnvelope::new(ServerMessage::LatencyReport {
+            pipeline_ms: 120,
+            buffered_audio_ms: 2_000,
+            received_at_ms: 1_700_000_000_000,
+            sent_at_ms: 1_700_000_000_125,
+        }))
+        .expect("serializes");
+        assert_eq!(raw["type"], "latency_report");
+        assert_eq!(raw["payload"]["pipeline_ms"], 120);
+        assert_eq!(raw["payload"]["buffered_audio_ms"], 2_000);
+    }
+
+    #[test]
+    fn start_options_default_to_everything() {
+        let decoded: ClientEnvelope = serde_json::from_str(
+            r#"{"version":1,"type":"start","payload":{"session_id":null,"emit_alignment":false}}"#,
+        )
+        .expect("deserializes");
+        let ClientMessage::Start { verbosity, .. } = decoded.message else {
+            panic!("expected start");
+        };
+        assert_eq!(
+            verbosity,
+            Verbosity {
+                emit_alignment: false,
+                ..Verbosity::default()
+            }
+        );
+    }
+
+    #[test]
+    fn verbosity_filters_events() {
+        let quiet = Verbosity {
+            emit_tokens: false,
+            emit_alignment: false,
+            emit_partials: false,
+        };
+        let transcript = Transcript {
+            language: LanguageTag::en(),
+            segments: Vec::new(),
+        };
+        assert!(quiet
+            .apply(ServerMessage::PartialTranscript {
+                transcript: transcript.clone(),
+                stable_until_ms: None,
+            })
+            .is_none());
+        assert!(quiet
+            .apply(ServerMessage::AlignmentUpdate {
+                words: Vec::<WordTiming>::new(),
+            })
+            .is_none());
+        assert!(quiet
+            .apply(ServerMessage::FinalTranscript { transcript })
+            .is_some());
+    }
+
+    #[test]
+    fn messages_name_their_track() {
+        let decoded: ClientEnvelope =
+            serde_json::from_str(r#"{"version":1,"track":"mic","type":"flush"}"#)
+                .expect("deserializes");
+        assert_eq!(decoded.track.as_deref(), Some("mic"));
+        assert!(matches!(decoded.message, ClientMessage::Flush));
+
+        let tagged = serde_json::to_value(
+            ServerEnvelope::new(ServerMessage::Pong).with_track(Some("system".to_string())),
+        )
+        .expect("serializes");
+        assert_eq!(tagged["track"], "system");
+        let untagged = serde_json::to_value(ServerEnvelope::new(ServerMessage::Pong))
+            .expect("serializes");
+        assert!(untagged.get("track").is_none());
+    }
+
+    #[test]
+    fn server_events_decode() {
+        let decoded: ServerEnvelope = serde_json::from_value(serde_json::json!({
+            "version": 1,
+            "type": "stage_completed",
+            "payload": { "stage": "asr", "duration_ms": 42 }
+        }))
+        .expect("decodes");
+        assert!(matches!(
+            decoded.message,
+            ServerMessage::StageCompleted { duration_ms: 42, .. }
+        ));
+    }
+}
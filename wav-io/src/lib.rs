@@ -0,0 +1,256 @@
+//! WAV decoding for audio uploaded to the services' REST endpoints or read
+//! from disk by the client tools, and encoding for the audio the services
+//! return or record.
+
+use std::path::Path;
+
+const FORMAT_PCM: u16 = 1;
+const FORMAT_IEEE_FLOAT: u16 = 3;
+const FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+/// Mono `f32` samples decoded from an upload or a file.
+pub struct WavAudio {
+    pub sample_rate_hz: u32,
+    /// Channel count declared in the header, before the downmix.
+    pub channels: u16,
+    pub samples: Vec<f32>,
+}
+
+impl WavAudio {
+    pub fn duration_ms(&self) -> u64 {
+        if self.sample_rate_hz == 0 {
+            return 0;
+        }
+        self.samples.len() as u64 * 1_000 / u64::from(self.sample_rate_hz)
+    }
+}
+
+/// Reads and decodes a WAV file, prefixing errors with its path.
+pub fn read_wav(path: &Path) -> Result<WavAudio, String> {
+    let bytes =
+        std::fs::read(path).map_err(|err| format!("cannot read `{}`: {err}", path.display()))?;
+    decode_wav(&bytes).map_err(|err| format!("`{}`: {err}", path.display()))
+}
+
+/// Decodes 16/24/32-bit PCM and 32-bit float WAV data, averaging channels
+/// down to mono.
+pub fn decode_wav(bytes: &[u8]) -> Result<WavAudio, String> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err("not a RIFF/WAVE file".to_string());
+    }
+
+    let mut format = None;
+    let mut data = None;
+    let mut offset = 12;
+    while offset + 8 <= bytes.len() {
+        let id = &bytes[offset..offset + 4];
+        let size = read_u32(bytes, offset + 4) as usize;
+        let body_start = offset + 8;
+        let body_end = body_start.saturating_add(size).min(bytes.len());
+        let body = &bytes[body_start..body_end];
+        match id {
+            b"fmt " => format = Some(parse_format(body)?),
+            b"data" => data = Some(body),
+            _ => {}
+        }
+        // Chunks are padded to an even size.
+        offset = body_start.saturating_add(size + (size & 1));
+    }
+
+    let format = format.ok_or("missing fmt chunk")?;
+    let data = data.ok_or("missing data chunk")?;
+    let bytes_per_sample = usize::from(format.bits_per_sample / 8);
+    let frame_bytes = bytes_per_sample * usize::from(format.channels);
+    if frame_bytes == 0 {
+        return Err("invalid fmt chunk".to_string());
+    }
+
+    let whole_frames = data.len() / frame_bytes * frame_bytes;
+    let interleaved = decode_samples(format.encoding, &data[..whole_frames]);
+    let samples = downmix(interleaved, format.channels);
+    Ok(WavAudio {
+        sample_rate_hz: format.sample_rate_hz,
+        channels: format.channels,
+        samples,
+    })
+}
+
+/// Averages interleaved frames of `channels` samples down to mono. Mono
+/// input is returned as is and stereo takes a dedicated loop, the two
+/// layouts nearly every upload uses.
+pub fn downmix(interleaved: Vec<f32>, channels: u16) -> Vec<f32> {
+    match channels {
+        0 | 1 => interleaved,
+        2 => interleaved
+            .chunks_exact(2)
+            .map(|frame| (frame[0] + frame[1]) * 0.5)
+            .collect(),
+        _ => interleaved
+            .chunks_exact(usize::from(channels))
+            .map(|frame| frame.iter().sum::<f32>() / f32::from(channels))
+            .collect(),
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Encoding {
+    Pcm16,
+    Pcm24,
+    Pcm32,
+    Float32,
+}
+
+struct Format {
+    encoding: Encoding,
+    channels: u16,
+    sample_rate_hz: u32,
+    bits_per_sample: u16,
+}
+
+fn parse_format(body: &[u8]) -> Result<Format, String> {
+    if body.len() < 16 {
+        return Err("fmt chunk too short".to_string());
+    }
+    let mut tag = read_u16(body, 0);
+    if tag == FORMAT_EXTENSIBLE && body.len() >= 26 {
+        tag = read_u16(body, 24);
+    }
+    let channels = read_u16(body, 2);
+    let sample_rate_hz = read_u32(body, 4);
+    let bits_per_sample = read_u16(body, 14);
+    let encoding = match (tag, bits_per_sample) {
+        (FORMAT_PCM, 16) => Encoding::Pcm16,
+        (FORMAT_PCM, 24) => Encoding::Pcm24,
+        (FORMAT_PCM, 32) => Encoding::Pcm32,
+        (FORMAT_IEEE_FLOAT, 32) => Encoding::Float32,
+        _ => {
+            return Err(format!(
+                "unsupported WAV encoding (format {tag}, {bits_per_sample} bits)"
+            ))
+        }
+    };
+    if channels == 0 {
+        return Err("WAV file declares zero channels".to_string());
+    }
+    Ok(Format {
+        encoding,
+        channels,
+        sample_rate_hz,
+        bits_per_sample,
+    })
+}
+
+/// Decodes every sample of `data`, matching on the encoding once rather
+/// than per sample so each loop stays branch-free.
+fn decode_samples(encoding: Encoding, data: &[u8]) -> Vec<f32> {
+    match encoding {
+        Encoding::Pcm16 => data
+            .chunks_exact(2)
+            .map(|bytes| f32::from(i16::from_le_bytes([bytes[0], bytes[1]])) / 32_768.0)
+            .collect(),
+        Encoding::Pcm24 => data
+            .chunks_exact(3)
+            .map(|bytes| {
+                let value = i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 8;
+                value as f32 / 8_388_608.0
+            })
+            .collect(),
+        Encoding::Pcm32 => data
+            .chunks_exact(4)
+            .map(|bytes| {
+                i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32
+                    / 2_147_483_648.0
+            })
+            .collect(),
+        Encoding::Float32 => data
+            .chunks_exact(4)
+            .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect(),
+    }
+}
+
+/// Mono 32-bit float WAV, which [`decode_wav`] reads back losslessly.
+pub fn encode_wav_f32_mono(samples: &[f32], sample_rate_hz: u32) -> Vec<u8> {
+    let channels: u16 = 1;
+    let bits_per_sample: u16 = 32;
+    let bytes_per_sample = (bits_per_sample / 8) as u32;
+    let byte_rate = sample_rate_hz * channels as u32 * bytes_per_sample;
+    let block_align = channels * (bits_per_sample / 8);
+    let data_chunk_size = samples.len() as u32 * bytes_per_sample;
+    let riff_chunk_size = 36 + data_chunk_size;
+
+    let mut out = Vec::with_capacity((44 + data_chunk_size) as usize);
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&riff_chunk_size.to_le_bytes());
+    out.extend_from_slice(b"WAVE");
+    out.extend_from_slice(b"fmt ");
+    out.extend_from_slice(&16u32.to_le_bytes());
+    out.extend_from_slice(&FORMAT_IEEE_FLOAT.to_le_bytes());
+    out.extend_from_slice(&channels.to_le_bytes());
+    out.extend_from_slice(&sample_rate_hz.to_le_bytes());
+    out.extend_from_slice(&byte_rate.to_le_bytes());
+    out.extend_from_slice(&block_align.to_le_bytes());
+    out.extend_from_slice(&bits_per_sample.to_le_bytes());
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&data_chunk_size.to_le_bytes());
+
+    for sample in samples {
+        out.extend_from_slice(&sample.to_le_bytes());
+    }
+    out
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pcm16_upload_decodes_to_normalized_samples() {
+        let bytes = test_audio::wav_bytes(&[0.5, -0.5, 0.0], 16_000);
+        let wav = decode_wav(&bytes).expect("valid wav");
+        assert_eq!(wav.sample_rate_hz, 16_000);
+        assert_eq!(wav.channels, 1);
+        assert_eq!(wav.samples.len(), 3);
+        assert!((wav.samples[0] - 0.5).abs() < 1e-3);
+        assert!((wav.samples[1] + 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn channels_are_averaged_to_mono() {
+        assert_eq!(downmix(vec![0.5, -0.5, 1.0, 0.0], 2), vec![0.0, 0.5]);
+        assert_eq!(downmix(vec![0.25, 0.25, 1.0, 0.0, 0.0, 0.75], 3), vec![0.5, 0.25]);
+        assert_eq!(downmix(vec![0.25, 0.5], 1), vec![0.25, 0.5]);
+    }
+
+    #[test]
+    fn float_wav_round_trips() {
+        let samples = [0.25, -0.125, 1.0];
+        let wav = decode_wav(&encode_wav_f32_mono(&samples, 16_000)).expect("valid wav");
+        assert_eq!(wav.sample_rate_hz, 16_000);
+        assert_eq!(wav.samples, samples);
+    }
+
+    #[test]
+    fn duration_follows_the_sample_rate() {
+        let wav = decode_wav(&encode_wav_f32_mono(&[0.0; 8_000], 16_000)).expect("valid wav");
+        assert_eq!(wav.duration_ms(), 500);
+    }
+
+    #[test]
+    fn non_wav_bytes_are_rejected() {
+        assert!(decode_wav(b"not audio").is_err());
+    }
+}